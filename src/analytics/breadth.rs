@@ -0,0 +1,206 @@
+//! Market breadth over a subscribed universe.
+//!
+//! [`BreadthAggregator`] follows every instrument in the feed (index
+//! segments excluded) and summarises the universe as a [`BreadthSnapshot`]:
+//! advances/declines against the previous close, up/down volume, and how
+//! many instruments are trading at their session high or low. Use
+//! [`snapshots()`](BreadthAggregator::snapshots) for a periodic stream, e.g.
+//! as a regime filter.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+
+use crate::types::enums::ExchangeSegment;
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::MarketFeedEvent;
+
+/// Breadth of the universe at one moment.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BreadthSnapshot {
+    /// When the snapshot was taken.
+    pub taken_at: DateTime<Utc>,
+    /// Instruments with a known reference price.
+    pub total: usize,
+    /// Instruments above their previous close.
+    pub advances: usize,
+    /// Instruments below their previous close.
+    pub declines: usize,
+    /// Instruments at their previous close.
+    pub unchanged: usize,
+    /// Day volume of advancing instruments.
+    pub up_volume: i64,
+    /// Day volume of declining instruments.
+    pub down_volume: i64,
+    /// Instruments trading at their session high.
+    pub new_highs: usize,
+    /// Instruments trading at their session low.
+    pub new_lows: usize,
+}
+
+impl BreadthSnapshot {
+    /// Advances divided by declines; `None` without declines.
+    pub fn advance_decline_ratio(&self) -> Option<f64> {
+        (self.declines > 0).then(|| self.advances as f64 / self.declines as f64)
+    }
+
+    /// Advances minus declines.
+    pub fn net_advances(&self) -> i64 {
+        self.advances as i64 - self.declines as i64
+    }
+
+    /// Up volume as a share (0–1) of up plus down volume.
+    pub fn up_volume_share(&self) -> Option<f64> {
+        let total = self.up_volume + self.down_volume;
+        (total > 0).then(|| self.up_volume as f64 / total as f64)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Member {
+    prev_close: Option<f64>,
+    ltp: Option<f64>,
+    volume: i64,
+    high: Option<f64>,
+    low: Option<f64>,
+}
+
+/// Aggregates advances/declines and related breadth measures.
+#[derive(Debug, Clone, Default)]
+pub struct BreadthAggregator {
+    members: HashMap<InstrumentId, Member>,
+    universe: Option<Vec<InstrumentId>>,
+}
+
+impl BreadthAggregator {
+    /// Track every non-index instrument seen in the feed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track only `universe`.
+    pub fn with_universe(mut self, universe: impl IntoIterator<Item = InstrumentId>) -> Self {
+        self.universe = Some(universe.into_iter().collect());
+        self
+    }
+
+    /// Feed a market-feed event.
+    pub fn on_event(&mut self, event: &MarketFeedEvent) {
+        let Some(id) = InstrumentId::from_header(event.header()) else {
+            return;
+        };
+        let tracked = match &self.universe {
+            Some(universe) => universe.contains(&id),
+            None => id.segment != ExchangeSegment::IDX_I,
+        };
+        if !tracked {
+            return;
+        }
+        let member = self.members.entry(id).or_default();
+
+        if let MarketFeedEvent::PrevClose { prev_close, .. } = event {
+            member.prev_close = Some(f64::from(*prev_close)).filter(|p| *p > 0.0);
+            return;
+        }
+        let Some(tick) = event.to_tick().filter(|t| t.ltp > 0.0) else {
+            return;
+        };
+        member.ltp = Some(tick.ltp);
+        if let Some(v) = tick.volume {
+            member.volume = v;
+        }
+        let high = tick
+            .high
+            .filter(|h| *h > 0.0)
+            .unwrap_or(tick.ltp)
+            .max(tick.ltp);
+        let low = tick
+            .low
+            .filter(|l| *l > 0.0)
+            .unwrap_or(tick.ltp)
+            .min(tick.ltp);
+        member.high = Some(member.high.map_or(high, |h| h.max(high)));
+        member.low = Some(member.low.map_or(low, |l| l.min(low)));
+    }
+
+    /// Number of instruments tracked so far.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns `true` if no instrument has been seen.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Summarise the universe now.
+    pub fn snapshot(&self) -> BreadthSnapshot {
+        let mut snap = BreadthSnapshot {
+            taken_at: Utc::now(),
+            total: 0,
+            advances: 0,
+            declines: 0,
+            unchanged: 0,
+            up_volume: 0,
+            down_volume: 0,
+            new_highs: 0,
+            new_lows: 0,
+        };
+        for m in self.members.values() {
+            let Some(ltp) = m.ltp else { continue };
+            if m.high.is_some_and(|h| ltp >= h) {
+                snap.new_highs += 1;
+            }
+            if m.low.is_some_and(|l| ltp <= l) {
+                snap.new_lows += 1;
+            }
+            let Some(prev) = m.prev_close else { continue };
+            snap.total += 1;
+            match ltp.partial_cmp(&prev) {
+                Some(std::cmp::Ordering::Greater) => {
+                    snap.advances += 1;
+                    snap.up_volume += m.volume;
+                }
+                Some(std::cmp::Ordering::Less) => {
+                    snap.declines += 1;
+                    snap.down_volume += m.volume;
+                }
+                _ => snap.unchanged += 1,
+            }
+        }
+        snap
+    }
+
+    /// Consume `events` and emit a snapshot every `every`.
+    ///
+    /// The stream ends when `events` ends.
+    pub fn snapshots(
+        self,
+        events: impl Stream<Item = MarketFeedEvent>,
+        every: Duration,
+    ) -> impl Stream<Item = BreadthSnapshot> {
+        let mut interval = tokio::time::interval(every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let events = Box::pin(events);
+        futures_util::stream::unfold(
+            (self, events, interval),
+            |(mut agg, mut events, mut interval)| async move {
+                loop {
+                    tokio::select! {
+                        event = events.next() => match event {
+                            Some(event) => agg.on_event(&event),
+                            None => return None,
+                        },
+                        _ = interval.tick() => {
+                            let snap = agg.snapshot();
+                            return Some((snap, (agg, events, interval)));
+                        }
+                    }
+                }
+            },
+        )
+    }
+}
@@ -0,0 +1,14 @@
+//! Intraday analytics built on candles and ticks.
+//!
+//! - [`volume_profile`] — Volume profile and market (TPO) profile with
+//!   point of control and value area
+//! - [`rolling`] — Rolling mean / std / min / max / VWAP over tick streams
+//! - [`synthetic`] — Spread, ratio and basket series, and custom basket indices
+//! - [`oi`] — Open-interest change and price/OI quadrants for derivatives
+//! - [`breadth`] — Advances/declines and up/down volume across a universe
+
+pub mod breadth;
+pub mod oi;
+pub mod rolling;
+pub mod synthetic;
+pub mod volume_profile;
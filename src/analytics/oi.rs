@@ -0,0 +1,147 @@
+//! Open-interest analytics for derivatives.
+//!
+//! [`OiTracker`] follows OI and price per instrument from market-feed events
+//! (`PrevClose`, `OI`, `Full`, and price from `Ticker`/`Quote`) and, on every
+//! OI change, emits an [`OiUpdate`] with the session OI change and the
+//! classic price/OI [`OiQuadrant`]:
+//!
+//! | Price | OI   | Quadrant         |
+//! |-------|------|------------------|
+//! | ↑     | ↑    | Long buildup     |
+//! | ↓     | ↑    | Short buildup    |
+//! | ↑     | ↓    | Short covering   |
+//! | ↓     | ↓    | Long unwinding   |
+//!
+//! Changes are measured against the previous session (from the `PrevClose`
+//! packet) when known, otherwise against the first values seen.
+
+use std::collections::HashMap;
+
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::MarketFeedEvent;
+
+/// Price/OI regime of an instrument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OiQuadrant {
+    /// Price up, OI up: new longs.
+    LongBuildup,
+    /// Price down, OI up: new shorts.
+    ShortBuildup,
+    /// Price up, OI down: shorts exiting.
+    ShortCovering,
+    /// Price down, OI down: longs exiting.
+    LongUnwinding,
+}
+
+impl OiQuadrant {
+    /// Classify a price change and OI change; `None` if either is flat.
+    pub fn classify(price_change: f64, oi_change: i64) -> Option<Self> {
+        match (price_change.partial_cmp(&0.0)?, oi_change.signum()) {
+            (std::cmp::Ordering::Greater, 1) => Some(Self::LongBuildup),
+            (std::cmp::Ordering::Less, 1) => Some(Self::ShortBuildup),
+            (std::cmp::Ordering::Greater, -1) => Some(Self::ShortCovering),
+            (std::cmp::Ordering::Less, -1) => Some(Self::LongUnwinding),
+            _ => None,
+        }
+    }
+}
+
+/// An OI change of one instrument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OiUpdate {
+    /// The instrument.
+    pub instrument: InstrumentId,
+    /// Current open interest.
+    pub oi: i64,
+    /// OI change since the reference (previous close or first seen).
+    pub oi_change: i64,
+    /// `oi_change` as a percentage of the reference OI.
+    pub oi_change_pct: Option<f64>,
+    /// Last traded price, if seen.
+    pub price: Option<f64>,
+    /// Price change since the reference.
+    pub price_change: Option<f64>,
+    /// Price/OI regime, when both moved.
+    pub quadrant: Option<OiQuadrant>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct OiState {
+    base_oi: Option<i64>,
+    base_price: Option<f64>,
+    oi: Option<i64>,
+    price: Option<f64>,
+}
+
+/// Tracks OI and price per instrument.
+#[derive(Debug, Clone, Default)]
+pub struct OiTracker {
+    states: HashMap<InstrumentId, OiState>,
+}
+
+impl OiTracker {
+    /// Create an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a market-feed event; returns an update when OI changed.
+    pub fn on_event(&mut self, event: &MarketFeedEvent) -> Option<OiUpdate> {
+        let id = InstrumentId::from_header(event.header())?;
+        let state = self.states.entry(id).or_default();
+
+        if let MarketFeedEvent::PrevClose {
+            prev_close,
+            prev_oi,
+            ..
+        } = event
+        {
+            state.base_price = Some(f64::from(*prev_close)).filter(|p| *p > 0.0);
+            state.base_oi = Some(i64::from(*prev_oi)).filter(|oi| *oi > 0);
+            return None;
+        }
+
+        if let Some(tick) = event.to_tick().filter(|t| t.ltp > 0.0) {
+            state.price = Some(tick.ltp);
+            state.base_price.get_or_insert(tick.ltp);
+        }
+        let oi = match event {
+            MarketFeedEvent::OI { oi, .. } => i64::from(*oi),
+            _ => event.to_tick()?.oi?,
+        };
+        if oi <= 0 || state.oi == Some(oi) {
+            return None;
+        }
+        state.oi = Some(oi);
+        let base_oi = *state.base_oi.get_or_insert(oi);
+
+        let oi_change = oi - base_oi;
+        let price_change = state.price.zip(state.base_price).map(|(p, b)| p - b);
+        Some(OiUpdate {
+            instrument: id,
+            oi,
+            oi_change,
+            oi_change_pct: (base_oi > 0).then(|| oi_change as f64 / base_oi as f64 * 100.0),
+            price: state.price,
+            price_change,
+            quadrant: price_change.and_then(|pc| OiQuadrant::classify(pc, oi_change)),
+        })
+    }
+
+    /// Latest OI of `instrument`.
+    pub fn oi(&self, instrument: &InstrumentId) -> Option<i64> {
+        self.states.get(instrument)?.oi
+    }
+
+    /// Turn a feed-event stream into a stream of OI updates.
+    pub fn stream(
+        mut self,
+        events: impl Stream<Item = MarketFeedEvent>,
+    ) -> impl Stream<Item = OiUpdate> {
+        events.filter_map(move |event| std::future::ready(self.on_event(&event)))
+    }
+}
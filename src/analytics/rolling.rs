@@ -0,0 +1,250 @@
+//! Online rolling statistics over tick streams.
+//!
+//! [`RollingStats`] keeps a ring-buffer window of `(time, price, volume)`
+//! samples — bounded by sample count or by time span — and reports mean,
+//! standard deviation, min, max and VWAP in O(1) per query. [`TickStats`]
+//! keeps one window per instrument and feeds it straight from [`Tick`]s.
+//!
+//! ```
+//! use dhan_rs::analytics::rolling::{RollingStats, Window};
+//!
+//! let mut stats = RollingStats::new(Window::Count(3));
+//! for (t, price) in [(1, 10.0), (2, 12.0), (3, 11.0), (4, 15.0)] {
+//!     stats.push(t, price, 1.0);
+//! }
+//! assert_eq!(stats.len(), 3);
+//! assert_eq!(stats.mean(), Some(38.0 / 3.0));
+//! assert_eq!((stats.min(), stats.max()), (Some(11.0), Some(15.0)));
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::Tick;
+
+/// How many samples a rolling window keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Window {
+    /// The last `n` samples (minimum 1).
+    Count(usize),
+    /// Samples newer than this span before the latest sample (whole
+    /// seconds, matching exchange trade times).
+    Span(Duration),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    seq: u64,
+    time: i64,
+    price: f64,
+    volume: f64,
+}
+
+// ---------------------------------------------------------------------------
+// Rolling stats
+// ---------------------------------------------------------------------------
+
+/// Rolling mean / standard deviation / min / max / VWAP over one series.
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    window: Window,
+    samples: VecDeque<Sample>,
+    next_seq: u64,
+    sum: f64,
+    sum_sq: f64,
+    pv_sum: f64,
+    volume_sum: f64,
+    /// Monotonic deques of candidate minima / maxima.
+    mins: VecDeque<(u64, f64)>,
+    maxs: VecDeque<(u64, f64)>,
+}
+
+impl RollingStats {
+    /// Create an empty window.
+    pub fn new(window: Window) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+            next_seq: 0,
+            sum: 0.0,
+            sum_sq: 0.0,
+            pv_sum: 0.0,
+            volume_sum: 0.0,
+            mins: VecDeque::new(),
+            maxs: VecDeque::new(),
+        }
+    }
+
+    /// The window bound.
+    pub fn window(&self) -> Window {
+        self.window
+    }
+
+    /// Add a sample at epoch second `time`, then evict those that fell out
+    /// of the window.
+    pub fn push(&mut self, time: i64, price: f64, volume: f64) {
+        let sample = Sample {
+            seq: self.next_seq,
+            time,
+            price,
+            volume,
+        };
+        self.next_seq += 1;
+
+        self.sum += price;
+        self.sum_sq += price * price;
+        self.pv_sum += price * volume;
+        self.volume_sum += volume;
+        while self.mins.back().is_some_and(|&(_, p)| p >= price) {
+            self.mins.pop_back();
+        }
+        self.mins.push_back((sample.seq, price));
+        while self.maxs.back().is_some_and(|&(_, p)| p <= price) {
+            self.maxs.pop_back();
+        }
+        self.maxs.push_back((sample.seq, price));
+        self.samples.push_back(sample);
+
+        self.evict(time);
+    }
+
+    /// Number of samples in the window.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns `true` if the window holds no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The most recent price.
+    pub fn last(&self) -> Option<f64> {
+        self.samples.back().map(|s| s.price)
+    }
+
+    /// Arithmetic mean of the prices.
+    pub fn mean(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.sum / self.len() as f64)
+    }
+
+    /// Population standard deviation of the prices.
+    pub fn std_dev(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        let var = self.sum_sq / self.len() as f64 - mean * mean;
+        Some(var.max(0.0).sqrt())
+    }
+
+    /// Lowest price in the window.
+    pub fn min(&self) -> Option<f64> {
+        self.mins.front().map(|&(_, p)| p)
+    }
+
+    /// Highest price in the window.
+    pub fn max(&self) -> Option<f64> {
+        self.maxs.front().map(|&(_, p)| p)
+    }
+
+    /// Volume-weighted average price; `None` if no volume was traded.
+    pub fn vwap(&self) -> Option<f64> {
+        (self.volume_sum > 0.0).then(|| self.pv_sum / self.volume_sum)
+    }
+
+    /// Total volume in the window.
+    pub fn volume(&self) -> f64 {
+        self.volume_sum
+    }
+
+    /// Drop every sample.
+    pub fn clear(&mut self) {
+        *self = Self::new(self.window);
+    }
+
+    fn evict(&mut self, now: i64) {
+        while let Some(front) = self.samples.front() {
+            let expired = match self.window {
+                Window::Count(n) => self.samples.len() > n.max(1),
+                Window::Span(span) => front.time <= now - span.as_secs() as i64,
+            };
+            if !expired {
+                break;
+            }
+            let s = self.samples.pop_front().expect("front exists");
+            self.sum -= s.price;
+            self.sum_sq -= s.price * s.price;
+            self.pv_sum -= s.price * s.volume;
+            self.volume_sum -= s.volume;
+            if self.mins.front().is_some_and(|&(seq, _)| seq == s.seq) {
+                self.mins.pop_front();
+            }
+            if self.maxs.front().is_some_and(|&(seq, _)| seq == s.seq) {
+                self.maxs.pop_front();
+            }
+        }
+        if self.samples.is_empty() {
+            // Reset accumulated floating-point drift.
+            self.sum = 0.0;
+            self.sum_sq = 0.0;
+            self.pv_sum = 0.0;
+            self.volume_sum = 0.0;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Per-instrument tick stats
+// ---------------------------------------------------------------------------
+
+/// One [`RollingStats`] window per instrument, fed from ticks.
+#[derive(Debug, Clone)]
+pub struct TickStats {
+    window: Window,
+    stats: HashMap<InstrumentId, RollingStats>,
+    last_volume: HashMap<InstrumentId, i64>,
+}
+
+impl TickStats {
+    /// Create an empty set of windows, each bounded by `window`.
+    pub fn new(window: Window) -> Self {
+        Self {
+            window,
+            stats: HashMap::new(),
+            last_volume: HashMap::new(),
+        }
+    }
+
+    /// Feed a tick and return the updated window of its instrument.
+    ///
+    /// Sample volume is the change in cumulative day volume (Quote/Full
+    /// packets), falling back to the last traded quantity. Ticks without a
+    /// price are ignored.
+    pub fn on_tick(&mut self, tick: &Tick) -> Option<&RollingStats> {
+        if tick.ltp <= 0.0 {
+            return None;
+        }
+        let volume = match tick.volume {
+            Some(v) => {
+                let prev = self.last_volume.insert(tick.instrument, v);
+                prev.map_or(0, |p| (v - p).max(0))
+            }
+            None => tick.last_qty.unwrap_or(0).max(0),
+        };
+        let stats = self
+            .stats
+            .entry(tick.instrument)
+            .or_insert_with(|| RollingStats::new(self.window));
+        stats.push(tick.ltt, tick.ltp, volume as f64);
+        Some(stats)
+    }
+
+    /// The window of `instrument`.
+    pub fn get(&self, instrument: &InstrumentId) -> Option<&RollingStats> {
+        self.stats.get(instrument)
+    }
+
+    /// Instruments seen so far.
+    pub fn instruments(&self) -> impl Iterator<Item = &InstrumentId> {
+        self.stats.keys()
+    }
+}
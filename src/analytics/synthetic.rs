@@ -0,0 +1,378 @@
+//! Synthetic series derived from several instruments' live quotes.
+//!
+//! [`SyntheticSeries`] combines the latest prices of its legs with a
+//! [`SyntheticFormula`] — an `A − k·B` spread, an `A / B` ratio or a
+//! weighted basket — and emits a [`SyntheticPoint`] whenever a leg updates.
+//!
+//! **Alignment:** a point is only produced once every leg has a price.
+//! **Staleness:** if the oldest leg's last trade time lags the newest by
+//! more than the configured skew, the point is withheld, so a signal is
+//! never computed from prices that were not current at the same time.
+//!
+//! ```
+//! use dhan_rs::analytics::synthetic::{SyntheticFormula, SyntheticSeries};
+//! use dhan_rs::types::enums::ExchangeSegment;
+//! use dhan_rs::types::instrument::InstrumentId;
+//!
+//! let hdfc = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+//! let icici = InstrumentId::new(ExchangeSegment::NSE_EQ, 4963);
+//! let mut pair = SyntheticSeries::new(SyntheticFormula::ratio(hdfc, icici));
+//!
+//! assert!(pair.update(hdfc, 1_700_000_000, 1600.0).is_none()); // ICICI not seen yet
+//! let point = pair.update(icici, 1_700_000_001, 1000.0).unwrap();
+//! assert_eq!(point.value, 1.6);
+//! ```
+//!
+//! [`SyntheticIndex`] turns a weighted basket into an index-like series:
+//! ticks under an identifier of its own, optionally rebased to a starting
+//! value, plus OHLC candles built by a [`CandleAggregator`].
+
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+
+use crate::candles::CandleAggregator;
+use crate::types::historical::Candle;
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::Tick;
+
+/// Default maximum lag between the freshest and stalest leg.
+pub const DEFAULT_MAX_SKEW: Duration = Duration::from_secs(5);
+
+/// How leg prices combine into one value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntheticFormula {
+    /// `a − hedge_ratio × b`.
+    Spread {
+        /// First leg.
+        a: InstrumentId,
+        /// Second leg.
+        b: InstrumentId,
+        /// Units of `b` per unit of `a`.
+        hedge_ratio: f64,
+    },
+    /// `a / b`.
+    Ratio {
+        /// Numerator leg.
+        a: InstrumentId,
+        /// Denominator leg.
+        b: InstrumentId,
+    },
+    /// `Σ weight × price`, divided by `divisor`.
+    Basket {
+        /// `(instrument, weight)` per constituent.
+        legs: Vec<(InstrumentId, f64)>,
+        /// Scaling divisor (1.0 for a plain weighted sum).
+        divisor: f64,
+    },
+}
+
+impl SyntheticFormula {
+    /// A 1:1 spread `a − b`.
+    pub fn spread(a: InstrumentId, b: InstrumentId) -> Self {
+        Self::Spread {
+            a,
+            b,
+            hedge_ratio: 1.0,
+        }
+    }
+
+    /// The ratio `a / b`.
+    pub fn ratio(a: InstrumentId, b: InstrumentId) -> Self {
+        Self::Ratio { a, b }
+    }
+
+    /// A weighted sum of constituents.
+    pub fn basket(legs: impl IntoIterator<Item = (InstrumentId, f64)>) -> Self {
+        Self::Basket {
+            legs: legs.into_iter().collect(),
+            divisor: 1.0,
+        }
+    }
+
+    /// The instruments the formula reads, in leg order.
+    pub fn instruments(&self) -> Vec<InstrumentId> {
+        match self {
+            Self::Spread { a, b, .. } | Self::Ratio { a, b } => vec![*a, *b],
+            Self::Basket { legs, .. } => legs.iter().map(|(id, _)| *id).collect(),
+        }
+    }
+
+    /// Evaluate with `prices` in leg order.
+    fn evaluate(&self, prices: &[f64]) -> Option<f64> {
+        match self {
+            Self::Spread { hedge_ratio, .. } => Some(prices[0] - hedge_ratio * prices[1]),
+            Self::Ratio { .. } => (prices[1] != 0.0).then(|| prices[0] / prices[1]),
+            Self::Basket { legs, divisor } => {
+                let sum: f64 = legs.iter().zip(prices).map(|((_, w), p)| w * p).sum();
+                (*divisor != 0.0).then(|| sum / divisor)
+            }
+        }
+    }
+}
+
+/// One value of a synthetic series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntheticPoint {
+    /// Trade time of the freshest leg (epoch seconds).
+    pub time: i64,
+    /// The combined value.
+    pub value: f64,
+    /// Leg prices used, in leg order.
+    pub legs: Vec<f64>,
+}
+
+/// Combines live leg prices into a synthetic series.
+#[derive(Debug, Clone)]
+pub struct SyntheticSeries {
+    formula: SyntheticFormula,
+    instruments: Vec<InstrumentId>,
+    /// Latest `(time, price)` per leg.
+    quotes: Vec<Option<(i64, f64)>>,
+    max_skew: Duration,
+}
+
+impl SyntheticSeries {
+    /// Create a series with the [`DEFAULT_MAX_SKEW`] staleness rule.
+    pub fn new(formula: SyntheticFormula) -> Self {
+        let instruments = formula.instruments();
+        Self {
+            quotes: vec![None; instruments.len()],
+            instruments,
+            formula,
+            max_skew: DEFAULT_MAX_SKEW,
+        }
+    }
+
+    /// Withhold points when legs are more than `skew` apart (whole seconds).
+    pub fn with_max_skew(mut self, skew: Duration) -> Self {
+        self.max_skew = skew;
+        self
+    }
+
+    /// The formula.
+    pub fn formula(&self) -> &SyntheticFormula {
+        &self.formula
+    }
+
+    /// The instruments to subscribe to.
+    pub fn instruments(&self) -> &[InstrumentId] {
+        &self.instruments
+    }
+
+    /// Record a leg price and return the new point, if legs are aligned.
+    ///
+    /// Prices of instruments that are not legs, non-positive prices and
+    /// updates older than the leg's current price are ignored.
+    pub fn update(
+        &mut self,
+        instrument: InstrumentId,
+        time: i64,
+        price: f64,
+    ) -> Option<SyntheticPoint> {
+        let leg = self.instruments.iter().position(|id| *id == instrument)?;
+        if price <= 0.0 || self.quotes[leg].is_some_and(|(t, _)| time < t) {
+            return None;
+        }
+        self.quotes[leg] = Some((time, price));
+        self.current()
+    }
+
+    /// Feed a live tick.
+    pub fn on_tick(&mut self, tick: &Tick) -> Option<SyntheticPoint> {
+        self.update(tick.instrument, tick.ltt, tick.ltp)
+    }
+
+    /// The value from the latest leg prices, if they are aligned.
+    pub fn current(&self) -> Option<SyntheticPoint> {
+        let quotes: Vec<(i64, f64)> = self.quotes.iter().copied().collect::<Option<_>>()?;
+        let newest = quotes.iter().map(|(t, _)| *t).max()?;
+        let oldest = quotes.iter().map(|(t, _)| *t).min()?;
+        if newest - oldest > self.max_skew.as_secs() as i64 {
+            return None;
+        }
+        let legs: Vec<f64> = quotes.iter().map(|(_, p)| *p).collect();
+        Some(SyntheticPoint {
+            time: newest,
+            value: self.formula.evaluate(&legs)?,
+            legs,
+        })
+    }
+
+    /// Turn a tick stream into a stream of synthetic points.
+    pub fn stream(mut self, ticks: impl Stream<Item = Tick>) -> impl Stream<Item = SyntheticPoint> {
+        ticks.filter_map(move |tick| std::future::ready(self.on_tick(&tick)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Synthetic index
+// ---------------------------------------------------------------------------
+
+/// One constituent of a [`BasketIndexConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BasketConstituent {
+    /// The constituent.
+    pub instrument: InstrumentId,
+    /// Its weight (e.g. shares per basket unit).
+    pub weight: f64,
+}
+
+/// Definition of a custom basket index, e.g. loaded from JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BasketIndexConfig {
+    /// Identifier the index's ticks and candles carry; pick one no real
+    /// instrument uses.
+    pub id: InstrumentId,
+    /// Constituents and weights.
+    pub constituents: Vec<BasketConstituent>,
+    /// Divisor of the weighted sum. Default: 1.
+    #[serde(default = "default_divisor")]
+    pub divisor: f64,
+    /// Rebase the index so its first value is this (e.g. 1000). Default:
+    /// the plain weighted sum.
+    #[serde(default)]
+    pub base_value: Option<f64>,
+    /// Candle length in seconds. Default: 60.
+    #[serde(default = "default_candle_secs")]
+    pub candle_secs: u32,
+}
+
+fn default_divisor() -> f64 {
+    1.0
+}
+
+fn default_candle_secs() -> u32 {
+    60
+}
+
+/// One update of a [`SyntheticIndex`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexUpdate {
+    /// The index value as a tick of the index's identifier.
+    pub tick: Tick,
+    /// The candle this update closed, if any.
+    pub closed: Option<Candle>,
+}
+
+/// A weighted basket of constituents published as an index with candles.
+///
+/// ```
+/// use dhan_rs::analytics::synthetic::{BasketIndexConfig, SyntheticIndex};
+///
+/// let config: BasketIndexConfig = serde_json::from_str(r#"{
+///     "id": "IDX_I:900001",
+///     "constituents": [
+///         { "instrument": "NSE_EQ:1333", "weight": 2.0 },
+///         { "instrument": "NSE_EQ:4963", "weight": 1.0 }
+///     ],
+///     "base_value": 1000.0
+/// }"#).unwrap();
+/// let mut index = SyntheticIndex::new(config);
+///
+/// let hdfc = "NSE_EQ:1333".parse().unwrap();
+/// let icici = "NSE_EQ:4963".parse().unwrap();
+/// assert!(index.update(hdfc, 1_100_000_000, 1600.0).is_none());
+/// let first = index.update(icici, 1_100_000_000, 1000.0).unwrap();
+/// assert_eq!(first.tick.ltp, 1000.0);
+/// let next = index.update(icici, 1_100_000_001, 1210.0).unwrap();
+/// assert_eq!(next.tick.ltp, 1050.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SyntheticIndex {
+    id: InstrumentId,
+    series: SyntheticSeries,
+    base_value: Option<f64>,
+    /// Multiplier rebasing the raw value, fixed by the first value.
+    scale: Option<f64>,
+    aggregator: CandleAggregator,
+}
+
+impl SyntheticIndex {
+    /// Create an index from `config`, with the [`DEFAULT_MAX_SKEW`]
+    /// staleness rule.
+    pub fn new(config: BasketIndexConfig) -> Self {
+        let formula = SyntheticFormula::Basket {
+            legs: config
+                .constituents
+                .iter()
+                .map(|c| (c.instrument, c.weight))
+                .collect(),
+            divisor: config.divisor,
+        };
+        Self {
+            id: config.id,
+            series: SyntheticSeries::new(formula),
+            base_value: config.base_value,
+            scale: None,
+            aggregator: CandleAggregator::new(config.candle_secs),
+        }
+    }
+
+    /// Withhold values when constituents are more than `skew` apart.
+    pub fn with_max_skew(mut self, skew: Duration) -> Self {
+        self.series = self.series.with_max_skew(skew);
+        self
+    }
+
+    /// The index identifier.
+    pub fn id(&self) -> InstrumentId {
+        self.id
+    }
+
+    /// The constituents to subscribe to.
+    pub fn instruments(&self) -> &[InstrumentId] {
+        self.series.instruments()
+    }
+
+    /// Record a constituent price; see [`SyntheticSeries::update`].
+    pub fn update(
+        &mut self,
+        instrument: InstrumentId,
+        time: i64,
+        price: f64,
+    ) -> Option<IndexUpdate> {
+        let point = self.series.update(instrument, time, price)?;
+        let scale = *self.scale.get_or_insert_with(|| match self.base_value {
+            Some(base) if point.value != 0.0 => base / point.value,
+            _ => 1.0,
+        });
+        let tick = Tick {
+            instrument: self.id,
+            ltp: point.value * scale,
+            ltt: point.time,
+            last_qty: None,
+            volume: None,
+            open: None,
+            high: None,
+            low: None,
+            close: None,
+            oi: None,
+        };
+        Some(IndexUpdate {
+            closed: self.aggregator.on_tick(&tick),
+            tick,
+        })
+    }
+
+    /// Feed a live tick.
+    pub fn on_tick(&mut self, tick: &Tick) -> Option<IndexUpdate> {
+        self.update(tick.instrument, tick.ltt, tick.ltp)
+    }
+
+    /// The candle being built.
+    pub fn current_candle(&self) -> Option<&Candle> {
+        self.aggregator.current(&self.id)
+    }
+
+    /// Close and return the candle being built (e.g. at session end).
+    pub fn flush(&mut self) -> Option<Candle> {
+        self.aggregator.flush().into_iter().next().map(|(_, c)| c)
+    }
+
+    /// Turn a tick stream into a stream of index updates.
+    pub fn stream(mut self, ticks: impl Stream<Item = Tick>) -> impl Stream<Item = IndexUpdate> {
+        ticks.filter_map(move |tick| std::future::ready(self.on_tick(&tick)))
+    }
+}
@@ -0,0 +1,330 @@
+//! Price–volume histograms and market profile (TPO) analytics.
+//!
+//! [`VolumeProfile`] accumulates traded volume per price bucket from ticks or
+//! candles; [`MarketProfile`] counts the time periods (TPOs) in which each
+//! bucket traded. Both report the **point of control** (the busiest price)
+//! and the **value area** (the range around it holding a share of the
+//! activity, conventionally 70 %).
+//!
+//! Build one profile per instrument per session.
+//!
+//! ```
+//! use dhan_rs::analytics::volume_profile::VolumeProfile;
+//!
+//! let mut profile = VolumeProfile::new(0.5);
+//! profile.add(100.0, 300.0);
+//! profile.add(100.5, 900.0);
+//! profile.add(101.0, 200.0);
+//!
+//! assert_eq!(profile.poc(), Some(100.5));
+//! let va = profile.value_area(0.7).unwrap();
+//! assert_eq!((va.low, va.high), (100.0, 100.5));
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::types::historical::Candle;
+use crate::ws::market_feed::Tick;
+
+/// Conventional share of activity inside the value area.
+pub const DEFAULT_VALUE_AREA: f64 = 0.70;
+
+/// The value area of a profile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueArea {
+    /// Point of control: the price of the busiest bucket.
+    pub poc: f64,
+    /// Lowest price in the value area (VAL).
+    pub low: f64,
+    /// Highest price in the value area (VAH).
+    pub high: f64,
+    /// Activity inside the value area.
+    pub volume: f64,
+}
+
+// ---------------------------------------------------------------------------
+// Volume profile
+// ---------------------------------------------------------------------------
+
+/// Traded volume per price bucket.
+#[derive(Debug, Clone)]
+pub struct VolumeProfile {
+    bucket: f64,
+    bins: BTreeMap<i64, f64>,
+    last_volume: Option<i64>,
+}
+
+impl VolumeProfile {
+    /// Create an empty profile with buckets `bucket_size` wide (e.g. the
+    /// tick size, or a multiple of it).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bucket_size` is not positive.
+    pub fn new(bucket_size: f64) -> Self {
+        assert!(bucket_size > 0.0, "bucket size must be positive");
+        Self {
+            bucket: bucket_size,
+            bins: BTreeMap::new(),
+            last_volume: None,
+        }
+    }
+
+    /// Bucket width.
+    pub fn bucket_size(&self) -> f64 {
+        self.bucket
+    }
+
+    /// Add `volume` traded at `price`.
+    pub fn add(&mut self, price: f64, volume: f64) {
+        if price > 0.0 && volume > 0.0 {
+            *self.bins.entry(bucket_of(price, self.bucket)).or_default() += volume;
+        }
+    }
+
+    /// Add a live tick.
+    ///
+    /// Volume is the change in cumulative day volume since the previous
+    /// tick (Quote/Full packets); for Ticker packets, which carry no volume,
+    /// nothing is added.
+    pub fn add_tick(&mut self, tick: &Tick) {
+        let Some(volume) = tick.volume else {
+            return;
+        };
+        if let Some(prev) = self.last_volume.replace(volume) {
+            self.add(tick.ltp, (volume - prev).max(0) as f64);
+        }
+    }
+
+    /// Add a candle, spreading its volume evenly over the buckets between
+    /// its low and high.
+    pub fn add_candle(&mut self, candle: &Candle) {
+        let (lo, hi) = (
+            bucket_of(candle.low, self.bucket),
+            bucket_of(candle.high, self.bucket),
+        );
+        if candle.volume <= 0.0 || candle.low <= 0.0 || hi < lo {
+            return;
+        }
+        let share = candle.volume / (hi - lo + 1) as f64;
+        for b in lo..=hi {
+            *self.bins.entry(b).or_default() += share;
+        }
+    }
+
+    /// Build a profile from `candles`.
+    pub fn from_candles<'a>(
+        bucket_size: f64,
+        candles: impl IntoIterator<Item = &'a Candle>,
+    ) -> Self {
+        let mut profile = Self::new(bucket_size);
+        for candle in candles {
+            profile.add_candle(candle);
+        }
+        profile
+    }
+
+    /// Returns `true` if no volume has been added.
+    pub fn is_empty(&self) -> bool {
+        self.bins.is_empty()
+    }
+
+    /// Total volume across all buckets.
+    pub fn total_volume(&self) -> f64 {
+        self.bins.values().sum()
+    }
+
+    /// `(price, volume)` per bucket, lowest price first.
+    pub fn levels(&self) -> Vec<(f64, f64)> {
+        levels(&self.bins, self.bucket)
+    }
+
+    /// Price of the bucket with the most volume.
+    pub fn poc(&self) -> Option<f64> {
+        poc(&self.bins).map(|b| price_of(b, self.bucket))
+    }
+
+    /// Smallest range around the POC holding at least `share` (0–1) of the
+    /// volume.
+    pub fn value_area(&self, share: f64) -> Option<ValueArea> {
+        value_area(&self.bins, self.bucket, share)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Market profile
+// ---------------------------------------------------------------------------
+
+/// Time-price opportunity (TPO) counts per price bucket.
+///
+/// The session is split into periods (traditionally 30 minutes); each
+/// bucket scores one TPO for every period in which price traded there.
+#[derive(Debug, Clone)]
+pub struct MarketProfile {
+    bucket: f64,
+    period_secs: i64,
+    /// Period start → buckets touched in that period.
+    periods: BTreeMap<i64, (i64, i64)>,
+}
+
+impl MarketProfile {
+    /// Create an empty profile with `bucket_size`-wide buckets and periods of
+    /// `period_secs` seconds (minimum 1).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bucket_size` is not positive.
+    pub fn new(bucket_size: f64, period_secs: u32) -> Self {
+        assert!(bucket_size > 0.0, "bucket size must be positive");
+        Self {
+            bucket: bucket_size,
+            period_secs: i64::from(period_secs.max(1)),
+            periods: BTreeMap::new(),
+        }
+    }
+
+    /// Add a trade at `price` and epoch time `timestamp`.
+    pub fn add_price(&mut self, timestamp: i64, price: f64) {
+        if price > 0.0 {
+            let b = bucket_of(price, self.bucket);
+            self.extend_period(timestamp, b, b);
+        }
+    }
+
+    /// Add a live tick.
+    pub fn add_tick(&mut self, tick: &Tick) {
+        self.add_price(tick.ltt, tick.ltp);
+    }
+
+    /// Add a candle's range to the period containing its start time.
+    pub fn add_candle(&mut self, candle: &Candle) {
+        if candle.low > 0.0 && candle.high >= candle.low {
+            self.extend_period(
+                candle.timestamp,
+                bucket_of(candle.low, self.bucket),
+                bucket_of(candle.high, self.bucket),
+            );
+        }
+    }
+
+    /// Number of periods seen.
+    pub fn period_count(&self) -> usize {
+        self.periods.len()
+    }
+
+    /// `(price, tpo_count)` per bucket, lowest price first.
+    pub fn levels(&self) -> Vec<(f64, u32)> {
+        self.tpo_bins()
+            .into_iter()
+            .map(|(b, n)| (price_of(b, self.bucket), n as u32))
+            .collect()
+    }
+
+    /// Price of the bucket with the most TPOs.
+    pub fn poc(&self) -> Option<f64> {
+        poc(&self.tpo_bins()).map(|b| price_of(b, self.bucket))
+    }
+
+    /// Smallest range around the POC holding at least `share` (0–1) of the
+    /// TPOs. [`ValueArea::volume`] holds the TPO count.
+    pub fn value_area(&self, share: f64) -> Option<ValueArea> {
+        value_area(&self.tpo_bins(), self.bucket, share)
+    }
+
+    /// Low and high of the first `periods` periods (the initial balance is
+    /// conventionally the first two 30-minute periods).
+    pub fn initial_balance(&self, periods: usize) -> Option<(f64, f64)> {
+        let (lo, hi) = self.periods.values().take(periods).fold(
+            None,
+            |acc: Option<(i64, i64)>, &(lo, hi)| {
+                Some(acc.map_or((lo, hi), |(a, b)| (a.min(lo), b.max(hi))))
+            },
+        )?;
+        Some((price_of(lo, self.bucket), price_of(hi, self.bucket)))
+    }
+
+    fn extend_period(&mut self, timestamp: i64, lo: i64, hi: i64) {
+        let start = timestamp - timestamp.rem_euclid(self.period_secs);
+        let range = self.periods.entry(start).or_insert((lo, hi));
+        range.0 = range.0.min(lo);
+        range.1 = range.1.max(hi);
+    }
+
+    fn tpo_bins(&self) -> BTreeMap<i64, f64> {
+        let mut bins = BTreeMap::new();
+        for &(lo, hi) in self.periods.values() {
+            for b in lo..=hi {
+                *bins.entry(b).or_default() += 1.0;
+            }
+        }
+        bins
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shared histogram helpers
+// ---------------------------------------------------------------------------
+
+fn bucket_of(price: f64, bucket: f64) -> i64 {
+    (price / bucket).round() as i64
+}
+
+fn price_of(bucket_index: i64, bucket: f64) -> f64 {
+    bucket_index as f64 * bucket
+}
+
+fn levels(bins: &BTreeMap<i64, f64>, bucket: f64) -> Vec<(f64, f64)> {
+    bins.iter()
+        .map(|(&b, &v)| (price_of(b, bucket), v))
+        .collect()
+}
+
+/// Busiest bucket; ties go to the lower price.
+fn poc(bins: &BTreeMap<i64, f64>) -> Option<i64> {
+    bins.iter()
+        .fold(None, |best: Option<(i64, f64)>, (&b, &v)| match best {
+            Some((_, bv)) if bv >= v => best,
+            _ => Some((b, v)),
+        })
+        .map(|(b, _)| b)
+}
+
+/// Grow outwards from the POC, each step taking the busier neighbouring
+/// bucket, until `share` of the total is covered.
+fn value_area(bins: &BTreeMap<i64, f64>, bucket: f64, share: f64) -> Option<ValueArea> {
+    let poc = poc(bins)?;
+    let total: f64 = bins.values().sum();
+    let target = total * share.clamp(0.0, 1.0);
+    let keys: Vec<i64> = bins.keys().copied().collect();
+    let at = |i: usize| bins[&keys[i]];
+
+    let mut lo = keys.binary_search(&poc).expect("poc is a key");
+    let mut hi = lo;
+    let mut volume = at(lo);
+    while volume < target && (lo > 0 || hi + 1 < keys.len()) {
+        let below = (lo > 0).then(|| at(lo - 1));
+        let above = (hi + 1 < keys.len()).then(|| at(hi + 1));
+        match (below, above) {
+            (Some(b), Some(a)) if a >= b => {
+                hi += 1;
+                volume += a;
+            }
+            (Some(b), _) => {
+                lo -= 1;
+                volume += b;
+            }
+            (None, Some(a)) => {
+                hi += 1;
+                volume += a;
+            }
+            (None, None) => break,
+        }
+    }
+
+    Some(ValueArea {
+        poc: price_of(poc, bucket),
+        low: price_of(keys[lo], bucket),
+        high: price_of(keys[hi], bucket),
+        volume,
+    })
+}
@@ -6,7 +6,7 @@
 use reqwest::header::HeaderValue;
 use serde_json::Value;
 
-use crate::client::DhanClient;
+use crate::client::{DhanClient, EndpointGroup};
 use crate::constants::AUTH_BASE_URL;
 use crate::error::{ApiErrorBody, DhanError, Result};
 use crate::types::auth::{AppConsentResponse, PartnerConsentResponse, TokenResponse};
@@ -77,7 +77,8 @@ impl DhanClient {
     /// Renew the current access token for another 24 hours.
     ///
     /// Only works for tokens generated from Dhan Web that are still active.
-    /// This expires the current token and returns a new one.
+    /// This expires the current token and returns a new one, which this
+    /// client and its clones use from then on.
     ///
     /// **Endpoint:** `GET /v2/RenewToken`
     ///
@@ -86,19 +87,20 @@ impl DhanClient {
     /// The RenewToken endpoint uses `dhanClientId` as its client
     /// identification header, unlike most other endpoints that use `client-id`.
     /// This method handles the difference automatically.
-    pub async fn renew_token(&mut self) -> Result<TokenResponse> {
-        let url = format!("{}/v2/RenewToken", self.base_url());
+    pub async fn renew_token(&self) -> Result<TokenResponse> {
+        let url = format!("{}/v2/RenewToken", self.base_url_for(EndpointGroup::Auth));
 
         tracing::debug!(%url, "GET renew_token");
 
         let resp = self
-            .http()
-            .get(&url)
-            .header("access-token", self.access_token())
-            .header("dhanClientId", self.client_id())
-            .header("Content-Type", "application/json")
-            .header("Accept", "application/json")
-            .send()
+            .send(
+                self.http()
+                    .get(&url)
+                    .header("access-token", self.access_token())
+                    .header("dhanClientId", self.client_id())
+                    .header("Content-Type", "application/json")
+                    .header("Accept", "application/json"),
+            )
             .await?;
 
         let status = resp.status();
@@ -2,6 +2,7 @@
 
 use crate::client::DhanClient;
 use crate::error::Result;
+use crate::types::builders::ConditionalTriggerRequestBuilder;
 use crate::types::conditional::*;
 
 impl DhanClient {
@@ -15,6 +16,16 @@ impl DhanClient {
         self.post("/v2/alerts/orders", req).await
     }
 
+    /// Like [`Self::place_conditional_trigger`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn place_conditional_trigger_for_self(
+        &self,
+        req: ConditionalTriggerRequestBuilder,
+    ) -> Result<ConditionalTriggerResponse> {
+        self.place_conditional_trigger(&self.build_request(req)?)
+            .await
+    }
+
     /// Modify an existing conditional trigger.
     ///
     /// **Endpoint:** `PUT /v2/alerts/orders/{alertId}`
@@ -27,6 +38,17 @@ impl DhanClient {
             .await
     }
 
+    /// Like [`Self::modify_conditional_trigger`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn modify_conditional_trigger_for_self(
+        &self,
+        alert_id: &str,
+        req: ConditionalTriggerRequestBuilder,
+    ) -> Result<ConditionalTriggerResponse> {
+        self.modify_conditional_trigger(alert_id, &self.build_request(req)?)
+            .await
+    }
+
     /// Delete a conditional trigger.
     ///
     /// **Endpoint:** `DELETE /v2/alerts/orders/{alertId}`
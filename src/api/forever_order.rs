@@ -2,6 +2,7 @@
 
 use crate::client::DhanClient;
 use crate::error::Result;
+use crate::types::builders::{CreateForeverOrderRequestBuilder, ModifyForeverOrderRequestBuilder};
 use crate::types::forever_order::*;
 use crate::types::orders::OrderResponse;
 
@@ -13,7 +14,17 @@ impl DhanClient {
         &self,
         req: &CreateForeverOrderRequest,
     ) -> Result<OrderResponse> {
-        self.post("/v2/forever/orders", req).await
+        self.post("/v2/forever/orders", &*self.correlated(req))
+            .await
+    }
+
+    /// Like [`Self::create_forever_order`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn create_forever_order_for_self(
+        &self,
+        req: CreateForeverOrderRequestBuilder,
+    ) -> Result<OrderResponse> {
+        self.create_forever_order(&self.build_request(req)?).await
     }
 
     /// Modify an existing forever order.
@@ -28,6 +39,17 @@ impl DhanClient {
             .await
     }
 
+    /// Like [`Self::modify_forever_order`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn modify_forever_order_for_self(
+        &self,
+        order_id: &str,
+        req: ModifyForeverOrderRequestBuilder,
+    ) -> Result<OrderResponse> {
+        self.modify_forever_order(order_id, &self.build_request(req)?)
+            .await
+    }
+
     /// Delete a pending forever order.
     ///
     /// **Endpoint:** `DELETE /v2/forever/orders/{order-id}`
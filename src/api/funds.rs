@@ -1,8 +1,14 @@
-//! Funds & Margin endpoints — Margin Calculator, Fund Limit.
+//! Funds & Margin endpoints — Margin Calculator, Fund Limit, Funds Forecast.
+
+use std::collections::HashMap;
 
 use crate::client::DhanClient;
-use crate::error::Result;
+use crate::error::{DhanError, Result};
+use crate::types::builders::{MarginCalculatorRequestBuilder, MultiMarginRequestBuilder};
 use crate::types::funds::*;
+use crate::types::instrument::InstrumentId;
+use crate::types::market_quote::MarketQuoteRequest;
+use crate::types::orders::PlaceOrderRequest;
 
 impl DhanClient {
     /// Calculate margin requirement for a single order.
@@ -15,6 +21,15 @@ impl DhanClient {
         self.post("/v2/margincalculator", req).await
     }
 
+    /// Like [`Self::calculate_margin`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn calculate_margin_for_self(
+        &self,
+        req: MarginCalculatorRequestBuilder,
+    ) -> Result<MarginCalculatorResponse> {
+        self.calculate_margin(&self.build_request(req)?).await
+    }
+
     /// Calculate margin requirements for multiple scripts in a single request.
     ///
     /// **Endpoint:** `POST /v2/margincalculator/multi`
@@ -25,6 +40,82 @@ impl DhanClient {
         self.post("/v2/margincalculator/multi", req).await
     }
 
+    /// Like [`Self::calculate_multi_margin`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn calculate_multi_margin_for_self(
+        &self,
+        req: MultiMarginRequestBuilder,
+    ) -> Result<MultiMarginResponse> {
+        self.calculate_multi_margin(&self.build_request(req)?).await
+    }
+
+    /// Predict the funds left after `orders` execute; see
+    /// [`FundsForecast`].
+    ///
+    /// The basket's margin — with the hedge benefit between its legs, but
+    /// not against open positions or orders — comes from the multi-order
+    /// margin calculator, fetched alongside the fund limits. Orders are
+    /// priced at their price, else their trigger price, else the last
+    /// traded price, looked up in one request.
+    ///
+    /// **Endpoints:** `POST /v2/margincalculator/multi`, `GET /v2/fundlimit`
+    /// and, for unpriced orders, `POST /v2/marketfeed/ltp`
+    pub async fn forecast_funds(&self, orders: &[PlaceOrderRequest]) -> Result<FundsForecast> {
+        if orders.is_empty() {
+            return Err(DhanError::InvalidArgument("empty basket".into()));
+        }
+        let mut unpriced: MarketQuoteRequest = HashMap::new();
+        for order in orders
+            .iter()
+            .filter(|o| o.price.or(o.trigger_price).is_none())
+        {
+            let security_id = order.security_id.parse().map_err(|_| {
+                DhanError::InvalidArgument(format!(
+                    "security ID {:?} is not numeric",
+                    order.security_id
+                ))
+            })?;
+            unpriced
+                .entry(order.exchange_segment.to_string())
+                .or_default()
+                .push(security_id);
+        }
+        let ltp = if unpriced.is_empty() {
+            None
+        } else {
+            Some(self.get_ltp(&unpriced).await?)
+        };
+
+        let mut scripts = Vec::with_capacity(orders.len());
+        for order in orders {
+            let price = match order.price.or(order.trigger_price) {
+                Some(price) => price,
+                None => {
+                    let id = InstrumentId::new(
+                        order.exchange_segment,
+                        order.security_id.parse().unwrap_or_default(),
+                    );
+                    ltp.as_ref()
+                        .and_then(|r| r.get(&id))
+                        .map(|t| t.last_price)
+                        .ok_or_else(|| {
+                            DhanError::InvalidArgument(format!("no last traded price for {id}"))
+                        })?
+                }
+            };
+            scripts.push(MarginScript::from_order(order, price));
+        }
+        let req = MultiMarginRequest {
+            include_position: Some(false),
+            include_orders: Some(false),
+            dhan_client_id: Some(self.client_id().to_owned()),
+            scripts,
+        };
+        let (margin, funds) =
+            tokio::try_join!(self.calculate_multi_margin(&req), self.get_fund_limit())?;
+        FundsForecast::new(&funds, &margin)
+    }
+
     /// Retrieve fund limits for the trading account.
     ///
     /// Returns balance, margin utilised, collateral, and other fund details.
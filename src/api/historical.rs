@@ -1,9 +1,23 @@
 //! Historical Data endpoints — Daily and Intraday candle data.
 
+use std::io::{self, BufReader, Read};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_util::Stream;
+use tokio::sync::mpsc;
+
 use crate::client::DhanClient;
-use crate::error::Result;
+use crate::error::{DhanError, Result};
 use crate::types::historical::*;
 
+/// Response chunks buffered between the network and the parser.
+const CHUNK_BUFFER: usize = 16;
+
+/// Parsed candles buffered ahead of the consumer.
+const CANDLE_BUFFER: usize = 1024;
+
 impl DhanClient {
     /// Retrieve daily OHLCV candle data for an instrument.
     ///
@@ -14,6 +28,52 @@ impl DhanClient {
         self.post("/v2/charts/historical", req).await
     }
 
+    /// Retrieve daily OHLCV candles as a stream, for multi-year requests.
+    ///
+    /// Unlike [`Self::get_daily_historical`], the response body is never
+    /// held in memory: it is parsed as it arrives, so peak memory is the
+    /// parsed arrays rather than the JSON text plus the arrays. Candles are
+    /// then yielded one at a time; a body that fails to parse ends the
+    /// stream with an error.
+    ///
+    /// **Endpoint:** `POST /v2/charts/historical`
+    ///
+    /// ```no_run
+    /// use dhan_rs::DhanClient;
+    /// use dhan_rs::types::enums::{ExchangeSegment, Instrument};
+    /// use dhan_rs::types::historical::HistoricalDataRequest;
+    /// use futures_util::StreamExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> dhan_rs::Result<()> {
+    /// let client = DhanClient::new("client-id", "token");
+    /// let req = HistoricalDataRequest {
+    ///     security_id: "1333".into(),
+    ///     exchange_segment: ExchangeSegment::NSE_EQ,
+    ///     instrument: Instrument::EQUITY,
+    ///     expiry_code: None,
+    ///     oi: None,
+    ///     from_date: "2005-01-01".into(),
+    ///     to_date: "2025-01-01".into(),
+    /// };
+    /// let mut candles = client.get_daily_historical_stream(&req).await?;
+    /// while let Some(candle) = candles.next().await {
+    ///     let candle = candle?;
+    ///     println!("{} {}", candle.timestamp, candle.close);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_daily_historical_stream(
+        &self,
+        req: &HistoricalDataRequest,
+    ) -> Result<CandleStream> {
+        let resp = self
+            .post_raw("/v2/charts/historical", serde_json::to_vec(req)?)
+            .await?;
+        Ok(CandleStream::spawn(resp))
+    }
+
     /// Retrieve intraday OHLCV candle data for an instrument.
     ///
     /// Supports 1, 5, 15, 25, and 60-minute intervals.
@@ -24,3 +84,87 @@ impl DhanClient {
         self.post("/v2/charts/intraday", req).await
     }
 }
+
+// ---------------------------------------------------------------------------
+// Candle stream
+// ---------------------------------------------------------------------------
+
+/// Candles of a streamed historical response, in response order.
+///
+/// Returned by [`DhanClient::get_daily_historical_stream`]. Dropping the
+/// stream stops the download.
+#[derive(Debug)]
+pub struct CandleStream {
+    rx: mpsc::Receiver<Result<Candle>>,
+}
+
+impl CandleStream {
+    /// Download `resp` and parse it on a blocking thread.
+    fn spawn(mut resp: reqwest::Response) -> Self {
+        let (chunk_tx, chunk_rx) = mpsc::channel::<io::Result<Bytes>>(CHUNK_BUFFER);
+        let (tx, rx) = mpsc::channel(CANDLE_BUFFER);
+
+        tokio::spawn(async move {
+            loop {
+                let chunk = match resp.chunk().await {
+                    Ok(Some(chunk)) => Ok(chunk),
+                    Ok(None) => break,
+                    Err(e) => Err(io::Error::other(e)),
+                };
+                let failed = chunk.is_err();
+                if chunk_tx.send(chunk).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        tokio::task::spawn_blocking(move || {
+            let reader = BufReader::new(ChunkReader {
+                rx: chunk_rx,
+                chunk: Bytes::new(),
+            });
+            let data: CandleData = match serde_json::from_reader(reader) {
+                Ok(data) => data,
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(DhanError::Json(e)));
+                    return;
+                }
+            };
+            for candle in (0..data.len()).filter_map(|i| data.candle(i)) {
+                if tx.blocking_send(Ok(candle)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Self { rx }
+    }
+}
+
+impl Stream for CandleStream {
+    type Item = Result<Candle>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Blocking [`Read`] over response chunks sent from the download task.
+struct ChunkReader {
+    rx: mpsc::Receiver<io::Result<Bytes>>,
+    chunk: Bytes,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.rx.blocking_recv() {
+                Some(chunk) => self.chunk = chunk?,
+                None => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.chunk.len());
+        buf[..n].copy_from_slice(&self.chunk.split_to(n));
+        Ok(n)
+    }
+}
@@ -1,8 +1,11 @@
 //! Static IP management endpoints.
 
 use crate::client::DhanClient;
-use crate::error::Result;
-use crate::types::auth::{IpInfo, IpSetResponse, SetIpRequest};
+use crate::constants::PUBLIC_IP_ECHO_URL;
+use crate::error::{DhanError, Result};
+use crate::types::IpFlag;
+use crate::types::auth::{IpInfo, IpSetResponse, IpWhitelistStatus, SetIpRequest};
+use crate::types::builders::SetIpRequestBuilder;
 
 impl DhanClient {
     /// Set a primary or secondary static IP for the account.
@@ -34,6 +37,12 @@ impl DhanClient {
         self.post("/v2/ip/setIP", req).await
     }
 
+    /// Like [`Self::set_ip`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn set_ip_for_self(&self, req: SetIpRequestBuilder) -> Result<IpSetResponse> {
+        self.set_ip(&self.build_request(req)?).await
+    }
+
     /// Modify a previously set primary or secondary static IP.
     ///
     /// Can only be used when IP modification is allowed (once every 7 days).
@@ -43,6 +52,12 @@ impl DhanClient {
         self.put("/v2/ip/modifyIP", req).await
     }
 
+    /// Like [`Self::modify_ip`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn modify_ip_for_self(&self, req: SetIpRequestBuilder) -> Result<IpSetResponse> {
+        self.modify_ip(&self.build_request(req)?).await
+    }
+
     /// Get the currently configured static IPs and their modification dates.
     ///
     /// **Endpoint:** `GET /v2/ip/getIP`
@@ -64,4 +79,105 @@ impl DhanClient {
     pub async fn get_ip(&self) -> Result<IpInfo> {
         self.get("/v2/ip/getIP").await
     }
+
+    // -----------------------------------------------------------------------
+    // Current-IP helpers
+    // -----------------------------------------------------------------------
+
+    /// Discover this machine's public IP from [`PUBLIC_IP_ECHO_URL`].
+    pub async fn public_ip(&self) -> Result<String> {
+        self.public_ip_from(PUBLIC_IP_ECHO_URL).await
+    }
+
+    /// Discover this machine's public IP from an echo service that returns
+    /// it as plain text.
+    pub async fn public_ip_from(&self, echo_url: &str) -> Result<String> {
+        let body = self
+            .send(
+                self.http()
+                    .get(echo_url)
+                    .header(reqwest::header::ACCEPT, "text/plain"),
+            )
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let ip = body.trim();
+        ip.parse::<std::net::IpAddr>().map_err(|_| {
+            DhanError::InvalidArgument(format!("echo service returned {ip:?}, not an IP"))
+        })?;
+        Ok(ip.to_owned())
+    }
+
+    /// Whitelist this machine's public IP in `slot`.
+    ///
+    /// Calls [`Self::set_ip`] for an empty slot and [`Self::modify_ip`] for
+    /// one holding another IP. Fails without calling either if the IP is
+    /// already in `slot`, since every change locks the slot for 7 days.
+    pub async fn set_current_public_ip(&self, slot: IpFlag) -> Result<IpSetResponse> {
+        self.set_current_public_ip_via(slot, PUBLIC_IP_ECHO_URL)
+            .await
+    }
+
+    /// Like [`Self::set_current_public_ip`], discovering the IP from
+    /// `echo_url`.
+    pub async fn set_current_public_ip_via(
+        &self,
+        slot: IpFlag,
+        echo_url: &str,
+    ) -> Result<IpSetResponse> {
+        let ip = self.public_ip_from(echo_url).await?;
+        let info = self.get_ip().await?;
+        if info.ip(slot) == Some(ip.as_str()) {
+            return Err(DhanError::InvalidArgument(format!(
+                "{ip} is already whitelisted as {slot:?}"
+            )));
+        }
+        let req = SetIpRequest {
+            dhan_client_id: self.client_id().to_owned(),
+            ip,
+            ip_flag: slot,
+        };
+        tracing::info!(
+            ip = req.ip,
+            ?slot,
+            "Whitelisting IP; the slot locks for 7 days"
+        );
+        if info.ip(slot).is_some() {
+            self.modify_ip(&req).await
+        } else {
+            self.set_ip(&req).await
+        }
+    }
+
+    /// Compare the whitelisted IPs against this machine's public IP.
+    ///
+    /// Logs a warning if the current IP is not whitelisted, including the
+    /// date each slot can next be modified.
+    pub async fn verify_ip_whitelisting(&self) -> Result<IpWhitelistStatus> {
+        self.verify_ip_whitelisting_via(PUBLIC_IP_ECHO_URL).await
+    }
+
+    /// Like [`Self::verify_ip_whitelisting`], discovering the IP from
+    /// `echo_url`.
+    pub async fn verify_ip_whitelisting_via(&self, echo_url: &str) -> Result<IpWhitelistStatus> {
+        let current_ip = self.public_ip_from(echo_url).await?;
+        let info = self.get_ip().await?;
+        let status = IpWhitelistStatus {
+            slot: info.slot_of(&current_ip),
+            current_ip,
+            info,
+        };
+        if !status.is_whitelisted() {
+            tracing::warn!(
+                current_ip = status.current_ip,
+                primary = ?status.info.primary_ip,
+                primary_modifiable_from = ?status.info.modify_date(IpFlag::PRIMARY),
+                secondary = ?status.info.secondary_ip,
+                secondary_modifiable_from = ?status.info.modify_date(IpFlag::SECONDARY),
+                "Current IP is not whitelisted; order APIs will be refused"
+            );
+        }
+        Ok(status)
+    }
 }
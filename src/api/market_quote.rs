@@ -1,9 +1,18 @@
 //! Market Quote endpoints — LTP, OHLC, Market Depth snapshots.
 
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::batch::BatchReport;
+use crate::cache::REST_QUOTE_INTERVAL;
 use crate::client::DhanClient;
-use crate::error::Result;
+use crate::error::{DhanError, Result};
 use crate::types::market_quote::*;
 
+/// A response being parsed off the async path.
+type Parsing = JoinHandle<Result<MarketQuoteResponse<QuoteData>>>;
+
 impl DhanClient {
     /// Retrieve LTP (Last Traded Price) for a list of instruments.
     ///
@@ -41,4 +50,66 @@ impl DhanClient {
     ) -> Result<MarketQuoteResponse<QuoteData>> {
         self.post("/v2/marketfeed/quote", instruments).await
     }
+
+    /// Fetch full quotes for several requests.
+    ///
+    /// Requests go out one at a time, [`REST_QUOTE_INTERVAL`] apart as the
+    /// endpoint allows, while the next body is serialized and earlier
+    /// responses are parsed on blocking threads, so a batch of large
+    /// requests costs little more than the pacing itself. Keep each request
+    /// within 1000 instruments. Look quotes up across the responses with
+    /// [`BatchReport::get`].
+    ///
+    /// A failed request does not stop the others: it is reported in
+    /// [`BatchReport::failed`] with its error, ready for
+    /// [`BatchReport::retry_failed`].
+    ///
+    /// **Endpoint:** `POST /v2/marketfeed/quote`
+    pub async fn get_quotes_multi(
+        &self,
+        requests: Vec<MarketQuoteRequest>,
+    ) -> BatchReport<MarketQuoteResponse<QuoteData>, MarketQuoteRequest> {
+        let (body_tx, mut body_rx) = mpsc::channel(2);
+        tokio::task::spawn_blocking(move || {
+            for req in requests {
+                let body = serde_json::to_vec(&req);
+                if body_tx.blocking_send((req, body)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let mut pending: Vec<(MarketQuoteRequest, Result<Parsing>)> = Vec::new();
+        let mut last_sent: Option<Instant> = None;
+        while let Some((req, body)) = body_rx.recv().await {
+            if let Some(at) = last_sent {
+                tokio::time::sleep_until(at + REST_QUOTE_INTERVAL).await;
+            }
+            last_sent = Some(Instant::now());
+            let fetched = async {
+                let resp = self.post_raw("/v2/marketfeed/quote", body?).await?;
+                let bytes = resp.bytes().await?;
+                Ok(tokio::task::spawn_blocking(move || {
+                    serde_json::from_slice(&bytes).map_err(DhanError::Json)
+                }))
+            };
+            pending.push((req, fetched.await));
+        }
+
+        let mut report = BatchReport::new();
+        for (index, (req, parsing)) in pending.into_iter().enumerate() {
+            let parsed = match parsing {
+                Ok(handle) => handle
+                    .await
+                    .unwrap_or_else(|e| Err(DhanError::Io(std::io::Error::other(e)))),
+                Err(e) => Err(e),
+            };
+            if let Err(error) = &parsed {
+                let instruments = req.values().map(Vec::len).sum::<usize>();
+                tracing::warn!(index, instruments, %error, "Quote request failed");
+            }
+            report.push(req, parsed);
+        }
+        report
+    }
 }
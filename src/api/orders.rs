@@ -1,7 +1,16 @@
 //! Order management endpoints.
 
+use std::time::Duration;
+
+use futures_util::{StreamExt, stream};
+use tokio::time::Instant;
+
+use crate::batch::BatchReport;
 use crate::client::DhanClient;
-use crate::error::Result;
+use crate::constants::rate_limits::orders::PER_SECOND;
+use crate::error::{DhanError, Result};
+use crate::oms::bulk::OrderFilter;
+use crate::types::builders::{ModifyOrderRequestBuilder, PlaceOrderRequestBuilder};
 use crate::types::orders::*;
 
 impl DhanClient {
@@ -9,7 +18,17 @@ impl DhanClient {
     ///
     /// **Endpoint:** `POST /v2/orders`
     pub async fn place_order(&self, req: &PlaceOrderRequest) -> Result<OrderResponse> {
-        self.post("/v2/orders", req).await
+        self.check_duplicate(req)?;
+        self.post("/v2/orders", &*self.correlated(req)).await
+    }
+
+    /// Like [`Self::place_order`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn place_order_for_self(
+        &self,
+        req: PlaceOrderRequestBuilder,
+    ) -> Result<OrderResponse> {
+        self.place_order(&self.build_request(req)?).await
     }
 
     /// Modify a pending order.
@@ -23,6 +42,83 @@ impl DhanClient {
         self.put(&format!("/v2/orders/{order_id}"), req).await
     }
 
+    /// Like [`Self::modify_order`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn modify_order_for_self(
+        &self,
+        order_id: &str,
+        req: ModifyOrderRequestBuilder,
+    ) -> Result<OrderResponse> {
+        self.modify_order(order_id, &self.build_request(req)?).await
+    }
+
+    /// Modify a pending order starting from its current state.
+    ///
+    /// The API wants every field of [`ModifyOrderRequest`] even to change
+    /// one, so this fetches the order, fills the request from it (see
+    /// [`ModifyOrderRequest::from_order`]), lets `edit` change what should
+    /// change and sends the result. Fails without sending if the order is
+    /// no longer working.
+    ///
+    /// ```no_run
+    /// # async fn run(client: &dhan_rs::DhanClient) -> dhan_rs::Result<()> {
+    /// client
+    ///     .modify_order_with("112111182198", |req| {
+    ///         req.price = Some(1502.5);
+    ///         req.quantity = Some(20);
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn modify_order_with(
+        &self,
+        order_id: &str,
+        edit: impl FnOnce(&mut ModifyOrderRequest),
+    ) -> Result<OrderResponse> {
+        let order = self.get_order(order_id).await?;
+        if let Some(status) = order.status() {
+            if !status.is_working() {
+                return Err(DhanError::InvalidArgument(format!(
+                    "order {order_id} is {status:?}, not working"
+                )));
+            }
+        }
+        let mut req = ModifyOrderRequest::from_order(self.client_id(), &order)?;
+        req.order_id = order_id.to_owned();
+        edit(&mut req);
+        self.modify_order(order_id, &req).await
+    }
+
+    /// Change the limit price of a pending order, keeping everything else.
+    /// See [`Self::modify_order_with`].
+    pub async fn modify_order_price(&self, order_id: &str, price: f64) -> Result<OrderResponse> {
+        self.modify_order_with(order_id, |req| req.price = Some(price))
+            .await
+    }
+
+    /// Change the trigger price of a pending stop-loss order, keeping
+    /// everything else. See [`Self::modify_order_with`].
+    pub async fn modify_order_trigger_price(
+        &self,
+        order_id: &str,
+        trigger_price: f64,
+    ) -> Result<OrderResponse> {
+        self.modify_order_with(order_id, |req| req.trigger_price = Some(trigger_price))
+            .await
+    }
+
+    /// Change the quantity of a pending order, keeping everything else.
+    /// See [`Self::modify_order_with`].
+    pub async fn modify_order_quantity(
+        &self,
+        order_id: &str,
+        quantity: u64,
+    ) -> Result<OrderResponse> {
+        self.modify_order_with(order_id, |req| req.quantity = Some(quantity))
+            .await
+    }
+
     /// Cancel a pending order.
     ///
     /// **Endpoint:** `DELETE /v2/orders/{order-id}`
@@ -34,7 +130,68 @@ impl DhanClient {
     ///
     /// **Endpoint:** `POST /v2/orders/slicing`
     pub async fn slice_order(&self, req: &PlaceOrderRequest) -> Result<Vec<OrderResponse>> {
-        self.post("/v2/orders/slicing", req).await
+        self.check_duplicate(req)?;
+        self.post("/v2/orders/slicing", &*self.correlated(req))
+            .await
+    }
+
+    /// Like [`Self::slice_order`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn slice_order_for_self(
+        &self,
+        req: PlaceOrderRequestBuilder,
+    ) -> Result<Vec<OrderResponse>> {
+        self.slice_order(&self.build_request(req)?).await
+    }
+
+    /// Cancel every working order matching `filter`.
+    ///
+    /// Fetches the order book and cancels the matching orders concurrently,
+    /// starting at most [`rate_limits::orders::PER_SECOND`] cancellations a
+    /// second. Fails only if the order book cannot be fetched; each
+    /// cancellation's outcome is in the report, keyed by its order.
+    ///
+    /// ```no_run
+    /// use dhan_rs::oms::bulk::OrderFilter;
+    /// use dhan_rs::types::enums::ExchangeSegment;
+    ///
+    /// # async fn run(client: &dhan_rs::DhanClient) -> dhan_rs::Result<()> {
+    /// let filter = OrderFilter::new().with_segment(ExchangeSegment::NSE_FNO);
+    /// let report = client.cancel_all_orders(&filter).await?;
+    /// for (order, err) in &report.failed {
+    ///     eprintln!("{:?} still open: {err}", order.order_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`rate_limits::orders::PER_SECOND`]: crate::constants::rate_limits::orders::PER_SECOND
+    pub async fn cancel_all_orders(
+        &self,
+        filter: &OrderFilter,
+    ) -> Result<BatchReport<OrderResponse, OrderDetail>> {
+        let orders = self.get_orders().await?;
+        let pacing = Duration::from_secs(1) / PER_SECOND;
+        let start = Instant::now();
+        let report = stream::iter(
+            orders
+                .into_iter()
+                .filter(|o| filter.matches(o) && o.order_id.is_some()),
+        )
+        .enumerate()
+        .map(|(i, order)| async move {
+            tokio::time::sleep_until(start + pacing * i as u32).await;
+            let order_id = order.order_id.as_deref().unwrap_or_default();
+            let result = self.cancel_order(order_id).await;
+            if let Err(e) = &result {
+                tracing::warn!(order_id, "Cancel failed: {e}");
+            }
+            (order, result)
+        })
+        .buffer_unordered(PER_SECOND as usize)
+        .collect::<BatchReport<_, _>>()
+        .await;
+        Ok(report)
     }
 
     /// Retrieve all orders for the day.
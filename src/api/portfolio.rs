@@ -1,7 +1,13 @@
-//! Portfolio endpoints — Holdings, Positions, Convert Position, Exit All.
+//! Portfolio endpoints — Holdings, Positions, Convert Position, Square Off,
+//! Exit All.
 
 use crate::client::DhanClient;
-use crate::error::Result;
+use crate::error::{DhanError, Result};
+use crate::types::builders::ConvertPositionRequestBuilder;
+use crate::types::enums::OrderType;
+use crate::types::instrument::InstrumentId;
+use crate::types::market_quote::MarketQuoteRequest;
+use crate::types::orders::{OrderResponse, PlaceOrderRequest};
 use crate::types::portfolio::*;
 
 impl DhanClient {
@@ -28,6 +34,59 @@ impl DhanClient {
         self.post_no_content("/v2/positions/convert", req).await
     }
 
+    /// Like [`Self::convert_position`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn convert_position_for_self(
+        &self,
+        req: ConvertPositionRequestBuilder,
+    ) -> Result<()> {
+        self.convert_position(&self.build_request(req)?).await
+    }
+
+    /// Flatten one position with an opposite-side order; see
+    /// [`PlaceOrderRequest::square_off`].
+    ///
+    /// A [`OrderType::LIMIT`] order is priced at the instrument's last
+    /// traded price, fetched first. Stop-loss types need a trigger price:
+    /// build those with [`PlaceOrderRequest::square_off`] and
+    /// [`Self::place_order`].
+    pub async fn square_off_position(
+        &self,
+        position: &Position,
+        order_type: OrderType,
+    ) -> Result<OrderResponse> {
+        let mut order = PlaceOrderRequest::square_off(self.client_id(), position, order_type)?;
+        match order_type {
+            OrderType::MARKET => {}
+            OrderType::LIMIT => {
+                let id = InstrumentId::new(
+                    order.exchange_segment,
+                    order.security_id.parse().map_err(|_| {
+                        DhanError::InvalidArgument(format!(
+                            "security ID {:?} is not numeric",
+                            order.security_id
+                        ))
+                    })?,
+                );
+                let req = MarketQuoteRequest::from([(
+                    id.segment.to_string(),
+                    vec![u64::from(id.security_id)],
+                )]);
+                let ltp = self.get_ltp(&req).await?;
+                let price = ltp.get(&id).map(|t| t.last_price).ok_or_else(|| {
+                    DhanError::InvalidArgument(format!("no last traded price for {id}"))
+                })?;
+                order.price = Some(price);
+            }
+            OrderType::STOP_LOSS | OrderType::STOP_LOSS_MARKET => {
+                return Err(DhanError::InvalidArgument(
+                    "stop-loss square-off needs a trigger price".into(),
+                ));
+            }
+        }
+        self.place_order(&order).await
+    }
+
     /// Exit all active positions and cancel all open orders.
     ///
     /// **Endpoint:** `DELETE /v2/positions`
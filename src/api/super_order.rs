@@ -2,6 +2,7 @@
 
 use crate::client::DhanClient;
 use crate::error::Result;
+use crate::types::builders::{ModifySuperOrderRequestBuilder, PlaceSuperOrderRequestBuilder};
 use crate::types::orders::OrderResponse;
 use crate::types::super_order::*;
 
@@ -10,7 +11,16 @@ impl DhanClient {
     ///
     /// **Endpoint:** `POST /v2/super/orders`
     pub async fn place_super_order(&self, req: &PlaceSuperOrderRequest) -> Result<OrderResponse> {
-        self.post("/v2/super/orders", req).await
+        self.post("/v2/super/orders", &*self.correlated(req)).await
+    }
+
+    /// Like [`Self::place_super_order`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn place_super_order_for_self(
+        &self,
+        req: PlaceSuperOrderRequestBuilder,
+    ) -> Result<OrderResponse> {
+        self.place_super_order(&self.build_request(req)?).await
     }
 
     /// Modify a pending super order.
@@ -24,6 +34,17 @@ impl DhanClient {
         self.put(&format!("/v2/super/orders/{order_id}"), req).await
     }
 
+    /// Like [`Self::modify_super_order`], filling `dhan_client_id` from this client
+    /// when the builder leaves it unset.
+    pub async fn modify_super_order_for_self(
+        &self,
+        order_id: &str,
+        req: ModifySuperOrderRequestBuilder,
+    ) -> Result<OrderResponse> {
+        self.modify_super_order(order_id, &self.build_request(req)?)
+            .await
+    }
+
     /// Cancel a super order leg.
     ///
     /// Cancelling the `ENTRY_LEG` cancels all legs.
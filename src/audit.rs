@@ -0,0 +1,216 @@
+//! Tamper-evident audit trail of outgoing order requests.
+//!
+//! For compliance it is not enough to log orders; one must be able to show
+//! later exactly what was sent and when. A client with an [`AuditLog`]
+//! (see [`DhanClient::with_audit_log`]) hashes the exact bytes of every
+//! order-changing request (everything needing [`Scope::OrderWrite`]) with
+//! SHA-256 and appends the hash, method, path and timestamp to a
+//! [`Journal`] *before* sending it. Records are synced to disk, and if one
+//! cannot be written the request is not sent.
+//!
+//! Each record also carries the hash of the previous one, so editing,
+//! removing or reordering records breaks the chain. [`AuditLog::verify`]
+//! checks the chain, including for a torn final record, and
+//! [`AuditLog::find`] locates the records of a given payload.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::audit::AuditLog;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let audit = AuditLog::open("orders-audit.jsonl")?;
+//! let client = DhanClient::new("client-id", "token").with_audit_log(audit);
+//! // ... place orders ...
+//!
+//! let report = AuditLog::verify("orders-audit.jsonl")?;
+//! assert!(report.is_intact());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Scope::OrderWrite`]: crate::scope::Scope::OrderWrite
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::journal::Journal;
+
+/// `prev_hash` of the first record.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One audited request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Position in the log, from 0.
+    pub seq: u64,
+    /// When the request was about to be sent.
+    pub at: DateTime<Utc>,
+    /// HTTP method.
+    pub method: String,
+    /// Request path (with query string).
+    pub path: String,
+    /// SHA-256 of the request body bytes, hex.
+    pub payload_sha256: String,
+    /// `hash` of the previous record ([`GENESIS_HASH`] for the first).
+    pub prev_hash: String,
+    /// SHA-256 over this record's other fields, hex.
+    pub hash: String,
+}
+
+impl AuditRecord {
+    /// The hash this record should carry, recomputed from its fields.
+    pub fn compute_hash(&self) -> String {
+        let at = self.at.to_rfc3339_opts(SecondsFormat::Nanos, true);
+        sha256_hex(
+            format!(
+                "{}|{}|{at}|{}|{}|{}",
+                self.prev_hash, self.seq, self.method, self.path, self.payload_sha256
+            )
+            .as_bytes(),
+        )
+    }
+}
+
+/// Result of [`AuditLog::verify`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditReport {
+    /// Records read.
+    pub records: usize,
+    /// Sequence number of the first record whose hash, chain link or
+    /// sequence is wrong.
+    pub first_invalid: Option<u64>,
+    /// Whether the journal ends in a partially written record, e.g. from a
+    /// crash mid-write. The torn record is not counted in `records`.
+    pub torn_tail: bool,
+}
+
+impl AuditReport {
+    /// Returns `true` if every record checks out and none is torn.
+    pub fn is_intact(&self) -> bool {
+        self.first_invalid.is_none() && !self.torn_tail
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    journal: Journal,
+    next_seq: u64,
+    last_hash: String,
+}
+
+/// Appends [`AuditRecord`]s to a journal.
+///
+/// Cloning is cheap and clones append to the same chain.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    state: Arc<Mutex<State>>,
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Open (or create) the audit journal at `path`, continuing its chain.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let last = Journal::read::<AuditRecord>(&path)?.pop();
+        Ok(Self {
+            state: Arc::new(Mutex::new(State {
+                journal: Journal::open(&path)?.with_sync(true),
+                next_seq: last.as_ref().map_or(0, |r| r.seq + 1),
+                last_hash: last.map_or_else(|| GENESIS_HASH.to_owned(), |r| r.hash),
+            })),
+            path,
+        })
+    }
+
+    /// Path of the audit journal.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record that `method path` is being sent with body `payload`.
+    pub fn record(&self, method: &str, path: &str, payload: &[u8]) -> Result<AuditRecord> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut record = AuditRecord {
+            seq: state.next_seq,
+            at: Utc::now(),
+            method: method.to_owned(),
+            path: path.to_owned(),
+            payload_sha256: payload_hash(payload),
+            prev_hash: state.last_hash.clone(),
+            hash: String::new(),
+        };
+        record.hash = record.compute_hash();
+        state.journal.append(&record)?;
+        state.next_seq += 1;
+        state.last_hash = record.hash.clone();
+        Ok(record)
+    }
+
+    /// Check every record of the audit journal at `path`: its hash, its
+    /// link to the previous record and its sequence number.
+    pub fn verify(path: impl AsRef<Path>) -> Result<AuditReport> {
+        let (records, torn_tail) = Journal::read_with_torn_tail::<AuditRecord>(path)?;
+        let mut prev_hash = GENESIS_HASH.to_owned();
+        let mut first_invalid = None;
+        for (i, record) in records.iter().enumerate() {
+            if record.seq != i as u64
+                || record.prev_hash != prev_hash
+                || record.hash != record.compute_hash()
+            {
+                first_invalid = Some(i as u64);
+                break;
+            }
+            prev_hash = record.hash.clone();
+        }
+        Ok(AuditReport {
+            records: records.len(),
+            first_invalid,
+            torn_tail,
+        })
+    }
+
+    /// Records in the audit journal at `path` whose body was exactly
+    /// `payload`.
+    pub fn find(path: impl AsRef<Path>, payload: &[u8]) -> Result<Vec<AuditRecord>> {
+        let hash = payload_hash(payload);
+        Ok(Journal::read::<AuditRecord>(path)?
+            .into_iter()
+            .filter(|r| r.payload_sha256 == hash)
+            .collect())
+    }
+}
+
+/// SHA-256 of a request body, hex, as stored in
+/// [`AuditRecord::payload_sha256`].
+///
+/// Request structs are sent as `serde_json::to_vec(&request)`; hash those
+/// bytes to look a request up.
+pub fn payload_hash(payload: &[u8]) -> String {
+    sha256_hex(payload)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        })
+}
+
+impl DhanClient {
+    /// Record every order-changing request in `log` before sending it; see
+    /// [`crate::audit`].
+    pub fn with_audit_log(mut self, log: AuditLog) -> Self {
+        self.set_audit_log(log);
+        self
+    }
+}
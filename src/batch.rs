@@ -0,0 +1,120 @@
+//! Partial-failure results of batch operations.
+//!
+//! Operations that send many requests — chunked quote fetches, square-off
+//! rounds, alert fan-out — keep going when one request fails. They return
+//! a [`BatchReport`]: the outputs of the requests that succeeded and, for
+//! each one that failed, its input together with the error, so callers
+//! handle partial failure the same way everywhere and can resend exactly
+//! what failed:
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::types::market_quote::MarketQuoteRequest;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let client = DhanClient::new("client-id", "token");
+//! let requests: Vec<MarketQuoteRequest> = Vec::new();
+//!
+//! let mut report = client.get_quotes_multi(requests).await;
+//! if !report.all_ok() {
+//!     report.retry_failed(async |req| client.get_quote(req).await).await;
+//! }
+//! for (req, err) in &report.failed {
+//!     eprintln!("{} instruments not fetched: {err}", req.values().map(Vec::len).sum::<usize>());
+//! }
+//! # }
+//! ```
+
+use crate::error::{DhanError, Result};
+
+/// Outputs of the succeeded requests of a batch and the inputs of the
+/// failed ones with their errors.
+#[derive(Debug)]
+pub struct BatchReport<T, I> {
+    /// Outputs of the requests that succeeded, in completion order.
+    pub succeeded: Vec<T>,
+    /// Inputs of the requests that failed, with their errors.
+    pub failed: Vec<(I, DhanError)>,
+}
+
+impl<T, I> Default for BatchReport<T, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, I> BatchReport<T, I> {
+    /// An empty report.
+    pub fn new() -> Self {
+        Self {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+
+    /// Record the outcome of the request made for `input`.
+    pub fn push(&mut self, input: I, result: Result<T>) {
+        match result {
+            Ok(output) => self.succeeded.push(output),
+            Err(e) => self.failed.push((input, e)),
+        }
+    }
+
+    /// Returns `true` if no request failed.
+    pub fn all_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Number of requests recorded.
+    pub fn len(&self) -> usize {
+        self.succeeded.len() + self.failed.len()
+    }
+
+    /// Returns `true` if no request was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The inputs of the failed requests.
+    pub fn failed_inputs(&self) -> impl Iterator<Item = &I> {
+        self.failed.iter().map(|(input, _)| input)
+    }
+
+    /// Send every failed input through `op` again, one at a time.
+    ///
+    /// Successes move to [`Self::succeeded`]; inputs failing again stay in
+    /// [`Self::failed`] with the new error. Returns how many succeeded.
+    pub async fn retry_failed(&mut self, mut op: impl AsyncFnMut(&I) -> Result<T>) -> usize {
+        let before = self.succeeded.len();
+        for (input, _) in std::mem::take(&mut self.failed) {
+            let result = op(&input).await;
+            self.push(input, result);
+        }
+        self.succeeded.len() - before
+    }
+
+    /// The outputs if every request succeeded, otherwise the first error.
+    pub fn into_result(self) -> Result<Vec<T>> {
+        match self.failed.into_iter().next() {
+            Some((_, e)) => Err(e),
+            None => Ok(self.succeeded),
+        }
+    }
+}
+
+impl<T, I> FromIterator<(I, Result<T>)> for BatchReport<T, I> {
+    fn from_iter<It: IntoIterator<Item = (I, Result<T>)>>(iter: It) -> Self {
+        let mut report = Self::new();
+        report.extend(iter);
+        report
+    }
+}
+
+impl<T, I> Extend<(I, Result<T>)> for BatchReport<T, I> {
+    fn extend<It: IntoIterator<Item = (I, Result<T>)>>(&mut self, iter: It) {
+        for (input, result) in iter {
+            self.push(input, result);
+        }
+    }
+}
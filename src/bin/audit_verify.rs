@@ -0,0 +1,60 @@
+//! Binary to verify an order audit journal (see `dhan_rs::audit`) and,
+//! optionally, show when a given request body was sent.
+//!
+//! # Usage
+//!
+//! ```sh
+//! cargo run --bin audit_verify --features cli -- orders-audit.jsonl
+//! cargo run --bin audit_verify --features cli -- orders-audit.jsonl request.json
+//! ```
+//!
+//! `request.json` must hold the exact bytes that were sent, i.e.
+//! `serde_json::to_vec` of the request struct. Exits with status 1 if the
+//! chain is broken, the last record is torn or the body was never sent.
+
+use std::env;
+use std::process::ExitCode;
+
+use dhan_rs::audit::AuditLog;
+
+fn main() -> dhan_rs::error::Result<ExitCode> {
+    let mut args = env::args().skip(1);
+    let Some(journal) = args.next() else {
+        eprintln!("usage: audit_verify <journal> [payload-file]");
+        return Ok(ExitCode::from(2));
+    };
+
+    let report = AuditLog::verify(&journal)?;
+    match report.first_invalid {
+        None if report.torn_tail => {
+            println!(
+                "{journal}: {} records, chain intact but last record TORN",
+                report.records
+            );
+            return Ok(ExitCode::FAILURE);
+        }
+        None => println!("{journal}: {} records, chain intact", report.records),
+        Some(seq) => {
+            println!(
+                "{journal}: {} records, chain BROKEN at record {seq}",
+                report.records
+            );
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+
+    if let Some(payload) = args.next() {
+        let found = AuditLog::find(&journal, &std::fs::read(&payload)?)?;
+        if found.is_empty() {
+            println!("{payload}: not found");
+            return Ok(ExitCode::FAILURE);
+        }
+        for record in found {
+            println!(
+                "{payload}: record {} {} {} at {} (hash {})",
+                record.seq, record.method, record.path, record.at, record.hash
+            );
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,13 @@
+//! Re-distribution of the market feed beyond this process.
+//!
+//! Dhan limits a user to five feed connections. The bridges here share
+//! one connection's ticks with other machines on the LAN.
+//!
+//! ## [`multicast`] — UDP Multicast
+//!
+//! Sequenced tick datagrams sent to a multicast group, and a receiver that
+//! detects gaps and publisher restarts.
+//!
+//! For processes on the same host, see the `ws::shm` ring (feature `shm`).
+
+pub mod multicast;
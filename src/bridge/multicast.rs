@@ -0,0 +1,321 @@
+//! UDP multicast re-broadcast of normalized ticks.
+//!
+//! A [`MulticastPublisher`] sends every [`Tick`] of a feed channel to a
+//! multicast group, one datagram per tick; any number of machines on the
+//! LAN receive them with a [`MulticastSubscriber`]. UDP does not retransmit,
+//! so every datagram carries a sequence number and receivers count what
+//! they missed ([`MulticastMessage::missed`]) — re-fetch a REST quote for
+//! the affected instruments if the gap matters.
+//!
+//! # Datagram layout
+//!
+//! 104 bytes, little-endian:
+//!
+//! | Byte | Type      | Field                                                 |
+//! |------|-----------|-------------------------------------------------------|
+//! | 0    | `[u8; 4]` | Magic, ASCII `DHM1`                                   |
+//! | 4    | u32       | Session: random per publisher, changes on restart     |
+//! | 8    | u64       | Sequence number, from 0 per session                   |
+//! | 16   | u8        | Exchange segment code                                 |
+//! | 17   | u8        | Presence flags of the optional fields below (bit 0–6) |
+//! | 18   | u16       | Reserved (0)                                          |
+//! | 20   | u32       | Security ID                                           |
+//! | 24   | f64       | LTP                                                   |
+//! | 32   | i64       | Last trade time (feed epoch seconds)                  |
+//! | 40   | i64       | Last traded quantity (bit 0)                          |
+//! | 48   | i64       | Day volume (bit 1)                                    |
+//! | 56   | f64×4     | Open, high, low, close (bits 2–5)                     |
+//! | 88   | i64       | Open interest (bit 6)                                 |
+//!
+//! Absent optional fields are sent as 0 with their flag cleared.
+//!
+//! ```no_run
+//! use std::net::{Ipv4Addr, SocketAddrV4};
+//!
+//! use dhan_rs::bridge::multicast::{MulticastPublisher, MulticastSubscriber};
+//! use dhan_rs::ws::manager::{ConnectionId, DhanFeedManager};
+//!
+//! # async fn example(manager: &DhanFeedManager) -> dhan_rs::Result<()> {
+//! let group = SocketAddrV4::new(Ipv4Addr::new(239, 10, 10, 1), 30_001);
+//!
+//! // On the machine holding the Dhan connection:
+//! let publisher = MulticastPublisher::bind(group, Ipv4Addr::UNSPECIFIED).await?;
+//! let _task = publisher.spawn(manager.get_parsed_channel(ConnectionId(0)).unwrap());
+//!
+//! // On every other machine:
+//! let mut subscriber = MulticastSubscriber::join(group, Ipv4Addr::UNSPECIFIED).await?;
+//! loop {
+//!     let msg = subscriber.recv().await?;
+//!     if msg.missed > 0 {
+//!         eprintln!("lost {} ticks", msg.missed);
+//!     }
+//!     println!("{} {}", msg.tick.instrument, msg.tick.ltp);
+//! }
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::net::UdpSocket;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+use crate::error::Result;
+use crate::types::enums::ExchangeSegment;
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::{MarketFeedEvent, Tick};
+
+/// Size of one datagram.
+pub const DATAGRAM_LEN: usize = 104;
+
+const MAGIC: &[u8; 4] = b"DHM1";
+
+/// Default multicast TTL: stay on the local network.
+pub const DEFAULT_TTL: u32 = 1;
+
+// ---------------------------------------------------------------------------
+// Publisher
+// ---------------------------------------------------------------------------
+
+/// Sends sequenced tick datagrams to a multicast group.
+#[derive(Debug)]
+pub struct MulticastPublisher {
+    socket: UdpSocket,
+    target: SocketAddr,
+    session: u32,
+    next_seq: u64,
+}
+
+impl MulticastPublisher {
+    /// Send to `group` through the interface with address `interface`
+    /// ([`Ipv4Addr::UNSPECIFIED`] for the default route), with a TTL of
+    /// [`DEFAULT_TTL`].
+    pub async fn bind(group: SocketAddrV4, interface: Ipv4Addr) -> Result<Self> {
+        let socket = UdpSocket::bind(SocketAddrV4::new(interface, 0)).await?;
+        socket.set_multicast_ttl_v4(DEFAULT_TTL)?;
+        socket.set_multicast_loop_v4(true)?;
+        Ok(Self::from_socket(socket, group.into()))
+    }
+
+    /// Send through an already configured socket to `target`, which may
+    /// also be a unicast address.
+    pub fn from_socket(socket: UdpSocket, target: SocketAddr) -> Self {
+        let session = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| {
+            d.subsec_nanos() ^ d.as_secs() as u32 ^ std::process::id()
+        });
+        Self {
+            socket,
+            target,
+            session,
+            next_seq: 0,
+        }
+    }
+
+    /// Set the multicast TTL (router hops the datagrams may cross).
+    pub fn set_ttl(&self, ttl: u32) -> Result<()> {
+        Ok(self.socket.set_multicast_ttl_v4(ttl)?)
+    }
+
+    /// Session identifier sent with every datagram.
+    pub fn session(&self) -> u32 {
+        self.session
+    }
+
+    /// Send `tick`, returning its sequence number.
+    ///
+    /// The sequence number is consumed even if sending fails, so receivers
+    /// see the failure as a gap.
+    pub async fn send(&mut self, tick: &Tick) -> Result<u64> {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        let datagram = encode(self.session, seq, tick);
+        self.socket.send_to(&datagram, self.target).await?;
+        Ok(seq)
+    }
+
+    /// Send the ticks of `rx` (Ticker, Quote and Full events) in the
+    /// background until the channel closes. Send errors are logged.
+    pub fn spawn(mut self, mut rx: broadcast::Receiver<MarketFeedEvent>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        let Some(tick) = event.to_tick() else {
+                            continue;
+                        };
+                        if let Err(e) = self.send(&tick).await {
+                            tracing::warn!(error = %e, "Multicast send failed");
+                        }
+                    }
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!(dropped = n, "Multicast publisher fell behind");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Subscriber
+// ---------------------------------------------------------------------------
+
+/// A tick received by a [`MulticastSubscriber`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MulticastMessage {
+    /// Address of the publisher.
+    pub source: SocketAddr,
+    /// Publisher session.
+    pub session: u32,
+    /// Sequence number within the session.
+    pub seq: u64,
+    /// Datagrams of this session lost (or reordered) since the previous
+    /// one received.
+    pub missed: u64,
+    /// The tick.
+    pub tick: Tick,
+}
+
+/// Receives tick datagrams and tracks sequence gaps per publisher.
+#[derive(Debug)]
+pub struct MulticastSubscriber {
+    socket: UdpSocket,
+    /// (session, next expected sequence) per publisher address.
+    expected: HashMap<SocketAddr, (u32, u64)>,
+    missed: u64,
+    invalid: u64,
+}
+
+impl MulticastSubscriber {
+    /// Join `group` on the interface with address `interface`
+    /// ([`Ipv4Addr::UNSPECIFIED`] to let the OS choose), listening on the
+    /// group's port.
+    ///
+    /// Only one subscriber per host can bind the port unless the socket
+    /// is created with `SO_REUSEADDR`; pass such a socket to
+    /// [`Self::from_socket`] to run several.
+    pub async fn join(group: SocketAddrV4, interface: Ipv4Addr) -> Result<Self> {
+        let socket =
+            UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, group.port())).await?;
+        socket.join_multicast_v4(*group.ip(), interface)?;
+        Ok(Self::from_socket(socket))
+    }
+
+    /// Receive on an already bound (and joined) socket.
+    pub fn from_socket(socket: UdpSocket) -> Self {
+        Self {
+            socket,
+            expected: HashMap::new(),
+            missed: 0,
+            invalid: 0,
+        }
+    }
+
+    /// The next tick. Datagrams that are not valid tick datagrams are
+    /// skipped and counted in [`Self::invalid`].
+    pub async fn recv(&mut self) -> Result<MulticastMessage> {
+        let mut buf = [0u8; DATAGRAM_LEN + 1];
+        loop {
+            let (len, source) = self.socket.recv_from(&mut buf).await?;
+            let Some((session, seq, tick)) = decode(&buf[..len]) else {
+                self.invalid += 1;
+                continue;
+            };
+
+            let missed = match self.expected.get(&source) {
+                Some(&(s, next)) if s == session && seq >= next => seq - next,
+                // Late (reordered or duplicated) datagram of this session.
+                Some(&(s, next)) if s == session && seq < next => {
+                    self.invalid += 1;
+                    continue;
+                }
+                // First datagram of a publisher, or a publisher restart.
+                _ => 0,
+            };
+            if missed > 0 {
+                tracing::warn!(%source, missed, "Multicast sequence gap");
+            }
+            self.missed += missed;
+            self.expected.insert(source, (session, seq + 1));
+            return Ok(MulticastMessage {
+                source,
+                session,
+                seq,
+                missed,
+                tick,
+            });
+        }
+    }
+
+    /// Datagrams lost so far, across publishers.
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+
+    /// Malformed, late or duplicated datagrams skipped so far.
+    pub fn invalid(&self) -> u64 {
+        self.invalid
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Encoding
+// ---------------------------------------------------------------------------
+
+fn encode(session: u32, seq: u64, tick: &Tick) -> [u8; DATAGRAM_LEN] {
+    let mut out = [0u8; DATAGRAM_LEN];
+    out[0..4].copy_from_slice(MAGIC);
+    out[4..8].copy_from_slice(&session.to_le_bytes());
+    out[8..16].copy_from_slice(&seq.to_le_bytes());
+    out[16] = tick.instrument.segment.segment_code();
+    out[20..24].copy_from_slice(&tick.instrument.security_id.to_le_bytes());
+    out[24..32].copy_from_slice(&tick.ltp.to_le_bytes());
+    out[32..40].copy_from_slice(&tick.ltt.to_le_bytes());
+
+    let mut flags = 0u8;
+    let mut put = |offset: usize, bit: u8, value: Option<[u8; 8]>| {
+        if let Some(bytes) = value {
+            flags |= 1 << bit;
+            out[offset..offset + 8].copy_from_slice(&bytes);
+        }
+    };
+    put(40, 0, tick.last_qty.map(i64::to_le_bytes));
+    put(48, 1, tick.volume.map(i64::to_le_bytes));
+    put(56, 2, tick.open.map(f64::to_le_bytes));
+    put(64, 3, tick.high.map(f64::to_le_bytes));
+    put(72, 4, tick.low.map(f64::to_le_bytes));
+    put(80, 5, tick.close.map(f64::to_le_bytes));
+    put(88, 6, tick.oi.map(i64::to_le_bytes));
+    out[17] = flags;
+    out
+}
+
+fn decode(buf: &[u8]) -> Option<(u32, u64, Tick)> {
+    if buf.len() != DATAGRAM_LEN || &buf[0..4] != MAGIC {
+        return None;
+    }
+    let word = |offset: usize| -> [u8; 8] { buf[offset..offset + 8].try_into().unwrap() };
+    let flags = buf[17];
+    let opt = |offset: usize, bit: u8| (flags & (1 << bit) != 0).then(|| word(offset));
+
+    let session = u32::from_le_bytes(buf[4..8].try_into().unwrap());
+    let seq = u64::from_le_bytes(word(8));
+    let segment = ExchangeSegment::from_segment_code(buf[16])?;
+    let security_id = u32::from_le_bytes(buf[20..24].try_into().unwrap());
+    let tick = Tick {
+        instrument: InstrumentId::new(segment, security_id),
+        ltp: f64::from_le_bytes(word(24)),
+        ltt: i64::from_le_bytes(word(32)),
+        last_qty: opt(40, 0).map(i64::from_le_bytes),
+        volume: opt(48, 1).map(i64::from_le_bytes),
+        open: opt(56, 2).map(f64::from_le_bytes),
+        high: opt(64, 3).map(f64::from_le_bytes),
+        low: opt(72, 4).map(f64::from_le_bytes),
+        close: opt(80, 5).map(f64::from_le_bytes),
+        oi: opt(88, 6).map(i64::from_le_bytes),
+    };
+    Some((session, seq, tick))
+}
@@ -0,0 +1,118 @@
+//! Order routing behind a trait, live or simulated.
+//!
+//! [`Broker`] covers what strategy code needs from an account: placing,
+//! modifying and cancelling orders, and reading the order book, positions
+//! and holdings. [`DhanClient`] implements it against the live API;
+//! [`paper::PaperBroker`] fills orders in memory against market feed ticks
+//! or historical candles, so strategies can be exercised without the
+//! sandbox (which leaves most endpoints unimplemented).
+//!
+//! A [`StrategyRunner`] routes its orders through any broker given to
+//! [`StrategyRunner::with_broker`].
+//!
+//! ```
+//! use dhan_rs::broker::Broker;
+//! use dhan_rs::broker::paper::PaperBroker;
+//! use dhan_rs::types::enums::*;
+//! use dhan_rs::types::instrument::InstrumentId;
+//! use dhan_rs::types::orders::PlaceOrderRequest;
+//!
+//! async fn buy_one(broker: &dyn Broker) -> dhan_rs::Result<String> {
+//!     let req = PlaceOrderRequest::builder()
+//!         .dhan_client_id("1000000001")
+//!         .transaction_type(TransactionType::BUY)
+//!         .exchange_segment(ExchangeSegment::NSE_EQ)
+//!         .product_type(ProductType::INTRADAY)
+//!         .order_type(OrderType::MARKET)
+//!         .validity(Validity::DAY)
+//!         .security_id("1333")
+//!         .quantity(1)
+//!         .build()?;
+//!     Ok(broker.place_order(&req).await?.order_id)
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let paper = PaperBroker::new();
+//! paper.on_price(InstrumentId::new(ExchangeSegment::NSE_EQ, 1333), 1500.0);
+//! buy_one(&paper).await?;
+//! let positions = paper.get_positions().await?;
+//! assert_eq!(positions[0].net_qty, Some(1));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`StrategyRunner`]: crate::runtime::StrategyRunner
+//! [`StrategyRunner::with_broker`]: crate::runtime::StrategyRunner::with_broker
+
+pub mod paper;
+
+use futures_util::future::BoxFuture;
+
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::types::orders::{ModifyOrderRequest, OrderDetail, OrderResponse, PlaceOrderRequest};
+use crate::types::portfolio::{Holding, Position};
+
+/// Where orders go: the live API or a simulation.
+///
+/// The methods mirror the [`DhanClient`] endpoints of the same names.
+pub trait Broker: Send + Sync {
+    /// Place a new order.
+    fn place_order<'a>(
+        &'a self,
+        req: &'a PlaceOrderRequest,
+    ) -> BoxFuture<'a, Result<OrderResponse>>;
+
+    /// Modify a pending order.
+    fn modify_order<'a>(
+        &'a self,
+        order_id: &'a str,
+        req: &'a ModifyOrderRequest,
+    ) -> BoxFuture<'a, Result<OrderResponse>>;
+
+    /// Cancel a pending order.
+    fn cancel_order<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<OrderResponse>>;
+
+    /// All orders of the day.
+    fn get_orders(&self) -> BoxFuture<'_, Result<Vec<OrderDetail>>>;
+
+    /// Open positions.
+    fn get_positions(&self) -> BoxFuture<'_, Result<Vec<Position>>>;
+
+    /// Demat holdings.
+    fn get_holdings(&self) -> BoxFuture<'_, Result<Vec<Holding>>>;
+}
+
+impl Broker for DhanClient {
+    fn place_order<'a>(
+        &'a self,
+        req: &'a PlaceOrderRequest,
+    ) -> BoxFuture<'a, Result<OrderResponse>> {
+        Box::pin(DhanClient::place_order(self, req))
+    }
+
+    fn modify_order<'a>(
+        &'a self,
+        order_id: &'a str,
+        req: &'a ModifyOrderRequest,
+    ) -> BoxFuture<'a, Result<OrderResponse>> {
+        Box::pin(DhanClient::modify_order(self, order_id, req))
+    }
+
+    fn cancel_order<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<OrderResponse>> {
+        Box::pin(DhanClient::cancel_order(self, order_id))
+    }
+
+    fn get_orders(&self) -> BoxFuture<'_, Result<Vec<OrderDetail>>> {
+        Box::pin(DhanClient::get_orders(self))
+    }
+
+    fn get_positions(&self) -> BoxFuture<'_, Result<Vec<Position>>> {
+        Box::pin(DhanClient::get_positions(self))
+    }
+
+    fn get_holdings(&self) -> BoxFuture<'_, Result<Vec<Holding>>> {
+        Box::pin(DhanClient::get_holdings(self))
+    }
+}
@@ -0,0 +1,629 @@
+//! In-memory broker simulating fills against market data.
+//!
+//! A [`PaperBroker`] accepts the same requests as the live API and keeps
+//! its own order book and positions. Feed it prices with
+//! [`PaperBroker::on_event`] / [`PaperBroker::on_tick`] (live feed) or
+//! [`PaperBroker::on_candle`] (historical bars); working orders are matched
+//! against every update:
+//!
+//! | Order type         | Fills when                   | At                               |
+//! |--------------------|------------------------------|----------------------------------|
+//! | `MARKET`           | at once, or on the next price| that price, plus slippage        |
+//! | `LIMIT`            | the price reaches the limit  | the limit, or a better open      |
+//! | `STOP_LOSS_MARKET` | the price reaches the trigger| the trigger (or a gap open), plus slippage |
+//! | `STOP_LOSS`        | triggered, then as `LIMIT`   | as `LIMIT`                       |
+//!
+//! Orders fill completely or not at all, and `IOC` orders that cannot fill
+//! on the last known price are cancelled. Bracket and cover orders are
+//! refused. Delivery (`CNC`) buys show up as positions, not holdings:
+//! holdings are whatever [`PaperBroker::with_holdings`] seeded.
+//!
+//! Every status change is also published as an [`OrderUpdate`] (see
+//! [`PaperBroker::subscribe_updates`]) so order trackers and strategies
+//! see the same lifecycle as on the order-update WebSocket.
+//!
+//! The simulation clock follows the market data: order times are the time
+//! of the last tick or candle, or the wall clock before the first one.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::broker::Broker;
+use crate::error::{DhanError, Result};
+use crate::time::to_ist;
+use crate::types::enums::{OrderStatus, OrderType, ProductType, TransactionType, Validity};
+use crate::types::historical::Candle;
+use crate::types::instrument::InstrumentId;
+use crate::types::orders::{ModifyOrderRequest, OrderDetail, OrderResponse, PlaceOrderRequest};
+use crate::types::portfolio::{Holding, Position};
+use crate::ws::market_feed::{MarketFeedEvent, Tick};
+use crate::ws::order_update::OrderUpdate;
+
+/// Capacity of the order update channel.
+const UPDATE_CHANNEL_CAPACITY: usize = 1024;
+
+// ---------------------------------------------------------------------------
+// Broker
+// ---------------------------------------------------------------------------
+
+/// A simulated account; see the [module docs](self).
+///
+/// Cloning is cheap and clones share the account, so one clone can be
+/// handed to a runner while another is fed market data.
+#[derive(Debug, Clone)]
+pub struct PaperBroker {
+    state: Arc<Mutex<State>>,
+    updates: broadcast::Sender<OrderUpdate>,
+}
+
+impl Default for PaperBroker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PaperBroker {
+    /// An empty account without slippage.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(State::default())),
+            updates: broadcast::channel(UPDATE_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Fill market and stop-market orders `bps` basis points worse than
+    /// the reference price.
+    pub fn with_slippage_bps(self, bps: f64) -> Self {
+        self.lock().slippage = bps / 10_000.0;
+        self
+    }
+
+    /// Report `holdings` from [`Broker::get_holdings`].
+    pub fn with_holdings(self, holdings: Vec<Holding>) -> Self {
+        self.lock().holdings = holdings;
+        self
+    }
+
+    /// A receiver of every order status change.
+    pub fn subscribe_updates(&self) -> broadcast::Receiver<OrderUpdate> {
+        self.updates.subscribe()
+    }
+
+    /// The last price seen for `instrument`.
+    pub fn last_price(&self, instrument: &InstrumentId) -> Option<f64> {
+        self.lock().prices.get(instrument).copied()
+    }
+
+    /// Match working orders against a feed packet carrying a price.
+    pub fn on_event(&self, event: &MarketFeedEvent) {
+        if let Some(tick) = event.to_tick() {
+            self.on_tick(&tick);
+        }
+    }
+
+    /// Match working orders against a tick.
+    pub fn on_tick(&self, tick: &Tick) {
+        self.advance(
+            tick.instrument,
+            Bar::at(tick.ltp),
+            tick.ltp,
+            tick.trade_time(),
+        );
+    }
+
+    /// Match working orders against a trade at `price`, e.g. to seed a
+    /// price before the first tick.
+    pub fn on_price(&self, instrument: InstrumentId, price: f64) {
+        self.advance(instrument, Bar::at(price), price, None);
+    }
+
+    /// Match working orders against a historical bar of `instrument`.
+    ///
+    /// Orders see the open first, then the range: a buy limit above the
+    /// open fills at the open, one within the range at its limit. Feed the
+    /// bars of all instruments in time order.
+    pub fn on_candle(&self, instrument: InstrumentId, candle: &Candle) {
+        let bar = Bar {
+            open: candle.open,
+            high: candle.high,
+            low: candle.low,
+        };
+        let at = DateTime::from_timestamp(candle.timestamp, 0);
+        self.advance(instrument, bar, candle.close, at);
+    }
+
+    /// Match against `bar`, then remember `last` as the price later orders
+    /// are placed at.
+    fn advance(&self, instrument: InstrumentId, bar: Bar, last: f64, at: Option<DateTime<Utc>>) {
+        let updates = {
+            let mut state = self.lock();
+            if at.is_some() {
+                state.clock = at;
+            }
+            let updates = state.match_orders(instrument, bar);
+            state.prices.insert(instrument, last);
+            updates
+        };
+        self.publish(updates);
+    }
+
+    fn publish(&self, updates: Vec<OrderUpdate>) {
+        for update in updates {
+            // No receivers is fine: nobody is tracking orders.
+            let _ = self.updates.send(update);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn place(&self, req: &PlaceOrderRequest) -> Result<OrderResponse> {
+        let (response, updates) = self.lock().place(req)?;
+        self.publish(updates);
+        Ok(response)
+    }
+
+    fn modify(&self, order_id: &str, req: &ModifyOrderRequest) -> Result<OrderResponse> {
+        let (response, updates) = self.lock().modify(order_id, req)?;
+        self.publish(updates);
+        Ok(response)
+    }
+
+    fn cancel(&self, order_id: &str) -> Result<OrderResponse> {
+        let (response, updates) = self.lock().cancel(order_id)?;
+        self.publish(updates);
+        Ok(response)
+    }
+}
+
+impl Broker for PaperBroker {
+    fn place_order<'a>(
+        &'a self,
+        req: &'a PlaceOrderRequest,
+    ) -> BoxFuture<'a, Result<OrderResponse>> {
+        Box::pin(async move { self.place(req) })
+    }
+
+    fn modify_order<'a>(
+        &'a self,
+        order_id: &'a str,
+        req: &'a ModifyOrderRequest,
+    ) -> BoxFuture<'a, Result<OrderResponse>> {
+        Box::pin(async move { self.modify(order_id, req) })
+    }
+
+    fn cancel_order<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<OrderResponse>> {
+        Box::pin(async move { self.cancel(order_id) })
+    }
+
+    fn get_orders(&self) -> BoxFuture<'_, Result<Vec<OrderDetail>>> {
+        Box::pin(async move { Ok(self.lock().orders.iter().map(PaperOrder::detail).collect()) })
+    }
+
+    fn get_positions(&self) -> BoxFuture<'_, Result<Vec<Position>>> {
+        Box::pin(async move { Ok(self.lock().positions()) })
+    }
+
+    fn get_holdings(&self) -> BoxFuture<'_, Result<Vec<Holding>>> {
+        Box::pin(async move { Ok(self.lock().holdings.clone()) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Matching
+// ---------------------------------------------------------------------------
+
+/// The prices an order is matched against: a bar, or a single trade.
+#[derive(Debug, Clone, Copy)]
+struct Bar {
+    open: f64,
+    high: f64,
+    low: f64,
+}
+
+impl Bar {
+    fn at(price: f64) -> Self {
+        Self {
+            open: price,
+            high: price,
+            low: price,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PaperOrder {
+    order_id: String,
+    req: PlaceOrderRequest,
+    instrument: InstrumentId,
+    status: OrderStatus,
+    /// Whether a stop order's trigger has been reached.
+    triggered: bool,
+    fill_price: Option<f64>,
+    created: DateTime<Utc>,
+    updated: DateTime<Utc>,
+}
+
+impl PaperOrder {
+    fn is_buy(&self) -> bool {
+        self.req.transaction_type == TransactionType::BUY
+    }
+
+    /// The fill price against `bar`, if the order fills.
+    fn fill_against(&mut self, bar: Bar, slippage: f64) -> Option<f64> {
+        let buy = self.is_buy();
+        let mut reference = bar.open;
+        if matches!(
+            self.req.order_type,
+            OrderType::STOP_LOSS | OrderType::STOP_LOSS_MARKET
+        ) && !self.triggered
+        {
+            let trigger = self.req.trigger_price?;
+            let reached = if buy {
+                bar.high >= trigger
+            } else {
+                bar.low <= trigger
+            };
+            if !reached {
+                return None;
+            }
+            self.triggered = true;
+            // A gap through the trigger fills at the open.
+            reference = if buy {
+                bar.open.max(trigger)
+            } else {
+                bar.open.min(trigger)
+            };
+        }
+
+        match self.req.order_type {
+            OrderType::MARKET | OrderType::STOP_LOSS_MARKET => Some(if buy {
+                reference * (1.0 + slippage)
+            } else {
+                reference * (1.0 - slippage)
+            }),
+            OrderType::LIMIT | OrderType::STOP_LOSS => {
+                let limit = self.req.price?;
+                if buy {
+                    if reference <= limit {
+                        Some(reference)
+                    } else {
+                        (bar.low <= limit).then_some(limit)
+                    }
+                } else if reference >= limit {
+                    Some(reference)
+                } else {
+                    (bar.high >= limit).then_some(limit)
+                }
+            }
+        }
+    }
+
+    fn response(&self) -> OrderResponse {
+        OrderResponse {
+            order_id: self.order_id.clone(),
+            order_status: wire_name(&self.status).unwrap_or_default(),
+            oms_error_code: None,
+            oms_error_description: None,
+        }
+    }
+
+    fn update(&self) -> OrderUpdate {
+        let req = &self.req;
+        let filled = self.fill_price.is_some().then_some(req.quantity as i64);
+        OrderUpdate {
+            source: Some("P".into()),
+            security_id: Some(req.security_id.clone()),
+            client_id: Some(req.dhan_client_id.clone()),
+            order_id: Some(self.order_id.clone()),
+            correlation_id: req.correlation_id.clone(),
+            product_type: Some(req.product_type),
+            transaction_type: Some(req.transaction_type),
+            order_type: Some(req.order_type),
+            validity: Some(req.validity),
+            status: Some(self.status),
+            quantity: Some(req.quantity as i64),
+            traded_quantity: Some(filled.unwrap_or(0)),
+            remaining_quantity: Some(req.quantity as i64 - filled.unwrap_or(0)),
+            price: req.price,
+            trigger_price: req.trigger_price,
+            traded_price: self.fill_price,
+            average_traded_price: self.fill_price,
+            order_time: Some(to_ist(self.created).naive_local()),
+            last_updated_time: Some(to_ist(self.updated).naive_local()),
+            ..OrderUpdate::default()
+        }
+    }
+
+    fn detail(&self) -> OrderDetail {
+        let req = &self.req;
+        let time = |at: DateTime<Utc>| Some(to_ist(at).format("%Y-%m-%d %H:%M:%S").to_string());
+        let filled = self.fill_price.map_or(0, |_| req.quantity);
+        OrderDetail {
+            dhan_client_id: Some(req.dhan_client_id.clone()),
+            order_id: Some(self.order_id.clone()),
+            correlation_id: req.correlation_id.clone(),
+            order_status: wire_name(&self.status),
+            transaction_type: wire_name(&req.transaction_type),
+            exchange_segment: Some(req.exchange_segment.as_str().to_owned()),
+            product_type: wire_name(&req.product_type),
+            order_type: wire_name(&req.order_type),
+            validity: wire_name(&req.validity),
+            trading_symbol: None,
+            security_id: Some(req.security_id.clone()),
+            quantity: Some(req.quantity),
+            disclosed_quantity: req.disclosed_quantity,
+            price: req.price,
+            trigger_price: req.trigger_price,
+            after_market_order: req.after_market_order,
+            bo_profit_value: None,
+            bo_stop_loss_value: None,
+            leg_name: None,
+            create_time: time(self.created),
+            update_time: time(self.updated),
+            exchange_time: self.fill_price.and_then(|_| time(self.updated)),
+            drv_expiry_date: None,
+            drv_option_type: None,
+            drv_strike_price: None,
+            oms_error_code: None,
+            oms_error_description: None,
+            algo_id: None,
+            remaining_quantity: Some(req.quantity - filled),
+            average_traded_price: self.fill_price,
+            filled_qty: Some(filled),
+        }
+    }
+}
+
+/// The serialized name of an API enum value, e.g. `"STOP_LOSS"`.
+fn wire_name<T: Serialize>(value: &T) -> Option<String> {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => Some(s),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Account state
+// ---------------------------------------------------------------------------
+
+/// Fills of one instrument and product.
+#[derive(Debug, Default, Clone, Copy)]
+struct Book {
+    buy_qty: i64,
+    buy_value: f64,
+    sell_qty: i64,
+    sell_value: f64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    orders: Vec<PaperOrder>,
+    books: HashMap<(InstrumentId, ProductType), Book>,
+    prices: HashMap<InstrumentId, f64>,
+    holdings: Vec<Holding>,
+    slippage: f64,
+    clock: Option<DateTime<Utc>>,
+    next_id: u64,
+}
+
+impl State {
+    fn now(&self) -> DateTime<Utc> {
+        self.clock.unwrap_or_else(Utc::now)
+    }
+
+    fn order_mut(&mut self, order_id: &str) -> Result<&mut PaperOrder> {
+        self.orders
+            .iter_mut()
+            .find(|o| o.order_id == order_id)
+            .ok_or_else(|| DhanError::InvalidArgument(format!("unknown paper order {order_id}")))
+    }
+
+    fn place(&mut self, req: &PlaceOrderRequest) -> Result<(OrderResponse, Vec<OrderUpdate>)> {
+        let invalid = |msg: &str| Err(DhanError::InvalidArgument(msg.into()));
+        if matches!(req.product_type, ProductType::BO | ProductType::CO) {
+            return invalid("paper broker does not simulate bracket or cover orders");
+        }
+        if req.quantity == 0 {
+            return invalid("quantity must be positive");
+        }
+        if matches!(req.order_type, OrderType::LIMIT | OrderType::STOP_LOSS) && req.price.is_none()
+        {
+            return invalid("limit orders need a price");
+        }
+        if matches!(
+            req.order_type,
+            OrderType::STOP_LOSS | OrderType::STOP_LOSS_MARKET
+        ) && req.trigger_price.is_none()
+        {
+            return invalid("stop-loss orders need a trigger price");
+        }
+        let Ok(security_id) = req.security_id.trim().parse() else {
+            return invalid("security ID must be numeric");
+        };
+
+        self.next_id += 1;
+        let now = self.now();
+        self.orders.push(PaperOrder {
+            order_id: format!("PAPER-{}", self.next_id),
+            req: req.clone(),
+            instrument: InstrumentId::new(req.exchange_segment, security_id),
+            status: OrderStatus::PENDING,
+            triggered: false,
+            fill_price: None,
+            created: now,
+            updated: now,
+        });
+        let index = self.orders.len() - 1;
+        let mut updates = vec![self.orders[index].update()];
+
+        let instrument = self.orders[index].instrument;
+        if let Some(price) = self.prices.get(&instrument).copied() {
+            updates.extend(self.try_fill(index, Bar::at(price)));
+        }
+        let order = &mut self.orders[index];
+        if order.status == OrderStatus::PENDING && order.req.validity == Validity::IOC {
+            order.status = OrderStatus::CANCELLED;
+            updates.push(order.update());
+        }
+        Ok((self.orders[index].response(), updates))
+    }
+
+    fn modify(
+        &mut self,
+        order_id: &str,
+        req: &ModifyOrderRequest,
+    ) -> Result<(OrderResponse, Vec<OrderUpdate>)> {
+        let now = self.now();
+        let order = self.order_mut(order_id)?;
+        if order.status != OrderStatus::PENDING {
+            return Err(DhanError::InvalidArgument(format!(
+                "paper order {order_id} is no longer pending"
+            )));
+        }
+        order.req.order_type = req.order_type;
+        order.req.validity = req.validity;
+        if let Some(quantity) = req.quantity.filter(|q| *q > 0) {
+            order.req.quantity = quantity;
+        }
+        order.req.price = req.price.or(order.req.price);
+        order.req.trigger_price = req.trigger_price.or(order.req.trigger_price);
+        order.req.disclosed_quantity = req.disclosed_quantity.or(order.req.disclosed_quantity);
+        order.triggered = false;
+        order.updated = now;
+        let (response, instrument) = (order.response(), order.instrument);
+        let mut updates = vec![order.update()];
+
+        if let Some(price) = self.prices.get(&instrument).copied() {
+            let index = self.orders.iter().position(|o| o.order_id == order_id);
+            if let Some(index) = index {
+                updates.extend(self.try_fill(index, Bar::at(price)));
+            }
+        }
+        Ok((response, updates))
+    }
+
+    fn cancel(&mut self, order_id: &str) -> Result<(OrderResponse, Vec<OrderUpdate>)> {
+        let now = self.now();
+        let order = self.order_mut(order_id)?;
+        if order.status != OrderStatus::PENDING {
+            return Err(DhanError::InvalidArgument(format!(
+                "paper order {order_id} is no longer pending"
+            )));
+        }
+        order.status = OrderStatus::CANCELLED;
+        order.updated = now;
+        Ok((order.response(), vec![order.update()]))
+    }
+
+    /// Match every pending order of `instrument` against `bar`.
+    fn match_orders(&mut self, instrument: InstrumentId, bar: Bar) -> Vec<OrderUpdate> {
+        let pending: Vec<usize> = (0..self.orders.len())
+            .filter(|i| {
+                let order = &self.orders[*i];
+                order.instrument == instrument && order.status == OrderStatus::PENDING
+            })
+            .collect();
+        pending
+            .into_iter()
+            .filter_map(|i| self.try_fill(i, bar))
+            .collect()
+    }
+
+    /// Fill order `index` if `bar` reaches it.
+    fn try_fill(&mut self, index: usize, bar: Bar) -> Option<OrderUpdate> {
+        let now = self.now();
+        let slippage = self.slippage;
+        let order = &mut self.orders[index];
+        let price = order.fill_against(bar, slippage)?;
+        order.status = OrderStatus::TRADED;
+        order.fill_price = Some(price);
+        order.updated = now;
+
+        let quantity = order.req.quantity as i64;
+        let book = self
+            .books
+            .entry((order.instrument, order.req.product_type))
+            .or_default();
+        if order.is_buy() {
+            book.buy_qty += quantity;
+            book.buy_value += price * quantity as f64;
+        } else {
+            book.sell_qty += quantity;
+            book.sell_value += price * quantity as f64;
+        }
+        tracing::debug!(order_id = order.order_id, price, "Paper order filled");
+        Some(order.update())
+    }
+
+    fn positions(&self) -> Vec<Position> {
+        let mut positions: Vec<Position> = self
+            .books
+            .iter()
+            .map(|((id, product), book)| position(*id, *product, book, self.prices.get(id)))
+            .collect();
+        positions.sort_by(|a, b| {
+            (&a.exchange_segment, &a.security_id, &a.product_type).cmp(&(
+                &b.exchange_segment,
+                &b.security_id,
+                &b.product_type,
+            ))
+        });
+        positions
+    }
+}
+
+/// The position view of `book`, marked to `ltp`.
+fn position(id: InstrumentId, product: ProductType, book: &Book, ltp: Option<&f64>) -> Position {
+    let avg = |value: f64, qty: i64| if qty > 0 { value / qty as f64 } else { 0.0 };
+    let buy_avg = avg(book.buy_value, book.buy_qty);
+    let sell_avg = avg(book.sell_value, book.sell_qty);
+    let net_qty = book.buy_qty - book.sell_qty;
+    let matched = book.buy_qty.min(book.sell_qty) as f64;
+    let unrealized = match (net_qty, ltp) {
+        (n, Some(ltp)) if n > 0 => n as f64 * (ltp - buy_avg),
+        (n, Some(ltp)) if n < 0 => -n as f64 * (sell_avg - ltp),
+        _ => 0.0,
+    };
+    let position_type = match net_qty {
+        n if n > 0 => "LONG",
+        n if n < 0 => "SHORT",
+        _ => "CLOSED",
+    };
+    Position {
+        dhan_client_id: None,
+        trading_symbol: None,
+        security_id: Some(id.security_id.to_string()),
+        position_type: Some(position_type.into()),
+        exchange_segment: Some(id.segment.as_str().to_owned()),
+        product_type: wire_name(&product),
+        buy_avg: Some(buy_avg),
+        buy_qty: Some(book.buy_qty),
+        cost_price: Some(if net_qty < 0 { sell_avg } else { buy_avg }),
+        sell_avg: Some(sell_avg),
+        sell_qty: Some(book.sell_qty),
+        net_qty: Some(net_qty),
+        realized_profit: Some(matched * (sell_avg - buy_avg)),
+        unrealized_profit: Some(unrealized),
+        rbi_reference_rate: None,
+        multiplier: Some(1),
+        carry_forward_buy_qty: Some(0),
+        carry_forward_sell_qty: Some(0),
+        carry_forward_buy_value: Some(0.0),
+        carry_forward_sell_value: Some(0.0),
+        day_buy_qty: Some(book.buy_qty),
+        day_sell_qty: Some(book.sell_qty),
+        day_buy_value: Some(book.buy_value),
+        day_sell_value: Some(book.sell_value),
+        drv_expiry_date: None,
+        drv_option_type: None,
+        drv_strike_price: None,
+        cross_currency: None,
+    }
+}
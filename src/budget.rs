@@ -0,0 +1,283 @@
+//! Daily and hourly order budget.
+//!
+//! Dhan caps order requests at 7,000 a day and 1,000 an hour per account
+//! (see [`rate_limits::orders`]); running out mid-session locks the account
+//! out of trading until the window rolls over. An [`OrderBudget`] attached
+//! to a client (see [`DhanClient::with_order_budget`]) counts every order
+//! request — place, modify and cancel of regular, super and forever orders
+//! — warns as usage crosses configurable fractions of either limit, and
+//! with [`OrderBudget::with_hard_stop`] refuses requests that would exceed
+//! it with [`DhanError::RiskRejected`] before they are sent.
+//!
+//! The day rolls over at midnight IST; the hour is a rolling 60 minutes.
+//!
+//! ```
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::budget::OrderBudget;
+//!
+//! let client = DhanClient::new("client-id", "token")
+//!     .with_order_budget(OrderBudget::new().with_warnings([0.5, 0.9]).with_hard_stop());
+//! let remaining = client.remaining_order_budget().unwrap();
+//! assert_eq!((remaining.daily, remaining.hourly), (7000, 1000));
+//! ```
+//!
+//! [`rate_limits::orders`]: crate::constants::rate_limits::orders
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::client::DhanClient;
+use crate::constants::rate_limits::orders::{PER_DAY, PER_HOUR};
+use crate::error::{DhanError, Result};
+use crate::events::{EventBus, RiskEvent, SessionEvent};
+use crate::time::to_ist;
+
+/// Path prefixes of the requests that count against the budget.
+const ORDER_PATHS: &[&str] = &["/v2/orders", "/v2/super/", "/v2/forever/"];
+
+/// Which limit a [`BudgetWarning`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BudgetWindow {
+    /// Orders since midnight IST.
+    Daily,
+    /// Orders in the last 60 minutes.
+    Hourly,
+}
+
+/// Usage of a window crossed a warning threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BudgetWarning {
+    /// The window.
+    pub window: BudgetWindow,
+    /// Orders counted in it.
+    pub used: u32,
+    /// Its limit.
+    pub limit: u32,
+}
+
+/// Orders still allowed in each window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct OrderBudgetRemaining {
+    /// Until midnight IST.
+    pub daily: u32,
+    /// In the rolling hour.
+    pub hourly: u32,
+}
+
+impl OrderBudgetRemaining {
+    /// Orders that can be sent right now.
+    pub fn orders(&self) -> u32 {
+        self.daily.min(self.hourly)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    day: Option<NaiveDate>,
+    daily: u32,
+    /// Send times of the orders of the last hour, oldest first.
+    hourly: VecDeque<DateTime<Utc>>,
+    /// Index of the next threshold to warn at, per window.
+    daily_level: usize,
+    hourly_level: usize,
+}
+
+impl State {
+    /// Forget orders outside the windows as of `now`.
+    fn roll(&mut self, now: DateTime<Utc>) {
+        let today = to_ist(now).date_naive();
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.daily = 0;
+            self.daily_level = 0;
+        }
+        while self
+            .hourly
+            .front()
+            .is_some_and(|t| now - *t >= Duration::hours(1))
+        {
+            self.hourly.pop_front();
+        }
+    }
+}
+
+/// Counts order requests against the daily and hourly limits.
+///
+/// Cloning is cheap and clones share the counts, as clones of a client
+/// share the account.
+#[derive(Debug, Clone)]
+pub struct OrderBudget {
+    daily_limit: u32,
+    hourly_limit: u32,
+    /// Ascending fractions of a limit to warn at; 1.0 is always last.
+    thresholds: Vec<f64>,
+    hard_stop: bool,
+    events: Option<EventBus>,
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for OrderBudget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OrderBudget {
+    /// Dhan's limits, warning at 80% and 90% of either, without a hard stop.
+    pub fn new() -> Self {
+        Self {
+            daily_limit: PER_DAY,
+            hourly_limit: PER_HOUR,
+            thresholds: vec![0.8, 0.9, 1.0],
+            hard_stop: false,
+            events: None,
+            state: Arc::default(),
+        }
+    }
+
+    /// Use lower limits, e.g. to keep headroom for manual trading.
+    pub fn with_limits(mut self, daily: u32, hourly: u32) -> Self {
+        self.daily_limit = daily;
+        self.hourly_limit = hourly;
+        self
+    }
+
+    /// Warn when usage reaches each of `fractions` of a limit (and when it
+    /// is exhausted).
+    pub fn with_warnings(mut self, fractions: impl IntoIterator<Item = f64>) -> Self {
+        let mut thresholds: Vec<f64> = fractions
+            .into_iter()
+            .filter(|f| *f > 0.0 && *f < 1.0)
+            .collect();
+        thresholds.sort_by(f64::total_cmp);
+        thresholds.dedup();
+        thresholds.push(1.0);
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Refuse requests once either limit is reached instead of only
+    /// warning.
+    pub fn with_hard_stop(mut self) -> Self {
+        self.hard_stop = true;
+        self
+    }
+
+    /// Publish warnings on `bus` as [`RiskEvent::OrderBudget`].
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
+    }
+
+    /// Orders still allowed now.
+    pub fn remaining(&self) -> OrderBudgetRemaining {
+        self.remaining_at(Utc::now())
+    }
+
+    /// Orders still allowed at `now`.
+    pub fn remaining_at(&self, now: DateTime<Utc>) -> OrderBudgetRemaining {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.roll(now);
+        OrderBudgetRemaining {
+            daily: self.daily_limit.saturating_sub(state.daily),
+            hourly: self.hourly_limit.saturating_sub(state.hourly.len() as u32),
+        }
+    }
+
+    /// Count an order request sent now.
+    pub fn record(&self) -> Result<()> {
+        self.record_at(Utc::now())
+    }
+
+    /// Count an order request sent at `now`, warning on threshold crossings.
+    ///
+    /// With a hard stop, fails with [`DhanError::RiskRejected`] (and counts
+    /// nothing) if either limit is already reached.
+    pub fn record_at(&self, now: DateTime<Utc>) -> Result<()> {
+        let mut warnings = Vec::new();
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.roll(now);
+            let hourly = state.hourly.len() as u32;
+            if self.hard_stop {
+                let full = if state.daily >= self.daily_limit {
+                    Some(("daily", self.daily_limit))
+                } else if hourly >= self.hourly_limit {
+                    Some(("hourly", self.hourly_limit))
+                } else {
+                    None
+                };
+                if let Some((window, limit)) = full {
+                    return Err(DhanError::RiskRejected(format!(
+                        "order budget: {window} limit of {limit} orders reached"
+                    )));
+                }
+            }
+            state.daily += 1;
+            state.hourly.push_back(now);
+
+            let hourly = state.hourly.len() as u32;
+            let (daily, daily_level) = (state.daily, state.daily_level);
+            state.daily_level = self.level(daily, self.daily_limit);
+            if state.daily_level > daily_level {
+                warnings.push(BudgetWarning {
+                    window: BudgetWindow::Daily,
+                    used: daily,
+                    limit: self.daily_limit,
+                });
+            }
+            // Levels are recomputed, so thresholds re-arm as the hour rolls.
+            let hourly_level = state.hourly_level;
+            state.hourly_level = self.level(hourly, self.hourly_limit);
+            if state.hourly_level > hourly_level {
+                warnings.push(BudgetWarning {
+                    window: BudgetWindow::Hourly,
+                    used: hourly,
+                    limit: self.hourly_limit,
+                });
+            }
+        }
+        for warning in warnings {
+            tracing::warn!(
+                window = ?warning.window,
+                used = warning.used,
+                limit = warning.limit,
+                "Order budget threshold crossed"
+            );
+            if let Some(bus) = &self.events {
+                bus.publish(SessionEvent::Risk(RiskEvent::OrderBudget(warning)));
+            }
+        }
+        Ok(())
+    }
+
+    /// How many thresholds `used` of `limit` has reached.
+    fn level(&self, used: u32, limit: u32) -> usize {
+        self.thresholds
+            .iter()
+            .take_while(|f| f64::from(used) >= *f * f64::from(limit))
+            .count()
+    }
+}
+
+/// Whether a request to `path` counts against the order budget.
+pub(crate) fn is_order_request(path: &str) -> bool {
+    ORDER_PATHS.iter().any(|p| path.starts_with(p))
+}
+
+impl DhanClient {
+    /// Count order requests of this client and its clones against
+    /// `budget`; see [`crate::budget`].
+    pub fn with_order_budget(mut self, budget: OrderBudget) -> Self {
+        self.set_order_budget(budget);
+        self
+    }
+
+    /// Orders still allowed, if an order budget is attached.
+    pub fn remaining_order_budget(&self) -> Option<OrderBudgetRemaining> {
+        self.order_budget().map(OrderBudget::remaining)
+    }
+}
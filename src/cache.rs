@@ -0,0 +1,271 @@
+//! Shared latest-quote cache fed by the market feed.
+//!
+//! [`QuoteCache`] keeps the most recent [`Tick`] per instrument behind a
+//! cheap-to-clone handle, so several consumers (strategies, risk checks,
+//! dashboards) can read prices without each subscribing to the feed.
+//!
+//! The cache also holds each instrument's [`CircuitBand`], which the feed
+//! does not carry; load it from REST quotes with
+//! [`QuoteCache::update_from_quotes`].
+//!
+//! [`HybridQuotes`] serves prices from the cache but falls back to the REST
+//! LTP endpoint when the cached value is older than a threshold, so readers
+//! never silently act on stale prices.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+
+use crate::types::enums::ExchangeSegment;
+use crate::types::instrument::InstrumentId;
+use crate::types::market_quote::{MarketQuoteResponse, QuoteData};
+use crate::ws::market_feed::Tick;
+
+/// The daily price band outside which the exchange rejects orders.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircuitBand {
+    /// Lower circuit limit.
+    pub lower: f64,
+    /// Upper circuit limit.
+    pub upper: f64,
+}
+
+impl CircuitBand {
+    /// Returns `true` if `price` lies within the band (inclusive).
+    pub fn contains(&self, price: f64) -> bool {
+        price >= self.lower && price <= self.upper
+    }
+
+    /// The band of a REST quote, if both limits are present and non-zero.
+    pub fn from_quote(quote: &QuoteData) -> Option<Self> {
+        match (quote.lower_circuit_limit, quote.upper_circuit_limit) {
+            (Some(lower), Some(upper)) if lower > 0.0 && upper >= lower => {
+                Some(Self { lower, upper })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Cached {
+    tick: Tick,
+    received_at: Instant,
+}
+
+/// Latest tick per instrument, shared between clones.
+#[derive(Debug, Clone, Default)]
+pub struct QuoteCache {
+    inner: Arc<RwLock<HashMap<InstrumentId, Cached>>>,
+    bands: Arc<RwLock<HashMap<InstrumentId, CircuitBand>>>,
+}
+
+impl QuoteCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `tick` as the latest quote of its instrument.
+    ///
+    /// Ticks older than the cached one (by trade time) are ignored.
+    pub fn update(&self, tick: &Tick) {
+        let mut map = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        match map.get(&tick.instrument) {
+            Some(prev) if prev.tick.ltt > tick.ltt => {}
+            _ => {
+                map.insert(
+                    tick.instrument,
+                    Cached {
+                        tick: *tick,
+                        received_at: Instant::now(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Latest tick of `instrument`.
+    pub fn get(&self, instrument: &InstrumentId) -> Option<Tick> {
+        self.read().get(instrument).map(|c| c.tick)
+    }
+
+    /// Time since the cached tick of `instrument` was stored.
+    pub fn age(&self, instrument: &InstrumentId) -> Option<Duration> {
+        self.read().get(instrument).map(|c| c.received_at.elapsed())
+    }
+
+    /// Latest traded price of `instrument`.
+    pub fn ltp(&self, instrument: &InstrumentId) -> Option<f64> {
+        self.get(instrument).map(|t| t.ltp)
+    }
+
+    /// Copy of every cached tick.
+    pub fn snapshot(&self) -> HashMap<InstrumentId, Tick> {
+        self.read().iter().map(|(id, c)| (*id, c.tick)).collect()
+    }
+
+    /// Number of instruments with a cached quote.
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Returns `true` if nothing has been cached yet.
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Set the circuit band of `instrument`.
+    pub fn set_circuit_band(&self, instrument: InstrumentId, band: CircuitBand) {
+        self.bands
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(instrument, band);
+    }
+
+    /// Circuit band of `instrument`, if known.
+    pub fn circuit_band(&self, instrument: &InstrumentId) -> Option<CircuitBand> {
+        self.bands
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(instrument)
+            .copied()
+    }
+
+    /// Store the circuit bands of a `get_market_quote` response.
+    ///
+    /// Returns the number of instruments updated; entries with an unknown
+    /// segment, a non-numeric security ID or no limits are skipped.
+    pub fn update_from_quotes(&self, quotes: &MarketQuoteResponse<QuoteData>) -> usize {
+        let mut updated = 0;
+        for (segment, securities) in &quotes.data {
+            let Ok(segment) = segment.parse::<ExchangeSegment>() else {
+                continue;
+            };
+            for (security_id, quote) in securities {
+                let (Ok(security_id), Some(band)) =
+                    (security_id.parse(), CircuitBand::from_quote(quote))
+                else {
+                    continue;
+                };
+                self.set_circuit_band(InstrumentId::new(segment, security_id), band);
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HashMap<InstrumentId, Cached>> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Hybrid quotes
+// ---------------------------------------------------------------------------
+
+/// Default age after which a cached price is refreshed over REST.
+pub const DEFAULT_MAX_QUOTE_AGE: Duration = Duration::from_secs(5);
+
+/// Minimum spacing of REST LTP calls (the endpoint allows 1 request/second).
+pub const REST_QUOTE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Serves prices from a [`QuoteCache`], refreshing stale ones over REST.
+///
+/// Instruments whose cached tick is missing or older than the maximum age
+/// are fetched together in one `get_ltp` call; calls are spaced at least
+/// [`REST_QUOTE_INTERVAL`] apart. Fetched prices are written back into the
+/// cache with the current time as trade time.
+#[derive(Debug, Clone)]
+pub struct HybridQuotes {
+    client: DhanClient,
+    cache: QuoteCache,
+    max_age: Duration,
+    last_call: Arc<tokio::sync::Mutex<Option<tokio::time::Instant>>>,
+}
+
+impl HybridQuotes {
+    /// Serve from `cache`, falling back to `client`.
+    pub fn new(client: DhanClient, cache: QuoteCache) -> Self {
+        Self {
+            client,
+            cache,
+            max_age: DEFAULT_MAX_QUOTE_AGE,
+            last_call: Arc::default(),
+        }
+    }
+
+    /// Treat cached prices older than `max_age` as stale.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// The underlying cache.
+    pub fn cache(&self) -> &QuoteCache {
+        &self.cache
+    }
+
+    /// Fresh last traded price of `instrument`.
+    pub async fn ltp(&self, instrument: InstrumentId) -> Result<f64> {
+        self.ltps(&[instrument])
+            .await?
+            .remove(&instrument)
+            .ok_or_else(|| DhanError::InvalidArgument(format!("no price for {instrument}")))
+    }
+
+    /// Fresh last traded prices of `instruments`.
+    ///
+    /// Instruments the REST call returns no price for are absent from the
+    /// result.
+    pub async fn ltps(&self, instruments: &[InstrumentId]) -> Result<HashMap<InstrumentId, f64>> {
+        let mut prices = HashMap::new();
+        let mut stale = Vec::new();
+        for id in instruments {
+            match (self.cache.get(id), self.cache.age(id)) {
+                (Some(tick), Some(age)) if age <= self.max_age && tick.ltp > 0.0 => {
+                    prices.insert(*id, tick.ltp);
+                }
+                _ => stale.push(*id),
+            }
+        }
+        if stale.is_empty() {
+            return Ok(prices);
+        }
+
+        tracing::debug!(count = stale.len(), "Refreshing stale quotes over REST");
+        let mut req: crate::types::market_quote::MarketQuoteRequest = HashMap::new();
+        for id in &stale {
+            req.entry(id.segment.to_string())
+                .or_default()
+                .push(u64::from(id.security_id));
+        }
+        self.throttle().await;
+        let resp = self.client.get_ltp(&req).await?;
+
+        let now = crate::time::unix_to_feed_epoch(chrono::Utc::now().timestamp());
+        for id in stale {
+            let Some(data) = resp.get(&id) else {
+                continue;
+            };
+            let mut tick = self.cache.get(&id).unwrap_or_else(|| Tick::default_for(id));
+            tick.ltp = data.last_price;
+            tick.ltt = tick.ltt.max(now);
+            self.cache.update(&tick);
+            prices.insert(id, data.last_price);
+        }
+        Ok(prices)
+    }
+
+    /// Wait until the next REST call is allowed.
+    async fn throttle(&self) {
+        let mut last = self.last_call.lock().await;
+        if let Some(at) = *last {
+            tokio::time::sleep_until(at + REST_QUOTE_INTERVAL).await;
+        }
+        *last = Some(tokio::time::Instant::now());
+    }
+}
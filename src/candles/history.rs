@@ -0,0 +1,210 @@
+//! Persistent 1-minute candles with gap backfill on startup.
+//!
+//! [`CandleHistory`] keeps today's 1-minute candles per instrument in a
+//! directory of JSON-lines files (one per instrument per IST trading day).
+//! After a restart, [`backfill()`](CandleHistory::backfill) loads what was
+//! persisted and fetches the missing minutes from the intraday endpoint;
+//! live ticks then continue the series through
+//! [`on_tick()`](CandleHistory::on_tick), so consumers see one gap-free
+//! series regardless of process restarts.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::candles::history::CandleHistory;
+//! use dhan_rs::types::enums::{ExchangeSegment, Instrument};
+//! use dhan_rs::types::instrument::InstrumentId;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let mut history = CandleHistory::open("candles")?;
+//! let reliance = InstrumentId::new(ExchangeSegment::NSE_EQ, 2885);
+//! let fetched = history
+//!     .backfill(&client, reliance, Instrument::EQUITY, chrono::Utc::now())
+//!     .await?;
+//! println!("backfilled {fetched} minutes, {} total", history.candles(&reliance).len());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+
+use crate::candles::CandleAggregator;
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::journal::Journal;
+use crate::scheduler::ist;
+use crate::types::enums::Instrument;
+use crate::types::historical::{Candle, IntradayDataRequest};
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::Tick;
+
+/// Candle length kept by [`CandleHistory`].
+pub const CANDLE_SECS: i64 = 60;
+
+/// IST time the backfill starts from on a day with no persisted candles.
+const SESSION_OPEN: NaiveTime = NaiveTime::from_hms_opt(9, 15, 0).expect("valid time");
+
+/// Today's 1-minute candles per instrument, persisted to disk.
+#[derive(Debug)]
+pub struct CandleHistory {
+    dir: PathBuf,
+    aggregator: CandleAggregator,
+    series: HashMap<InstrumentId, Vec<Candle>>,
+    journals: HashMap<InstrumentId, (NaiveDate, Journal)>,
+}
+
+impl CandleHistory {
+    /// Store candles under `dir`, creating it if needed.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            aggregator: CandleAggregator::new(CANDLE_SECS as u32),
+            series: HashMap::new(),
+            journals: HashMap::new(),
+        })
+    }
+
+    /// File holding the candles of `instrument` for the IST day `date`.
+    pub fn path_for(&self, instrument: &InstrumentId, date: NaiveDate) -> PathBuf {
+        self.dir.join(format!(
+            "{}_{}_{}.jsonl",
+            instrument.segment,
+            instrument.security_id,
+            date.format("%Y-%m-%d")
+        ))
+    }
+
+    /// Load the persisted candles of `instrument` for `date`, replacing any
+    /// held in memory.
+    pub fn load(&mut self, instrument: InstrumentId, date: NaiveDate) -> Result<&[Candle]> {
+        let mut candles: Vec<Candle> = Journal::read(self.path_for(&instrument, date))?;
+        candles.sort_by_key(|c| c.timestamp);
+        candles.dedup_by_key(|c| c.timestamp);
+        Ok(self
+            .series
+            .entry(instrument)
+            .insert_entry(candles)
+            .into_mut())
+    }
+
+    /// Load today's persisted candles of `instrument`, then fetch the
+    /// completed minutes since the last one (or since the 09:15 IST open)
+    /// from the intraday endpoint.
+    ///
+    /// Returns the number of candles fetched. The minute in progress at
+    /// `now` is left to the live feed.
+    pub async fn backfill(
+        &mut self,
+        client: &DhanClient,
+        instrument: InstrumentId,
+        kind: Instrument,
+        now: DateTime<Utc>,
+    ) -> Result<usize> {
+        let now_ist = now.with_timezone(&ist());
+        self.load(instrument, now_ist.date_naive())?;
+
+        let open = ist()
+            .from_local_datetime(&now_ist.date_naive().and_time(SESSION_OPEN))
+            .single()
+            .expect("fixed offset is unambiguous")
+            .timestamp();
+        let from = self
+            .last(&instrument)
+            .map_or(open, |c| c.timestamp + CANDLE_SECS);
+        let until = now.timestamp() - now.timestamp().rem_euclid(CANDLE_SECS);
+        if from >= until {
+            return Ok(0);
+        }
+
+        let fmt = |ts: i64| {
+            DateTime::from_timestamp(ts, 0)
+                .unwrap_or_default()
+                .with_timezone(&ist())
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        };
+        let req = IntradayDataRequest {
+            security_id: instrument.security_id.to_string(),
+            exchange_segment: instrument.segment,
+            instrument: kind,
+            interval: "1".into(),
+            oi: None,
+            from_date: fmt(from),
+            to_date: fmt(until),
+        };
+        let data = client.get_intraday_historical(&req).await?;
+
+        let mut added = 0;
+        for candle in data.candles() {
+            if candle.timestamp < until && self.insert(instrument, candle)? {
+                added += 1;
+            }
+        }
+        tracing::info!(%instrument, added, "Candle backfill complete");
+        Ok(added)
+    }
+
+    /// Feed a live tick. Returns (and persists) the candle it completes.
+    ///
+    /// A completed candle no newer than the last stored one — e.g. the
+    /// partial minute right after a restart that backfill already covered —
+    /// is dropped.
+    pub fn on_tick(&mut self, tick: &Tick) -> Result<Option<Candle>> {
+        let Some(candle) = self.aggregator.on_tick(tick) else {
+            return Ok(None);
+        };
+        Ok(self.insert(tick.instrument, candle)?.then_some(candle))
+    }
+
+    /// Append `candle` if it is newer than the last stored candle.
+    ///
+    /// Returns `true` if it was stored.
+    pub fn insert(&mut self, instrument: InstrumentId, candle: Candle) -> Result<bool> {
+        if self
+            .last(&instrument)
+            .is_some_and(|last| candle.timestamp <= last.timestamp)
+        {
+            return Ok(false);
+        }
+        let date = DateTime::from_timestamp(candle.timestamp, 0)
+            .unwrap_or_default()
+            .with_timezone(&ist())
+            .date_naive();
+        let journal = match self.journals.get_mut(&instrument) {
+            Some((d, journal)) if *d == date => journal,
+            _ => {
+                let journal = Journal::open(self.path_for(&instrument, date))?;
+                &mut self
+                    .journals
+                    .entry(instrument)
+                    .insert_entry((date, journal))
+                    .into_mut()
+                    .1
+            }
+        };
+        journal.append(&candle)?;
+        self.series.entry(instrument).or_default().push(candle);
+        Ok(true)
+    }
+
+    /// Stored candles of `instrument`, oldest first.
+    pub fn candles(&self, instrument: &InstrumentId) -> &[Candle] {
+        self.series.get(instrument).map_or(&[], Vec::as_slice)
+    }
+
+    /// Most recent stored candle of `instrument`.
+    pub fn last(&self, instrument: &InstrumentId) -> Option<&Candle> {
+        self.candles(instrument).last()
+    }
+
+    /// Instruments with stored candles.
+    pub fn instruments(&self) -> impl Iterator<Item = &InstrumentId> {
+        self.series.keys()
+    }
+}
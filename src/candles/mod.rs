@@ -0,0 +1,236 @@
+//! Live candle aggregation from market feed ticks.
+//!
+//! [`CandleAggregator`] turns a stream of [`Tick`]s into fixed-interval OHLCV
+//! [`Candle`]s per instrument. Buckets are aligned to IST clock boundaries
+//! and candle timestamps are Unix seconds, like historical candles (see
+//! [`crate::time`]). [`MultiCandleAggregator`] builds several intervals
+//! (e.g. 1, 5 and 15 minutes) from the same ticks, and
+//! [`CandleData::from_candles`] turns finished bars back into the
+//! historical response shape, so live and historical data share one bar
+//! type.
+//!
+//! ```
+//! use dhan_rs::candles::MultiCandleAggregator;
+//!
+//! let bars = MultiCandleAggregator::standard();
+//! assert_eq!(bars.intervals(), vec![60, 300, 900]);
+//! ```
+//!
+//! [`CandleData::from_candles`]: crate::types::historical::CandleData::from_candles
+//!
+//! - [`history`] — Persisted 1-minute candles with startup backfill
+//! - [`quality`] — Integrity checks and gap detection for historical candles
+//! - [`tape`] — Trade prints inferred from feed volume, streamed and persisted
+
+pub mod history;
+pub mod quality;
+pub mod tape;
+
+use std::collections::HashMap;
+
+use crate::time;
+use crate::types::historical::Candle;
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::{MarketFeedEvent, Tick};
+
+/// Per-instrument aggregation state.
+#[derive(Debug, Clone, Copy)]
+struct Building {
+    candle: Candle,
+    /// Cumulative day volume at the start of the bar.
+    base_volume: Option<i64>,
+}
+
+/// Builds fixed-interval candles from ticks.
+#[derive(Debug, Clone)]
+pub struct CandleAggregator {
+    interval_secs: i64,
+    building: HashMap<InstrumentId, Building>,
+    last_volume: HashMap<InstrumentId, i64>,
+}
+
+impl CandleAggregator {
+    /// Create an aggregator for bars of `interval_secs` seconds (minimum 1).
+    pub fn new(interval_secs: u32) -> Self {
+        Self {
+            interval_secs: i64::from(interval_secs.max(1)),
+            building: HashMap::new(),
+            last_volume: HashMap::new(),
+        }
+    }
+
+    /// Bar length in seconds.
+    pub fn interval_secs(&self) -> i64 {
+        self.interval_secs
+    }
+
+    /// Feed a tick. Returns the previous bar of the instrument when the tick
+    /// opens a new one.
+    ///
+    /// Ticks without a trade time, or older than the current bar, are ignored.
+    /// Bar volume is derived from the cumulative day volume of Quote/Full
+    /// ticks and stays zero for Ticker-only subscriptions.
+    pub fn on_tick(&mut self, tick: &Tick) -> Option<Candle> {
+        if tick.ltt <= 0 || tick.ltp <= 0.0 {
+            return None;
+        }
+        let bucket = time::feed_epoch_to_unix(tick.ltt - tick.ltt.rem_euclid(self.interval_secs));
+        let prev_volume = self.last_volume.get(&tick.instrument).copied();
+        if let Some(v) = tick.volume {
+            self.last_volume.insert(tick.instrument, v);
+        }
+
+        let mut closed = None;
+        if let Some(b) = self.building.get_mut(&tick.instrument) {
+            if bucket < b.candle.timestamp {
+                return None;
+            }
+            if bucket == b.candle.timestamp {
+                let c = &mut b.candle;
+                c.high = c.high.max(tick.ltp);
+                c.low = c.low.min(tick.ltp);
+                c.close = tick.ltp;
+                if let (Some(base), Some(v)) = (b.base_volume, tick.volume) {
+                    c.volume = (v - base).max(0) as f64;
+                }
+                if let Some(oi) = tick.oi {
+                    c.open_interest = Some(oi as f64);
+                }
+                return None;
+            }
+            closed = Some(b.candle);
+        }
+
+        self.building.insert(
+            tick.instrument,
+            Building {
+                candle: Candle {
+                    timestamp: bucket,
+                    open: tick.ltp,
+                    high: tick.ltp,
+                    low: tick.ltp,
+                    close: tick.ltp,
+                    volume: match (prev_volume, tick.volume) {
+                        (Some(p), Some(v)) => (v - p).max(0) as f64,
+                        _ => 0.0,
+                    },
+                    open_interest: tick.oi.map(|oi| oi as f64),
+                },
+                base_volume: prev_volume.or(tick.volume),
+            },
+        );
+        closed
+    }
+
+    /// Feed a market feed event. Ticker, Quote and Full packets are used;
+    /// returns the instrument and its previous bar when a new one opens.
+    pub fn on_event(&mut self, event: &MarketFeedEvent) -> Option<(InstrumentId, Candle)> {
+        let tick = event.to_tick()?;
+        self.on_tick(&tick).map(|candle| (tick.instrument, candle))
+    }
+
+    /// The bar currently being built for `instrument`.
+    pub fn current(&self, instrument: &InstrumentId) -> Option<&Candle> {
+        self.building.get(instrument).map(|b| &b.candle)
+    }
+
+    /// Close and return every bar in progress (e.g. at session end).
+    pub fn flush(&mut self) -> Vec<(InstrumentId, Candle)> {
+        self.building
+            .drain()
+            .map(|(id, b)| (id, b.candle))
+            .collect()
+    }
+}
+
+/// A bar closed by a [`MultiCandleAggregator`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClosedCandle {
+    /// Bar length in seconds.
+    pub interval_secs: i64,
+    /// The instrument.
+    pub instrument: InstrumentId,
+    /// The bar.
+    pub candle: Candle,
+}
+
+/// Builds candles of several intervals from the same ticks.
+#[derive(Debug, Clone)]
+pub struct MultiCandleAggregator {
+    /// One aggregator per interval, shortest first.
+    aggregators: Vec<CandleAggregator>,
+}
+
+impl MultiCandleAggregator {
+    /// Bars of each of `interval_secs` (duplicates are dropped).
+    pub fn new(interval_secs: impl IntoIterator<Item = u32>) -> Self {
+        let mut aggregators: Vec<CandleAggregator> = interval_secs
+            .into_iter()
+            .map(CandleAggregator::new)
+            .collect();
+        aggregators.sort_by_key(CandleAggregator::interval_secs);
+        aggregators.dedup_by_key(|a| a.interval_secs());
+        Self { aggregators }
+    }
+
+    /// 1-, 5- and 15-minute bars.
+    pub fn standard() -> Self {
+        Self::new([60, 300, 900])
+    }
+
+    /// Bar lengths in seconds, shortest first.
+    pub fn intervals(&self) -> Vec<i64> {
+        self.aggregators
+            .iter()
+            .map(CandleAggregator::interval_secs)
+            .collect()
+    }
+
+    /// Feed a tick. Returns the bars it closed, shortest interval first.
+    pub fn on_tick(&mut self, tick: &Tick) -> Vec<ClosedCandle> {
+        self.aggregators
+            .iter_mut()
+            .filter_map(|a| {
+                let candle = a.on_tick(tick)?;
+                Some(ClosedCandle {
+                    interval_secs: a.interval_secs(),
+                    instrument: tick.instrument,
+                    candle,
+                })
+            })
+            .collect()
+    }
+
+    /// Feed a market feed event (see [`CandleAggregator::on_event`]).
+    pub fn on_event(&mut self, event: &MarketFeedEvent) -> Vec<ClosedCandle> {
+        match event.to_tick() {
+            Some(tick) => self.on_tick(&tick),
+            None => Vec::new(),
+        }
+    }
+
+    /// The `interval_secs` bar currently being built for `instrument`.
+    pub fn current(&self, interval_secs: i64, instrument: &InstrumentId) -> Option<&Candle> {
+        self.aggregators
+            .iter()
+            .find(|a| a.interval_secs() == interval_secs)?
+            .current(instrument)
+    }
+
+    /// Close and return every bar in progress.
+    pub fn flush(&mut self) -> Vec<ClosedCandle> {
+        self.aggregators
+            .iter_mut()
+            .flat_map(|a| {
+                let interval_secs = a.interval_secs();
+                a.flush()
+                    .into_iter()
+                    .map(move |(instrument, candle)| ClosedCandle {
+                        interval_secs,
+                        instrument,
+                        candle,
+                    })
+            })
+            .collect()
+    }
+}
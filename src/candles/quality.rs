@@ -0,0 +1,287 @@
+//! Integrity checks for historical candle responses.
+//!
+//! [`CandleData`] arrives as parallel arrays, and nothing stops a malformed
+//! response (arrays of different lengths, timestamps out of order, negative
+//! prices, missing bars) from flowing straight into a backtest.
+//! [`CandleData::validate_daily`] and [`CandleData::validate_intraday`]
+//! check a response and return a [`CandleQualityReport`] listing every
+//! [`CandleIssue`] found.
+//!
+//! Gaps are measured against a [`TradingCalendar`]: weekends, listed
+//! holidays and time outside the session are not gaps.
+//!
+//! ```
+//! use dhan_rs::candles::quality::{CandleIssue, TradingCalendar};
+//! use dhan_rs::types::historical::CandleData;
+//!
+//! // 09:15, 09:16 and 09:19 IST on 2024-09-11.
+//! let start = 1_726_026_300.0;
+//! let data = CandleData {
+//!     open: vec![100.0, 101.0, 102.0],
+//!     high: vec![101.0, 102.0, 103.0],
+//!     low: vec![99.0, 100.0, 101.0],
+//!     close: vec![101.0, 102.0, 102.5],
+//!     volume: vec![10.0, 12.0, 8.0],
+//!     timestamp: vec![start, start + 60.0, start + 240.0],
+//!     open_interest: vec![],
+//! };
+//!
+//! let report = data.validate_intraday(60, &TradingCalendar::nse());
+//! assert!(!report.is_clean());
+//! assert!(matches!(report.issues[..], [CandleIssue::Gap { missing: 2, .. }]));
+//! ```
+
+use std::collections::BTreeSet;
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+use serde::Serialize;
+
+use crate::time;
+use crate::types::historical::CandleData;
+
+// ---------------------------------------------------------------------------
+// Trading calendar
+// ---------------------------------------------------------------------------
+
+/// Trading days and session hours, for telling gaps from closed markets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradingCalendar {
+    holidays: BTreeSet<NaiveDate>,
+    open: NaiveTime,
+    close: NaiveTime,
+}
+
+impl TradingCalendar {
+    /// NSE/BSE cash session: weekdays, 09:15–15:30 IST, no holidays (add
+    /// them with [`Self::with_holidays`]).
+    pub fn nse() -> Self {
+        Self {
+            holidays: BTreeSet::new(),
+            open: NaiveTime::from_hms_opt(9, 15, 0).expect("valid time"),
+            close: NaiveTime::from_hms_opt(15, 30, 0).expect("valid time"),
+        }
+    }
+
+    /// Add exchange holidays.
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    /// Use a different session (IST, `open` inclusive, `close` exclusive).
+    pub fn with_session(mut self, open: NaiveTime, close: NaiveTime) -> Self {
+        self.open = open;
+        self.close = close;
+        self
+    }
+
+    /// Returns `true` if the market trades on `date`.
+    pub fn is_trading_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    /// Returns `true` if the Unix time `secs` falls within a session.
+    pub fn in_session(&self, secs: i64) -> bool {
+        let Some(at) = time::from_epoch(secs) else {
+            return false;
+        };
+        let at = time::to_ist(at);
+        let t = at.time();
+        self.is_trading_day(at.date_naive()) && t >= self.open && t < self.close
+    }
+}
+
+impl Default for TradingCalendar {
+    fn default() -> Self {
+        Self::nse()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+/// One problem found in a [`CandleData`] response.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CandleIssue {
+    /// An array's length differs from `timestamp`'s. Only candles present
+    /// in every array are checked further.
+    LengthMismatch {
+        /// The array, e.g. `"close"`.
+        field: &'static str,
+        /// Its length.
+        len: usize,
+        /// Length of `timestamp`.
+        expected: usize,
+    },
+    /// A timestamp is not after the previous one.
+    NonMonotonic {
+        /// Index of the candle.
+        index: usize,
+        /// Its timestamp (Unix seconds).
+        timestamp: i64,
+        /// Timestamp of the last candle in order before it.
+        previous: i64,
+    },
+    /// A price, volume or open interest is negative or not a number.
+    NegativeValue {
+        /// Index of the candle.
+        index: usize,
+        /// The array, e.g. `"volume"`.
+        field: &'static str,
+        /// The offending value.
+        value: f64,
+    },
+    /// Bars expected by the calendar are missing between two candles.
+    Gap {
+        /// Timestamp of the candle before the gap.
+        after: i64,
+        /// Timestamp of the candle after the gap.
+        before: i64,
+        /// Number of missing bars (trading days for daily data).
+        missing: usize,
+    },
+}
+
+/// The result of validating a [`CandleData`] response.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CandleQualityReport {
+    /// Candles checked (those present in every array).
+    pub candles: usize,
+    /// Problems found, in array order.
+    pub issues: Vec<CandleIssue>,
+}
+
+impl CandleQualityReport {
+    /// Returns `true` if no problem was found.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Total bars missing across all gaps.
+    pub fn missing_bars(&self) -> usize {
+        self.issues
+            .iter()
+            .map(|issue| match issue {
+                CandleIssue::Gap { missing, .. } => *missing,
+                _ => 0,
+            })
+            .sum()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+impl CandleData {
+    /// Validate a daily response; a gap is a trading day without a candle.
+    pub fn validate_daily(&self, calendar: &TradingCalendar) -> CandleQualityReport {
+        self.validate(|after, before| {
+            let (Some(a), Some(b)) = (ist_date(after), ist_date(before)) else {
+                return 0;
+            };
+            a.iter_days()
+                .skip(1)
+                .take_while(|d| *d < b)
+                .filter(|d| calendar.is_trading_day(*d))
+                .count()
+        })
+    }
+
+    /// Validate an intraday response of `interval_secs` bars; a gap is a
+    /// bar start within the session without a candle.
+    pub fn validate_intraday(
+        &self,
+        interval_secs: i64,
+        calendar: &TradingCalendar,
+    ) -> CandleQualityReport {
+        let interval = interval_secs.max(1);
+        self.validate(|after, before| {
+            (1..)
+                .map(|i| after + i * interval)
+                .take_while(|t| *t < before)
+                .filter(|t| calendar.in_session(*t))
+                .count()
+        })
+    }
+
+    /// Structural checks plus gaps counted by `missing(after, before)`.
+    fn validate(&self, missing: impl Fn(i64, i64) -> usize) -> CandleQualityReport {
+        let mut issues = Vec::new();
+        let expected = self.timestamp.len();
+        let fields = [
+            ("open", &self.open),
+            ("high", &self.high),
+            ("low", &self.low),
+            ("close", &self.close),
+            ("volume", &self.volume),
+        ];
+        for (field, values) in fields {
+            if values.len() != expected {
+                issues.push(CandleIssue::LengthMismatch {
+                    field,
+                    len: values.len(),
+                    expected,
+                });
+            }
+        }
+        // Open interest is optional: empty is fine, partial is not.
+        if !self.open_interest.is_empty() && self.open_interest.len() != expected {
+            issues.push(CandleIssue::LengthMismatch {
+                field: "open_interest",
+                len: self.open_interest.len(),
+                expected,
+            });
+        }
+
+        let candles = fields
+            .iter()
+            .map(|(_, v)| v.len())
+            .fold(expected, usize::min);
+        let mut previous: Option<i64> = None;
+        for index in 0..candles {
+            let values = fields
+                .iter()
+                .map(|(field, v)| (*field, v[index]))
+                .chain(self.open_interest.get(index).map(|v| ("open_interest", *v)));
+            for (field, value) in values {
+                if value.is_nan() || value < 0.0 {
+                    issues.push(CandleIssue::NegativeValue {
+                        index,
+                        field,
+                        value,
+                    });
+                }
+            }
+
+            let timestamp = self.timestamp[index] as i64;
+            if let Some(prev) = previous {
+                if timestamp <= prev {
+                    issues.push(CandleIssue::NonMonotonic {
+                        index,
+                        timestamp,
+                        previous: prev,
+                    });
+                    continue;
+                }
+                let gap = missing(prev, timestamp);
+                if gap > 0 {
+                    issues.push(CandleIssue::Gap {
+                        after: prev,
+                        before: timestamp,
+                        missing: gap,
+                    });
+                }
+            }
+            previous = Some(timestamp);
+        }
+
+        CandleQualityReport { candles, issues }
+    }
+}
+
+fn ist_date(secs: i64) -> Option<NaiveDate> {
+    time::from_epoch(secs).map(|at| time::to_ist(at).date_naive())
+}
@@ -0,0 +1,242 @@
+//! Trade tape reconstructed from the market feed.
+//!
+//! Dhan has no tick history API, and the feed reports the last trade rather
+//! than every trade. [`TapeBuilder`] infers prints from consecutive ticks:
+//! whenever the cumulative day volume of a Quote/Full tick grows, one
+//! [`TapeTrade`] is emitted at the tick's LTP with the volume difference as
+//! its size. Trades that happen between two packets are therefore merged
+//! into one print. Instruments subscribed as Ticker only carry no volume;
+//! their prints are emitted on every LTP or trade-time change, without a
+//! size.
+//!
+//! [`TapeStore`] persists trades as JSON-lines files (one per instrument per
+//! IST day), and [`spawn_tape`] runs both over a feed channel, yielding the
+//! trades as a stream.
+//!
+//! ```no_run
+//! use dhan_rs::candles::tape::{TapeStore, spawn_tape};
+//! use dhan_rs::ws::manager::{ConnectionId, DhanFeedManager};
+//! use futures_util::StreamExt;
+//!
+//! # async fn example(manager: &DhanFeedManager) -> dhan_rs::Result<()> {
+//! let store = TapeStore::open("tape")?;
+//! let mut trades = spawn_tape(manager.get_parsed_channel(ConnectionId(0)).unwrap(), Some(store));
+//! while let Some(trade) = trades.next().await {
+//!     println!("{} {} x {:?}", trade.instrument, trade.price, trade.size);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use chrono::{DateTime, NaiveDate};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc;
+
+use crate::error::Result;
+use crate::journal::Journal;
+use crate::scheduler::ist;
+use crate::time;
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::{MarketFeedEvent, Tick};
+
+/// Trades buffered ahead of a [`TapeStream`] consumer.
+const TAPE_BUFFER: usize = 4096;
+
+/// One inferred print.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TapeTrade {
+    /// The instrument traded.
+    pub instrument: InstrumentId,
+    /// Trade time (Unix seconds).
+    pub timestamp: i64,
+    /// Last traded price.
+    pub price: f64,
+    /// Volume traded since the previous print, if the feed carries volume.
+    pub size: Option<i64>,
+}
+
+/// What the last tick of an instrument showed.
+#[derive(Debug, Clone, Copy, Default)]
+struct LastSeen {
+    volume: Option<i64>,
+    ltp: f64,
+    ltt: i64,
+}
+
+// ---------------------------------------------------------------------------
+// Builder
+// ---------------------------------------------------------------------------
+
+/// Infers trades from ticks.
+#[derive(Debug, Clone, Default)]
+pub struct TapeBuilder {
+    last: HashMap<InstrumentId, LastSeen>,
+}
+
+impl TapeBuilder {
+    /// An empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a tick. Returns the print it reveals, if any.
+    ///
+    /// The first tick carrying volume only sets the baseline. Once an
+    /// instrument has shown volume, ticks without it (e.g. Ticker packets
+    /// of a Quote subscription) are ignored, so prints are not duplicated.
+    pub fn on_tick(&mut self, tick: &Tick) -> Option<TapeTrade> {
+        if tick.ltt <= 0 || tick.ltp <= 0.0 {
+            return None;
+        }
+        let last = self.last.entry(tick.instrument).or_default();
+        let size = match (last.volume, tick.volume) {
+            (Some(prev), Some(v)) if v > prev => Some(v - prev),
+            (_, Some(_)) | (Some(_), None) => None,
+            (None, None) => {
+                if tick.ltp == last.ltp && tick.ltt == last.ltt {
+                    return None;
+                }
+                last.ltp = tick.ltp;
+                last.ltt = tick.ltt;
+                return Some(Self::trade(tick, None));
+            }
+        };
+        if let Some(v) = tick.volume {
+            // Volume resets (e.g. a new session) start a new baseline.
+            last.volume = Some(v);
+        }
+        last.ltp = tick.ltp;
+        last.ltt = tick.ltt;
+        size.map(|size| Self::trade(tick, Some(size)))
+    }
+
+    fn trade(tick: &Tick, size: Option<i64>) -> TapeTrade {
+        TapeTrade {
+            instrument: tick.instrument,
+            timestamp: time::feed_epoch_to_unix(tick.ltt),
+            price: tick.ltp,
+            size,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Store
+// ---------------------------------------------------------------------------
+
+/// Trades per instrument per IST day, persisted as JSON lines.
+#[derive(Debug)]
+pub struct TapeStore {
+    dir: PathBuf,
+    journals: HashMap<InstrumentId, (NaiveDate, Journal)>,
+}
+
+impl TapeStore {
+    /// Store trades under `dir`, creating it if needed.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            journals: HashMap::new(),
+        })
+    }
+
+    /// File holding the trades of `instrument` for the IST day `date`.
+    pub fn path_for(&self, instrument: &InstrumentId, date: NaiveDate) -> PathBuf {
+        self.dir.join(format!(
+            "{}_{}_{}.tape.jsonl",
+            instrument.segment,
+            instrument.security_id,
+            date.format("%Y-%m-%d")
+        ))
+    }
+
+    /// Append `trade` to its day's file.
+    pub fn append(&mut self, trade: &TapeTrade) -> Result<()> {
+        let date = DateTime::from_timestamp(trade.timestamp, 0)
+            .unwrap_or_default()
+            .with_timezone(&ist())
+            .date_naive();
+        let journal = match self.journals.get_mut(&trade.instrument) {
+            Some((d, journal)) if *d == date => journal,
+            _ => {
+                let journal = Journal::open(self.path_for(&trade.instrument, date))?;
+                &mut self
+                    .journals
+                    .entry(trade.instrument)
+                    .insert_entry((date, journal))
+                    .into_mut()
+                    .1
+            }
+        };
+        journal.append(trade)
+    }
+
+    /// The persisted trades of `instrument` for `date`, in order.
+    pub fn load(&self, instrument: &InstrumentId, date: NaiveDate) -> Result<Vec<TapeTrade>> {
+        Journal::read(self.path_for(instrument, date))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Stream
+// ---------------------------------------------------------------------------
+
+/// Build the tape of every instrument on `rx` in the background, persisting
+/// it to `store` if given.
+///
+/// The task ends when the feed channel closes or the stream is dropped.
+/// Persistence errors are logged and do not stop the stream.
+pub fn spawn_tape(
+    mut rx: broadcast::Receiver<MarketFeedEvent>,
+    mut store: Option<TapeStore>,
+) -> TapeStream {
+    let (tx, trades) = mpsc::channel(TAPE_BUFFER);
+    tokio::spawn(async move {
+        let mut builder = TapeBuilder::new();
+        loop {
+            let event = match rx.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(n)) => {
+                    tracing::warn!(dropped = n, "Trade tape fell behind the feed");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+            let Some(trade) = event.to_tick().and_then(|t| builder.on_tick(&t)) else {
+                continue;
+            };
+            if let Some(store) = &mut store {
+                if let Err(e) = store.append(&trade) {
+                    tracing::warn!(error = %e, "Failed to persist trade");
+                }
+            }
+            if tx.send(trade).await.is_err() {
+                break;
+            }
+        }
+    });
+    TapeStream { rx: trades }
+}
+
+/// Trades inferred by [`spawn_tape`], in feed order.
+#[derive(Debug)]
+pub struct TapeStream {
+    rx: mpsc::Receiver<TapeTrade>,
+}
+
+impl Stream for TapeStream {
+    type Item = TapeTrade;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
@@ -0,0 +1,216 @@
+//! Circuit breaker for the market data and historical endpoints.
+//!
+//! When the quote or chart APIs are down, retrying every call only adds
+//! load and latency. A [`CircuitBreaker`] attached to a client (see
+//! [`DhanClient::with_circuit_breaker`]) counts consecutive outage failures
+//! of [`EndpointGroup::Data`] and [`EndpointGroup::Historical`] requests —
+//! transport errors, HTTP 5xx and 429, and Dhan's rate-limit, server and
+//! network errors (`DH-904`, `DH-908`, `DH-909`) — and after a threshold
+//! *opens*: further calls fail at once with [`DhanError::CircuitOpen`].
+//! After a cooldown one probe call is let through (*half-open*); its
+//! success closes the circuit, its failure opens it again.
+//!
+//! Order endpoints are never blocked. The state can be watched with
+//! [`CircuitBreaker::subscribe`], which is how
+//! [`runtime::degrade`](crate::runtime::degrade) switches a session into
+//! safe mode.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::circuit::{CircuitBreaker, CircuitState};
+//!
+//! let breaker = CircuitBreaker::new()
+//!     .with_failure_threshold(3)
+//!     .with_cooldown(Duration::from_secs(10));
+//! let client = DhanClient::new("client-id", "token").with_circuit_breaker(breaker.clone());
+//! assert_eq!(breaker.state(), CircuitState::Closed);
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::client::{DhanClient, EndpointGroup};
+use crate::error::{DhanError, Result};
+
+/// Consecutive failures that open the circuit by default.
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// Default time the circuit stays open before a probe.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// State of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Calls go through.
+    Closed,
+    /// Calls fail fast until the cooldown has passed.
+    Open,
+    /// One probe call is in flight; the rest fail fast.
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    failures: u32,
+    /// When the circuit last opened or a probe was let through.
+    since: Option<Instant>,
+}
+
+/// Opens after repeated outage failures of the data endpoints.
+///
+/// Cloning is cheap and clones share the state.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    inner: Arc<Mutex<Inner>>,
+    state: Arc<watch::Sender<CircuitState>>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CircuitBreaker {
+    /// Open after [`DEFAULT_FAILURE_THRESHOLD`] failures in a row and probe
+    /// after [`DEFAULT_COOLDOWN`].
+    pub fn new() -> Self {
+        Self {
+            threshold: DEFAULT_FAILURE_THRESHOLD,
+            cooldown: DEFAULT_COOLDOWN,
+            inner: Arc::default(),
+            state: Arc::new(watch::Sender::new(CircuitState::Closed)),
+        }
+    }
+
+    /// Open after `failures` consecutive failures (at least 1).
+    pub fn with_failure_threshold(mut self, failures: u32) -> Self {
+        self.threshold = failures.max(1);
+        self
+    }
+
+    /// Stay open for `cooldown` before letting a probe through.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// The current state.
+    pub fn state(&self) -> CircuitState {
+        *self.state.borrow()
+    }
+
+    /// Watch state changes.
+    pub fn subscribe(&self) -> watch::Receiver<CircuitState> {
+        self.state.subscribe()
+    }
+
+    /// Whether a call may be made now. Moves an open circuit whose cooldown
+    /// has passed to half-open and lets that call through as the probe.
+    pub fn allow(&self) -> bool {
+        let mut inner = self.lock();
+        match self.state() {
+            CircuitState::Closed => true,
+            // A probe that never reported back (e.g. its future was
+            // dropped) is replaced after another cooldown.
+            CircuitState::Open | CircuitState::HalfOpen => {
+                if inner.since.is_some_and(|t| t.elapsed() < self.cooldown) {
+                    return false;
+                }
+                inner.since = Some(Instant::now());
+                self.set(CircuitState::HalfOpen);
+                true
+            }
+        }
+    }
+
+    /// Report a successful call, closing the circuit.
+    pub fn record_success(&self) {
+        self.lock().failures = 0;
+        self.set(CircuitState::Closed);
+    }
+
+    /// Report an outage failure, opening the circuit at the threshold or
+    /// if it was probing.
+    pub fn record_failure(&self) {
+        let mut inner = self.lock();
+        inner.failures = inner.failures.saturating_add(1);
+        if inner.failures >= self.threshold || self.state() == CircuitState::HalfOpen {
+            inner.since = Some(Instant::now());
+            self.set(CircuitState::Open);
+        }
+    }
+
+    /// Close the circuit and forget past failures.
+    pub fn reset(&self) {
+        self.record_success();
+    }
+
+    fn set(&self, state: CircuitState) {
+        self.state.send_if_modified(|current| {
+            if *current == state {
+                return false;
+            }
+            tracing::info!(from = ?*current, to = ?state, "Circuit breaker state changed");
+            *current = state;
+            true
+        });
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Whether requests of `group` go through the breaker.
+pub(crate) fn is_guarded(group: EndpointGroup) -> bool {
+    matches!(group, EndpointGroup::Data | EndpointGroup::Historical)
+}
+
+/// Whether `err` means the endpoint is unavailable rather than that the
+/// request was wrong.
+fn is_outage(err: &DhanError) -> bool {
+    match err {
+        DhanError::Http(_) => true,
+        DhanError::HttpStatus { status, .. } => status.is_server_error() || status.as_u16() == 429,
+        DhanError::Api(body) => matches!(
+            body.error_code.as_deref(),
+            Some("DH-904" | "DH-908" | "DH-909")
+        ),
+        _ => false,
+    }
+}
+
+impl DhanClient {
+    /// Fail market data and historical calls of this client (and its
+    /// clones) fast while `breaker` is open; see [`crate::circuit`].
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.set_circuit_breaker(breaker);
+        self
+    }
+
+    /// Report the outcome of a call to `path` to the breaker, if it guards
+    /// it.
+    pub(crate) fn observe<T>(&self, path: &str, result: Result<T>) -> Result<T> {
+        if let Some(breaker) = self.circuit_breaker() {
+            if is_guarded(EndpointGroup::of(path)) {
+                match &result {
+                    Ok(_) => breaker.record_success(),
+                    Err(e) if is_outage(e) => breaker.record_failure(),
+                    // The server answered; the request itself was at fault.
+                    Err(_) => breaker.record_success(),
+                }
+            }
+        }
+        result
+    }
+}
@@ -6,19 +6,42 @@
 //!
 //! API endpoint methods are added to `DhanClient` via `impl` blocks in the
 //! [`crate::api`] module.
+//!
+//! Extra headers — tracing IDs, partner headers, an API version opt-in —
+//! go on every request of a client with [`DhanClient::with_headers`], or on
+//! the requests of one handle with [`DhanClient::with_options`].
+
+use std::collections::HashMap;
+use std::io::Write as _;
+use std::sync::Arc;
+use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use reqwest::Method;
 use reqwest::header::{self, HeaderMap, HeaderValue};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 
-use crate::constants::API_BASE_URL;
+use crate::audit::AuditLog;
+use crate::budget::{OrderBudget, is_order_request};
+use crate::circuit::{CircuitBreaker, is_guarded};
+use crate::constants::{API_BASE_URL, API_VERSION_HEADER};
+use crate::dns::DnsPins;
 use crate::error::{ApiErrorBody, DhanError, Result};
+use crate::oms::correlation::CorrelationIdGenerator;
+use crate::risk::duplicate::DuplicateOrderGuard;
+use crate::scope::{Scope, Scopes, required_scope};
+use crate::token::SharedToken;
+use crate::transport::HttpTransport;
+use crate::types::orders::PlaceOrderRequest;
 
 /// Core HTTP client for the DhanHQ REST API v2.
 ///
 /// Wraps [`reqwest::Client`] and injects the required authentication headers
-/// into every request. Auth header values are cached at construction time to
-/// avoid per-request allocation.
+/// into every request. The client ID header is cached at construction time;
+/// the access token is read from a [`SharedToken`] on every request, so a
+/// renewal ([`set_access_token`](Self::set_access_token) or a
+/// [`TokenManager`](crate::token::TokenManager)) reaches all clones.
 ///
 /// # Example
 ///
@@ -37,13 +60,65 @@ pub struct DhanClient {
     http: reqwest::Client,
     /// The Dhan client ID (user-specific identification).
     client_id: String,
-    /// JWT access token.
-    access_token: String,
+    /// JWT access token, shared by all clones so a renewal reaches them.
+    token: SharedToken,
     /// Base URL for REST API requests (defaults to [`API_BASE_URL`]).
     base_url: String,
-    /// Pre-built auth header values, cached to avoid per-request allocation.
-    auth_header_token: HeaderValue,
+    /// Per-group overrides of `base_url`.
+    group_base_urls: HashMap<EndpointGroup, String>,
+    /// Pre-built client ID header value, cached to avoid per-request
+    /// allocation.
     auth_header_client_id: HeaderValue,
+    /// Endpoints this handle may call (see [`DhanClient::scoped`]).
+    scopes: Scopes,
+    /// Journal of order-changing requests (see [`crate::audit`]).
+    audit: Option<AuditLog>,
+    /// Daily/hourly order request counter (see [`crate::budget`]).
+    order_budget: Option<OrderBudget>,
+    /// Fills in missing correlation IDs (see [`crate::oms::correlation`]).
+    correlation_ids: Option<Arc<dyn CorrelationIdGenerator>>,
+    /// Rejects repeated orders (see [`crate::risk::duplicate`]).
+    duplicate_guard: Option<DuplicateOrderGuard>,
+    /// Fails data calls fast during outages (see [`crate::circuit`]).
+    circuit_breaker: Option<CircuitBreaker>,
+    /// Gzip request bodies of at least this many bytes.
+    compress_requests_from: Option<usize>,
+    /// Settings `http` was built with.
+    http_config: HttpConfig,
+    /// Sends requests instead of `http` (see [`crate::transport`]).
+    transport: Option<Arc<dyn HttpTransport>>,
+    /// Sent with every request, under the auth headers.
+    extra_headers: HeaderMap,
+}
+
+/// Settings of the underlying `reqwest::Client`, kept so it can be rebuilt
+/// when one of them changes.
+#[derive(Debug, Clone)]
+struct HttpConfig {
+    /// Whether to ask for and decode gzip/deflate responses.
+    response_compression: bool,
+    /// Pinned addresses to resolve hosts through (see [`crate::dns`]).
+    dns_pins: Option<DnsPins>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<reqwest::Proxy>,
+    user_agent: Option<String>,
+    /// The client was supplied by the caller and is never rebuilt.
+    custom: bool,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            response_compression: true,
+            dns_pins: None,
+            timeout: None,
+            connect_timeout: None,
+            proxy: None,
+            user_agent: None,
+            custom: false,
+        }
+    }
 }
 
 impl DhanClient {
@@ -62,49 +137,268 @@ impl DhanClient {
         access_token: impl Into<String>,
         base_url: impl Into<String>,
     ) -> Self {
-        let http = reqwest::Client::builder()
-            .default_headers(Self::default_headers())
-            .build()
-            .expect("failed to build reqwest client");
+        let config = HttpConfig::default();
+        let http = Self::build_http(&config).expect("failed to build reqwest client");
+        Self::from_parts(
+            client_id.into(),
+            access_token.into(),
+            base_url.into(),
+            http,
+            config,
+        )
+    }
 
-        let access_token = access_token.into();
-        let client_id = client_id.into();
+    /// A [`DhanClientBuilder`] for timeouts, a proxy, a user agent or a
+    /// pre-built `reqwest::Client`.
+    pub fn builder(
+        client_id: impl Into<String>,
+        access_token: impl Into<String>,
+    ) -> DhanClientBuilder {
+        DhanClientBuilder::new(client_id, access_token)
+    }
 
-        let auth_header_token = HeaderValue::from_str(&access_token)
-            .expect("access token contains invalid header characters");
+    fn from_parts(
+        client_id: String,
+        access_token: String,
+        base_url: String,
+        http: reqwest::Client,
+        http_config: HttpConfig,
+    ) -> Self {
         let auth_header_client_id = HeaderValue::from_str(&client_id)
             .expect("client id contains invalid header characters");
 
         Self {
             http,
             client_id,
-            access_token,
-            base_url: base_url.into().trim_end_matches('/').to_owned(),
-            auth_header_token,
+            token: SharedToken::new(access_token),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            group_base_urls: HashMap::new(),
             auth_header_client_id,
+            scopes: Scopes::all(),
+            audit: None,
+            order_budget: None,
+            correlation_ids: None,
+            duplicate_guard: None,
+            circuit_breaker: None,
+            compress_requests_from: None,
+            http_config,
+            transport: None,
+            extra_headers: HeaderMap::new(),
         }
     }
 
+    /// Create a client that refuses every mutating endpoint.
+    ///
+    /// Placing, modifying or cancelling orders, converting positions, the
+    /// kill switch and P&L exit, IP whitelisting and eDIS calls fail with
+    /// [`DhanError::ScopeDenied`] before any request is sent. Market data,
+    /// margin calculation and all reads work as usual. Meant for analytics
+    /// and dashboard deployments that must be unable to trade.
+    ///
+    /// Requests sent directly through [`Self::http`] are not guarded.
+    pub fn read_only(client_id: impl Into<String>, access_token: impl Into<String>) -> Self {
+        Self::new(client_id, access_token).into_read_only()
+    }
+
+    /// Turn this client (and its future clones) read-only; see
+    /// [`Self::read_only`]. There is no way back.
+    pub fn into_read_only(self) -> Self {
+        self.scoped(Scopes::READ_ONLY)
+    }
+
+    /// Returns `true` if mutating endpoints are refused.
+    pub fn is_read_only(&self) -> bool {
+        !self.scopes.contains(Scope::OrderWrite) && !self.scopes.contains(Scope::Admin)
+    }
+
+    /// A handle limited to the scopes in both `scopes` and this client's
+    /// own; see [`crate::scope`]. The handle shares the connection pool.
+    pub fn scoped(&self, scopes: Scopes) -> Self {
+        let mut client = self.clone();
+        client.scopes = self.scopes.intersection(scopes);
+        client
+    }
+
+    /// A handle that also sends the headers of `options`; see
+    /// [`RequestOptions`]. The handle shares the connection pool.
+    ///
+    /// ```no_run
+    /// use dhan_rs::client::{DhanClient, RequestOptions};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> dhan_rs::Result<()> {
+    /// let client = DhanClient::new("1000000001", "token");
+    /// let options = RequestOptions::new().header("x-request-id", "rebalance-42")?;
+    /// let orders = client.with_options(&options).get_orders().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_options(&self, options: &RequestOptions) -> Self {
+        let mut client = self.clone();
+        client.extra_headers.extend(options.headers.clone());
+        client
+    }
+
+    /// The scopes this handle may use.
+    pub fn scopes(&self) -> Scopes {
+        self.scopes
+    }
+
+    /// Returns `true` if this handle may use `scope`.
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(scope)
+    }
+
     /// Returns a reference to the underlying `reqwest::Client`.
     pub fn http(&self) -> &reqwest::Client {
         &self.http
     }
 
+    /// Send every request through `transport` instead of the HTTP client,
+    /// e.g. a [`MockTransport`](crate::transport::MockTransport) in tests.
+    /// See [`crate::transport`].
+    pub fn with_transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Resolve hosts through `pins` (see [`Self::with_dns_pins`]).
+    pub(crate) fn set_dns_pins(&mut self, pins: DnsPins) {
+        self.http_config.dns_pins = Some(pins);
+        self.rebuild_http();
+    }
+
+    /// Ask for gzip/deflate responses and decode them transparently (the
+    /// default), or request and accept uncompressed bodies only.
+    ///
+    /// Compression shrinks large quote batches and long historical pulls
+    /// several times over, at a small CPU cost.
+    ///
+    /// Has no effect on a client given to [`DhanClientBuilder::http_client`].
+    pub fn with_response_compression(mut self, enabled: bool) -> Self {
+        self.http_config.response_compression = enabled;
+        self.rebuild_http();
+        self
+    }
+
+    /// Gzip JSON request bodies of at least `min_bytes` bytes and send them
+    /// with `Content-Encoding: gzip`. Off by default.
+    ///
+    /// Worth it for batches of hundreds of instruments; only enable it if
+    /// the endpoint accepts compressed bodies. Audit hashes (see
+    /// [`crate::audit`]) are always of the uncompressed JSON.
+    pub fn with_request_compression(mut self, min_bytes: usize) -> Self {
+        self.compress_requests_from = Some(min_bytes);
+        self
+    }
+
+    /// Send `headers` with every request, replacing earlier values of the
+    /// same names. The `access-token` and `client-id` headers cannot be
+    /// overridden.
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.extra_headers.extend(headers);
+        self
+    }
+
+    /// Opt into API version `version` by sending it as the
+    /// [`API_VERSION_HEADER`] header.
+    ///
+    /// # Panics
+    ///
+    /// If `version` is not a valid header value.
+    pub fn with_api_version(mut self, version: &'static str) -> Self {
+        self.extra_headers
+            .insert(API_VERSION_HEADER, HeaderValue::from_static(version));
+        self
+    }
+
+    /// The extra headers sent with every request.
+    pub fn extra_headers(&self) -> &HeaderMap {
+        &self.extra_headers
+    }
+
+    /// Whether responses are requested compressed.
+    pub fn response_compression(&self) -> bool {
+        self.http_config.response_compression
+    }
+
+    /// Replace the audit log (see [`Self::with_audit_log`]).
+    pub(crate) fn set_audit_log(&mut self, log: AuditLog) {
+        self.audit = Some(log);
+    }
+
+    /// Replace the order budget (see [`Self::with_order_budget`]).
+    pub(crate) fn set_order_budget(&mut self, budget: OrderBudget) {
+        self.order_budget = Some(budget);
+    }
+
+    /// The attached order budget.
+    pub(crate) fn order_budget(&self) -> Option<&OrderBudget> {
+        self.order_budget.as_ref()
+    }
+
+    /// Replace the correlation ID generator (see
+    /// [`Self::with_correlation_ids`]).
+    pub(crate) fn set_correlation_ids(&mut self, generator: Arc<dyn CorrelationIdGenerator>) {
+        self.correlation_ids = Some(generator);
+    }
+
+    /// The correlation ID generator.
+    pub(crate) fn correlation_ids(&self) -> Option<&dyn CorrelationIdGenerator> {
+        self.correlation_ids.as_deref()
+    }
+
+    /// Replace the duplicate-order guard (see
+    /// [`Self::with_duplicate_guard`]).
+    pub(crate) fn set_duplicate_guard(&mut self, guard: DuplicateOrderGuard) {
+        self.duplicate_guard = Some(guard);
+    }
+
+    /// Run `req` through the duplicate-order guard, if one is attached.
+    pub(crate) fn check_duplicate(&self, req: &PlaceOrderRequest) -> Result<()> {
+        match &self.duplicate_guard {
+            Some(guard) => guard.check_order(req),
+            None => Ok(()),
+        }
+    }
+
+    /// Replace the circuit breaker (see [`Self::with_circuit_breaker`]).
+    pub(crate) fn set_circuit_breaker(&mut self, breaker: CircuitBreaker) {
+        self.circuit_breaker = Some(breaker);
+    }
+
+    /// The circuit breaker, if one is attached.
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.circuit_breaker.as_ref()
+    }
+
     /// Returns the Dhan client ID.
     pub fn client_id(&self) -> &str {
         &self.client_id
     }
 
     /// Returns the current access token.
-    pub fn access_token(&self) -> &str {
-        &self.access_token
+    pub fn access_token(&self) -> String {
+        self.token.get()
+    }
+
+    /// Replace the access token (e.g. after renewal) of this client, its
+    /// clones and everything else sharing its [`SharedToken`].
+    pub fn set_access_token(&self, token: impl Into<String>) {
+        self.token.set(token);
+    }
+
+    /// The token this client reads on every request, to share with feed
+    /// connections so they reconnect with renewed tokens.
+    pub fn shared_token(&self) -> SharedToken {
+        self.token.clone()
     }
 
-    /// Replace the access token (e.g. after renewal).
-    pub fn set_access_token(&mut self, token: impl Into<String>) {
-        self.access_token = token.into();
-        self.auth_header_token = HeaderValue::from_str(&self.access_token)
-            .expect("access token contains invalid header characters");
+    /// Read the access token from `token` from now on, e.g. to make
+    /// clients of different configurations follow one renewal.
+    pub fn with_shared_token(mut self, token: SharedToken) -> Self {
+        self.token = token;
+        self
     }
 
     /// Returns the base URL.
@@ -112,52 +406,85 @@ impl DhanClient {
         &self.base_url
     }
 
+    /// Send requests of `group` to `base_url` instead of the client's base
+    /// URL, e.g. to route market data through a caching proxy while orders
+    /// go direct.
+    ///
+    /// ```
+    /// use dhan_rs::DhanClient;
+    /// use dhan_rs::client::EndpointGroup;
+    ///
+    /// let client = DhanClient::new("1000000001", "token")
+    ///     .with_group_base_url(EndpointGroup::Data, "http://quote-cache:8080/");
+    /// assert_eq!(client.base_url_for(EndpointGroup::Data), "http://quote-cache:8080");
+    /// assert_eq!(client.base_url_for(EndpointGroup::Orders), "https://api.dhan.co");
+    /// assert_eq!(EndpointGroup::of("/v2/marketfeed/ltp"), EndpointGroup::Data);
+    /// ```
+    pub fn with_group_base_url(
+        mut self,
+        group: EndpointGroup,
+        base_url: impl Into<String>,
+    ) -> Self {
+        self.group_base_urls
+            .insert(group, base_url.into().trim_end_matches('/').to_owned());
+        self
+    }
+
+    /// The base URL requests of `group` are sent to.
+    pub fn base_url_for(&self, group: EndpointGroup) -> &str {
+        self.group_base_urls
+            .get(&group)
+            .map_or(&self.base_url, String::as_str)
+    }
+
     // -----------------------------------------------------------------------
     // Generic HTTP helpers
     // -----------------------------------------------------------------------
 
     /// Perform a GET request and deserialize the JSON response.
     pub async fn get<R: DeserializeOwned>(&self, path: &str) -> Result<R> {
+        self.guard(Method::GET, path, &[])?;
         let url = self.url(path);
         tracing::debug!(%url, "GET");
 
-        let resp = self
-            .http
-            .get(&url)
-            .headers(self.auth_headers())
-            .send()
-            .await?;
-
-        self.handle_response(resp).await
+        let result = match self
+            .send(self.http.get(&url).headers(self.auth_headers()))
+            .await
+        {
+            Ok(resp) => self.handle_response(resp).await,
+            Err(e) => Err(e),
+        };
+        self.observe(path, result)
     }
 
     /// Perform a POST request with a JSON body and deserialize the response.
     pub async fn post<B: Serialize, R: DeserializeOwned>(&self, path: &str, body: &B) -> Result<R> {
+        let body = serde_json::to_vec(body)?;
+        self.guard(Method::POST, path, &body)?;
+        let (headers, body) = self.encode_body(body)?;
         let url = self.url(path);
         tracing::debug!(%url, "POST");
 
-        let resp = self
-            .http
-            .post(&url)
-            .headers(self.auth_headers())
-            .json(body)
-            .send()
-            .await?;
-
-        self.handle_response(resp).await
+        let result = match self
+            .send(self.http.post(&url).headers(headers).body(body))
+            .await
+        {
+            Ok(resp) => self.handle_response(resp).await,
+            Err(e) => Err(e),
+        };
+        self.observe(path, result)
     }
 
     /// Perform a PUT request with a JSON body and deserialize the response.
     pub async fn put<B: Serialize, R: DeserializeOwned>(&self, path: &str, body: &B) -> Result<R> {
+        let body = serde_json::to_vec(body)?;
+        self.guard(Method::PUT, path, &body)?;
+        let (headers, body) = self.encode_body(body)?;
         let url = self.url(path);
         tracing::debug!(%url, "PUT");
 
         let resp = self
-            .http
-            .put(&url)
-            .headers(self.auth_headers())
-            .json(body)
-            .send()
+            .send(self.http.put(&url).headers(headers).body(body))
             .await?;
 
         self.handle_response(resp).await
@@ -165,14 +492,12 @@ impl DhanClient {
 
     /// Perform a DELETE request and deserialize the JSON response.
     pub async fn delete<R: DeserializeOwned>(&self, path: &str) -> Result<R> {
+        self.guard(Method::DELETE, path, &[])?;
         let url = self.url(path);
         tracing::debug!(%url, "DELETE");
 
         let resp = self
-            .http
-            .delete(&url)
-            .headers(self.auth_headers())
-            .send()
+            .send(self.http.delete(&url).headers(self.auth_headers()))
             .await?;
 
         self.handle_response(resp).await
@@ -180,14 +505,12 @@ impl DhanClient {
 
     /// Perform a DELETE request that returns no body (expects 202 Accepted).
     pub async fn delete_no_content(&self, path: &str) -> Result<()> {
+        self.guard(Method::DELETE, path, &[])?;
         let url = self.url(path);
         tracing::debug!(%url, "DELETE (no content)");
 
         let resp = self
-            .http
-            .delete(&url)
-            .headers(self.auth_headers())
-            .send()
+            .send(self.http.delete(&url).headers(self.auth_headers()))
             .await?;
 
         let status = resp.status();
@@ -201,14 +524,12 @@ impl DhanClient {
 
     /// Perform a GET request that returns no body (expects 202 Accepted).
     pub async fn get_no_content(&self, path: &str) -> Result<()> {
+        self.guard(Method::GET, path, &[])?;
         let url = self.url(path);
         tracing::debug!(%url, "GET (no content)");
 
         let resp = self
-            .http
-            .get(&url)
-            .headers(self.auth_headers())
-            .send()
+            .send(self.http.get(&url).headers(self.auth_headers()))
             .await?;
 
         let status = resp.status();
@@ -222,15 +543,14 @@ impl DhanClient {
 
     /// Perform a POST request that returns no body (expects 202 Accepted).
     pub async fn post_no_content<B: Serialize>(&self, path: &str, body: &B) -> Result<()> {
+        let body = serde_json::to_vec(body)?;
+        self.guard(Method::POST, path, &body)?;
+        let (headers, body) = self.encode_body(body)?;
         let url = self.url(path);
         tracing::debug!(%url, "POST (no content)");
 
         let resp = self
-            .http
-            .post(&url)
-            .headers(self.auth_headers())
-            .json(body)
-            .send()
+            .send(self.http.post(&url).headers(headers).body(body))
             .await?;
 
         let status = resp.status();
@@ -242,16 +562,149 @@ impl DhanClient {
         }
     }
 
+    /// Perform a POST request with an already serialized JSON body and
+    /// return the successful response with its body unread, for callers
+    /// that serialize or parse off the async path.
+    pub(crate) async fn post_raw(&self, path: &str, body: Vec<u8>) -> Result<reqwest::Response> {
+        self.guard(Method::POST, path, &body)?;
+        let (headers, body) = self.encode_body(body)?;
+        let url = self.url(path);
+        tracing::debug!(%url, "POST (raw)");
+
+        let result = match self
+            .send(self.http.post(&url).headers(headers).body(body))
+            .await
+        {
+            Ok(resp) if resp.status().is_success() => Ok(resp),
+            Ok(resp) => {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                Err(self.parse_error_body(status, &body))
+            }
+            Err(e) => Err(e),
+        };
+        self.observe(path, result)
+    }
+
+    /// Perform a GET request and return the response's `Date` header,
+    /// whatever the status (see [`crate::clock`]).
+    pub(crate) async fn server_date(&self, path: &str) -> Result<Option<DateTime<Utc>>> {
+        self.guard(Method::GET, path, &[])?;
+        let url = self.url(path);
+        tracing::debug!(%url, "GET (date)");
+
+        let resp = self
+            .send(self.http.get(&url).headers(self.auth_headers()))
+            .await?;
+
+        Ok(resp
+            .headers()
+            .get(header::DATE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| DateTime::parse_from_rfc2822(s).ok())
+            .map(|d| d.with_timezone(&Utc)))
+    }
+
+    /// Build `request` and send it through the transport.
+    pub(crate) async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let request = request.build()?;
+        match &self.transport {
+            Some(transport) => transport.execute(request).await,
+            None => Ok(self.http.execute(request).await?),
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Private helpers
     // -----------------------------------------------------------------------
 
+    /// Fail with [`DhanError::ScopeDenied`] if `method path` needs a scope
+    /// this handle lacks, or with [`DhanError::CircuitOpen`] if the circuit
+    /// breaker blocks it; otherwise count order requests against the order
+    /// budget and record order-changing requests in the audit log, failing
+    /// if either does.
+    fn guard(&self, method: Method, path: &str, body: &[u8]) -> Result<()> {
+        let scope = required_scope(&method, path);
+        if !self.scopes.contains(scope) {
+            return Err(DhanError::ScopeDenied {
+                scope,
+                endpoint: format!("{method} {}", path.split('?').next().unwrap_or(path)),
+            });
+        }
+        if let Some(breaker) = &self.circuit_breaker {
+            let group = EndpointGroup::of(path);
+            if is_guarded(group) && !breaker.allow() {
+                return Err(DhanError::CircuitOpen(group));
+            }
+        }
+        if let (Scope::OrderWrite, Some(budget)) = (scope, &self.order_budget) {
+            if is_order_request(path) {
+                budget.record()?;
+            }
+        }
+        if let (Scope::OrderWrite, Some(audit)) = (scope, &self.audit) {
+            audit.record(method.as_str(), path, body)?;
+        }
+        Ok(())
+    }
+
+    /// The HTTP client for the given settings.
+    fn build_http(config: &HttpConfig) -> reqwest::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .default_headers(Self::default_headers())
+            .gzip(config.response_compression)
+            .deflate(config.response_compression);
+        if let Some(pins) = &config.dns_pins {
+            builder = builder.dns_resolver(Arc::new(pins.clone()));
+        }
+        if let Some(timeout) = config.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = config.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        if let Some(agent) = &config.user_agent {
+            builder = builder.user_agent(agent);
+        }
+        builder.build()
+    }
+
+    /// Rebuild `http` after a settings change, unless it is the caller's.
+    fn rebuild_http(&mut self) {
+        if !self.http_config.custom {
+            self.http =
+                Self::build_http(&self.http_config).expect("failed to build reqwest client");
+        }
+    }
+
+    /// Auth headers and the body to send, gzipped if it is large enough
+    /// (see [`Self::with_request_compression`]).
+    fn encode_body(&self, body: Vec<u8>) -> Result<(HeaderMap, Vec<u8>)> {
+        let mut headers = self.auth_headers();
+        match self.compress_requests_from {
+            Some(min) if body.len() >= min => {
+                let mut encoder = flate2::write::GzEncoder::new(
+                    Vec::with_capacity(body.len() / 4),
+                    flate2::Compression::fast(),
+                );
+                encoder.write_all(&body)?;
+                headers.insert(header::CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+                Ok((headers, encoder.finish()?))
+            }
+            _ => Ok((headers, body)),
+        }
+    }
+
     /// Build the full URL from a path segment.
     fn url(&self, path: &str) -> String {
+        let base_url = self.base_url_for(EndpointGroup::of(path));
         if path.starts_with('/') {
-            format!("{}{}", self.base_url, path)
+            format!("{base_url}{path}")
         } else {
-            format!("{}/{}", self.base_url, path)
+            format!("{base_url}/{path}")
         }
     }
 
@@ -266,11 +719,20 @@ impl DhanClient {
         headers
     }
 
-    /// Per-request auth headers. Uses cached [`HeaderValue`]s — only the
-    /// [`HeaderMap`] container is allocated per call (no string parsing).
+    /// Per-request auth headers, over the extra headers. Uses cached
+    /// [`HeaderValue`]s — only the [`HeaderMap`] container is allocated per
+    /// call (no string parsing).
     fn auth_headers(&self) -> HeaderMap {
-        let mut headers = HeaderMap::with_capacity(2);
-        headers.insert("access-token", self.auth_header_token.clone());
+        // A caller-supplied client lacks our default headers.
+        let mut headers = if self.http_config.custom {
+            Self::default_headers()
+        } else {
+            HeaderMap::with_capacity(2 + self.extra_headers.len())
+        };
+        if !self.extra_headers.is_empty() {
+            headers.extend(self.extra_headers.clone());
+        }
+        headers.insert("access-token", self.token.header());
         headers.insert("client-id", self.auth_header_client_id.clone());
         headers
     }
@@ -306,3 +768,174 @@ impl DhanClient {
         }
     }
 }
+
+// ---------------------------------------------------------------------------
+// Builder
+// ---------------------------------------------------------------------------
+
+/// Builder for a [`DhanClient`] with custom HTTP settings.
+///
+/// Settings not covered here (read-only mode, compression, DNS pins, …) are
+/// applied to the built client with its `with_*` methods as usual.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use dhan_rs::client::DhanClientBuilder;
+///
+/// # fn main() -> dhan_rs::Result<()> {
+/// let client = DhanClientBuilder::new("client_id", "access_token")
+///     .timeout(Duration::from_secs(5))
+///     .connect_timeout(Duration::from_secs(2))
+///     .proxy(reqwest::Proxy::all("http://10.0.0.5:3128")?)
+///     .user_agent("my-algo/1.0")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct DhanClientBuilder {
+    client_id: String,
+    access_token: String,
+    base_url: String,
+    config: HttpConfig,
+    http: Option<reqwest::Client>,
+}
+
+impl DhanClientBuilder {
+    /// Create a new builder with the given credentials.
+    pub fn new(client_id: impl Into<String>, access_token: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            access_token: access_token.into(),
+            base_url: API_BASE_URL.to_owned(),
+            config: HttpConfig::default(),
+            http: None,
+        }
+    }
+
+    /// Set the REST base URL. Default: [`API_BASE_URL`].
+    pub fn base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
+    /// Fail requests that take longer than `timeout` from connecting to
+    /// reading the whole body. Default: none.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = Some(timeout);
+        self
+    }
+
+    /// Fail connections that take longer than `timeout` to establish.
+    /// Default: none.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Send requests through `proxy`, e.g. a static-IP egress registered
+    /// with Dhan's IP allowlist. Default: the system proxy settings.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.config.proxy = Some(proxy);
+        self
+    }
+
+    /// Set the `User-Agent` header. Default: none.
+    pub fn user_agent(mut self, agent: impl Into<String>) -> Self {
+        self.config.user_agent = Some(agent.into());
+        self
+    }
+
+    /// Use `http` as is instead of building a client; the timeout, proxy
+    /// and user agent settings are then ignored, as are later compression
+    /// and DNS pin changes.
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Build the [`DhanClient`].
+    ///
+    /// Fails with [`DhanError::Http`] if the HTTP client cannot be built,
+    /// e.g. because the TLS backend fails to initialize.
+    pub fn build(mut self) -> Result<DhanClient> {
+        let http = match self.http.take() {
+            Some(http) => {
+                self.config.custom = true;
+                http
+            }
+            None => DhanClient::build_http(&self.config)?,
+        };
+        Ok(DhanClient::from_parts(
+            self.client_id,
+            self.access_token,
+            self.base_url,
+            http,
+            self.config,
+        ))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Request options
+// ---------------------------------------------------------------------------
+
+/// Extra settings for the requests of one handle (see
+/// [`DhanClient::with_options`]).
+#[derive(Debug, Clone, Default)]
+pub struct RequestOptions {
+    /// Headers sent on top of the client's extra headers, replacing those
+    /// of the same names.
+    pub headers: HeaderMap,
+}
+
+impl RequestOptions {
+    /// Options without extra headers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also send header `name` with `value`. Fails if either is not valid
+    /// in a header.
+    pub fn header(mut self, name: &str, value: &str) -> Result<Self> {
+        let name = header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| DhanError::InvalidArgument(format!("header name {name:?}: {e}")))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| DhanError::InvalidArgument(format!("header {name} value: {e}")))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+}
+
+/// A group of REST endpoints that can be routed to its own base URL (see
+/// [`DhanClient::with_group_base_url`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointGroup {
+    /// Orders, portfolio, funds, statements and everything not below.
+    Orders,
+    /// Market quotes and option chains.
+    Data,
+    /// Historical and intraday charts.
+    Historical,
+    /// Token renewal.
+    Auth,
+}
+
+impl EndpointGroup {
+    /// The group `path` belongs to.
+    pub fn of(path: &str) -> Self {
+        let path = path.trim_start_matches('/');
+        if path.starts_with("v2/marketfeed/") || path.starts_with("v2/optionchain") {
+            Self::Data
+        } else if path.starts_with("v2/charts/") {
+            Self::Historical
+        } else if path.starts_with("v2/RenewToken") {
+            Self::Auth
+        } else {
+            Self::Orders
+        }
+    }
+}
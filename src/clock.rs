@@ -0,0 +1,203 @@
+//! Checking the local clock against exchange time.
+//!
+//! Candle bucketing, session windows and expiry logic all read the local
+//! clock; if it is off by a few seconds, bars close early and square-off
+//! jobs fire late without any error. Two estimates of the offset are
+//! available:
+//!
+//! - [`clock_skew`] compares the `Date` header of a few REST responses with
+//!   the local send and receive times. The header has one-second
+//!   resolution, so the estimate is good to about half a second plus half
+//!   the round trip.
+//! - [`FeedClock`] compares the last trade time of feed ticks with their
+//!   arrival time. The freshest trade in a window bounds the offset from
+//!   below, so it needs at least one actively traded instrument.
+//!
+//! Both log a warning when the offset exceeds a threshold
+//! ([`DEFAULT_MAX_SKEW`] unless set).
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::clock::clock_skew;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let skew = clock_skew(&client).await?;
+//! println!("local clock is {} ms behind the server", skew.offset.num_milliseconds());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::ws::market_feed::Tick;
+
+/// Offset beyond which a warning is logged by default.
+pub const DEFAULT_MAX_SKEW: Duration = Duration::from_secs(1);
+
+/// REST responses sampled by [`clock_skew`].
+const REST_SAMPLES: usize = 3;
+
+/// Ticks [`FeedClock`] keeps by default.
+const DEFAULT_FEED_WINDOW: usize = 256;
+
+/// An estimate of how far the local clock is off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockSkew {
+    /// Server (exchange) time minus local time: positive when the local
+    /// clock is behind.
+    pub offset: TimeDelta,
+    /// How far the true offset may be from `offset`.
+    pub uncertainty: TimeDelta,
+    /// Observations the estimate is based on.
+    pub samples: usize,
+}
+
+impl ClockSkew {
+    /// Whether the local clock is off by more than `threshold` even
+    /// allowing for the uncertainty.
+    pub fn exceeds(&self, threshold: Duration) -> bool {
+        let threshold = TimeDelta::from_std(threshold).unwrap_or(TimeDelta::MAX);
+        self.offset.abs() - self.uncertainty > threshold
+    }
+
+    fn warn_if_exceeds(&self, threshold: Duration, source: &str) {
+        if self.exceeds(threshold) {
+            tracing::warn!(
+                source,
+                offset_ms = self.offset.num_milliseconds(),
+                uncertainty_ms = self.uncertainty.num_milliseconds(),
+                "Local clock is off from exchange time"
+            );
+        }
+    }
+}
+
+/// Estimate the local clock offset from the `Date` headers of a few
+/// `GET /v2/profile` responses, warning beyond [`DEFAULT_MAX_SKEW`].
+pub async fn clock_skew(client: &DhanClient) -> Result<ClockSkew> {
+    clock_skew_with_threshold(client, DEFAULT_MAX_SKEW).await
+}
+
+/// [`clock_skew`] with a custom warning threshold.
+pub async fn clock_skew_with_threshold(
+    client: &DhanClient,
+    threshold: Duration,
+) -> Result<ClockSkew> {
+    let mut best: Option<(TimeDelta, TimeDelta)> = None;
+    let mut samples = 0;
+    for _ in 0..REST_SAMPLES {
+        let sent = Utc::now();
+        let Some(date) = client.server_date("/v2/profile").await? else {
+            continue;
+        };
+        let received = Utc::now();
+        samples += 1;
+        let half_trip = (received - sent) / 2;
+        // The header is truncated to the second: its midpoint is the best
+        // guess for the server time.
+        let server = date + TimeDelta::milliseconds(500);
+        let offset = server - (sent + half_trip);
+        let uncertainty = half_trip + TimeDelta::milliseconds(500);
+        if best.is_none_or(|(_, u)| uncertainty < u) {
+            best = Some((offset, uncertainty));
+        }
+    }
+    let Some((offset, uncertainty)) = best else {
+        return Err(DhanError::InvalidArgument(
+            "no Date header in the server responses".into(),
+        ));
+    };
+    let skew = ClockSkew {
+        offset,
+        uncertainty,
+        samples,
+    };
+    skew.warn_if_exceeds(threshold, "rest");
+    Ok(skew)
+}
+
+/// Estimates the local clock offset from feed last-trade times.
+///
+/// Feed `ltt` values are whole seconds, so each observation allows for
+/// half a second of truncation.
+#[derive(Debug, Clone)]
+pub struct FeedClock {
+    /// Trade time minus arrival time of the latest ticks, oldest first.
+    lags: VecDeque<TimeDelta>,
+    window: usize,
+    threshold: Duration,
+    warned: bool,
+}
+
+impl Default for FeedClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FeedClock {
+    /// Keep the last 256 ticks and warn beyond [`DEFAULT_MAX_SKEW`].
+    pub fn new() -> Self {
+        Self {
+            lags: VecDeque::new(),
+            window: DEFAULT_FEED_WINDOW,
+            threshold: DEFAULT_MAX_SKEW,
+            warned: false,
+        }
+    }
+
+    /// Estimate from the last `ticks` ticks (at least 1).
+    pub fn with_window(mut self, ticks: usize) -> Self {
+        self.window = ticks.max(1);
+        self
+    }
+
+    /// Warn beyond `threshold`.
+    pub fn with_threshold(mut self, threshold: Duration) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Record a tick arriving now.
+    pub fn observe(&mut self, tick: &Tick) {
+        self.observe_at(tick, Utc::now());
+    }
+
+    /// Record a tick that arrived at `received`. Warns once each time the
+    /// estimate starts exceeding the threshold.
+    pub fn observe_at(&mut self, tick: &Tick, received: DateTime<Utc>) {
+        let Some(traded) = tick.trade_time() else {
+            return;
+        };
+        while self.lags.len() >= self.window {
+            self.lags.pop_front();
+        }
+        self.lags
+            .push_back(traded + TimeDelta::milliseconds(500) - received);
+        if let Some(skew) = self.skew() {
+            let exceeds = skew.exceeds(self.threshold);
+            if exceeds && !self.warned {
+                skew.warn_if_exceeds(self.threshold, "feed");
+            }
+            self.warned = exceeds;
+        }
+    }
+
+    /// The current estimate: the freshest trade in the window, i.e. the
+    /// smallest lag between trade and arrival.
+    pub fn skew(&self) -> Option<ClockSkew> {
+        let offset = self.lags.iter().max()?;
+        Some(ClockSkew {
+            offset: *offset,
+            uncertainty: TimeDelta::milliseconds(500),
+            samples: self.lags.len(),
+        })
+    }
+}
@@ -14,6 +14,21 @@ pub const API_BASE_URL: &str = "https://api.dhan.co";
 /// Base URL for authentication endpoints.
 pub const AUTH_BASE_URL: &str = "https://auth.dhan.co";
 
+/// Default echo service used to discover this machine's public IP (returns
+/// the IP as plain text).
+pub const PUBLIC_IP_ECHO_URL: &str = "https://api.ipify.org";
+
+/// Compact scrip master CSV (see [`crate::instruments`]).
+pub const SCRIP_MASTER_URL: &str = "https://images.dhan.co/api-data/api-scrip-master.csv";
+
+/// Detailed scrip master CSV, with ISINs and underlyings.
+pub const SCRIP_MASTER_DETAILED_URL: &str =
+    "https://images.dhan.co/api-data/api-scrip-master-detailed.csv";
+
+/// Header selecting the REST API version (see
+/// [`crate::client::DhanClient::with_api_version`]).
+pub const API_VERSION_HEADER: &str = "x-api-version";
+
 // ---------------------------------------------------------------------------
 // WebSocket URLs
 // ---------------------------------------------------------------------------
@@ -0,0 +1,182 @@
+//! Exact decimal prices and amounts (feature `decimal`).
+//!
+//! Request and response types keep the API's JSON numbers as `f64`. Summing
+//! P&L or stepping prices by the tick in `f64` drifts (`0.1 + 0.2`), so this
+//! module mirrors those fields as [`Decimal`]s:
+//!
+//! - [`ToDecimal`] reads any `f64` or `Option<f64>` field — order prices,
+//!   fund limits, position averages — as the decimal the API sent: the
+//!   shortest decimal that parses back to the same `f64`, so `1500.05`
+//!   becomes exactly `1500.05`.
+//! - [`to_f64`] and the `price_decimal`/`trigger_price_decimal` setters of
+//!   the order builders go the other way; the JSON sent then carries the
+//!   decimal digits unchanged (up to 15 significant digits).
+//! - [`round_to_tick`] rounds a price to the tick size.
+//! - [`float`] and [`float_option`] (de)serialize `Decimal` fields of your
+//!   own types as JSON numbers, also accepting numeric strings.
+//!
+//! ```
+//! use dhan_rs::decimal::{Decimal, ToDecimal, round_to_tick};
+//! use dhan_rs::types::portfolio::Position;
+//!
+//! let position: Position = serde_json::from_str(
+//!     r#"{"buyAvg": 100.1, "buyQty": 3, "sellAvg": 100.3, "sellQty": 3}"#,
+//! )
+//! .unwrap();
+//! let per_share = position.sell_avg.to_decimal().unwrap() - position.buy_avg.to_decimal().unwrap();
+//! assert_eq!(per_share * Decimal::from(3), Decimal::new(6, 1));
+//!
+//! let tick = Decimal::new(5, 2);
+//! assert_eq!(round_to_tick(Decimal::new(150_012, 2), tick), Decimal::new(150_010, 2));
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+pub use rust_decimal::Decimal;
+use rust_decimal::RoundingStrategy;
+
+use crate::types::builders::{ModifyOrderRequestBuilder, PlaceOrderRequestBuilder};
+
+/// Reads a JSON-number field as a [`Decimal`].
+pub trait ToDecimal {
+    /// The value as a decimal, or `None` if it is unset, not finite or out
+    /// of `Decimal`'s range.
+    fn to_decimal(&self) -> Option<Decimal>;
+}
+
+impl ToDecimal for f64 {
+    fn to_decimal(&self) -> Option<Decimal> {
+        from_f64(*self)
+    }
+}
+
+impl ToDecimal for Option<f64> {
+    fn to_decimal(&self) -> Option<Decimal> {
+        self.and_then(from_f64)
+    }
+}
+
+/// The shortest decimal that parses back to `value`; `None` if `value` is
+/// not finite or out of `Decimal`'s range.
+pub fn from_f64(value: f64) -> Option<Decimal> {
+    if !value.is_finite() {
+        return None;
+    }
+    // `Display` prints the shortest round-trip digits, never in exponent form.
+    Decimal::from_str(&value.to_string()).ok()
+}
+
+/// The `f64` nearest to `value`, as sent in request bodies.
+pub fn to_f64(value: Decimal) -> f64 {
+    value.to_string().parse().unwrap_or(f64::NAN)
+}
+
+/// `price` rounded to the nearest multiple of `tick`, halves away from
+/// zero; `price` itself if `tick` is not positive.
+pub fn round_to_tick(price: Decimal, tick: Decimal) -> Decimal {
+    if tick <= Decimal::ZERO {
+        return price;
+    }
+    (price / tick).round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero) * tick
+}
+
+impl PlaceOrderRequestBuilder {
+    /// Set `price` from a decimal.
+    pub fn price_decimal(self, value: Decimal) -> Self {
+        self.price(to_f64(value))
+    }
+
+    /// Set `trigger_price` from a decimal.
+    pub fn trigger_price_decimal(self, value: Decimal) -> Self {
+        self.trigger_price(to_f64(value))
+    }
+}
+
+impl ModifyOrderRequestBuilder {
+    /// Set `price` from a decimal.
+    pub fn price_decimal(self, value: Decimal) -> Self {
+        self.price(to_f64(value))
+    }
+
+    /// Set `trigger_price` from a decimal.
+    pub fn trigger_price_decimal(self, value: Decimal) -> Self {
+        self.trigger_price(to_f64(value))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Serde
+// ---------------------------------------------------------------------------
+
+struct DecimalVisitor;
+
+impl serde::de::Visitor<'_> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a decimal number or numeric string")
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Decimal, E> {
+        from_f64(v).ok_or_else(|| E::custom(format!("{v} is out of decimal range")))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Decimal, E> {
+        Ok(Decimal::from(v))
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Decimal, E> {
+        Ok(Decimal::from(v))
+    }
+
+    fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Decimal, E> {
+        Decimal::from_str(v.trim()).map_err(E::custom)
+    }
+}
+
+/// `#[serde(with = "dhan_rs::decimal::float")]` for `Decimal` fields: a
+/// JSON number out, a number or numeric string in.
+pub mod float {
+    use serde::{Deserializer, Serializer};
+
+    use super::{Decimal, DecimalVisitor, to_f64};
+
+    /// Serialize `value` as a number.
+    pub fn serialize<S: Serializer>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(to_f64(*value))
+    }
+
+    /// Deserialize a number or numeric string.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        deserializer.deserialize_any(DecimalVisitor)
+    }
+}
+
+/// [`float`] for `Option<Decimal>` fields; `null` is `None`.
+pub mod float_option {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{Decimal, to_f64};
+
+    /// Serialize `value` as a number or `null`.
+    pub fn serialize<S: Serializer>(
+        value: &Option<Decimal>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(v) => serializer.serialize_some(&to_f64(*v)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Deserialize a number, numeric string or `null`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Decimal>, D::Error> {
+        #[derive(Deserialize)]
+        struct Wrapped(#[serde(with = "super::float")] Decimal);
+
+        Ok(Option::<Wrapped>::deserialize(deserializer)?.map(|Wrapped(v)| v))
+    }
+}
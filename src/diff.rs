@@ -0,0 +1,216 @@
+//! Structured differences between REST snapshots.
+//!
+//! Polling the order book or positions gives a full list each time; what a
+//! consumer wants is what changed. [`diff_orders`] and [`diff_positions`]
+//! compare two snapshots and return a [`ChangeSet`] of added, removed and
+//! changed entries, the latter with the individual [`FieldChange`]s.
+//! [`DhanClient::poll_orders`] and [`DhanClient::poll_positions`] fetch,
+//! diff and keep the latest snapshot in one call.
+//!
+//! ```
+//! use dhan_rs::diff::diff_orders;
+//! use dhan_rs::types::orders::OrderDetail;
+//!
+//! let order = |status: &str| -> OrderDetail {
+//!     serde_json::from_value(serde_json::json!({
+//!         "orderId": "O1",
+//!         "orderStatus": status,
+//!         "quantity": 10
+//!     }))
+//!     .unwrap()
+//! };
+//!
+//! let changes = diff_orders(&[order("PENDING")], &[order("TRADED")]);
+//! assert!(changes.added.is_empty() && changes.removed.is_empty());
+//! let modified = &changes.changed[0];
+//! assert_eq!(modified.key, "O1");
+//! assert_eq!(modified.fields[0].field, "orderStatus");
+//! assert_eq!(modified.fields[0].new, "TRADED");
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::types::orders::OrderDetail;
+use crate::types::portfolio::Position;
+
+/// One field that differs between two versions of an entry.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// Field name as in the API's JSON.
+    pub field: String,
+    /// Old value (`null` if absent).
+    pub old: Value,
+    /// New value (`null` if absent).
+    pub new: Value,
+}
+
+/// An entry present in both snapshots with different contents.
+#[derive(Debug, Clone, Serialize)]
+pub struct Modified<T> {
+    /// The entry's key (e.g. the order ID).
+    pub key: String,
+    /// The entry in the old snapshot.
+    pub old: T,
+    /// The entry in the new snapshot.
+    pub new: T,
+    /// The fields that differ, sorted by name.
+    pub fields: Vec<FieldChange>,
+}
+
+impl<T> Modified<T> {
+    /// The change of `field`, if it changed.
+    pub fn field(&self, field: &str) -> Option<&FieldChange> {
+        self.fields.iter().find(|f| f.field == field)
+    }
+}
+
+/// Differences between two snapshots.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangeSet<T> {
+    /// Entries only in the new snapshot, in its order.
+    pub added: Vec<T>,
+    /// Entries only in the old snapshot, in its order.
+    pub removed: Vec<T>,
+    /// Entries in both whose contents differ, in the new snapshot's order.
+    pub changed: Vec<Modified<T>>,
+}
+
+impl<T> Default for ChangeSet<T> {
+    fn default() -> Self {
+        Self {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        }
+    }
+}
+
+impl<T> ChangeSet<T> {
+    /// Returns `true` if the snapshots are the same.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Number of added, removed and changed entries.
+    pub fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.changed.len()
+    }
+}
+
+/// Diff two snapshots whose entries are identified by `key`.
+///
+/// Entries without a key are ignored. If a key repeats within a snapshot,
+/// the last entry wins.
+pub fn diff_by<T, F>(old: &[T], new: &[T], key: F) -> ChangeSet<T>
+where
+    T: Clone + Serialize,
+    F: Fn(&T) -> Option<String>,
+{
+    let old_by_key: HashMap<String, &T> = old.iter().filter_map(|e| Some((key(e)?, e))).collect();
+    let new_keys: BTreeSet<String> = new.iter().filter_map(&key).collect();
+
+    let mut changes = ChangeSet::default();
+    let mut seen = BTreeSet::new();
+    for entry in new {
+        let Some(k) = key(entry) else { continue };
+        if !seen.insert(k.clone()) {
+            continue;
+        }
+        match old_by_key.get(&k) {
+            None => changes.added.push(entry.clone()),
+            Some(previous) => {
+                let fields = field_changes(*previous, entry);
+                if !fields.is_empty() {
+                    changes.changed.push(Modified {
+                        key: k,
+                        old: (*previous).clone(),
+                        new: entry.clone(),
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+    let mut removed = BTreeSet::new();
+    for entry in old {
+        if let Some(k) = key(entry) {
+            if !new_keys.contains(&k) && removed.insert(k) {
+                changes.removed.push(entry.clone());
+            }
+        }
+    }
+    changes
+}
+
+/// Fields of `old` and `new` whose JSON values differ.
+fn field_changes<T: Serialize>(old: &T, new: &T) -> Vec<FieldChange> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) =
+        (serde_json::to_value(old), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    let fields: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    fields
+        .into_iter()
+        .filter_map(|field| {
+            let before = old.get(field).cloned().unwrap_or(Value::Null);
+            let after = new.get(field).cloned().unwrap_or(Value::Null);
+            (before != after).then(|| FieldChange {
+                field: field.clone(),
+                old: before,
+                new: after,
+            })
+        })
+        .collect()
+}
+
+/// Diff two order books, keyed by order ID.
+pub fn diff_orders(old: &[OrderDetail], new: &[OrderDetail]) -> ChangeSet<OrderDetail> {
+    diff_by(old, new, |o| o.order_id.clone())
+}
+
+/// Diff two position lists, keyed by `SEGMENT:SECURITY_ID:PRODUCT`.
+pub fn diff_positions(old: &[Position], new: &[Position]) -> ChangeSet<Position> {
+    diff_by(old, new, position_key)
+}
+
+/// The key [`diff_positions`] identifies a position by.
+pub fn position_key(position: &Position) -> Option<String> {
+    Some(format!(
+        "{}:{}:{}",
+        position.exchange_segment.as_deref().unwrap_or_default(),
+        position.security_id.as_deref()?,
+        position.product_type.as_deref().unwrap_or_default()
+    ))
+}
+
+impl DhanClient {
+    /// Fetch the order book, diff it against `previous` and store it there.
+    ///
+    /// Start with an empty `previous` to get every order as added.
+    pub async fn poll_orders(
+        &self,
+        previous: &mut Vec<OrderDetail>,
+    ) -> Result<ChangeSet<OrderDetail>> {
+        let current = self.get_orders().await?;
+        let changes = diff_orders(previous, &current);
+        *previous = current;
+        Ok(changes)
+    }
+
+    /// Fetch positions, diff them against `previous` and store them there.
+    pub async fn poll_positions(
+        &self,
+        previous: &mut Vec<Position>,
+    ) -> Result<ChangeSet<Position>> {
+        let current = self.get_positions().await?;
+        let changes = diff_positions(previous, &current);
+        *previous = current;
+        Ok(changes)
+    }
+}
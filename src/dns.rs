@@ -0,0 +1,266 @@
+//! DNS pre-resolution and endpoint pinning.
+//!
+//! A DNS lookup on the order path costs milliseconds at best and fails
+//! outright when the resolver is down. [`DnsPins`] resolves the Dhan hosts
+//! once at startup, keeps the addresses in memory (refreshing them
+//! periodically with [`DnsPins::spawn_refresh`]) and serves them to the
+//! REST client ([`DhanClient::with_dns_pins`]) and the market feed
+//! ([`DhanFeedManager::with_dns_pins`], [`MarketFeedStream::connect_pinned`]).
+//!
+//! Alternate addresses added with [`DnsPins::with_fallback`] are tried after
+//! the resolved ones, so a connection fails over to them if the resolved
+//! addresses are unreachable — or if resolution never succeeded.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::dns::{DEFAULT_PINNED_HOSTS, DnsPins};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let pins = DnsPins::resolve(DEFAULT_PINNED_HOSTS).await?;
+//! let _refresh = pins.spawn_refresh(Duration::from_secs(300));
+//!
+//! let client = DhanClient::new("client-id", "token").with_dns_pins(&pins);
+//! let orders = client.get_orders().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`DhanFeedManager::with_dns_pins`]: crate::ws::manager::DhanFeedManager::with_dns_pins
+//! [`MarketFeedStream::connect_pinned`]: crate::ws::market_feed::MarketFeedStream::connect_pinned
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, client_async_tls, connect_async};
+
+use crate::client::DhanClient;
+use crate::error::Result;
+
+/// The REST, market feed and order update hosts.
+pub const DEFAULT_PINNED_HOSTS: &[&str] = &[
+    "api.dhan.co",
+    "api-feed.dhan.co",
+    "api-order-update.dhan.co",
+];
+
+/// Suggested interval for [`DnsPins::spawn_refresh`].
+pub const DEFAULT_DNS_REFRESH: Duration = Duration::from_secs(300);
+
+/// Default for [`DnsPins::with_connect_timeout`].
+pub const DEFAULT_PIN_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Default)]
+struct Entry {
+    resolved: Vec<IpAddr>,
+    fallback: Vec<IpAddr>,
+}
+
+/// Pinned addresses per host.
+///
+/// Cloning is cheap and clones share the addresses, so a refresh is seen
+/// by every client using them.
+#[derive(Debug, Clone)]
+pub struct DnsPins {
+    hosts: Arc<RwLock<HashMap<String, Entry>>>,
+    connect_timeout: Duration,
+}
+
+impl Default for DnsPins {
+    fn default() -> Self {
+        Self {
+            hosts: Arc::default(),
+            connect_timeout: DEFAULT_PIN_CONNECT_TIMEOUT,
+        }
+    }
+}
+
+impl DnsPins {
+    /// No pinned hosts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve `hosts` and pin the results.
+    ///
+    /// Fails if any host cannot be resolved; use [`Self::pin`] and
+    /// [`Self::with_fallback`] to start from known addresses instead.
+    pub async fn resolve(hosts: &[&str]) -> Result<Self> {
+        let pins = Self::new();
+        for host in hosts {
+            let addrs = lookup(host).await?;
+            pins.pin(host, addrs);
+        }
+        Ok(pins)
+    }
+
+    /// Pin `host` to `addrs`, replacing its resolved addresses.
+    pub fn pin(&self, host: &str, addrs: impl IntoIterator<Item = IpAddr>) {
+        self.write().entry(host.to_owned()).or_default().resolved = addrs.into_iter().collect();
+    }
+
+    /// Add alternate addresses for `host`, tried after the resolved ones.
+    pub fn with_fallback(self, host: &str, addrs: impl IntoIterator<Item = IpAddr>) -> Self {
+        self.write()
+            .entry(host.to_owned())
+            .or_default()
+            .fallback
+            .extend(addrs);
+        self
+    }
+
+    /// Give up on a pinned address after `timeout` and try the next one
+    /// (default [`DEFAULT_PIN_CONNECT_TIMEOUT`]). Applies to WebSocket
+    /// connections; the REST client has its own connect timeout.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Deadline for connecting to one pinned address.
+    pub fn connect_timeout(&self) -> Duration {
+        self.connect_timeout
+    }
+
+    /// Addresses to try for `host`, resolved first; empty if the host is
+    /// not pinned.
+    pub fn addrs(&self, host: &str) -> Vec<IpAddr> {
+        let hosts = self.hosts.read().unwrap_or_else(|e| e.into_inner());
+        let Some(entry) = hosts.get(host) else {
+            return Vec::new();
+        };
+        let mut addrs = entry.resolved.clone();
+        for ip in &entry.fallback {
+            if !addrs.contains(ip) {
+                addrs.push(*ip);
+            }
+        }
+        addrs
+    }
+
+    /// Pinned host names, sorted.
+    pub fn hosts(&self) -> Vec<String> {
+        let mut hosts: Vec<String> = self
+            .hosts
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        hosts.sort();
+        hosts
+    }
+
+    /// Re-resolve every pinned host. A host that fails to resolve keeps
+    /// its previous addresses; the first error is returned.
+    pub async fn refresh(&self) -> Result<()> {
+        let mut first_err = None;
+        for host in self.hosts() {
+            match lookup(&host).await {
+                Ok(addrs) => self.pin(&host, addrs),
+                Err(e) => {
+                    tracing::warn!(host, error = %e, "DNS refresh failed; keeping pinned addresses");
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+        first_err.map_or(Ok(()), Err)
+    }
+
+    /// Call [`Self::refresh`] every `interval` in the background.
+    pub fn spawn_refresh(&self, interval: Duration) -> JoinHandle<()> {
+        let pins = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let _ = pins.refresh().await;
+            }
+        })
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, Entry>> {
+        self.hosts.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+async fn lookup(host: &str) -> Result<Vec<IpAddr>> {
+    Ok(tokio::net::lookup_host((host, 0))
+        .await?
+        .map(|addr| addr.ip())
+        .collect())
+}
+
+/// Pinned hosts resolve from memory; others through the system resolver.
+impl Resolve for DnsPins {
+    fn resolve(&self, name: Name) -> Resolving {
+        let addrs = self.addrs(name.as_str());
+        let host = name.as_str().to_owned();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = if addrs.is_empty() {
+                tokio::net::lookup_host((host.as_str(), 0)).await?.collect()
+            } else {
+                addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)).collect()
+            };
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+impl DhanClient {
+    /// Resolve hosts through `pins` instead of a DNS lookup per new
+    /// connection. Later refreshes of `pins` apply to this client.
+    pub fn with_dns_pins(mut self, pins: &DnsPins) -> Self {
+        self.set_dns_pins(pins.clone());
+        self
+    }
+}
+
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Open a WebSocket to `url`, connecting to the pinned addresses of its
+/// host in order when `pins` has any, each within the pins' connect
+/// timeout.
+pub(crate) async fn connect_ws(
+    url: &str,
+    pins: Option<&DnsPins>,
+) -> std::result::Result<(WsStream, Response), tokio_tungstenite::tungstenite::Error> {
+    let parsed = url::Url::parse(url).ok();
+    let target = parsed.as_ref().zip(pins).and_then(|(u, pins)| {
+        let host = u.host_str()?;
+        let addrs = pins.addrs(host);
+        (!addrs.is_empty()).then(|| (addrs, u.port_or_known_default().unwrap_or(443), pins))
+    });
+    let Some((addrs, port, pins)) = target else {
+        return connect_async(url).await;
+    };
+
+    let mut last_err = None;
+    for ip in addrs {
+        let attempt = tokio::time::timeout(pins.connect_timeout, TcpStream::connect((ip, port)));
+        match attempt.await.unwrap_or_else(|_| {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "connect timed out",
+            ))
+        }) {
+            Ok(stream) => return client_async_tls(url, stream).await,
+            Err(e) => {
+                tracing::warn!(%ip, port, error = %e, "Pinned address unreachable");
+                last_err = Some(e);
+            }
+        }
+    }
+    Err(last_err
+        .unwrap_or_else(|| std::io::Error::other("no pinned address"))
+        .into())
+}
@@ -8,8 +8,16 @@
 //! - **HTTP status errors** — Unexpected status codes with response body
 //! - **HTTP transport errors** — Network, TLS, timeout failures
 //! - **JSON errors** — Deserialization failures
-//! - **WebSocket errors** — Connection and protocol errors
+//! - **WebSocket errors** — Connection, protocol and authentication errors
+//! - **Expired tokens** — Access tokens the REST API or market feed refused
 //! - **URL errors** — Malformed URL construction
+//! - **Risk rejections** — Orders blocked by client-side pre-trade checks
+//! - **Missing sell authorization** — CNC sells without eDIS approval or DDPI
+//! - **Scope refusals** — Calls outside a scoped or read-only client's scopes
+//! - **Order rejections** — Orders the OMS accepted the request for but rejected
+//! - **Postback rejections** — Webhook deliveries failing source or secret checks
+//! - **I/O errors** — Local file access (recordings, journals)
+//! - **CSV errors** — Malformed scrip master files
 //! - **Invalid arguments** — Client-side validation errors
 
 use std::fmt;
@@ -73,6 +81,74 @@ pub enum DhanError {
     #[error("URL error: {0}")]
     Url(#[from] url::ParseError),
 
+    /// The server rejected (or never acknowledged) a WebSocket login.
+    #[error("WebSocket authentication failed: {0}")]
+    WsAuthFailed(String),
+
+    /// The access token is expired or invalid: the REST API answered
+    /// `DH-901`, or the market feed refused the connection. Retrying with
+    /// the same token cannot succeed; renew or replace it.
+    #[error("Access token expired or invalid: {0}")]
+    TokenExpired(String),
+
+    /// A WebSocket saw no traffic (messages or pongs) within its idle window.
+    #[error("WebSocket connection stale: no traffic for {0:?}")]
+    StaleConnection(std::time::Duration),
+
+    /// An order was blocked by a client-side pre-trade risk check.
+    #[error("Risk check rejected order: {0}")]
+    RiskRejected(String),
+
+    /// A CNC sell order has neither eDIS approval nor DDPI for its quantity
+    /// (see [`DhanClient::check_sell_authorization`]).
+    ///
+    /// [`DhanClient::check_sell_authorization`]: crate::DhanClient::check_sell_authorization
+    #[error("Sell authorization missing: {0}")]
+    AuthorizationMissing(String),
+
+    /// The client handle lacks the scope an endpoint needs (see
+    /// [`crate::scope`] and [`DhanClient::read_only`]).
+    ///
+    /// [`DhanClient::read_only`]: crate::DhanClient::read_only
+    #[error("{endpoint} needs scope {scope}")]
+    ScopeDenied {
+        /// The missing scope.
+        scope: crate::scope::Scope,
+        /// Method and path of the refused call.
+        endpoint: String,
+    },
+
+    /// The OMS rejected an order (see [`OrderResponse::into_result`]).
+    ///
+    /// [`OrderResponse::into_result`]: crate::types::orders::OrderResponse::into_result
+    #[error("Order rejected: {0}")]
+    OrderRejected(crate::types::orders::OrderRejection),
+
+    /// A postback failed the source-IP or shared-secret check (see
+    /// [`PostbackValidator`]).
+    ///
+    /// [`PostbackValidator`]: crate::types::postback::PostbackValidator
+    #[error("Postback rejected: {0}")]
+    PostbackRejected(String),
+
+    /// A local I/O error (e.g. reading or writing a recording file).
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Failed to parse a CSV file (e.g. the scrip master).
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    /// A circuit breaker is open after repeated failures of this endpoint
+    /// group; the call was not sent (see [`crate::circuit`]).
+    #[error("Circuit open for {0:?} endpoints")]
+    CircuitOpen(crate::client::EndpointGroup),
+
+    /// No market feed connection could be opened within the account's
+    /// connection limit (see [`crate::ws::slots`]).
+    #[error("Connection limit reached: {0}")]
+    ConnectionLimit(String),
+
     /// The caller provided an invalid argument.
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
@@ -84,5 +160,17 @@ impl From<tokio_tungstenite::tungstenite::Error> for DhanError {
     }
 }
 
+impl DhanError {
+    /// Returns `true` if the error means the access token has to be
+    /// renewed: [`DhanError::TokenExpired`] or a `DH-901` API error.
+    pub fn is_token_expired(&self) -> bool {
+        match self {
+            DhanError::TokenExpired(_) => true,
+            DhanError::Api(body) => body.error_code.as_deref() == Some("DH-901"),
+            _ => false,
+        }
+    }
+}
+
 /// Convenience alias used throughout the crate.
 pub type Result<T> = std::result::Result<T, DhanError>;
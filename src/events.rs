@@ -0,0 +1,376 @@
+//! One event bus for everything that happens in a session.
+//!
+//! Order updates, market packets, feed connection changes, risk decisions,
+//! journal records and REST outcomes each come from their own stream or
+//! callback. An [`EventBus`] carries all of them as one [`SessionEvent`]
+//! enum, and subscribers pick what they want with an [`EventFilter`], so an
+//! application can run a single consumer loop.
+//!
+//! Publishers: [`RiskEngine::with_event_bus`], [`Journal::with_event_bus`],
+//! [`DhanFeedManager::with_event_bus`], [`EventBus::forward`] for any stream
+//! and [`EventBus::publish_rest`] for REST results.
+//!
+//! ```
+//! use dhan_rs::events::{EventBus, EventFilter, EventKind, RiskEvent, SessionEvent};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let bus = EventBus::new();
+//! let mut risk = bus.subscribe(EventFilter::kinds([EventKind::Risk]));
+//!
+//! bus.publish_rest("place_order", &Ok::<_, dhan_rs::DhanError>(()));
+//! bus.publish(SessionEvent::Risk(RiskEvent::Halted));
+//!
+//! // The REST event is filtered out.
+//! assert!(matches!(risk.recv().await, Some(SessionEvent::Risk(RiskEvent::Halted))));
+//! # }
+//! ```
+//!
+//! [`RiskEngine::with_event_bus`]: crate::risk::RiskEngine::with_event_bus
+//! [`Journal::with_event_bus`]: crate::journal::Journal::with_event_bus
+//! [`DhanFeedManager::with_event_bus`]: crate::ws::manager::DhanFeedManager::with_event_bus
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::budget::BudgetWarning;
+use crate::error::Result;
+use crate::risk::RiskLimits;
+use crate::ws::manager::{ConnectionId, FeedAuthFailure};
+use crate::ws::market_feed::MarketFeedEvent;
+use crate::ws::order_update::OrderUpdate;
+use crate::ws::quality::FeedQualityEvent;
+
+/// Default number of events buffered per subscriber.
+pub const DEFAULT_EVENT_BUS_CAPACITY: usize = 4096;
+
+// ---------------------------------------------------------------------------
+// Events
+// ---------------------------------------------------------------------------
+
+/// Category of a [`SessionEvent`], for filtering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum EventKind {
+    /// [`SessionEvent::Order`].
+    Order,
+    /// [`SessionEvent::Market`].
+    Market,
+    /// [`SessionEvent::Feed`].
+    Feed,
+    /// [`SessionEvent::Risk`].
+    Risk,
+    /// [`SessionEvent::Journal`].
+    Journal,
+    /// [`SessionEvent::Rest`].
+    Rest,
+    /// [`SessionEvent::Custom`].
+    Custom,
+}
+
+/// A change in the state of a market feed connection.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedLifecycle {
+    /// A connection was established (initially or after a reconnect).
+    Connected {
+        /// The connection.
+        connection: ConnectionId,
+    },
+    /// A connection was lost.
+    Disconnected {
+        /// The connection.
+        connection: ConnectionId,
+        /// Why, as far as it is known.
+        reason: String,
+    },
+    /// No frame arrived within the idle timeout, not even a pong to the
+    /// heartbeat ping; the connection is dropped (and reconnected if
+    /// enabled).
+    Stale {
+        /// The connection.
+        connection: ConnectionId,
+        /// How long it was silent.
+        idle: Duration,
+    },
+    /// Reconnecting failed; the connection stays down.
+    ReconnectFailed {
+        /// The connection.
+        connection: ConnectionId,
+        /// The connect error.
+        error: String,
+    },
+    /// The server dropped the connection because the account has too many
+    /// (Disconnect reason 805); it stays down rather than knock off another.
+    ConnectionLimit {
+        /// The connection.
+        connection: ConnectionId,
+    },
+    /// The server rejected the credentials; the connection stays down
+    /// until [`DhanFeedManager::reconnect_with_token`].
+    ///
+    /// [`DhanFeedManager::reconnect_with_token`]: crate::ws::manager::DhanFeedManager::reconnect_with_token
+    AuthFailed(FeedAuthFailure),
+    /// A feed-quality problem or recovery.
+    Quality(FeedQualityEvent),
+}
+
+/// A decision of the risk engine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskEvent {
+    /// An order was blocked.
+    Rejected {
+        /// Security ID of the order.
+        security_id: String,
+        /// Rejection reason (prefixed with the check name).
+        reason: String,
+    },
+    /// Trading was halted.
+    Halted,
+    /// Trading was resumed.
+    Resumed,
+    /// Adjustable limits were replaced.
+    LimitsChanged(RiskLimits),
+    /// Order requests crossed a threshold of the daily or hourly budget
+    /// (see [`crate::budget`]).
+    OrderBudget(BudgetWarning),
+    /// A session switched its strategies to safe mode: new entries are
+    /// refused, exits still go through (see [`crate::runtime::degrade`]).
+    SafeModeEntered {
+        /// Why, e.g. the circuit breaker state.
+        reason: String,
+    },
+    /// Safe mode ended.
+    SafeModeExited,
+}
+
+/// A record appended to a journal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEvent {
+    /// The journal file.
+    pub path: PathBuf,
+    /// The record as JSON.
+    pub record: serde_json::Value,
+}
+
+/// The outcome of a REST call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestEvent {
+    /// Name of the operation (e.g. `"place_order"`).
+    pub operation: String,
+    /// The error message, if the call failed.
+    pub error: Option<String>,
+}
+
+/// Anything published on an [`EventBus`].
+#[derive(Debug, Clone)]
+pub enum SessionEvent {
+    /// A normalized order update (boxed: it is much larger than a tick).
+    Order(Box<OrderUpdate>),
+    /// A parsed market feed packet.
+    Market(MarketFeedEvent),
+    /// A feed connection change.
+    Feed(FeedLifecycle),
+    /// A risk engine decision.
+    Risk(RiskEvent),
+    /// A journal record.
+    Journal(JournalEvent),
+    /// A REST call outcome.
+    Rest(RestEvent),
+    /// An application-defined event.
+    Custom {
+        /// Application-defined name.
+        name: String,
+        /// Event data.
+        payload: serde_json::Value,
+    },
+}
+
+impl SessionEvent {
+    /// The event's category.
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::Order(_) => EventKind::Order,
+            Self::Market(_) => EventKind::Market,
+            Self::Feed(_) => EventKind::Feed,
+            Self::Risk(_) => EventKind::Risk,
+            Self::Journal(_) => EventKind::Journal,
+            Self::Rest(_) => EventKind::Rest,
+            Self::Custom { .. } => EventKind::Custom,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Filter
+// ---------------------------------------------------------------------------
+
+type Predicate = Arc<dyn Fn(&SessionEvent) -> bool + Send + Sync>;
+
+/// Selects the events a subscription receives.
+#[derive(Clone, Default)]
+pub struct EventFilter {
+    kinds: Option<HashSet<EventKind>>,
+    predicate: Option<Predicate>,
+}
+
+impl std::fmt::Debug for EventFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventFilter")
+            .field("kinds", &self.kinds)
+            .field("predicate", &self.predicate.is_some())
+            .finish()
+    }
+}
+
+impl EventFilter {
+    /// Accept every event.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Accept only events of `kinds`.
+    pub fn kinds(kinds: impl IntoIterator<Item = EventKind>) -> Self {
+        Self::default().with_kinds(kinds)
+    }
+
+    /// Also accept events of `kinds` (the first call narrows an
+    /// accept-all filter).
+    pub fn with_kinds(mut self, kinds: impl IntoIterator<Item = EventKind>) -> Self {
+        self.kinds.get_or_insert_with(HashSet::new).extend(kinds);
+        self
+    }
+
+    /// Additionally require `predicate` to return `true`.
+    pub fn with_predicate(
+        mut self,
+        predicate: impl Fn(&SessionEvent) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Returns `true` if `event` passes the filter.
+    pub fn matches(&self, event: &SessionEvent) -> bool {
+        self.kinds
+            .as_ref()
+            .is_none_or(|k| k.contains(&event.kind()))
+            && self.predicate.as_ref().is_none_or(|p| p(event))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Bus
+// ---------------------------------------------------------------------------
+
+/// Broadcasts [`SessionEvent`]s to filtered subscribers.
+///
+/// Cloning is cheap and clones publish to the same subscribers.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<SessionEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    /// Create a bus buffering [`DEFAULT_EVENT_BUS_CAPACITY`] events per
+    /// subscriber.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_EVENT_BUS_CAPACITY)
+    }
+
+    /// Create a bus buffering `capacity` events per subscriber.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity.max(1));
+        Self { tx }
+    }
+
+    /// Publish `event`. Returns the number of subscribers it reached
+    /// (before filtering); publishing with no subscribers is not an error.
+    pub fn publish(&self, event: SessionEvent) -> usize {
+        self.tx.send(event).unwrap_or(0)
+    }
+
+    /// Publish the outcome of a REST call.
+    pub fn publish_rest<T>(&self, operation: impl Into<String>, result: &Result<T>) -> usize {
+        self.publish(SessionEvent::Rest(RestEvent {
+            operation: operation.into(),
+            error: result.as_ref().err().map(ToString::to_string),
+        }))
+    }
+
+    /// Subscribe to events passing `filter`, from now on.
+    pub fn subscribe(&self, filter: EventFilter) -> EventSubscription {
+        EventSubscription {
+            rx: self.tx.subscribe(),
+            filter,
+        }
+    }
+
+    /// Number of live subscriptions.
+    pub fn subscriber_count(&self) -> usize {
+        self.tx.receiver_count()
+    }
+
+    /// Publish every item of `stream` that `map` turns into an event, on a
+    /// background task that ends with the stream.
+    pub fn forward<S, F>(&self, stream: S, map: F) -> JoinHandle<()>
+    where
+        S: Stream + Send + 'static,
+        F: Fn(S::Item) -> Option<SessionEvent> + Send + 'static,
+    {
+        let bus = self.clone();
+        tokio::spawn(async move {
+            let mut stream = std::pin::pin!(stream);
+            while let Some(item) = stream.next().await {
+                if let Some(event) = map(item) {
+                    bus.publish(event);
+                }
+            }
+        })
+    }
+}
+
+/// A filtered view of an [`EventBus`].
+#[derive(Debug)]
+pub struct EventSubscription {
+    rx: broadcast::Receiver<SessionEvent>,
+    filter: EventFilter,
+}
+
+impl EventSubscription {
+    /// The next matching event, or `None` once every publisher is gone.
+    ///
+    /// A subscriber that falls behind skips the missed events (with a
+    /// warning) rather than ending the subscription.
+    pub async fn recv(&mut self) -> Option<SessionEvent> {
+        loop {
+            match self.rx.recv().await {
+                Ok(event) if self.filter.matches(&event) => return Some(event),
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    tracing::warn!(skipped = n, "Event subscriber lagging behind bus");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Turn the subscription into a stream of matching events.
+    pub fn into_stream(self) -> impl Stream<Item = SessionEvent> {
+        futures_util::stream::unfold(self, |mut sub| async move {
+            let event = sub.recv().await?;
+            Some((event, sub))
+        })
+    }
+}
@@ -0,0 +1,566 @@
+//! Instrument master (scrip master) download and lookups.
+//!
+//! Every API takes security IDs, which Dhan publishes in its scrip master
+//! CSV. [`InstrumentMaster`] downloads and parses it into
+//! [`InstrumentRecord`]s and indexes them by instrument ID, symbol, ISIN,
+//! security ID and exchange segment.
+//!
+//! Both the compact ([`SCRIP_MASTER_URL`]) and the detailed
+//! ([`SCRIP_MASTER_DETAILED_URL`]) files are understood; only the detailed
+//! one carries ISINs and underlyings. Rows of exchange/segment pairs with
+//! no [`ExchangeSegment`] are skipped and counted.
+//!
+//! [`InstrumentMaster::filter`] selects contracts by segment, underlying,
+//! option side and expiry (a date, [`next_weekly`] or [`next_monthly`]),
+//! e.g. to subscribe a whole chain with
+//! [`DhanFeedManager::subscribe_filter`].
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::constants::SCRIP_MASTER_DETAILED_URL;
+//! use dhan_rs::instruments::InstrumentMaster;
+//! use dhan_rs::types::enums::ExchangeSegment;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let master = InstrumentMaster::download(client.http(), SCRIP_MASTER_DETAILED_URL).await?;
+//!
+//! let reliance = master.find(ExchangeSegment::NSE_EQ, "RELIANCE").unwrap();
+//! println!("{} lot {}", reliance.id, reliance.lot_size);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`DhanFeedManager::subscribe_filter`]: crate::ws::manager::DhanFeedManager::subscribe_filter
+//! [`SCRIP_MASTER_URL`]: crate::constants::SCRIP_MASTER_URL
+//! [`SCRIP_MASTER_DETAILED_URL`]: crate::constants::SCRIP_MASTER_DETAILED_URL
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{DhanError, Result};
+use crate::types::enums::{ExchangeSegment, Instrument};
+use crate::types::instrument::InstrumentId;
+
+/// One row of the scrip master.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstrumentRecord {
+    /// Exchange segment and security ID.
+    pub id: InstrumentId,
+    /// Exchange (`NSE`, `BSE`, `MCX`).
+    pub exchange: String,
+    /// Instrument type, if it is one the API accepts.
+    pub instrument: Option<Instrument>,
+    /// Trading symbol, e.g. `RELIANCE` or `NIFTY-Jun2025-24000-CE`.
+    pub symbol: String,
+    /// Display name, e.g. `Reliance Industries`.
+    pub display_name: Option<String>,
+    /// ISIN (detailed file only).
+    pub isin: Option<String>,
+    /// Exchange series, e.g. `EQ`.
+    pub series: Option<String>,
+    /// Lot size (1 for equities).
+    pub lot_size: f64,
+    /// Tick size, as published.
+    pub tick_size: f64,
+    /// Expiry date of derivatives.
+    pub expiry: Option<NaiveDate>,
+    /// Strike price of options.
+    pub strike: Option<f64>,
+    /// `CE` or `PE` for options.
+    pub option_type: Option<String>,
+    /// Security ID of the underlying (detailed file only).
+    pub underlying_security_id: Option<u32>,
+    /// Symbol of the underlying (detailed file only).
+    pub underlying_symbol: Option<String>,
+}
+
+impl InstrumentRecord {
+    /// Symbol of the underlying, or else the trading symbol up to the first
+    /// `-` (`NIFTY` for `NIFTY-Jun2025-24000-CE`).
+    pub fn underlying(&self) -> &str {
+        match &self.underlying_symbol {
+            Some(symbol) => symbol,
+            None => self.symbol.split('-').next().unwrap_or_default(),
+        }
+    }
+
+    /// What contracts of one series share: the underlying's security ID or
+    /// symbol.
+    fn series_key(&self) -> String {
+        match self.underlying_security_id {
+            Some(id) => id.to_string(),
+            None => self.underlying().to_ascii_uppercase(),
+        }
+    }
+}
+
+/// The segment of an exchange/segment pair as published in the master.
+fn segment_of(exchange: &str, segment: &str) -> Option<ExchangeSegment> {
+    Some(match (exchange, segment) {
+        (_, "I") => ExchangeSegment::IDX_I,
+        ("NSE", "E") => ExchangeSegment::NSE_EQ,
+        ("NSE", "D") => ExchangeSegment::NSE_FNO,
+        ("NSE", "C") => ExchangeSegment::NSE_CURRENCY,
+        ("BSE", "E") => ExchangeSegment::BSE_EQ,
+        ("BSE", "D") => ExchangeSegment::BSE_FNO,
+        ("BSE", "C") => ExchangeSegment::BSE_CURRENCY,
+        ("MCX", "M") => ExchangeSegment::MCX_COMM,
+        _ => return None,
+    })
+}
+
+fn instrument_of(name: &str) -> Option<Instrument> {
+    Some(match name {
+        "INDEX" => Instrument::INDEX,
+        "FUTIDX" => Instrument::FUTIDX,
+        "OPTIDX" => Instrument::OPTIDX,
+        "EQUITY" => Instrument::EQUITY,
+        "FUTSTK" => Instrument::FUTSTK,
+        "OPTSTK" => Instrument::OPTSTK,
+        "FUTCOM" => Instrument::FUTCOM,
+        "OPTFUT" => Instrument::OPTFUT,
+        "FUTCUR" => Instrument::FUTCUR,
+        "OPTCUR" => Instrument::OPTCUR,
+        _ => return None,
+    })
+}
+
+/// Column positions, resolved from the header of either file format.
+struct Columns {
+    exchange: usize,
+    segment: usize,
+    security_id: usize,
+    instrument: Option<usize>,
+    symbol: usize,
+    display_name: Option<usize>,
+    isin: Option<usize>,
+    series: Option<usize>,
+    lot_size: Option<usize>,
+    tick_size: Option<usize>,
+    expiry: Option<usize>,
+    strike: Option<usize>,
+    option_type: Option<usize>,
+    underlying_security_id: Option<usize>,
+    underlying_symbol: Option<usize>,
+}
+
+impl Columns {
+    fn resolve(headers: &csv::StringRecord) -> Result<Self> {
+        let find = |names: &[&str]| {
+            headers
+                .iter()
+                .position(|h| names.iter().any(|n| h.trim().eq_ignore_ascii_case(n)))
+        };
+        let require = |names: &[&str]| {
+            find(names).ok_or_else(|| {
+                DhanError::InvalidArgument(format!("scrip master has no {} column", names[0]))
+            })
+        };
+        Ok(Self {
+            exchange: require(&["SEM_EXM_EXCH_ID", "EXCH_ID"])?,
+            segment: require(&["SEM_SEGMENT", "SEGMENT"])?,
+            security_id: require(&["SEM_SMST_SECURITY_ID", "SECURITY_ID"])?,
+            instrument: find(&["SEM_INSTRUMENT_NAME", "INSTRUMENT"]),
+            symbol: require(&["SEM_TRADING_SYMBOL", "SYMBOL_NAME"])?,
+            display_name: find(&["SEM_CUSTOM_SYMBOL", "DISPLAY_NAME"]),
+            isin: find(&["ISIN"]),
+            series: find(&["SEM_SERIES", "SERIES"]),
+            lot_size: find(&["SEM_LOT_UNITS", "LOT_SIZE"]),
+            tick_size: find(&["SEM_TICK_SIZE", "TICK_SIZE"]),
+            expiry: find(&["SEM_EXPIRY_DATE", "SM_EXPIRY_DATE"]),
+            strike: find(&["SEM_STRIKE_PRICE", "STRIKE_PRICE"]),
+            option_type: find(&["SEM_OPTION_TYPE", "OPTION_TYPE"]),
+            underlying_security_id: find(&["UNDERLYING_SECURITY_ID"]),
+            underlying_symbol: find(&["UNDERLYING_SYMBOL"]),
+        })
+    }
+
+    /// The record of `row`, or `None` if its segment or ID is unusable.
+    fn record(&self, row: &csv::StringRecord) -> Option<InstrumentRecord> {
+        let get = |i: usize| row.get(i).map(str::trim).unwrap_or("");
+        let text = |i: Option<usize>| {
+            i.map(get)
+                .filter(|s| !s.is_empty() && *s != "NA" && *s != "XX")
+                .map(str::to_owned)
+        };
+        let number = |i: Option<usize>| i.and_then(|i| get(i).parse::<f64>().ok());
+
+        let exchange = get(self.exchange);
+        let segment = segment_of(exchange, get(self.segment))?;
+        let security_id = get(self.security_id).parse().ok()?;
+        Some(InstrumentRecord {
+            id: InstrumentId::new(segment, security_id),
+            exchange: exchange.to_owned(),
+            instrument: self.instrument.and_then(|i| instrument_of(get(i))),
+            symbol: get(self.symbol).to_owned(),
+            display_name: text(self.display_name),
+            isin: text(self.isin),
+            series: text(self.series),
+            lot_size: number(self.lot_size).unwrap_or(1.0),
+            tick_size: number(self.tick_size).unwrap_or(0.0),
+            expiry: self.expiry.and_then(|i| {
+                let s = get(i);
+                NaiveDate::parse_from_str(s.get(..10)?, "%Y-%m-%d").ok()
+            }),
+            strike: number(self.strike).filter(|s| *s > 0.0),
+            option_type: text(self.option_type),
+            underlying_security_id: self
+                .underlying_security_id
+                .and_then(|i| get(i).parse().ok())
+                .filter(|id| *id > 0),
+            underlying_symbol: text(self.underlying_symbol),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Master
+// ---------------------------------------------------------------------------
+
+/// Parsed scrip master with lookup indexes.
+#[derive(Debug, Clone, Default)]
+pub struct InstrumentMaster {
+    records: Vec<InstrumentRecord>,
+    by_id: HashMap<InstrumentId, usize>,
+    by_symbol: HashMap<String, Vec<usize>>,
+    by_isin: HashMap<String, Vec<usize>>,
+    by_security_id: HashMap<u32, Vec<usize>>,
+    by_segment: HashMap<ExchangeSegment, Vec<usize>>,
+    skipped: usize,
+}
+
+impl InstrumentMaster {
+    /// Download the scrip master at `url` (see
+    /// [`SCRIP_MASTER_DETAILED_URL`](crate::constants::SCRIP_MASTER_DETAILED_URL))
+    /// and parse it on a blocking thread.
+    pub async fn download(http: &reqwest::Client, url: &str) -> Result<Self> {
+        let resp = http.get(url).send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(DhanError::HttpStatus {
+                status,
+                body: resp.text().await.unwrap_or_default(),
+            });
+        }
+        let body = resp.bytes().await?;
+        tokio::task::spawn_blocking(move || Self::from_reader(&body[..]))
+            .await
+            .map_err(|e| DhanError::InvalidArgument(format!("scrip master parser failed: {e}")))?
+    }
+
+    /// Parse a scrip master saved at `path`.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_reader(std::fs::File::open(path)?)
+    }
+
+    /// Parse a scrip master CSV (compact or detailed).
+    pub fn from_reader(reader: impl Read) -> Result<Self> {
+        let mut csv = csv::ReaderBuilder::new().flexible(true).from_reader(reader);
+        let columns = Columns::resolve(csv.headers()?)?;
+        let mut master = Self::default();
+        let mut row = csv::StringRecord::new();
+        while csv.read_record(&mut row)? {
+            match columns.record(&row) {
+                Some(record) => master.insert(record),
+                None => master.skipped += 1,
+            }
+        }
+        Ok(master)
+    }
+
+    /// Build a master from already parsed records.
+    pub fn from_records(records: impl IntoIterator<Item = InstrumentRecord>) -> Self {
+        let mut master = Self::default();
+        for record in records {
+            master.insert(record);
+        }
+        master
+    }
+
+    fn insert(&mut self, record: InstrumentRecord) {
+        let i = self.records.len();
+        self.by_id.insert(record.id, i);
+        self.by_symbol
+            .entry(record.symbol.to_ascii_uppercase())
+            .or_default()
+            .push(i);
+        if let Some(isin) = &record.isin {
+            self.by_isin
+                .entry(isin.to_ascii_uppercase())
+                .or_default()
+                .push(i);
+        }
+        self.by_security_id
+            .entry(record.id.security_id)
+            .or_default()
+            .push(i);
+        self.by_segment
+            .entry(record.id.segment)
+            .or_default()
+            .push(i);
+        self.records.push(record);
+    }
+
+    fn pick(&self, indexes: Option<&Vec<usize>>) -> Vec<&InstrumentRecord> {
+        indexes
+            .map(|v| v.iter().map(|&i| &self.records[i]).collect())
+            .unwrap_or_default()
+    }
+
+    /// The record of `id`.
+    pub fn get(&self, id: &InstrumentId) -> Option<&InstrumentRecord> {
+        self.by_id.get(id).map(|&i| &self.records[i])
+    }
+
+    /// Records with trading symbol `symbol` (case-insensitive), across
+    /// segments.
+    pub fn by_symbol(&self, symbol: &str) -> Vec<&InstrumentRecord> {
+        self.pick(self.by_symbol.get(&symbol.trim().to_ascii_uppercase()))
+    }
+
+    /// The record with trading symbol `symbol` in `segment`.
+    pub fn find(&self, segment: ExchangeSegment, symbol: &str) -> Option<&InstrumentRecord> {
+        self.by_symbol(symbol)
+            .into_iter()
+            .find(|r| r.id.segment == segment)
+    }
+
+    /// Records with ISIN `isin` (e.g. the NSE and BSE listings of a stock).
+    pub fn by_isin(&self, isin: &str) -> Vec<&InstrumentRecord> {
+        self.pick(self.by_isin.get(&isin.trim().to_ascii_uppercase()))
+    }
+
+    /// Records with security ID `security_id`, which is only unique within
+    /// a segment.
+    pub fn by_security_id(&self, security_id: u32) -> Vec<&InstrumentRecord> {
+        self.pick(self.by_security_id.get(&security_id))
+    }
+
+    /// Records of `segment`.
+    pub fn in_segment(&self, segment: ExchangeSegment) -> Vec<&InstrumentRecord> {
+        self.pick(self.by_segment.get(&segment))
+    }
+
+    /// The contract of the same series as `record` with the nearest expiry
+    /// after it: same segment, instrument type and underlying, and for
+    /// options the same strike and side. `None` for records without an
+    /// expiry or when the master lists no later contract.
+    pub fn next_in_series(&self, record: &InstrumentRecord) -> Option<&InstrumentRecord> {
+        let expiry = record.expiry?;
+        let series = record.series_key();
+        self.pick(self.by_segment.get(&record.id.segment))
+            .into_iter()
+            .filter(|r| {
+                r.expiry.is_some_and(|e| e > expiry)
+                    && r.instrument == record.instrument
+                    && r.strike == record.strike
+                    && r.option_type == record.option_type
+                    && r.series_key() == series
+            })
+            .min_by_key(|r| r.expiry)
+    }
+
+    /// Start a query over the records; see [`InstrumentFilter`].
+    pub fn filter(&self) -> InstrumentFilter<'_> {
+        InstrumentFilter {
+            master: self,
+            segment: None,
+            instrument: None,
+            underlying: None,
+            option_type: None,
+            expiry: None,
+            today: None,
+        }
+    }
+
+    /// All records, in file order.
+    pub fn iter(&self) -> impl Iterator<Item = &InstrumentRecord> {
+        self.records.iter()
+    }
+
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if there are no records.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Rows skipped because their segment or security ID was unusable.
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Filters
+// ---------------------------------------------------------------------------
+
+/// Which expiry an [`InstrumentFilter`] keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpirySelector {
+    /// Contracts expiring on this date.
+    On(NaiveDate),
+    /// The nearest expiry on or after today, e.g. this week's for weekly
+    /// options.
+    Next,
+    /// The last expiry of the month of the nearest expiry, i.e. the current
+    /// monthly contract.
+    NextMonthly,
+}
+
+impl From<NaiveDate> for ExpirySelector {
+    fn from(date: NaiveDate) -> Self {
+        Self::On(date)
+    }
+}
+
+/// The nearest expiry; see [`ExpirySelector::Next`].
+pub fn next_weekly() -> ExpirySelector {
+    ExpirySelector::Next
+}
+
+/// The current monthly expiry; see [`ExpirySelector::NextMonthly`].
+pub fn next_monthly() -> ExpirySelector {
+    ExpirySelector::NextMonthly
+}
+
+/// A query over an [`InstrumentMaster`], built with
+/// [`InstrumentMaster::filter`]. Criteria that are not set match every
+/// record.
+///
+/// ```
+/// use dhan_rs::instruments::{InstrumentMaster, next_weekly};
+/// use dhan_rs::types::enums::ExchangeSegment;
+///
+/// let master = InstrumentMaster::default();
+/// let contracts = master
+///     .filter()
+///     .segment(ExchangeSegment::NSE_FNO)
+///     .underlying("BANKNIFTY")
+///     .expiry(next_weekly())
+///     .ids();
+/// assert!(contracts.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct InstrumentFilter<'a> {
+    master: &'a InstrumentMaster,
+    segment: Option<ExchangeSegment>,
+    instrument: Option<Instrument>,
+    underlying: Option<String>,
+    option_type: Option<String>,
+    expiry: Option<ExpirySelector>,
+    today: Option<NaiveDate>,
+}
+
+impl<'a> InstrumentFilter<'a> {
+    /// Only records of `segment`.
+    pub fn segment(mut self, segment: ExchangeSegment) -> Self {
+        self.segment = Some(segment);
+        self
+    }
+
+    /// Only records of instrument type `instrument`.
+    pub fn instrument(mut self, instrument: Instrument) -> Self {
+        self.instrument = Some(instrument);
+        self
+    }
+
+    /// Only derivatives of `underlying` (case-insensitive; see
+    /// [`InstrumentRecord::underlying`]).
+    pub fn underlying(mut self, underlying: &str) -> Self {
+        self.underlying = Some(underlying.trim().to_ascii_uppercase());
+        self
+    }
+
+    /// Only options of side `option_type` (`CE` or `PE`).
+    pub fn option_type(mut self, option_type: &str) -> Self {
+        self.option_type = Some(option_type.trim().to_ascii_uppercase());
+        self
+    }
+
+    /// Only contracts of the selected expiry.
+    pub fn expiry(mut self, expiry: impl Into<ExpirySelector>) -> Self {
+        self.expiry = Some(expiry.into());
+        self
+    }
+
+    /// Resolve relative expiries as of the IST date `today` instead of the
+    /// current date.
+    pub fn as_of(mut self, today: NaiveDate) -> Self {
+        self.today = Some(today);
+        self
+    }
+
+    /// The matching records, in file order.
+    pub fn records(&self) -> Vec<&'a InstrumentRecord> {
+        let candidates: Vec<&'a InstrumentRecord> = match self.segment {
+            Some(segment) => self.master.in_segment(segment),
+            None => self.master.iter().collect(),
+        }
+        .into_iter()
+        .filter(|r| self.instrument.is_none_or(|i| r.instrument == Some(i)))
+        .filter(|r| {
+            self.underlying
+                .as_deref()
+                .is_none_or(|u| r.underlying().eq_ignore_ascii_case(u))
+        })
+        .filter(|r| {
+            self.option_type.as_deref().is_none_or(|o| {
+                r.option_type
+                    .as_deref()
+                    .is_some_and(|t| t.eq_ignore_ascii_case(o))
+            })
+        })
+        .collect();
+        let Some(selector) = self.expiry else {
+            return candidates;
+        };
+        let Some(expiry) = self.resolve(selector, &candidates) else {
+            return Vec::new();
+        };
+        candidates
+            .into_iter()
+            .filter(|r| r.expiry == Some(expiry))
+            .collect()
+    }
+
+    /// IDs of the matching records, in file order.
+    pub fn ids(&self) -> Vec<InstrumentId> {
+        self.records().into_iter().map(|r| r.id).collect()
+    }
+
+    fn resolve(
+        &self,
+        selector: ExpirySelector,
+        candidates: &[&InstrumentRecord],
+    ) -> Option<NaiveDate> {
+        let today = self
+            .today
+            .unwrap_or_else(|| crate::time::to_ist(chrono::Utc::now()).date_naive());
+        let upcoming = || {
+            candidates
+                .iter()
+                .filter_map(|r| r.expiry)
+                .filter(|e| *e >= today)
+        };
+        match selector {
+            ExpirySelector::On(date) => Some(date),
+            ExpirySelector::Next => upcoming().min(),
+            ExpirySelector::NextMonthly => {
+                let next = upcoming().min()?;
+                upcoming()
+                    .filter(|e| e.year() == next.year() && e.month() == next.month())
+                    .max()
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Static IP failover planning.
+//!
+//! Order APIs only accept calls from the account's primary or secondary
+//! static IP, and each slot is locked for [`MODIFY_LOCK_DAYS`] days after it
+//! is changed. For primary/secondary server deployments, [`IpPlan`] tracks
+//! both slots and their locks and, when a server has to move to a new IP,
+//! says which slot can take it and whether that is a `set` or a `modify`.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use dhan_rs::ip::{IpAction, IpPlan};
+//! use dhan_rs::types::IpFlag;
+//!
+//! let today = NaiveDate::from_ymd_opt(2025, 1, 10).unwrap();
+//! let mut plan = IpPlan::new();
+//! plan.record_change(IpFlag::PRIMARY, "10.0.0.1", today);
+//!
+//! // The secondary slot is empty: the standby server can be added now.
+//! let action = plan.plan("10.0.0.2", Some("10.0.0.1"), today);
+//! assert_eq!(action, IpAction::Set(IpFlag::SECONDARY));
+//! plan.record_change(IpFlag::SECONDARY, "10.0.0.2", today);
+//!
+//! // Both slots were just changed: a third IP has to wait for the lock.
+//! let action = plan.plan("10.0.0.3", Some("10.0.0.2"), today);
+//! assert_eq!(
+//!     action,
+//!     IpAction::Locked { until: NaiveDate::from_ymd_opt(2025, 1, 17) }
+//! );
+//! ```
+
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::types::IpFlag;
+use crate::types::auth::{IpInfo, SetIpRequest};
+
+/// Days a slot stays locked after it is set or modified.
+pub const MODIFY_LOCK_DAYS: u64 = 7;
+
+/// One whitelisted IP slot.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpSlot {
+    /// The whitelisted IP, if the slot is in use.
+    pub ip: Option<String>,
+    /// First date the slot can be modified; `None` if it is not locked.
+    pub modifiable_from: Option<NaiveDate>,
+}
+
+impl IpSlot {
+    /// Returns `true` if the slot can be changed on `today`.
+    pub fn is_modifiable(&self, today: NaiveDate) -> bool {
+        self.modifiable_from.is_none_or(|from| from <= today)
+    }
+}
+
+/// What to do to whitelist a new IP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IpAction {
+    /// The IP is already whitelisted in this slot; nothing to do.
+    AlreadyWhitelisted(IpFlag),
+    /// The slot is empty: call `set_ip`.
+    Set(IpFlag),
+    /// The slot holds another IP and is unlocked: call `modify_ip`.
+    Modify(IpFlag),
+    /// No usable slot before `until`.
+    Locked {
+        /// Earliest date a usable slot unlocks, if any slot will.
+        until: Option<NaiveDate>,
+    },
+}
+
+impl IpAction {
+    /// The slot to change, for [`Set`](Self::Set) and [`Modify`](Self::Modify).
+    pub fn slot(&self) -> Option<IpFlag> {
+        match self {
+            Self::Set(slot) | Self::Modify(slot) => Some(*slot),
+            _ => None,
+        }
+    }
+}
+
+/// Both static IP slots and their modification locks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpPlan {
+    /// The primary slot.
+    pub primary: IpSlot,
+    /// The secondary slot.
+    pub secondary: IpSlot,
+}
+
+impl IpPlan {
+    /// Both slots empty and unlocked.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a plan from a `get_ip` response.
+    pub fn from_info(info: &IpInfo) -> Self {
+        let slot = |flag| IpSlot {
+            ip: info.ip(flag).map(str::to_owned),
+            modifiable_from: info.modify_date(flag),
+        };
+        Self {
+            primary: slot(IpFlag::PRIMARY),
+            secondary: slot(IpFlag::SECONDARY),
+        }
+    }
+
+    /// Fetch the current slots with [`DhanClient::get_ip`].
+    pub async fn fetch(client: &DhanClient) -> Result<Self> {
+        Ok(Self::from_info(&client.get_ip().await?))
+    }
+
+    /// The slot for `flag`.
+    pub fn slot(&self, flag: IpFlag) -> &IpSlot {
+        match flag {
+            IpFlag::PRIMARY => &self.primary,
+            IpFlag::SECONDARY => &self.secondary,
+        }
+    }
+
+    fn slot_mut(&mut self, flag: IpFlag) -> &mut IpSlot {
+        match flag {
+            IpFlag::PRIMARY => &mut self.primary,
+            IpFlag::SECONDARY => &mut self.secondary,
+        }
+    }
+
+    /// The slot `ip` is whitelisted in, if any.
+    pub fn slot_of(&self, ip: &str) -> Option<IpFlag> {
+        [IpFlag::PRIMARY, IpFlag::SECONDARY]
+            .into_iter()
+            .find(|flag| self.slot(*flag).ip.as_deref() == Some(ip))
+    }
+
+    /// Slots that can be changed on `today`.
+    pub fn modifiable_slots(&self, today: NaiveDate) -> Vec<IpFlag> {
+        [IpFlag::PRIMARY, IpFlag::SECONDARY]
+            .into_iter()
+            .filter(|flag| self.slot(*flag).is_modifiable(today))
+            .collect()
+    }
+
+    /// How to whitelist `new_ip` on `today`.
+    ///
+    /// A slot holding `keep` (the IP of the server that stays up) is never
+    /// chosen. Empty slots are preferred over replacing an IP, and primary
+    /// over secondary.
+    pub fn plan(&self, new_ip: &str, keep: Option<&str>, today: NaiveDate) -> IpAction {
+        if let Some(slot) = self.slot_of(new_ip) {
+            return IpAction::AlreadyWhitelisted(slot);
+        }
+        let candidates: Vec<IpFlag> = [IpFlag::PRIMARY, IpFlag::SECONDARY]
+            .into_iter()
+            .filter(|flag| keep.is_none() || self.slot(*flag).ip.as_deref() != keep)
+            .collect();
+        let usable = |empty: bool| {
+            candidates.iter().copied().find(|flag| {
+                let slot = self.slot(*flag);
+                slot.ip.is_none() == empty && slot.is_modifiable(today)
+            })
+        };
+        if let Some(flag) = usable(true) {
+            return IpAction::Set(flag);
+        }
+        if let Some(flag) = usable(false) {
+            return IpAction::Modify(flag);
+        }
+        IpAction::Locked {
+            until: candidates
+                .iter()
+                .filter_map(|flag| self.slot(*flag).modifiable_from)
+                .min(),
+        }
+    }
+
+    /// Record that `flag` was set to `ip` on `today`, locking it for
+    /// [`MODIFY_LOCK_DAYS`].
+    pub fn record_change(&mut self, flag: IpFlag, ip: impl Into<String>, today: NaiveDate) {
+        *self.slot_mut(flag) = IpSlot {
+            ip: Some(ip.into()),
+            modifiable_from: today.checked_add_days(Days::new(MODIFY_LOCK_DAYS)),
+        };
+    }
+
+    /// The request carrying out `action` for `new_ip`, if it needs one.
+    pub fn request(
+        action: IpAction,
+        client_id: impl Into<String>,
+        new_ip: impl Into<String>,
+    ) -> Option<SetIpRequest> {
+        Some(SetIpRequest {
+            dhan_client_id: client_id.into(),
+            ip: new_ip.into(),
+            ip_flag: action.slot()?,
+        })
+    }
+}
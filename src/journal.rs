@@ -0,0 +1,162 @@
+//! Append-only JSON-lines journal for state that must survive restarts.
+//!
+//! Each record is serialized as one JSON document per line and flushed
+//! immediately; [`Journal::with_sync`] also syncs it to disk. Readers skip
+//! blank lines and a torn final line (e.g. from a crash mid-write), and
+//! opening a journal for appending cuts such a line off first, so a journal
+//! can always be reopened and appended to.
+//!
+//! ```no_run
+//! use dhan_rs::journal::Journal;
+//!
+//! # fn main() -> dhan_rs::Result<()> {
+//! let mut journal = Journal::open("state.jsonl")?;
+//! journal.append(&serde_json::json!({ "event": "started" }))?;
+//!
+//! let records: Vec<serde_json::Value> = Journal::read("state.jsonl")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::error::Result;
+use crate::events::{EventBus, JournalEvent, SessionEvent};
+
+/// An append-only JSON-lines file.
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    events: Option<EventBus>,
+    sync: bool,
+}
+
+impl Journal {
+    /// Open (or create) the journal at `path` for appending.
+    ///
+    /// A final line without a newline is a torn write; it is truncated so
+    /// the next record starts on a line of its own.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        let torn = cut_torn_tail(&mut file)?;
+        if torn > 0 {
+            tracing::warn!(path = %path.display(), bytes = torn, "Truncated torn journal line");
+        }
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            events: None,
+            sync: false,
+        })
+    }
+
+    /// Publish every appended record on `bus`.
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
+    }
+
+    /// Sync every appended record to disk (`fsync`) before
+    /// [`append`](Self::append) returns, so it survives a power loss and
+    /// not just a process crash. Slower; off by default.
+    pub fn with_sync(mut self, sync: bool) -> Self {
+        self.sync = sync;
+        self
+    }
+
+    /// Path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one record and flush it to the OS, syncing it to disk too if
+    /// [`with_sync`](Self::with_sync) is set.
+    pub fn append<T: Serialize>(&mut self, record: &T) -> Result<()> {
+        let line = serde_json::to_string(record)?;
+        writeln!(self.writer, "{line}")?;
+        self.writer.flush()?;
+        if self.sync {
+            self.writer.get_ref().sync_data()?;
+        }
+        if let Some(bus) = &self.events {
+            bus.publish(SessionEvent::Journal(JournalEvent {
+                path: self.path.clone(),
+                record: serde_json::from_str(&line)?,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Read every record of the journal at `path`.
+    ///
+    /// A missing file yields no records. A malformed **last** line is treated
+    /// as a torn write and skipped; malformed lines elsewhere are errors.
+    pub fn read<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec<T>> {
+        Self::read_with_torn_tail(path).map(|(records, _)| records)
+    }
+
+    /// Like [`read`](Self::read), but also returns whether a torn last line
+    /// was skipped.
+    pub fn read_with_torn_tail<T: DeserializeOwned>(
+        path: impl AsRef<Path>,
+    ) -> Result<(Vec<T>, bool)> {
+        let contents = match std::fs::read_to_string(path.as_ref()) {
+            Ok(c) => c,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok((Vec::new(), false)),
+            Err(e) => return Err(e.into()),
+        };
+
+        let lines: Vec<&str> = contents.lines().filter(|l| !l.trim().is_empty()).collect();
+        let mut records = Vec::with_capacity(lines.len());
+        let mut torn = false;
+        for (i, line) in lines.iter().enumerate() {
+            match serde_json::from_str(line) {
+                Ok(record) => records.push(record),
+                Err(e) if i + 1 == lines.len() => {
+                    torn = true;
+                    tracing::warn!(
+                        path = %path.as_ref().display(),
+                        "Skipping torn journal line: {e}"
+                    );
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok((records, torn))
+    }
+}
+
+/// Truncate `file` after its last newline, returning how many bytes of a
+/// partial final line were removed.
+fn cut_torn_tail(file: &mut File) -> std::io::Result<u64> {
+    const CHUNK: u64 = 4096;
+    let len = file.metadata()?.len();
+    let mut end = len;
+    let mut buf = [0u8; CHUNK as usize];
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK);
+        let chunk = &mut buf[..(end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(chunk)?;
+        if let Some(i) = chunk.iter().rposition(|&b| b == b'\n') {
+            end = start + i as u64 + 1;
+            break;
+        }
+        end = start;
+    }
+    if end < len {
+        file.set_len(end)?;
+    }
+    Ok(len - end)
+}
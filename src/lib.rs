@@ -42,25 +42,16 @@
 //!     let client = DhanClient::new("your-client-id", "your-access-token");
 //!
 //!     // Place an order
-//!     let req = PlaceOrderRequest {
-//!         dhan_client_id: "your-client-id".into(),
-//!         correlation_id: None,
-//!         transaction_type: TransactionType::BUY,
-//!         exchange_segment: ExchangeSegment::NSE_EQ,
-//!         product_type: ProductType::INTRADAY,
-//!         order_type: OrderType::LIMIT,
-//!         validity: Validity::DAY,
-//!         security_id: "1333".into(),
-//!         quantity: 1,
-//!         price: Some(1500.0),
-//!         disclosed_quantity: None,
-//!         trigger_price: None,
-//!         after_market_order: None,
-//!         amo_time: None,
-//!         bo_profit_value: None,
-//!         bo_stop_loss_value: None,
-//!     };
-//!     let response = client.place_order(&req).await?;
+//!     let req = PlaceOrderRequest::builder()
+//!         .transaction_type(TransactionType::BUY)
+//!         .exchange_segment(ExchangeSegment::NSE_EQ)
+//!         .product_type(ProductType::INTRADAY)
+//!         .order_type(OrderType::LIMIT)
+//!         .validity(Validity::DAY)
+//!         .security_id("1333")
+//!         .quantity(1)
+//!         .price(1500.0);
+//!     let response = client.place_order_for_self(req).await?;
 //!     println!("Order placed: {:?}", response);
 //!
 //!     // Fetch holdings
@@ -122,24 +113,90 @@
 //! - [`types`] — Request/response structs and shared enums
 //! - [`api`] — REST endpoint implementations (methods on `DhanClient`)
 //! - [`ws`] — WebSocket streaming (market feed + order updates)
+//! - [`runtime`] — Strategy trait and runner wiring feeds, orders and risk
+//! - [`risk`] — Client-side pre-trade risk checks and trading halt
+//! - [`batch`] — `BatchReport` of succeeded outputs and failed inputs for batch operations
+//! - [`broker`] — `Broker` trait over the live API and a paper-trading simulation
+//! - [`budget`] — Daily/hourly order request budget with warnings and a hard stop
+//! - [`candles`] — Live OHLCV candle aggregation and persisted history
+//! - [`cache`] — Shared latest-quote cache with REST fallback
+//! - [`circuit`] — Circuit breaker failing data calls fast during API outages
+//! - [`analytics`] — Profiles, rolling statistics, spreads, OI and market breadth
+//! - [`portfolio`] — Book-level position views (netting by underlying, live P&L)
+//! - [`oms`] — Client-side order management (tracker, local OCO, expiry, chains)
+//! - [`instruments`] — Scrip master download with symbol, ISIN and ID lookups
+//! - [`ip`] — Static IP slot planning for primary/secondary failover
+//! - [`audit`] — SHA-256 hash chain of sent order requests, with verification
+//! - [`diff`] — Added/removed/changed sets between order and position snapshots
+//! - [`bridge`] — Re-broadcasting ticks to other machines over UDP multicast
+//! - [`dns`] — Pinned, periodically refreshed addresses for the Dhan hosts
+//! - [`events`] — One filtered event bus for orders, feed, risk and journal events
+//! - [`journal`] — Append-only JSON-lines journal for restart-safe state
+//! - [`scope`] — Capability scopes for handing out limited client handles
+//! - [`scheduler`] — IST time-of-day jobs (portfolio snapshots, square-off)
+//! - [`transport`] — Replaceable HTTP layer with a canned-response mock for tests
+//! - [`time`] — Normalizing feed, REST and order timestamps to UTC/IST
+//! - [`clock`] — Local clock skew against server and exchange time
+//! - [`vault`] — Per-user token storage and renewal for partner integrations
+//! - [`token`] — Unattended daily access token refresh (TOTP, consent, renewal)
+//! - `notify` — Alert sinks for Slack, Telegram and webhooks (feature `notify`)
+//! - `decimal` — Exact `Decimal` views of prices and amounts (feature `decimal`)
 //!
 //! ## Feature Flags
 //!
-//! This crate currently has no optional feature flags. All functionality is
-//! included by default.
+//! | Feature   | Description                                              |
+//! |-----------|----------------------------------------------------------|
+//! | `notify`  | `notify` module: alert sinks for Slack/Telegram/webhooks |
+//! | `control` | `runtime::control`: HTTP control server for sessions     |
+//! | `cli`     | Builds the `ws_check` and `audit_verify` binaries        |
+//! | `bincode` | `ws::encode::BincodeEncoder` for feed events             |
+//! | `shm`     | `ws::shm`: shared-memory tick ring for local readers     |
+//! | `decimal` | `decimal`: `rust_decimal` prices, tick rounding, serde   |
+//!
+//! Everything else is included by default.
 
 #![warn(missing_docs)]
 #![allow(clippy::doc_markdown)]
 #![doc(html_root_url = "https://docs.rs/dhan-rs/0.1.6")]
 
+pub mod analytics;
 pub mod api;
+pub mod audit;
+pub mod batch;
+pub mod bridge;
+pub mod broker;
+pub mod budget;
+pub mod cache;
+pub mod candles;
+pub mod circuit;
 pub mod client;
+pub mod clock;
 pub mod constants;
+#[cfg(feature = "decimal")]
+pub mod decimal;
+pub mod diff;
+pub mod dns;
 pub mod error;
+pub mod events;
+pub mod instruments;
+pub mod ip;
+pub mod journal;
+#[cfg(feature = "notify")]
+pub mod notify;
+pub mod oms;
+pub mod portfolio;
+pub mod risk;
+pub mod runtime;
+pub mod scheduler;
+pub mod scope;
+pub mod time;
+pub mod token;
+pub mod transport;
 pub mod types;
+pub mod vault;
 pub mod ws;
 
-/// Re-export the main client type at crate root for convenience.
-pub use client::DhanClient;
+/// Re-export the main client types at crate root for convenience.
+pub use client::{DhanClient, DhanClientBuilder};
 /// Re-export the error type and Result alias.
 pub use error::{DhanError, Result};
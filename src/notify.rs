@@ -0,0 +1,426 @@
+//! Alert delivery to chat and webhook endpoints (feature `notify`).
+//!
+//! A [`Notifier`] fans an [`Alert`] out to every configured [`AlertSink`].
+//! Built-in sinks cover Slack incoming webhooks, Telegram bots and generic
+//! JSON webhooks; custom sinks implement the trait directly.
+//!
+//! Sinks can be configured declaratively from any serde format:
+//!
+//! ```no_run
+//! use dhan_rs::notify::{Alert, AlertKind, Notifier, NotifyConfig, Severity};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let config: NotifyConfig = serde_json::from_str(r#"{
+//!     "min_severity": "warning",
+//!     "sinks": [
+//!         { "type": "slack", "webhook_url": "https://hooks.slack.com/services/..." },
+//!         { "type": "telegram", "bot_token": "123:abc", "chat_id": "-100123" }
+//!     ]
+//! }"#)?;
+//! let notifier = Notifier::from_config(&config);
+//!
+//! notifier
+//!     .notify(&Alert::new(Severity::Critical, AlertKind::KillSwitch, "Kill switch activated"))
+//!     .await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use futures_util::future::{BoxFuture, join_all};
+use serde::{Deserialize, Serialize};
+
+use crate::batch::BatchReport;
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::scheduler::ist;
+use crate::ws::order_update::OrderUpdate;
+
+/// Base URL of the Telegram Bot API.
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// Overall deadline for one sink request.
+const SINK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Deadline for establishing the sink's connection.
+const SINK_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ---------------------------------------------------------------------------
+// Alerts
+// ---------------------------------------------------------------------------
+
+/// How urgent an alert is. Ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Informational.
+    Info,
+    /// Needs attention.
+    Warning,
+    /// Needs immediate action.
+    Critical,
+}
+
+/// What an alert is about.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    /// A risk limit was hit or approached.
+    Risk,
+    /// An order was rejected by the broker or exchange.
+    OrderRejected,
+    /// A WebSocket could not be re-established.
+    ReconnectFailed,
+    /// The kill switch was activated.
+    KillSwitch,
+    /// Anything else.
+    Custom(String),
+}
+
+/// A message to deliver to the configured sinks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    /// Urgency.
+    pub severity: Severity,
+    /// Category.
+    pub kind: AlertKind,
+    /// Human-readable message.
+    pub message: String,
+    /// When the alert was raised (IST).
+    pub raised_at: DateTime<FixedOffset>,
+}
+
+impl Alert {
+    /// Create an alert raised now.
+    pub fn new(severity: Severity, kind: AlertKind, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            kind,
+            message: message.into(),
+            raised_at: Utc::now().with_timezone(&ist()),
+        }
+    }
+
+    /// Alert for a rejected order.
+    pub fn order_rejected(update: &OrderUpdate) -> Self {
+        Self::new(
+            Severity::Warning,
+            AlertKind::OrderRejected,
+            format!(
+                "Order {} ({}) rejected: {}",
+                update.order_id.as_deref().unwrap_or("?"),
+                update
+                    .symbol
+                    .as_deref()
+                    .or(update.security_id.as_deref())
+                    .unwrap_or("?"),
+                update
+                    .reason_description
+                    .as_deref()
+                    .unwrap_or("no reason given"),
+            ),
+        )
+    }
+
+    /// One-line text rendering used by the chat sinks.
+    pub fn to_text(&self) -> String {
+        let kind = match &self.kind {
+            AlertKind::Risk => "risk",
+            AlertKind::OrderRejected => "order rejected",
+            AlertKind::ReconnectFailed => "reconnect failed",
+            AlertKind::KillSwitch => "kill switch",
+            AlertKind::Custom(k) => k.as_str(),
+        };
+        format!(
+            "[{:?}] {kind}: {} ({})",
+            self.severity,
+            self.message,
+            self.raised_at.format("%Y-%m-%d %H:%M:%S")
+        )
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Sinks
+// ---------------------------------------------------------------------------
+
+/// A destination for alerts.
+pub trait AlertSink: Send + Sync {
+    /// Short name used in logs.
+    fn name(&self) -> &str;
+
+    /// Deliver one alert.
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>>;
+}
+
+/// HTTP client shared by the built-in sinks, bounded so a hung endpoint
+/// cannot stall [`Notifier::notify`].
+fn sink_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(SINK_TIMEOUT)
+        .connect_timeout(SINK_CONNECT_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+/// POST `body` as JSON and map non-success statuses to errors.
+///
+/// Transport errors have their URL stripped: sink URLs carry secrets
+/// (the Telegram bot token, Slack webhook keys) and would otherwise end
+/// up in logs and reports.
+async fn post_json(http: &reqwest::Client, url: &str, body: &serde_json::Value) -> Result<()> {
+    let resp = http
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .map_err(|err| DhanError::from(err.without_url()))?;
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = resp.text().await.unwrap_or_default();
+    Err(DhanError::HttpStatus { status, body })
+}
+
+/// Posts `{"text": ...}` to a Slack incoming webhook.
+#[derive(Debug, Clone)]
+pub struct SlackSink {
+    http: reqwest::Client,
+    webhook_url: String,
+}
+
+impl SlackSink {
+    /// Create a sink for the given incoming-webhook URL.
+    pub fn new(webhook_url: impl Into<String>) -> Self {
+        Self {
+            http: sink_client(),
+            webhook_url: webhook_url.into(),
+        }
+    }
+}
+
+impl AlertSink for SlackSink {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let body = serde_json::json!({ "text": alert.to_text() });
+            post_json(&self.http, &self.webhook_url, &body).await
+        })
+    }
+}
+
+/// Sends messages through a Telegram bot.
+#[derive(Debug, Clone)]
+pub struct TelegramSink {
+    http: reqwest::Client,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramSink {
+    /// Create a sink posting to `chat_id` as the bot identified by `bot_token`.
+    pub fn new(bot_token: impl Into<String>, chat_id: impl Into<String>) -> Self {
+        Self {
+            http: sink_client(),
+            bot_token: bot_token.into(),
+            chat_id: chat_id.into(),
+        }
+    }
+}
+
+impl AlertSink for TelegramSink {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let url = format!("{TELEGRAM_API_URL}/bot{}/sendMessage", self.bot_token);
+            let body = serde_json::json!({ "chat_id": self.chat_id, "text": alert.to_text() });
+            post_json(&self.http, &url, &body).await
+        })
+    }
+}
+
+/// POSTs the [`Alert`] itself as JSON to an arbitrary URL.
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl WebhookSink {
+    /// Create a sink for `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            http: sink_client(),
+            url: url.into(),
+        }
+    }
+}
+
+impl AlertSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let body = serde_json::to_value(alert)?;
+            post_json(&self.http, &self.url, &body).await
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// Declarative configuration of a built-in sink.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// A Slack incoming webhook.
+    Slack {
+        /// Incoming-webhook URL.
+        webhook_url: String,
+    },
+    /// A Telegram bot chat.
+    Telegram {
+        /// Bot token from BotFather.
+        bot_token: String,
+        /// Target chat ID.
+        chat_id: String,
+    },
+    /// A generic JSON webhook.
+    Webhook {
+        /// Target URL.
+        url: String,
+    },
+}
+
+/// Declarative configuration of a [`Notifier`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    /// Alerts below this severity are dropped (default `info`).
+    #[serde(default = "default_min_severity")]
+    pub min_severity: Severity,
+    /// Sinks to deliver to.
+    #[serde(default)]
+    pub sinks: Vec<SinkConfig>,
+}
+
+fn default_min_severity() -> Severity {
+    Severity::Info
+}
+
+// ---------------------------------------------------------------------------
+// Notifier
+// ---------------------------------------------------------------------------
+
+/// Fans alerts out to a set of sinks.
+#[derive(Default)]
+pub struct Notifier {
+    sinks: Vec<Box<dyn AlertSink>>,
+    min_severity: Option<Severity>,
+}
+
+impl std::fmt::Debug for Notifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Notifier")
+            .field(
+                "sinks",
+                &self.sinks.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            )
+            .field("min_severity", &self.min_severity)
+            .finish()
+    }
+}
+
+impl Notifier {
+    /// Create a notifier with no sinks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the sinks described by `config`.
+    pub fn from_config(config: &NotifyConfig) -> Self {
+        let mut notifier = Self::new().with_min_severity(config.min_severity);
+        for sink in &config.sinks {
+            notifier = match sink {
+                SinkConfig::Slack { webhook_url } => {
+                    notifier.with_sink(SlackSink::new(webhook_url))
+                }
+                SinkConfig::Telegram { bot_token, chat_id } => {
+                    notifier.with_sink(TelegramSink::new(bot_token, chat_id))
+                }
+                SinkConfig::Webhook { url } => notifier.with_sink(WebhookSink::new(url)),
+            };
+        }
+        notifier
+    }
+
+    /// Add a sink.
+    pub fn with_sink(mut self, sink: impl AlertSink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// Drop alerts below `severity`.
+    pub fn with_min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = Some(severity);
+        self
+    }
+
+    /// Number of configured sinks.
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    /// Returns `true` if no sinks are configured.
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Deliver `alert` to every sink concurrently.
+    ///
+    /// Returns the names of the sinks that took the alert and, for the
+    /// ones that failed, their names with the errors. Failures are logged
+    /// and never prevent delivery to the other sinks.
+    pub async fn notify(&self, alert: &Alert) -> BatchReport<String, String> {
+        if self.min_severity.is_some_and(|min| alert.severity < min) {
+            return BatchReport::new();
+        }
+        let results = join_all(self.sinks.iter().map(|s| s.send(alert))).await;
+        self.sinks
+            .iter()
+            .zip(results)
+            .map(|(sink, result)| {
+                if let Err(err) = &result {
+                    tracing::warn!(sink = sink.name(), "Alert delivery failed: {err}");
+                }
+                let name = sink.name().to_owned();
+                (name.clone(), result.map(|()| name))
+            })
+            .collect()
+    }
+
+    /// Activate the kill switch through `client` and raise a critical alert.
+    pub async fn activate_kill_switch(&self, client: &DhanClient, reason: &str) -> Result<()> {
+        client.manage_kill_switch("ACTIVATE").await?;
+        self.notify(&Alert::new(
+            Severity::Critical,
+            AlertKind::KillSwitch,
+            format!("Kill switch activated: {reason}"),
+        ))
+        .await;
+        Ok(())
+    }
+}
@@ -0,0 +1,358 @@
+//! Bulk modification of working orders.
+//!
+//! [`BulkModifier::modify_orders_where`] re-prices every working order that
+//! matches an [`OrderFilter`] — e.g. chase the market by one tick or shift
+//! all stop-losses by a percentage — pacing the calls to the order rate
+//! limit and refusing to exceed Dhan's per-order modification cap.
+//!
+//! Modification counts are kept by the modifier, so reuse one instance for
+//! the whole session; modifications made elsewhere are not counted.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::batch::BatchReport;
+use crate::client::DhanClient;
+use crate::constants::rate_limits::orders::{MAX_MODIFICATIONS_PER_ORDER, PER_SECOND};
+use crate::error::Result;
+use crate::types::enums::*;
+use crate::types::orders::{ModifyOrderRequest, OrderDetail, OrderResponse};
+
+/// Default price tick used to round adjusted prices.
+pub const DEFAULT_TICK_SIZE: f64 = 0.05;
+
+// ---------------------------------------------------------------------------
+// Filter
+// ---------------------------------------------------------------------------
+
+/// Selects working orders from the order book.
+///
+/// An empty filter matches every working order; each `with_*` call narrows
+/// it further. Used by [`BulkModifier::modify_orders_where`] and
+/// [`DhanClient::cancel_all_orders`].
+#[derive(Debug, Clone, Default)]
+pub struct OrderFilter {
+    security_id: Option<String>,
+    exchange_segment: Option<ExchangeSegment>,
+    product_type: Option<ProductType>,
+    status: Option<OrderStatus>,
+    transaction_type: Option<TransactionType>,
+    order_type: Option<OrderType>,
+    correlation_prefix: Option<String>,
+}
+
+impl OrderFilter {
+    /// Match every working order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only orders for `security_id`.
+    pub fn with_security_id(mut self, security_id: impl Into<String>) -> Self {
+        self.security_id = Some(security_id.into());
+        self
+    }
+
+    /// Only orders on `segment`.
+    pub fn with_segment(mut self, segment: ExchangeSegment) -> Self {
+        self.exchange_segment = Some(segment);
+        self
+    }
+
+    /// Only orders of `product_type`.
+    pub fn with_product_type(mut self, product_type: ProductType) -> Self {
+        self.product_type = Some(product_type);
+        self
+    }
+
+    /// Only orders in working state `status` (e.g. `PENDING` to leave
+    /// partially filled orders alone).
+    pub fn with_status(mut self, status: OrderStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only buy or only sell orders.
+    pub fn with_transaction_type(mut self, side: TransactionType) -> Self {
+        self.transaction_type = Some(side);
+        self
+    }
+
+    /// Only orders of `order_type` (e.g. `STOP_LOSS` to move all SLs).
+    pub fn with_order_type(mut self, order_type: OrderType) -> Self {
+        self.order_type = Some(order_type);
+        self
+    }
+
+    /// Only orders whose correlation ID starts with `prefix`.
+    pub fn with_correlation_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.correlation_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Returns `true` if `order` is working and matches every criterion.
+    pub fn matches(&self, order: &OrderDetail) -> bool {
+        let Some(status) = order.status().filter(|s| s.is_working()) else {
+            return false;
+        };
+        if self.status.is_some_and(|s| s != status) {
+            return false;
+        }
+        if let Some(id) = &self.security_id {
+            if order.security_id.as_deref() != Some(id.as_str()) {
+                return false;
+            }
+        }
+        if let Some(segment) = self.exchange_segment {
+            if order.exchange_segment.as_deref() != Some(segment.as_str()) {
+                return false;
+            }
+        }
+        if let Some(product_type) = self.product_type {
+            let parsed = order
+                .product_type
+                .as_deref()
+                .and_then(ProductType::from_order_update_code);
+            if parsed != Some(product_type) {
+                return false;
+            }
+        }
+        if let Some(side) = self.transaction_type {
+            let parsed = order
+                .transaction_type
+                .as_deref()
+                .and_then(TransactionType::from_order_update_code);
+            if parsed != Some(side) {
+                return false;
+            }
+        }
+        if let Some(order_type) = self.order_type {
+            if order_type_of(order) != Some(order_type) {
+                return false;
+            }
+        }
+        if let Some(prefix) = &self.correlation_prefix {
+            if !order
+                .correlation_id
+                .as_deref()
+                .is_some_and(|c| c.starts_with(prefix.as_str()))
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+fn order_type_of(order: &OrderDetail) -> Option<OrderType> {
+    order
+        .order_type
+        .as_deref()
+        .and_then(OrderType::from_order_update_code)
+}
+
+// ---------------------------------------------------------------------------
+// Price adjustment
+// ---------------------------------------------------------------------------
+
+/// How to move an order's price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PriceAdjustment {
+    /// Add an absolute amount (negative to lower).
+    Offset(f64),
+    /// Move by a percentage of the current price (`-1.0` = 1% lower).
+    Percent(f64),
+    /// Set an absolute price.
+    To(f64),
+}
+
+impl PriceAdjustment {
+    /// Apply the adjustment to `price`, rounded to `tick_size`.
+    pub fn apply(self, price: f64, tick_size: f64) -> f64 {
+        let raw = match self {
+            Self::Offset(delta) => price + delta,
+            Self::Percent(pct) => price * (1.0 + pct / 100.0),
+            Self::To(target) => target,
+        };
+        if tick_size > 0.0 {
+            let ticks = (raw / tick_size).round();
+            // Trim float noise such as 1500.0500000001 at the tick's own
+            // precision, so sub-paisa ticks keep their last digits.
+            let scale = 10f64.powi(tick_decimals(tick_size));
+            (ticks * tick_size * scale).round() / scale
+        } else {
+            raw
+        }
+    }
+}
+
+/// Number of decimal places in `tick_size`, e.g. 2 for `0.05` and 4 for
+/// `0.0025`.
+fn tick_decimals(tick_size: f64) -> i32 {
+    (0..10)
+        .find(|&d| {
+            let scaled = tick_size * 10f64.powi(d);
+            (scaled - scaled.round()).abs() < 1e-9
+        })
+        .unwrap_or(10)
+}
+
+// ---------------------------------------------------------------------------
+// Results
+// ---------------------------------------------------------------------------
+
+/// What happened to one matching order.
+#[derive(Debug)]
+pub enum ModifyOutcome {
+    /// The order was modified.
+    Modified(OrderResponse),
+    /// The order already used its modification allowance.
+    CapReached,
+    /// The adjusted prices equal the current ones (or the order has no
+    /// adjustable price, e.g. a market order).
+    Unchanged,
+}
+
+/// Result for one order that [`BulkModifier::modify_orders_where`] did not
+/// fail to modify.
+#[derive(Debug)]
+pub struct BulkModifyResult {
+    /// Dhan order ID.
+    pub order_id: String,
+    /// New limit price, if the order has one.
+    pub price: Option<f64>,
+    /// New trigger price, if the order has one.
+    pub trigger_price: Option<f64>,
+    /// What happened.
+    pub outcome: ModifyOutcome,
+}
+
+// ---------------------------------------------------------------------------
+// Modifier
+// ---------------------------------------------------------------------------
+
+/// Re-prices matching working orders, tracking modifications per order.
+#[derive(Debug, Clone)]
+pub struct BulkModifier {
+    client: DhanClient,
+    counts: HashMap<String, u32>,
+    pacing: Duration,
+    tick_size: f64,
+}
+
+impl BulkModifier {
+    /// Modify orders through `client`, paced to the order rate limit.
+    pub fn new(client: DhanClient) -> Self {
+        Self {
+            client,
+            counts: HashMap::new(),
+            pacing: Duration::from_secs(1) / PER_SECOND,
+            tick_size: DEFAULT_TICK_SIZE,
+        }
+    }
+
+    /// Wait `pacing` between modify calls.
+    pub fn with_pacing(mut self, pacing: Duration) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    /// Round adjusted prices to `tick_size`.
+    pub fn with_tick_size(mut self, tick_size: f64) -> Self {
+        self.tick_size = tick_size;
+        self
+    }
+
+    /// Modifications made to `order_id` through this modifier.
+    pub fn modifications(&self, order_id: &str) -> u32 {
+        self.counts.get(order_id).copied().unwrap_or(0)
+    }
+
+    /// Modifications left before `order_id` reaches the exchange cap.
+    pub fn remaining_modifications(&self, order_id: &str) -> u32 {
+        MAX_MODIFICATIONS_PER_ORDER.saturating_sub(self.modifications(order_id))
+    }
+
+    /// Apply `adjustment` to every working order matching `filter`.
+    ///
+    /// Limit prices move for `LIMIT` and `STOP_LOSS` orders, trigger prices
+    /// for `STOP_LOSS` and `STOP_LOSS_MARKET` orders. Fails only if the
+    /// order book cannot be fetched; orders whose modify call failed are in
+    /// the report's `failed`, keyed by the order.
+    pub async fn modify_orders_where(
+        &mut self,
+        filter: &OrderFilter,
+        adjustment: PriceAdjustment,
+    ) -> Result<BatchReport<BulkModifyResult, OrderDetail>> {
+        let orders = self.client.get_orders().await?;
+        let mut report = BatchReport::new();
+        let mut first = true;
+
+        for order in orders.into_iter().filter(|o| filter.matches(o)) {
+            let Some(order_id) = order.order_id.clone() else {
+                continue;
+            };
+            let order_type = order_type_of(&order);
+            let adjust = |p: Option<f64>| {
+                p.filter(|p| *p > 0.0)
+                    .map(|p| adjustment.apply(p, self.tick_size))
+            };
+            let price = match order_type {
+                Some(OrderType::LIMIT | OrderType::STOP_LOSS) => adjust(order.price),
+                _ => None,
+            };
+            let trigger_price = match order_type {
+                Some(OrderType::STOP_LOSS | OrderType::STOP_LOSS_MARKET) => {
+                    adjust(order.trigger_price)
+                }
+                _ => None,
+            };
+
+            let changed = price.is_some_and(|p| Some(p) != order.price)
+                || trigger_price.is_some_and(|t| Some(t) != order.trigger_price);
+            let outcome = if !changed {
+                Ok(ModifyOutcome::Unchanged)
+            } else if self.remaining_modifications(&order_id) == 0 {
+                tracing::warn!(order_id, "Modification cap reached; skipping");
+                Ok(ModifyOutcome::CapReached)
+            } else {
+                if !first {
+                    tokio::time::sleep(self.pacing).await;
+                }
+                first = false;
+                match self.modify(&order, &order_id, price, trigger_price).await {
+                    Ok(resp) => {
+                        *self.counts.entry(order_id.clone()).or_default() += 1;
+                        Ok(ModifyOutcome::Modified(resp))
+                    }
+                    Err(e) => {
+                        tracing::warn!(order_id, "Bulk modify failed: {e}");
+                        Err(e)
+                    }
+                }
+            };
+
+            let result = outcome.map(|outcome| BulkModifyResult {
+                order_id,
+                price,
+                trigger_price,
+                outcome,
+            });
+            report.push(order, result);
+        }
+        Ok(report)
+    }
+
+    async fn modify(
+        &self,
+        order: &OrderDetail,
+        order_id: &str,
+        price: Option<f64>,
+        trigger_price: Option<f64>,
+    ) -> Result<OrderResponse> {
+        let mut req = ModifyOrderRequest::from_order(self.client.client_id(), order)?;
+        req.price = price.or(req.price);
+        req.trigger_price = trigger_price.or(req.trigger_price);
+        self.client.modify_order(order_id, &req).await
+    }
+}
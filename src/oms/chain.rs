@@ -0,0 +1,440 @@
+//! Conditional order-on-fill chaining.
+//!
+//! [`OrderChains`] holds follow-up orders keyed by a parent order ID. When the
+//! [`OrderTracker`](super::tracker::OrderTracker) reports the parent as fully
+//! traded, the follow-ups are placed (e.g. the stop-loss for a fresh entry).
+//!
+//! Every change is written to a [`Journal`], so armed chains survive a
+//! restart. Fills that happened while the process was down are picked up by
+//! [`OrderChains::reconcile`].
+//!
+//! A follow-up is placed with a correlation ID derived from its chain (unless
+//! it has one already), and the intent is journaled first. A chain found
+//! mid-placement after a restart is looked up by that ID and only placed
+//! if the exchange never received it, so a crash cannot place it twice.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::oms::chain::OrderChains;
+//! use dhan_rs::oms::tracker::OrderTracker;
+//! use dhan_rs::ws::order_update::OrderUpdateStream;
+//! use futures_util::StreamExt;
+//! # use dhan_rs::types::orders::PlaceOrderRequest;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! # let stop_loss: PlaceOrderRequest = todo!();
+//! let client = DhanClient::new("client-id", "token");
+//! let mut chains = OrderChains::open(client, "chains.jsonl")?;
+//! chains.reconcile().await;
+//! chains.on_fill("entry-order-id", stop_loss)?;
+//!
+//! let mut tracker = OrderTracker::new();
+//! let mut updates = OrderUpdateStream::connect("client-id", "token").await?;
+//! while let Some(msg) = updates.next().await {
+//!     for event in tracker.apply(&msg?.into_update()) {
+//!         for chained in chains.handle(&event).await {
+//!             println!("{chained:?}");
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::journal::Journal;
+use crate::oms::tags::MAX_CORRELATION_ID_LEN;
+use crate::oms::tracker::TrackerEvent;
+use crate::types::enums::OrderStatus;
+use crate::types::orders::{OrderResponse, PlaceOrderRequest};
+
+/// Journal record for a chain's lifecycle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum ChainRecord {
+    Armed {
+        chain_id: u64,
+        parent_order_id: String,
+        request: PlaceOrderRequest,
+    },
+    Firing {
+        chain_id: u64,
+        correlation_id: String,
+    },
+    Fired {
+        chain_id: u64,
+        order_id: String,
+    },
+    Dropped {
+        chain_id: u64,
+        status: OrderStatus,
+    },
+    Failed {
+        chain_id: u64,
+        error: String,
+    },
+}
+
+/// An armed follow-up order.
+#[derive(Debug, Clone)]
+pub struct PendingChain {
+    /// Journal-assigned chain ID.
+    pub chain_id: u64,
+    /// The order whose fill triggers the follow-up.
+    pub parent_order_id: String,
+    /// The follow-up order to place.
+    pub request: PlaceOrderRequest,
+}
+
+/// Outcome of a chain being resolved.
+#[derive(Debug, Clone)]
+pub enum ChainEvent {
+    /// The parent filled and the follow-up was placed.
+    Fired {
+        /// Chain ID.
+        chain_id: u64,
+        /// The parent order.
+        parent_order_id: String,
+        /// Placement response of the follow-up order.
+        response: OrderResponse,
+    },
+    /// The parent ended without filling; the follow-up was discarded.
+    Dropped {
+        /// Chain ID.
+        chain_id: u64,
+        /// The parent order.
+        parent_order_id: String,
+        /// Terminal status of the parent.
+        status: OrderStatus,
+    },
+    /// Placing the follow-up failed. The chain is not retried.
+    Failed {
+        /// Chain ID.
+        chain_id: u64,
+        /// The parent order.
+        parent_order_id: String,
+        /// Error returned by the placement call.
+        error: String,
+    },
+}
+
+/// Journal-backed follow-up orders triggered by fills.
+#[derive(Debug)]
+pub struct OrderChains {
+    client: DhanClient,
+    journal: Journal,
+    next_id: u64,
+    pending: BTreeMap<u64, PendingChain>,
+    /// Correlation IDs of chains whose placement was started.
+    firing: BTreeMap<u64, String>,
+}
+
+impl OrderChains {
+    /// Open the chain journal at `path`, restoring chains that were armed
+    /// but not yet resolved.
+    pub fn open(client: DhanClient, path: impl AsRef<Path>) -> Result<Self> {
+        let mut pending = BTreeMap::new();
+        let mut firing = BTreeMap::new();
+        let mut next_id = 1;
+        for record in Journal::read::<ChainRecord>(path.as_ref())? {
+            match record {
+                ChainRecord::Armed {
+                    chain_id,
+                    parent_order_id,
+                    request,
+                } => {
+                    next_id = next_id.max(chain_id + 1);
+                    pending.insert(
+                        chain_id,
+                        PendingChain {
+                            chain_id,
+                            parent_order_id,
+                            request,
+                        },
+                    );
+                }
+                ChainRecord::Firing {
+                    chain_id,
+                    correlation_id,
+                } => {
+                    firing.insert(chain_id, correlation_id);
+                }
+                ChainRecord::Fired { chain_id, .. }
+                | ChainRecord::Dropped { chain_id, .. }
+                | ChainRecord::Failed { chain_id, .. } => {
+                    pending.remove(&chain_id);
+                    firing.remove(&chain_id);
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            tracing::info!(count = pending.len(), "Restored pending order chains");
+        }
+        Ok(Self {
+            client,
+            journal: Journal::open(path)?,
+            next_id,
+            pending,
+            firing,
+        })
+    }
+
+    /// Arm `next` to be placed once `order_id` is fully traded.
+    ///
+    /// Several follow-ups may be attached to the same parent. Returns the
+    /// chain ID.
+    pub fn on_fill(&mut self, order_id: &str, next: PlaceOrderRequest) -> Result<u64> {
+        let chain_id = self.next_id;
+        self.journal.append(&ChainRecord::Armed {
+            chain_id,
+            parent_order_id: order_id.to_owned(),
+            request: next.clone(),
+        })?;
+        self.next_id += 1;
+        self.pending.insert(
+            chain_id,
+            PendingChain {
+                chain_id,
+                parent_order_id: order_id.to_owned(),
+                request: next,
+            },
+        );
+        Ok(chain_id)
+    }
+
+    /// Chains that have not been resolved yet, in arming order.
+    pub fn pending(&self) -> impl Iterator<Item = &PendingChain> {
+        self.pending.values()
+    }
+
+    /// React to a tracker event: fire follow-ups of filled parents and drop
+    /// those of parents that ended without filling.
+    pub async fn handle(&mut self, event: &TrackerEvent) -> Vec<ChainEvent> {
+        match event {
+            TrackerEvent::Filled { order_id, .. } => self.fire(order_id).await,
+            TrackerEvent::StatusChanged { order_id, to, .. }
+                if to.is_terminal() && *to != OrderStatus::TRADED =>
+            {
+                self.drop_chains(order_id, *to)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Check every pending parent through the REST API and resolve chains
+    /// whose parent filled or ended while no updates were being received.
+    pub async fn reconcile(&mut self) -> Vec<ChainEvent> {
+        let parents: BTreeSet<String> = self
+            .pending
+            .values()
+            .map(|c| c.parent_order_id.clone())
+            .collect();
+
+        let mut events = Vec::new();
+        for parent in parents {
+            let status = match self.client.get_order(&parent).await {
+                Ok(detail) => detail.status(),
+                Err(e) => {
+                    tracing::warn!(parent, "Failed to reconcile order chain: {e}");
+                    continue;
+                }
+            };
+            match status {
+                Some(OrderStatus::TRADED) => events.extend(self.fire(&parent).await),
+                Some(s) if s.is_terminal() => events.extend(self.drop_chains(&parent, s)),
+                _ => {}
+            }
+        }
+        events
+    }
+
+    /// Remove and return the pending chains of `parent`.
+    fn take_chains(&mut self, parent: &str) -> Vec<PendingChain> {
+        let ids: Vec<u64> = self
+            .pending
+            .values()
+            .filter(|c| c.parent_order_id == parent)
+            .map(|c| c.chain_id)
+            .collect();
+        ids.iter()
+            .filter_map(|id| self.pending.remove(id))
+            .collect()
+    }
+
+    /// Place every follow-up attached to `parent`.
+    async fn fire(&mut self, parent: &str) -> Vec<ChainEvent> {
+        let mut events = Vec::new();
+        for chain in self.take_chains(parent) {
+            let result = match self.firing.get(&chain.chain_id).cloned() {
+                Some(correlation_id) => match self.recover(&correlation_id).await {
+                    Ok(Some(response)) => Ok(response),
+                    Ok(None) => self.place(&chain, correlation_id).await,
+                    Err(e) => {
+                        // Unknown whether it was placed: retry on the next
+                        // reconcile rather than risk a second order.
+                        tracing::warn!(
+                            chain_id = chain.chain_id,
+                            parent,
+                            "Could not look up chained order: {e}"
+                        );
+                        self.pending.insert(chain.chain_id, chain);
+                        continue;
+                    }
+                },
+                None => {
+                    let correlation_id = chain
+                        .request
+                        .correlation_id
+                        .clone()
+                        .unwrap_or_else(|| chain_correlation_id(parent, chain.chain_id));
+                    self.record(&ChainRecord::Firing {
+                        chain_id: chain.chain_id,
+                        correlation_id: correlation_id.clone(),
+                    });
+                    self.place(&chain, correlation_id).await
+                }
+            };
+            self.firing.remove(&chain.chain_id);
+
+            let (record, event) = match result {
+                Ok(response) => {
+                    tracing::info!(
+                        chain_id = chain.chain_id,
+                        parent,
+                        order_id = response.order_id,
+                        "Placed chained order"
+                    );
+                    (
+                        ChainRecord::Fired {
+                            chain_id: chain.chain_id,
+                            order_id: response.order_id.clone(),
+                        },
+                        ChainEvent::Fired {
+                            chain_id: chain.chain_id,
+                            parent_order_id: chain.parent_order_id,
+                            response,
+                        },
+                    )
+                }
+                Err(e) => {
+                    tracing::error!(
+                        chain_id = chain.chain_id,
+                        parent,
+                        "Chained order failed: {e}"
+                    );
+                    (
+                        ChainRecord::Failed {
+                            chain_id: chain.chain_id,
+                            error: e.to_string(),
+                        },
+                        ChainEvent::Failed {
+                            chain_id: chain.chain_id,
+                            parent_order_id: chain.parent_order_id,
+                            error: e.to_string(),
+                        },
+                    )
+                }
+            };
+            self.record(&record);
+            events.push(event);
+        }
+        events
+    }
+
+    /// Place the follow-up of `chain` under `correlation_id`.
+    async fn place(
+        &mut self,
+        chain: &PendingChain,
+        correlation_id: String,
+    ) -> Result<OrderResponse> {
+        self.firing.insert(chain.chain_id, correlation_id.clone());
+        let mut request = chain.request.clone();
+        request.correlation_id = Some(correlation_id);
+        self.client.place_order(&request).await
+    }
+
+    /// The order placed under `correlation_id` before a restart, if the
+    /// exchange has one.
+    ///
+    /// An error answer from the API means there is none; other failures
+    /// leave it unknown and are returned.
+    async fn recover(&self, correlation_id: &str) -> Result<Option<OrderResponse>> {
+        match self
+            .client
+            .get_order_by_correlation_id(correlation_id)
+            .await
+        {
+            Ok(detail) => Ok(detail.order_id.clone().map(|order_id| {
+                tracing::info!(
+                    order_id,
+                    correlation_id,
+                    "Found chained order placed before restart"
+                );
+                OrderResponse {
+                    order_id,
+                    order_status: detail.order_status.unwrap_or_default(),
+                    oms_error_code: detail.oms_error_code,
+                    oms_error_description: detail.oms_error_description,
+                }
+            })),
+            Err(DhanError::Api(_) | DhanError::HttpStatus { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Discard every follow-up attached to `parent`.
+    fn drop_chains(&mut self, parent: &str, status: OrderStatus) -> Vec<ChainEvent> {
+        let mut events = Vec::new();
+        for chain in self.take_chains(parent) {
+            tracing::info!(
+                chain_id = chain.chain_id,
+                parent,
+                ?status,
+                "Dropped order chain"
+            );
+            self.record(&ChainRecord::Dropped {
+                chain_id: chain.chain_id,
+                status,
+            });
+            events.push(ChainEvent::Dropped {
+                chain_id: chain.chain_id,
+                parent_order_id: chain.parent_order_id,
+                status,
+            });
+        }
+        events
+    }
+
+    /// Journal a record. A write failure is logged rather than returned: a
+    /// resolution already happened on the exchange and must still be
+    /// reported, and a protective follow-up is placed even if its intent
+    /// could not be written.
+    fn record(&mut self, record: &ChainRecord) {
+        if let Err(e) = self.journal.append(record) {
+            tracing::error!("Failed to journal order chain: {e}");
+        }
+    }
+}
+
+/// The correlation ID of chain `chain_id` of `parent`: `ch<chain>-<parent>`,
+/// keeping the end of the parent ID if it would not fit.
+fn chain_correlation_id(parent: &str, chain_id: u64) -> String {
+    let prefix = format!("ch{chain_id}-");
+    let room = MAX_CORRELATION_ID_LEN.saturating_sub(prefix.len());
+    let start = parent
+        .char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| parent.len() - i <= room)
+        .unwrap_or(parent.len());
+    prefix + &parent[start..]
+}
@@ -0,0 +1,81 @@
+//! Waiting for an order to leave `TRANSIT`.
+//!
+//! A placed order starts in `TRANSIT` until the OMS forwards it to the
+//! exchange, and some never get that far. [`DhanClient::place_order_and_confirm`]
+//! places an order and polls `get_order` until it is accepted, rejected, or
+//! the timeout passes.
+
+use std::time::Duration;
+
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::types::enums::OrderStatus;
+use crate::types::orders::{OrderDetail, OrderRejection, PlaceOrderRequest};
+
+/// How often [`DhanClient::confirm_order`] polls the order.
+pub const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Where an order ended up after waiting for it to leave `TRANSIT`.
+#[derive(Debug, Clone)]
+pub enum Acceptance {
+    /// The order reached the exchange (pending, traded, cancelled, ...).
+    Accepted(Box<OrderDetail>),
+    /// The OMS rejected the order.
+    Rejected(OrderRejection),
+    /// The order was still in `TRANSIT` (or could not be read) at the timeout.
+    TimedOut {
+        /// The order that did not leave `TRANSIT`.
+        order_id: String,
+    },
+}
+
+impl Acceptance {
+    /// Returns `true` if the order reached the exchange.
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Self::Accepted(_))
+    }
+}
+
+impl DhanClient {
+    /// Place an order and wait up to `timeout` for it to leave `TRANSIT`.
+    ///
+    /// Rejections reported directly in the placement response return
+    /// immediately. Errors placing the order are returned as `Err`; errors
+    /// while polling are logged and retried until the timeout.
+    pub async fn place_order_and_confirm(
+        &self,
+        req: &PlaceOrderRequest,
+        timeout: Duration,
+    ) -> Result<Acceptance> {
+        let response = self.place_order(req).await?;
+        if let Some(rejection) = response.rejection() {
+            return Ok(Acceptance::Rejected(rejection));
+        }
+        Ok(self.confirm_order(&response.order_id, timeout).await)
+    }
+
+    /// Poll `order_id` until it leaves `TRANSIT` or `timeout` passes.
+    pub async fn confirm_order(&self, order_id: &str, timeout: Duration) -> Acceptance {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            match self.get_order(order_id).await {
+                Ok(detail) => {
+                    if let Some(rejection) = detail.rejection() {
+                        return Acceptance::Rejected(rejection);
+                    }
+                    if !matches!(detail.status(), Some(OrderStatus::TRANSIT) | None) {
+                        return Acceptance::Accepted(Box::new(detail));
+                    }
+                }
+                Err(e) => tracing::warn!(order_id, "Failed to poll order status: {e}"),
+            }
+            if tokio::time::Instant::now() + CONFIRM_POLL_INTERVAL > deadline {
+                tracing::warn!(order_id, ?timeout, "Order still in transit at timeout");
+                return Acceptance::TimedOut {
+                    order_id: order_id.to_owned(),
+                };
+            }
+            tokio::time::sleep(CONFIRM_POLL_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Automatic correlation IDs.
+//!
+//! A client with a [`CorrelationIdGenerator`] (see
+//! [`DhanClient::with_correlation_ids`]) fills in the correlation ID of
+//! every order, super order and forever order placed without one, so each
+//! order can be found again with [`DhanClient::get_order_by_correlation_id`]
+//! and matched to journal records and order updates. IDs set by the caller
+//! are left alone.
+//!
+//! Three generators are provided, all within Dhan's
+//! [`MAX_CORRELATION_ID_LEN`]:
+//!
+//! - [`UlidGenerator`] — 26-character ULIDs, sortable by creation time
+//! - [`UuidGenerator`] — random v4 UUIDs in base 36 (25 characters; the
+//!   hyphenated form is too long)
+//! - [`SequenceGenerator`] — a prefix and a counter, e.g. `bot-1`, `bot-2`
+//!
+//! ```
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::oms::correlation::{CorrelationIdGenerator, UlidGenerator};
+//!
+//! let client = DhanClient::new("client-id", "token").with_correlation_ids(UlidGenerator);
+//! assert_eq!(UlidGenerator.next_id().len(), 26);
+//! ```
+//!
+//! [`MAX_CORRELATION_ID_LEN`]: crate::oms::tags::MAX_CORRELATION_ID_LEN
+
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::client::DhanClient;
+use crate::types::forever_order::CreateForeverOrderRequest;
+use crate::types::orders::PlaceOrderRequest;
+use crate::types::super_order::PlaceSuperOrderRequest;
+
+/// Produces correlation IDs for orders placed without one.
+pub trait CorrelationIdGenerator: Send + Sync + std::fmt::Debug {
+    /// A new ID, unique among the IDs this generator returns.
+    fn next_id(&self) -> String;
+}
+
+/// ULIDs: 48-bit millisecond timestamp and 80 random bits in Crockford
+/// base 32, so IDs sort by creation time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UlidGenerator;
+
+impl CorrelationIdGenerator for UlidGenerator {
+    fn next_id(&self) -> String {
+        const DIGITS: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+        let millis = chrono::Utc::now().timestamp_millis().max(0) as u128;
+        let random = u128::from_be_bytes(*uuid::Uuid::new_v4().as_bytes()) & ((1 << 80) - 1);
+        let value = ((millis & ((1 << 48) - 1)) << 80) | random;
+        (0..26)
+            .rev()
+            .map(|i| DIGITS[((value >> (i * 5)) & 31) as usize] as char)
+            .collect()
+    }
+}
+
+/// Random (v4) UUIDs written in lowercase base 36.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidGenerator;
+
+impl CorrelationIdGenerator for UuidGenerator {
+    fn next_id(&self) -> String {
+        const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+        let mut value = uuid::Uuid::new_v4().as_u128();
+        let mut out = [b'0'; 25];
+        for digit in out.iter_mut().rev() {
+            *digit = DIGITS[(value % 36) as usize];
+            value /= 36;
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+}
+
+/// `prefix` followed by a counter.
+///
+/// Unique only within one run unless started past the last number used
+/// (see [`Self::starting_at`]).
+#[derive(Debug)]
+pub struct SequenceGenerator {
+    prefix: String,
+    next: AtomicU64,
+}
+
+impl SequenceGenerator {
+    /// Count from 1.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self::starting_at(prefix, 1)
+    }
+
+    /// Count from `first`, e.g. one past the last ID in a journal.
+    pub fn starting_at(prefix: impl Into<String>, first: u64) -> Self {
+        Self {
+            prefix: prefix.into(),
+            next: AtomicU64::new(first),
+        }
+    }
+}
+
+impl CorrelationIdGenerator for SequenceGenerator {
+    fn next_id(&self) -> String {
+        format!(
+            "{}{}",
+            self.prefix,
+            self.next.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+}
+
+/// An order request with a correlation ID.
+pub(crate) trait Correlated: Clone {
+    fn correlation_id_mut(&mut self) -> &mut Option<String>;
+
+    fn correlation_id(&self) -> Option<&str>;
+}
+
+impl Correlated for PlaceOrderRequest {
+    fn correlation_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.correlation_id
+    }
+
+    fn correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+}
+
+impl Correlated for PlaceSuperOrderRequest {
+    fn correlation_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.correlation_id
+    }
+
+    fn correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+}
+
+impl Correlated for CreateForeverOrderRequest {
+    fn correlation_id_mut(&mut self) -> &mut Option<String> {
+        &mut self.correlation_id
+    }
+
+    fn correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+}
+
+impl DhanClient {
+    /// Give orders placed through this client (and its clones) without a
+    /// correlation ID one from `generator`; see [`crate::oms::correlation`].
+    pub fn with_correlation_ids(
+        mut self,
+        generator: impl CorrelationIdGenerator + 'static,
+    ) -> Self {
+        self.set_correlation_ids(Arc::new(generator));
+        self
+    }
+
+    /// `req`, with a generated correlation ID if it has none and a
+    /// generator is set.
+    pub(crate) fn correlated<'a, T: Correlated>(&self, req: &'a T) -> Cow<'a, T> {
+        let Some(generator) = self.correlation_ids() else {
+            return Cow::Borrowed(req);
+        };
+        let mut req = Cow::Borrowed(req);
+        if req.correlation_id().is_none_or(str::is_empty) {
+            *req.to_mut().correlation_id_mut() = Some(generator.next_id());
+        }
+        req
+    }
+}
@@ -0,0 +1,115 @@
+//! Time-based order expiry (a local "good till time").
+//!
+//! Dhan only supports `DAY` and `IOC` validity. [`DhanClient::place_order_with_expiry`]
+//! places a regular order and arms a background timer that cancels it at the
+//! requested time if it is still working.
+//!
+//! The timer lives in the current process: if the process exits before
+//! `expire_at`, the order stays on the exchange with its original validity.
+
+use chrono::{DateTime, Utc};
+use tokio::task::JoinHandle;
+
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::types::enums::OrderStatus;
+use crate::types::orders::{OrderResponse, PlaceOrderRequest};
+
+/// What the expiry timer did when it fired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryOutcome {
+    /// The order was still working and has been cancelled.
+    Cancelled,
+    /// The order had already reached `status` (or was otherwise not working),
+    /// so nothing was done.
+    NotWorking(OrderStatus),
+    /// The timer was disarmed before it fired.
+    Disarmed,
+}
+
+/// A placed order with an armed expiry timer.
+///
+/// Dropping this value does **not** disarm the timer; call
+/// [`disarm()`](Self::disarm) to keep the order alive.
+#[derive(Debug)]
+pub struct ExpiringOrder {
+    /// The placement response.
+    pub response: OrderResponse,
+    expire_at: DateTime<Utc>,
+    timer: JoinHandle<Result<ExpiryOutcome>>,
+}
+
+impl ExpiringOrder {
+    /// The Dhan order ID.
+    pub fn order_id(&self) -> &str {
+        &self.response.order_id
+    }
+
+    /// When the order will be cancelled if still working.
+    pub fn expire_at(&self) -> DateTime<Utc> {
+        self.expire_at
+    }
+
+    /// Stop the timer; the order keeps its exchange validity.
+    pub fn disarm(&self) {
+        self.timer.abort();
+    }
+
+    /// Wait for the timer to fire and return what it did.
+    pub async fn outcome(self) -> Result<ExpiryOutcome> {
+        match self.timer.await {
+            Ok(result) => result,
+            Err(e) if e.is_cancelled() => Ok(ExpiryOutcome::Disarmed),
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+}
+
+impl DhanClient {
+    /// Place an order that is cancelled automatically at `expire_at` if it is
+    /// still working (transit, pending, triggered or partially traded) by
+    /// then.
+    ///
+    /// An `expire_at` in the past cancels the order on the next scheduler
+    /// tick. Timestamps in IST can be converted with
+    /// `.with_timezone(&chrono::Utc)`.
+    pub async fn place_order_with_expiry(
+        &self,
+        req: &PlaceOrderRequest,
+        expire_at: DateTime<Utc>,
+    ) -> Result<ExpiringOrder> {
+        let response = self.place_order(req).await?;
+        let client = self.clone();
+        let order_id = response.order_id.clone();
+        let delay = (expire_at - Utc::now()).to_std().unwrap_or_default();
+
+        tracing::info!(order_id, %expire_at, "Armed order expiry");
+        let timer = tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            expire_order(&client, &order_id).await
+        });
+
+        Ok(ExpiringOrder {
+            response,
+            expire_at,
+            timer,
+        })
+    }
+}
+
+/// Cancel `order_id` if it is still working.
+async fn expire_order(client: &DhanClient, order_id: &str) -> Result<ExpiryOutcome> {
+    let status = client.get_order(order_id).await?.status();
+
+    match status {
+        Some(s) if !s.is_working() => {
+            tracing::debug!(order_id, ?s, "Order no longer working at expiry");
+            Ok(ExpiryOutcome::NotWorking(s))
+        }
+        _ => {
+            client.cancel_order(order_id).await?;
+            tracing::info!(order_id, "Cancelled expired order");
+            Ok(ExpiryOutcome::Cancelled)
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Client-side order management helpers built on the order-update stream.
+//!
+//! Dhan only offers server-side OCO for forever orders and DAY/IOC validity
+//! for regular orders. The types in this module fill those gaps locally: they
+//! consume normalized [`OrderUpdate`](crate::ws::order_update::OrderUpdate)s
+//! and issue the follow-up REST calls through [`DhanClient`](crate::DhanClient).
+//!
+//! - [`tracker`] — Latest order state and fill detection from updates
+//! - [`oco`] — One-cancels-other emulation for a target / stop-loss pair
+//! - [`expiry`] — Cancel working orders at a chosen time of day
+//! - [`bulk`] — Re-price all working orders matching a filter
+//! - [`chain`] — Place follow-up orders when a parent fills (journaled)
+//! - [`confirm`] — Wait for a placed order to leave `TRANSIT`
+//! - [`tags`] — Group orders by tags encoded in the correlation ID
+//! - [`correlation`] — Generate missing correlation IDs (ULID, UUID, sequence)
+
+pub mod bulk;
+pub mod chain;
+pub mod confirm;
+pub mod correlation;
+pub mod expiry;
+pub mod oco;
+pub mod tags;
+pub mod tracker;
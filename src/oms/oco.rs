@@ -0,0 +1,480 @@
+//! Local one-cancels-other (OCO) emulation.
+//!
+//! [`OcoCoordinator`] watches pairs of resting orders — typically a target
+//! and a stop-loss for an intraday position — and cancels the sibling as soon
+//! as one leg is fully traded. While a leg is only partly traded, the sibling
+//! is reduced to the leg's remaining quantity.
+//!
+//! The bookkeeping lives in [`OcoBook`], which is a pure state machine driven
+//! by [`OrderUpdate`]s and can be used on its own (e.g. with a custom order
+//! gateway). The coordinator adds the REST cancellation on top.
+//!
+//! # Races
+//!
+//! Both legs may trade before the sibling cancellation reaches the exchange.
+//! This is detected either from the cancel call failing while the sibling is
+//! `TRADED`, or from the sibling's own fill updates arriving — including a
+//! partial fill followed by the cancellation — and reported once as
+//! [`OcoEvent::BothFilled`] so the caller can flatten the extra position.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::oms::oco::OcoCoordinator;
+//! use dhan_rs::ws::order_update::OrderUpdateStream;
+//! use futures_util::StreamExt;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let mut oco = OcoCoordinator::new(client);
+//! oco.watch("target-order-id", "stop-loss-order-id");
+//!
+//! let mut updates = OrderUpdateStream::connect("client-id", "token").await?;
+//! while let Some(msg) = updates.next().await {
+//!     for event in oco.handle(&msg?.into_update()).await {
+//!         println!("{event:?}");
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::client::DhanClient;
+use crate::types::enums::OrderStatus;
+use crate::ws::order_update::OrderUpdate;
+
+// ---------------------------------------------------------------------------
+// Identifiers and events
+// ---------------------------------------------------------------------------
+
+/// Identifier of a watched OCO pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OcoId(pub u64);
+
+impl fmt::Display for OcoId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "oco-{}", self.0)
+    }
+}
+
+/// One side of an OCO pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OcoLeg {
+    /// The profit-taking order.
+    Target,
+    /// The protective stop-loss order.
+    StopLoss,
+}
+
+impl OcoLeg {
+    /// The other leg of the pair.
+    pub fn sibling(self) -> Self {
+        match self {
+            Self::Target => Self::StopLoss,
+            Self::StopLoss => Self::Target,
+        }
+    }
+}
+
+/// Something that happened to a watched pair.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OcoEvent {
+    /// A leg was fully traded; its sibling must be cancelled.
+    Filled {
+        /// The pair.
+        id: OcoId,
+        /// The leg that traded.
+        leg: OcoLeg,
+        /// Dhan order ID of the traded leg.
+        order_id: String,
+        /// Dhan order ID of the leg to cancel.
+        sibling_order_id: String,
+    },
+    /// A leg was partially traded. The sibling must be reduced to
+    /// `remaining_quantity` so the two legs never cover more than the
+    /// position; [`OcoCoordinator`] does this automatically.
+    PartiallyFilled {
+        /// The pair.
+        id: OcoId,
+        /// The leg that partly traded.
+        leg: OcoLeg,
+        /// Dhan order ID of the partly traded leg.
+        order_id: String,
+        /// Dhan order ID of the leg to reduce.
+        sibling_order_id: String,
+        /// Quantity of the leg traded so far.
+        traded_quantity: i64,
+        /// Quantity of the leg still open, if the update carried it.
+        remaining_quantity: Option<i64>,
+    },
+    /// The sibling was cancelled after `winner` traded. The pair is done.
+    Completed {
+        /// The pair.
+        id: OcoId,
+        /// The leg that traded.
+        winner: OcoLeg,
+    },
+    /// Both legs traded, the sibling at least partly, before it could be
+    /// cancelled. The pair is done and the position is likely reversed —
+    /// the caller must act.
+    BothFilled {
+        /// The pair.
+        id: OcoId,
+        /// Quantity the sibling traded, if known.
+        sibling_traded_quantity: Option<i64>,
+    },
+    /// A leg ended without trading (cancelled, rejected or expired) while the
+    /// pair was still open. The pair is dropped; the other leg is untouched.
+    Broken {
+        /// The pair.
+        id: OcoId,
+        /// The leg that ended.
+        leg: OcoLeg,
+        /// The status it ended with.
+        status: OrderStatus,
+    },
+    /// Cancelling the sibling failed and it did not turn out to be traded.
+    /// The pair stays in the cancelling state.
+    CancelFailed {
+        /// The pair.
+        id: OcoId,
+        /// Dhan order ID of the sibling.
+        sibling_order_id: String,
+        /// Why the call failed.
+        error: String,
+    },
+    /// Reducing the sibling after a partial fill failed; it still has its
+    /// previous quantity. The pair stays watched.
+    ResizeFailed {
+        /// The pair.
+        id: OcoId,
+        /// Dhan order ID of the sibling.
+        sibling_order_id: String,
+        /// Why the call failed.
+        error: String,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// OcoBook — pure state machine
+// ---------------------------------------------------------------------------
+
+/// Lifecycle of a watched pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PairState {
+    /// Both legs resting.
+    Watching,
+    /// A leg traded; waiting for the sibling's cancellation.
+    Cancelling {
+        /// The leg that traded.
+        winner: OcoLeg,
+        /// Quantity the sibling has traded meanwhile.
+        sibling_traded: i64,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Pair {
+    target: String,
+    stop_loss: String,
+    state: PairState,
+}
+
+impl Pair {
+    fn order_id(&self, leg: OcoLeg) -> &str {
+        match leg {
+            OcoLeg::Target => &self.target,
+            OcoLeg::StopLoss => &self.stop_loss,
+        }
+    }
+}
+
+/// Bookkeeping for watched OCO pairs, driven by order updates.
+#[derive(Debug, Default)]
+pub struct OcoBook {
+    next_id: u64,
+    pairs: HashMap<OcoId, Pair>,
+    by_order: HashMap<String, (OcoId, OcoLeg)>,
+}
+
+impl OcoBook {
+    /// Create an empty book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start watching a target / stop-loss pair of resting orders.
+    pub fn watch(&mut self, target_order_id: &str, stop_loss_order_id: &str) -> OcoId {
+        self.next_id += 1;
+        let id = OcoId(self.next_id);
+        self.by_order
+            .insert(target_order_id.to_owned(), (id, OcoLeg::Target));
+        self.by_order
+            .insert(stop_loss_order_id.to_owned(), (id, OcoLeg::StopLoss));
+        self.pairs.insert(
+            id,
+            Pair {
+                target: target_order_id.to_owned(),
+                stop_loss: stop_loss_order_id.to_owned(),
+                state: PairState::Watching,
+            },
+        );
+        id
+    }
+
+    /// Stop watching a pair without touching its orders.
+    ///
+    /// Returns `false` if the pair was not being watched.
+    pub fn unwatch(&mut self, id: OcoId) -> bool {
+        match self.pairs.remove(&id) {
+            Some(pair) => {
+                self.by_order.remove(&pair.target);
+                self.by_order.remove(&pair.stop_loss);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of pairs currently watched.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns `true` if no pairs are watched.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Returns `true` if `order_id` is a leg of a watched pair.
+    pub fn is_watching(&self, order_id: &str) -> bool {
+        self.by_order.contains_key(order_id)
+    }
+
+    /// Apply an order update and return the resulting events.
+    ///
+    /// A [`OcoEvent::Filled`] event means the sibling order must now be
+    /// cancelled; [`OcoCoordinator`] does this automatically.
+    pub fn on_update(&mut self, update: &OrderUpdate) -> Vec<OcoEvent> {
+        let Some(order_id) = update.order_id.as_deref() else {
+            return Vec::new();
+        };
+        let Some(&(id, leg)) = self.by_order.get(order_id) else {
+            return Vec::new();
+        };
+        let Some(status) = update.status else {
+            return Vec::new();
+        };
+        let Some(pair) = self.pairs.get_mut(&id) else {
+            return Vec::new();
+        };
+
+        match (pair.state, status) {
+            (PairState::Watching, OrderStatus::TRADED) => {
+                pair.state = PairState::Cancelling {
+                    winner: leg,
+                    sibling_traded: 0,
+                };
+                vec![OcoEvent::Filled {
+                    id,
+                    leg,
+                    order_id: order_id.to_owned(),
+                    sibling_order_id: pair.order_id(leg.sibling()).to_owned(),
+                }]
+            }
+            (PairState::Watching, OrderStatus::PART_TRADED) => {
+                let traded_quantity = update.traded_quantity.unwrap_or_default();
+                let remaining_quantity = update
+                    .remaining_quantity
+                    .or_else(|| update.quantity.map(|q| q - traded_quantity));
+                vec![OcoEvent::PartiallyFilled {
+                    id,
+                    leg,
+                    order_id: order_id.to_owned(),
+                    sibling_order_id: pair.order_id(leg.sibling()).to_owned(),
+                    traded_quantity,
+                    remaining_quantity,
+                }]
+            }
+            (PairState::Watching, s) if s.is_terminal() => {
+                self.unwatch(id);
+                vec![OcoEvent::Broken { id, leg, status: s }]
+            }
+            (PairState::Cancelling { winner, .. }, OrderStatus::PART_TRADED) if leg != winner => {
+                pair.state = PairState::Cancelling {
+                    winner,
+                    sibling_traded: update.traded_quantity.unwrap_or_default(),
+                };
+                Vec::new()
+            }
+            (PairState::Cancelling { winner, .. }, OrderStatus::TRADED) if leg != winner => {
+                self.unwatch(id);
+                vec![OcoEvent::BothFilled {
+                    id,
+                    sibling_traded_quantity: update.traded_quantity,
+                }]
+            }
+            (
+                PairState::Cancelling {
+                    winner,
+                    sibling_traded,
+                },
+                s,
+            ) if leg != winner && s.is_terminal() => {
+                self.unwatch(id);
+                let traded = sibling_traded.max(update.traded_quantity.unwrap_or_default());
+                if traded > 0 {
+                    vec![OcoEvent::BothFilled {
+                        id,
+                        sibling_traded_quantity: Some(traded),
+                    }]
+                } else {
+                    vec![OcoEvent::Completed { id, winner }]
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Record that the sibling of `id` was found traded after a failed cancel.
+    fn mark_both_filled(&mut self, id: OcoId, traded: Option<i64>) -> Option<OcoEvent> {
+        self.unwatch(id).then_some(OcoEvent::BothFilled {
+            id,
+            sibling_traded_quantity: traded,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// OcoCoordinator — book + REST cancellation
+// ---------------------------------------------------------------------------
+
+/// Emulates OCO by cancelling the sibling order through the REST API.
+#[derive(Debug)]
+pub struct OcoCoordinator {
+    client: DhanClient,
+    book: OcoBook,
+}
+
+impl OcoCoordinator {
+    /// Create a coordinator that cancels orders through `client`.
+    pub fn new(client: DhanClient) -> Self {
+        Self {
+            client,
+            book: OcoBook::new(),
+        }
+    }
+
+    /// Start watching a target / stop-loss pair of resting orders.
+    pub fn watch(&mut self, target_order_id: &str, stop_loss_order_id: &str) -> OcoId {
+        let id = self.book.watch(target_order_id, stop_loss_order_id);
+        tracing::info!(%id, target_order_id, stop_loss_order_id, "Watching OCO pair");
+        id
+    }
+
+    /// Stop watching a pair without touching its orders.
+    pub fn unwatch(&mut self, id: OcoId) -> bool {
+        self.book.unwatch(id)
+    }
+
+    /// The underlying bookkeeping.
+    pub fn book(&self) -> &OcoBook {
+        &self.book
+    }
+
+    /// Apply an order update, cancelling siblings of filled legs and
+    /// reducing siblings of partly filled ones.
+    ///
+    /// Cancellation and modification failures are reported as events rather
+    /// than errors so a single bad pair never stops the update loop.
+    pub async fn handle(&mut self, update: &OrderUpdate) -> Vec<OcoEvent> {
+        let mut out = Vec::new();
+        for event in self.book.on_update(update) {
+            let follow_up = match &event {
+                OcoEvent::Filled {
+                    id,
+                    sibling_order_id,
+                    ..
+                } => {
+                    let (id, sibling) = (*id, sibling_order_id.clone());
+                    out.push(event);
+                    self.cancel_sibling(id, &sibling).await
+                }
+                OcoEvent::PartiallyFilled {
+                    id,
+                    sibling_order_id,
+                    remaining_quantity: Some(remaining),
+                    ..
+                } if *remaining > 0 => {
+                    let (id, sibling, remaining) = (*id, sibling_order_id.clone(), *remaining);
+                    out.push(event);
+                    self.resize_sibling(id, &sibling, remaining as u64).await
+                }
+                _ => {
+                    out.push(event);
+                    None
+                }
+            };
+            out.extend(follow_up);
+        }
+        out
+    }
+
+    /// Reduce the sibling to the partly filled leg's remaining quantity.
+    async fn resize_sibling(
+        &mut self,
+        id: OcoId,
+        sibling_order_id: &str,
+        quantity: u64,
+    ) -> Option<OcoEvent> {
+        match self
+            .client
+            .modify_order_quantity(sibling_order_id, quantity)
+            .await
+        {
+            Ok(_) => {
+                tracing::info!(%id, sibling_order_id, quantity, "Resized OCO sibling");
+                None
+            }
+            Err(e) => {
+                tracing::error!(%id, sibling_order_id, "Failed to resize OCO sibling: {e}");
+                Some(OcoEvent::ResizeFailed {
+                    id,
+                    sibling_order_id: sibling_order_id.to_owned(),
+                    error: e.to_string(),
+                })
+            }
+        }
+    }
+
+    /// Cancel the sibling order, resolving the both-filled race on failure.
+    async fn cancel_sibling(&mut self, id: OcoId, sibling_order_id: &str) -> Option<OcoEvent> {
+        let err = match self.client.cancel_order(sibling_order_id).await {
+            Ok(_) => {
+                tracing::info!(%id, sibling_order_id, "Cancelled OCO sibling");
+                // Completion is confirmed by the sibling's CANCELLED update.
+                return None;
+            }
+            Err(e) => e,
+        };
+
+        let sibling = self.client.get_order(sibling_order_id).await.ok();
+        if sibling.as_ref().and_then(|o| o.status()) == Some(OrderStatus::TRADED) {
+            tracing::warn!(%id, sibling_order_id, "Both OCO legs traded");
+            let traded = sibling.and_then(|o| o.filled_qty).map(|q| q as i64);
+            return self.book.mark_both_filled(id, traded);
+        }
+
+        tracing::error!(%id, sibling_order_id, "Failed to cancel OCO sibling: {err}");
+        Some(OcoEvent::CancelFailed {
+            id,
+            sibling_order_id: sibling_order_id.to_owned(),
+            error: err.to_string(),
+        })
+    }
+}
@@ -0,0 +1,114 @@
+//! Order tags encoded in the correlation ID.
+//!
+//! Dhan echoes an order's correlation ID in the order book and on the
+//! order-update stream, so it can carry a few labels (strategy, leg, batch)
+//! that group related orders without any extra storage. Tags are joined
+//! with `-` and followed by a unique suffix:
+//!
+//! ```text
+//! MOMO-LEG1-B7-lq2x9k0a03
+//! ^^^^ ^^^^ ^^ ^^^^^^^^^^ suffix
+//! ```
+//!
+//! This is the same shape the strategy runtime uses for its per-strategy
+//! prefix, so orders placed by a session strategy carry its name as a tag.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::types::orders::{OrderDetail, OrderResponse, PlaceOrderRequest};
+
+/// Longest correlation ID Dhan accepts.
+pub const MAX_CORRELATION_ID_LEN: usize = 30;
+
+/// Separator between tags (and before the suffix).
+pub const TAG_SEPARATOR: &str = "-";
+
+static SEQUENCE: AtomicU32 = AtomicU32::new(0);
+
+/// Build a correlation ID from `tags` and `suffix`.
+///
+/// Tags must be non-empty and ASCII alphanumeric. Fails if the result is
+/// longer than [`MAX_CORRELATION_ID_LEN`].
+pub fn encode_tags(tags: &[&str], suffix: &str) -> Result<String> {
+    if let Some(bad) = tags
+        .iter()
+        .find(|t| t.is_empty() || !t.chars().all(|c| c.is_ascii_alphanumeric()))
+    {
+        return Err(DhanError::InvalidArgument(format!(
+            "tag {bad:?} must be non-empty and alphanumeric"
+        )));
+    }
+    let mut id = tags.join(TAG_SEPARATOR);
+    if !suffix.is_empty() {
+        if !id.is_empty() {
+            id.push_str(TAG_SEPARATOR);
+        }
+        id.push_str(suffix);
+    }
+    if id.len() > MAX_CORRELATION_ID_LEN {
+        return Err(DhanError::InvalidArgument(format!(
+            "correlation ID {id:?} exceeds {MAX_CORRELATION_ID_LEN} characters"
+        )));
+    }
+    Ok(id)
+}
+
+/// The tags of a correlation ID: every segment except the trailing suffix.
+pub fn tags_of(correlation_id: &str) -> Vec<&str> {
+    let mut parts: Vec<&str> = correlation_id.split(TAG_SEPARATOR).collect();
+    parts.pop();
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
+/// Returns `true` if `correlation_id` carries `tag`.
+pub fn has_tag(correlation_id: &str, tag: &str) -> bool {
+    tags_of(correlation_id).contains(&tag)
+}
+
+/// A short suffix unique within this process (base-36 millis + sequence).
+fn unique_suffix() -> String {
+    let millis = chrono::Utc::now().timestamp_millis().max(0) as u64;
+    let seq = u64::from(SEQUENCE.fetch_add(1, Ordering::Relaxed) % 1296);
+    format!("{}{:0>2}", base36(millis), base36(seq))
+}
+
+fn base36(mut n: u64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut out = Vec::new();
+    loop {
+        out.push(DIGITS[(n % 36) as usize]);
+        n /= 36;
+        if n == 0 {
+            break;
+        }
+    }
+    out.reverse();
+    String::from_utf8(out).unwrap_or_default()
+}
+
+impl DhanClient {
+    /// Place an order tagged with `tags`.
+    ///
+    /// An existing correlation ID on `req` is kept as the suffix; otherwise
+    /// a unique one is generated.
+    pub async fn place_order_tagged(
+        &self,
+        req: &PlaceOrderRequest,
+        tags: &[&str],
+    ) -> Result<OrderResponse> {
+        let mut req = req.clone();
+        let suffix = req.correlation_id.take().unwrap_or_else(unique_suffix);
+        req.correlation_id = Some(encode_tags(tags, &suffix)?);
+        self.place_order(&req).await
+    }
+
+    /// Today's orders carrying `tag`, filtered locally from the order book.
+    pub async fn get_orders_by_tag(&self, tag: &str) -> Result<Vec<OrderDetail>> {
+        let mut orders = self.get_orders().await?;
+        orders.retain(|o| o.correlation_id.as_deref().is_some_and(|c| has_tag(c, tag)));
+        Ok(orders)
+    }
+}
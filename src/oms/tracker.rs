@@ -0,0 +1,165 @@
+//! Local order book maintained from order updates.
+//!
+//! [`OrderTracker`] keeps the latest known state of every order seen on the
+//! order-update stream and turns raw updates into [`TrackerEvent`]s such as
+//! "this order just became fully traded". With
+//! [`OrderTracker::with_duplicate_guard`] it also tells a
+//! [`DuplicateOrderGuard`] about rejected orders, so they can be re-sent.
+
+use std::collections::HashMap;
+
+use crate::risk::duplicate::DuplicateOrderGuard;
+use crate::types::enums::{OrderStatus, TransactionType};
+use crate::ws::order_update::OrderUpdate;
+
+/// Latest known state of a single order.
+#[derive(Debug, Clone)]
+pub struct TrackedOrder {
+    /// Dhan order ID.
+    pub order_id: String,
+    /// Latest status, if the update carried a recognizable one.
+    pub status: Option<OrderStatus>,
+    /// Total order quantity.
+    pub quantity: Option<i64>,
+    /// Quantity traded so far.
+    pub traded_quantity: i64,
+    /// Average traded price so far.
+    pub average_traded_price: Option<f64>,
+    /// The most recent update received for this order.
+    pub last_update: OrderUpdate,
+}
+
+/// A state change detected by [`OrderTracker::apply`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TrackerEvent {
+    /// The order's status changed (including the first update for an order).
+    StatusChanged {
+        /// Dhan order ID.
+        order_id: String,
+        /// Previous status (`None` for a newly seen order).
+        from: Option<OrderStatus>,
+        /// New status.
+        to: OrderStatus,
+    },
+    /// The order became fully traded. Emitted once per order.
+    Filled {
+        /// Dhan order ID.
+        order_id: String,
+        /// Total traded quantity.
+        quantity: i64,
+        /// Average traded price, if reported.
+        average_price: Option<f64>,
+    },
+}
+
+/// Tracks order state from the order-update stream.
+#[derive(Debug, Default)]
+pub struct OrderTracker {
+    orders: HashMap<String, TrackedOrder>,
+    duplicate_guard: Option<DuplicateOrderGuard>,
+}
+
+impl OrderTracker {
+    /// Create an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forward the first rejection of each order to `guard` (see
+    /// [`DuplicateOrderGuard::on_update`]).
+    pub fn with_duplicate_guard(mut self, guard: DuplicateOrderGuard) -> Self {
+        self.duplicate_guard = Some(guard);
+        self
+    }
+
+    /// Apply an order update and return the resulting events.
+    ///
+    /// Updates without an order ID are ignored.
+    pub fn apply(&mut self, update: &OrderUpdate) -> Vec<TrackerEvent> {
+        let Some(order_id) = update.order_id.clone() else {
+            return Vec::new();
+        };
+
+        let mut events = Vec::new();
+        let previous = self.orders.get(&order_id).and_then(|o| o.status);
+        if let Some(to) = update.status.filter(|&to| previous != Some(to)) {
+            events.push(TrackerEvent::StatusChanged {
+                order_id: order_id.clone(),
+                from: previous,
+                to,
+            });
+            if to == OrderStatus::REJECTED {
+                if let Some(guard) = &self.duplicate_guard {
+                    guard.on_update(update);
+                }
+            }
+        }
+
+        let entry = self
+            .orders
+            .entry(order_id.clone())
+            .or_insert_with(|| TrackedOrder {
+                order_id: order_id.clone(),
+                status: None,
+                quantity: None,
+                traded_quantity: 0,
+                average_traded_price: None,
+                last_update: update.clone(),
+            });
+        entry.status = update.status.or(entry.status);
+        entry.quantity = update.quantity.or(entry.quantity);
+        entry.traded_quantity = update.traded_quantity.unwrap_or(entry.traded_quantity);
+        entry.average_traded_price = update.average_traded_price.or(entry.average_traded_price);
+        entry.last_update = update.clone();
+
+        if update.status == Some(OrderStatus::TRADED) && previous != Some(OrderStatus::TRADED) {
+            events.push(TrackerEvent::Filled {
+                order_id,
+                quantity: entry.traded_quantity,
+                average_price: entry.average_traded_price,
+            });
+        }
+        events
+    }
+
+    /// Latest state of `order_id`, if it has been seen.
+    pub fn get(&self, order_id: &str) -> Option<&TrackedOrder> {
+        self.orders.get(order_id)
+    }
+
+    /// All tracked orders.
+    pub fn orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders.values()
+    }
+
+    /// Orders that are still working on the exchange.
+    pub fn working(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders
+            .values()
+            .filter(|o| o.status.is_some_and(OrderStatus::is_working))
+    }
+
+    /// Net traded quantity of `security_id` over the tracked orders: buys
+    /// count positive, sells negative.
+    pub fn net_position(&self, security_id: &str) -> i64 {
+        self.orders
+            .values()
+            .filter(|o| o.last_update.security_id.as_deref() == Some(security_id))
+            .map(|o| match o.last_update.transaction_type {
+                Some(TransactionType::BUY) => o.traded_quantity,
+                Some(TransactionType::SELL) => -o.traded_quantity,
+                None => 0,
+            })
+            .sum()
+    }
+
+    /// Number of tracked orders.
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Returns `true` if no orders have been tracked.
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+}
@@ -0,0 +1,252 @@
+//! Daily P&L attribution into overnight gap and intraday move.
+//!
+//! For carry strategies the question is how much of a day's P&L came from
+//! the opening gap on positions held overnight, rather than from the
+//! session itself. [`PnlAttribution`] splits each instrument's day P&L
+//! (marked to the last price) into:
+//!
+//! - **gap** — overnight quantity × (open − previous close)
+//! - **carry intraday** — overnight quantity × (last − open)
+//! - **trading** — today's fills marked to last: Σ quantity × (last − fill price)
+//!
+//! which add up to the day's P&L. Overnight quantities come from the
+//! carry-forward fields of the REST position book, the previous close from
+//! the feed's PrevClose packets (or [`PnlAttribution::set_prev_close`]),
+//! open and last from Quote/Full packets, and fills from the trade book
+//! (or [`PnlAttribution::apply_fill`]).
+//!
+//! ```
+//! use dhan_rs::portfolio::attribution::PnlAttribution;
+//! use dhan_rs::types::enums::{ExchangeSegment, TransactionType};
+//! use dhan_rs::types::instrument::InstrumentId;
+//!
+//! let id = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+//! let mut attribution = PnlAttribution::new();
+//! attribution.set_overnight(id, 100, 1.0);
+//! attribution.set_prev_close(id, 1600.0);
+//! attribution.set_prices(id, 1620.0, 1610.0); // gapped up 20, faded 10
+//! attribution.apply_fill(id, TransactionType::SELL, 50, 1615.0);
+//!
+//! let row = &attribution.report()[0];
+//! assert_eq!(row.gap, Some(2000.0));
+//! assert_eq!(row.carry_intraday, Some(-1000.0));
+//! assert_eq!(row.trading, Some(250.0));
+//! assert_eq!(row.total(), Some(1250.0));
+//! ```
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::portfolio::pnl::{position_instrument, trade_instrument};
+use crate::types::enums::TransactionType;
+use crate::types::instrument::InstrumentId;
+use crate::types::orders::TradeDetail;
+use crate::types::portfolio::Position;
+use crate::ws::market_feed::MarketFeedEvent;
+
+/// Inputs gathered for one instrument.
+#[derive(Debug, Clone, Default)]
+struct Entry {
+    overnight_qty: i64,
+    multiplier: f64,
+    prev_close: Option<f64>,
+    open: Option<f64>,
+    last: Option<f64>,
+    /// Today's fills: signed quantity and price.
+    fills: Vec<(i64, f64)>,
+}
+
+/// One row of the attribution table.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AttributionRow {
+    /// The instrument.
+    pub instrument: InstrumentId,
+    /// Net quantity carried in from the previous session.
+    pub overnight_qty: i64,
+    /// Quantity bought (positive) or sold (negative) today.
+    pub day_qty: i64,
+    /// Previous session's close.
+    pub prev_close: Option<f64>,
+    /// Today's open.
+    pub open: Option<f64>,
+    /// Last traded price.
+    pub last: Option<f64>,
+    /// P&L of the overnight quantity from previous close to open.
+    pub gap: Option<f64>,
+    /// P&L of the overnight quantity from open to last.
+    pub carry_intraday: Option<f64>,
+    /// P&L of today's fills, marked to last.
+    pub trading: Option<f64>,
+}
+
+impl AttributionRow {
+    /// Total day P&L, if every part could be computed.
+    pub fn total(&self) -> Option<f64> {
+        Some(self.gap? + self.carry_intraday? + self.trading?)
+    }
+
+    /// Intraday P&L: the carried quantity's session move plus trading.
+    pub fn intraday(&self) -> Option<f64> {
+        Some(self.carry_intraday? + self.trading?)
+    }
+}
+
+/// Gathers positions, prices and fills for a day's P&L attribution.
+#[derive(Debug, Clone, Default)]
+pub struct PnlAttribution {
+    entries: HashMap<InstrumentId, Entry>,
+}
+
+impl PnlAttribution {
+    /// An empty attribution.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take overnight quantities (carry-forward buys minus sells) and
+    /// multipliers from the REST position book.
+    pub fn from_positions(positions: &[Position]) -> Self {
+        let mut attribution = Self::new();
+        for pos in positions {
+            let Some(id) = position_instrument(pos) else {
+                continue;
+            };
+            let overnight =
+                pos.carry_forward_buy_qty.unwrap_or(0) - pos.carry_forward_sell_qty.unwrap_or(0);
+            let multiplier = pos.multiplier.filter(|m| *m > 0).unwrap_or(1) as f64;
+            attribution.set_overnight(id, overnight, multiplier);
+        }
+        attribution
+    }
+
+    fn entry(&mut self, instrument: InstrumentId) -> &mut Entry {
+        self.entries.entry(instrument).or_insert_with(|| Entry {
+            multiplier: 1.0,
+            ..Entry::default()
+        })
+    }
+
+    /// Set the quantity carried into today and the contract multiplier.
+    pub fn set_overnight(&mut self, instrument: InstrumentId, quantity: i64, multiplier: f64) {
+        let entry = self.entry(instrument);
+        entry.overnight_qty = quantity;
+        entry.multiplier = multiplier;
+    }
+
+    /// Set the previous session's close (e.g. from a REST quote).
+    pub fn set_prev_close(&mut self, instrument: InstrumentId, price: f64) {
+        self.entry(instrument).prev_close = Some(price);
+    }
+
+    /// Set today's open and last price.
+    pub fn set_prices(&mut self, instrument: InstrumentId, open: f64, last: f64) {
+        let entry = self.entry(instrument);
+        entry.open = Some(open);
+        entry.last = Some(last);
+    }
+
+    /// Record one of today's fills.
+    pub fn apply_fill(
+        &mut self,
+        instrument: InstrumentId,
+        side: TransactionType,
+        quantity: i64,
+        price: f64,
+    ) {
+        if quantity <= 0 || price <= 0.0 {
+            return;
+        }
+        let signed = match side {
+            TransactionType::BUY => quantity,
+            TransactionType::SELL => -quantity,
+        };
+        self.entry(instrument).fills.push((signed, price));
+    }
+
+    /// Record a trade-book row. Returns `false` if it could not be mapped.
+    pub fn apply_trade(&mut self, trade: &TradeDetail) -> bool {
+        let (Some(id), Some(side), Some(qty), Some(price)) = (
+            trade_instrument(trade),
+            trade
+                .transaction_type
+                .as_deref()
+                .and_then(TransactionType::from_order_update_code),
+            trade.traded_quantity,
+            trade.traded_price,
+        ) else {
+            return false;
+        };
+        self.apply_fill(id, side, qty as i64, price);
+        true
+    }
+
+    /// Take previous close, open and last price from a feed event, for
+    /// instruments already known.
+    pub fn on_event(&mut self, event: &MarketFeedEvent) {
+        if let MarketFeedEvent::PrevClose { prev_close, .. } = event {
+            let id = InstrumentId::from_header(event.header());
+            if let Some(entry) = id.and_then(|id| self.entries.get_mut(&id)) {
+                entry.prev_close = Some(f64::from(*prev_close)).filter(|p| *p > 0.0);
+            }
+            return;
+        }
+        let Some(tick) = event.to_tick().filter(|t| t.ltp > 0.0) else {
+            return;
+        };
+        if let Some(entry) = self.entries.get_mut(&tick.instrument) {
+            entry.last = Some(tick.ltp);
+            if let Some(open) = tick.open.filter(|o| *o > 0.0) {
+                entry.open = Some(open);
+            }
+        }
+    }
+
+    /// The attribution table, ordered by instrument.
+    ///
+    /// Parts whose prices are not known yet are `None`; an instrument with
+    /// no overnight quantity has zero gap and carry even without prices.
+    pub fn report(&self) -> Vec<AttributionRow> {
+        let mut rows: Vec<AttributionRow> = self
+            .entries
+            .iter()
+            .map(|(&instrument, e)| {
+                let carried = e.overnight_qty as f64 * e.multiplier;
+                let part = |from: Option<f64>, to: Option<f64>| {
+                    if e.overnight_qty == 0 {
+                        return Some(0.0);
+                    }
+                    Some((to? - from?) * carried)
+                };
+                let trading = if e.fills.is_empty() {
+                    Some(0.0)
+                } else {
+                    e.last.map(|last| {
+                        e.fills
+                            .iter()
+                            .map(|(q, p)| *q as f64 * (last - p) * e.multiplier)
+                            .sum()
+                    })
+                };
+                AttributionRow {
+                    instrument,
+                    overnight_qty: e.overnight_qty,
+                    day_qty: e.fills.iter().map(|(q, _)| q).sum(),
+                    prev_close: e.prev_close,
+                    open: e.open,
+                    last: e.last,
+                    gap: part(e.prev_close, e.open),
+                    carry_intraday: part(e.open, e.last),
+                    trading,
+                }
+            })
+            .collect();
+        rows.sort_by_key(|r| {
+            (
+                r.instrument.segment.segment_code(),
+                r.instrument.security_id,
+            )
+        });
+        rows
+    }
+}
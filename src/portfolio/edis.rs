@@ -0,0 +1,201 @@
+//! Bulk eDIS authorization for selling holdings.
+//!
+//! Selling delivery holdings needs a CDSL eDIS authorization, which the API
+//! only exposes one step at a time. [`EdisAuthorizer::authorize_all`] runs
+//! the whole flow for every sellable holding: it requests a bulk eDIS form,
+//! hands it to the caller to present (the T-PIN is entered by the user on
+//! the CDSL page), then polls the inquiry endpoint for each ISIN until it
+//! is approved or the timeout passes.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::portfolio::edis;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let holdings = client.get_holdings().await?;
+//! let outcomes = edis::authorize_all(&client, &holdings, |form| {
+//!     std::fs::write("edis.html", &form.edis_form_html).ok();
+//! })
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::types::edis::{EdisFormRequest, EdisFormResponse, EdisInquiry};
+use crate::types::portfolio::Holding;
+
+/// Default spacing of inquiry polls.
+pub const DEFAULT_EDIS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default time to wait for approvals.
+pub const DEFAULT_EDIS_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Authorization state of one ISIN at the end of the flow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EdisOutcome {
+    /// The requested quantity (or more) is approved.
+    Approved {
+        /// Approved quantity.
+        approved_qty: i64,
+    },
+    /// Not fully approved when the timeout passed.
+    Pending {
+        /// Approved quantity so far.
+        approved_qty: i64,
+        /// Quantity that needs approval.
+        required_qty: i64,
+    },
+    /// The last inquiry for this ISIN failed.
+    Failed(String),
+}
+
+/// Drives the bulk eDIS flow.
+#[derive(Debug, Clone)]
+pub struct EdisAuthorizer {
+    client: DhanClient,
+    poll_interval: Duration,
+    timeout: Duration,
+    generate_tpin: bool,
+}
+
+impl EdisAuthorizer {
+    /// Authorize through `client`.
+    pub fn new(client: DhanClient) -> Self {
+        Self {
+            client,
+            poll_interval: DEFAULT_EDIS_POLL_INTERVAL,
+            timeout: DEFAULT_EDIS_TIMEOUT,
+            generate_tpin: false,
+        }
+    }
+
+    /// Poll the inquiry endpoint every `interval`.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Give up waiting for approvals after `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send a new T-PIN to the registered mobile before requesting the form.
+    pub fn with_tpin(mut self) -> Self {
+        self.generate_tpin = true;
+        self
+    }
+
+    /// Authorize every sellable holding.
+    ///
+    /// `present` receives the bulk eDIS form (escaped HTML) and should show
+    /// it to the user. Holdings with no available quantity are skipped. The
+    /// result maps each ISIN to its final state.
+    pub async fn authorize_all(
+        &self,
+        holdings: &[Holding],
+        present: impl FnOnce(&EdisFormResponse),
+    ) -> Result<BTreeMap<String, EdisOutcome>> {
+        let required = sellable_quantities(holdings);
+        let Some((first_isin, first_qty)) = required.iter().next() else {
+            return Ok(BTreeMap::new());
+        };
+
+        if self.generate_tpin {
+            self.client.generate_tpin().await?;
+        }
+        let form = self
+            .client
+            .generate_edis_form(&EdisFormRequest {
+                isin: first_isin.clone(),
+                qty: (*first_qty).max(0) as u64,
+                exchange: "NSE".into(),
+                segment: "EQ".into(),
+                bulk: Some(true),
+            })
+            .await?;
+        present(&form);
+
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        let mut outcomes: BTreeMap<String, EdisOutcome> = BTreeMap::new();
+        loop {
+            for (isin, qty) in &required {
+                if matches!(outcomes.get(isin), Some(EdisOutcome::Approved { .. })) {
+                    continue;
+                }
+                let outcome = match self.client.inquire_edis(isin).await {
+                    Ok(inquiry) => outcome_of(&inquiry, *qty),
+                    Err(e) => EdisOutcome::Failed(e.to_string()),
+                };
+                outcomes.insert(isin.clone(), outcome);
+            }
+            let done = outcomes
+                .values()
+                .all(|o| matches!(o, EdisOutcome::Approved { .. }));
+            if done || tokio::time::Instant::now() + self.poll_interval > deadline {
+                break;
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+        Ok(outcomes)
+    }
+
+    /// Inquire the state of one ISIN against a required quantity.
+    pub async fn status(&self, isin: &str, required_qty: i64) -> Result<EdisOutcome> {
+        if isin.is_empty() {
+            return Err(DhanError::InvalidArgument("empty ISIN".into()));
+        }
+        Ok(outcome_of(
+            &self.client.inquire_edis(isin).await?,
+            required_qty,
+        ))
+    }
+}
+
+/// Authorize every sellable holding with the default poll interval and
+/// timeout. See [`EdisAuthorizer::authorize_all`].
+pub async fn authorize_all(
+    client: &DhanClient,
+    holdings: &[Holding],
+    present: impl FnOnce(&EdisFormResponse),
+) -> Result<BTreeMap<String, EdisOutcome>> {
+    EdisAuthorizer::new(client.clone())
+        .authorize_all(holdings, present)
+        .await
+}
+
+/// Sellable quantity per ISIN: available quantity (or total, if the
+/// available quantity is missing) of holdings with an ISIN.
+pub fn sellable_quantities(holdings: &[Holding]) -> BTreeMap<String, i64> {
+    let mut out = BTreeMap::new();
+    for h in holdings {
+        let Some(isin) = h.isin.clone().filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        let qty = h.available_qty.or(h.total_qty).unwrap_or(0);
+        if qty > 0 {
+            *out.entry(isin).or_insert(0) += qty;
+        }
+    }
+    out
+}
+
+fn outcome_of(inquiry: &EdisInquiry, required_qty: i64) -> EdisOutcome {
+    let approved_qty = inquiry.aprvd_qty.unwrap_or(0);
+    if approved_qty >= required_qty {
+        EdisOutcome::Approved { approved_qty }
+    } else {
+        EdisOutcome::Pending {
+            approved_qty,
+            required_qty,
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Equity, drawdown and exposure time series.
+//!
+//! [`EquityRecorder`] samples account equity — start-of-day funds plus the
+//! P&L of a [`PnlBook`] — together with gross exposure at a fixed cadence,
+//! and accumulates traded value. Drawdown, exposure and turnover statistics
+//! are computed on demand from the samples, so the same numbers are
+//! available for a live session and a backtest.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::portfolio::equity::EquityRecorder;
+//! use dhan_rs::portfolio::pnl::PnlBook;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let funds = client.get_fund_limit().await?.sod_limit.unwrap_or(0.0);
+//! let book = PnlBook::from_positions(&client.get_positions().await?);
+//!
+//! let mut recorder = EquityRecorder::new(Duration::from_secs(60));
+//! let mut every = tokio::time::interval(Duration::from_secs(60));
+//! loop {
+//!     every.tick().await;
+//!     recorder.record_book(chrono::Utc::now().timestamp(), funds, &book);
+//!     if let Some(dd) = recorder.max_drawdown() {
+//!         println!("max drawdown {:.2}%", dd.fraction * 100.0);
+//!     }
+//! }
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::portfolio::pnl::PnlBook;
+
+/// One sample of the series.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EquitySample {
+    /// Sample time (Unix seconds).
+    pub at: i64,
+    /// Funds plus P&L.
+    pub equity: f64,
+    /// Gross notional of open positions.
+    pub exposure: f64,
+}
+
+/// The largest peak-to-trough fall of a series.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Drawdown {
+    /// Time of the peak (Unix seconds).
+    pub peak_at: i64,
+    /// Equity at the peak.
+    pub peak: f64,
+    /// Time of the trough (Unix seconds).
+    pub trough_at: i64,
+    /// Equity at the trough.
+    pub trough: f64,
+    /// `peak − trough`.
+    pub amount: f64,
+    /// `amount / peak` (0 if the peak is not positive).
+    pub fraction: f64,
+}
+
+/// Equity and exposure samples with on-demand statistics.
+#[derive(Debug, Clone)]
+pub struct EquityRecorder {
+    cadence: Duration,
+    samples: Vec<EquitySample>,
+    turnover: f64,
+}
+
+impl EquityRecorder {
+    /// Record at most one sample per `cadence` (whole seconds).
+    pub fn new(cadence: Duration) -> Self {
+        Self {
+            cadence,
+            samples: Vec::new(),
+            turnover: 0.0,
+        }
+    }
+
+    /// Record a sample at `at` unless the previous one is less than the
+    /// cadence old. Returns `true` if it was recorded.
+    pub fn record(&mut self, at: i64, equity: f64, exposure: f64) -> bool {
+        if self
+            .samples
+            .last()
+            .is_some_and(|last| at - last.at < self.cadence.as_secs() as i64)
+        {
+            return false;
+        }
+        self.samples.push(EquitySample {
+            at,
+            equity,
+            exposure,
+        });
+        true
+    }
+
+    /// Record `funds` plus the book's realized and unrealized P&L, with the
+    /// book's gross exposure at last prices.
+    pub fn record_book(&mut self, at: i64, funds: f64, book: &PnlBook) -> bool {
+        let update = book.update();
+        let exposure = update
+            .per_instrument
+            .values()
+            .map(|p| {
+                (p.net_quantity as f64 * p.ltp.unwrap_or(p.average_price) * p.multiplier).abs()
+            })
+            .sum();
+        self.record(at, funds + update.total(), exposure)
+    }
+
+    /// Add the value of a fill (`quantity × price`, either side) to the
+    /// turnover.
+    pub fn add_turnover(&mut self, value: f64) {
+        self.turnover += value.abs();
+    }
+
+    /// The samples, oldest first.
+    pub fn samples(&self) -> &[EquitySample] {
+        &self.samples
+    }
+
+    /// The latest sample.
+    pub fn last(&self) -> Option<&EquitySample> {
+        self.samples.last()
+    }
+
+    /// The largest peak-to-trough fall so far, if equity ever fell.
+    pub fn max_drawdown(&self) -> Option<Drawdown> {
+        let mut peak = *self.samples.first()?;
+        let mut worst: Option<Drawdown> = None;
+        for s in &self.samples {
+            if s.equity > peak.equity {
+                peak = *s;
+                continue;
+            }
+            let amount = peak.equity - s.equity;
+            if amount > 0.0 && worst.is_none_or(|w| amount > w.amount) {
+                worst = Some(Drawdown {
+                    peak_at: peak.at,
+                    peak: peak.equity,
+                    trough_at: s.at,
+                    trough: s.equity,
+                    amount,
+                    fraction: if peak.equity > 0.0 {
+                        amount / peak.equity
+                    } else {
+                        0.0
+                    },
+                });
+            }
+        }
+        worst
+    }
+
+    /// How far the latest equity is below the running peak (0 at a peak).
+    pub fn current_drawdown(&self) -> f64 {
+        let peak = self
+            .samples
+            .iter()
+            .map(|s| s.equity)
+            .fold(f64::NEG_INFINITY, f64::max);
+        self.last().map_or(0.0, |s| peak - s.equity)
+    }
+
+    /// Largest gross exposure sampled.
+    pub fn max_exposure(&self) -> f64 {
+        self.samples.iter().map(|s| s.exposure).fold(0.0, f64::max)
+    }
+
+    /// Mean gross exposure over the samples.
+    pub fn average_exposure(&self) -> f64 {
+        mean(self.samples.iter().map(|s| s.exposure))
+    }
+
+    /// Total traded value added with [`Self::add_turnover`].
+    pub fn turnover(&self) -> f64 {
+        self.turnover
+    }
+
+    /// Turnover divided by mean equity (0 without positive equity).
+    pub fn turnover_ratio(&self) -> f64 {
+        let equity = mean(self.samples.iter().map(|s| s.equity));
+        if equity > 0.0 {
+            self.turnover / equity
+        } else {
+            0.0
+        }
+    }
+}
+
+fn mean(values: impl ExactSizeIterator<Item = f64>) -> f64 {
+    let n = values.len();
+    if n == 0 {
+        0.0
+    } else {
+        values.sum::<f64>() / n as f64
+    }
+}
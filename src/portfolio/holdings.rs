@@ -0,0 +1,238 @@
+//! Holdings changes not explained by trades.
+//!
+//! Holdings also move for reasons that never show up in the trade book:
+//! bonuses and splits, buy-backs, off-market transfers and pledges. The
+//! [`HoldingsWatcher`] keeps a journal of holdings snapshots and, on each
+//! check, compares today's holdings with the last snapshot, subtracting
+//! delivery trades made since. Whatever remains is reported as a
+//! [`HoldingDelta`] for someone to look at.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::portfolio::holdings::HoldingsWatcher;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let mut watcher = HoldingsWatcher::open("holdings.jsonl")?;
+//! for delta in watcher.check(&client).await? {
+//!     println!("{delta:?}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::journal::Journal;
+use crate::time::ist;
+use crate::types::enums::{ProductType, TransactionType};
+use crate::types::portfolio::Holding;
+use crate::types::statements::TradeHistoryEntry;
+
+/// Most trade-history pages fetched per check.
+const MAX_TRADE_PAGES: u32 = 50;
+
+/// Holdings recorded at a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldingsSnapshot {
+    /// When the holdings were fetched (IST).
+    pub taken_at: DateTime<FixedOffset>,
+    /// The holdings.
+    pub holdings: Vec<Holding>,
+}
+
+/// A holding whose quantity changed by more than its trades explain.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HoldingDelta {
+    /// ISIN, or security ID when the ISIN is missing.
+    pub key: String,
+    /// Trading symbol, if known.
+    pub symbol: Option<String>,
+    /// Quantity in the previous snapshot.
+    pub previous_qty: i64,
+    /// Quantity now.
+    pub current_qty: i64,
+    /// Net delivery quantity bought minus sold in between.
+    pub traded_qty: i64,
+    /// `current - previous - traded`.
+    pub unexplained_qty: i64,
+}
+
+/// Compare two holdings lists, netting out delivery `trades`.
+///
+/// Only `CNC` and `MTF` trades count. Returns the holdings whose change is
+/// not fully explained, ordered by key.
+pub fn diff_holdings(
+    previous: &[Holding],
+    current: &[Holding],
+    trades: &[TradeHistoryEntry],
+) -> Vec<HoldingDelta> {
+    #[derive(Default)]
+    struct Row {
+        symbol: Option<String>,
+        previous: i64,
+        current: i64,
+        traded: i64,
+    }
+
+    let mut rows: BTreeMap<String, Row> = BTreeMap::new();
+    for h in previous {
+        if let Some(key) = holding_key(h) {
+            let row = rows.entry(key).or_default();
+            row.previous += h.total_qty.unwrap_or(0);
+            row.symbol = row.symbol.take().or_else(|| h.trading_symbol.clone());
+        }
+    }
+    for h in current {
+        if let Some(key) = holding_key(h) {
+            let row = rows.entry(key).or_default();
+            row.current += h.total_qty.unwrap_or(0);
+            row.symbol = row.symbol.take().or_else(|| h.trading_symbol.clone());
+        }
+    }
+    for t in trades.iter().filter(|t| is_delivery(t)) {
+        let Some(key) = t
+            .isin
+            .clone()
+            .filter(|s| !s.is_empty())
+            .or_else(|| t.security_id.clone())
+        else {
+            continue;
+        };
+        let qty = t.traded_quantity.unwrap_or(0);
+        let signed = match t
+            .transaction_type
+            .as_deref()
+            .and_then(TransactionType::from_order_update_code)
+        {
+            Some(TransactionType::BUY) => qty,
+            Some(TransactionType::SELL) => -qty,
+            None => continue,
+        };
+        let row = rows.entry(key).or_default();
+        row.traded += signed;
+        row.symbol = row.symbol.take().or_else(|| t.trading_symbol.clone());
+    }
+
+    rows.into_iter()
+        .filter_map(|(key, r)| {
+            let unexplained = r.current - r.previous - r.traded;
+            (unexplained != 0).then_some(HoldingDelta {
+                key,
+                symbol: r.symbol,
+                previous_qty: r.previous,
+                current_qty: r.current,
+                traded_qty: r.traded,
+                unexplained_qty: unexplained,
+            })
+        })
+        .collect()
+}
+
+fn holding_key(h: &Holding) -> Option<String> {
+    h.isin
+        .clone()
+        .filter(|s| !s.is_empty())
+        .or_else(|| h.security_id.clone())
+}
+
+fn is_delivery(t: &TradeHistoryEntry) -> bool {
+    matches!(
+        t.product_type
+            .as_deref()
+            .and_then(ProductType::from_order_update_code),
+        Some(ProductType::CNC | ProductType::MTF)
+    )
+}
+
+/// Journals holdings snapshots and reports unexplained changes.
+#[derive(Debug)]
+pub struct HoldingsWatcher {
+    journal: Journal,
+    last: Option<HoldingsSnapshot>,
+}
+
+impl HoldingsWatcher {
+    /// Open (or create) the snapshot journal at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let last = if path.exists() {
+            Journal::read::<HoldingsSnapshot>(path)?.pop()
+        } else {
+            None
+        };
+        Ok(Self {
+            journal: Journal::open(path)?,
+            last,
+        })
+    }
+
+    /// The most recent snapshot.
+    pub fn last(&self) -> Option<&HoldingsSnapshot> {
+        self.last.as_ref()
+    }
+
+    /// Record `snapshot` as the new baseline.
+    pub fn record(&mut self, snapshot: HoldingsSnapshot) -> Result<()> {
+        self.journal.append(&snapshot)?;
+        self.last = Some(snapshot);
+        Ok(())
+    }
+
+    /// Fetch holdings and the trades made since the last snapshot, record
+    /// a new snapshot and return the unexplained changes.
+    ///
+    /// The first check only records a baseline and returns nothing.
+    pub async fn check(&mut self, client: &DhanClient) -> Result<Vec<HoldingDelta>> {
+        let now = Utc::now().with_timezone(&ist());
+        let holdings = client.get_holdings().await?;
+        let deltas = match &self.last {
+            Some(prev) => {
+                let from = prev.taken_at.date_naive().to_string();
+                let to = now.date_naive().to_string();
+                let since = prev.taken_at.with_timezone(&Utc);
+                let mut trades = fetch_trades(client, &from, &to).await?;
+                trades.retain(|t| {
+                    t.exchange_time_utc()
+                        .or_else(|| t.create_time_utc())
+                        .is_none_or(|at| at >= since)
+                });
+                diff_holdings(&prev.holdings, &holdings, &trades)
+            }
+            None => Vec::new(),
+        };
+        for d in &deltas {
+            tracing::warn!(
+                key = d.key,
+                symbol = ?d.symbol,
+                unexplained = d.unexplained_qty,
+                "Holding changed without matching trades"
+            );
+        }
+        self.record(HoldingsSnapshot {
+            taken_at: now,
+            holdings,
+        })?;
+        Ok(deltas)
+    }
+}
+
+/// All trade-history pages between two dates.
+async fn fetch_trades(client: &DhanClient, from: &str, to: &str) -> Result<Vec<TradeHistoryEntry>> {
+    let mut trades = Vec::new();
+    for page in 0..MAX_TRADE_PAGES {
+        let batch = client.get_trade_history(from, to, page).await?;
+        if batch.is_empty() {
+            break;
+        }
+        trades.extend(batch);
+    }
+    Ok(trades)
+}
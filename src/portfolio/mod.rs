@@ -0,0 +1,23 @@
+//! Book-level views over positions.
+//!
+//! - [`netting`] — Group derivative positions by underlying with
+//!   delta-adjusted quantity, net premium and margin
+//! - [`edis`] — Bulk eDIS authorization of sellable holdings
+//! - [`holdings`] — Holdings changes not explained by trades (corporate
+//!   actions, off-market transfers)
+//! - [`pnl`] — Live realized/unrealized P&L from positions, fills and the feed
+//! - [`attribution`] — Day P&L split into overnight gap and intraday move
+//! - [`equity`] — Sampled equity with drawdown, exposure and turnover statistics
+//! - [`performance`] — Per-strategy P&L, win rate, costs and Sharpe, side by side
+//! - [`reconcile`] — Trade charges checked against ledger bills per settlement date
+
+pub mod attribution;
+pub mod edis;
+pub mod equity;
+pub mod holdings;
+pub mod netting;
+pub mod performance;
+pub mod pnl;
+pub mod reconcile;
+
+pub use netting::net_by_underlying;
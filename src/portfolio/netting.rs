@@ -0,0 +1,132 @@
+//! Netting positions by underlying.
+//!
+//! Raw [`Position`] rows are per contract. [`net_by_underlying`] folds
+//! equity, future and option positions on the same underlying into one
+//! [`UnderlyingExposure`], using an [`InstrumentMaster`] for the underlying
+//! name, option deltas and margin. Without master data the underlying is
+//! taken from the trading symbol (`"NIFTY-Dec2024-24000-CE"` → `"NIFTY"`),
+//! equities and futures count with delta 1, and options without a delta are
+//! reported in [`UnderlyingExposure::missing_delta`].
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::types::portfolio::Position;
+
+/// Reference data for one instrument.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstrumentInfo {
+    /// Underlying symbol (e.g. `"NIFTY"`).
+    pub underlying: Option<String>,
+    /// Delta per unit; negative for puts.
+    pub delta: Option<f64>,
+    /// Margin blocked per unit of net quantity.
+    pub margin_per_unit: Option<f64>,
+}
+
+/// Source of [`InstrumentInfo`] by security ID.
+pub trait InstrumentMaster {
+    /// Reference data for `security_id`, if known.
+    fn instrument(&self, security_id: &str) -> Option<InstrumentInfo>;
+}
+
+impl InstrumentMaster for HashMap<String, InstrumentInfo> {
+    fn instrument(&self, security_id: &str) -> Option<InstrumentInfo> {
+        self.get(security_id).cloned()
+    }
+}
+
+/// Net exposure to one underlying.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UnderlyingExposure {
+    /// Underlying symbol.
+    pub underlying: String,
+    /// Position rows folded in.
+    pub positions: usize,
+    /// Sum of net quantities of equity and futures positions.
+    pub net_quantity: i64,
+    /// Sum of net quantity × delta across all positions with a known delta.
+    pub delta_quantity: f64,
+    /// Option positions without a delta (excluded from `delta_quantity`).
+    pub missing_delta: usize,
+    /// Option premium paid (positive) or received (negative).
+    pub net_premium: f64,
+    /// Realized P&L.
+    pub realized: f64,
+    /// Unrealized P&L.
+    pub unrealized: f64,
+    /// Margin attributed from `margin_per_unit`, if known for any position.
+    pub margin: Option<f64>,
+}
+
+/// Group `positions` by underlying, sorted by underlying name.
+///
+/// Closed positions (zero net quantity) still contribute P&L and premium.
+pub fn net_by_underlying(
+    positions: &[Position],
+    master: &impl InstrumentMaster,
+) -> Vec<UnderlyingExposure> {
+    let mut book: BTreeMap<String, UnderlyingExposure> = BTreeMap::new();
+    for pos in positions {
+        let info = pos
+            .security_id
+            .as_deref()
+            .and_then(|id| master.instrument(id))
+            .unwrap_or_default();
+        let Some(underlying) = info
+            .underlying
+            .clone()
+            .or_else(|| pos.trading_symbol.as_deref().map(underlying_of))
+        else {
+            continue;
+        };
+
+        let e = book
+            .entry(underlying.clone())
+            .or_insert_with(|| UnderlyingExposure {
+                underlying,
+                ..Default::default()
+            });
+        let qty = pos.net_qty.unwrap_or(0);
+        e.positions += 1;
+        e.realized += pos.realized_profit.unwrap_or(0.0);
+        e.unrealized += pos.unrealized_profit.unwrap_or(0.0);
+        if let Some(per_unit) = info.margin_per_unit {
+            *e.margin.get_or_insert(0.0) += qty.unsigned_abs() as f64 * per_unit;
+        }
+
+        if is_option(pos) {
+            e.net_premium += value(pos.buy_qty, pos.buy_avg) - value(pos.sell_qty, pos.sell_avg);
+            match info.delta {
+                Some(delta) => e.delta_quantity += qty as f64 * delta,
+                None if qty != 0 => e.missing_delta += 1,
+                None => {}
+            }
+        } else {
+            e.net_quantity += qty;
+            e.delta_quantity += qty as f64 * info.delta.unwrap_or(1.0);
+        }
+    }
+    book.into_values().collect()
+}
+
+/// Underlying of a Dhan trading symbol: the part before the first `-` or
+/// space.
+fn underlying_of(symbol: &str) -> String {
+    symbol.split(['-', ' ']).next().unwrap_or(symbol).to_owned()
+}
+
+fn is_option(pos: &Position) -> bool {
+    matches!(
+        pos.drv_option_type
+            .as_deref()
+            .map(str::to_ascii_uppercase)
+            .as_deref(),
+        Some("CALL" | "PUT" | "CE" | "PE")
+    )
+}
+
+fn value(qty: Option<i64>, avg: Option<f64>) -> f64 {
+    qty.unwrap_or(0) as f64 * avg.unwrap_or(0.0)
+}
@@ -0,0 +1,298 @@
+//! Side-by-side performance comparison of strategies.
+//!
+//! Strategies are told apart by the correlation IDs of their orders (see
+//! [`StrategyRunner::with_correlation_prefix`]). [`StrategyReport`] maps
+//! order IDs to strategy tags, replays the trade history of a date range
+//! with average-cost accounting and the statement's charges, and produces
+//! one [`StrategyStats`] row per strategy: P&L before and after costs, win
+//! rate and average win/loss per round trip (flat to flat), and the Sharpe
+//! ratio of an equity series if one was recorded (see
+//! [`crate::portfolio::equity`]). Rows export as JSON or CSV.
+//!
+//! The order book only covers today, so keep the tags of earlier days
+//! (e.g. in a [`crate::journal::Journal`]) and add them with
+//! [`StrategyReport::tag_order`].
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::portfolio::performance::StrategyReport;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let mut report = StrategyReport::new();
+//! report.tag_orders_by_prefix(&client.get_orders().await?, &["mr-", "breakout-"]);
+//! report
+//!     .load_trade_history(&client, "2024-09-01", "2024-09-30")
+//!     .await?;
+//! println!("{}", report.to_csv()?);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`StrategyRunner::with_correlation_prefix`]: crate::runtime::StrategyRunner::with_correlation_prefix
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::portfolio::equity::EquitySample;
+use crate::types::enums::TransactionType;
+use crate::types::orders::OrderDetail;
+use crate::types::statements::TradeHistoryEntry;
+
+/// Comparison row of one strategy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StrategyStats {
+    /// Strategy tag.
+    pub strategy: String,
+    /// Fills replayed.
+    pub fills: usize,
+    /// Positions taken from flat back to flat.
+    pub round_trips: usize,
+    /// Realized P&L before costs.
+    pub gross_pnl: f64,
+    /// Charges of the fills (brokerage, STT, exchange, SEBI, GST, stamp).
+    pub costs: f64,
+    /// `gross_pnl − costs`.
+    pub net_pnl: f64,
+    /// Share of round trips with positive gross P&L.
+    pub win_rate: Option<f64>,
+    /// Mean gross P&L of winning round trips.
+    pub average_win: Option<f64>,
+    /// Mean gross P&L of losing round trips (negative).
+    pub average_loss: Option<f64>,
+    /// Traded value.
+    pub turnover: f64,
+    /// Annualized Sharpe ratio of the recorded equity series.
+    pub sharpe: Option<f64>,
+}
+
+/// Open position of one strategy in one instrument.
+#[derive(Debug, Clone, Copy, Default)]
+struct Leg {
+    net: i64,
+    average: f64,
+    /// Realized since the position was last flat.
+    trip: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Tally {
+    stats: StrategyStats,
+    legs: HashMap<String, Leg>,
+    trips: Vec<f64>,
+}
+
+impl Tally {
+    fn fill(&mut self, instrument: String, quantity: i64, price: f64) {
+        let leg = self.legs.entry(instrument).or_default();
+        if leg.net == 0 || leg.net.signum() == quantity.signum() {
+            let open = leg.net.unsigned_abs() as f64;
+            let added = quantity.unsigned_abs() as f64;
+            leg.average = (leg.average * open + price * added) / (open + added);
+            leg.net += quantity;
+            return;
+        }
+        let closed = quantity.unsigned_abs().min(leg.net.unsigned_abs());
+        let pnl = closed as f64 * (price - leg.average) * leg.net.signum() as f64;
+        leg.trip += pnl;
+        self.stats.gross_pnl += pnl;
+        let reversed = quantity.unsigned_abs() > leg.net.unsigned_abs();
+        leg.net += quantity;
+        if leg.net == 0 || reversed {
+            self.trips.push(leg.trip);
+            leg.trip = 0.0;
+            leg.average = if reversed { price } else { 0.0 };
+        }
+    }
+}
+
+/// Builds per-strategy comparison rows from tagged trades.
+#[derive(Debug, Clone, Default)]
+pub struct StrategyReport {
+    tags: HashMap<String, String>,
+    tallies: BTreeMap<String, Tally>,
+    equity: HashMap<String, Vec<EquitySample>>,
+    periods_per_year: f64,
+}
+
+/// Annualization factor for daily equity samples.
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+impl StrategyReport {
+    /// An empty report annualizing Sharpe for daily equity samples.
+    pub fn new() -> Self {
+        Self {
+            periods_per_year: TRADING_DAYS_PER_YEAR,
+            ..Self::default()
+        }
+    }
+
+    /// Annualize Sharpe for equity samples taken `periods` times a year.
+    pub fn with_periods_per_year(mut self, periods: f64) -> Self {
+        self.periods_per_year = periods;
+        self
+    }
+
+    /// Attribute order `order_id` to `strategy`.
+    pub fn tag_order(&mut self, order_id: impl Into<String>, strategy: impl Into<String>) {
+        self.tags.insert(order_id.into(), strategy.into());
+    }
+
+    /// Attribute every order whose correlation ID starts with one of
+    /// `prefixes` to that prefix (without a trailing `-` or `_`).
+    pub fn tag_orders_by_prefix(&mut self, orders: &[OrderDetail], prefixes: &[&str]) {
+        for order in orders {
+            let (Some(id), Some(correlation)) = (&order.order_id, &order.correlation_id) else {
+                continue;
+            };
+            if let Some(prefix) = prefixes.iter().find(|p| correlation.starts_with(**p)) {
+                self.tag_order(id.clone(), prefix.trim_end_matches(['-', '_']));
+            }
+        }
+    }
+
+    /// Use `samples` as the equity series of `strategy`.
+    pub fn set_equity(&mut self, strategy: impl Into<String>, samples: Vec<EquitySample>) {
+        self.equity.insert(strategy.into(), samples);
+    }
+
+    /// Replay trades, oldest first. Trades of untagged orders are skipped;
+    /// returns how many were used.
+    pub fn add_trades(&mut self, trades: &[TradeHistoryEntry]) -> usize {
+        let mut sorted: Vec<&TradeHistoryEntry> = trades.iter().collect();
+        sorted.sort_by(|a, b| {
+            let time = |t: &TradeHistoryEntry| t.exchange_time.clone().or(t.create_time.clone());
+            time(a).cmp(&time(b))
+        });
+        sorted.into_iter().filter(|t| self.add_trade(t)).count()
+    }
+
+    /// Replay one trade. Returns `false` if its order is untagged or it
+    /// lacks side, quantity or price.
+    pub fn add_trade(&mut self, trade: &TradeHistoryEntry) -> bool {
+        let Some(strategy) = trade.order_id.as_ref().and_then(|id| self.tags.get(id)) else {
+            return false;
+        };
+        let (Some(side), Some(qty), Some(price)) = (
+            trade
+                .transaction_type
+                .as_deref()
+                .and_then(TransactionType::from_order_update_code),
+            trade.traded_quantity.filter(|q| *q > 0),
+            trade.traded_price.filter(|p| *p > 0.0),
+        ) else {
+            return false;
+        };
+        let instrument = format!(
+            "{}:{}",
+            trade.exchange_segment.as_deref().unwrap_or_default(),
+            trade.security_id.as_deref().unwrap_or_default()
+        );
+        let signed = match side {
+            TransactionType::BUY => qty,
+            TransactionType::SELL => -qty,
+        };
+        let costs = trade.total_charges();
+
+        let tally = self.tallies.entry(strategy.clone()).or_default();
+        tally.stats.fills += 1;
+        tally.stats.costs += costs;
+        tally.stats.turnover += qty as f64 * price;
+        tally.fill(instrument, signed, price);
+        true
+    }
+
+    /// Fetch every page of the trade history from `from_date` to `to_date`
+    /// (`YYYY-MM-DD`) and replay it. Returns the number of trades used.
+    pub async fn load_trade_history(
+        &mut self,
+        client: &DhanClient,
+        from_date: &str,
+        to_date: &str,
+    ) -> Result<usize> {
+        let mut trades = Vec::new();
+        for page in 0.. {
+            let batch = client.get_trade_history(from_date, to_date, page).await?;
+            if batch.is_empty() {
+                break;
+            }
+            trades.extend(batch);
+        }
+        Ok(self.add_trades(&trades))
+    }
+
+    /// One row per strategy, ordered by tag.
+    pub fn rows(&self) -> Vec<StrategyStats> {
+        let mut strategies: Vec<&String> = self.tallies.keys().collect();
+        strategies.extend(
+            self.equity
+                .keys()
+                .filter(|s| !self.tallies.contains_key(*s)),
+        );
+        strategies.sort();
+        strategies
+            .into_iter()
+            .map(|strategy| {
+                let tally = self.tallies.get(strategy).cloned().unwrap_or_default();
+                let mut stats = tally.stats;
+                stats.strategy = strategy.clone();
+                stats.net_pnl = stats.gross_pnl - stats.costs;
+                stats.round_trips = tally.trips.len();
+                let (wins, losses): (Vec<f64>, Vec<f64>) =
+                    tally.trips.iter().partition(|pnl| **pnl > 0.0);
+                if !tally.trips.is_empty() {
+                    stats.win_rate = Some(wins.len() as f64 / tally.trips.len() as f64);
+                }
+                stats.average_win = mean(&wins);
+                stats.average_loss = mean(&losses);
+                stats.sharpe = self
+                    .equity
+                    .get(strategy)
+                    .and_then(|samples| sharpe(samples, self.periods_per_year));
+                stats
+            })
+            .collect()
+    }
+
+    /// The rows as a JSON array.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.rows())?)
+    }
+
+    /// The rows as CSV with a header line.
+    pub fn to_csv(&self) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for row in self.rows() {
+            writer.serialize(row)?;
+        }
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| DhanError::Io(e.into_error()))?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Annualized Sharpe ratio (zero risk-free rate) of sample-to-sample
+/// returns, or `None` with fewer than two returns or no variance.
+fn sharpe(samples: &[EquitySample], periods_per_year: f64) -> Option<f64> {
+    let returns: Vec<f64> = samples
+        .windows(2)
+        .filter(|w| w[0].equity > 0.0)
+        .map(|w| w[1].equity / w[0].equity - 1.0)
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = mean(&returns)?;
+    let variance =
+        returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    (variance > 0.0).then(|| mean / variance.sqrt() * periods_per_year.sqrt())
+}
@@ -0,0 +1,411 @@
+//! Live intraday P&L from positions, fills and the market feed.
+//!
+//! [`PnlBook`] holds net quantity, average price and realized P&L per
+//! instrument. Seed it from the REST position book, then keep it current
+//! with fills (order updates or trade-book rows) and marks from the feed.
+//! [`PnlStream`] drives a book from a feed stream and, optionally, the
+//! order-update stream, yielding a [`PnlUpdate`] on every change or at a
+//! throttled interval.
+//!
+//! Average cost accounting is used: adding to a position moves the average
+//! price, reducing it realizes `(price - average) × quantity`.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+
+use crate::types::enums::{ExchangeSegment, TransactionType};
+use crate::types::instrument::InstrumentId;
+use crate::types::orders::TradeDetail;
+use crate::types::portfolio::Position;
+use crate::ws::market_feed::MarketFeedEvent;
+use crate::ws::order_update::OrderUpdate;
+
+/// P&L state of one instrument.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct InstrumentPnl {
+    /// Net quantity (negative when short).
+    pub net_quantity: i64,
+    /// Average price of the open quantity.
+    pub average_price: f64,
+    /// Realized P&L.
+    pub realized: f64,
+    /// Last traded price, once the feed has sent one.
+    pub ltp: Option<f64>,
+    /// Contract multiplier (1 except for currency/commodity contracts).
+    pub multiplier: f64,
+}
+
+impl Default for InstrumentPnl {
+    fn default() -> Self {
+        Self {
+            net_quantity: 0,
+            average_price: 0.0,
+            realized: 0.0,
+            ltp: None,
+            multiplier: 1.0,
+        }
+    }
+}
+
+impl InstrumentPnl {
+    /// Mark-to-market P&L of the open quantity (zero until an LTP arrives).
+    pub fn unrealized(&self) -> f64 {
+        match self.ltp {
+            Some(ltp) if self.net_quantity != 0 => {
+                (ltp - self.average_price) * self.net_quantity as f64 * self.multiplier
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Apply a fill of `quantity` (negative for sells) at `price`.
+    fn fill(&mut self, quantity: i64, price: f64) {
+        let net = self.net_quantity;
+        if net == 0 || net.signum() == quantity.signum() {
+            let open = net.unsigned_abs() as f64;
+            let added = quantity.unsigned_abs() as f64;
+            self.average_price = (self.average_price * open + price * added) / (open + added);
+        } else {
+            let closed = quantity.unsigned_abs().min(net.unsigned_abs()) as f64;
+            self.realized +=
+                closed * (price - self.average_price) * net.signum() as f64 * self.multiplier;
+            if quantity.unsigned_abs() > net.unsigned_abs() {
+                self.average_price = price;
+            }
+        }
+        self.net_quantity = net + quantity;
+        if self.net_quantity == 0 {
+            self.average_price = 0.0;
+        }
+    }
+}
+
+/// Book-wide P&L at one point in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PnlUpdate {
+    /// Total realized P&L.
+    pub realized: f64,
+    /// Total unrealized P&L.
+    pub unrealized: f64,
+    /// State per instrument.
+    pub per_instrument: HashMap<InstrumentId, InstrumentPnl>,
+}
+
+impl PnlUpdate {
+    /// Realized plus unrealized.
+    pub fn total(&self) -> f64 {
+        self.realized + self.unrealized
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Book
+// ---------------------------------------------------------------------------
+
+/// Per-instrument P&L kept current from fills and marks.
+#[derive(Debug, Clone, Default)]
+pub struct PnlBook {
+    instruments: HashMap<InstrumentId, InstrumentPnl>,
+    /// Cumulative traded quantity and average price per order, to turn
+    /// order updates into fill deltas.
+    orders: HashMap<String, (i64, f64)>,
+}
+
+impl PnlBook {
+    /// Create an empty book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed a book from the REST position book.
+    ///
+    /// Fills already reflected in `positions` must not be applied again;
+    /// feed only later order updates or trades.
+    pub fn from_positions(positions: &[Position]) -> Self {
+        let mut book = Self::new();
+        for pos in positions {
+            let Some(id) = position_instrument(pos) else {
+                continue;
+            };
+            let net = pos.net_qty.unwrap_or(0);
+            let average_price = match net.signum() {
+                1 => pos.buy_avg.unwrap_or(0.0),
+                -1 => pos.sell_avg.unwrap_or(0.0),
+                _ => 0.0,
+            };
+            book.instruments.insert(
+                id,
+                InstrumentPnl {
+                    net_quantity: net,
+                    average_price,
+                    realized: pos.realized_profit.unwrap_or(0.0),
+                    ltp: None,
+                    multiplier: pos.multiplier.filter(|m| *m > 0).unwrap_or(1) as f64,
+                },
+            );
+        }
+        book
+    }
+
+    /// Apply a fill of `quantity` at `price`.
+    pub fn apply_fill(
+        &mut self,
+        instrument: InstrumentId,
+        side: TransactionType,
+        quantity: i64,
+        price: f64,
+    ) {
+        if quantity <= 0 || price <= 0.0 {
+            return;
+        }
+        let signed = match side {
+            TransactionType::BUY => quantity,
+            TransactionType::SELL => -quantity,
+        };
+        self.instruments
+            .entry(instrument)
+            .or_default()
+            .fill(signed, price);
+    }
+
+    /// Apply a trade-book row. Returns `false` if it could not be mapped.
+    pub fn apply_trade(&mut self, trade: &TradeDetail) -> bool {
+        let (Some(id), Some(side), Some(qty), Some(price)) = (
+            trade_instrument(trade),
+            trade
+                .transaction_type
+                .as_deref()
+                .and_then(TransactionType::from_order_update_code),
+            trade.traded_quantity,
+            trade.traded_price,
+        ) else {
+            return false;
+        };
+        self.apply_fill(id, side, qty as i64, price);
+        true
+    }
+
+    /// Apply the new fills reported by an order update.
+    ///
+    /// Updates carry cumulative traded quantity and average price per
+    /// order; the difference to the previous update is applied. Returns
+    /// `true` if a fill was applied.
+    pub fn on_order_update(&mut self, update: &OrderUpdate) -> bool {
+        let (Some(order_id), Some(id), Some(side)) = (
+            update.order_id.as_deref(),
+            update.instrument(),
+            update.transaction_type,
+        ) else {
+            return false;
+        };
+        let traded = update.traded_quantity.unwrap_or(0);
+        let (prev_qty, prev_avg) = self.orders.get(order_id).copied().unwrap_or((0, 0.0));
+        if traded <= prev_qty {
+            return false;
+        }
+        let avg = update
+            .average_traded_price
+            .or(update.traded_price)
+            .unwrap_or(0.0);
+        let delta = traded - prev_qty;
+        let price = if prev_qty > 0 && avg > 0.0 {
+            (avg * traded as f64 - prev_avg * prev_qty as f64) / delta as f64
+        } else {
+            update.traded_price.filter(|p| *p > 0.0).unwrap_or(avg)
+        };
+        self.orders.insert(order_id.to_owned(), (traded, avg));
+        self.apply_fill(id, side, delta, price);
+        true
+    }
+
+    /// Mark an instrument with a feed event's LTP. Returns `true` if the
+    /// instrument is in the book and its price changed.
+    pub fn on_event(&mut self, event: &MarketFeedEvent) -> bool {
+        let Some(tick) = event.to_tick().filter(|t| t.ltp > 0.0) else {
+            return false;
+        };
+        match self.instruments.get_mut(&tick.instrument) {
+            Some(pnl) if pnl.ltp != Some(tick.ltp) => {
+                pnl.ltp = Some(tick.ltp);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// State of `instrument`.
+    pub fn get(&self, instrument: &InstrumentId) -> Option<&InstrumentPnl> {
+        self.instruments.get(instrument)
+    }
+
+    /// Instruments in the book.
+    pub fn instruments(&self) -> impl Iterator<Item = &InstrumentId> {
+        self.instruments.keys()
+    }
+
+    /// Current totals and per-instrument state.
+    pub fn update(&self) -> PnlUpdate {
+        PnlUpdate {
+            realized: self.instruments.values().map(|p| p.realized).sum(),
+            unrealized: self
+                .instruments
+                .values()
+                .map(InstrumentPnl::unrealized)
+                .sum(),
+            per_instrument: self.instruments.clone(),
+        }
+    }
+}
+
+pub(super) fn position_instrument(pos: &Position) -> Option<InstrumentId> {
+    instrument_of(
+        pos.exchange_segment.as_deref()?,
+        pos.security_id.as_deref()?,
+    )
+}
+
+pub(super) fn trade_instrument(trade: &TradeDetail) -> Option<InstrumentId> {
+    instrument_of(
+        trade.exchange_segment.as_deref()?,
+        trade.security_id.as_deref()?,
+    )
+}
+
+fn instrument_of(segment: &str, security_id: &str) -> Option<InstrumentId> {
+    Some(InstrumentId::new(
+        segment.parse::<ExchangeSegment>().ok()?,
+        security_id.trim().parse().ok()?,
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Stream
+// ---------------------------------------------------------------------------
+
+type OrderUpdates = Pin<Box<dyn Stream<Item = OrderUpdate> + Send>>;
+
+/// Drives a [`PnlBook`] from the market feed and order updates.
+pub struct PnlStream {
+    book: PnlBook,
+    throttle: Option<Duration>,
+    orders: Option<OrderUpdates>,
+}
+
+impl std::fmt::Debug for PnlStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PnlStream")
+            .field("book", &self.book)
+            .field("throttle", &self.throttle)
+            .field("orders", &self.orders.is_some())
+            .finish()
+    }
+}
+
+impl PnlStream {
+    /// Stream updates of `book`.
+    pub fn new(book: PnlBook) -> Self {
+        Self {
+            book,
+            throttle: None,
+            orders: None,
+        }
+    }
+
+    /// Emit at most one update per `every` (only if something changed),
+    /// instead of one per change.
+    pub fn with_throttle(mut self, every: Duration) -> Self {
+        self.throttle = Some(every);
+        self
+    }
+
+    /// Apply fills from `updates` (e.g. a normalized order-update stream).
+    pub fn with_order_updates(
+        mut self,
+        updates: impl Stream<Item = OrderUpdate> + Send + 'static,
+    ) -> Self {
+        self.orders = Some(Box::pin(updates));
+        self
+    }
+
+    /// Consume feed `events` and yield P&L updates.
+    ///
+    /// The stream ends when `events` ends.
+    pub fn stream(
+        self,
+        events: impl Stream<Item = MarketFeedEvent>,
+    ) -> impl Stream<Item = PnlUpdate> {
+        let interval = self.throttle.map(|every| {
+            let mut interval = tokio::time::interval(every);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval
+        });
+        let state = StreamState {
+            book: self.book,
+            orders: self.orders,
+            interval,
+            dirty: false,
+        };
+        futures_util::stream::unfold(
+            (state, Box::pin(events)),
+            |(mut state, mut events)| async move {
+                loop {
+                    let changed = tokio::select! {
+                        event = events.next() => match event {
+                            Some(event) => state.book.on_event(&event),
+                            None => return None,
+                        },
+                        update = next_order(&mut state.orders) => match update {
+                            Some(update) => state.book.on_order_update(&update),
+                            None => {
+                                state.orders = None;
+                                false
+                            }
+                        },
+                        _ = tick(&mut state.interval) => {
+                            if std::mem::take(&mut state.dirty) {
+                                let update = state.book.update();
+                                return Some((update, (state, events)));
+                            }
+                            false
+                        }
+                    };
+                    if changed {
+                        if state.interval.is_some() {
+                            state.dirty = true;
+                        } else {
+                            let update = state.book.update();
+                            return Some((update, (state, events)));
+                        }
+                    }
+                }
+            },
+        )
+    }
+}
+
+struct StreamState {
+    book: PnlBook,
+    orders: Option<OrderUpdates>,
+    interval: Option<tokio::time::Interval>,
+    dirty: bool,
+}
+
+async fn next_order(orders: &mut Option<OrderUpdates>) -> Option<OrderUpdate> {
+    match orders {
+        Some(orders) => orders.next().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn tick(interval: &mut Option<tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
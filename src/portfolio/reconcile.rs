@@ -0,0 +1,350 @@
+//! Contract notes reconciled against the ledger.
+//!
+//! Every trading day ends in a bill: the sale proceeds less the purchases
+//! and the day's charges, posted to the ledger. [`Reconciler`] recomputes
+//! that amount from the trade history — traded value plus the brokerage,
+//! STT, exchange, SEBI, GST and stamp duty reported per trade — and sets it
+//! beside the ledger entries of the same settlement date. Days whose
+//! difference exceeds the tolerance are flagged, along with bills without
+//! trades and trades without bills, so wrong brokerage or charges surface
+//! without reading contract notes by hand.
+//!
+//! Only ledger entries that look like trade bills take part (see
+//! [`is_trade_entry`]); funds added or withdrawn and DP charges are left
+//! out. Futures carried overnight settle through mark-to-market entries
+//! that the trade history cannot reproduce, so their days show up as
+//! mismatches.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::portfolio::reconcile::Reconciler;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let report = Reconciler::new().load_month(&client, 2024, 9).await?;
+//! for day in report.discrepancies() {
+//!     println!("{}: {:?} by {:.2}", day.date, day.status, day.difference);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use serde::Serialize;
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::time::parse_naive;
+use crate::types::enums::TransactionType;
+use crate::types::statements::{LedgerEntry, TradeHistoryEntry};
+
+/// Default tolerance, in rupees, for rounding in bills.
+pub const DEFAULT_TOLERANCE: f64 = 1.0;
+
+/// Outcome of one settlement date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SettlementStatus {
+    /// The ledger agrees with the trades within the tolerance.
+    Matched,
+    /// The ledger and the trades disagree.
+    Mismatch,
+    /// Trades settle on the date, but the ledger has no bill for it.
+    MissingLedger,
+    /// The ledger has a bill, but no trades settle on the date.
+    MissingTrades,
+}
+
+/// Trades and ledger bills of one settlement date.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettlementDay {
+    /// Settlement date.
+    pub date: NaiveDate,
+    /// Trades settling on the date.
+    pub trades: usize,
+    /// Value bought.
+    pub buy_value: f64,
+    /// Value sold.
+    pub sell_value: f64,
+    /// Charges reported with the trades.
+    pub charges: f64,
+    /// Expected ledger amount: `sell_value − buy_value − charges`.
+    pub expected: f64,
+    /// Ledger bill entries of the date.
+    pub ledger_entries: usize,
+    /// Net of those entries (credit minus debit).
+    pub ledger: f64,
+    /// Charges the ledger implies: `sell_value − buy_value − ledger`.
+    pub ledger_charges: f64,
+    /// `ledger − expected`; negative when more was debited than expected.
+    pub difference: f64,
+    /// Whether the date reconciles.
+    pub status: SettlementStatus,
+}
+
+/// Settlement dates from a [`Reconciler`], oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReconciliationReport {
+    /// One row per settlement date with trades or ledger bills.
+    pub days: Vec<SettlementDay>,
+    /// Trades left out for lacking side, quantity, price or time.
+    pub skipped_trades: usize,
+    /// Ledger bills left out for lacking a readable voucher date.
+    pub skipped_entries: usize,
+}
+
+impl ReconciliationReport {
+    /// The days that do not reconcile.
+    pub fn discrepancies(&self) -> impl Iterator<Item = &SettlementDay> {
+        self.days
+            .iter()
+            .filter(|d| d.status != SettlementStatus::Matched)
+    }
+
+    /// Returns `true` if every day reconciles.
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies().next().is_none()
+    }
+
+    /// The days as CSV with a header line.
+    pub fn to_csv(&self) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        for day in &self.days {
+            writer.serialize(day)?;
+        }
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| DhanError::Io(e.into_error()))?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// Returns `true` for ledger entries that look like trade bills: the
+/// narration or voucher description mentions a bill, trade, settlement or
+/// contract note.
+pub fn is_trade_entry(entry: &LedgerEntry) -> bool {
+    [&entry.narration, &entry.voucherdesc]
+        .into_iter()
+        .flatten()
+        .map(|s| s.to_ascii_lowercase())
+        .any(|s| {
+            ["bill", "trade", "settlement", "contract"]
+                .iter()
+                .any(|word| s.contains(word))
+        })
+}
+
+/// Matches trade-history charges against ledger bills per settlement date.
+#[derive(Debug, Clone)]
+pub struct Reconciler {
+    tolerance: f64,
+    settlement_lag: u32,
+    holidays: BTreeSet<NaiveDate>,
+    ledger_filter: fn(&LedgerEntry) -> bool,
+}
+
+impl Default for Reconciler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reconciler {
+    /// Bills dated on the trade date, [`DEFAULT_TOLERANCE`], and
+    /// [`is_trade_entry`] to pick bills from the ledger.
+    pub fn new() -> Self {
+        Self {
+            tolerance: DEFAULT_TOLERANCE,
+            settlement_lag: 0,
+            holidays: BTreeSet::new(),
+            ledger_filter: is_trade_entry,
+        }
+    }
+
+    /// Flag days whose difference exceeds `rupees`.
+    pub fn with_tolerance(mut self, rupees: f64) -> Self {
+        self.tolerance = rupees.abs();
+        self
+    }
+
+    /// Expect bills `days` trading days after the trade date (e.g. 1 for a
+    /// ledger posted on T+1).
+    pub fn with_settlement_lag(mut self, days: u32) -> Self {
+        self.settlement_lag = days;
+        self
+    }
+
+    /// Exchange holidays to skip when applying the settlement lag.
+    pub fn with_holidays(mut self, holidays: impl IntoIterator<Item = NaiveDate>) -> Self {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    /// Pick trade bills from the ledger with `filter` instead of
+    /// [`is_trade_entry`].
+    pub fn with_ledger_filter(mut self, filter: fn(&LedgerEntry) -> bool) -> Self {
+        self.ledger_filter = filter;
+        self
+    }
+
+    /// The date trades made on `trade_date` settle in the ledger.
+    pub fn settlement_date(&self, trade_date: NaiveDate) -> NaiveDate {
+        let mut date = trade_date;
+        for _ in 0..self.settlement_lag {
+            date = date + Days::new(1);
+            while matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+                || self.holidays.contains(&date)
+            {
+                date = date + Days::new(1);
+            }
+        }
+        date
+    }
+
+    /// Reconcile `trades` against `ledger`.
+    pub fn reconcile(
+        &self,
+        ledger: &[LedgerEntry],
+        trades: &[TradeHistoryEntry],
+    ) -> ReconciliationReport {
+        #[derive(Default)]
+        struct Row {
+            trades: usize,
+            buy_value: f64,
+            sell_value: f64,
+            charges: f64,
+            ledger_entries: usize,
+            ledger: f64,
+        }
+
+        let mut report = ReconciliationReport::default();
+        let mut rows: BTreeMap<NaiveDate, Row> = BTreeMap::new();
+        for trade in trades {
+            let (Some(side), Some(qty), Some(price), Some(date)) = (
+                trade
+                    .transaction_type
+                    .as_deref()
+                    .and_then(TransactionType::from_order_update_code),
+                trade.traded_quantity.filter(|q| *q > 0),
+                trade.traded_price.filter(|p| *p > 0.0),
+                trade_date(trade),
+            ) else {
+                report.skipped_trades += 1;
+                continue;
+            };
+            let row = rows.entry(self.settlement_date(date)).or_default();
+            row.trades += 1;
+            row.charges += trade.total_charges();
+            let value = qty as f64 * price;
+            match side {
+                TransactionType::BUY => row.buy_value += value,
+                TransactionType::SELL => row.sell_value += value,
+            }
+        }
+        for entry in ledger.iter().filter(|e| (self.ledger_filter)(e)) {
+            let Some(date) = entry.voucher_date() else {
+                report.skipped_entries += 1;
+                continue;
+            };
+            let row = rows.entry(date).or_default();
+            row.ledger_entries += 1;
+            row.ledger += entry.net_amount();
+        }
+
+        report.days = rows
+            .into_iter()
+            .map(|(date, r)| {
+                let traded = r.sell_value - r.buy_value;
+                let expected = traded - r.charges;
+                let difference = r.ledger - expected;
+                let status = if r.trades == 0 {
+                    SettlementStatus::MissingTrades
+                } else if r.ledger_entries == 0 {
+                    SettlementStatus::MissingLedger
+                } else if difference.abs() > self.tolerance {
+                    SettlementStatus::Mismatch
+                } else {
+                    SettlementStatus::Matched
+                };
+                SettlementDay {
+                    date,
+                    trades: r.trades,
+                    buy_value: r.buy_value,
+                    sell_value: r.sell_value,
+                    charges: r.charges,
+                    expected,
+                    ledger_entries: r.ledger_entries,
+                    ledger: r.ledger,
+                    ledger_charges: traded - r.ledger,
+                    difference,
+                    status,
+                }
+            })
+            .collect();
+        report
+    }
+
+    /// Fetch the trade history from `from_date` to `to_date` (`YYYY-MM-DD`)
+    /// and the ledger up to their last settlement date, and reconcile them.
+    ///
+    /// Ledger bills dated after the last settlement date are not fetched;
+    /// ones before it whose trades fall before `from_date` show up as
+    /// [`SettlementStatus::MissingTrades`].
+    pub async fn load(
+        &self,
+        client: &DhanClient,
+        from_date: &str,
+        to_date: &str,
+    ) -> Result<ReconciliationReport> {
+        let parse = |s: &str| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .map_err(|e| DhanError::InvalidArgument(format!("date {s:?}: {e}")))
+        };
+        let from = parse(from_date)?;
+        let to = parse(to_date)?;
+
+        let mut trades = Vec::new();
+        for page in 0.. {
+            let batch = client.get_trade_history(from_date, to_date, page).await?;
+            if batch.is_empty() {
+                break;
+            }
+            trades.extend(batch);
+        }
+        let ledger = client
+            .get_ledger(
+                &self.settlement_date(from).to_string(),
+                &self.settlement_date(to).to_string(),
+            )
+            .await?;
+        Ok(self.reconcile(&ledger, &trades))
+    }
+
+    /// [`Self::load`] for the calendar month `month` (1–12) of `year`.
+    pub async fn load_month(
+        &self,
+        client: &DhanClient,
+        year: i32,
+        month: u32,
+    ) -> Result<ReconciliationReport> {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)
+            .ok_or_else(|| DhanError::InvalidArgument(format!("month {year}-{month}")))?;
+        let last = first
+            .checked_add_months(chrono::Months::new(1))
+            .and_then(|d| d.pred_opt())
+            .ok_or_else(|| DhanError::InvalidArgument(format!("month {year}-{month}")))?;
+        self.load(client, &first.to_string(), &last.to_string())
+            .await
+    }
+}
+
+/// The IST date a trade was made on.
+fn trade_date(trade: &TradeHistoryEntry) -> Option<NaiveDate> {
+    [&trade.exchange_time, &trade.create_time]
+        .into_iter()
+        .flatten()
+        .find_map(|s| parse_naive(s))
+        .map(|t| t.date())
+}
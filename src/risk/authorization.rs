@@ -0,0 +1,174 @@
+//! Sell authorization (eDIS / DDPI) checks for delivery sells.
+//!
+//! Selling delivery holdings (`CNC`) needs either DDPI on the account or a
+//! CDSL eDIS approval covering the quantity. Without one the exchange
+//! rejects the order with an unhelpful message. [`SellAuthorization`] holds
+//! the account's DDPI flag and approved eDIS quantities;
+//! [`SellAuthorizationCheck`] plugs it into a [`RiskEngine`] and
+//! [`DhanClient::check_sell_authorization`] checks a single order live.
+//!
+//! [`RiskEngine`]: super::RiskEngine
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::types::enums::{ProductType, TransactionType};
+use crate::types::orders::PlaceOrderRequest;
+
+use super::PreTradeCheck;
+
+/// DDPI status and approved eDIS quantities, by security ID.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SellAuthorization {
+    ddpi: bool,
+    approved: HashMap<String, i64>,
+}
+
+impl SellAuthorization {
+    /// No DDPI and no approvals.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether DDPI is active.
+    pub fn with_ddpi(mut self, active: bool) -> Self {
+        self.ddpi = active;
+        self
+    }
+
+    /// Record `quantity` as eDIS-approved for `security_id`.
+    pub fn with_approval(mut self, security_id: impl Into<String>, quantity: i64) -> Self {
+        self.approved.insert(security_id.into(), quantity);
+        self
+    }
+
+    /// Returns `true` if DDPI is active.
+    pub fn ddpi(&self) -> bool {
+        self.ddpi
+    }
+
+    /// Approved eDIS quantity for `security_id`.
+    pub fn approved_qty(&self, security_id: &str) -> Option<i64> {
+        self.approved.get(security_id).copied()
+    }
+
+    /// Return `Err(reason)` if `req` is a CNC sell not covered by DDPI or
+    /// an eDIS approval. Other orders always pass.
+    pub fn verify(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        if !needs_authorization(req) || self.ddpi {
+            return Ok(());
+        }
+        match self.approved_qty(&req.security_id) {
+            Some(approved) if approved >= req.quantity as i64 => Ok(()),
+            Some(approved) => Err(format!(
+                "eDIS approved for {approved} of {} shares of security {}",
+                req.quantity, req.security_id
+            )),
+            None => Err(format!(
+                "no eDIS approval for security {} and DDPI is not active",
+                req.security_id
+            )),
+        }
+    }
+}
+
+/// Returns `true` for orders that need eDIS or DDPI: delivery sells.
+pub fn needs_authorization(req: &PlaceOrderRequest) -> bool {
+    req.transaction_type == TransactionType::SELL && req.product_type == ProductType::CNC
+}
+
+/// [`PreTradeCheck`] against a [`SellAuthorization`] refreshed from the API.
+///
+/// Cloning is cheap and clones share the state.
+#[derive(Debug, Clone, Default)]
+pub struct SellAuthorizationCheck {
+    state: Arc<RwLock<SellAuthorization>>,
+}
+
+impl SellAuthorizationCheck {
+    /// Check against `state`.
+    pub fn new(state: SellAuthorization) -> Self {
+        Self {
+            state: Arc::new(RwLock::new(state)),
+        }
+    }
+
+    /// Current state.
+    pub fn state(&self) -> SellAuthorization {
+        self.state.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the state.
+    pub fn set(&self, state: SellAuthorization) {
+        *self.state.write().unwrap_or_else(|e| e.into_inner()) = state;
+    }
+
+    /// Reload the state with [`DhanClient::sell_authorization`].
+    pub async fn refresh(&self, client: &DhanClient) -> Result<()> {
+        self.set(client.sell_authorization().await?);
+        Ok(())
+    }
+}
+
+impl PreTradeCheck for SellAuthorizationCheck {
+    fn name(&self) -> &str {
+        "sell_authorization"
+    }
+
+    fn check(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        self.state
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .verify(req)
+    }
+}
+
+impl DhanClient {
+    /// Fetch the DDPI flag and, without DDPI, the eDIS approval of every
+    /// holding.
+    pub async fn sell_authorization(&self) -> Result<SellAuthorization> {
+        let mut auth = SellAuthorization::new().with_ddpi(self.get_profile().await?.ddpi_active());
+        if auth.ddpi {
+            return Ok(auth);
+        }
+        for holding in self.get_holdings().await? {
+            let (Some(security_id), Some(isin)) = (holding.security_id, holding.isin) else {
+                continue;
+            };
+            let inquiry = self.inquire_edis(&isin).await?;
+            auth.approved
+                .insert(security_id, inquiry.aprvd_qty.unwrap_or(0));
+        }
+        Ok(auth)
+    }
+
+    /// Check that `req` can be sold: a no-op unless it is a CNC sell, in
+    /// which case DDPI must be active or the ISIN's eDIS approval must
+    /// cover the quantity.
+    ///
+    /// Fails with [`DhanError::AuthorizationMissing`] otherwise.
+    pub async fn check_sell_authorization(&self, req: &PlaceOrderRequest) -> Result<()> {
+        if !needs_authorization(req) || self.get_profile().await?.ddpi_active() {
+            return Ok(());
+        }
+        let isin = self
+            .get_holdings()
+            .await?
+            .into_iter()
+            .find(|h| h.security_id.as_deref() == Some(req.security_id.as_str()))
+            .and_then(|h| h.isin)
+            .ok_or_else(|| {
+                DhanError::AuthorizationMissing(format!(
+                    "security {} is not in holdings",
+                    req.security_id
+                ))
+            })?;
+        let approved = self.inquire_edis(&isin).await?.aprvd_qty.unwrap_or(0);
+        SellAuthorization::new()
+            .with_approval(req.security_id.clone(), approved)
+            .verify(req)
+            .map_err(DhanError::AuthorizationMissing)
+    }
+}
@@ -0,0 +1,197 @@
+//! Rejecting accidental double submits.
+//!
+//! A glitching signal or a retried request can place the same order twice
+//! within moments. [`DuplicateOrderGuard`] remembers the orders it passed
+//! for a time window and rejects another one with the same instrument,
+//! side, quantity and price until the window has passed. An intended
+//! repeat can be let through once with [`DuplicateOrderGuard::allow_next`],
+//! and an order the exchange rejected stops counting once its update is
+//! fed to [`DuplicateOrderGuard::on_update`] (or to an [`OrderTracker`]
+//! holding the guard), so it can be re-sent.
+//!
+//! The guard is a [`PreTradeCheck`] for a [`RiskEngine`], and can also be
+//! attached to a client with [`DhanClient::with_duplicate_guard`], which
+//! checks every placed and sliced order.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use dhan_rs::risk::duplicate::DuplicateOrderGuard;
+//! use dhan_rs::risk::RiskEngine;
+//!
+//! let guard = DuplicateOrderGuard::new(Duration::from_secs(5));
+//! let risk = RiskEngine::new().with_check(guard.clone());
+//! ```
+//!
+//! [`RiskEngine`]: super::RiskEngine
+//! [`OrderTracker`]: crate::oms::tracker::OrderTracker
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::risk::PreTradeCheck;
+use crate::types::enums::{ExchangeSegment, OrderStatus, TransactionType};
+use crate::types::orders::PlaceOrderRequest;
+use crate::ws::order_update::OrderUpdate;
+
+/// What makes two orders the same.
+#[derive(Debug, Clone, PartialEq)]
+struct Fingerprint {
+    segment: ExchangeSegment,
+    security_id: String,
+    side: TransactionType,
+    quantity: u64,
+    price: Option<f64>,
+}
+
+impl Fingerprint {
+    fn of(req: &PlaceOrderRequest) -> Self {
+        Self {
+            segment: req.exchange_segment,
+            security_id: req.security_id.clone(),
+            side: req.transaction_type,
+            quantity: req.quantity,
+            price: req.price.filter(|p| *p > 0.0),
+        }
+    }
+
+    /// Whether `update` describes an order with this fingerprint. Updates
+    /// carry the segment in the exchange's own notation, so it is not
+    /// compared.
+    fn matches(&self, update: &OrderUpdate) -> bool {
+        update.security_id.as_deref() == Some(self.security_id.as_str())
+            && update.transaction_type == Some(self.side)
+            && update.quantity == Some(self.quantity as i64)
+            && (self.price.is_none() || update.price == self.price)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Orders passed within the window, oldest first.
+    recent: VecDeque<(Instant, Fingerprint)>,
+    /// Fingerprints allowed through once despite a recent twin.
+    allowed: Vec<Fingerprint>,
+}
+
+/// Rejects an order identical to one passed within a time window.
+///
+/// Cloning is cheap and clones share the remembered orders.
+#[derive(Debug, Clone)]
+pub struct DuplicateOrderGuard {
+    window: Duration,
+    warn_only: bool,
+    state: Arc<Mutex<State>>,
+}
+
+impl DuplicateOrderGuard {
+    /// Reject repeats within `window` of the previous identical order.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            warn_only: false,
+            state: Arc::default(),
+        }
+    }
+
+    /// Only log a warning for a repeat instead of rejecting it.
+    pub fn warn_only(mut self) -> Self {
+        self.warn_only = true;
+        self
+    }
+
+    /// The window.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Let the next order identical to `req` through even if it repeats a
+    /// recent one.
+    pub fn allow_next(&self, req: &PlaceOrderRequest) {
+        self.lock().allowed.push(Fingerprint::of(req));
+    }
+
+    /// Forget the recent order an update is about if the exchange rejected
+    /// it, so it can be re-sent. Other updates are ignored.
+    pub fn on_update(&self, update: &OrderUpdate) {
+        if update.status != Some(OrderStatus::REJECTED) {
+            return;
+        }
+        let mut state = self.lock();
+        if let Some(pos) = state.recent.iter().rposition(|(_, f)| f.matches(update)) {
+            state.recent.remove(pos);
+        }
+    }
+
+    /// Check `req` and, if it passes, remember it.
+    pub fn check_order(&self, req: &PlaceOrderRequest) -> Result<()> {
+        self.admit(req)
+            .map_err(|reason| DhanError::RiskRejected(format!("{}: {reason}", self.name())))
+    }
+
+    fn admit(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        let now = Instant::now();
+        let fingerprint = Fingerprint::of(req);
+        let mut state = self.lock();
+        while state
+            .recent
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) >= self.window)
+        {
+            state.recent.pop_front();
+        }
+        let previous = state
+            .recent
+            .iter()
+            .rev()
+            .find(|(_, f)| *f == fingerprint)
+            .map(|(at, _)| now.duration_since(*at));
+        if let Some(ago) = previous {
+            if let Some(pos) = state.allowed.iter().position(|f| *f == fingerprint) {
+                state.allowed.remove(pos);
+            } else {
+                let reason = format!(
+                    "same order for security {} placed {ago:?} ago",
+                    req.security_id
+                );
+                if !self.warn_only {
+                    return Err(reason);
+                }
+                tracing::warn!(
+                    security_id = req.security_id,
+                    "Possible duplicate: {reason}"
+                );
+            }
+        }
+        state.recent.push_back((now, fingerprint));
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl PreTradeCheck for DuplicateOrderGuard {
+    fn name(&self) -> &str {
+        "duplicate_order"
+    }
+
+    fn check(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        self.admit(req)
+    }
+}
+
+impl DhanClient {
+    /// Check every order placed or sliced through this client (and its
+    /// clones) with `guard`; see [`crate::risk::duplicate`].
+    pub fn with_duplicate_guard(mut self, guard: DuplicateOrderGuard) -> Self {
+        self.set_duplicate_guard(guard);
+        self
+    }
+}
@@ -0,0 +1,362 @@
+//! Client-side pre-trade risk checks.
+//!
+//! A [`RiskEngine`] runs every order through a list of [`PreTradeCheck`]s
+//! before it is sent, returning [`DhanError::RiskRejected`] on the first
+//! failure. The engine can also be halted, which blocks all new orders
+//! until it is resumed, and carries [`RiskLimits`] that can be adjusted
+//! while it is in use.
+//!
+//! [`authorization`] adds a check that CNC sells are covered by eDIS
+//! approval or DDPI, and [`duplicate`] one that rejects an order repeated
+//! within a few seconds.
+//!
+//! ```
+//! use dhan_rs::risk::{MaxOrderQuantity, RiskEngine};
+//!
+//! let risk = RiskEngine::new().with_check(MaxOrderQuantity(500));
+//! assert!(!risk.is_halted());
+//! ```
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache::QuoteCache;
+use crate::error::{DhanError, Result};
+use crate::events::{EventBus, RiskEvent, SessionEvent};
+use crate::types::enums::ExchangeSegment;
+use crate::types::instrument::InstrumentId;
+use crate::types::orders::PlaceOrderRequest;
+
+pub mod authorization;
+pub mod duplicate;
+
+pub use authorization::{SellAuthorization, SellAuthorizationCheck};
+pub use duplicate::DuplicateOrderGuard;
+
+/// A single pre-trade rule.
+pub trait PreTradeCheck: Send + Sync {
+    /// Short name used in rejection messages and logs.
+    fn name(&self) -> &str;
+
+    /// Return `Err(reason)` to block `req`.
+    fn check(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String>;
+}
+
+/// Rejects orders above a quantity.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxOrderQuantity(pub u64);
+
+impl PreTradeCheck for MaxOrderQuantity {
+    fn name(&self) -> &str {
+        "max_order_quantity"
+    }
+
+    fn check(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        if req.quantity > self.0 {
+            return Err(format!(
+                "quantity {} exceeds limit {}",
+                req.quantity, self.0
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Rejects priced orders whose `price × quantity` exceeds a notional value.
+///
+/// Market orders carry no price and are not checked.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxOrderValue(pub f64);
+
+impl PreTradeCheck for MaxOrderValue {
+    fn name(&self) -> &str {
+        "max_order_value"
+    }
+
+    fn check(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        let Some(price) = req.price.or(req.trigger_price) else {
+            return Ok(());
+        };
+        let value = price * req.quantity as f64;
+        if value > self.0 {
+            return Err(format!(
+                "order value {value:.2} exceeds limit {:.2}",
+                self.0
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Rejects orders outside a set of exchange segments.
+#[derive(Debug, Clone)]
+pub struct AllowedSegments(pub Vec<ExchangeSegment>);
+
+impl PreTradeCheck for AllowedSegments {
+    fn name(&self) -> &str {
+        "allowed_segments"
+    }
+
+    fn check(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        if self.0.contains(&req.exchange_segment) {
+            return Ok(());
+        }
+        Err(format!("segment {} is not allowed", req.exchange_segment))
+    }
+}
+
+/// Checks limit and trigger prices against the instrument's circuit band.
+///
+/// Bands come from a [`QuoteCache`] (see
+/// [`QuoteCache::update_from_quotes`]); orders for instruments without a
+/// known band, and market orders, pass. By default out-of-band orders are
+/// rejected, since the exchange would reject them anyway;
+/// [`warn_only()`](Self::warn_only) just logs them.
+#[derive(Debug, Clone)]
+pub struct CircuitLimitCheck {
+    quotes: QuoteCache,
+    warn_only: bool,
+}
+
+impl CircuitLimitCheck {
+    /// Check against the bands in `quotes`.
+    pub fn new(quotes: QuoteCache) -> Self {
+        Self {
+            quotes,
+            warn_only: false,
+        }
+    }
+
+    /// Log out-of-band orders instead of rejecting them.
+    pub fn warn_only(mut self) -> Self {
+        self.warn_only = true;
+        self
+    }
+}
+
+impl PreTradeCheck for CircuitLimitCheck {
+    fn name(&self) -> &str {
+        "circuit_limit"
+    }
+
+    fn check(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        let Ok(security_id) = req.security_id.parse() else {
+            return Ok(());
+        };
+        let id = InstrumentId::new(req.exchange_segment, security_id);
+        let Some(band) = self.quotes.circuit_band(&id) else {
+            return Ok(());
+        };
+        for (label, price) in [("price", req.price), ("trigger price", req.trigger_price)] {
+            let Some(price) = price.filter(|p| *p > 0.0) else {
+                continue;
+            };
+            if band.contains(price) {
+                continue;
+            }
+            let reason = format!(
+                "{label} {price} is outside the circuit band {}–{}",
+                band.lower, band.upper
+            );
+            if self.warn_only {
+                tracing::warn!(security_id = req.security_id, "{reason}");
+            } else {
+                return Err(reason);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Caps the number of orders that may pass the engine — a per-strategy
+/// order budget.
+///
+/// Every order that passes this check consumes one unit, whether or not the
+/// API later accepts it.
+#[derive(Debug)]
+pub struct OrderCountBudget {
+    limit: u32,
+    used: AtomicU32,
+}
+
+impl OrderCountBudget {
+    /// Allow at most `limit` orders.
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            used: AtomicU32::new(0),
+        }
+    }
+
+    /// Orders still allowed.
+    pub fn remaining(&self) -> u32 {
+        self.limit.saturating_sub(self.used.load(Ordering::SeqCst))
+    }
+}
+
+impl PreTradeCheck for OrderCountBudget {
+    fn name(&self) -> &str {
+        "order_count_budget"
+    }
+
+    fn check(&self, _req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        let claimed = self
+            .used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                (used < self.limit).then_some(used + 1)
+            });
+        match claimed {
+            Ok(_) => Ok(()),
+            Err(_) => Err(format!("order budget of {} exhausted", self.limit)),
+        }
+    }
+}
+
+/// Limits that can be changed while a [`RiskEngine`] is in use.
+///
+/// `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RiskLimits {
+    /// Maximum quantity of a single order.
+    #[serde(default)]
+    pub max_order_quantity: Option<u64>,
+    /// Maximum `price × quantity` of a single priced order.
+    #[serde(default)]
+    pub max_order_value: Option<f64>,
+}
+
+impl RiskLimits {
+    fn check(&self, req: &PlaceOrderRequest) -> std::result::Result<(), String> {
+        if let Some(max) = self.max_order_quantity {
+            MaxOrderQuantity(max).check(req)?;
+        }
+        if let Some(max) = self.max_order_value {
+            MaxOrderValue(max).check(req)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs pre-trade checks and holds the trading halt flag.
+///
+/// Cloning is cheap and clones share the halt flag, limits and checks.
+#[derive(Clone, Default)]
+pub struct RiskEngine {
+    checks: Vec<Arc<dyn PreTradeCheck>>,
+    halted: Arc<AtomicBool>,
+    limits: Arc<RwLock<RiskLimits>>,
+    events: Option<EventBus>,
+}
+
+impl std::fmt::Debug for RiskEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RiskEngine")
+            .field(
+                "checks",
+                &self.checks.iter().map(|c| c.name()).collect::<Vec<_>>(),
+            )
+            .field("halted", &self.is_halted())
+            .field("limits", &self.limits())
+            .finish()
+    }
+}
+
+impl RiskEngine {
+    /// Create an engine with no checks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a check.
+    pub fn with_check(mut self, check: impl PreTradeCheck + 'static) -> Self {
+        self.checks.push(Arc::new(check));
+        self
+    }
+
+    /// Publish rejections, halts and limit changes on `bus`.
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
+    }
+
+    fn publish(&self, event: RiskEvent) {
+        if let Some(bus) = &self.events {
+            bus.publish(SessionEvent::Risk(event));
+        }
+    }
+
+    /// Block all new orders until [`resume()`](Self::resume) is called.
+    pub fn halt(&self) {
+        self.halted.store(true, Ordering::SeqCst);
+        tracing::warn!("Risk engine halted: new orders are blocked");
+        self.publish(RiskEvent::Halted);
+    }
+
+    /// Allow new orders again.
+    pub fn resume(&self) {
+        self.halted.store(false, Ordering::SeqCst);
+        tracing::info!("Risk engine resumed");
+        self.publish(RiskEvent::Resumed);
+    }
+
+    /// Returns `true` while new orders are blocked.
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+
+    /// Start with `limits`.
+    pub fn with_limits(self, limits: RiskLimits) -> Self {
+        self.set_limits(limits);
+        self
+    }
+
+    /// Current adjustable limits.
+    pub fn limits(&self) -> RiskLimits {
+        *self.limits.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replace the adjustable limits; applies to the next order checked.
+    pub fn set_limits(&self, limits: RiskLimits) {
+        *self.limits.write().unwrap_or_else(|e| e.into_inner()) = limits;
+        tracing::info!(?limits, "Risk limits updated");
+        self.publish(RiskEvent::LimitsChanged(limits));
+    }
+
+    /// Run every check against `req`.
+    pub fn check(&self, req: &PlaceOrderRequest) -> Result<()> {
+        let result = self.run_checks(req);
+        if let Err(DhanError::RiskRejected(reason)) = &result {
+            self.publish(RiskEvent::Rejected {
+                security_id: req.security_id.clone(),
+                reason: reason.clone(),
+            });
+        }
+        result
+    }
+
+    fn run_checks(&self, req: &PlaceOrderRequest) -> Result<()> {
+        if self.is_halted() {
+            return Err(DhanError::RiskRejected("trading is halted".into()));
+        }
+        if let Err(reason) = self.limits().check(req) {
+            tracing::warn!(security_id = req.security_id, "{reason}");
+            return Err(DhanError::RiskRejected(format!("limits: {reason}")));
+        }
+        for check in &self.checks {
+            if let Err(reason) = check.check(req) {
+                tracing::warn!(
+                    check = check.name(),
+                    security_id = req.security_id,
+                    "{reason}"
+                );
+                return Err(DhanError::RiskRejected(format!(
+                    "{}: {reason}",
+                    check.name()
+                )));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,406 @@
+//! Embedded HTTP control server for a running [`DhanSession`].
+//!
+//! Requires the `control` feature. [`ControlServer`] exposes a small JSON
+//! API over plain HTTP/1.1 so a deployed trading daemon can be inspected and
+//! steered without restarting it. Every request must carry
+//! `Authorization: Bearer <token>`.
+//!
+//! | Method | Path                          | Action                                 |
+//! |--------|-------------------------------|----------------------------------------|
+//! | `GET`  | `/health`                     | Uptime, cached quotes, strategy states |
+//! | `GET`  | `/strategies`                 | All strategies                         |
+//! | `GET`  | `/strategies/{name}`          | One strategy                           |
+//! | `POST` | `/strategies/{name}/pause`    | Withhold market data                   |
+//! | `POST` | `/strategies/{name}/resume`   | Deliver market data again              |
+//! | `POST` | `/strategies/{name}/halt`     | Halt the strategy's risk engine        |
+//! | `POST` | `/strategies/{name}/unhalt`   | Resume the strategy's risk engine      |
+//! | `PUT`  | `/strategies/{name}/limits`   | Replace its [`RiskLimits`] (JSON body) |
+//! | `POST` | `/flatten`                    | Halt everything and square off         |
+//!
+//! `/flatten` halts every strategy's risk engine first, so nothing re-enters
+//! while positions are being closed, then cancels every working order with
+//! [`DhanClient::cancel_all_orders`], so no resting entry fills afterwards,
+//! and finally runs [`SquareOffScheduler::square_off_now`] over positions of
+//! every product type. The response reports both steps. An optional body
+//! `{"dry_run": true}` cancels nothing and only reports the orders it would
+//! place.
+//!
+//! The server binds wherever it is told to; keep it on localhost or behind a
+//! TLS-terminating proxy — the token travels in clear text.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::runtime::control::ControlServer;
+//! use dhan_rs::runtime::session::DhanSession;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let session = DhanSession::new(DhanClient::new("client-id", "token"));
+//! let (addr, _server) = ControlServer::new(session.handle(), "s3cret")
+//!     .spawn("127.0.0.1:7878")
+//!     .await?;
+//! println!("control API on http://{addr}");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`DhanSession`]: crate::runtime::session::DhanSession
+//! [`DhanClient::cancel_all_orders`]: crate::DhanClient::cancel_all_orders
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinHandle;
+
+use crate::error::{DhanError, Result};
+use crate::oms::bulk::OrderFilter;
+use crate::risk::RiskLimits;
+use crate::runtime::session::SessionHandle;
+use crate::scheduler::square_off::{SquareOffConfig, SquareOffScheduler};
+use crate::types::enums::ProductType;
+
+/// Largest request (headers plus body) the server accepts.
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+/// Time allowed for a client to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Products `/flatten` closes: all of them.
+const FLATTEN_PRODUCT_TYPES: [ProductType; 6] = [
+    ProductType::CNC,
+    ProductType::INTRADAY,
+    ProductType::MARGIN,
+    ProductType::MTF,
+    ProductType::CO,
+    ProductType::BO,
+];
+
+/// Token-authenticated HTTP control API for a session.
+#[derive(Debug, Clone)]
+pub struct ControlServer {
+    session: SessionHandle,
+    token: String,
+    square_off: SquareOffConfig,
+}
+
+impl ControlServer {
+    /// Serve `session`, accepting requests bearing `token`.
+    pub fn new(session: SessionHandle, token: impl Into<String>) -> Self {
+        Self {
+            session,
+            token: token.into(),
+            square_off: SquareOffConfig::default(),
+        }
+    }
+
+    /// Square-off settings used by `/flatten` (defaults to
+    /// [`SquareOffConfig::default()`]). Its product types are ignored:
+    /// `/flatten` always closes every product.
+    pub fn with_square_off(mut self, config: SquareOffConfig) -> Self {
+        self.square_off = config;
+        self
+    }
+
+    /// Bind `addr` and serve on a background task.
+    ///
+    /// Returns the bound address (useful with port `0`) and the task handle.
+    pub async fn spawn(self, addr: impl ToSocketAddrs) -> Result<(SocketAddr, JoinHandle<()>)> {
+        if self.token.is_empty() {
+            return Err(DhanError::InvalidArgument(
+                "control server token must not be empty".into(),
+            ));
+        }
+        let listener = TcpListener::bind(addr).await?;
+        let local = listener.local_addr()?;
+        tracing::info!(addr = %local, "Control server listening");
+        Ok((local, tokio::spawn(self.serve(listener))))
+    }
+
+    /// Accept connections on `listener` forever.
+    pub async fn serve(self, listener: TcpListener) {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Control server accept failed");
+                    continue;
+                }
+            };
+            let server = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.handle_connection(stream).await {
+                    tracing::debug!(%peer, error = %e, "Control connection failed");
+                }
+            });
+        }
+    }
+
+    async fn handle_connection(&self, mut stream: TcpStream) -> Result<()> {
+        let (status, body) =
+            match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+                Ok(Ok(req)) => self.respond(&req).await,
+                Ok(Err(e)) => (400, error_body(&e.to_string())),
+                Err(_) => (408, error_body("request timed out")),
+            };
+        let body = body.to_string();
+        let head = format!(
+            "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            reason_phrase(status),
+            body.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(body.as_bytes()).await?;
+        stream.shutdown().await?;
+        Ok(())
+    }
+
+    async fn respond(&self, req: &Request) -> (u16, Value) {
+        if !self.authorized(req) {
+            return (401, error_body("missing or invalid bearer token"));
+        }
+        tracing::info!(method = req.method, path = req.path, "Control request");
+        match self.route(req).await {
+            Ok(body) => (200, body),
+            Err(Route::NotFound) => (404, error_body("not found")),
+            Err(Route::MethodNotAllowed) => (405, error_body("method not allowed")),
+            Err(Route::Failed(e)) => {
+                let status = match e {
+                    DhanError::InvalidArgument(_) | DhanError::Json(_) => 400,
+                    _ => 502,
+                };
+                (status, error_body(&e.to_string()))
+            }
+        }
+    }
+
+    async fn route(&self, req: &Request) -> std::result::Result<Value, Route> {
+        let segments: Vec<&str> = req.path.split('/').filter(|s| !s.is_empty()).collect();
+        let method = req.method.as_str();
+        match segments.as_slice() {
+            ["health"] => {
+                expect_method(method, "GET")?;
+                Ok(json!({
+                    "status": "ok",
+                    "uptime_secs": self.session.uptime().as_secs(),
+                    "cached_quotes": self.session.quotes().len(),
+                    "strategies": self.session.strategies(),
+                }))
+            }
+            ["strategies"] => {
+                expect_method(method, "GET")?;
+                Ok(json!(self.session.strategies()))
+            }
+            ["strategies", name] => {
+                expect_method(method, "GET")?;
+                Ok(json!(self.session.strategy(name)?))
+            }
+            ["strategies", name, action] => {
+                match (*action, method) {
+                    ("pause", "POST") => self.session.pause(name)?,
+                    ("resume", "POST") => self.session.resume(name)?,
+                    ("halt", "POST") => self.session.risk(name)?.halt(),
+                    ("unhalt", "POST") => self.session.risk(name)?.resume(),
+                    ("limits", "PUT") => {
+                        let limits: RiskLimits =
+                            serde_json::from_slice(&req.body).map_err(DhanError::from)?;
+                        self.session.risk(name)?.set_limits(limits);
+                    }
+                    ("pause" | "resume" | "halt" | "unhalt" | "limits", _) => {
+                        return Err(Route::MethodNotAllowed);
+                    }
+                    _ => return Err(Route::NotFound),
+                }
+                Ok(json!(self.session.strategy(name)?))
+            }
+            ["flatten"] => {
+                expect_method(method, "POST")?;
+                self.flatten(&req.body).await
+            }
+            _ => Err(Route::NotFound),
+        }
+    }
+
+    async fn flatten(&self, body: &[u8]) -> std::result::Result<Value, Route> {
+        let mut config = self.square_off.clone();
+        config.product_types = FLATTEN_PRODUCT_TYPES.to_vec();
+        if !body.is_empty() {
+            let opts: Value = serde_json::from_slice(body).map_err(DhanError::from)?;
+            if let Some(dry_run) = opts.get("dry_run").and_then(Value::as_bool) {
+                config.dry_run = dry_run;
+            }
+        }
+        let mut cancelled = Vec::new();
+        let mut cancel_failed = Vec::new();
+        if !config.dry_run {
+            self.session.halt_all();
+            match self
+                .session
+                .client()
+                .cancel_all_orders(&OrderFilter::new())
+                .await
+            {
+                Ok(cancels) => {
+                    cancelled.extend(cancels.succeeded.into_iter().map(|r| r.order_id));
+                    cancel_failed.extend(
+                        cancels
+                            .failed
+                            .into_iter()
+                            .map(|(order, e)| (order.order_id, e.to_string())),
+                    );
+                }
+                // The order book could not be read; still close positions.
+                Err(e) => cancel_failed.push((None, e.to_string())),
+            }
+        }
+        let report = SquareOffScheduler::new(self.session.client().clone(), config)
+            .square_off_now()
+            .await?;
+        Ok(json!({
+            "dry_run": report.dry_run,
+            "cancelled": cancelled,
+            "cancel_failed": cancel_failed,
+            "flat": report.is_flat(),
+            "attempts": report.attempts,
+            "planned": report.planned,
+            "placed": report.orders.succeeded.iter().map(|r| &r.order_id).collect::<Vec<_>>(),
+            "failed": report
+                .orders
+                .failed
+                .iter()
+                .map(|(order, e)| (&order.security_id, e.to_string()))
+                .collect::<Vec<_>>(),
+            "remaining": report.remaining.len(),
+        }))
+    }
+
+    fn authorized(&self, req: &Request) -> bool {
+        req.header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.trim().as_bytes(), self.token.as_bytes()))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Minimal HTTP/1.1 parsing
+// ---------------------------------------------------------------------------
+
+enum Route {
+    NotFound,
+    MethodNotAllowed,
+    Failed(DhanError),
+}
+
+impl From<DhanError> for Route {
+    fn from(e: DhanError) -> Self {
+        Self::Failed(e)
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    let mut buf = Vec::with_capacity(1024);
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() >= MAX_REQUEST_BYTES {
+            return Err(DhanError::InvalidArgument("request too large".into()));
+        }
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(DhanError::InvalidArgument(
+                "connection closed mid-request".into(),
+            ));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = std::str::from_utf8(&buf[..header_end])
+        .map_err(|_| DhanError::InvalidArgument("request head is not UTF-8".into()))?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Err(DhanError::InvalidArgument("malformed request line".into()));
+    };
+    let path = target.split('?').next().unwrap_or_default().to_owned();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_owned(), v.trim().to_owned()))
+        .collect();
+
+    let mut req = Request {
+        method: method.to_ascii_uppercase(),
+        path,
+        headers,
+        body: buf[header_end + 4..].to_vec(),
+    };
+    let content_length: usize = match req.header("content-length") {
+        Some(v) => v
+            .parse()
+            .map_err(|_| DhanError::InvalidArgument("invalid Content-Length".into()))?,
+        None => 0,
+    };
+    if header_end + 4 + content_length > MAX_REQUEST_BYTES {
+        return Err(DhanError::InvalidArgument("request too large".into()));
+    }
+    while req.body.len() < content_length {
+        let mut chunk = [0u8; 4096];
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(DhanError::InvalidArgument(
+                "connection closed mid-body".into(),
+            ));
+        }
+        req.body.extend_from_slice(&chunk[..n]);
+    }
+    req.body.truncate(content_length);
+    Ok(req)
+}
+
+fn expect_method(method: &str, expected: &str) -> std::result::Result<(), Route> {
+    if method == expected {
+        Ok(())
+    } else {
+        Err(Route::MethodNotAllowed)
+    }
+}
+
+fn error_body(message: &str) -> Value {
+    json!({ "error": message })
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        _ => "Bad Gateway",
+    }
+}
+
+/// Compare secrets without short-circuiting on the first differing byte.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
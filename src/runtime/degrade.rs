@@ -0,0 +1,174 @@
+//! Safe mode while the data APIs are failing.
+//!
+//! Strategies that size or time entries from quotes and candles should not
+//! open positions while those endpoints are down, but must still be able to
+//! close what they hold. In [`SafeMode`] a runner refuses every order that
+//! does not reduce the strategy's net position in its instrument (as seen by
+//! its [`OrderTracker`]) with [`DhanError::RiskRejected`]; exits and
+//! cancellations go through. [`StrategyContext::in_safe_mode`] lets a
+//! strategy notice and stand down.
+//!
+//! A [`DegradationPolicy`] attached to a session (see
+//! [`DhanSession::with_degradation`]) enters safe mode while a
+//! [`CircuitBreaker`] is not closed, leaves it when the breaker closes, and
+//! publishes [`RiskEvent::SafeModeEntered`] / [`RiskEvent::SafeModeExited`].
+//! With [`SafeModeAction::HaltTrading`] it halts the strategies' risk
+//! engines instead, blocking exits too.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::circuit::CircuitBreaker;
+//! use dhan_rs::events::EventBus;
+//! use dhan_rs::runtime::degrade::DegradationPolicy;
+//! use dhan_rs::runtime::session::DhanSession;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let breaker = CircuitBreaker::new();
+//! let client = DhanClient::new("client-id", "token").with_circuit_breaker(breaker.clone());
+//! let session = DhanSession::new(client)
+//!     .with_degradation(DegradationPolicy::new(breaker).with_event_bus(EventBus::new()));
+//! # }
+//! ```
+//!
+//! [`OrderTracker`]: crate::oms::tracker::OrderTracker
+//! [`DhanError::RiskRejected`]: crate::error::DhanError::RiskRejected
+//! [`StrategyContext::in_safe_mode`]: super::StrategyContext::in_safe_mode
+//! [`DhanSession::with_degradation`]: super::session::DhanSession::with_degradation
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::circuit::{CircuitBreaker, CircuitState};
+use crate::events::{EventBus, RiskEvent, SessionEvent};
+use crate::oms::tracker::OrderTracker;
+use crate::risk::RiskEngine;
+use crate::runtime::session::SessionHandle;
+use crate::types::enums::TransactionType;
+use crate::types::orders::PlaceOrderRequest;
+
+/// Shared on/off switch for safe mode.
+///
+/// Cloning is cheap and clones share the switch.
+#[derive(Debug, Clone, Default)]
+pub struct SafeMode(Arc<AtomicBool>);
+
+impl SafeMode {
+    /// A switch that starts off.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refuse new entries.
+    pub fn enter(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Allow new entries again.
+    pub fn exit(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether new entries are refused.
+    pub fn is_active(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// What a [`DegradationPolicy`] does while the circuit is not closed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SafeModeAction {
+    /// Refuse new entries; exits still go through.
+    #[default]
+    BlockEntries,
+    /// Also halt the risk engines of all strategies, refusing every order.
+    /// Only engines halted by the policy are resumed afterwards.
+    HaltTrading,
+}
+
+/// Ties a session's safe mode to a circuit breaker.
+#[derive(Debug, Clone)]
+pub struct DegradationPolicy {
+    breaker: CircuitBreaker,
+    action: SafeModeAction,
+    events: Option<EventBus>,
+}
+
+impl DegradationPolicy {
+    /// Block entries while `breaker` is open or half-open.
+    pub fn new(breaker: CircuitBreaker) -> Self {
+        Self {
+            breaker,
+            action: SafeModeAction::default(),
+            events: None,
+        }
+    }
+
+    /// Take `action` instead.
+    pub fn with_action(mut self, action: SafeModeAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Publish safe mode changes on `bus`.
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
+    }
+
+    /// Follow the breaker until it is dropped.
+    pub(crate) async fn supervise(self, handle: SessionHandle) {
+        let mut states = self.breaker.subscribe();
+        let mut active = false;
+        let mut halted: Vec<RiskEngine> = Vec::new();
+        loop {
+            let state = *states.borrow_and_update();
+            let degraded = state != CircuitState::Closed;
+            if degraded && !active {
+                active = true;
+                handle.safe_mode().enter();
+                if self.action == SafeModeAction::HaltTrading {
+                    for info in handle.strategies() {
+                        if let Ok(risk) = handle.risk(&info.name) {
+                            if !risk.is_halted() {
+                                risk.halt();
+                                halted.push(risk);
+                            }
+                        }
+                    }
+                }
+                let reason = format!("data circuit breaker {state:?}");
+                tracing::warn!(reason, "Session entering safe mode");
+                self.publish(RiskEvent::SafeModeEntered { reason });
+            } else if !degraded && active {
+                active = false;
+                handle.safe_mode().exit();
+                for risk in halted.drain(..) {
+                    risk.resume();
+                }
+                tracing::info!("Session leaving safe mode");
+                self.publish(RiskEvent::SafeModeExited);
+            }
+            if states.changed().await.is_err() {
+                break;
+            }
+        }
+    }
+
+    fn publish(&self, event: RiskEvent) {
+        if let Some(bus) = &self.events {
+            bus.publish(SessionEvent::Risk(event));
+        }
+    }
+}
+
+/// Whether `req` only reduces the net position `tracker` has seen in its
+/// instrument.
+pub(crate) fn is_exit(tracker: &OrderTracker, req: &PlaceOrderRequest) -> bool {
+    let position = tracker.net_position(&req.security_id);
+    let reduces = match req.transaction_type {
+        TransactionType::BUY => position < 0,
+        TransactionType::SELL => position > 0,
+    };
+    reduces && req.quantity <= position.unsigned_abs()
+}
@@ -0,0 +1,616 @@
+//! Strategy runtime: wires strategies to feeds, order updates and risk.
+//!
+//! Implement [`Strategy`] and hand it to a [`StrategyRunner`]. The runner
+//! normalizes market feed packets into [`Tick`]s, aggregates them into
+//! [`Candle`]s, tracks orders with an [`OrderTracker`] and routes the
+//! orders a strategy submits through a [`RiskEngine`] before they reach the
+//! API.
+//!
+//! Hooks are synchronous: they inspect state through the
+//! [`StrategyContext`] and queue actions (place / cancel / stop) on it. The
+//! runner executes queued actions after each hook returns, and reports
+//! failures back through [`Strategy::on_error`].
+//!
+//! To run several strategies over one shared feed, see [`session`]; for
+//! refusing new entries while the data APIs are down, see [`degrade`]; for
+//! journaling each signal with the inputs behind it, see [`signals`]. With
+//! the `control` feature, `control` exposes a running session over HTTP.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::runtime::{RuntimeEvent, Strategy, StrategyContext, StrategyRunner};
+//! use dhan_rs::types::instrument::InstrumentId;
+//! use dhan_rs::types::enums::ExchangeSegment;
+//! use dhan_rs::ws::market_feed::Tick;
+//!
+//! struct PrintLtp;
+//!
+//! impl Strategy for PrintLtp {
+//!     fn name(&self) -> &str {
+//!         "print-ltp"
+//!     }
+//!
+//!     fn instruments(&self) -> Vec<InstrumentId> {
+//!         vec![InstrumentId::new(ExchangeSegment::NSE_EQ, 1333)]
+//!     }
+//!
+//!     fn on_tick(&mut self, _ctx: &mut StrategyContext, tick: &Tick) {
+//!         println!("{} {}", tick.instrument, tick.ltp);
+//!     }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! # let events = futures_util::stream::empty::<RuntimeEvent>();
+//! let client = DhanClient::new("client-id", "token");
+//! let runner = StrategyRunner::new(PrintLtp, client);
+//! runner.run(events).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "control")]
+pub mod control;
+pub mod degrade;
+pub mod session;
+pub mod signals;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures_util::{Stream, StreamExt};
+
+use crate::broker::Broker;
+use crate::cache::QuoteCache;
+use crate::candles::CandleAggregator;
+use crate::candles::history::CandleHistory;
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::oms::tags::MAX_CORRELATION_ID_LEN;
+use crate::oms::tracker::{OrderTracker, TrackedOrder};
+use crate::risk::RiskEngine;
+use crate::runtime::degrade::{SafeMode, is_exit};
+use crate::runtime::signals::{Signal, SignalLog, SignalOrder, SignalRecord};
+use crate::types::builders::PlaceOrderRequestBuilder;
+use crate::types::enums::{OrderType, TransactionType, Validity};
+use crate::types::historical::Candle;
+use crate::types::instrument::InstrumentId;
+use crate::types::orders::{OrderResponse, PlaceOrderRequest};
+use crate::ws::manager::DhanFeedManager;
+use crate::ws::market_feed::{MarketFeedEvent, Tick};
+use crate::ws::order_update::{OrderUpdate, OrderUpdateStream};
+
+// ---------------------------------------------------------------------------
+// Strategy trait
+// ---------------------------------------------------------------------------
+
+/// A trading strategy driven by the [`StrategyRunner`].
+///
+/// Every hook has a no-op default, so strategies only implement what they
+/// need.
+#[allow(unused_variables)]
+pub trait Strategy: Send {
+    /// Name used in logs.
+    fn name(&self) -> &str;
+
+    /// Instruments the strategy wants market data for.
+    fn instruments(&self) -> Vec<InstrumentId> {
+        Vec::new()
+    }
+
+    /// Called once before any event is delivered.
+    fn on_start(&mut self, ctx: &mut StrategyContext) {}
+
+    /// Called for every Ticker / Quote / Full update.
+    fn on_tick(&mut self, ctx: &mut StrategyContext, tick: &Tick) {}
+
+    /// Called when a bar of the runner's candle interval completes.
+    fn on_candle(&mut self, ctx: &mut StrategyContext, instrument: InstrumentId, candle: &Candle) {}
+
+    /// Called for every order update.
+    fn on_order_update(&mut self, ctx: &mut StrategyContext, update: &OrderUpdate) {}
+
+    /// Called when a queued action fails (risk rejection, API error, …).
+    fn on_error(&mut self, ctx: &mut StrategyContext, error: &DhanError) {
+        tracing::warn!(strategy = self.name(), "Strategy action failed: {error}");
+    }
+
+    /// Called once after the last event.
+    fn on_stop(&mut self, ctx: &mut StrategyContext) {}
+}
+
+// ---------------------------------------------------------------------------
+// Context
+// ---------------------------------------------------------------------------
+
+/// An action queued by a strategy hook.
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// Place an order (after risk checks).
+    Place(PlaceOrderRequest),
+    /// Cancel an order by Dhan order ID.
+    Cancel(String),
+    /// Journal a signal, linking the orders queued after it.
+    Signal(Signal),
+}
+
+/// State and action queue handed to every [`Strategy`] hook.
+#[derive(Debug)]
+pub struct StrategyContext {
+    client_id: String,
+    correlation_prefix: Option<String>,
+    ticks: HashMap<InstrumentId, Tick>,
+    quotes: QuoteCache,
+    tracker: OrderTracker,
+    safe_mode: SafeMode,
+    actions: Vec<Action>,
+    stop_requested: bool,
+}
+
+impl StrategyContext {
+    fn new(client_id: &str) -> Self {
+        Self {
+            client_id: client_id.to_owned(),
+            correlation_prefix: None,
+            ticks: HashMap::new(),
+            quotes: QuoteCache::new(),
+            tracker: OrderTracker::new(),
+            safe_mode: SafeMode::new(),
+            actions: Vec::new(),
+            stop_requested: false,
+        }
+    }
+
+    /// The Dhan client ID orders are placed for.
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Prefix added to the correlation ID of every order this strategy
+    /// places, if the runner has one.
+    pub fn correlation_prefix(&self) -> Option<&str> {
+        self.correlation_prefix.as_deref()
+    }
+
+    /// Shared quote cache (latest tick of every instrument on the feed).
+    pub fn quotes(&self) -> &QuoteCache {
+        &self.quotes
+    }
+
+    /// Latest tick for `instrument` delivered to this strategy.
+    pub fn last_tick(&self, instrument: &InstrumentId) -> Option<&Tick> {
+        self.ticks.get(instrument)
+    }
+
+    /// Latest known state of an order.
+    pub fn order(&self, order_id: &str) -> Option<&TrackedOrder> {
+        self.tracker.get(order_id)
+    }
+
+    /// The order tracker fed by the runner.
+    pub fn tracker(&self) -> &OrderTracker {
+        &self.tracker
+    }
+
+    /// Whether new entries are currently refused (see [`degrade`]).
+    pub fn in_safe_mode(&self) -> bool {
+        self.safe_mode.is_active()
+    }
+
+    /// Queue an order for placement.
+    pub fn place_order(&mut self, req: PlaceOrderRequest) {
+        self.actions.push(Action::Place(req));
+    }
+
+    /// A market buy of `quantity` of `instrument` for the day, ready for a
+    /// product type: finish it and pass it to [`Self::place_order`].
+    ///
+    /// ```
+    /// # use dhan_rs::runtime::StrategyContext;
+    /// # use dhan_rs::types::enums::ProductType;
+    /// # use dhan_rs::ws::market_feed::Tick;
+    /// # fn on_tick(ctx: &mut StrategyContext, tick: &Tick) -> dhan_rs::Result<()> {
+    /// let order = ctx
+    ///     .buy_at_market(tick.instrument, 1)
+    ///     .product_type(ProductType::INTRADAY)
+    ///     .build()?;
+    /// ctx.place_order(order);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn buy_at_market(
+        &self,
+        instrument: InstrumentId,
+        quantity: u64,
+    ) -> PlaceOrderRequestBuilder {
+        self.market_order(instrument, TransactionType::BUY, quantity)
+    }
+
+    /// A market sell of `quantity` of `instrument`; see
+    /// [`Self::buy_at_market`].
+    pub fn sell_at_market(
+        &self,
+        instrument: InstrumentId,
+        quantity: u64,
+    ) -> PlaceOrderRequestBuilder {
+        self.market_order(instrument, TransactionType::SELL, quantity)
+    }
+
+    fn market_order(
+        &self,
+        instrument: InstrumentId,
+        side: TransactionType,
+        quantity: u64,
+    ) -> PlaceOrderRequestBuilder {
+        instrument
+            .order_seed()
+            .dhan_client_id(self.client_id.as_str())
+            .transaction_type(side)
+            .order_type(OrderType::MARKET)
+            .validity(Validity::DAY)
+            .quantity(quantity)
+    }
+
+    /// Queue a cancellation.
+    pub fn cancel_order(&mut self, order_id: impl Into<String>) {
+        self.actions.push(Action::Cancel(order_id.into()));
+    }
+
+    /// Journal `signal` (see [`signals`]). Orders queued after it in the
+    /// same hook, up to the next signal, are recorded as its orders.
+    pub fn log_signal(&mut self, signal: Signal) {
+        self.actions.push(Action::Signal(signal));
+    }
+
+    /// Ask the runner to stop after the current event.
+    pub fn stop(&mut self) {
+        self.stop_requested = true;
+    }
+
+    /// Actions queued but not yet executed.
+    pub fn pending_actions(&self) -> &[Action] {
+        &self.actions
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Runner
+// ---------------------------------------------------------------------------
+
+/// An input to the runtime.
+#[derive(Debug, Clone)]
+pub enum RuntimeEvent {
+    /// A parsed market feed packet.
+    Market(MarketFeedEvent),
+    /// A normalized order update (boxed: it is much larger than a tick).
+    Order(Box<OrderUpdate>),
+}
+
+/// Stream the parsed events of every connection of `manager` as
+/// [`RuntimeEvent::Market`]s.
+///
+/// Slow consumers that lag behind the broadcast channel skip the missed
+/// packets (with a warning) rather than ending the stream.
+pub fn feed_events(manager: &DhanFeedManager) -> impl Stream<Item = RuntimeEvent> + use<> {
+    manager
+        .merged_stream()
+        .map(|(_, ev)| RuntimeEvent::Market(ev))
+}
+
+/// Stream an [`OrderUpdateStream`] as [`RuntimeEvent::Order`]s.
+///
+/// Messages that fail to parse are logged and skipped.
+pub fn order_events(updates: OrderUpdateStream) -> impl Stream<Item = RuntimeEvent> {
+    updates.filter_map(|msg| async move {
+        match msg {
+            Ok(m) => Some(RuntimeEvent::Order(Box::new(m.into_update()))),
+            Err(e) => {
+                tracing::warn!("Skipping order update: {e}");
+                None
+            }
+        }
+    })
+}
+
+/// Drives one [`Strategy`] from a stream of [`RuntimeEvent`]s.
+///
+/// Combine [`feed_events`] and [`order_events`] with
+/// `futures_util::stream::select` to run against live data.
+pub struct StrategyRunner<S> {
+    strategy: S,
+    broker: Arc<dyn Broker>,
+    risk: RiskEngine,
+    candles: CandleAggregator,
+    history: Option<CandleHistory>,
+    signals: Option<SignalLog>,
+    ctx: StrategyContext,
+    started: bool,
+    order_seq: u64,
+}
+
+impl<S: Strategy> StrategyRunner<S> {
+    /// Create a runner with no risk checks and 1-minute candles.
+    pub fn new(strategy: S, client: DhanClient) -> Self {
+        let ctx = StrategyContext::new(client.client_id());
+        Self {
+            strategy,
+            broker: Arc::new(client),
+            risk: RiskEngine::new(),
+            candles: CandleAggregator::new(60),
+            history: None,
+            signals: None,
+            ctx,
+            started: false,
+            order_seq: 0,
+        }
+    }
+
+    /// Tag every order with `prefix` in its correlation ID, giving the
+    /// strategy its own order namespace.
+    ///
+    /// A request without a correlation ID gets `"{prefix}{sequence}"`; an
+    /// existing one is prefixed. Orders whose prefixed ID would exceed
+    /// [`MAX_CORRELATION_ID_LEN`] are refused with
+    /// [`DhanError::InvalidArgument`] and reported through
+    /// [`Strategy::on_error`].
+    pub fn with_correlation_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.ctx.correlation_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Send orders to `broker` instead of the client, e.g. a
+    /// [`PaperBroker`](crate::broker::paper::PaperBroker) to run the
+    /// strategy without trading.
+    pub fn with_broker(mut self, broker: impl Broker + 'static) -> Self {
+        self.broker = Arc::new(broker);
+        self
+    }
+
+    /// Read and update `quotes` instead of a private cache.
+    pub fn with_quote_cache(mut self, quotes: QuoteCache) -> Self {
+        self.ctx.quotes = quotes;
+        self
+    }
+
+    /// Route orders through `risk`.
+    pub fn with_risk(mut self, risk: RiskEngine) -> Self {
+        self.risk = risk;
+        self
+    }
+
+    /// Refuse orders that open or add to a position while `safe_mode` is
+    /// on (see [`degrade`]).
+    pub fn with_safe_mode(mut self, safe_mode: SafeMode) -> Self {
+        self.ctx.safe_mode = safe_mode;
+        self
+    }
+
+    /// Deliver candles of `secs` seconds to [`Strategy::on_candle`].
+    pub fn with_candle_interval(mut self, secs: u32) -> Self {
+        self.candles = CandleAggregator::new(secs);
+        self
+    }
+
+    /// Build candles through `history` instead of an in-memory aggregator.
+    ///
+    /// The candles already in `history` (typically backfilled with
+    /// [`CandleHistory::backfill`]) are replayed to
+    /// [`Strategy::on_candle`] on start, then live candles continue the
+    /// series and are persisted. Overrides
+    /// [`with_candle_interval()`](Self::with_candle_interval).
+    pub fn with_candle_history(mut self, history: CandleHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Journal the signals the strategy logs to `log`; without one they
+    /// are only traced.
+    pub fn with_signal_log(mut self, log: SignalLog) -> Self {
+        self.signals = Some(log);
+        self
+    }
+
+    /// The candle history, if one was attached.
+    pub fn candle_history(&self) -> Option<&CandleHistory> {
+        self.history.as_ref()
+    }
+
+    /// The strategy.
+    pub fn strategy(&self) -> &S {
+        &self.strategy
+    }
+
+    /// The risk engine.
+    pub fn risk(&self) -> &RiskEngine {
+        &self.risk
+    }
+
+    /// Whether a hook asked the runner to stop.
+    pub fn stop_requested(&self) -> bool {
+        self.ctx.stop_requested
+    }
+
+    /// Call [`Strategy::on_start`] (once) and execute its actions.
+    pub async fn start(&mut self) {
+        if self.started {
+            return;
+        }
+        self.started = true;
+        tracing::info!(strategy = self.strategy.name(), "Strategy started");
+        self.strategy.on_start(&mut self.ctx);
+        if let Some(history) = &self.history {
+            for id in history.instruments() {
+                for candle in history.candles(id) {
+                    self.strategy.on_candle(&mut self.ctx, *id, candle);
+                }
+            }
+        }
+        self.execute_actions().await;
+    }
+
+    /// Dispatch one event to the strategy and execute the resulting actions.
+    pub async fn handle_event(&mut self, event: RuntimeEvent) {
+        match event {
+            RuntimeEvent::Market(ev) => {
+                let Some(tick) = ev.to_tick() else {
+                    return;
+                };
+                self.ctx.ticks.insert(tick.instrument, tick);
+                self.ctx.quotes.update(&tick);
+                self.strategy.on_tick(&mut self.ctx, &tick);
+                let closed = match &mut self.history {
+                    Some(history) => history.on_tick(&tick).unwrap_or_else(|e| {
+                        self.strategy.on_error(&mut self.ctx, &e);
+                        None
+                    }),
+                    None => self.candles.on_tick(&tick),
+                };
+                if let Some(candle) = closed {
+                    self.strategy
+                        .on_candle(&mut self.ctx, tick.instrument, &candle);
+                }
+            }
+            RuntimeEvent::Order(update) => {
+                self.ctx.tracker.apply(&update);
+                self.strategy.on_order_update(&mut self.ctx, &update);
+            }
+        }
+        self.execute_actions().await;
+    }
+
+    /// Call [`Strategy::on_stop`] and execute its actions.
+    pub async fn stop(&mut self) {
+        self.strategy.on_stop(&mut self.ctx);
+        self.execute_actions().await;
+        tracing::info!(strategy = self.strategy.name(), "Strategy stopped");
+    }
+
+    /// Run until `events` ends or the strategy calls
+    /// [`StrategyContext::stop`], then return the strategy.
+    pub async fn run(mut self, events: impl Stream<Item = RuntimeEvent>) -> Result<S> {
+        let mut events = std::pin::pin!(events);
+        self.start().await;
+        while !self.ctx.stop_requested {
+            let Some(event) = events.next().await else {
+                break;
+            };
+            self.handle_event(event).await;
+        }
+        self.stop().await;
+        Ok(self.strategy)
+    }
+
+    /// Execute queued actions, reporting failures to the strategy.
+    async fn execute_actions(&mut self) {
+        // Errors reported via on_error may queue further actions; bound the
+        // loop so a strategy that retries forever cannot stall the runner.
+        for _ in 0..8 {
+            let actions = std::mem::take(&mut self.ctx.actions);
+            if actions.is_empty() {
+                return;
+            }
+            let mut signal = None;
+            for action in actions {
+                match action {
+                    Action::Place(req) => {
+                        let (req, result) = match self.namespaced(&req) {
+                            Ok(req) => {
+                                let result = self.place(&req).await;
+                                (req, result)
+                            }
+                            Err(e) => (req, Err(e)),
+                        };
+                        if let Some(record) = &mut signal {
+                            push_order(record, &req, &result);
+                        }
+                        if let Err(e) = result {
+                            self.strategy.on_error(&mut self.ctx, &e);
+                        }
+                    }
+                    Action::Cancel(order_id) => {
+                        if let Err(e) = self.broker.cancel_order(&order_id).await {
+                            self.strategy.on_error(&mut self.ctx, &e);
+                        }
+                    }
+                    Action::Signal(next) => {
+                        self.journal_signal(signal.take());
+                        let ticks = &self.ctx.ticks;
+                        signal = Some(
+                            next.into_record(self.strategy.name(), |id| ticks.get(id).copied()),
+                        );
+                    }
+                }
+            }
+            self.journal_signal(signal);
+        }
+    }
+
+    async fn place(&mut self, req: &PlaceOrderRequest) -> Result<OrderResponse> {
+        if self.ctx.safe_mode.is_active() && !is_exit(&self.ctx.tracker, req) {
+            return Err(DhanError::RiskRejected(
+                "safe mode: new entries are paused".into(),
+            ));
+        }
+        self.risk.check(req)?;
+        let resp = self.broker.place_order(req).await?;
+        tracing::info!(
+            strategy = self.strategy.name(),
+            order_id = resp.order_id,
+            "Strategy order placed"
+        );
+        Ok(resp)
+    }
+
+    /// Write a completed signal to the signal log, if there is one.
+    fn journal_signal(&mut self, record: Option<SignalRecord>) {
+        let Some(record) = record else {
+            return;
+        };
+        tracing::debug!(
+            strategy = record.strategy,
+            signal = record.name,
+            orders = record.orders.len(),
+            "Strategy signal"
+        );
+        if let Some(log) = &self.signals {
+            if let Err(e) = log.record(&record) {
+                self.strategy.on_error(&mut self.ctx, &e);
+            }
+        }
+    }
+
+    /// Apply the correlation prefix to `req`.
+    ///
+    /// Fails rather than truncating when the prefixed ID would exceed
+    /// [`MAX_CORRELATION_ID_LEN`], since a cut ID no longer carries the
+    /// strategy's namespace.
+    fn namespaced(&mut self, req: &PlaceOrderRequest) -> Result<PlaceOrderRequest> {
+        let mut req = req.clone();
+        if let Some(prefix) = &self.ctx.correlation_prefix {
+            self.order_seq += 1;
+            let id = match req.correlation_id.take() {
+                Some(id) if id.starts_with(prefix.as_str()) => id,
+                Some(id) => format!("{prefix}{id}"),
+                None => format!("{prefix}{}", self.order_seq),
+            };
+            if id.len() > MAX_CORRELATION_ID_LEN {
+                return Err(DhanError::InvalidArgument(format!(
+                    "correlation ID {id:?} exceeds {MAX_CORRELATION_ID_LEN} characters"
+                )));
+            }
+            req.correlation_id = Some(id);
+        }
+        Ok(req)
+    }
+}
+
+/// Link the outcome of placing `req` to `record`.
+fn push_order(record: &mut SignalRecord, req: &PlaceOrderRequest, result: &Result<OrderResponse>) {
+    record.orders.push(SignalOrder {
+        correlation_id: req.correlation_id.clone(),
+        order_id: result.as_ref().ok().map(|r| r.order_id.clone()),
+        error: result.as_ref().err().map(ToString::to_string),
+    });
+}
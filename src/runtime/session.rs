@@ -0,0 +1,576 @@
+//! Multi-strategy sessions sharing one data layer.
+//!
+//! A [`DhanSession`] runs several [`Strategy`]s in one process. All of them
+//! share the session's feed and [`QuoteCache`], while each keeps:
+//!
+//! - its own **order namespace** — a correlation-ID prefix derived from the
+//!   strategy name, used to route order updates back to their owner;
+//! - its own **risk budget** — a dedicated [`RiskEngine`];
+//! - its own **lifecycle** — strategies can be paused, resumed and stopped
+//!   independently.
+//!
+//! All strategies share the session's [`SafeMode`] switch, which a
+//! [`DegradationPolicy`] flips while the data APIs are failing.
+//!
+//! Each strategy runs on its own Tokio task, so a slow strategy only delays
+//! itself. Market data for a strategy that falls behind is dropped (with a
+//! warning); order updates are always delivered.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::risk::{OrderCountBudget, RiskEngine};
+//! use dhan_rs::runtime::session::DhanSession;
+//! use dhan_rs::runtime::{Strategy, feed_events, order_events};
+//! use dhan_rs::types::enums::FeedRequestCode;
+//! use dhan_rs::ws::manager::DhanFeedManagerBuilder;
+//! use dhan_rs::ws::order_update::OrderUpdateStream;
+//!
+//! # struct Momentum; impl Strategy for Momentum { fn name(&self) -> &str { "momentum" } }
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let mut session = DhanSession::new(client);
+//! session.add_strategy(Momentum, RiskEngine::new().with_check(OrderCountBudget::new(50)))?;
+//!
+//! let mut feed = DhanFeedManagerBuilder::new("client-id", "token").build();
+//! feed.start().await?;
+//! session.subscribe_all(&mut feed, FeedRequestCode::SubscribeQuote).await?;
+//!
+//! let orders = order_events(OrderUpdateStream::connect("client-id", "token").await?);
+//! session
+//!     .run(futures_util::stream::select(feed_events(&feed), orders))
+//!     .await;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::cache::QuoteCache;
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::risk::{RiskEngine, RiskLimits};
+use crate::runtime::degrade::{DegradationPolicy, SafeMode};
+use crate::runtime::signals::SignalLog;
+use crate::runtime::{RuntimeEvent, Strategy, StrategyRunner};
+use crate::types::enums::FeedRequestCode;
+use crate::types::instrument::InstrumentId;
+use crate::ws::manager::DhanFeedManager;
+
+/// Events buffered per strategy before market data is dropped.
+const STRATEGY_CHANNEL_CAPACITY: usize = 4096;
+
+/// How long dispatch waits on a full strategy channel before dropping an
+/// order update. Dispatch is shared, so an unbounded wait would stall
+/// every other strategy behind the lagging one.
+const ORDER_UPDATE_SEND_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Lifecycle state of a strategy within a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum StrategyState {
+    /// Receiving market data and order updates.
+    Running = 0,
+    /// Receiving order updates only; market data is withheld.
+    Paused = 1,
+    /// Finished; `on_stop` has run.
+    Stopped = 2,
+}
+
+impl StrategyState {
+    fn from_u8(v: u8) -> Self {
+        match v {
+            0 => Self::Running,
+            1 => Self::Paused,
+            _ => Self::Stopped,
+        }
+    }
+}
+
+/// Summary of a strategy registered with a session.
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyInfo {
+    /// Strategy name.
+    pub name: String,
+    /// Correlation-ID prefix of its orders.
+    pub correlation_prefix: String,
+    /// Current state.
+    pub state: StrategyState,
+    /// Instruments it asked for (empty = all).
+    pub instruments: Vec<InstrumentId>,
+    /// Whether its risk engine is halted.
+    pub halted: bool,
+    /// Its adjustable risk limits.
+    pub limits: RiskLimits,
+}
+
+/// The part of a strategy slot shared with [`SessionHandle`]s.
+struct SlotControl {
+    name: String,
+    prefix: String,
+    instruments: Vec<InstrumentId>,
+    risk: RiskEngine,
+    state: Arc<AtomicU8>,
+}
+
+impl SlotControl {
+    fn state(&self) -> StrategyState {
+        StrategyState::from_u8(self.state.load(Ordering::SeqCst))
+    }
+
+    fn wants(&self, instrument: &InstrumentId) -> bool {
+        self.instruments.is_empty() || self.instruments.contains(instrument)
+    }
+
+    fn info(&self) -> StrategyInfo {
+        StrategyInfo {
+            name: self.name.clone(),
+            correlation_prefix: self.prefix.clone(),
+            state: self.state(),
+            instruments: self.instruments.clone(),
+            halted: self.risk.is_halted(),
+            limits: self.risk.limits(),
+        }
+    }
+}
+
+struct StrategySlot {
+    control: Arc<SlotControl>,
+    tx: Option<mpsc::Sender<RuntimeEvent>>,
+    task: Option<JoinHandle<()>>,
+}
+
+type Controls = Arc<RwLock<Vec<Arc<SlotControl>>>>;
+
+/// Runs several strategies over one shared feed and quote cache.
+pub struct DhanSession {
+    handle: SessionHandle,
+    strategies: Vec<StrategySlot>,
+    degradation: Option<JoinHandle<()>>,
+    signals: Option<SignalLog>,
+}
+
+impl std::fmt::Debug for DhanSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DhanSession")
+            .field("strategies", &self.strategies())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DhanSession {
+    /// Create a session placing orders through `client`.
+    pub fn new(client: DhanClient) -> Self {
+        Self {
+            handle: SessionHandle {
+                client,
+                quotes: QuoteCache::new(),
+                controls: Controls::default(),
+                safe_mode: SafeMode::new(),
+                started_at: Instant::now(),
+            },
+            strategies: Vec::new(),
+            degradation: None,
+            signals: None,
+        }
+    }
+
+    /// Switch to safe mode as `policy` directs; see
+    /// [`crate::runtime::degrade`]. Must be called within a Tokio runtime.
+    pub fn with_degradation(mut self, policy: DegradationPolicy) -> Self {
+        if let Some(task) = self.degradation.take() {
+            task.abort();
+        }
+        self.degradation = Some(tokio::spawn(policy.supervise(self.handle.clone())));
+        self
+    }
+
+    /// Journal the signals of strategies added from now on to `log` (see
+    /// [`crate::runtime::signals`]).
+    pub fn with_signal_log(mut self, log: SignalLog) -> Self {
+        self.signals = Some(log);
+        self
+    }
+
+    /// A cheap, cloneable handle for controlling the session from other
+    /// tasks while [`run()`](Self::run) is in progress.
+    pub fn handle(&self) -> SessionHandle {
+        self.handle.clone()
+    }
+
+    /// The REST client shared by all strategies.
+    pub fn client(&self) -> &DhanClient {
+        &self.handle.client
+    }
+
+    /// The shared quote cache.
+    pub fn quotes(&self) -> &QuoteCache {
+        &self.handle.quotes
+    }
+
+    /// Register and start `strategy` with its own risk engine.
+    ///
+    /// The correlation prefix is derived from the strategy name (up to 10
+    /// alphanumeric characters, upper-cased, followed by `-`) and must be
+    /// unique within the session.
+    pub fn add_strategy<S: Strategy + 'static>(
+        &mut self,
+        strategy: S,
+        risk: RiskEngine,
+    ) -> Result<()> {
+        let name = strategy.name().to_owned();
+        let prefix = correlation_prefix(&name);
+        if prefix.len() <= 1 {
+            return Err(DhanError::InvalidArgument(format!(
+                "strategy name {name:?} has no alphanumeric characters"
+            )));
+        }
+        if self
+            .strategies
+            .iter()
+            .any(|s| s.control.name == name || s.control.prefix == prefix)
+        {
+            return Err(DhanError::InvalidArgument(format!(
+                "strategy {name:?} clashes with an existing strategy (prefix {prefix})"
+            )));
+        }
+
+        let instruments = strategy.instruments();
+        let mut runner = StrategyRunner::new(strategy, self.handle.client.clone())
+            .with_risk(risk.clone())
+            .with_correlation_prefix(prefix.clone())
+            .with_quote_cache(self.handle.quotes.clone())
+            .with_safe_mode(self.handle.safe_mode.clone());
+        if let Some(log) = &self.signals {
+            runner = runner.with_signal_log(log.clone());
+        }
+        let (tx, rx) = mpsc::channel(STRATEGY_CHANNEL_CAPACITY);
+        let state = Arc::new(AtomicU8::new(StrategyState::Running as u8));
+        let task = tokio::spawn(run_strategy(runner, rx, state.clone()));
+
+        tracing::info!(strategy = name, prefix, "Strategy added to session");
+        let control = Arc::new(SlotControl {
+            name,
+            prefix,
+            instruments,
+            risk,
+            state,
+        });
+        self.handle
+            .controls
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(control.clone());
+        self.strategies.push(StrategySlot {
+            control,
+            tx: Some(tx),
+            task: Some(task),
+        });
+        Ok(())
+    }
+
+    /// Summaries of every registered strategy.
+    pub fn strategies(&self) -> Vec<StrategyInfo> {
+        self.handle.strategies()
+    }
+
+    /// The risk engine of strategy `name`.
+    pub fn risk(&self, name: &str) -> Option<&RiskEngine> {
+        self.slot(name).ok().map(|s| &s.control.risk)
+    }
+
+    /// Union of the instruments requested by all strategies.
+    pub fn instruments(&self) -> Vec<InstrumentId> {
+        let mut all: Vec<InstrumentId> = Vec::new();
+        for id in self.strategies.iter().flat_map(|s| &s.control.instruments) {
+            if !all.contains(id) {
+                all.push(*id);
+            }
+        }
+        all
+    }
+
+    /// Subscribe the shared feed to every instrument the strategies need.
+    pub async fn subscribe_all(
+        &self,
+        manager: &mut DhanFeedManager,
+        mode: FeedRequestCode,
+    ) -> Result<()> {
+        let instruments: Vec<_> = self
+            .instruments()
+            .iter()
+            .map(InstrumentId::to_feed_instrument)
+            .collect();
+        if instruments.is_empty() {
+            return Ok(());
+        }
+        manager.subscribe(&instruments, mode).await
+    }
+
+    /// Withhold market data from strategy `name` until resumed.
+    pub fn pause(&self, name: &str) -> Result<()> {
+        self.handle.pause(name)
+    }
+
+    /// Resume delivering market data to strategy `name`.
+    pub fn resume(&self, name: &str) -> Result<()> {
+        self.handle.resume(name)
+    }
+
+    /// Stop strategy `name` and wait for its `on_stop` hook to finish.
+    pub async fn stop_strategy(&mut self, name: &str) -> Result<()> {
+        let slot = self.slot_mut(name)?;
+        slot.tx = None;
+        if let Some(task) = slot.task.take() {
+            let _ = task.await;
+        }
+        Ok(())
+    }
+
+    /// Stop every strategy.
+    pub async fn shutdown(&mut self) {
+        let names: Vec<String> = self
+            .strategies
+            .iter()
+            .map(|s| s.control.name.clone())
+            .collect();
+        for name in names {
+            let _ = self.stop_strategy(&name).await;
+        }
+        if let Some(task) = self.degradation.take() {
+            task.abort();
+        }
+    }
+
+    /// Route one event.
+    ///
+    /// Market data updates the quote cache and goes to every running
+    /// strategy interested in the instrument. Order updates go to the
+    /// strategy whose correlation prefix matches, or nowhere.
+    pub async fn dispatch(&self, event: RuntimeEvent) {
+        match &event {
+            RuntimeEvent::Market(ev) => {
+                let Some(tick) = ev.to_tick() else {
+                    return;
+                };
+                self.handle.quotes.update(&tick);
+                for slot in &self.strategies {
+                    let control = &slot.control;
+                    if control.state() != StrategyState::Running || !control.wants(&tick.instrument)
+                    {
+                        continue;
+                    }
+                    let Some(tx) = &slot.tx else { continue };
+                    if tx.try_send(event.clone()).is_err() {
+                        tracing::warn!(
+                            strategy = control.name,
+                            "Strategy lagging; dropped market event"
+                        );
+                    }
+                }
+            }
+            RuntimeEvent::Order(update) => {
+                let owner = update.correlation_id.as_deref().and_then(|cid| {
+                    self.strategies
+                        .iter()
+                        .find(|s| cid.starts_with(&s.control.prefix))
+                });
+                match owner.and_then(|s| s.tx.as_ref()) {
+                    Some(tx) => {
+                        let strategy = owner.map(|s| s.control.name.as_str());
+                        if let Err(mpsc::error::TrySendError::Full(event)) =
+                            tx.try_send(event.clone())
+                        {
+                            tracing::warn!(
+                                strategy,
+                                "Strategy lagging; waiting to deliver order update"
+                            );
+                            let sent =
+                                tokio::time::timeout(ORDER_UPDATE_SEND_TIMEOUT, tx.send(event));
+                            if sent.await.is_err() {
+                                tracing::error!(
+                                    strategy,
+                                    order_id = update.order_id.as_deref(),
+                                    "Strategy lagging; dropped order update"
+                                );
+                            }
+                        }
+                    }
+                    None => tracing::debug!(
+                        order_id = update.order_id.as_deref(),
+                        "Order update not owned by any strategy"
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Dispatch `events` until the stream ends, then stop every strategy.
+    pub async fn run(&mut self, events: impl Stream<Item = RuntimeEvent>) {
+        let mut events = std::pin::pin!(events);
+        while let Some(event) = events.next().await {
+            self.dispatch(event).await;
+        }
+        self.shutdown().await;
+    }
+
+    fn slot(&self, name: &str) -> Result<&StrategySlot> {
+        self.strategies
+            .iter()
+            .find(|s| s.control.name == name)
+            .ok_or_else(|| unknown_strategy(name))
+    }
+
+    fn slot_mut(&mut self, name: &str) -> Result<&mut StrategySlot> {
+        self.strategies
+            .iter_mut()
+            .find(|s| s.control.name == name)
+            .ok_or_else(|| unknown_strategy(name))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Session handle
+// ---------------------------------------------------------------------------
+
+/// Cloneable control handle of a [`DhanSession`].
+///
+/// Obtained from [`DhanSession::handle()`]; lets other tasks (such as a
+/// control server) inspect strategies, pause or resume them and adjust their
+/// risk while the session is running.
+#[derive(Clone)]
+pub struct SessionHandle {
+    client: DhanClient,
+    quotes: QuoteCache,
+    controls: Controls,
+    safe_mode: SafeMode,
+    started_at: Instant,
+}
+
+impl std::fmt::Debug for SessionHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SessionHandle")
+            .field("strategies", &self.strategies())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SessionHandle {
+    /// The REST client shared by all strategies.
+    pub fn client(&self) -> &DhanClient {
+        &self.client
+    }
+
+    /// The shared quote cache.
+    pub fn quotes(&self) -> &QuoteCache {
+        &self.quotes
+    }
+
+    /// The safe mode switch shared by all strategies; can also be flipped
+    /// by hand.
+    pub fn safe_mode(&self) -> &SafeMode {
+        &self.safe_mode
+    }
+
+    /// Time since the session was created.
+    pub fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    /// Summaries of every registered strategy.
+    pub fn strategies(&self) -> Vec<StrategyInfo> {
+        self.read().iter().map(|c| c.info()).collect()
+    }
+
+    /// Summary of strategy `name`.
+    pub fn strategy(&self, name: &str) -> Result<StrategyInfo> {
+        self.control(name).map(|c| c.info())
+    }
+
+    /// The risk engine of strategy `name`.
+    pub fn risk(&self, name: &str) -> Result<RiskEngine> {
+        self.control(name).map(|c| c.risk.clone())
+    }
+
+    /// Withhold market data from strategy `name` until resumed.
+    pub fn pause(&self, name: &str) -> Result<()> {
+        self.transition(name, StrategyState::Paused)
+    }
+
+    /// Resume delivering market data to strategy `name`.
+    pub fn resume(&self, name: &str) -> Result<()> {
+        self.transition(name, StrategyState::Running)
+    }
+
+    /// Halt the risk engine of every strategy, blocking all new orders.
+    pub fn halt_all(&self) {
+        for control in self.read().iter() {
+            control.risk.halt();
+        }
+    }
+
+    fn transition(&self, name: &str, to: StrategyState) -> Result<()> {
+        let control = self.control(name)?;
+        if control.state() == StrategyState::Stopped {
+            return Err(DhanError::InvalidArgument(format!(
+                "strategy {name:?} is stopped"
+            )));
+        }
+        control.state.store(to as u8, Ordering::SeqCst);
+        tracing::info!(strategy = name, state = ?to, "Strategy state changed");
+        Ok(())
+    }
+
+    fn control(&self, name: &str) -> Result<Arc<SlotControl>> {
+        self.read()
+            .iter()
+            .find(|c| c.name == name)
+            .cloned()
+            .ok_or_else(|| unknown_strategy(name))
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Vec<Arc<SlotControl>>> {
+        self.controls.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn unknown_strategy(name: &str) -> DhanError {
+    DhanError::InvalidArgument(format!("unknown strategy {name:?}"))
+}
+
+/// Derive a correlation-ID prefix from a strategy name.
+fn correlation_prefix(name: &str) -> String {
+    let mut prefix: String = name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .take(10)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    prefix.push('-');
+    prefix
+}
+
+/// Task body of one strategy.
+async fn run_strategy<S: Strategy>(
+    mut runner: StrategyRunner<S>,
+    mut rx: mpsc::Receiver<RuntimeEvent>,
+    state: Arc<AtomicU8>,
+) {
+    runner.start().await;
+    while !runner.stop_requested() {
+        match rx.recv().await {
+            Some(event) => runner.handle_event(event).await,
+            None => break,
+        }
+    }
+    runner.stop().await;
+    state.store(StrategyState::Stopped as u8, Ordering::SeqCst);
+}
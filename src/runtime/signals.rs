@@ -0,0 +1,297 @@
+//! Journaling trade signals with the inputs that produced them.
+//!
+//! A strategy describes each decision as a [`Signal`] — the instruments it
+//! looked at, indicator values, positions and any other context — and
+//! hands it to [`StrategyContext::log_signal`] before queueing the orders
+//! it leads to. A [`StrategyRunner`] with a [`SignalLog`] (see
+//! [`StrategyRunner::with_signal_log`]) completes it into a
+//! [`SignalRecord`]: the latest tick of every listed instrument is captured
+//! unless given explicitly, and the correlation IDs, order IDs or errors of
+//! the orders queued after it in the same hook are attached. Records go to
+//! a [`Journal`], one per line, for post-trade analysis.
+//!
+//! ```
+//! use dhan_rs::runtime::StrategyContext;
+//! use dhan_rs::runtime::signals::Signal;
+//! use dhan_rs::types::instrument::InstrumentId;
+//! use dhan_rs::types::orders::PlaceOrderRequest;
+//!
+//! fn enter(ctx: &mut StrategyContext, id: InstrumentId, rsi: f64, order: PlaceOrderRequest) {
+//!     ctx.log_signal(
+//!         Signal::new("rsi-oversold")
+//!             .instrument(id)
+//!             .indicator("rsi_14", rsi)
+//!             .note("RSI crossed below 30"),
+//!     );
+//!     ctx.place_order(order);
+//! }
+//! ```
+//!
+//! [`StrategyContext::log_signal`]: super::StrategyContext::log_signal
+//! [`StrategyRunner`]: super::StrategyRunner
+//! [`StrategyRunner::with_signal_log`]: super::StrategyRunner::with_signal_log
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::journal::Journal;
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::Tick;
+
+// ---------------------------------------------------------------------------
+// Signal
+// ---------------------------------------------------------------------------
+
+/// A trading decision and its inputs, as described by a strategy.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signal {
+    name: String,
+    instruments: Vec<InstrumentId>,
+    quotes: Vec<QuoteSnapshot>,
+    indicators: BTreeMap<String, f64>,
+    positions: Vec<PositionSnapshot>,
+    context: BTreeMap<String, serde_json::Value>,
+    note: Option<String>,
+}
+
+impl Signal {
+    /// A signal of kind `name`, e.g. `"breakout-long"`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            instruments: Vec::new(),
+            quotes: Vec::new(),
+            indicators: BTreeMap::new(),
+            positions: Vec::new(),
+            context: BTreeMap::new(),
+            note: None,
+        }
+    }
+
+    /// An instrument the decision is about; its latest tick is captured
+    /// unless [`Self::quote`] gives one.
+    pub fn instrument(mut self, id: InstrumentId) -> Self {
+        if !self.instruments.contains(&id) {
+            self.instruments.push(id);
+        }
+        self
+    }
+
+    /// Record `tick` as the quote the decision was based on.
+    pub fn quote(mut self, tick: &Tick) -> Self {
+        self = self.instrument(tick.instrument);
+        self.quotes.retain(|q| q.instrument != tick.instrument);
+        self.quotes.push(QuoteSnapshot::from(tick));
+        self
+    }
+
+    /// Record an indicator value.
+    pub fn indicator(mut self, name: impl Into<String>, value: f64) -> Self {
+        self.indicators.insert(name.into(), value);
+        self
+    }
+
+    /// Record the net position held in `id`.
+    pub fn position(mut self, id: InstrumentId, net_qty: i64) -> Self {
+        self.positions.retain(|p| p.instrument != id);
+        self.positions.push(PositionSnapshot {
+            instrument: id,
+            net_qty,
+        });
+        self
+    }
+
+    /// Record any other input under `key`.
+    pub fn context(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.context.insert(key.into(), value);
+        self
+    }
+
+    /// A free-text explanation.
+    pub fn note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    /// The signal's kind.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The instruments the decision is about.
+    pub fn instruments(&self) -> &[InstrumentId] {
+        &self.instruments
+    }
+
+    /// Complete the signal into a record for `strategy`, taking the quotes
+    /// not given explicitly from `latest`.
+    pub(crate) fn into_record(
+        self,
+        strategy: &str,
+        latest: impl Fn(&InstrumentId) -> Option<Tick>,
+    ) -> SignalRecord {
+        let mut quotes = self.quotes;
+        for id in &self.instruments {
+            if quotes.iter().any(|q| q.instrument == *id) {
+                continue;
+            }
+            if let Some(tick) = latest(id) {
+                quotes.push(QuoteSnapshot::from(&tick));
+            }
+        }
+        SignalRecord {
+            signal_id: uuid::Uuid::new_v4().simple().to_string(),
+            strategy: strategy.to_owned(),
+            at: Utc::now(),
+            name: self.name,
+            instruments: self.instruments,
+            quotes,
+            indicators: self.indicators,
+            positions: self.positions,
+            context: self.context,
+            note: self.note,
+            orders: Vec::new(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Records
+// ---------------------------------------------------------------------------
+
+/// The price data of a [`Tick`] kept in a signal record.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QuoteSnapshot {
+    /// The instrument.
+    pub instrument: InstrumentId,
+    /// Last traded price.
+    pub ltp: f64,
+    /// Last trade time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trade_time: Option<DateTime<Utc>>,
+    /// Cumulative traded volume for the day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<i64>,
+    /// Open interest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oi: Option<i64>,
+}
+
+impl From<&Tick> for QuoteSnapshot {
+    fn from(tick: &Tick) -> Self {
+        Self {
+            instrument: tick.instrument,
+            ltp: tick.ltp,
+            trade_time: tick.trade_time(),
+            volume: tick.volume,
+            oi: tick.oi,
+        }
+    }
+}
+
+/// A position held when a signal was raised.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PositionSnapshot {
+    /// The instrument.
+    pub instrument: InstrumentId,
+    /// Net quantity, negative when short.
+    pub net_qty: i64,
+}
+
+/// An order a signal led to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignalOrder {
+    /// Correlation ID the order was placed with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    /// Order ID, if placement succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+    /// Why placement failed (risk rejection, API error, …).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A journaled signal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalRecord {
+    /// Unique ID of the record.
+    pub signal_id: String,
+    /// The strategy that raised the signal.
+    pub strategy: String,
+    /// When the runner processed the signal.
+    pub at: DateTime<Utc>,
+    /// The signal's kind.
+    pub name: String,
+    /// The instruments the decision was about.
+    pub instruments: Vec<InstrumentId>,
+    /// Quotes at decision time.
+    pub quotes: Vec<QuoteSnapshot>,
+    /// Indicator values.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub indicators: BTreeMap<String, f64>,
+    /// Positions at decision time.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub positions: Vec<PositionSnapshot>,
+    /// Other inputs.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub context: BTreeMap<String, serde_json::Value>,
+    /// Free-text explanation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// The orders placed for the signal.
+    pub orders: Vec<SignalOrder>,
+}
+
+impl SignalRecord {
+    /// Returns `true` if one of the signal's orders carries
+    /// `correlation_id`.
+    pub fn has_correlation_id(&self, correlation_id: &str) -> bool {
+        self.orders
+            .iter()
+            .any(|o| o.correlation_id.as_deref() == Some(correlation_id))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Log
+// ---------------------------------------------------------------------------
+
+/// A journal of [`SignalRecord`]s. Clones append to the same file, so one
+/// log can serve every strategy of a session.
+#[derive(Debug, Clone)]
+pub struct SignalLog {
+    journal: Arc<Mutex<Journal>>,
+}
+
+impl SignalLog {
+    /// Open (or create) the signal journal at `path` for appending.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::from_journal(Journal::open(path)?))
+    }
+
+    /// Append to `journal`, e.g. one publishing its records on an event bus.
+    pub fn from_journal(journal: Journal) -> Self {
+        Self {
+            journal: Arc::new(Mutex::new(journal)),
+        }
+    }
+
+    /// Append `record`.
+    pub fn record(&self, record: &SignalRecord) -> Result<()> {
+        self.journal
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .append(record)
+    }
+
+    /// Read every record of the signal journal at `path`.
+    pub fn read(path: impl AsRef<Path>) -> Result<Vec<SignalRecord>> {
+        Journal::read(path)
+    }
+}
@@ -0,0 +1,100 @@
+//! Lightweight in-process scheduling at fixed IST times of day.
+//!
+//! [`DailySchedule`] computes the next occurrence of one or more wall-clock
+//! times in Indian Standard Time, so recurring jobs (portfolio snapshots,
+//! end-of-day square-off, …) can run without an external cron.
+//!
+//! - [`snapshot`] — Periodic holdings / positions / funds snapshots
+//! - [`square_off`] — End-of-day square-off of intraday positions
+
+pub mod snapshot;
+pub mod square_off;
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc, Weekday};
+
+use crate::error::{DhanError, Result};
+
+pub use crate::time::ist;
+
+/// A set of IST times of day at which a job should run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailySchedule {
+    times: Vec<NaiveTime>,
+    weekdays_only: bool,
+}
+
+impl DailySchedule {
+    /// Create a schedule from IST times of day (order and duplicates don't matter).
+    pub fn new(times: impl IntoIterator<Item = NaiveTime>) -> Self {
+        let mut times: Vec<NaiveTime> = times.into_iter().collect();
+        times.sort();
+        times.dedup();
+        Self {
+            times,
+            weekdays_only: false,
+        }
+    }
+
+    /// Create a schedule with a single IST time of day.
+    pub fn at(time: NaiveTime) -> Self {
+        Self::new([time])
+    }
+
+    /// Parse `"HH:MM"` or `"HH:MM:SS"` IST times, e.g. `["09:20", "15:25"]`.
+    pub fn parse<S: AsRef<str>>(times: &[S]) -> Result<Self> {
+        let parsed = times
+            .iter()
+            .map(|t| {
+                let t = t.as_ref().trim();
+                NaiveTime::parse_from_str(t, "%H:%M:%S")
+                    .or_else(|_| NaiveTime::parse_from_str(t, "%H:%M"))
+                    .map_err(|_| DhanError::InvalidArgument(format!("invalid time of day: {t}")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(parsed))
+    }
+
+    /// Skip Saturdays and Sundays. Exchange holidays are not known here.
+    pub fn weekdays_only(mut self) -> Self {
+        self.weekdays_only = true;
+        self
+    }
+
+    /// The scheduled IST times of day, sorted.
+    pub fn times(&self) -> &[NaiveTime] {
+        &self.times
+    }
+
+    /// The first scheduled instant strictly after `now`, or `None` if the
+    /// schedule is empty.
+    pub fn next_after(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let tz = ist();
+        let local = now.with_timezone(&tz);
+        // A week ahead always contains a weekday.
+        for day in 0..8 {
+            let date = local.date_naive() + Duration::days(day);
+            if self.weekdays_only && matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+                continue;
+            }
+            for time in &self.times {
+                let Some(at) = tz.from_local_datetime(&date.and_time(*time)).single() else {
+                    continue;
+                };
+                if at > local {
+                    return Some(at.with_timezone(&Utc));
+                }
+            }
+        }
+        None
+    }
+
+    /// Sleep until the next scheduled instant and return it.
+    ///
+    /// Returns `None` immediately if the schedule is empty.
+    pub async fn wait_next(&self) -> Option<DateTime<Utc>> {
+        let next = self.next_after(Utc::now())?;
+        let delay = (next - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(delay).await;
+        Some(next)
+    }
+}
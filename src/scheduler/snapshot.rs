@@ -0,0 +1,123 @@
+//! Scheduled portfolio snapshots.
+//!
+//! [`SnapshotScheduler`] captures holdings, positions and fund limits at the
+//! times of a [`DailySchedule`] and appends each [`PortfolioSnapshot`] to a
+//! JSON-lines [`Journal`], enabling end-of-day diffs and intraday exposure
+//! audits.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::scheduler::DailySchedule;
+//! use dhan_rs::scheduler::snapshot::SnapshotScheduler;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let schedule = DailySchedule::parse(&["09:20", "15:25"])?.weekdays_only();
+//! let handle = SnapshotScheduler::new(client, schedule, "snapshots.jsonl")?.spawn();
+//! # handle.abort();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::journal::Journal;
+use crate::scheduler::{DailySchedule, ist};
+use crate::types::funds::FundLimit;
+use crate::types::portfolio::{Holding, Position};
+
+/// Account state captured at a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    /// When the snapshot was taken (IST).
+    pub taken_at: DateTime<FixedOffset>,
+    /// Demat holdings.
+    pub holdings: Vec<Holding>,
+    /// Open and closed positions of the day.
+    pub positions: Vec<Position>,
+    /// Fund limits.
+    pub funds: FundLimit,
+}
+
+impl PortfolioSnapshot {
+    /// Fetch holdings, positions and fund limits now.
+    pub async fn capture(client: &DhanClient) -> Result<Self> {
+        let taken_at = Utc::now().with_timezone(&ist());
+        let (holdings, positions, funds) = tokio::try_join!(
+            client.get_holdings(),
+            client.get_positions(),
+            client.get_fund_limit(),
+        )?;
+        Ok(Self {
+            taken_at,
+            holdings,
+            positions,
+            funds,
+        })
+    }
+
+    /// Load every snapshot stored at `path`, oldest first.
+    pub fn load_all(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        Journal::read(path)
+    }
+}
+
+/// Captures [`PortfolioSnapshot`]s on a [`DailySchedule`].
+#[derive(Debug)]
+pub struct SnapshotScheduler {
+    client: DhanClient,
+    schedule: DailySchedule,
+    journal: Journal,
+}
+
+impl SnapshotScheduler {
+    /// Create a scheduler that appends snapshots to the store at `path`.
+    pub fn new(
+        client: DhanClient,
+        schedule: DailySchedule,
+        path: impl AsRef<Path>,
+    ) -> Result<Self> {
+        Ok(Self {
+            client,
+            schedule,
+            journal: Journal::open(path)?,
+        })
+    }
+
+    /// Capture and store a snapshot immediately.
+    pub async fn snapshot_now(&mut self) -> Result<PortfolioSnapshot> {
+        let snapshot = PortfolioSnapshot::capture(&self.client).await?;
+        self.journal.append(&snapshot)?;
+        tracing::info!(
+            taken_at = %snapshot.taken_at,
+            holdings = snapshot.holdings.len(),
+            positions = snapshot.positions.len(),
+            "Stored portfolio snapshot"
+        );
+        Ok(snapshot)
+    }
+
+    /// Run forever, capturing a snapshot at every scheduled time.
+    ///
+    /// Failed captures are logged and the next scheduled time is awaited.
+    pub async fn run(mut self) {
+        while self.schedule.wait_next().await.is_some() {
+            if let Err(e) = self.snapshot_now().await {
+                tracing::error!("Portfolio snapshot failed: {e}");
+            }
+        }
+        tracing::warn!("Snapshot schedule is empty; scheduler stopped");
+    }
+
+    /// Run the scheduler on a background task.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+}
@@ -0,0 +1,277 @@
+//! End-of-day auto square-off of intraday positions.
+//!
+//! [`SquareOffScheduler`] closes every open position of the configured
+//! product types (by default `INTRADAY`) with market orders at a fixed IST
+//! time, ahead of the broker's own forced square-off and its charges. Each
+//! attempt re-reads positions, so partially closed positions are retried and
+//! the final state is verified. Before a retry, exits of the previous attempt
+//! that are still working are cancelled and must settle first, so a slow
+//! fill is never doubled into a reverse position.
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::scheduler::square_off::{SquareOffConfig, SquareOffScheduler};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("client-id", "token");
+//! let config = SquareOffConfig {
+//!     dry_run: true,
+//!     ..Default::default()
+//! };
+//! let report = SquareOffScheduler::new(client, config).square_off_now().await?;
+//! println!("would place {} orders", report.planned.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use tokio::task::JoinHandle;
+
+use crate::batch::BatchReport;
+use crate::client::DhanClient;
+use crate::error::Result;
+use crate::scheduler::DailySchedule;
+use crate::types::enums::{OrderStatus, OrderType, ProductType};
+use crate::types::orders::{OrderResponse, PlaceOrderRequest};
+use crate::types::portfolio::Position;
+
+/// Configuration for [`SquareOffScheduler`].
+#[derive(Debug, Clone)]
+pub struct SquareOffConfig {
+    /// IST time of day at which to square off (default 15:12).
+    pub at: NaiveTime,
+    /// Product types to close (default `[INTRADAY]`).
+    pub product_types: Vec<ProductType>,
+    /// Maximum number of place-and-verify rounds (default 3).
+    pub max_attempts: u32,
+    /// Wait between placing orders and re-reading positions (default 2 s).
+    pub verify_delay: Duration,
+    /// Log and report the orders without placing them (default `false`).
+    pub dry_run: bool,
+}
+
+impl Default for SquareOffConfig {
+    fn default() -> Self {
+        Self {
+            at: NaiveTime::from_hms_opt(15, 12, 0).expect("valid time"),
+            product_types: vec![ProductType::INTRADAY],
+            max_attempts: 3,
+            verify_delay: Duration::from_secs(2),
+            dry_run: false,
+        }
+    }
+}
+
+/// Result of a square-off run.
+#[derive(Debug, Default)]
+pub struct SquareOffReport {
+    /// Orders built in the first round (the only round in dry-run mode).
+    pub planned: Vec<PlaceOrderRequest>,
+    /// Orders accepted or rejected by the API across all rounds.
+    pub orders: BatchReport<OrderResponse, PlaceOrderRequest>,
+    /// Matching positions still open after the last round.
+    pub remaining: Vec<Position>,
+    /// Number of place-and-verify rounds performed.
+    pub attempts: u32,
+    /// Whether this was a dry run.
+    pub dry_run: bool,
+}
+
+impl SquareOffReport {
+    /// Returns `true` if no matching position is left open.
+    pub fn is_flat(&self) -> bool {
+        self.remaining.is_empty()
+    }
+}
+
+/// Squares off open positions at a fixed IST time every weekday.
+#[derive(Debug, Clone)]
+pub struct SquareOffScheduler {
+    client: DhanClient,
+    config: SquareOffConfig,
+}
+
+impl SquareOffScheduler {
+    /// Create a scheduler.
+    pub fn new(client: DhanClient, config: SquareOffConfig) -> Self {
+        Self { client, config }
+    }
+
+    /// The active configuration.
+    pub fn config(&self) -> &SquareOffConfig {
+        &self.config
+    }
+
+    /// Build the market orders that flatten the matching open positions.
+    ///
+    /// Positions with an unknown segment or product are skipped.
+    pub fn build_orders(
+        client_id: &str,
+        positions: &[Position],
+        product_types: &[ProductType],
+    ) -> Vec<PlaceOrderRequest> {
+        positions
+            .iter()
+            .filter_map(|p| square_off_order(client_id, p, product_types))
+            .collect()
+    }
+
+    /// Square off now, retrying until flat or `max_attempts` is reached.
+    ///
+    /// An attempt whose predecessor's orders are still working after being
+    /// cancelled places nothing and only waits for the next one.
+    pub async fn square_off_now(&self) -> Result<SquareOffReport> {
+        let mut report = SquareOffReport {
+            dry_run: self.config.dry_run,
+            ..Default::default()
+        };
+
+        let mut working: Vec<String> = Vec::new();
+        for attempt in 1..=self.config.max_attempts.max(1) {
+            if !working.is_empty() {
+                if !self.settle(&working).await {
+                    tracing::warn!(
+                        orders = working.len(),
+                        "Square-off orders still working; not placing more"
+                    );
+                    tokio::time::sleep(self.config.verify_delay).await;
+                    continue;
+                }
+                working.clear();
+            }
+            let positions = self.client.get_positions().await?;
+            let orders = Self::build_orders(
+                self.client.client_id(),
+                &positions,
+                &self.config.product_types,
+            );
+            if orders.is_empty() {
+                break;
+            }
+            report.attempts = attempt;
+            if attempt == 1 {
+                report.planned = orders.clone();
+            }
+            if self.config.dry_run {
+                for o in &orders {
+                    tracing::info!(
+                        security_id = o.security_id,
+                        side = ?o.transaction_type,
+                        quantity = o.quantity,
+                        "[dry run] would square off"
+                    );
+                }
+                return Ok(report);
+            }
+
+            for order in orders {
+                let result = self.client.place_order(&order).await;
+                match &result {
+                    Ok(response) => working.push(response.order_id.clone()),
+                    Err(e) => {
+                        tracing::error!(security_id = order.security_id, "Square-off failed: {e}")
+                    }
+                }
+                report.orders.push(order, result);
+            }
+            tokio::time::sleep(self.config.verify_delay).await;
+        }
+
+        if !self.config.dry_run {
+            let positions = self.client.get_positions().await?;
+            // Judged per position, so a flat leg in another segment or
+            // product of the same security does not count as open.
+            report.remaining = positions
+                .into_iter()
+                .filter(|p| {
+                    square_off_order(self.client.client_id(), p, &self.config.product_types)
+                        .is_some()
+                })
+                .collect();
+        }
+
+        if report.is_flat() {
+            tracing::info!(
+                placed = report.orders.succeeded.len(),
+                "Square-off complete"
+            );
+        } else {
+            tracing::error!(
+                remaining = report.remaining.len(),
+                "Positions still open after square-off"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Cancel the orders among `order_ids` that are still working, then
+    /// return whether every one of them has settled.
+    ///
+    /// An order whose status cannot be read or parsed counts as working.
+    async fn settle(&self, order_ids: &[String]) -> bool {
+        let mut cancelled = false;
+        for order_id in order_ids {
+            if self.is_working(order_id).await {
+                tracing::warn!(order_id, "Square-off order still working; cancelling");
+                if let Err(e) = self.client.cancel_order(order_id).await {
+                    tracing::warn!(order_id, "Failed to cancel square-off order: {e}");
+                }
+                cancelled = true;
+            }
+        }
+        if !cancelled {
+            return true;
+        }
+        for order_id in order_ids {
+            if self.is_working(order_id).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn is_working(&self, order_id: &str) -> bool {
+        match self.client.get_order(order_id).await {
+            Ok(order) => order.status().is_none_or(OrderStatus::is_working),
+            Err(e) => {
+                tracing::warn!(order_id, "Failed to read square-off order: {e}");
+                true
+            }
+        }
+    }
+
+    /// Run forever, squaring off at the configured time every weekday.
+    pub async fn run(self) {
+        let schedule = DailySchedule::at(self.config.at).weekdays_only();
+        while schedule.wait_next().await.is_some() {
+            if let Err(e) = self.square_off_now().await {
+                tracing::error!("Square-off run failed: {e}");
+            }
+        }
+    }
+
+    /// Run the scheduler on a background task.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+}
+
+/// The market order flattening `position`, if it is open and its product is
+/// one of `product_types`.
+fn square_off_order(
+    client_id: &str,
+    position: &Position,
+    product_types: &[ProductType],
+) -> Option<PlaceOrderRequest> {
+    let product = position
+        .product_type
+        .as_deref()
+        .and_then(ProductType::from_order_update_code)?;
+    if !product_types.contains(&product) {
+        return None;
+    }
+    PlaceOrderRequest::square_off(client_id, position, OrderType::MARKET).ok()
+}
@@ -0,0 +1,194 @@
+//! Capability scopes for client handles.
+//!
+//! A large application hands different components different powers: a
+//! scanner needs market data, an executor needs to trade. A [`DhanClient`]
+//! carries a set of [`Scopes`]; every REST call needs one [`Scope`] (see
+//! [`required_scope`]) and calls outside the set fail with
+//! [`DhanError::ScopeDenied`] before any request is sent.
+//!
+//! Scopes can only be narrowed: [`DhanClient::scoped`] returns a handle with
+//! the intersection of its own scopes and the requested ones.
+//!
+//! ```
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::scope::{Scope, Scopes};
+//!
+//! let client = DhanClient::new("1000000001", "token");
+//! let scanner = client.scoped(Scopes::only(Scope::MarketData));
+//! let executor = client.scoped(Scopes::from([Scope::OrderRead, Scope::OrderWrite]));
+//!
+//! assert!(!scanner.has_scope(Scope::OrderWrite));
+//! assert!(executor.has_scope(Scope::OrderWrite));
+//! // Narrowing the scanner cannot widen it again.
+//! assert!(!scanner.scoped(Scopes::all()).has_scope(Scope::Funds));
+//! ```
+//!
+//! [`DhanClient`]: crate::DhanClient
+//! [`DhanClient::scoped`]: crate::DhanClient::scoped
+//! [`DhanError::ScopeDenied`]: crate::DhanError::ScopeDenied
+
+use std::fmt;
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+/// A capability needed by a REST endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Scope {
+    /// Quotes, historical charts and option chains.
+    MarketData,
+    /// Orders, trades, positions, holdings, eDIS status and account status
+    /// (profile, IP, kill switch, P&L exit).
+    OrderRead,
+    /// Placing, modifying and cancelling orders of every kind, converting
+    /// and exiting positions, and eDIS.
+    OrderWrite,
+    /// Fund limits, margin calculation and the ledger.
+    Funds,
+    /// Account controls: static IP, kill switch and P&L exit.
+    Admin,
+}
+
+impl Scope {
+    /// Every scope.
+    pub const ALL: [Scope; 5] = [
+        Scope::MarketData,
+        Scope::OrderRead,
+        Scope::OrderWrite,
+        Scope::Funds,
+        Scope::Admin,
+    ];
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A set of [`Scope`]s.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Scopes(u8);
+
+impl Scopes {
+    /// The scopes of a read-only client: everything except
+    /// [`Scope::OrderWrite`] and [`Scope::Admin`].
+    pub const READ_ONLY: Scopes = Scopes(
+        1 << Scope::MarketData as u8 | 1 << Scope::OrderRead as u8 | 1 << Scope::Funds as u8,
+    );
+
+    /// Every scope (the default for a new client).
+    pub fn all() -> Self {
+        Self::from(Scope::ALL)
+    }
+
+    /// No scope at all.
+    pub fn none() -> Self {
+        Self(0)
+    }
+
+    /// Just `scope`.
+    pub fn only(scope: Scope) -> Self {
+        Self(scope.bit())
+    }
+
+    /// Returns `true` if `scope` is in the set.
+    pub fn contains(self, scope: Scope) -> bool {
+        self.0 & scope.bit() != 0
+    }
+
+    /// The set with `scope` added.
+    pub fn with(self, scope: Scope) -> Self {
+        Self(self.0 | scope.bit())
+    }
+
+    /// The scopes in both sets.
+    pub fn intersection(self, other: Scopes) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    /// The scopes in the set, in [`Scope::ALL`] order.
+    pub fn iter(self) -> impl Iterator<Item = Scope> {
+        Scope::ALL.into_iter().filter(move |s| self.contains(*s))
+    }
+}
+
+impl Default for Scopes {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl<const N: usize> From<[Scope; N]> for Scopes {
+    fn from(scopes: [Scope; N]) -> Self {
+        scopes.into_iter().collect()
+    }
+}
+
+impl FromIterator<Scope> for Scopes {
+    fn from_iter<I: IntoIterator<Item = Scope>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::none(), Self::with)
+    }
+}
+
+impl fmt::Debug for Scopes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Non-GET endpoints that only read data, and the scope they need.
+const READING_POSTS: &[(&str, Scope)] = &[
+    ("v2/marketfeed/", Scope::MarketData),
+    ("v2/charts/", Scope::MarketData),
+    ("v2/optionchain", Scope::MarketData),
+    ("v2/margincalculator", Scope::Funds),
+];
+
+/// Non-GET endpoints that trade.
+const TRADING_PATHS: &[&str] = &[
+    "v2/orders",
+    "v2/super/",
+    "v2/forever/",
+    "v2/alerts/",
+    "v2/positions",
+    "v2/edis/",
+];
+
+/// The scope `method path` needs.
+///
+/// Writes outside the trading endpoints (static IP, kill switch, P&L exit)
+/// need [`Scope::Admin`], as do unknown paths, so a new endpoint is never
+/// less guarded than the known ones. Unknown reads need
+/// [`Scope::OrderRead`].
+pub fn required_scope(method: &Method, path: &str) -> Scope {
+    // Like `EndpointGroup::of`, accept paths with or without the leading
+    // slash so an unslashed path cannot pick up a different scope.
+    let path = path
+        .split('?')
+        .next()
+        .unwrap_or(path)
+        .trim_start_matches('/');
+    if let Some((_, scope)) = READING_POSTS.iter().find(|(p, _)| path.starts_with(p)) {
+        return *scope;
+    }
+    if *method == Method::GET {
+        return if path.starts_with("v2/fundlimit") || path.starts_with("v2/ledger") {
+            Scope::Funds
+        } else if path.starts_with("v2/edis/tpin") {
+            // Generating a TPIN sends an SMS; it is the first step of eDIS.
+            Scope::OrderWrite
+        } else {
+            Scope::OrderRead
+        };
+    }
+    if TRADING_PATHS.iter().any(|p| path.starts_with(p)) {
+        Scope::OrderWrite
+    } else {
+        Scope::Admin
+    }
+}
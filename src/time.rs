@@ -0,0 +1,122 @@
+//! Timestamp normalization between exchange time, IST and UTC.
+//!
+//! The API reports time in three forms, all rooted in Indian Standard Time
+//! (UTC+05:30):
+//!
+//! | Source                                      | Form                                  | Convert with              |
+//! |---------------------------------------------|---------------------------------------|---------------------------|
+//! | Market feed `ltt` (WebSocket packets, [`Tick`](crate::ws::market_feed::Tick)) | IST wall-clock seconds since 1970-01-01 | [`from_feed_epoch`] |
+//! | Historical candle `timestamp`               | Unix seconds (UTC)                    | [`from_epoch`]            |
+//! | Order, trade and statement strings          | IST wall-clock text, no zone          | [`parse_ist`]             |
+//!
+//! Feed epochs are the exchange clock written as if it were UTC, so a trade
+//! at 09:15 IST arrives as `…T09:15:00Z`; [`from_feed_epoch`] subtracts the
+//! IST offset to recover the true instant. Everything is normalized to
+//! `DateTime<Utc>`; use [`to_ist`] for display or session-time logic.
+//!
+//! ```
+//! use dhan_rs::time;
+//!
+//! let at = time::parse_ist("2024-09-11 09:58:03").unwrap();
+//! assert_eq!(at.to_rfc3339(), "2024-09-11T04:28:03+00:00");
+//! assert_eq!(time::to_ist(at).format("%H:%M").to_string(), "09:58");
+//! ```
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+
+use crate::types::forever_order::ForeverOrderDetail;
+use crate::types::orders::{OrderDetail, TradeDetail};
+use crate::types::statements::TradeHistoryEntry;
+use crate::types::super_order::SuperOrderDetail;
+
+/// Offset of Indian Standard Time from UTC, in seconds.
+pub const IST_OFFSET_SECS: i32 = 5 * 3600 + 30 * 60;
+
+/// Indian Standard Time (UTC+05:30), the exchange time zone.
+pub fn ist() -> FixedOffset {
+    FixedOffset::east_opt(IST_OFFSET_SECS).expect("valid IST offset")
+}
+
+/// Convert a UTC instant to IST.
+pub fn to_ist(at: DateTime<Utc>) -> DateTime<FixedOffset> {
+    at.with_timezone(&ist())
+}
+
+/// Interpret a naive wall-clock time as IST.
+pub fn ist_to_utc(local: NaiveDateTime) -> DateTime<Utc> {
+    ist()
+        .from_local_datetime(&local)
+        .single()
+        .expect("fixed offset is unambiguous")
+        .with_timezone(&Utc)
+}
+
+/// Convert Unix seconds (e.g. historical candle timestamps).
+pub fn from_epoch(secs: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(secs, 0)
+}
+
+/// Convert a market-feed epoch (IST wall-clock seconds) to UTC.
+pub fn from_feed_epoch(secs: i64) -> Option<DateTime<Utc>> {
+    from_epoch(feed_epoch_to_unix(secs))
+}
+
+/// Convert a market-feed epoch to Unix seconds.
+pub fn feed_epoch_to_unix(secs: i64) -> i64 {
+    secs - i64::from(IST_OFFSET_SECS)
+}
+
+/// Convert Unix seconds to a market-feed epoch.
+pub fn unix_to_feed_epoch(secs: i64) -> i64 {
+    secs + i64::from(IST_OFFSET_SECS)
+}
+
+/// Parse a zone-less timestamp such as `"2024-09-11 09:58:03"`.
+///
+/// Accepts a space or `T` separator and optional fractional seconds.
+pub fn parse_naive(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f"))
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f"))
+        .ok()
+}
+
+/// Parse an IST timestamp string from an order, trade or statement.
+pub fn parse_ist(s: &str) -> Option<DateTime<Utc>> {
+    parse_naive(s).map(ist_to_utc)
+}
+
+/// Adds UTC accessors for the `create_time` / `update_time` /
+/// `exchange_time` strings of REST order-like types.
+macro_rules! impl_order_times {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl $ty {
+                /// `create_time` as UTC.
+                pub fn create_time_utc(&self) -> Option<DateTime<Utc>> {
+                    self.create_time.as_deref().and_then(parse_ist)
+                }
+
+                /// `update_time` as UTC.
+                pub fn update_time_utc(&self) -> Option<DateTime<Utc>> {
+                    self.update_time.as_deref().and_then(parse_ist)
+                }
+
+                /// `exchange_time` as UTC.
+                pub fn exchange_time_utc(&self) -> Option<DateTime<Utc>> {
+                    self.exchange_time.as_deref().and_then(parse_ist)
+                }
+            }
+        )*
+    };
+}
+
+impl_order_times!(
+    OrderDetail,
+    TradeDetail,
+    TradeHistoryEntry,
+    SuperOrderDetail,
+    ForeverOrderDetail,
+);
@@ -0,0 +1,480 @@
+//! Keeping a long-running process's access token fresh.
+//!
+//! Dhan access tokens last about a day, so a bot started yesterday stops
+//! working this morning. A [`TokenManager`] reads the token's expiry from
+//! the profile (`token_validity`), and shortly before it obtains a new
+//! token from a [`TokenSource`] and installs it on the client. The client
+//! shares its token with all its clones (see
+//! [`DhanClient::set_access_token`]), so every handle in the process picks
+//! it up.
+//!
+//! Sources:
+//!
+//! - [`TotpLogin`] — PIN and TOTP secret, fully unattended
+//! - [`ConsentLogin`] — API key and secret; the browser login step is
+//!   delegated to a callback
+//! - [`Renewal`] — `GET /v2/RenewToken`, for Dhan Web tokens that are still
+//!   active
+//!
+//! Feed connections sharing the client's [`SharedToken`] reconnect with
+//! the renewed token; [`TokenManager::subscribe`] reports each one, e.g.
+//! to move open sockets onto it without a gap with
+//! [`DhanFeedManager::rotate_token`] and [`OrderUpdateStream::rotate_token`].
+//!
+//! ```no_run
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::token::{TokenManager, TotpLogin};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let client = DhanClient::new("1000000001", "");
+//! let login = TotpLogin::new("123456", "JBSWY3DPEHPK3PXP")?;
+//! let manager = TokenManager::new(client.clone(), login);
+//! manager.refresh_now().await?;
+//! manager.spawn();
+//! // `client` and its clones now stay logged in.
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`DhanFeedManager::rotate_token`]: crate::ws::manager::DhanFeedManager::rotate_token
+//! [`OrderUpdateStream::rotate_token`]: crate::ws::order_update::OrderUpdateStream::rotate_token
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use reqwest::header::HeaderValue;
+use sha1::{Digest, Sha1};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::events::{EventBus, RestEvent, SessionEvent};
+use crate::time::parse_ist;
+use crate::types::auth::TokenResponse;
+use crate::vault::DEFAULT_TOKEN_LIFETIME;
+
+/// How long before expiry the token is refreshed by default.
+pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(30 * 60);
+
+/// How long to wait after a failed refresh by default.
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+// ---------------------------------------------------------------------------
+// Shared token
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct AccessToken {
+    value: String,
+    /// Pre-built header value, cached to avoid per-request parsing.
+    header: HeaderValue,
+}
+
+impl AccessToken {
+    fn new(value: String) -> Self {
+        let header =
+            HeaderValue::from_str(&value).expect("access token contains invalid header characters");
+        Self { value, header }
+    }
+}
+
+/// An access token that can be replaced in place.
+///
+/// Every [`DhanClient`] reads its token from one (see
+/// [`DhanClient::shared_token`]); a [`DhanFeedManager`] or
+/// [`ReconnectingMarketFeedStream`] given the same one reconnects with
+/// whatever token it holds at the time, so a renewal installed on the
+/// client reaches them without rebuilding anything. [`MarketFeedStream`]
+/// and [`OrderUpdateStream`] do not reconnect by themselves; connect them
+/// again with [`Self::get`].
+///
+/// Cloning is cheap and clones share the token.
+///
+/// ```
+/// use dhan_rs::DhanClient;
+///
+/// let client = DhanClient::new("1000000001", "old");
+/// let token = client.shared_token();
+/// client.set_access_token("new");
+/// assert_eq!(token.get(), "new");
+/// ```
+///
+/// [`DhanFeedManager`]: crate::ws::manager::DhanFeedManager
+/// [`ReconnectingMarketFeedStream`]: crate::ws::reconnect::ReconnectingMarketFeedStream
+/// [`MarketFeedStream`]: crate::ws::market_feed::MarketFeedStream
+/// [`OrderUpdateStream`]: crate::ws::order_update::OrderUpdateStream
+#[derive(Debug, Clone)]
+pub struct SharedToken(Arc<RwLock<AccessToken>>);
+
+impl SharedToken {
+    /// Hold `token`.
+    ///
+    /// # Panics
+    ///
+    /// If the token contains characters not allowed in an HTTP header.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(Arc::new(RwLock::new(AccessToken::new(token.into()))))
+    }
+
+    /// The current token.
+    pub fn get(&self) -> String {
+        self.read().value.clone()
+    }
+
+    /// Replace the token for every holder.
+    ///
+    /// # Panics
+    ///
+    /// If the token contains characters not allowed in an HTTP header.
+    pub fn set(&self, token: impl Into<String>) {
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = AccessToken::new(token.into());
+    }
+
+    /// The current token as a header value.
+    pub(crate) fn header(&self) -> HeaderValue {
+        self.read().header.clone()
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, AccessToken> {
+        self.0.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Sources
+// ---------------------------------------------------------------------------
+
+/// Obtains a new access token.
+pub trait TokenSource: Send + Sync {
+    /// Short name used in logs.
+    fn name(&self) -> &str;
+
+    /// Fetch a new token for the user of `client`.
+    fn fetch<'a>(&'a self, client: &'a DhanClient) -> BoxFuture<'a, Result<TokenResponse>>;
+}
+
+/// Logs in with the Dhan PIN and a code from the account's TOTP secret
+/// (`POST /app/generateAccessToken`). TOTP must be enabled on the account.
+#[derive(Clone)]
+pub struct TotpLogin {
+    pin: String,
+    secret: Vec<u8>,
+}
+
+impl TotpLogin {
+    /// `secret` is the base32 key shown when TOTP was set up (spaces and
+    /// padding are ignored).
+    pub fn new(pin: impl Into<String>, secret: &str) -> Result<Self> {
+        let secret = decode_base32(secret)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| DhanError::InvalidArgument("TOTP secret is not valid base32".into()))?;
+        Ok(Self {
+            pin: pin.into(),
+            secret,
+        })
+    }
+
+    /// The 6-digit code valid at `at` (RFC 6238, 30-second steps).
+    pub fn code_at(&self, at: DateTime<Utc>) -> String {
+        let step = (at.timestamp().max(0) / 30) as u64;
+        let mac = hmac_sha1(&self.secret, &step.to_be_bytes());
+        let offset = (mac[19] & 0x0f) as usize;
+        let value = u32::from_be_bytes([
+            mac[offset] & 0x7f,
+            mac[offset + 1],
+            mac[offset + 2],
+            mac[offset + 3],
+        ]);
+        format!("{:06}", value % 1_000_000)
+    }
+}
+
+impl std::fmt::Debug for TotpLogin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TotpLogin").finish_non_exhaustive()
+    }
+}
+
+impl TokenSource for TotpLogin {
+    fn name(&self) -> &str {
+        "totp"
+    }
+
+    fn fetch<'a>(&'a self, client: &'a DhanClient) -> BoxFuture<'a, Result<TokenResponse>> {
+        Box::pin(async move {
+            let totp = self.code_at(Utc::now());
+            DhanClient::generate_access_token(client.client_id(), &self.pin, &totp).await
+        })
+    }
+}
+
+type LoginCallback = dyn Fn(String) -> BoxFuture<'static, Result<String>> + Send + Sync;
+
+/// Logs in through the API key consent flow. The callback receives the
+/// browser login URL and returns the `tokenId` the redirect carried, e.g.
+/// after driving a headless browser or asking an operator.
+pub struct ConsentLogin {
+    app_id: String,
+    app_secret: String,
+    login: Box<LoginCallback>,
+}
+
+impl ConsentLogin {
+    /// Use the API key `app_id` and its secret.
+    pub fn new<F, Fut>(app_id: impl Into<String>, app_secret: impl Into<String>, login: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String>> + Send + 'static,
+    {
+        Self {
+            app_id: app_id.into(),
+            app_secret: app_secret.into(),
+            login: Box::new(move |url| Box::pin(login(url))),
+        }
+    }
+}
+
+impl std::fmt::Debug for ConsentLogin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConsentLogin")
+            .field("app_id", &self.app_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenSource for ConsentLogin {
+    fn name(&self) -> &str {
+        "consent"
+    }
+
+    fn fetch<'a>(&'a self, client: &'a DhanClient) -> BoxFuture<'a, Result<TokenResponse>> {
+        Box::pin(async move {
+            let consent =
+                DhanClient::generate_consent(client.client_id(), &self.app_id, &self.app_secret)
+                    .await?;
+            let url = DhanClient::consent_login_url(&consent.consent_app_id);
+            let token_id = (self.login)(url).await?;
+            DhanClient::consume_consent(&token_id, &self.app_id, &self.app_secret).await
+        })
+    }
+}
+
+/// Renews the client's current token (`GET /v2/RenewToken`). Only works
+/// for Dhan Web tokens that have not expired yet.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Renewal;
+
+impl TokenSource for Renewal {
+    fn name(&self) -> &str {
+        "renewal"
+    }
+
+    fn fetch<'a>(&'a self, client: &'a DhanClient) -> BoxFuture<'a, Result<TokenResponse>> {
+        Box::pin(client.renew_token())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Manager
+// ---------------------------------------------------------------------------
+
+/// Refreshes a client's access token before it expires.
+///
+/// Cloning is cheap and clones share the known expiry.
+#[derive(Clone)]
+pub struct TokenManager {
+    client: DhanClient,
+    source: Arc<dyn TokenSource>,
+    margin: Duration,
+    retry_delay: Duration,
+    events: Option<EventBus>,
+    expires_at: Arc<Mutex<Option<DateTime<Utc>>>>,
+    tokens: Arc<watch::Sender<String>>,
+}
+
+impl std::fmt::Debug for TokenManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenManager")
+            .field("source", &self.source.name())
+            .field("margin", &self.margin)
+            .field("expires_at", &self.expires_at())
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenManager {
+    /// Keep `client`'s token fresh with tokens from `source`.
+    pub fn new(client: DhanClient, source: impl TokenSource + 'static) -> Self {
+        let token = client.access_token();
+        Self {
+            client,
+            source: Arc::new(source),
+            margin: DEFAULT_REFRESH_MARGIN,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            events: None,
+            expires_at: Arc::default(),
+            tokens: Arc::new(watch::Sender::new(token)),
+        }
+    }
+
+    /// Refresh `margin` before expiry (default [`DEFAULT_REFRESH_MARGIN`]).
+    pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Wait `delay` after a failed refresh before trying again (default
+    /// [`DEFAULT_RETRY_DELAY`]).
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Publish each refresh attempt on `bus` as a [`RestEvent`] named
+    /// `token_refresh`.
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
+    }
+
+    /// The managed client.
+    pub fn client(&self) -> &DhanClient {
+        &self.client
+    }
+
+    /// When the current token expires, once known.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        *self.expires_at.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// A receiver that changes with every new token, e.g. to reconnect
+    /// feed connections with it.
+    pub fn subscribe(&self) -> watch::Receiver<String> {
+        self.tokens.subscribe()
+    }
+
+    /// Read the current token's expiry from the profile and remember it.
+    pub async fn check_validity(&self) -> Result<DateTime<Utc>> {
+        let profile = self.client.get_profile().await?;
+        let expiry = profile.token_expiry().ok_or_else(|| {
+            DhanError::InvalidArgument(format!(
+                "unrecognised token validity {:?}",
+                profile.token_validity
+            ))
+        })?;
+        self.set_expiry(expiry);
+        Ok(expiry)
+    }
+
+    /// Fetch a new token now and install it on the client.
+    pub async fn refresh_now(&self) -> Result<TokenResponse> {
+        let result = self.source.fetch(&self.client).await;
+        self.publish(result.as_ref().err());
+        let token = result?;
+        self.client.set_access_token(&token.access_token);
+        self.tokens.send_replace(token.access_token.clone());
+        let expiry = match token.expiry_time.as_deref().and_then(parse_ist) {
+            Some(expiry) => expiry,
+            None => match self.check_validity().await {
+                Ok(expiry) => expiry,
+                Err(_) => Utc::now() + DEFAULT_TOKEN_LIFETIME,
+            },
+        };
+        self.set_expiry(expiry);
+        tracing::info!(source = self.source.name(), %expiry, "Access token refreshed");
+        Ok(token)
+    }
+
+    /// Refresh the token before each expiry, forever. A token whose expiry
+    /// cannot be read (e.g. because it has already expired) is refreshed at
+    /// once; failed refreshes are retried after the retry delay.
+    pub async fn run(self) {
+        loop {
+            let expiry = match self.expires_at() {
+                Some(expiry) => Some(expiry),
+                None => self.check_validity().await.ok(),
+            };
+            if let Some(expiry) = expiry {
+                let due = expiry - chrono::Duration::from_std(self.margin).unwrap_or_default();
+                if let Ok(wait) = (due - Utc::now()).to_std() {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+            if let Err(e) = self.refresh_now().await {
+                tracing::warn!(
+                    source = self.source.name(),
+                    error = %e,
+                    "Access token refresh failed"
+                );
+                tokio::time::sleep(self.retry_delay).await;
+            }
+        }
+    }
+
+    /// Run [`Self::run`] on a background task.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+
+    fn set_expiry(&self, expiry: DateTime<Utc>) {
+        *self.expires_at.lock().unwrap_or_else(|e| e.into_inner()) = Some(expiry);
+    }
+
+    fn publish(&self, error: Option<&DhanError>) {
+        if let Some(bus) = &self.events {
+            bus.publish(SessionEvent::Rest(RestEvent {
+                operation: "token_refresh".into(),
+                error: error.map(ToString::to_string),
+            }));
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TOTP helpers
+// ---------------------------------------------------------------------------
+
+/// RFC 4648 base32, case-insensitive, ignoring spaces and padding.
+fn decode_base32(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in s.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u32 - 'A' as u32,
+            c @ '2'..='7' => c as u32 - '2' as u32 + 26,
+            _ => return None,
+        };
+        buffer = ((buffer << 5) | value) & 0xffff;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+fn hmac_sha1(key: &[u8], message: &[u8]) -> [u8; 20] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..20].copy_from_slice(&Sha1::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let pad = |byte: u8| block.map(|b| b ^ byte);
+    let inner = Sha1::new()
+        .chain_update(pad(0x36))
+        .chain_update(message)
+        .finalize();
+    Sha1::new()
+        .chain_update(pad(0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
@@ -0,0 +1,250 @@
+//! The HTTP layer under [`DhanClient`], replaceable for tests.
+//!
+//! Every REST call ends in [`HttpTransport::execute`]. By default that is
+//! the client's `reqwest::Client`; [`DhanClient::with_transport`] swaps in
+//! any other implementation, such as [`MockTransport`], which answers with
+//! canned responses and records what was sent. The client's own handling —
+//! scopes, budgets, audit log, error parsing — runs unchanged on top, so
+//! error paths can be tested offline:
+//!
+//! ```
+//! use dhan_rs::DhanClient;
+//! use dhan_rs::error::DhanError;
+//! use dhan_rs::transport::{MockResponse, MockTransport};
+//! use reqwest::Method;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let mock = MockTransport::new().on(
+//!     Method::GET,
+//!     "/v2/holdings",
+//!     MockResponse::api_error(429, "DH-905", "Too many requests"),
+//! );
+//! let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+//!
+//! let err = client.get_holdings().await.unwrap_err();
+//! assert!(matches!(&err, DhanError::Api(body) if body.error_code.as_deref() == Some("DH-905")));
+//! assert_eq!(mock.requests()[0].header("client-id"), Some("1000000001"));
+//! # }
+//! ```
+//!
+//! Requests the mock has no route for get a `404` with an explanatory
+//! body.
+//!
+//! [`DhanClient`]: crate::client::DhanClient
+//! [`DhanClient::with_transport`]: crate::client::DhanClient::with_transport
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use futures_util::future::BoxFuture;
+use reqwest::Method;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::error::Result;
+
+/// Sends requests built by the client and returns the responses.
+pub trait HttpTransport: Send + Sync + std::fmt::Debug {
+    /// Send `request`.
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>>;
+}
+
+impl HttpTransport for reqwest::Client {
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>> {
+        Box::pin(async move { Ok(reqwest::Client::execute(self, request).await?) })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Mock
+// ---------------------------------------------------------------------------
+
+/// A canned HTTP response.
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    body: Vec<u8>,
+}
+
+impl MockResponse {
+    /// A response with `status` and a JSON `body`.
+    pub fn json(status: u16, body: serde_json::Value) -> Self {
+        Self {
+            status,
+            headers: vec![(
+                reqwest::header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            )],
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    /// A Dhan error response, e.g. `api_error(429, "DH-905", "...")`.
+    pub fn api_error(status: u16, code: &str, message: &str) -> Self {
+        Self::json(
+            status,
+            serde_json::json!({
+                "errorType": "Error",
+                "errorCode": code,
+                "errorMessage": message,
+            }),
+        )
+    }
+
+    /// A response with `status` and a plain `body`.
+    pub fn text(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into().into_bytes(),
+        }
+    }
+
+    /// Add a header.
+    ///
+    /// # Panics
+    ///
+    /// If `name` or `value` is not a valid header name or value.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((
+            HeaderName::from_bytes(name.as_bytes()).expect("invalid header name"),
+            HeaderValue::from_str(value).expect("invalid header value"),
+        ));
+        self
+    }
+
+    fn into_response(self) -> reqwest::Response {
+        let mut response = http::Response::new(self.body);
+        *response.status_mut() =
+            http::StatusCode::from_u16(self.status).unwrap_or(http::StatusCode::OK);
+        response.headers_mut().extend(self.headers);
+        reqwest::Response::from(response)
+    }
+}
+
+/// A request received by a [`MockTransport`].
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// HTTP method.
+    pub method: Method,
+    /// URL path, e.g. `/v2/orders`.
+    pub path: String,
+    /// Query string, if any.
+    pub query: Option<String>,
+    /// Request headers.
+    pub headers: HeaderMap,
+    /// Request body (possibly gzipped; see `Content-Encoding`).
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// The value of header `name`, if present and text.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// The body parsed as JSON.
+    pub fn json(&self) -> Result<serde_json::Value> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+#[derive(Debug)]
+struct Route {
+    method: Method,
+    path: String,
+    responses: VecDeque<MockResponse>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    routes: Vec<Route>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// An [`HttpTransport`] answering from canned responses.
+///
+/// Routes match on method and URL path; the query string and host are
+/// ignored. Responses given for one route are served in order, the last
+/// one for every request after that. Clones share routes and recorded
+/// requests.
+#[derive(Debug, Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockTransport {
+    /// A transport without routes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer `method path` with `response` (after any responses already
+    /// queued for it).
+    pub fn on(self, method: Method, path: &str, response: MockResponse) -> Self {
+        {
+            let mut state = self.lock();
+            match state
+                .routes
+                .iter_mut()
+                .find(|r| r.method == method && r.path == path)
+            {
+                Some(route) => route.responses.push_back(response),
+                None => state.routes.push(Route {
+                    method,
+                    path: path.to_owned(),
+                    responses: VecDeque::from([response]),
+                }),
+            }
+        }
+        self
+    }
+
+    /// Every request received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn respond(&self, request: reqwest::Request) -> reqwest::Response {
+        let recorded = RecordedRequest {
+            method: request.method().clone(),
+            path: request.url().path().to_owned(),
+            query: request.url().query().map(str::to_owned),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|b| b.as_bytes())
+                .map(<[u8]>::to_vec)
+                .unwrap_or_default(),
+        };
+        let mut state = self.lock();
+        let response = state
+            .routes
+            .iter_mut()
+            .find(|r| r.method == recorded.method && r.path == recorded.path)
+            .and_then(|route| match route.responses.len() {
+                0 => None,
+                1 => route.responses.front().cloned(),
+                _ => route.responses.pop_front(),
+            })
+            .unwrap_or_else(|| {
+                MockResponse::text(
+                    404,
+                    format!("no mock response for {} {}", recorded.method, recorded.path),
+                )
+            });
+        state.requests.push(recorded);
+        response.into_response()
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>> {
+        Box::pin(async move { Ok(self.respond(request)) })
+    }
+}
@@ -106,6 +106,57 @@ pub struct IpInfo {
     pub modify_date_secondary: Option<String>,
 }
 
+impl IpInfo {
+    /// The IP set in `slot`.
+    pub fn ip(&self, slot: crate::types::IpFlag) -> Option<&str> {
+        match slot {
+            crate::types::IpFlag::PRIMARY => self.primary_ip.as_deref(),
+            crate::types::IpFlag::SECONDARY => self.secondary_ip.as_deref(),
+        }
+        .filter(|ip| !ip.is_empty())
+    }
+
+    /// The date from which `slot` can be modified.
+    pub fn modify_date(&self, slot: crate::types::IpFlag) -> Option<chrono::NaiveDate> {
+        match slot {
+            crate::types::IpFlag::PRIMARY => self.modify_date_primary.as_deref(),
+            crate::types::IpFlag::SECONDARY => self.modify_date_secondary.as_deref(),
+        }
+        .and_then(|d| chrono::NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+    }
+
+    /// The slot `ip` is whitelisted in, if any.
+    pub fn slot_of(&self, ip: &str) -> Option<crate::types::IpFlag> {
+        [
+            crate::types::IpFlag::PRIMARY,
+            crate::types::IpFlag::SECONDARY,
+        ]
+        .into_iter()
+        .find(|slot| self.ip(*slot) == Some(ip))
+    }
+}
+
+/// Result of [`DhanClient::verify_ip_whitelisting`].
+///
+/// [`DhanClient::verify_ip_whitelisting`]: crate::DhanClient::verify_ip_whitelisting
+#[derive(Debug, Clone)]
+pub struct IpWhitelistStatus {
+    /// This machine's public IP.
+    pub current_ip: String,
+    /// The slot it is whitelisted in, or `None` if API calls from it will
+    /// be refused.
+    pub slot: Option<crate::types::IpFlag>,
+    /// The whitelisted IPs.
+    pub info: IpInfo,
+}
+
+impl IpWhitelistStatus {
+    /// Returns `true` if the current IP is whitelisted.
+    pub fn is_whitelisted(&self) -> bool {
+        self.slot.is_some()
+    }
+}
+
 /// Generic success response from set/modify IP.
 #[derive(Debug, Clone, Deserialize)]
 pub struct IpSetResponse {
@@ -0,0 +1,417 @@
+//! Builders for request types.
+//!
+//! Every request struct gets a `builder()` constructor returning a
+//! `*Builder` with one setter per field. [`build`](PlaceOrderRequestBuilder::build)
+//! fails with [`DhanError::InvalidArgument`] naming any required field that
+//! was not set; optional fields default to `None`.
+//!
+//! ```
+//! use dhan_rs::types::enums::*;
+//! use dhan_rs::types::orders::PlaceOrderRequest;
+//!
+//! let req = PlaceOrderRequest::builder()
+//!     .dhan_client_id("1000000001")
+//!     .transaction_type(TransactionType::BUY)
+//!     .exchange_segment(ExchangeSegment::NSE_EQ)
+//!     .product_type(ProductType::INTRADAY)
+//!     .order_type(OrderType::LIMIT)
+//!     .validity(Validity::DAY)
+//!     .security_id("1333")
+//!     .quantity(1)
+//!     .price(1500.0)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(req.price, Some(1500.0));
+//! assert!(req.trigger_price.is_none());
+//! ```
+//!
+//! Builders of requests carrying a `dhan_client_id` implement
+//! [`ClientRequestBuilder`], so the field can be left unset and filled from
+//! the client instead — see [`DhanClient::build_request`] and the
+//! `*_for_self` endpoint methods such as [`DhanClient::place_order_for_self`].
+
+use crate::client::DhanClient;
+use crate::error::{DhanError, Result};
+use crate::types::IpFlag;
+use crate::types::auth::SetIpRequest;
+use crate::types::conditional::{AlertCondition, AlertOrder, ConditionalTriggerRequest};
+use crate::types::edis::EdisFormRequest;
+use crate::types::enums::*;
+use crate::types::forever_order::{CreateForeverOrderRequest, ModifyForeverOrderRequest};
+use crate::types::funds::{MarginCalculatorRequest, MarginScript, MultiMarginRequest};
+use crate::types::historical::{HistoricalDataRequest, IntradayDataRequest};
+use crate::types::orders::{ModifyOrderRequest, PlaceOrderRequest};
+use crate::types::portfolio::ConvertPositionRequest;
+use crate::types::super_order::{ModifySuperOrderRequest, PlaceSuperOrderRequest};
+use crate::types::traders_control::PnlExitRequest;
+
+/// A request builder whose `dhan_client_id` can be supplied by the client.
+pub trait ClientRequestBuilder {
+    /// The request being built.
+    type Request;
+
+    /// Build the request, using `client_id` when `dhan_client_id` is unset.
+    fn build_for(self, client_id: &str) -> Result<Self::Request>;
+}
+
+/// Generates `$builder` for `$target`.
+///
+/// Fields marked `@into` take `impl Into<T>` (used for strings); the rest
+/// take `T` so integer literals infer correctly. Optional fields are
+/// `Option<T>` on the target and take `T` in the setter.
+macro_rules! request_builder {
+    (@setter [into] $field:ident: $ty:ty) => {
+        #[doc = concat!("Set `", stringify!($field), "`.")]
+        pub fn $field(mut self, value: impl Into<$ty>) -> Self {
+            self.$field = Some(value.into());
+            self
+        }
+    };
+    (@setter [] $field:ident: $ty:ty) => {
+        #[doc = concat!("Set `", stringify!($field), "`.")]
+        pub fn $field(mut self, value: $ty) -> Self {
+            self.$field = Some(value);
+            self
+        }
+    };
+    (
+        $target:ident => $builder:ident {
+            required { $($req:ident: $(@$req_into:ident)? $req_ty:ty),* $(,)? }
+            optional { $($opt:ident: $(@$opt_into:ident)? $opt_ty:ty),* $(,)? }
+        }
+    ) => {
+        #[doc = concat!("Builder for [`", stringify!($target), "`].")]
+        #[derive(Debug, Clone, Default)]
+        pub struct $builder {
+            $($req: Option<$req_ty>,)*
+            $($opt: Option<$opt_ty>,)*
+        }
+
+        impl $target {
+            #[doc = concat!("Start building a [`", stringify!($target), "`].")]
+            pub fn builder() -> $builder {
+                $builder::default()
+            }
+        }
+
+        impl $builder {
+            $(request_builder!(@setter [$($req_into)?] $req: $req_ty);)*
+            $(request_builder!(@setter [$($opt_into)?] $opt: $opt_ty);)*
+
+            /// Build the request, failing if a required field is unset.
+            pub fn build(self) -> Result<$target> {
+                let mut missing: Vec<&str> = Vec::new();
+                $(if self.$req.is_none() {
+                    missing.push(stringify!($req));
+                })*
+                if !missing.is_empty() {
+                    return Err(DhanError::InvalidArgument(format!(
+                        "{}: missing required field(s): {}",
+                        stringify!($target),
+                        missing.join(", ")
+                    )));
+                }
+                Ok($target {
+                    $($req: self.$req.expect("checked above"),)*
+                    $($opt: self.$opt,)*
+                })
+            }
+        }
+    };
+}
+
+/// Implements [`ClientRequestBuilder`] for builders with a
+/// `dhan_client_id` setter.
+macro_rules! impl_client_request_builder {
+    ($($builder:ident => $target:ident),* $(,)?) => {
+        $(
+            impl ClientRequestBuilder for $builder {
+                type Request = $target;
+
+                fn build_for(mut self, client_id: &str) -> Result<$target> {
+                    if self.dhan_client_id.is_none() {
+                        self.dhan_client_id = Some(client_id.to_owned());
+                    }
+                    self.build()
+                }
+            }
+        )*
+    };
+}
+
+request_builder! {
+    PlaceOrderRequest => PlaceOrderRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            transaction_type: TransactionType,
+            exchange_segment: ExchangeSegment,
+            product_type: ProductType,
+            order_type: OrderType,
+            validity: Validity,
+            security_id: @into String,
+            quantity: u64,
+        }
+        optional {
+            correlation_id: @into String,
+            disclosed_quantity: u64,
+            price: f64,
+            trigger_price: f64,
+            after_market_order: bool,
+            amo_time: AmoTime,
+            bo_profit_value: f64,
+            bo_stop_loss_value: f64,
+        }
+    }
+}
+
+request_builder! {
+    ModifyOrderRequest => ModifyOrderRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            order_id: @into String,
+            order_type: OrderType,
+            validity: Validity,
+        }
+        optional {
+            leg_name: LegName,
+            quantity: u64,
+            price: f64,
+            disclosed_quantity: u64,
+            trigger_price: f64,
+        }
+    }
+}
+
+request_builder! {
+    PlaceSuperOrderRequest => PlaceSuperOrderRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            transaction_type: TransactionType,
+            exchange_segment: ExchangeSegment,
+            product_type: ProductType,
+            order_type: OrderType,
+            security_id: @into String,
+            quantity: u64,
+            price: f64,
+            target_price: f64,
+            stop_loss_price: f64,
+            trailing_jump: f64,
+        }
+        optional {
+            correlation_id: @into String,
+        }
+    }
+}
+
+request_builder! {
+    ModifySuperOrderRequest => ModifySuperOrderRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            order_id: @into String,
+            leg_name: LegName,
+        }
+        optional {
+            order_type: OrderType,
+            quantity: u64,
+            price: f64,
+            target_price: f64,
+            stop_loss_price: f64,
+            trailing_jump: f64,
+        }
+    }
+}
+
+request_builder! {
+    CreateForeverOrderRequest => CreateForeverOrderRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            order_flag: OrderFlag,
+            transaction_type: TransactionType,
+            exchange_segment: ExchangeSegment,
+            product_type: ProductType,
+            order_type: OrderType,
+            validity: Validity,
+            security_id: @into String,
+            quantity: u64,
+            price: f64,
+            trigger_price: f64,
+        }
+        optional {
+            correlation_id: @into String,
+            disclosed_quantity: u64,
+            price1: f64,
+            trigger_price1: f64,
+            quantity1: u64,
+        }
+    }
+}
+
+request_builder! {
+    ModifyForeverOrderRequest => ModifyForeverOrderRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            order_id: @into String,
+            order_flag: OrderFlag,
+            order_type: OrderType,
+            leg_name: LegName,
+            quantity: u64,
+            price: f64,
+            trigger_price: f64,
+            validity: Validity,
+        }
+        optional {
+            disclosed_quantity: u64,
+        }
+    }
+}
+
+request_builder! {
+    ConditionalTriggerRequest => ConditionalTriggerRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            condition: AlertCondition,
+            orders: Vec<AlertOrder>,
+        }
+        optional {
+            alert_id: @into String,
+        }
+    }
+}
+
+request_builder! {
+    MarginCalculatorRequest => MarginCalculatorRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            exchange_segment: ExchangeSegment,
+            transaction_type: TransactionType,
+            quantity: u64,
+            product_type: ProductType,
+            security_id: @into String,
+            price: f64,
+        }
+        optional {
+            trigger_price: f64,
+        }
+    }
+}
+
+request_builder! {
+    MultiMarginRequest => MultiMarginRequestBuilder {
+        required {
+            scripts: Vec<MarginScript>,
+        }
+        optional {
+            include_position: bool,
+            include_orders: bool,
+            dhan_client_id: @into String,
+        }
+    }
+}
+
+request_builder! {
+    ConvertPositionRequest => ConvertPositionRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            from_product_type: ProductType,
+            exchange_segment: ExchangeSegment,
+            position_type: PositionType,
+            security_id: @into String,
+            convert_qty: u64,
+            to_product_type: ProductType,
+        }
+        optional {
+            trading_symbol: @into String,
+        }
+    }
+}
+
+request_builder! {
+    EdisFormRequest => EdisFormRequestBuilder {
+        required {
+            isin: @into String,
+            qty: u64,
+            exchange: @into String,
+            segment: @into String,
+        }
+        optional {
+            bulk: bool,
+        }
+    }
+}
+
+request_builder! {
+    HistoricalDataRequest => HistoricalDataRequestBuilder {
+        required {
+            security_id: @into String,
+            exchange_segment: ExchangeSegment,
+            instrument: Instrument,
+            from_date: @into String,
+            to_date: @into String,
+        }
+        optional {
+            expiry_code: u8,
+            oi: bool,
+        }
+    }
+}
+
+request_builder! {
+    IntradayDataRequest => IntradayDataRequestBuilder {
+        required {
+            security_id: @into String,
+            exchange_segment: ExchangeSegment,
+            instrument: Instrument,
+            interval: @into String,
+            from_date: @into String,
+            to_date: @into String,
+        }
+        optional {
+            oi: bool,
+        }
+    }
+}
+
+request_builder! {
+    PnlExitRequest => PnlExitRequestBuilder {
+        required {
+            profit_value: @into String,
+            loss_value: @into String,
+            product_type: Vec<String>,
+            enable_kill_switch: bool,
+        }
+        optional {}
+    }
+}
+
+request_builder! {
+    SetIpRequest => SetIpRequestBuilder {
+        required {
+            dhan_client_id: @into String,
+            ip: @into String,
+            ip_flag: IpFlag,
+        }
+        optional {}
+    }
+}
+
+impl_client_request_builder!(
+    PlaceOrderRequestBuilder => PlaceOrderRequest,
+    ModifyOrderRequestBuilder => ModifyOrderRequest,
+    PlaceSuperOrderRequestBuilder => PlaceSuperOrderRequest,
+    ModifySuperOrderRequestBuilder => ModifySuperOrderRequest,
+    CreateForeverOrderRequestBuilder => CreateForeverOrderRequest,
+    ModifyForeverOrderRequestBuilder => ModifyForeverOrderRequest,
+    ConditionalTriggerRequestBuilder => ConditionalTriggerRequest,
+    MarginCalculatorRequestBuilder => MarginCalculatorRequest,
+    MultiMarginRequestBuilder => MultiMarginRequest,
+    ConvertPositionRequestBuilder => ConvertPositionRequest,
+    SetIpRequestBuilder => SetIpRequest,
+);
+
+impl DhanClient {
+    /// Build a request, filling `dhan_client_id` with this client's ID when
+    /// the builder leaves it unset.
+    pub fn build_request<B: ClientRequestBuilder>(&self, builder: B) -> Result<B::Request> {
+        builder.build_for(self.client_id())
+    }
+}
@@ -0,0 +1,199 @@
+//! Full market depth order book shared by REST quotes and WebSocket feeds.
+//!
+//! [`DepthBook`] holds up to [`MAX_DEPTH_LEVELS`] bid and ask levels and can
+//! be populated from the REST quote depth ([`DepthData`]), the 5-level depth
+//! of a market-feed Full packet, or per-side level lists such as those of the
+//! 20-level depth feed.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::market_quote::DepthData;
+use crate::ws::market_feed;
+
+/// Maximum number of levels kept per side (the 20-level depth feed).
+pub const MAX_DEPTH_LEVELS: usize = 20;
+
+// ---------------------------------------------------------------------------
+// Book level
+// ---------------------------------------------------------------------------
+
+/// A single price level on one side of the book.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BookLevel {
+    /// Price of the level.
+    pub price: f64,
+    /// Total quantity resting at this price.
+    pub quantity: u64,
+    /// Number of orders resting at this price.
+    pub orders: u32,
+}
+
+impl BookLevel {
+    /// Create a new level.
+    pub fn new(price: f64, quantity: u64, orders: u32) -> Self {
+        Self {
+            price,
+            quantity,
+            orders,
+        }
+    }
+
+    /// Empty levels (zero price or quantity) are padding sent by the exchange.
+    fn is_empty(&self) -> bool {
+        self.price <= 0.0 || self.quantity == 0
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Depth book
+// ---------------------------------------------------------------------------
+
+/// A bid/ask order book of up to [`MAX_DEPTH_LEVELS`] levels per side.
+///
+/// Bids are kept sorted best (highest) first and asks best (lowest) first.
+/// Empty padding levels are dropped.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DepthBook {
+    /// Bid levels, best (highest price) first.
+    pub bids: Vec<BookLevel>,
+    /// Ask levels, best (lowest price) first.
+    pub asks: Vec<BookLevel>,
+}
+
+impl DepthBook {
+    /// Build a book from bid and ask levels in any order.
+    pub fn new(
+        bids: impl IntoIterator<Item = BookLevel>,
+        asks: impl IntoIterator<Item = BookLevel>,
+    ) -> Self {
+        let mut book = Self::default();
+        book.set_bids(bids);
+        book.set_asks(asks);
+        book
+    }
+
+    /// Build a book from the 5-level depth of a market-feed Full packet.
+    pub fn from_feed_depth(depth: &[market_feed::DepthLevel]) -> Self {
+        Self::new(
+            depth.iter().map(|l| {
+                BookLevel::new(
+                    f64::from(l.bid_price),
+                    l.bid_qty.max(0) as u64,
+                    l.bid_orders.max(0) as u32,
+                )
+            }),
+            depth.iter().map(|l| {
+                BookLevel::new(
+                    f64::from(l.ask_price),
+                    l.ask_qty.max(0) as u64,
+                    l.ask_orders.max(0) as u32,
+                )
+            }),
+        )
+    }
+
+    /// Replace the bid side (e.g. from a 20-level bid packet).
+    pub fn set_bids(&mut self, levels: impl IntoIterator<Item = BookLevel>) {
+        self.bids = normalize(levels, |a, b| b.price.total_cmp(&a.price));
+    }
+
+    /// Replace the ask side (e.g. from a 20-level ask packet).
+    pub fn set_asks(&mut self, levels: impl IntoIterator<Item = BookLevel>) {
+        self.asks = normalize(levels, |a, b| a.price.total_cmp(&b.price));
+    }
+
+    /// Returns `true` if both sides are empty.
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    /// Best (highest) bid level.
+    pub fn best_bid(&self) -> Option<&BookLevel> {
+        self.bids.first()
+    }
+
+    /// Best (lowest) ask level.
+    pub fn best_ask(&self) -> Option<&BookLevel> {
+        self.asks.first()
+    }
+
+    /// Best ask minus best bid.
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+
+    /// Midpoint of the best bid and ask.
+    pub fn mid_price(&self) -> Option<f64> {
+        Some((self.best_ask()?.price + self.best_bid()?.price) / 2.0)
+    }
+
+    /// Size-weighted mid price of the top of book.
+    ///
+    /// `(bid × ask_qty + ask × bid_qty) / (bid_qty + ask_qty)` — leans towards
+    /// the side with less resting quantity, i.e. the likely next trade price.
+    pub fn microprice(&self) -> Option<f64> {
+        let bid = self.best_bid()?;
+        let ask = self.best_ask()?;
+        let total = (bid.quantity + ask.quantity) as f64;
+        if total == 0.0 {
+            return None;
+        }
+        Some((bid.price * ask.quantity as f64 + ask.price * bid.quantity as f64) / total)
+    }
+
+    /// Total bid quantity at prices greater than or equal to `price` — what a
+    /// sell order limited at `price` could fill against.
+    pub fn cumulative_bid_quantity_at(&self, price: f64) -> u64 {
+        self.bids
+            .iter()
+            .take_while(|l| l.price >= price)
+            .map(|l| l.quantity)
+            .sum()
+    }
+
+    /// Total ask quantity at prices less than or equal to `price` — what a
+    /// buy order limited at `price` could fill against.
+    pub fn cumulative_ask_quantity_at(&self, price: f64) -> u64 {
+        self.asks
+            .iter()
+            .take_while(|l| l.price <= price)
+            .map(|l| l.quantity)
+            .sum()
+    }
+
+    /// Total quantity across all bid levels.
+    pub fn total_bid_quantity(&self) -> u64 {
+        self.bids.iter().map(|l| l.quantity).sum()
+    }
+
+    /// Total quantity across all ask levels.
+    pub fn total_ask_quantity(&self) -> u64 {
+        self.asks.iter().map(|l| l.quantity).sum()
+    }
+}
+
+impl From<&DepthData> for DepthBook {
+    fn from(depth: &DepthData) -> Self {
+        let level = |l: &crate::types::market_quote::DepthLevel| {
+            BookLevel::new(l.price, l.quantity.max(0) as u64, l.orders.max(0) as u32)
+        };
+        Self::new(depth.buy.iter().map(level), depth.sell.iter().map(level))
+    }
+}
+
+impl From<DepthData> for DepthBook {
+    fn from(depth: DepthData) -> Self {
+        Self::from(&depth)
+    }
+}
+
+/// Drop empty levels, sort best-first and cap at [`MAX_DEPTH_LEVELS`].
+fn normalize(
+    levels: impl IntoIterator<Item = BookLevel>,
+    best_first: impl FnMut(&BookLevel, &BookLevel) -> std::cmp::Ordering,
+) -> Vec<BookLevel> {
+    let mut out: Vec<BookLevel> = levels.into_iter().filter(|l| !l.is_empty()).collect();
+    out.sort_by(best_first);
+    out.truncate(MAX_DEPTH_LEVELS);
+    out
+}
@@ -61,6 +61,45 @@ impl ExchangeSegment {
             _ => None,
         }
     }
+
+    /// The segment name as used in REST payloads (e.g. `"NSE_EQ"`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::IDX_I => "IDX_I",
+            Self::NSE_EQ => "NSE_EQ",
+            Self::NSE_FNO => "NSE_FNO",
+            Self::NSE_CURRENCY => "NSE_CURRENCY",
+            Self::BSE_EQ => "BSE_EQ",
+            Self::MCX_COMM => "MCX_COMM",
+            Self::BSE_CURRENCY => "BSE_CURRENCY",
+            Self::BSE_FNO => "BSE_FNO",
+        }
+    }
+}
+
+impl std::fmt::Display for ExchangeSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses segment names such as `"NSE_EQ"` (case-insensitive).
+impl std::str::FromStr for ExchangeSegment {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "IDX_I" => Ok(Self::IDX_I),
+            "NSE_EQ" => Ok(Self::NSE_EQ),
+            "NSE_FNO" => Ok(Self::NSE_FNO),
+            "NSE_CURRENCY" => Ok(Self::NSE_CURRENCY),
+            "BSE_EQ" => Ok(Self::BSE_EQ),
+            "MCX_COMM" => Ok(Self::MCX_COMM),
+            "BSE_CURRENCY" => Ok(Self::BSE_CURRENCY),
+            "BSE_FNO" => Ok(Self::BSE_FNO),
+            _ => Err(format!("unknown exchange segment: {s}")),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -74,6 +113,18 @@ pub enum TransactionType {
     SELL,
 }
 
+impl TransactionType {
+    /// Parse the abbreviated code used by the order-update WebSocket
+    /// (`"B"` = Buy, `"S"` = Sell). Full names are accepted as well.
+    pub fn from_order_update_code(code: &str) -> Option<Self> {
+        match code.trim().to_ascii_uppercase().as_str() {
+            "B" | "BUY" => Some(Self::BUY),
+            "S" | "SELL" => Some(Self::SELL),
+            _ => None,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Product Type
 // ---------------------------------------------------------------------------
@@ -95,6 +146,23 @@ pub enum ProductType {
     BO,
 }
 
+impl ProductType {
+    /// Parse the abbreviated code used by the order-update WebSocket
+    /// (`"C"` = CNC, `"I"` = Intraday, `"M"` = Margin, `"F"` = MTF,
+    /// `"V"` = CO, `"B"` = BO). Full names are accepted as well.
+    pub fn from_order_update_code(code: &str) -> Option<Self> {
+        match code.trim().to_ascii_uppercase().as_str() {
+            "C" | "CNC" => Some(Self::CNC),
+            "I" | "INTRADAY" => Some(Self::INTRADAY),
+            "M" | "MARGIN" => Some(Self::MARGIN),
+            "F" | "MTF" => Some(Self::MTF),
+            "V" | "CO" => Some(Self::CO),
+            "B" | "BO" => Some(Self::BO),
+            _ => None,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Order Type
 // ---------------------------------------------------------------------------
@@ -108,6 +176,20 @@ pub enum OrderType {
     STOP_LOSS_MARKET,
 }
 
+impl OrderType {
+    /// Parse the abbreviated code used by the order-update WebSocket
+    /// (`"LMT"`, `"MKT"`, `"SL"`, `"SLM"`). Full names are accepted as well.
+    pub fn from_order_update_code(code: &str) -> Option<Self> {
+        match code.trim().to_ascii_uppercase().as_str() {
+            "LMT" | "LIMIT" => Some(Self::LIMIT),
+            "MKT" | "MARKET" => Some(Self::MARKET),
+            "SL" | "STOP_LOSS" => Some(Self::STOP_LOSS),
+            "SLM" | "SL-M" | "STOP_LOSS_MARKET" => Some(Self::STOP_LOSS_MARKET),
+            _ => None,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Order Status
 // ---------------------------------------------------------------------------
@@ -137,6 +219,50 @@ pub enum OrderStatus {
     CONFIRM,
 }
 
+impl OrderStatus {
+    /// Returns `true` if the order can no longer change on the exchange.
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            Self::REJECTED | Self::CANCELLED | Self::TRADED | Self::EXPIRED | Self::CLOSED
+        )
+    }
+
+    /// Returns `true` if the order is still working (can still be cancelled).
+    pub fn is_working(self) -> bool {
+        matches!(
+            self,
+            Self::TRANSIT | Self::PENDING | Self::TRIGGERED | Self::PART_TRADED
+        )
+    }
+}
+
+/// Parses order status strings case-insensitively.
+///
+/// The REST API uses `SCREAMING_SNAKE_CASE` (`"PART_TRADED"`) while the
+/// order-update WebSocket uses title case (`"Traded"`, `"Part Traded"`), so
+/// spaces and hyphens are treated as underscores.
+impl std::str::FromStr for OrderStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let normalized = s.trim().to_ascii_uppercase().replace([' ', '-'], "_");
+        match normalized.as_str() {
+            "TRANSIT" => Ok(Self::TRANSIT),
+            "PENDING" => Ok(Self::PENDING),
+            "CLOSED" => Ok(Self::CLOSED),
+            "TRIGGERED" => Ok(Self::TRIGGERED),
+            "REJECTED" => Ok(Self::REJECTED),
+            "CANCELLED" | "CANCELED" => Ok(Self::CANCELLED),
+            "PART_TRADED" | "PARTIALLY_TRADED" => Ok(Self::PART_TRADED),
+            "TRADED" => Ok(Self::TRADED),
+            "EXPIRED" => Ok(Self::EXPIRED),
+            "CONFIRM" | "CONFIRMED" => Ok(Self::CONFIRM),
+            _ => Err(format!("unknown order status: {s}")),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Validity
 // ---------------------------------------------------------------------------
@@ -150,6 +276,17 @@ pub enum Validity {
     IOC,
 }
 
+impl Validity {
+    /// Parse a validity string as sent by the order-update WebSocket.
+    pub fn from_order_update_code(code: &str) -> Option<Self> {
+        match code.trim().to_ascii_uppercase().as_str() {
+            "DAY" => Some(Self::DAY),
+            "IOC" => Some(Self::IOC),
+            _ => None,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Leg Name
 // ---------------------------------------------------------------------------
@@ -316,7 +453,7 @@ impl Serialize for FeedRequestCode {
 // ---------------------------------------------------------------------------
 
 /// Response codes received in binary market feed packets.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 #[repr(u8)]
 pub enum FeedResponseCode {
     /// Index packet.
@@ -3,7 +3,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{DhanError, Result};
 use crate::types::enums::*;
+use crate::types::orders::PlaceOrderRequest;
 
 // ---------------------------------------------------------------------------
 // Margin Calculator (single)
@@ -66,6 +68,21 @@ pub struct MarginScript {
     pub trigger_price: Option<f64>,
 }
 
+impl MarginScript {
+    /// The entry for `order`, priced at `price`.
+    pub fn from_order(order: &PlaceOrderRequest, price: f64) -> Self {
+        Self {
+            exchange_segment: order.exchange_segment,
+            transaction_type: order.transaction_type,
+            quantity: order.quantity,
+            product_type: order.product_type,
+            security_id: order.security_id.clone(),
+            price,
+            trigger_price: order.trigger_price,
+        }
+    }
+}
+
 /// Request body for calculating margin for multiple scripts.
 ///
 /// Used by `POST /v2/margincalculator/multi`.
@@ -106,7 +123,7 @@ pub struct MultiMarginResponse {
 /// Returned by `GET /v2/fundlimit`.
 ///
 /// Note: The API misspells `availabelBalance` (missing 'l' in 'available').
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FundLimit {
     pub dhan_client_id: Option<String>,
@@ -127,3 +144,100 @@ pub struct FundLimit {
     #[serde(default)]
     pub withdrawable_balance: Option<f64>,
 }
+
+// ---------------------------------------------------------------------------
+// Funds Forecast
+// ---------------------------------------------------------------------------
+
+/// Funds after a basket of orders executes, predicted from the fund limits
+/// and the basket's margin (see [`crate::client::DhanClient::forecast_funds`]).
+///
+/// Utilization is the share of the trading limit — available balance plus
+/// utilized amount — in use; it exceeds 1 when the basket is unaffordable.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct FundsForecast {
+    /// Balance available to trade now.
+    pub available_balance: f64,
+    /// Amount utilized now.
+    pub utilized_amount: f64,
+    /// Margin the basket needs, net of its hedge benefit.
+    pub required_margin: f64,
+    /// Hedge benefit between the basket's legs.
+    pub hedge_benefit: f64,
+    /// `available_balance − required_margin`; negative when short of funds.
+    pub available_after: f64,
+    /// Utilization now.
+    pub utilization: f64,
+    /// Utilization once the basket executes.
+    pub utilization_after: f64,
+}
+
+impl FundsForecast {
+    /// The forecast for a basket whose margin is `margin`, given `funds`.
+    ///
+    /// Fails if the fund limits lack the available balance or the margin
+    /// response lacks a numeric total.
+    ///
+    /// ```
+    /// use dhan_rs::types::funds::{FundLimit, FundsForecast, MultiMarginResponse};
+    ///
+    /// let funds: FundLimit = serde_json::from_str(
+    ///     r#"{"availabelBalance": 50000.0, "utilizedAmount": 50000.0}"#,
+    /// )
+    /// .unwrap();
+    /// let margin: MultiMarginResponse =
+    ///     serde_json::from_str(r#"{"total_margin": "60000.00", "hedge_benefit": "0"}"#).unwrap();
+    /// let forecast = FundsForecast::new(&funds, &margin).unwrap();
+    /// assert!(!forecast.is_affordable());
+    /// assert_eq!(forecast.shortfall(), 10000.0);
+    /// assert_eq!(forecast.utilization_after, 1.1);
+    /// ```
+    pub fn new(funds: &FundLimit, margin: &MultiMarginResponse) -> Result<Self> {
+        let available_balance = funds
+            .available_balance
+            .ok_or_else(|| missing("fund limits without an available balance"))?;
+        let utilized_amount = funds.utilized_amount.unwrap_or(0.0);
+        let required_margin = amount(&margin.total_margin)
+            .ok_or_else(|| missing("multi-margin response without a total margin"))?;
+        let limit = available_balance + utilized_amount;
+        Ok(Self {
+            available_balance,
+            utilized_amount,
+            required_margin,
+            hedge_benefit: amount(&margin.hedge_benefit).unwrap_or(0.0),
+            available_after: available_balance - required_margin,
+            utilization: share(utilized_amount, limit),
+            utilization_after: share(utilized_amount + required_margin, limit),
+        })
+    }
+
+    /// Returns `true` if the available balance covers the basket.
+    pub fn is_affordable(&self) -> bool {
+        self.available_after >= 0.0
+    }
+
+    /// How much more balance the basket needs; zero if affordable.
+    pub fn shortfall(&self) -> f64 {
+        (-self.available_after).max(0.0)
+    }
+}
+
+/// A margin amount sent as text, e.g. `"1,250.50"`.
+fn amount(value: &Option<String>) -> Option<f64> {
+    value.as_deref()?.trim().replace(',', "").parse().ok()
+}
+
+/// `used` as a share of `limit`.
+fn share(used: f64, limit: f64) -> f64 {
+    if limit > 0.0 {
+        used / limit
+    } else if used > 0.0 {
+        f64::INFINITY
+    } else {
+        0.0
+    }
+}
+
+fn missing(what: &str) -> DhanError {
+    DhanError::Json(serde::de::Error::custom(what))
+}
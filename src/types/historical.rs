@@ -61,7 +61,8 @@ pub struct IntradayDataRequest {
 /// OHLCV candle data returned by both daily and intraday endpoints.
 ///
 /// Each field is a parallel array — index `i` across all arrays corresponds
-/// to the same candle.
+/// to the same candle. [`Self::iter`] and [`Self::candle`] put them back
+/// together as [`Candle`]s.
 #[derive(Debug, Clone, Deserialize)]
 pub struct CandleData {
     pub open: Vec<f64>,
@@ -75,3 +76,163 @@ pub struct CandleData {
     #[serde(default)]
     pub open_interest: Vec<f64>,
 }
+
+impl CandleData {
+    /// Number of candles in the response.
+    pub fn len(&self) -> usize {
+        self.timestamp.len()
+    }
+
+    /// Returns `true` if the response contains no candles.
+    pub fn is_empty(&self) -> bool {
+        self.timestamp.is_empty()
+    }
+
+    /// Parallel arrays of `candles`, e.g. bars built from the live feed
+    /// (see [`crate::candles`]). Open interest is filled with zeros where a
+    /// candle has none, and left empty if none has any.
+    pub fn from_candles(candles: &[Candle]) -> Self {
+        let column = |f: fn(&Candle) -> f64| candles.iter().map(f).collect();
+        let open_interest = if candles.iter().any(|c| c.open_interest.is_some()) {
+            column(|c| c.open_interest.unwrap_or_default())
+        } else {
+            Vec::new()
+        };
+        Self {
+            open: column(|c| c.open),
+            high: column(|c| c.high),
+            low: column(|c| c.low),
+            close: column(|c| c.close),
+            volume: column(|c| c.volume),
+            timestamp: column(|c| c.timestamp as f64),
+            open_interest,
+        }
+    }
+
+    /// Convert the parallel arrays into a list of [`Candle`]s.
+    ///
+    /// Indices missing from any price or volume array are skipped.
+    pub fn candles(&self) -> Vec<Candle> {
+        self.iter().collect()
+    }
+
+    /// Consume the response into its [`Candle`]s; see [`Self::candles`].
+    pub fn into_candles(self) -> Vec<Candle> {
+        self.candles()
+    }
+
+    /// Iterate over the candles, oldest first, as [`Candle`]s.
+    ///
+    /// Like [`Self::candles`], stops at the end of the shortest price or
+    /// volume array.
+    ///
+    /// ```
+    /// use dhan_rs::types::historical::CandleData;
+    ///
+    /// let data: CandleData = serde_json::from_str(
+    ///     r#"{"open":[100.0,101.0],"high":[102.0,103.0],"low":[99.0,100.5],
+    ///         "close":[101.0,102.5],"volume":[1200.0,900.0],"timestamp":[1700000000.0,1700000060.0]}"#,
+    /// )
+    /// .unwrap();
+    /// let ranges: Vec<f64> = data.iter().map(|c| c.high - c.low).collect();
+    /// assert_eq!(ranges, [3.0, 2.5]);
+    /// assert_eq!(data.candle(1).unwrap().close, 102.5);
+    /// assert!(data.candle(2).is_none());
+    /// ```
+    pub fn iter(&self) -> CandleIter<'_> {
+        let complete = [
+            &self.timestamp,
+            &self.open,
+            &self.high,
+            &self.low,
+            &self.close,
+            &self.volume,
+        ]
+        .into_iter()
+        .map(Vec::len)
+        .min()
+        .unwrap_or(0);
+        CandleIter {
+            data: self,
+            range: 0..complete,
+        }
+    }
+
+    /// The candle at index `i`, or `None` if any price or volume array
+    /// lacks it.
+    pub fn candle(&self, i: usize) -> Option<Candle> {
+        Some(Candle {
+            timestamp: *self.timestamp.get(i)? as i64,
+            open: *self.open.get(i)?,
+            high: *self.high.get(i)?,
+            low: *self.low.get(i)?,
+            close: *self.close.get(i)?,
+            volume: *self.volume.get(i)?,
+            open_interest: self.open_interest.get(i).copied(),
+        })
+    }
+}
+
+impl<'a> IntoIterator for &'a CandleData {
+    type Item = Candle;
+    type IntoIter = CandleIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for CandleData {
+    type Item = Candle;
+    type IntoIter = std::vec::IntoIter<Candle>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_candles().into_iter()
+    }
+}
+
+/// Iterator over the candles of a [`CandleData`] (see [`CandleData::iter`]).
+#[derive(Debug, Clone)]
+pub struct CandleIter<'a> {
+    data: &'a CandleData,
+    range: std::ops::Range<usize>,
+}
+
+impl Iterator for CandleIter<'_> {
+    type Item = Candle;
+
+    fn next(&mut self) -> Option<Candle> {
+        self.range.next().and_then(|i| self.data.candle(i))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for CandleIter<'_> {
+    fn next_back(&mut self) -> Option<Candle> {
+        self.range.next_back().and_then(|i| self.data.candle(i))
+    }
+}
+
+impl ExactSizeIterator for CandleIter<'_> {}
+
+// ---------------------------------------------------------------------------
+// Candle
+// ---------------------------------------------------------------------------
+
+/// A single OHLCV bar, shared by historical responses and live aggregation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    /// Bar start time (epoch seconds).
+    pub timestamp: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    /// Open interest at the end of the bar, if available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open_interest: Option<f64>,
+}
@@ -0,0 +1,101 @@
+//! Compact instrument identifier shared across REST, WebSocket and runtime code.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::DhanError;
+use crate::types::builders::PlaceOrderRequestBuilder;
+use crate::types::enums::ExchangeSegment;
+use crate::types::orders::PlaceOrderRequest;
+use crate::ws::market_feed::{Instrument, PacketHeader};
+
+/// An instrument identified by exchange segment and security ID.
+///
+/// Displays and parses as `"SEGMENT:SECURITY_ID"`, e.g. `"NSE_EQ:1333"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstrumentId {
+    /// Exchange segment.
+    pub segment: ExchangeSegment,
+    /// Exchange standard security ID.
+    pub security_id: u32,
+}
+
+impl InstrumentId {
+    /// Create a new identifier.
+    pub fn new(segment: ExchangeSegment, security_id: u32) -> Self {
+        Self {
+            segment,
+            security_id,
+        }
+    }
+
+    /// Identifier of the instrument a feed packet belongs to, if its segment
+    /// is known.
+    pub fn from_header(header: &PacketHeader) -> Option<Self> {
+        Some(Self::new(header.exchange_segment?, header.security_id))
+    }
+
+    /// The subscription entry used by the market feed.
+    pub fn to_feed_instrument(&self) -> Instrument {
+        Instrument::new(self.segment.as_str(), self.security_id.to_string())
+    }
+
+    /// An order builder with the segment and security ID set, leaving the
+    /// side, product, type, validity and quantity to the caller.
+    ///
+    /// ```
+    /// use dhan_rs::types::enums::*;
+    /// use dhan_rs::types::instrument::InstrumentId;
+    ///
+    /// let req = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333)
+    ///     .order_seed()
+    ///     .dhan_client_id("1000000001")
+    ///     .transaction_type(TransactionType::BUY)
+    ///     .product_type(ProductType::CNC)
+    ///     .order_type(OrderType::MARKET)
+    ///     .validity(Validity::DAY)
+    ///     .quantity(5)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(req.security_id, "1333");
+    /// ```
+    pub fn order_seed(&self) -> PlaceOrderRequestBuilder {
+        PlaceOrderRequest::builder()
+            .exchange_segment(self.segment)
+            .security_id(self.security_id.to_string())
+    }
+}
+
+impl fmt::Display for InstrumentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.segment, self.security_id)
+    }
+}
+
+impl FromStr for InstrumentId {
+    type Err = DhanError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DhanError::InvalidArgument(format!("invalid instrument id: {s}"));
+        let (segment, id) = s.split_once(':').ok_or_else(invalid)?;
+        Ok(Self::new(
+            segment.parse().map_err(|_| invalid())?,
+            id.trim().parse().map_err(|_| invalid())?,
+        ))
+    }
+}
+
+impl Serialize for InstrumentId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InstrumentId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
@@ -5,6 +5,10 @@ use std::collections::HashMap;
 
 use serde::Deserialize;
 
+use crate::batch::BatchReport;
+use crate::types::enums::ExchangeSegment;
+use crate::types::instrument::InstrumentId;
+
 // ---------------------------------------------------------------------------
 // Request
 // ---------------------------------------------------------------------------
@@ -32,6 +36,52 @@ pub struct MarketQuoteResponse<T> {
     pub status: String,
 }
 
+impl<T> MarketQuoteResponse<T> {
+    /// The entry of `id`, if returned.
+    pub fn get(&self, id: &InstrumentId) -> Option<&T> {
+        self.data
+            .get(id.segment.as_str())?
+            .get(&id.security_id.to_string())
+    }
+
+    /// The entries keyed by [`InstrumentId`].
+    ///
+    /// Entries whose segment or security ID does not parse are dropped (and
+    /// logged).
+    ///
+    /// ```
+    /// use dhan_rs::types::enums::ExchangeSegment;
+    /// use dhan_rs::types::instrument::InstrumentId;
+    /// use dhan_rs::types::market_quote::{MarketQuoteResponse, TickerData};
+    ///
+    /// let resp: MarketQuoteResponse<TickerData> = serde_json::from_str(
+    ///     r#"{"status":"success","data":{"NSE_EQ":{"1333":{"last_price":1650.5}}}}"#,
+    /// )
+    /// .unwrap();
+    /// let prices = resp.flatten();
+    /// let hdfc = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+    /// assert_eq!(prices[&hdfc].last_price, 1650.5);
+    /// ```
+    pub fn flatten(self) -> HashMap<InstrumentId, T> {
+        let mut flat = HashMap::with_capacity(self.data.values().map(HashMap::len).sum());
+        for (segment, entries) in self.data {
+            let Ok(segment) = segment.parse::<ExchangeSegment>() else {
+                tracing::warn!(segment, "Unknown segment in quote response");
+                continue;
+            };
+            for (security_id, entry) in entries {
+                match security_id.parse() {
+                    Ok(id) => {
+                        flat.insert(InstrumentId::new(segment, id), entry);
+                    }
+                    Err(_) => tracing::warn!(security_id, "Invalid security ID in quote response"),
+                }
+            }
+        }
+        flat
+    }
+}
+
 // ---------------------------------------------------------------------------
 // OHLC response
 // ---------------------------------------------------------------------------
@@ -101,3 +151,31 @@ pub struct QuoteData {
     #[serde(default)]
     pub volume: Option<i64>,
 }
+
+impl QuoteData {
+    /// Market depth as a [`DepthBook`](crate::types::depth::DepthBook), if present.
+    pub fn depth_book(&self) -> Option<crate::types::depth::DepthBook> {
+        self.depth.as_ref().map(Into::into)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Multi-request quotes
+// ---------------------------------------------------------------------------
+
+impl<T> BatchReport<MarketQuoteResponse<T>, MarketQuoteRequest> {
+    /// The entry of `id` in any successful response, as returned by
+    /// [`DhanClient::get_quotes_multi`](crate::DhanClient::get_quotes_multi).
+    pub fn get(&self, id: &InstrumentId) -> Option<&T> {
+        self.succeeded.iter().find_map(|resp| resp.get(id))
+    }
+
+    /// Number of entries across the successful responses.
+    pub fn entry_count(&self) -> usize {
+        self.succeeded
+            .iter()
+            .flat_map(|resp| resp.data.values())
+            .map(HashMap::len)
+            .sum()
+    }
+}
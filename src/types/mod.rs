@@ -13,24 +13,30 @@
 //! - [`portfolio`] — Holdings, positions, and conversion types
 //! - [`funds`] — Margin calculator and fund limit types
 //! - [`market_quote`] — LTP, OHLC, and market depth quote types
+//! - [`depth`] — Full market depth order book shared by REST and WebSocket
 //! - [`historical`] — Daily and intraday candle data types
+//! - [`instrument`] — Compact `SEGMENT:SECURITY_ID` instrument identifier
 //! - [`option_chain`] — Option chain and expiry list types with Greeks
 //! - [`auth`] — Authentication request/response types
 //! - [`profile`] — User profile types
 //! - [`edis`] — eDIS (electronic delivery) types
 //! - [`traders_control`] — Kill switch and P&L exit types
 //! - [`statements`] — Ledger and trade history types
-//! - [`postback`] — Webhook payload deserialization type
+//! - [`postback`] — Webhook payload type and validation
+//! - [`builders`] — `builder()` constructors for every request type
 //!
 //! All enums are re-exported at the module root via `pub use enums::*`.
 
 pub mod auth;
+pub mod builders;
 pub mod conditional;
+pub mod depth;
 pub mod edis;
 pub mod enums;
 pub mod forever_order;
 pub mod funds;
 pub mod historical;
+pub mod instrument;
 pub mod market_quote;
 pub mod option_chain;
 pub mod orders;
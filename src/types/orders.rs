@@ -3,7 +3,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{DhanError, Result};
 use crate::types::enums::*;
+use crate::types::portfolio::Position;
 
 // ---------------------------------------------------------------------------
 // Place Order
@@ -12,7 +14,7 @@ use crate::types::enums::*;
 /// Request body for placing a new order.
 ///
 /// Used by `POST /v2/orders` and `POST /v2/orders/slicing`.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PlaceOrderRequest {
     /// User-specific identification generated by Dhan.
@@ -57,6 +59,67 @@ pub struct PlaceOrderRequest {
     pub bo_stop_loss_value: Option<f64>,
 }
 
+impl PlaceOrderRequest {
+    /// The order that flattens `position`: the opposite side for its whole
+    /// net quantity, in its segment and product, valid for the day.
+    ///
+    /// The side follows the sign of the net quantity, so a long position is
+    /// sold and a short one bought back. No price is set; fill in
+    /// [`Self::price`] for a limit order and [`Self::trigger_price`] for a
+    /// stop-loss. Fails if the position is flat or lacks a security ID or a
+    /// known segment or product.
+    pub fn square_off(
+        dhan_client_id: impl Into<String>,
+        position: &Position,
+        order_type: OrderType,
+    ) -> Result<Self> {
+        let security_id = position
+            .security_id
+            .clone()
+            .ok_or_else(|| DhanError::InvalidArgument("position without a security ID".into()))?;
+        let net = position
+            .net_qty
+            .filter(|q| *q != 0)
+            .ok_or_else(|| DhanError::InvalidArgument(format!("position {security_id} is flat")))?;
+        let exchange_segment = position
+            .exchange_segment
+            .as_deref()
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| {
+                DhanError::InvalidArgument(format!("position {security_id} has an unknown segment"))
+            })?;
+        let product_type = position
+            .product_type
+            .as_deref()
+            .and_then(ProductType::from_order_update_code)
+            .ok_or_else(|| {
+                DhanError::InvalidArgument(format!("position {security_id} has an unknown product"))
+            })?;
+        Ok(Self {
+            dhan_client_id: dhan_client_id.into(),
+            correlation_id: None,
+            transaction_type: if net > 0 {
+                TransactionType::SELL
+            } else {
+                TransactionType::BUY
+            },
+            exchange_segment,
+            product_type,
+            order_type,
+            validity: Validity::DAY,
+            security_id,
+            quantity: net.unsigned_abs(),
+            disclosed_quantity: None,
+            price: None,
+            trigger_price: None,
+            after_market_order: None,
+            amo_time: None,
+            bo_profit_value: None,
+            bo_stop_loss_value: None,
+        })
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Modify Order
 // ---------------------------------------------------------------------------
@@ -92,6 +155,49 @@ pub struct ModifyOrderRequest {
     pub validity: Validity,
 }
 
+impl ModifyOrderRequest {
+    /// A request that leaves `order` as it is, for changing single fields.
+    ///
+    /// Order type, leg, quantity, prices and validity are copied from the
+    /// order book entry; unset prices and a zero disclosed quantity stay
+    /// unset. Fails if the entry lacks an order ID or a known order type.
+    pub fn from_order(dhan_client_id: impl Into<String>, order: &OrderDetail) -> Result<Self> {
+        let order_id = order
+            .order_id
+            .clone()
+            .ok_or_else(|| DhanError::InvalidArgument("order without an order ID".into()))?;
+        let order_type = order
+            .order_type
+            .as_deref()
+            .and_then(OrderType::from_order_update_code)
+            .ok_or_else(|| {
+                DhanError::InvalidArgument(format!("order {order_id} has an unknown order type"))
+            })?;
+        let validity = order
+            .validity
+            .as_deref()
+            .and_then(Validity::from_order_update_code)
+            .unwrap_or(Validity::DAY);
+        let leg_name = match order.leg_name.as_deref() {
+            Some("ENTRY_LEG") => Some(LegName::ENTRY_LEG),
+            Some("TARGET_LEG") => Some(LegName::TARGET_LEG),
+            Some("STOP_LOSS_LEG") => Some(LegName::STOP_LOSS_LEG),
+            _ => None,
+        };
+        Ok(Self {
+            dhan_client_id: dhan_client_id.into(),
+            order_id,
+            order_type,
+            leg_name,
+            quantity: order.quantity,
+            price: order.price.filter(|p| *p > 0.0),
+            disclosed_quantity: order.disclosed_quantity.filter(|q| *q > 0),
+            trigger_price: order.trigger_price.filter(|p| *p > 0.0),
+            validity,
+        })
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Order Response
 // ---------------------------------------------------------------------------
@@ -104,6 +210,85 @@ pub struct OrderResponse {
     pub order_id: String,
     /// Last updated status of the order.
     pub order_status: String,
+    /// OMS error code, present when the order was rejected.
+    #[serde(default)]
+    pub oms_error_code: Option<String>,
+    /// OMS error description, present when the order was rejected.
+    #[serde(default)]
+    pub oms_error_description: Option<String>,
+}
+
+impl OrderResponse {
+    /// `order_status` parsed as an [`OrderStatus`].
+    pub fn status(&self) -> Option<OrderStatus> {
+        self.order_status.parse().ok()
+    }
+
+    /// The rejection reason, if the order was rejected.
+    ///
+    /// Some rejections arrive as a successful response with a `REJECTED`
+    /// status rather than an API error.
+    pub fn rejection(&self) -> Option<OrderRejection> {
+        OrderRejection::from_parts(
+            self.status(),
+            &self.order_id,
+            &self.oms_error_code,
+            &self.oms_error_description,
+        )
+    }
+
+    /// Turn a `REJECTED` response into [`DhanError::OrderRejected`].
+    pub fn into_result(self) -> Result<Self> {
+        match self.rejection() {
+            Some(rejection) => Err(DhanError::OrderRejected(rejection)),
+            None => Ok(self),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Order Rejection
+// ---------------------------------------------------------------------------
+
+/// Why the OMS rejected an order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderRejection {
+    /// The rejected order.
+    pub order_id: Option<String>,
+    /// OMS error code.
+    pub code: Option<String>,
+    /// OMS error description.
+    pub description: Option<String>,
+}
+
+impl OrderRejection {
+    fn from_parts(
+        status: Option<OrderStatus>,
+        order_id: &str,
+        code: &Option<String>,
+        description: &Option<String>,
+    ) -> Option<Self> {
+        let non_empty = |s: &Option<String>| s.clone().filter(|s| !s.trim().is_empty());
+        (status == Some(OrderStatus::REJECTED)).then(|| Self {
+            order_id: Some(order_id.to_owned()).filter(|s| !s.is_empty()),
+            code: non_empty(code),
+            description: non_empty(description),
+        })
+    }
+}
+
+impl std::fmt::Display for OrderRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(id) = &self.order_id {
+            write!(f, "order {id}: ")?;
+        }
+        match (&self.code, &self.description) {
+            (Some(code), Some(desc)) => write!(f, "[{code}] {desc}"),
+            (Some(code), None) => write!(f, "[{code}]"),
+            (None, Some(desc)) => f.write_str(desc),
+            (None, None) => f.write_str("no reason given"),
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -111,7 +296,7 @@ pub struct OrderResponse {
 // ---------------------------------------------------------------------------
 
 /// Full order detail as returned by the order book.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderDetail {
     pub dhan_client_id: Option<String>,
@@ -158,6 +343,23 @@ pub struct OrderDetail {
     pub filled_qty: Option<u64>,
 }
 
+impl OrderDetail {
+    /// `order_status` parsed as an [`OrderStatus`].
+    pub fn status(&self) -> Option<OrderStatus> {
+        self.order_status.as_deref().and_then(|s| s.parse().ok())
+    }
+
+    /// The rejection reason, if the order was rejected.
+    pub fn rejection(&self) -> Option<OrderRejection> {
+        OrderRejection::from_parts(
+            self.status(),
+            self.order_id.as_deref().unwrap_or_default(),
+            &self.oms_error_code,
+            &self.oms_error_description,
+        )
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Trade Detail (Trade Book entry)
 // ---------------------------------------------------------------------------
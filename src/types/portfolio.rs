@@ -10,7 +10,7 @@ use crate::types::enums::*;
 // ---------------------------------------------------------------------------
 
 /// A single holding in the demat account.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Holding {
     pub exchange: Option<String>,
@@ -36,7 +36,7 @@ pub struct Holding {
 // ---------------------------------------------------------------------------
 
 /// A single open position.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Position {
     pub dhan_client_id: Option<String>,
@@ -10,6 +10,11 @@
 //! incoming webhook bodies. It does **not** make any outgoing API calls — it
 //! is purely for users who host their own HTTP server to receive postbacks.
 //!
+//! [`PostbackValidator`] checks the source IP and an optional shared secret
+//! (e.g. a query parameter in the configured Postback URL), drops repeated
+//! deliveries and converts the payload into the normalized
+//! [`OrderUpdate`], so a handler only has to pass the request through.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -22,8 +27,16 @@
 //! }
 //! ```
 
+use std::collections::{HashSet, VecDeque};
+use std::net::IpAddr;
+use std::sync::Mutex;
+
 use serde::Deserialize;
 
+use crate::error::{DhanError, Result};
+use crate::types::enums::{ExchangeSegment, OrderType, ProductType, TransactionType, Validity};
+use crate::ws::order_update::OrderUpdate;
+
 /// Postback (webhook) payload sent by Dhan to your configured Postback URL.
 ///
 /// The JSON body is a raw `POST` request containing the order update. Fields
@@ -166,4 +179,241 @@ impl PostbackPayload {
     pub fn is_sell(&self) -> bool {
         self.transaction_type.as_deref() == Some("SELL")
     }
+
+    /// Key identifying one delivery: order ID, status and update time.
+    ///
+    /// Dhan may deliver the same postback more than once; deliveries with
+    /// the same key carry the same state.
+    pub fn dedup_key(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            self.order_id.as_deref().unwrap_or_default(),
+            self.order_status.as_deref().unwrap_or_default(),
+            self.update_time.as_deref().unwrap_or_default(),
+            self.filled_qty.unwrap_or_default(),
+        )
+    }
+
+    /// Convert into the normalized [`OrderUpdate`] used by the
+    /// order-update stream.
+    pub fn into_update(self) -> OrderUpdate {
+        OrderUpdate::from(self)
+    }
+}
+
+impl From<PostbackPayload> for OrderUpdate {
+    fn from(p: PostbackPayload) -> Self {
+        let (exchange, segment) = match p
+            .exchange_segment
+            .as_deref()
+            .and_then(|s| s.parse::<ExchangeSegment>().ok())
+        {
+            Some(ExchangeSegment::IDX_I) => (Some("IDX"), Some("I")),
+            Some(ExchangeSegment::NSE_EQ) => (Some("NSE"), Some("E")),
+            Some(ExchangeSegment::NSE_FNO) => (Some("NSE"), Some("D")),
+            Some(ExchangeSegment::NSE_CURRENCY) => (Some("NSE"), Some("C")),
+            Some(ExchangeSegment::BSE_EQ) => (Some("BSE"), Some("E")),
+            Some(ExchangeSegment::BSE_FNO) => (Some("BSE"), Some("D")),
+            Some(ExchangeSegment::BSE_CURRENCY) => (Some("BSE"), Some("C")),
+            Some(ExchangeSegment::MCX_COMM) => (Some("MCX"), Some("M")),
+            None => (None, None),
+        };
+        let quantity = p.quantity.map(|q| q as i64);
+        let traded_quantity = p.filled_qty.map(|q| q as i64);
+        Self {
+            exchange: exchange.map(str::to_owned),
+            segment: segment.map(str::to_owned),
+            security_id: p.security_id,
+            client_id: p.dhan_client_id,
+            order_id: p.order_id,
+            correlation_id: p.correlation_id.filter(|s| !s.is_empty()),
+            product_type: p
+                .product_type
+                .as_deref()
+                .and_then(ProductType::from_order_update_code),
+            transaction_type: p
+                .transaction_type
+                .as_deref()
+                .and_then(TransactionType::from_order_update_code),
+            order_type: p
+                .order_type
+                .as_deref()
+                .and_then(OrderType::from_order_update_code),
+            validity: p
+                .validity
+                .as_deref()
+                .and_then(Validity::from_order_update_code),
+            status: p.order_status.as_deref().and_then(|s| s.parse().ok()),
+            quantity,
+            traded_quantity,
+            remaining_quantity: quantity.zip(traded_quantity).map(|(q, t)| q - t),
+            disclosed_quantity: p.disclosed_quantity.map(|q| q as i64),
+            price: p.price,
+            trigger_price: p.trigger_price,
+            after_market_order: p.after_market_order.unwrap_or(false),
+            order_time: p.create_time.as_deref().and_then(crate::time::parse_naive),
+            exchange_order_time: p
+                .exchange_time
+                .as_deref()
+                .and_then(crate::time::parse_naive),
+            last_updated_time: p.update_time.as_deref().and_then(crate::time::parse_naive),
+            reason_description: p.oms_error_description.filter(|s| !s.is_empty()),
+            symbol: p.trading_symbol,
+            strike_price: p.drv_strike_price.filter(|s| *s > 0.0),
+            expiry_date: p
+                .drv_expiry_date
+                .as_deref()
+                .and_then(crate::time::parse_naive)
+                .map(|dt| dt.date())
+                .or_else(|| {
+                    p.drv_expiry_date
+                        .as_deref()
+                        .and_then(|d| chrono::NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+                }),
+            option_type: p.drv_option_type.filter(|t| !t.is_empty() && t != "NA"),
+            algo_id: p.algo_id.filter(|s| !s.is_empty()),
+            ..Default::default()
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Default number of recent delivery keys kept for duplicate detection.
+pub const DEFAULT_DEDUP_CAPACITY: usize = 10_000;
+
+/// Validates incoming postbacks and drops repeated deliveries.
+///
+/// All checks are optional: with no allowed IPs every source passes, and
+/// with no secret none is required.
+///
+/// ```
+/// use dhan_rs::types::postback::PostbackValidator;
+///
+/// let validator = PostbackValidator::new()
+///     .with_allowed_ip("203.0.113.10".parse().unwrap())
+///     .with_secret("s3cret");
+/// let body = r#"{"orderId":"1","orderStatus":"TRADED","updateTime":"2025-01-10 10:00:00"}"#;
+/// let ip = "203.0.113.10".parse().unwrap();
+///
+/// let update = validator.validate(ip, Some("s3cret"), body).unwrap();
+/// assert!(update.is_some());
+/// // The same delivery again is a duplicate.
+/// assert!(validator.validate(ip, Some("s3cret"), body).unwrap().is_none());
+/// // Wrong secret.
+/// assert!(validator.validate(ip, Some("guess"), body).is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct PostbackValidator {
+    allowed_ips: Vec<IpAddr>,
+    secret: Option<String>,
+    capacity: Option<usize>,
+    seen: Mutex<SeenKeys>,
+}
+
+#[derive(Debug, Default)]
+struct SeenKeys {
+    keys: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl PostbackValidator {
+    /// Create a validator with no IP or secret checks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept postbacks from `ip` (once any IP is added, others are
+    /// rejected).
+    pub fn with_allowed_ip(mut self, ip: IpAddr) -> Self {
+        self.allowed_ips.push(ip);
+        self
+    }
+
+    /// Accept postbacks from each of `ips`.
+    pub fn with_allowed_ips(mut self, ips: impl IntoIterator<Item = IpAddr>) -> Self {
+        self.allowed_ips.extend(ips);
+        self
+    }
+
+    /// Require `secret` with every postback.
+    pub fn with_secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// Remember the last `capacity` delivery keys (default
+    /// [`DEFAULT_DEDUP_CAPACITY`]).
+    pub fn with_dedup_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Check the source IP against the allowlist.
+    pub fn verify_source(&self, source_ip: IpAddr) -> Result<()> {
+        let ip = source_ip.to_canonical();
+        if self.allowed_ips.is_empty() || self.allowed_ips.iter().any(|a| a.to_canonical() == ip) {
+            return Ok(());
+        }
+        Err(DhanError::PostbackRejected(format!(
+            "source {source_ip} is not allowed"
+        )))
+    }
+
+    /// Check the shared secret supplied with the request.
+    pub fn verify_secret(&self, secret: Option<&str>) -> Result<()> {
+        let Some(expected) = &self.secret else {
+            return Ok(());
+        };
+        match secret {
+            Some(given) if constant_time_eq(given.as_bytes(), expected.as_bytes()) => Ok(()),
+            Some(_) => Err(DhanError::PostbackRejected("secret mismatch".into())),
+            None => Err(DhanError::PostbackRejected("secret missing".into())),
+        }
+    }
+
+    /// Record `payload`'s delivery key; returns `true` if it was seen before.
+    pub fn is_duplicate(&self, payload: &PostbackPayload) -> bool {
+        let key = payload.dedup_key();
+        let capacity = self.capacity.unwrap_or(DEFAULT_DEDUP_CAPACITY);
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        if seen.keys.contains(&key) {
+            return true;
+        }
+        seen.keys.insert(key.clone());
+        seen.order.push_back(key);
+        while seen.order.len() > capacity {
+            if let Some(old) = seen.order.pop_front() {
+                seen.keys.remove(&old);
+            }
+        }
+        false
+    }
+
+    /// Run every check on a raw postback request and parse its body.
+    ///
+    /// Returns `Ok(None)` for a repeated delivery, and
+    /// [`DhanError::PostbackRejected`] if the source or secret is wrong.
+    pub fn validate(
+        &self,
+        source_ip: IpAddr,
+        secret: Option<&str>,
+        body: &str,
+    ) -> Result<Option<OrderUpdate>> {
+        self.verify_source(source_ip)?;
+        self.verify_secret(secret)?;
+        let payload: PostbackPayload = serde_json::from_str(body)?;
+        if self.is_duplicate(&payload) {
+            tracing::debug!(order_id = ?payload.order_id, "Duplicate postback dropped");
+            return Ok(None);
+        }
+        Ok(Some(payload.into_update()))
+    }
+}
+
+/// Compare without short-circuiting on the first differing byte.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
 }
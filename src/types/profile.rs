@@ -1,8 +1,11 @@
 #![allow(missing_docs)]
 //! User profile types.
 
+use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::Deserialize;
 
+use crate::time::{ist_to_utc, parse_ist};
+
 /// Response from `GET /v2/profile`.
 ///
 /// Used to validate access token and check account setup.
@@ -29,3 +32,23 @@ pub struct UserProfile {
     #[serde(default)]
     pub data_validity: Option<String>,
 }
+
+impl UserProfile {
+    /// Returns `true` if DDPI is active, so delivery sells need no eDIS.
+    pub fn ddpi_active(&self) -> bool {
+        self.ddpi
+            .as_deref()
+            .is_some_and(|s| s.trim().eq_ignore_ascii_case("active"))
+    }
+
+    /// When the access token expires, from `token_validity` (an IST time
+    /// such as `30/03/2025 15:37`).
+    pub fn token_expiry(&self) -> Option<DateTime<Utc>> {
+        let s = self.token_validity.trim();
+        NaiveDateTime::parse_from_str(s, "%d/%m/%Y %H:%M")
+            .or_else(|_| NaiveDateTime::parse_from_str(s, "%d/%m/%Y %H:%M:%S"))
+            .ok()
+            .map(ist_to_utc)
+            .or_else(|| parse_ist(s))
+    }
+}
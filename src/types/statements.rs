@@ -1,6 +1,7 @@
 #![allow(missing_docs)]
 //! Statement types — Ledger Report, Trade History.
 
+use chrono::NaiveDate;
 use serde::Deserialize;
 
 // ---------------------------------------------------------------------------
@@ -24,6 +25,67 @@ pub struct LedgerEntry {
     pub runbal: Option<String>,
 }
 
+impl LedgerEntry {
+    /// `debit` as a number.
+    pub fn debit_amount(&self) -> Option<f64> {
+        self.debit.as_deref().and_then(parse_amount)
+    }
+
+    /// `credit` as a number.
+    pub fn credit_amount(&self) -> Option<f64> {
+        self.credit.as_deref().and_then(parse_amount)
+    }
+
+    /// `runbal` (running balance) as a number.
+    pub fn running_balance(&self) -> Option<f64> {
+        self.runbal.as_deref().and_then(parse_amount)
+    }
+
+    /// Credit minus debit, treating a missing side as zero.
+    pub fn net_amount(&self) -> f64 {
+        self.credit_amount().unwrap_or(0.0) - self.debit_amount().unwrap_or(0.0)
+    }
+
+    /// `voucherdate` as a date.
+    ///
+    /// Accepts `"Sep 12, 2024"`, `"12-Sep-2024"`, `"2024-09-12"` and
+    /// `"12/09/2024"`.
+    pub fn voucher_date(&self) -> Option<NaiveDate> {
+        let s = self.voucherdate.as_deref()?.trim();
+        ["%b %d, %Y", "%d-%b-%Y", "%Y-%m-%d", "%d/%m/%Y", "%d-%m-%Y"]
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+    }
+}
+
+/// Parse an amount as formatted in statements.
+///
+/// Accepts Indian and western digit grouping (`"1,23,456.78"`,
+/// `"123,456.78"`), a leading `₹`, surrounding whitespace, and negatives
+/// written as `-12.5` or `(12.5)`. Returns `None` for empty strings and
+/// placeholders such as `"-"`.
+///
+/// ```
+/// use dhan_rs::types::statements::parse_amount;
+///
+/// assert_eq!(parse_amount("1,23,456.78"), Some(123456.78));
+/// assert_eq!(parse_amount("(250.00)"), Some(-250.0));
+/// assert_eq!(parse_amount(" - "), None);
+/// ```
+pub fn parse_amount(s: &str) -> Option<f64> {
+    let s = s.trim().trim_start_matches('₹').trim();
+    let (negative, s) = match s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+        Some(inner) => (true, inner.trim()),
+        None => (false, s),
+    };
+    let cleaned: String = s
+        .chars()
+        .filter(|c| *c != ',' && !c.is_whitespace())
+        .collect();
+    let value: f64 = cleaned.parse().ok()?;
+    Some(if negative { -value } else { value })
+}
+
 // ---------------------------------------------------------------------------
 // Trade History Entry
 // ---------------------------------------------------------------------------
@@ -71,3 +133,21 @@ pub struct TradeHistoryEntry {
     #[serde(default)]
     pub drv_strike_price: Option<f64>,
 }
+
+impl TradeHistoryEntry {
+    /// Sum of the trade's charges: brokerage, STT, exchange transaction
+    /// charges, SEBI fee, GST and stamp duty.
+    pub fn total_charges(&self) -> f64 {
+        [
+            self.brokerage_charges,
+            self.stt,
+            self.exchange_transaction_charges,
+            self.sebi_tax,
+            self.service_tax,
+            self.stamp_duty,
+        ]
+        .iter()
+        .flatten()
+        .sum()
+    }
+}
@@ -0,0 +1,242 @@
+//! Per-user token storage for partner integrations.
+//!
+//! A partner app holds one access token per end user, each obtained through
+//! the partner consent flow and each expiring on its own schedule.
+//! [`TokenVault`] keeps them keyed by Dhan client ID, hands out a ready
+//! [`DhanClient`] per user and renews tokens that are about to expire.
+//!
+//! ```no_run
+//! use dhan_rs::vault::TokenVault;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let vault = TokenVault::new();
+//! // After the user returns from the consent login page with `tokenId`:
+//! let client_id = vault
+//!     .consume_consent("token-id", "partner-id", "partner-secret")
+//!     .await?;
+//! let client = vault.client(&client_id).expect("just stored");
+//! let funds = client.get_fund_limit().await?;
+//!
+//! // Periodically:
+//! for (client_id, result) in vault.refresh_expiring().await {
+//!     if let Err(e) = result {
+//!         eprintln!("{client_id}: {e}");
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::client::DhanClient;
+use crate::constants::API_BASE_URL;
+use crate::error::Result;
+use crate::time::parse_ist;
+use crate::types::auth::TokenResponse;
+
+/// Lifetime assumed for tokens whose response carries no expiry.
+pub const DEFAULT_TOKEN_LIFETIME: Duration = Duration::hours(24);
+
+/// Tokens expiring within this window are renewed by
+/// [`TokenVault::refresh_expiring`].
+pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::hours(1);
+
+/// One user's token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultEntry {
+    /// Dhan client ID of the user.
+    pub client_id: String,
+    /// Name registered on Dhan.
+    pub client_name: Option<String>,
+    /// Access token.
+    pub access_token: String,
+    /// When the token expires.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl VaultEntry {
+    /// Build an entry from a token response received at `now`.
+    pub fn from_token(token: &TokenResponse, now: DateTime<Utc>) -> Self {
+        Self {
+            client_id: token.dhan_client_id.clone(),
+            client_name: token.dhan_client_name.clone(),
+            access_token: token.access_token.clone(),
+            expires_at: token
+                .expiry_time
+                .as_deref()
+                .and_then(parse_ist)
+                .unwrap_or(now + DEFAULT_TOKEN_LIFETIME),
+        }
+    }
+
+    /// Returns `true` if the token has expired at `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Access tokens of many users, keyed by client ID.
+///
+/// Cloning is cheap and clones share the stored tokens.
+#[derive(Debug, Clone)]
+pub struct TokenVault {
+    entries: Arc<RwLock<HashMap<String, VaultEntry>>>,
+    base_url: String,
+    refresh_margin: Duration,
+}
+
+impl Default for TokenVault {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenVault {
+    /// Create an empty vault whose clients use the default API base URL.
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::default(),
+            base_url: API_BASE_URL.to_owned(),
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+        }
+    }
+
+    /// Point handed-out clients at `base_url`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Renew tokens expiring within `margin` (default
+    /// [`DEFAULT_REFRESH_MARGIN`]).
+    pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Store `entry`, replacing any token of the same user.
+    pub fn insert(&self, entry: VaultEntry) {
+        self.entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(entry.client_id.clone(), entry);
+    }
+
+    /// Store the token in `token`. Returns the user's client ID.
+    pub fn insert_token(&self, token: &TokenResponse) -> String {
+        let entry = VaultEntry::from_token(token, Utc::now());
+        let client_id = entry.client_id.clone();
+        self.insert(entry);
+        client_id
+    }
+
+    /// Complete the partner consent flow for a user and store the token.
+    ///
+    /// See [`DhanClient::partner_consume_consent`]. Returns the user's
+    /// client ID.
+    pub async fn consume_consent(
+        &self,
+        token_id: &str,
+        partner_id: &str,
+        partner_secret: &str,
+    ) -> Result<String> {
+        let token =
+            DhanClient::partner_consume_consent(token_id, partner_id, partner_secret).await?;
+        Ok(self.insert_token(&token))
+    }
+
+    /// Forget a user's token. Returns the removed entry.
+    pub fn remove(&self, client_id: &str) -> Option<VaultEntry> {
+        self.entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(client_id)
+    }
+
+    /// The stored entry of a user.
+    pub fn get(&self, client_id: &str) -> Option<VaultEntry> {
+        self.entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(client_id)
+            .cloned()
+    }
+
+    /// All stored entries, e.g. to persist them.
+    pub fn entries(&self) -> Vec<VaultEntry> {
+        self.entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Number of stored users.
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Returns `true` if no users are stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A client for a user, or `None` if the user is unknown or the token
+    /// has expired.
+    pub fn client(&self, client_id: &str) -> Option<DhanClient> {
+        self.get(client_id)
+            .filter(|e| !e.is_expired(Utc::now()))
+            .map(|e| DhanClient::with_base_url(e.client_id, e.access_token, &self.base_url))
+    }
+
+    /// Users whose tokens expire before `now + within` (expired ones
+    /// included), sorted.
+    pub fn expiring(&self, now: DateTime<Utc>, within: Duration) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .filter(|e| e.expires_at <= now + within)
+            .map(|e| e.client_id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Renew every token that expires within the refresh margin and has
+    /// not expired yet.
+    ///
+    /// Users are renewed one at a time; the result holds the outcome per
+    /// client ID. Expired tokens cannot be renewed and need a new consent.
+    pub async fn refresh_expiring(&self) -> BTreeMap<String, Result<()>> {
+        let now = Utc::now();
+        let mut results = BTreeMap::new();
+        for client_id in self.expiring(now, self.refresh_margin) {
+            let Some(entry) = self.get(&client_id).filter(|e| !e.is_expired(now)) else {
+                tracing::warn!(client_id, "Token expired; a new consent is required");
+                continue;
+            };
+            let client =
+                DhanClient::with_base_url(&entry.client_id, &entry.access_token, &self.base_url);
+            let result = client.renew_token().await.map(|token| {
+                self.insert(VaultEntry {
+                    client_name: token.dhan_client_name.clone().or(entry.client_name),
+                    ..VaultEntry::from_token(&token, Utc::now())
+                });
+            });
+            if let Err(e) = &result {
+                tracing::warn!(client_id, error = %e, "Token renewal failed");
+            }
+            results.insert(client_id, result);
+        }
+        results
+    }
+}
@@ -0,0 +1,234 @@
+//! Compact encodings of [`MarketFeedEvent`]s for internal redistribution.
+//!
+//! Applications that re-distribute the feed over shared memory or UDP
+//! multicast need events as bytes, not Rust values. An [`EventEncoder`]
+//! appends one encoded event to a buffer; pick one per transport:
+//!
+//! | Encoder          | Format                                             | Decode with                    |
+//! |------------------|----------------------------------------------------|--------------------------------|
+//! | [`FixedEncoder`] | Fixed little-endian structs (Dhan's packet layout) | [`parse_packet`]               |
+//! | [`JsonEncoder`]  | One compact JSON object, tagged by `type`          | any JSON parser                |
+//! | `BincodeEncoder` | bincode 2, standard config (feature `bincode`)     | the `type` string, then fields |
+//!
+//! [`FixedEncoder`] is the SBE-style choice: every template has a fixed
+//! size, fields sit at fixed offsets, and receivers in any language can
+//! read it without a schema compiler. Other formats (FlatBuffers, Cap'n
+//! Proto, …) plug in by implementing [`EventEncoder`].
+//!
+//! ```
+//! use dhan_rs::types::enums::{ExchangeSegment, FeedResponseCode};
+//! use dhan_rs::ws::encode::{EventEncoder, FixedEncoder};
+//! use dhan_rs::ws::market_feed::{MarketFeedEvent, PacketHeader, parse_packet};
+//!
+//! let event = MarketFeedEvent::Ticker {
+//!     header: PacketHeader {
+//!         response_code: FeedResponseCode::Ticker,
+//!         message_length: 16,
+//!         exchange_segment: Some(ExchangeSegment::NSE_EQ),
+//!         exchange_segment_raw: 1,
+//!         security_id: 1333,
+//!     },
+//!     ltp: 1650.5,
+//!     ltt: 1_726_048_740,
+//! };
+//!
+//! let mut buf = Vec::new();
+//! FixedEncoder.encode(&event, &mut buf).unwrap();
+//! assert_eq!(buf.len(), 16);
+//! let decoded = parse_packet(&buf).unwrap();
+//! assert_eq!(decoded.header().security_id, 1333);
+//! ```
+//!
+//! [`parse_packet`]: super::market_feed::parse_packet
+
+use crate::error::{DhanError, Result};
+use crate::ws::market_feed::{MarketFeedEvent, PacketHeader};
+
+/// Appends encoded events to a buffer.
+pub trait EventEncoder: Send + Sync {
+    /// Short name of the format, e.g. for logs.
+    fn name(&self) -> &'static str;
+
+    /// Append `event` to `out`.
+    fn encode(&self, event: &MarketFeedEvent, out: &mut Vec<u8>) -> Result<()>;
+
+    /// `event` as a new buffer.
+    fn to_vec(&self, event: &MarketFeedEvent) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        self.encode(event, &mut out)?;
+        Ok(out)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Fixed layout
+// ---------------------------------------------------------------------------
+
+/// Encodes events in the fixed little-endian layout of Dhan's binary feed
+/// packets, so [`parse_packet`](super::market_feed::parse_packet) decodes
+/// them.
+///
+/// The header's message length is written as the actual encoded length, so
+/// packets can be concatenated and split again; the snapshot marker of
+/// [`crate::ws::snapshot`] events does not survive encoding.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedEncoder;
+
+impl EventEncoder for FixedEncoder {
+    fn name(&self) -> &'static str {
+        "fixed"
+    }
+
+    fn encode(&self, event: &MarketFeedEvent, out: &mut Vec<u8>) -> Result<()> {
+        let start = out.len();
+        write_header(event.header(), out);
+        match event {
+            MarketFeedEvent::Ticker { ltp, ltt, .. } => {
+                out.extend_from_slice(&ltp.to_le_bytes());
+                out.extend_from_slice(&ltt.to_le_bytes());
+            }
+            MarketFeedEvent::PrevClose {
+                prev_close,
+                prev_oi,
+                ..
+            } => {
+                out.extend_from_slice(&prev_close.to_le_bytes());
+                out.extend_from_slice(&prev_oi.to_le_bytes());
+            }
+            MarketFeedEvent::Quote {
+                ltp,
+                last_qty,
+                ltt,
+                atp,
+                volume,
+                total_sell_qty,
+                total_buy_qty,
+                open,
+                close,
+                high,
+                low,
+                ..
+            } => {
+                out.extend_from_slice(&ltp.to_le_bytes());
+                out.extend_from_slice(&last_qty.to_le_bytes());
+                out.extend_from_slice(&ltt.to_le_bytes());
+                out.extend_from_slice(&atp.to_le_bytes());
+                out.extend_from_slice(&volume.to_le_bytes());
+                out.extend_from_slice(&total_sell_qty.to_le_bytes());
+                out.extend_from_slice(&total_buy_qty.to_le_bytes());
+                for v in [open, close, high, low] {
+                    out.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+            MarketFeedEvent::OI { oi, .. } => out.extend_from_slice(&oi.to_le_bytes()),
+            MarketFeedEvent::Full {
+                ltp,
+                last_qty,
+                ltt,
+                atp,
+                volume,
+                total_sell_qty,
+                total_buy_qty,
+                oi,
+                oi_day_high,
+                oi_day_low,
+                open,
+                close,
+                high,
+                low,
+                depth,
+                ..
+            } => {
+                out.extend_from_slice(&ltp.to_le_bytes());
+                out.extend_from_slice(&last_qty.to_le_bytes());
+                out.extend_from_slice(&ltt.to_le_bytes());
+                out.extend_from_slice(&atp.to_le_bytes());
+                for v in [
+                    volume,
+                    total_sell_qty,
+                    total_buy_qty,
+                    oi,
+                    oi_day_high,
+                    oi_day_low,
+                ] {
+                    out.extend_from_slice(&v.to_le_bytes());
+                }
+                for v in [open, close, high, low] {
+                    out.extend_from_slice(&v.to_le_bytes());
+                }
+                for level in depth {
+                    out.extend_from_slice(&level.bid_qty.to_le_bytes());
+                    out.extend_from_slice(&level.ask_qty.to_le_bytes());
+                    out.extend_from_slice(&level.bid_orders.to_le_bytes());
+                    out.extend_from_slice(&level.ask_orders.to_le_bytes());
+                    out.extend_from_slice(&level.bid_price.to_le_bytes());
+                    out.extend_from_slice(&level.ask_price.to_le_bytes());
+                }
+            }
+            MarketFeedEvent::MarketStatus { raw, .. } | MarketFeedEvent::Index { raw, .. } => {
+                out.extend_from_slice(raw);
+            }
+            MarketFeedEvent::Disconnect { reason_code, .. } => {
+                out.extend_from_slice(&reason_code.to_le_bytes());
+            }
+        }
+
+        let len = u16::try_from(out.len() - start).map_err(|_| {
+            out.truncate(start);
+            DhanError::InvalidArgument("event too large for a feed packet".into())
+        })?;
+        out[start + 1..start + 3].copy_from_slice(&len.to_le_bytes());
+        Ok(())
+    }
+}
+
+/// Write the 8-byte packet header with a placeholder length.
+fn write_header(header: &PacketHeader, out: &mut Vec<u8>) {
+    out.push(header.response_code as u8);
+    out.extend_from_slice(&0u16.to_le_bytes());
+    out.push(header.exchange_segment_raw);
+    out.extend_from_slice(&header.security_id.to_le_bytes());
+}
+
+// ---------------------------------------------------------------------------
+// JSON
+// ---------------------------------------------------------------------------
+
+/// Encodes events as compact JSON, tagged by `type` as in their
+/// `Serialize` impl.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonEncoder;
+
+impl EventEncoder for JsonEncoder {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn encode(&self, event: &MarketFeedEvent, out: &mut Vec<u8>) -> Result<()> {
+        serde_json::to_writer(out, event)?;
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// bincode
+// ---------------------------------------------------------------------------
+
+/// Encodes events with bincode 2 (standard config) through their
+/// `Serialize` impl.
+#[cfg(feature = "bincode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BincodeEncoder;
+
+#[cfg(feature = "bincode")]
+impl EventEncoder for BincodeEncoder {
+    fn name(&self) -> &'static str {
+        "bincode"
+    }
+
+    fn encode(&self, event: &MarketFeedEvent, out: &mut Vec<u8>) -> Result<()> {
+        bincode::serde::encode_into_std_write(event, out, bincode::config::standard())
+            .map_err(|e| DhanError::InvalidArgument(format!("bincode encoding failed: {e}")))?;
+        Ok(())
+    }
+}
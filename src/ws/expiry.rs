@@ -0,0 +1,157 @@
+//! Dropping expired derivative contracts from feed subscriptions.
+//!
+//! A daemon that runs for weeks keeps the futures and options it
+//! subscribed long after they expired: the exchange stops sending packets,
+//! but the entries still count against the per-connection limit. An
+//! [`ExpiryRoll`] attached to a [`DhanFeedManager`] (see
+//! [`DhanFeedManager::with_expiry_roll`]) looks up each subscribed
+//! instrument in an [`InstrumentMaster`];
+//! [`DhanFeedManager::roll_expired`] then unsubscribes every contract whose
+//! expiry day has passed and, with [`ExpiryRoll::with_next_contract`],
+//! subscribes the next contract of the same series in the same mode.
+//!
+//! Instruments the master does not list, or lists without an expiry, are
+//! left alone. Call `roll_expired` once a day before the open, e.g. from a
+//! [`DailySchedule`], with a master downloaded that morning so it lists the
+//! newly created contracts.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use dhan_rs::constants::SCRIP_MASTER_DETAILED_URL;
+//! use dhan_rs::instruments::InstrumentMaster;
+//! use dhan_rs::ws::expiry::ExpiryRoll;
+//! use dhan_rs::ws::manager::DhanFeedManagerBuilder;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let http = reqwest::Client::new();
+//! let master = InstrumentMaster::download(&http, SCRIP_MASTER_DETAILED_URL).await?;
+//! let mut manager = DhanFeedManagerBuilder::new("client-id", "token")
+//!     .expiry_roll(ExpiryRoll::new(Arc::new(master)).with_next_contract())
+//!     .build();
+//! manager.start().await?;
+//! // ... subscribe, then every morning:
+//! for roll in manager.roll_expired().await? {
+//!     println!("{} expired, now {:?}", roll.expired, roll.next);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`DhanFeedManager`]: super::manager::DhanFeedManager
+//! [`DhanFeedManager::with_expiry_roll`]: super::manager::DhanFeedManager::with_expiry_roll
+//! [`DhanFeedManager::roll_expired`]: super::manager::DhanFeedManager::roll_expired
+//! [`DailySchedule`]: crate::scheduler::DailySchedule
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::instruments::{InstrumentMaster, InstrumentRecord};
+use crate::types::enums::FeedRequestCode;
+use crate::types::instrument::InstrumentId;
+
+/// One expired subscription and what replaces it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContractRoll {
+    /// The expired contract.
+    pub expired: InstrumentId,
+    /// Its expiry date.
+    pub expiry: NaiveDate,
+    /// The mode it was subscribed in.
+    pub mode: FeedRequestCode,
+    /// The next contract of the series, subscribed in the same mode. `None`
+    /// unless [`ExpiryRoll::with_next_contract`] is set, and when the
+    /// master lists no later contract or it is already subscribed.
+    pub next: Option<InstrumentId>,
+}
+
+/// Finds expired subscriptions using an instrument master.
+#[derive(Debug, Clone)]
+pub struct ExpiryRoll {
+    master: Arc<InstrumentMaster>,
+    subscribe_next: bool,
+}
+
+impl ExpiryRoll {
+    /// Unsubscribe expired contracts listed in `master`.
+    pub fn new(master: Arc<InstrumentMaster>) -> Self {
+        Self {
+            master,
+            subscribe_next: false,
+        }
+    }
+
+    /// Also subscribe the next contract of each expired one's series.
+    pub fn with_next_contract(mut self) -> Self {
+        self.subscribe_next = true;
+        self
+    }
+
+    /// The instrument master.
+    pub fn master(&self) -> &InstrumentMaster {
+        &self.master
+    }
+
+    /// Replace the instrument master, e.g. with the day's fresh download.
+    pub fn set_master(&mut self, master: Arc<InstrumentMaster>) {
+        self.master = master;
+    }
+
+    /// The rolls due on `today` (an IST date) for `subscribed` instruments
+    /// and their modes: every contract that expired before `today`.
+    pub fn plan(
+        &self,
+        subscribed: impl IntoIterator<Item = (InstrumentId, FeedRequestCode)>,
+        today: NaiveDate,
+    ) -> Vec<ContractRoll> {
+        let subscribed: Vec<_> = subscribed.into_iter().collect();
+        let mut taken: HashSet<InstrumentId> = subscribed.iter().map(|(id, _)| *id).collect();
+        let mut rolls = Vec::new();
+        for (id, mode) in subscribed {
+            let Some(record) = self.master.get(&id) else {
+                continue;
+            };
+            let Some(expiry) = record.expiry.filter(|e| *e < today) else {
+                continue;
+            };
+            let next = if self.subscribe_next {
+                self.next_live(record, today)
+                    .filter(|next| taken.insert(*next))
+            } else {
+                None
+            };
+            rolls.push(ContractRoll {
+                expired: id,
+                expiry,
+                mode,
+                next,
+            });
+        }
+        rolls
+    }
+
+    /// The first contract after `record` in its series that has not expired
+    /// by `today`, skipping any the master still lists that have.
+    fn next_live(&self, record: &InstrumentRecord, today: NaiveDate) -> Option<InstrumentId> {
+        let mut next = self.master.next_in_series(record)?;
+        while next.expiry.is_some_and(|e| e < today) {
+            next = self.master.next_in_series(next)?;
+        }
+        Some(next.id)
+    }
+}
+
+/// The unsubscribe request code matching subscription `mode`.
+pub(crate) fn unsubscribe_code(mode: FeedRequestCode) -> FeedRequestCode {
+    match mode {
+        FeedRequestCode::SubscribeTicker => FeedRequestCode::UnsubscribeTicker,
+        FeedRequestCode::SubscribeQuote => FeedRequestCode::UnsubscribeQuote,
+        FeedRequestCode::SubscribeFull => FeedRequestCode::UnsubscribeFull,
+        FeedRequestCode::SubscribeFullMarketDepth => FeedRequestCode::UnsubscribeFullMarketDepth,
+        other => other,
+    }
+}
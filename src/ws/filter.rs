@@ -0,0 +1,327 @@
+//! Declarative filters for streams of feed events.
+//!
+//! [`FeedStreamExt`] adds combinators to any stream of
+//! [`MarketFeedEvent`]s — [`receiver_stream`] over a manager channel, or
+//! [`DhanFeedManager::merged_stream`], whose items carry their connection:
+//!
+//! - [`only`](FeedStreamExt::only) — events of the given instruments
+//! - [`segments`](FeedStreamExt::segments) — events of the given exchange
+//!   segments
+//! - [`event_kinds`](FeedStreamExt::event_kinds) — packets of the given
+//!   response codes
+//! - [`throttle_per_instrument`](FeedStreamExt::throttle_per_instrument) —
+//!   at most one packet of each kind per instrument per interval
+//! - [`conflate`](FeedStreamExt::conflate) — like throttling, but holding
+//!   back the latest packet instead of dropping it, for UIs (see
+//!   [`DhanFeedManager::ui_stream`])
+//!
+//! The adapters poll the stream they wrap in place, so it must be
+//! [`Unpin`]; box other streams with `StreamExt::boxed` first.
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use dhan_rs::types::enums::{ExchangeSegment, FeedResponseCode};
+//! use dhan_rs::ws::filter::FeedStreamExt;
+//! use dhan_rs::ws::manager::DhanFeedManagerBuilder;
+//! use futures_util::StreamExt;
+//!
+//! # async fn example() {
+//! let manager = DhanFeedManagerBuilder::new("client_id", "access_token").build();
+//! let mut options = manager
+//!     .merged_stream()
+//!     .segments(&[ExchangeSegment::NSE_FNO])
+//!     .event_kinds(&[FeedResponseCode::Ticker, FeedResponseCode::Full])
+//!     .throttle_per_instrument(Duration::from_millis(250));
+//! while let Some((_, event)) = options.next().await {
+//!     println!("{:?}", event.header().security_id);
+//! }
+//! # }
+//! ```
+//!
+//! [`DhanFeedManager::merged_stream`]: super::manager::DhanFeedManager::merged_stream
+//! [`DhanFeedManager::ui_stream`]: super::manager::DhanFeedManager::ui_stream
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_util::stream::BoxStream;
+use futures_util::{Stream, StreamExt};
+use tokio::sync::broadcast;
+use tokio::time::{Instant, Sleep};
+
+use crate::types::enums::{ExchangeSegment, FeedResponseCode};
+use crate::types::instrument::InstrumentId;
+use crate::ws::manager::ConnectionId;
+use crate::ws::market_feed::MarketFeedEvent;
+
+/// A stream item that carries a feed event.
+pub trait FeedItem {
+    /// The event.
+    fn event(&self) -> &MarketFeedEvent;
+}
+
+impl FeedItem for MarketFeedEvent {
+    fn event(&self) -> &MarketFeedEvent {
+        self
+    }
+}
+
+impl FeedItem for (ConnectionId, MarketFeedEvent) {
+    fn event(&self) -> &MarketFeedEvent {
+        &self.1
+    }
+}
+
+/// The events of a manager channel as a stream. A consumer that falls
+/// behind skips the lost events (with a warning) instead of ending the
+/// stream.
+pub fn receiver_stream(
+    rx: broadcast::Receiver<MarketFeedEvent>,
+) -> BoxStream<'static, MarketFeedEvent> {
+    futures_util::stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(ev) => return Some((ev, rx)),
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    tracing::warn!(skipped = n, "Feed stream lagging behind feed");
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Combinators for streams of feed events.
+pub trait FeedStreamExt: Stream<Item: FeedItem> + Sized {
+    /// Keep events of the instruments in `ids`.
+    fn only(self, ids: impl IntoIterator<Item = InstrumentId>) -> Filtered<Self> {
+        Filtered::new(self, Rule::Instruments(ids.into_iter().collect()))
+    }
+
+    /// Keep events of the exchange segments in `segments`.
+    fn segments(self, segments: &[ExchangeSegment]) -> Filtered<Self> {
+        Filtered::new(self, Rule::Segments(segments.to_vec()))
+    }
+
+    /// Keep packets whose response code is in `kinds`.
+    fn event_kinds(self, kinds: &[FeedResponseCode]) -> Filtered<Self> {
+        Filtered::new(self, Rule::Kinds(kinds.to_vec()))
+    }
+
+    /// Pass at most one packet of each kind per instrument every
+    /// `interval`, dropping the rest. Keying by kind keeps, say, an OI
+    /// packet from being swallowed by the Quote just before it. Events of
+    /// unknown segments pass unthrottled.
+    fn throttle_per_instrument(self, interval: Duration) -> Throttled<Self> {
+        Throttled {
+            inner: self,
+            interval,
+            last: HashMap::new(),
+        }
+    }
+
+    /// Pass at most `updates_per_sec` packets of each kind per instrument
+    /// per second, always ending with the latest: a packet arriving too
+    /// soon replaces any held one and is released once its interval is up.
+    ///
+    /// The wrapped stream is drained on every poll, so a slow consumer sees
+    /// fewer, fresh packets rather than falling behind. Events of unknown
+    /// segments pass at once.
+    fn conflate(self, updates_per_sec: u32) -> Conflated<Self> {
+        Conflated {
+            inner: Some(self),
+            interval: Duration::from_secs(1) / updates_per_sec.max(1),
+            last: HashMap::new(),
+            held: HashMap::new(),
+            queue: Vec::new(),
+            timer: None,
+        }
+    }
+}
+
+impl<S: Stream<Item: FeedItem>> FeedStreamExt for S {}
+
+#[derive(Debug, Clone)]
+enum Rule {
+    Instruments(HashSet<InstrumentId>),
+    Segments(Vec<ExchangeSegment>),
+    Kinds(Vec<FeedResponseCode>),
+}
+
+impl Rule {
+    fn keeps(&self, event: &MarketFeedEvent) -> bool {
+        let header = event.header();
+        match self {
+            Rule::Instruments(ids) => {
+                InstrumentId::from_header(header).is_some_and(|id| ids.contains(&id))
+            }
+            Rule::Segments(segments) => header
+                .exchange_segment
+                .is_some_and(|s| segments.contains(&s)),
+            Rule::Kinds(kinds) => kinds.contains(&header.response_code),
+        }
+    }
+}
+
+/// Stream returned by the filtering methods of [`FeedStreamExt`].
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Filtered<S> {
+    inner: S,
+    rule: Rule,
+}
+
+impl<S> Filtered<S> {
+    fn new(inner: S, rule: Rule) -> Self {
+        Self { inner, rule }
+    }
+}
+
+impl<S: Stream<Item: FeedItem> + Unpin> Stream for Filtered<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match self.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) if !self.rule.keeps(item.event()) => continue,
+                other => return other,
+            }
+        }
+    }
+}
+
+/// Stream returned by [`FeedStreamExt::throttle_per_instrument`].
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Throttled<S> {
+    inner: S,
+    interval: Duration,
+    /// When each instrument's packets of each kind last passed.
+    last: HashMap<(InstrumentId, FeedResponseCode), Instant>,
+}
+
+impl<S: Stream<Item: FeedItem> + Unpin> Stream for Throttled<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let item = match self.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => item,
+                other => return other,
+            };
+            let header = item.event().header();
+            let Some(id) = InstrumentId::from_header(header) else {
+                return Poll::Ready(Some(item));
+            };
+            let key = (id, header.response_code);
+            let now = Instant::now();
+            let interval = self.interval;
+            if self
+                .last
+                .get(&key)
+                .is_some_and(|at| now.duration_since(*at) < interval)
+            {
+                continue;
+            }
+            self.last.insert(key, now);
+            return Poll::Ready(Some(item));
+        }
+    }
+}
+
+/// Stream returned by [`FeedStreamExt::conflate`].
+#[must_use = "streams do nothing unless polled"]
+pub struct Conflated<S: Stream> {
+    /// `None` once the wrapped stream ended.
+    inner: Option<S>,
+    interval: Duration,
+    /// When each instrument's packets of each kind last passed.
+    last: HashMap<(InstrumentId, FeedResponseCode), Instant>,
+    /// The latest packet of each key waiting for its interval.
+    held: HashMap<(InstrumentId, FeedResponseCode), S::Item>,
+    /// Held keys in arrival order, so no instrument starves.
+    queue: Vec<(InstrumentId, FeedResponseCode)>,
+    timer: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S: Stream> std::fmt::Debug for Conflated<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Conflated")
+            .field("interval", &self.interval)
+            .field("held", &self.held.len())
+            .field("ended", &self.inner.is_none())
+            .finish()
+    }
+}
+
+impl<S: Stream<Item: FeedItem + Unpin> + Unpin> Stream for Conflated<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        // Drain what is ready, passing on the first packet that is due.
+        while let Some(inner) = &mut this.inner {
+            let item = match inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(item)) => item,
+                Poll::Ready(None) => {
+                    this.inner = None;
+                    break;
+                }
+                Poll::Pending => break,
+            };
+            let header = item.event().header();
+            let Some(id) = InstrumentId::from_header(header) else {
+                return Poll::Ready(Some(item));
+            };
+            let key = (id, header.response_code);
+            let now = Instant::now();
+            let due = this
+                .last
+                .get(&key)
+                .is_none_or(|at| now.duration_since(*at) >= this.interval);
+            if due && !this.held.contains_key(&key) {
+                this.last.insert(key, now);
+                return Poll::Ready(Some(item));
+            }
+            if this.held.insert(key, item).is_none() {
+                this.queue.push(key);
+            }
+        }
+
+        loop {
+            let now = Instant::now();
+            let mut next_due: Option<Instant> = None;
+            for (i, key) in this.queue.iter().enumerate() {
+                let due_at = this.last.get(key).map_or(now, |at| *at + this.interval);
+                if due_at <= now {
+                    let key = this.queue.remove(i);
+                    this.last.insert(key, now);
+                    return Poll::Ready(this.held.remove(&key));
+                }
+                next_due = Some(next_due.map_or(due_at, |t| t.min(due_at)));
+            }
+            let Some(deadline) = next_due else {
+                this.timer = None;
+                return if this.inner.is_none() {
+                    Poll::Ready(None)
+                } else {
+                    Poll::Pending
+                };
+            };
+            match &mut this.timer {
+                Some(timer) => timer.as_mut().reset(deadline),
+                None => this.timer = Some(Box::pin(tokio::time::sleep_until(deadline))),
+            }
+            if let Some(timer) = &mut this.timer {
+                if timer.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Lag telemetry for broadcast consumers.
+//!
+//! Feed events are fanned out on `tokio::sync::broadcast` channels, which
+//! never block the sender: a consumer that falls more than the channel
+//! capacity behind silently loses the oldest events and gets a single
+//! `RecvError::Lagged`. A [`MeteredReceiver`] wraps the receiver, skips
+//! past lags instead of returning them, and counts what was lost per named
+//! consumer. [`LagRegistry`] collects those counters so operators can see
+//! which consumer is falling behind (see
+//! [`DhanFeedManager::consumer_stats`]).
+//!
+//! ```
+//! use dhan_rs::ws::lag::LagRegistry;
+//! use tokio::sync::broadcast;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let (tx, rx) = broadcast::channel(2);
+//! let registry = LagRegistry::new();
+//! let mut rx = registry.wrap("slow-consumer", rx);
+//!
+//! for i in 0..5 {
+//!     tx.send(i).unwrap();
+//! }
+//! // The three oldest values were overwritten.
+//! assert_eq!(rx.recv().await, Some(3));
+//! assert_eq!(rx.dropped(), 3);
+//! assert_eq!(registry.stats()[0].lag_events, 1);
+//! # }
+//! ```
+//!
+//! [`DhanFeedManager::consumer_stats`]: super::manager::DhanFeedManager::consumer_stats
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError, error::TryRecvError};
+
+/// Counters of one consumer.
+#[derive(Debug)]
+struct Counters {
+    name: String,
+    received: AtomicU64,
+    dropped: AtomicU64,
+    lag_events: AtomicU64,
+}
+
+/// Snapshot of one consumer's counters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConsumerStats {
+    /// Name given when the receiver was created.
+    pub name: String,
+    /// Messages delivered.
+    pub received: u64,
+    /// Messages lost because the consumer fell behind.
+    pub dropped: u64,
+    /// Number of times it fell behind.
+    pub lag_events: u64,
+    /// Whether the receiver still exists.
+    pub active: bool,
+}
+
+/// Named consumer counters, shared by every [`MeteredReceiver`] created
+/// through it.
+///
+/// Cloning is cheap and clones share the counters. Counters of dropped
+/// receivers are kept (with `active: false`) so losses are not forgotten.
+#[derive(Debug, Clone, Default)]
+pub struct LagRegistry {
+    consumers: Arc<Mutex<Vec<Arc<Counters>>>>,
+}
+
+impl LagRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Meter `rx` as consumer `name`.
+    pub fn wrap<T: Clone>(
+        &self,
+        name: impl Into<String>,
+        rx: broadcast::Receiver<T>,
+    ) -> MeteredReceiver<T> {
+        let counters = Arc::new(Counters {
+            name: name.into(),
+            received: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            lag_events: AtomicU64::new(0),
+        });
+        self.consumers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Arc::clone(&counters));
+        MeteredReceiver { rx, counters }
+    }
+
+    /// Counters of every consumer, in creation order.
+    pub fn stats(&self) -> Vec<ConsumerStats> {
+        self.consumers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|c| ConsumerStats {
+                name: c.name.clone(),
+                received: c.received.load(Ordering::Relaxed),
+                dropped: c.dropped.load(Ordering::Relaxed),
+                lag_events: c.lag_events.load(Ordering::Relaxed),
+                active: Arc::strong_count(c) > 1,
+            })
+            .collect()
+    }
+
+    /// Messages lost across all consumers.
+    pub fn total_dropped(&self) -> u64 {
+        self.stats().iter().map(|s| s.dropped).sum()
+    }
+}
+
+/// A broadcast receiver that counts and skips past lag.
+#[derive(Debug)]
+pub struct MeteredReceiver<T> {
+    rx: broadcast::Receiver<T>,
+    counters: Arc<Counters>,
+}
+
+impl<T: Clone> MeteredReceiver<T> {
+    /// The next message, or `None` once the channel is closed.
+    ///
+    /// If the consumer fell behind, the lost messages are counted (and
+    /// logged) and the oldest message still buffered is returned.
+    pub async fn recv(&mut self) -> Option<T> {
+        loop {
+            match self.rx.recv().await {
+                Ok(msg) => {
+                    self.counters.received.fetch_add(1, Ordering::Relaxed);
+                    return Some(msg);
+                }
+                Err(RecvError::Lagged(n)) => self.record_lag(n),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// The next message if one is buffered, skipping past lag like
+    /// [`Self::recv`].
+    pub fn try_recv(&mut self) -> Option<T> {
+        loop {
+            match self.rx.try_recv() {
+                Ok(msg) => {
+                    self.counters.received.fetch_add(1, Ordering::Relaxed);
+                    return Some(msg);
+                }
+                Err(TryRecvError::Lagged(n)) => self.record_lag(n),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Messages delivered so far.
+    pub fn received(&self) -> u64 {
+        self.counters.received.load(Ordering::Relaxed)
+    }
+
+    /// Messages lost so far.
+    pub fn dropped(&self) -> u64 {
+        self.counters.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Messages buffered and not yet received.
+    pub fn backlog(&self) -> usize {
+        self.rx.len()
+    }
+
+    /// The wrapped receiver.
+    pub fn into_inner(self) -> broadcast::Receiver<T> {
+        self.rx
+    }
+
+    fn record_lag(&self, n: u64) {
+        self.counters.dropped.fetch_add(n, Ordering::Relaxed);
+        self.counters.lag_events.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(
+            consumer = %self.counters.name,
+            dropped = n,
+            "Broadcast consumer fell behind"
+        );
+    }
+}
@@ -27,6 +27,22 @@
 //! - **Raw** `bytes::Bytes` forwarded on a separate `broadcast` channel for
 //!   zero-copy / low-latency consumers.
 //!
+//! # Heartbeat
+//!
+//! Each connection watches for silence: after half of
+//! [`DhanFeedConfig::idle_timeout_ms`] without a frame it pings the server,
+//! and after the full window it publishes [`FeedLifecycle::Stale`], drops
+//! the socket and reconnects (if enabled). The time of each connection's
+//! last frame is reported by [`DhanFeedManager::health`].
+//!
+//! # Token renewal
+//!
+//! Sockets keep the token they were opened with. After renewing it, call
+//! [`DhanFeedManager::rotate_token`] to move every connection onto the new
+//! token before the old one expires, without a gap in the data; once a
+//! connection has been refused, [`DhanFeedManager::reconnect_with_token`]
+//! restarts them all.
+//!
 //! # Quick Start
 //!
 //! ```no_run
@@ -82,30 +98,49 @@
 //! - Up to **100** instruments per subscribe/unsubscribe message
 //! - Total capacity: **25,000** instruments
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::time::Duration;
 
 use bytes::Bytes;
-use futures_util::{SinkExt, StreamExt};
+use chrono::{NaiveDate, Utc};
+use futures_util::{SinkExt, Stream, StreamExt};
 use serde::Serialize;
 use tokio::net::TcpStream;
-use tokio::sync::{Mutex, broadcast};
+use tokio::sync::{Mutex, broadcast, watch};
 use tokio::task::JoinHandle;
 use tokio_tungstenite::tungstenite::Message;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 
+use crate::client::DhanClient;
 use crate::constants::WS_MARKET_FEED_URL;
+use crate::constants::rate_limits::websocket::PONG_TIMEOUT_SECS;
+use crate::dns::{DnsPins, connect_ws};
 use crate::error::{DhanError, Result};
+use crate::events::{EventBus, FeedLifecycle, SessionEvent};
+use crate::instruments::InstrumentFilter;
+use crate::time::to_ist;
+use crate::token::SharedToken;
 use crate::types::enums::FeedRequestCode;
-use crate::ws::market_feed::{Instrument, MarketFeedEvent, parse_packet};
+use crate::types::instrument::InstrumentId;
+use crate::ws::expiry::{ContractRoll, ExpiryRoll, unsubscribe_code};
+use crate::ws::filter::FeedStreamExt;
+use crate::ws::lag::{ConsumerStats, LagRegistry, MeteredReceiver};
+use crate::ws::market_feed::{
+    Instrument, MarketFeedEvent, connect_error, disconnect_auth_error, disconnect_code,
+    parse_packet,
+};
+use crate::ws::slots::{ConnectionSlots, SlotGuard};
+use crate::ws::snapshot::{is_snapshot, spawn_bootstrap};
+use crate::ws::warmup::{AckTracker, WarmupHandle, WarmupProgress};
 
 // ---------------------------------------------------------------------------
 // Connection ID
 // ---------------------------------------------------------------------------
 
 /// Identifies one of the managed WebSocket connections (0–4).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub struct ConnectionId(pub u8);
 
 impl std::fmt::Display for ConnectionId {
@@ -119,7 +154,7 @@ impl std::fmt::Display for ConnectionId {
 // ---------------------------------------------------------------------------
 
 /// Health status of a single managed connection.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ConnectionHealth {
     /// Whether the connection's background task is alive.
     pub is_alive: bool,
@@ -129,10 +164,12 @@ pub struct ConnectionHealth {
     pub instrument_count: usize,
     /// Number of reconnections that have occurred.
     pub reconnect_count: u64,
+    /// When the last frame of any kind arrived (Unix milliseconds).
+    pub last_message_at: Option<i64>,
 }
 
 /// Aggregate health summary across all managed connections.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct HealthSummary {
     /// Per-connection health snapshots.
     pub connections: Vec<ConnectionHealth>,
@@ -140,6 +177,98 @@ pub struct HealthSummary {
     pub total_instruments: usize,
     /// Number of connections that are alive.
     pub alive_connections: usize,
+    /// Counters of the metered consumers (see [`crate::ws::lag`]).
+    pub consumers: Vec<ConsumerStats>,
+}
+
+// ---------------------------------------------------------------------------
+// Subscription capacity
+// ---------------------------------------------------------------------------
+
+/// Instrument slots of a single managed connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ConnectionCapacity {
+    /// The connection's identifier.
+    pub id: ConnectionId,
+    /// Slots taken by subscribed instruments.
+    pub used: usize,
+    /// Slots still free.
+    pub free: usize,
+    /// [`DhanFeedConfig::max_instruments_per_connection`].
+    pub limit: usize,
+}
+
+/// Instrument slots across all managed connections, as they are or as
+/// they would be after a subscribe call.
+///
+/// With the default five connections of 5,000 instruments the overall
+/// limit is Dhan's ceiling of 25,000.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FeedCapacity {
+    /// Per-connection slots.
+    pub connections: Vec<ConnectionCapacity>,
+    /// Slots taken across all connections.
+    pub used: usize,
+    /// Slots still free across all connections.
+    pub free: usize,
+    /// Total slots.
+    pub limit: usize,
+    /// Instruments of the projected subscribe call that would not fit, in
+    /// which case the call fails (0 for the current state).
+    pub unplaced: usize,
+}
+
+impl FeedCapacity {
+    fn from_loads(ids: impl Iterator<Item = ConnectionId>, loads: &[usize], limit: usize) -> Self {
+        let connections: Vec<_> = ids
+            .zip(loads)
+            .map(|(id, &used)| ConnectionCapacity {
+                id,
+                used,
+                free: limit.saturating_sub(used),
+                limit,
+            })
+            .collect();
+        Self {
+            used: connections.iter().map(|c| c.used).sum(),
+            free: connections.iter().map(|c| c.free).sum(),
+            limit: limit * connections.len(),
+            connections,
+            unplaced: 0,
+        }
+    }
+}
+
+/// Most time [`DhanFeedManager::rotate_token`] keeps an old connection open
+/// while waiting for data on its replacement.
+pub const ROTATION_OVERLAP: Duration = Duration::from_secs(5);
+
+// ---------------------------------------------------------------------------
+// Authentication failure
+// ---------------------------------------------------------------------------
+
+/// A connection stopped because the server rejected the credentials.
+///
+/// The manager does not reconnect after this; renew the token and call
+/// [`DhanFeedManager::reconnect_with_token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct FeedAuthFailure {
+    /// The connection that was refused.
+    pub connection: ConnectionId,
+    /// Reason code of the Disconnect packet; `None` if the handshake itself
+    /// was refused.
+    pub reason_code: Option<i16>,
+}
+
+impl FeedAuthFailure {
+    /// The failure as an error, usually [`DhanError::TokenExpired`].
+    pub fn to_error(&self) -> DhanError {
+        self.reason_code
+            .and_then(disconnect_auth_error)
+            .unwrap_or_else(|| {
+                DhanError::TokenExpired(format!("{} handshake refused", self.connection))
+            })
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -175,6 +304,21 @@ pub struct DhanFeedConfig {
     pub raw_channel_capacity: usize,
     /// Whether to automatically reconnect on disconnect.
     pub auto_reconnect: bool,
+    /// Subscribe messages per second sent by
+    /// [`DhanFeedManager::warm_subscribe`].
+    pub warmup_messages_per_sec: u32,
+    /// How long [`WarmupHandle::await_warmup`] waits (milliseconds).
+    pub warmup_timeout_ms: u64,
+    /// Silence after which a connection is considered stale and dropped
+    /// (milliseconds); a ping is sent at half this. 0 disables the check.
+    pub idle_timeout_ms: u64,
+}
+
+impl DhanFeedConfig {
+    /// The idle timeout, or `None` if disabled.
+    fn idle_timeout(&self) -> Option<Duration> {
+        (self.idle_timeout_ms > 0).then(|| Duration::from_millis(self.idle_timeout_ms))
+    }
 }
 
 impl Default for DhanFeedConfig {
@@ -187,6 +331,9 @@ impl Default for DhanFeedConfig {
             parsed_channel_capacity: 4096,
             raw_channel_capacity: 4096,
             auto_reconnect: true,
+            warmup_messages_per_sec: 10,
+            warmup_timeout_ms: 60_000,
+            idle_timeout_ms: u64::from(PONG_TIMEOUT_SECS) * 1_000,
         }
     }
 }
@@ -209,8 +356,13 @@ impl Default for DhanFeedConfig {
 /// ```
 pub struct DhanFeedManagerBuilder {
     client_id: String,
-    access_token: String,
+    access_token: SharedToken,
     config: DhanFeedConfig,
+    events: Option<EventBus>,
+    snapshot_client: Option<DhanClient>,
+    dns_pins: Option<DnsPins>,
+    expiry_roll: Option<ExpiryRoll>,
+    slots: Option<ConnectionSlots>,
 }
 
 impl DhanFeedManagerBuilder {
@@ -218,8 +370,13 @@ impl DhanFeedManagerBuilder {
     pub fn new(client_id: impl Into<String>, access_token: impl Into<String>) -> Self {
         Self {
             client_id: client_id.into(),
-            access_token: access_token.into(),
+            access_token: SharedToken::new(access_token),
             config: DhanFeedConfig::default(),
+            events: None,
+            snapshot_client: None,
+            dns_pins: None,
+            expiry_roll: None,
+            slots: None,
         }
     }
 
@@ -265,9 +422,86 @@ impl DhanFeedManagerBuilder {
         self
     }
 
+    /// Set the pace of [`DhanFeedManager::warm_subscribe`] in subscribe
+    /// messages per second. Default: 10.
+    pub fn warmup_messages_per_sec(mut self, n: u32) -> Self {
+        self.config.warmup_messages_per_sec = n.max(1);
+        self
+    }
+
+    /// Set how long a warm-up may take in milliseconds. Default: 60,000.
+    pub fn warmup_timeout_ms(mut self, ms: u64) -> Self {
+        self.config.warmup_timeout_ms = ms;
+        self
+    }
+
+    /// Set the silence in milliseconds after which a connection is dropped
+    /// as stale (0 disables). Default: 40,000.
+    pub fn idle_timeout_ms(mut self, ms: u64) -> Self {
+        self.config.idle_timeout_ms = ms;
+        self
+    }
+
+    /// Publish connection changes on `bus`.
+    pub fn event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
+    }
+
+    /// Emit REST quote snapshots for new Quote/Full subscriptions, fetched
+    /// with `client`. See [`crate::ws::snapshot`].
+    pub fn snapshot_bootstrap(mut self, client: DhanClient) -> Self {
+        self.snapshot_client = Some(client);
+        self
+    }
+
+    /// Connect to the feed host's addresses in `pins` instead of resolving
+    /// it on every (re)connect.
+    pub fn dns_pins(mut self, pins: DnsPins) -> Self {
+        self.dns_pins = Some(pins);
+        self
+    }
+
+    /// Drop expired contracts with `roll`; see [`crate::ws::expiry`].
+    pub fn expiry_roll(mut self, roll: ExpiryRoll) -> Self {
+        self.expiry_roll = Some(roll);
+        self
+    }
+
+    /// Claim a slot from `slots` for every connection; see
+    /// [`crate::ws::slots`].
+    pub fn connection_slots(mut self, slots: ConnectionSlots) -> Self {
+        self.slots = Some(slots);
+        self
+    }
+
+    /// Read the access token from `token` on every (re)connect, e.g.
+    /// [`DhanClient::shared_token`], so renewals reach the feed.
+    pub fn shared_token(mut self, token: SharedToken) -> Self {
+        self.access_token = token;
+        self
+    }
+
     /// Build the [`DhanFeedManager`].
     pub fn build(self) -> DhanFeedManager {
-        DhanFeedManager::new(self.client_id, self.access_token, self.config)
+        let mut manager = DhanFeedManager::new(self.client_id, "", self.config)
+            .with_shared_token(self.access_token);
+        if let Some(bus) = self.events {
+            manager = manager.with_event_bus(bus);
+        }
+        if let Some(client) = self.snapshot_client {
+            manager = manager.with_snapshot_bootstrap(client);
+        }
+        if let Some(pins) = self.dns_pins {
+            manager = manager.with_dns_pins(pins);
+        }
+        if let Some(roll) = self.expiry_roll {
+            manager = manager.with_expiry_roll(roll);
+        }
+        if let Some(slots) = self.slots {
+            manager = manager.with_connection_slots(slots);
+        }
+        manager
     }
 }
 
@@ -291,6 +525,93 @@ impl From<&Instrument> for InstrumentKey {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Previous-close cache
+// ---------------------------------------------------------------------------
+
+/// Previous-session close of an instrument, from a `PrevClose` packet.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PrevClose {
+    /// Previous day closing price.
+    pub close: f64,
+    /// Previous day open interest.
+    pub oi: i64,
+}
+
+/// Previous-close values per instrument, shared by all connections.
+///
+/// `PrevClose` packets arrive once per subscription, so subscribers that
+/// start listening late would otherwise miss them. Cloning is cheap and
+/// clones share the same data.
+#[derive(Debug, Clone, Default)]
+pub struct PrevCloseCache {
+    inner: Arc<std::sync::RwLock<HashMap<InstrumentId, PrevClose>>>,
+}
+
+impl PrevCloseCache {
+    /// Record the previous close carried by `event`, if it is a `PrevClose`
+    /// packet.
+    pub fn observe(&self, event: &MarketFeedEvent) {
+        if let MarketFeedEvent::PrevClose {
+            header,
+            prev_close,
+            prev_oi,
+        } = event
+        {
+            if let Some(id) = InstrumentId::from_header(header) {
+                self.inner
+                    .write()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(
+                        id,
+                        PrevClose {
+                            close: f64::from(*prev_close),
+                            oi: i64::from(*prev_oi),
+                        },
+                    );
+            }
+        }
+    }
+
+    /// Previous close of `instrument`.
+    pub fn get(&self, instrument: &InstrumentId) -> Option<PrevClose> {
+        self.inner
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(instrument)
+            .copied()
+    }
+
+    /// Wrap `event` with its change against the cached previous close.
+    pub fn enrich(&self, event: MarketFeedEvent) -> EnrichedEvent {
+        let ltp = event.to_tick().map(|t| t.ltp).filter(|p| *p > 0.0);
+        let prev_close = InstrumentId::from_header(event.header())
+            .and_then(|id| self.get(&id))
+            .map(|p| p.close)
+            .filter(|c| *c > 0.0);
+        let change = ltp.zip(prev_close).map(|(ltp, prev)| ltp - prev);
+        EnrichedEvent {
+            change_pct: change.zip(prev_close).map(|(c, prev)| c / prev * 100.0),
+            change,
+            prev_close,
+            event,
+        }
+    }
+}
+
+/// A feed event with its change against the previous close.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnrichedEvent {
+    /// The original event.
+    pub event: MarketFeedEvent,
+    /// Cached previous close of the instrument.
+    pub prev_close: Option<f64>,
+    /// Last traded price minus previous close.
+    pub change: Option<f64>,
+    /// `change` as a percentage of the previous close.
+    pub change_pct: Option<f64>,
+}
+
 // ---------------------------------------------------------------------------
 // Per-connection state
 // ---------------------------------------------------------------------------
@@ -309,6 +630,8 @@ struct ManagedConnection {
     instruments: HashMap<InstrumentKey, (Instrument, FeedRequestCode)>,
     /// Reconnect count.
     reconnect_count: u64,
+    /// Unix milliseconds of the last received frame (0 before the first).
+    last_message: Arc<AtomicI64>,
 }
 
 type WriterHalf =
@@ -357,9 +680,18 @@ type WriterHalf =
 /// ```
 pub struct DhanFeedManager {
     client_id: String,
-    access_token: String,
+    access_token: SharedToken,
     config: DhanFeedConfig,
     connections: Vec<ManagedConnection>,
+    prev_closes: PrevCloseCache,
+    events: Option<EventBus>,
+    snapshot_client: Option<DhanClient>,
+    dns_pins: Option<DnsPins>,
+    expiry_roll: Option<ExpiryRoll>,
+    auth_failure: watch::Sender<Option<FeedAuthFailure>>,
+    lag: LagRegistry,
+    slots: Option<ConnectionSlots>,
+    slot_guards: Vec<SlotGuard>,
     started: bool,
 }
 
@@ -373,7 +705,7 @@ impl DhanFeedManager {
         config: DhanFeedConfig,
     ) -> Self {
         let client_id = client_id.into();
-        let access_token = access_token.into();
+        let access_token = SharedToken::new(access_token);
         let n = config.max_connections as usize;
 
         let connections = (0..n)
@@ -393,6 +725,7 @@ impl DhanFeedManager {
                     writer: Arc::new(Mutex::new(None)),
                     instruments: HashMap::new(),
                     reconnect_count: 0,
+                    last_message: Arc::new(AtomicI64::new(0)),
                 }
             })
             .collect();
@@ -402,10 +735,214 @@ impl DhanFeedManager {
             access_token,
             config,
             connections,
+            prev_closes: PrevCloseCache::default(),
+            events: None,
+            snapshot_client: None,
+            dns_pins: None,
+            expiry_roll: None,
+            auth_failure: watch::channel(None).0,
+            lag: LagRegistry::new(),
+            slots: None,
+            slot_guards: Vec::new(),
             started: false,
         }
     }
 
+    /// Publish connection changes ([`FeedLifecycle`]) on `bus`.
+    pub fn with_event_bus(mut self, bus: EventBus) -> Self {
+        self.events = Some(bus);
+        self
+    }
+
+    /// Read the access token from `token` on every (re)connect, e.g.
+    /// [`DhanClient::shared_token`], so connections that drop after a
+    /// renewal come back with the new token.
+    pub fn with_shared_token(mut self, token: SharedToken) -> Self {
+        self.access_token = token;
+        self
+    }
+
+    /// The token the connections read on every (re)connect.
+    pub fn shared_token(&self) -> SharedToken {
+        self.access_token.clone()
+    }
+
+    /// On every Quote or Full subscription, fetch REST quotes for the new
+    /// instruments with `client` and emit them as synthetic events before
+    /// the first live packets. See [`crate::ws::snapshot`].
+    pub fn with_snapshot_bootstrap(mut self, client: DhanClient) -> Self {
+        self.snapshot_client = Some(client);
+        self
+    }
+
+    /// Connect to the feed host's addresses in `pins` (see [`crate::dns`])
+    /// instead of resolving it on every (re)connect.
+    pub fn with_dns_pins(mut self, pins: DnsPins) -> Self {
+        self.dns_pins = Some(pins);
+        self
+    }
+
+    /// Coordinate connections with other processes sharing the account
+    /// through `slots` (see [`crate::ws::slots`]).
+    ///
+    /// [`Self::start`] then opens only as many connections as there are
+    /// free slots, failing with [`DhanError::ConnectionLimit`] if there is
+    /// none, and [`Self::rotate_token`] needs one more free slot.
+    pub fn with_connection_slots(mut self, slots: ConnectionSlots) -> Self {
+        self.slots = Some(slots);
+        self
+    }
+
+    /// Look up subscribed contracts with `roll` so that
+    /// [`Self::roll_expired`] can drop the expired ones. See
+    /// [`crate::ws::expiry`].
+    pub fn with_expiry_roll(mut self, roll: ExpiryRoll) -> Self {
+        self.expiry_roll = Some(roll);
+        self
+    }
+
+    /// The expiry roll, e.g. to replace its instrument master.
+    pub fn expiry_roll_mut(&mut self) -> Option<&mut ExpiryRoll> {
+        self.expiry_roll.as_mut()
+    }
+
+    /// The first connection refused for its credentials since the last
+    /// (re)start, if any.
+    pub fn auth_failure(&self) -> Option<FeedAuthFailure> {
+        *self.auth_failure.borrow()
+    }
+
+    /// A receiver that changes when a connection is refused for its
+    /// credentials, e.g. to trigger a token renewal.
+    pub fn watch_auth_failure(&self) -> watch::Receiver<Option<FeedAuthFailure>> {
+        self.auth_failure.subscribe()
+    }
+
+    /// Fail with [`DhanError::TokenExpired`] (or
+    /// [`DhanError::WsAuthFailed`]) if a connection was refused for its
+    /// credentials.
+    pub fn check_auth(&self) -> Result<()> {
+        match self.auth_failure() {
+            Some(failure) => Err(failure.to_error()),
+            None => Ok(()),
+        }
+    }
+
+    /// Replace the access token (for everything sharing it) and restart
+    /// every connection with it, re-subscribing its instruments.
+    pub async fn reconnect_with_token(&mut self, access_token: impl Into<String>) -> Result<()> {
+        if !self.started {
+            return Err(DhanError::InvalidArgument(
+                "manager not started — call start() first".into(),
+            ));
+        }
+        self.access_token.set(access_token);
+        self.auth_failure.send_replace(None);
+        for conn in &mut self.connections {
+            if let Some(task) = conn.task.take() {
+                task.abort();
+            }
+            *conn.writer.lock().await = None;
+            Self::spawn_connection(
+                &self.client_id,
+                &self.access_token,
+                conn,
+                self.config.auto_reconnect,
+                self.config.reconnect_delay_ms,
+                self.config.enable_raw_frames,
+                self.config.idle_timeout(),
+                self.prev_closes.clone(),
+                self.events.clone(),
+                self.auth_failure.clone(),
+                self.dns_pins.clone(),
+            )
+            .await?;
+            conn.reconnect_count += 1;
+        }
+        tracing::info!("DhanFeedManager reconnected with a new token");
+        Ok(())
+    }
+
+    /// Replace the access token (for everything sharing it) and move every
+    /// connection onto it without a gap in the data.
+    ///
+    /// Unlike [`Self::reconnect_with_token`], each connection is replaced
+    /// make-before-break: a new socket is opened with the new token and
+    /// re-subscribed, and the old one is closed once the new one delivers
+    /// its first frame (or after [`ROTATION_OVERLAP`]). Connections are
+    /// rotated one at a time, so one extra socket is open at any moment;
+    /// with all five account connections in use the extra one may be
+    /// refused, in which case this fails and the connection that could not
+    /// be replaced keeps its old socket. Consumers may see a few packets
+    /// twice during the overlap.
+    pub async fn rotate_token(&mut self, access_token: impl Into<String>) -> Result<()> {
+        if !self.started {
+            return Err(DhanError::InvalidArgument(
+                "manager not started — call start() first".into(),
+            ));
+        }
+        // The replacement socket needs a slot while the old one is open.
+        let _extra_slot = match &self.slots {
+            Some(slots) => Some(slots.try_acquire()?.ok_or_else(|| {
+                DhanError::ConnectionLimit("no free slot to rotate connections".into())
+            })?),
+            None => None,
+        };
+        self.access_token.set(access_token);
+        self.auth_failure.send_replace(None);
+        for conn in &mut self.connections {
+            let old_task = conn.task.take();
+            let old_writer = conn.writer.lock().await.take();
+            let old_last_message =
+                std::mem::replace(&mut conn.last_message, Arc::new(AtomicI64::new(0)));
+            let spawned = Self::spawn_connection(
+                &self.client_id,
+                &self.access_token,
+                conn,
+                self.config.auto_reconnect,
+                self.config.reconnect_delay_ms,
+                self.config.enable_raw_frames,
+                self.config.idle_timeout(),
+                self.prev_closes.clone(),
+                self.events.clone(),
+                self.auth_failure.clone(),
+                self.dns_pins.clone(),
+            )
+            .await;
+            if let Err(e) = spawned {
+                tracing::error!(connection = %conn.id, error = %e, "Token rotation failed");
+                conn.task = old_task;
+                *conn.writer.lock().await = old_writer;
+                conn.last_message = old_last_message;
+                return Err(e);
+            }
+
+            if !conn.instruments.is_empty() {
+                let deadline = tokio::time::Instant::now() + ROTATION_OVERLAP;
+                while conn.last_message.load(Ordering::Relaxed) == 0
+                    && tokio::time::Instant::now() < deadline
+                {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                if conn.last_message.load(Ordering::Relaxed) == 0 {
+                    tracing::warn!(
+                        connection = %conn.id,
+                        "No data on the rotated connection yet; closing the old one anyway"
+                    );
+                }
+            }
+            if let Some(task) = old_task {
+                task.abort();
+            }
+            if let Some(mut w) = old_writer {
+                let _ = w.send(Message::Close(None)).await;
+            }
+            conn.reconnect_count += 1;
+        }
+        tracing::info!("DhanFeedManager rotated to a new token");
+        Ok(())
+    }
+
     /// Start all configured WebSocket connections.
     ///
     /// Each connection is run in a dedicated Tokio task that reads binary
@@ -414,6 +951,23 @@ impl DhanFeedManager {
         if self.started {
             return Err(DhanError::InvalidArgument("manager already started".into()));
         }
+        if let Some(slots) = &self.slots {
+            self.slot_guards = slots.acquire_up_to(self.connections.len())?;
+            if self.slot_guards.is_empty() {
+                return Err(DhanError::ConnectionLimit(format!(
+                    "all {} feed connection slots are held",
+                    slots.limit()
+                )));
+            }
+            if self.slot_guards.len() < self.connections.len() {
+                tracing::warn!(
+                    free = self.slot_guards.len(),
+                    configured = self.connections.len(),
+                    "Fewer feed connection slots free than configured; opening fewer connections"
+                );
+                self.connections.truncate(self.slot_guards.len());
+            }
+        }
 
         for conn in &mut self.connections {
             Self::spawn_connection(
@@ -423,6 +977,11 @@ impl DhanFeedManager {
                 self.config.auto_reconnect,
                 self.config.reconnect_delay_ms,
                 self.config.enable_raw_frames,
+                self.config.idle_timeout(),
+                self.prev_closes.clone(),
+                self.events.clone(),
+                self.auth_failure.clone(),
+                self.dns_pins.clone(),
             )
             .await?;
         }
@@ -450,9 +1009,11 @@ impl DhanFeedManager {
                 "manager not started — call start() first".into(),
             ));
         }
+        self.check_auth()?;
 
         // Distribute instruments across connections
         let assignments = self.assign_instruments(instruments, mode)?;
+        self.bootstrap_snapshots(&assignments, mode);
 
         for (conn_idx, batch) in assignments {
             let conn = &mut self.connections[conn_idx];
@@ -495,6 +1056,165 @@ impl DhanFeedManager {
         Ok(())
     }
 
+    /// Subscribe a large set of instruments in the background, paced at
+    /// [`DhanFeedConfig::warmup_messages_per_sec`].
+    ///
+    /// Instruments are assigned to connections as in [`Self::subscribe`]
+    /// and tracked immediately (so a reconnect re-subscribes them). The
+    /// returned [`WarmupHandle`] reports progress; see
+    /// [`crate::ws::warmup`].
+    pub fn warm_subscribe(
+        &mut self,
+        instruments: &[Instrument],
+        mode: FeedRequestCode,
+    ) -> Result<WarmupHandle> {
+        if !self.started {
+            return Err(DhanError::InvalidArgument(
+                "manager not started — call start() first".into(),
+            ));
+        }
+        self.check_auth()?;
+
+        let assignments = self.assign_instruments(instruments, mode)?;
+        self.bootstrap_snapshots(&assignments, mode);
+        let mut batches = Vec::new();
+        let mut receivers = Vec::new();
+        let mut pending = HashSet::new();
+        let mut total_instruments = 0;
+        for (conn_idx, batch) in assignments {
+            let conn = &mut self.connections[conn_idx];
+            receivers.push(conn.parsed_tx.subscribe());
+            for inst in &batch {
+                conn.instruments
+                    .insert(InstrumentKey::from(inst), (inst.clone(), mode));
+                if let Some(id) = instrument_id(inst) {
+                    pending.insert(id);
+                }
+            }
+            total_instruments += batch.len();
+            for chunk in batch.chunks(100) {
+                batches.push((conn.id, conn.writer.clone(), chunk.to_vec()));
+            }
+        }
+
+        let (progress_tx, progress_rx) = watch::channel(WarmupProgress {
+            total_batches: batches.len(),
+            total_instruments,
+            // Instruments we cannot identify in packets count as acknowledged.
+            acknowledged: total_instruments - pending.len(),
+            ..Default::default()
+        });
+        let progress_tx = Arc::new(progress_tx);
+
+        let pace = Duration::from_secs(1) / self.config.warmup_messages_per_sec.max(1);
+        let sender_progress = progress_tx.clone();
+        tokio::spawn(async move {
+            for (i, (conn_id, writer, chunk)) in batches.into_iter().enumerate() {
+                if i > 0 {
+                    tokio::time::sleep(pace).await;
+                }
+                let req = FeedSubscribeRequest {
+                    RequestCode: mode as u8,
+                    InstrumentCount: chunk.len(),
+                    InstrumentList: chunk,
+                };
+                let sent = match serde_json::to_string(&req) {
+                    Ok(json) => match writer.lock().await.as_mut() {
+                        Some(w) => w.send(Message::Text(json.into())).await.is_ok(),
+                        None => false,
+                    },
+                    Err(_) => false,
+                };
+                if !sent {
+                    tracing::warn!(connection = %conn_id, "Warm-up subscribe message failed");
+                }
+                sender_progress.send_modify(|p| {
+                    if sent {
+                        p.batches_sent += 1;
+                    } else {
+                        p.failed_batches += 1;
+                    }
+                });
+            }
+        });
+
+        let ack_task = tokio::spawn(async move {
+            let mut tracker = AckTracker::new(pending);
+            let streams = receivers.into_iter().map(|rx| {
+                futures_util::stream::unfold(rx, |mut rx| async move {
+                    loop {
+                        match rx.recv().await {
+                            Ok(ev) => return Some((ev, rx)),
+                            Err(broadcast::error::RecvError::Lagged(_)) => {}
+                            Err(broadcast::error::RecvError::Closed) => return None,
+                        }
+                    }
+                })
+                .boxed()
+            });
+            let mut events = futures_util::stream::select_all(streams);
+            while !tracker.is_done() {
+                let Some(event) = events.next().await else {
+                    break;
+                };
+                if is_snapshot(&event) {
+                    continue;
+                }
+                if InstrumentId::from_header(event.header()).is_some_and(|id| tracker.ack(&id)) {
+                    progress_tx.send_modify(|p| p.acknowledged += 1);
+                }
+            }
+        });
+
+        tracing::info!(
+            instruments = total_instruments,
+            mode = ?mode,
+            "Feed warm-up started"
+        );
+        Ok(WarmupHandle::new(
+            progress_rx,
+            Duration::from_millis(self.config.warmup_timeout_ms),
+            ack_task,
+        ))
+    }
+
+    /// Subscribe every instrument `filter` matches, paced as in
+    /// [`Self::warm_subscribe`].
+    ///
+    /// ```no_run
+    /// use dhan_rs::instruments::{InstrumentMaster, next_weekly};
+    /// use dhan_rs::types::enums::{ExchangeSegment, FeedRequestCode};
+    /// use dhan_rs::ws::manager::DhanFeedManagerBuilder;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> dhan_rs::Result<()> {
+    /// # let master = InstrumentMaster::default();
+    /// let mut manager = DhanFeedManagerBuilder::new("client-id", "token").build();
+    /// manager.start().await?;
+    /// let warmup = manager.subscribe_filter(
+    ///     &master
+    ///         .filter()
+    ///         .segment(ExchangeSegment::NSE_FNO)
+    ///         .underlying("BANKNIFTY")
+    ///         .expiry(next_weekly()),
+    ///     FeedRequestCode::SubscribeQuote,
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subscribe_filter(
+        &mut self,
+        filter: &InstrumentFilter<'_>,
+        mode: FeedRequestCode,
+    ) -> Result<WarmupHandle> {
+        let instruments: Vec<_> = filter
+            .ids()
+            .iter()
+            .map(InstrumentId::to_feed_instrument)
+            .collect();
+        self.warm_subscribe(&instruments, mode)
+    }
+
     /// Unsubscribe instruments.
     ///
     /// Finds which connection each instrument lives on and sends the
@@ -555,6 +1275,54 @@ impl DhanFeedManager {
         Ok(())
     }
 
+    /// Unsubscribe contracts whose expiry day has passed (in IST) and, if
+    /// the expiry roll is set to, subscribe their successors.
+    ///
+    /// Fails if no [`ExpiryRoll`] is set.
+    pub async fn roll_expired(&mut self) -> Result<Vec<ContractRoll>> {
+        let today = to_ist(Utc::now()).date_naive();
+        self.roll_expired_on(today).await
+    }
+
+    /// [`Self::roll_expired`] as of the IST date `today`.
+    pub async fn roll_expired_on(&mut self, today: NaiveDate) -> Result<Vec<ContractRoll>> {
+        let Some(roll) = &self.expiry_roll else {
+            return Err(DhanError::InvalidArgument(
+                "no expiry roll configured — call with_expiry_roll() first".into(),
+            ));
+        };
+        let subscribed: HashMap<InstrumentId, (Instrument, FeedRequestCode)> = self
+            .connections
+            .iter()
+            .flat_map(|conn| conn.instruments.values())
+            .filter_map(|(inst, mode)| Some((instrument_id(inst)?, (inst.clone(), *mode))))
+            .collect();
+        let rolls = roll.plan(subscribed.iter().map(|(id, (_, mode))| (*id, *mode)), today);
+
+        let mut by_mode: HashMap<FeedRequestCode, (Vec<Instrument>, Vec<Instrument>)> =
+            HashMap::new();
+        for roll in &rolls {
+            let (expired, next) = by_mode.entry(roll.mode).or_default();
+            expired.push(subscribed[&roll.expired].0.clone());
+            next.extend(roll.next.map(|id| id.to_feed_instrument()));
+        }
+        for (mode, (expired, next)) in by_mode {
+            self.unsubscribe(&expired, unsubscribe_code(mode)).await?;
+            if !next.is_empty() {
+                self.subscribe(&next, mode).await?;
+            }
+        }
+        for roll in &rolls {
+            tracing::info!(
+                expired = %roll.expired,
+                expiry = %roll.expiry,
+                next = ?roll.next.map(|id| id.to_string()),
+                "Rolled expired contract"
+            );
+        }
+        Ok(rolls)
+    }
+
     /// Get a broadcast receiver for parsed [`MarketFeedEvent`]s from a
     /// specific connection.
     ///
@@ -580,6 +1348,116 @@ impl DhanFeedManager {
             .collect()
     }
 
+    /// One stream of the parsed events of **all** connections, tagged with
+    /// the connection they arrived on.
+    ///
+    /// Events of one connection keep their order; events of different
+    /// connections interleave as they arrive. A consumer that falls behind
+    /// skips the lost events (with a warning) instead of ending the stream,
+    /// which ends once every connection's channel is closed.
+    ///
+    /// ```no_run
+    /// use dhan_rs::ws::manager::DhanFeedManagerBuilder;
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn example() {
+    /// let manager = DhanFeedManagerBuilder::new("client_id", "access_token").build();
+    /// let mut events = manager.merged_stream();
+    /// while let Some((conn, event)) = events.next().await {
+    ///     println!("{conn}: {:?}", event.header().security_id);
+    /// }
+    /// # }
+    /// ```
+    pub fn merged_stream(
+        &self,
+    ) -> impl Stream<Item = (ConnectionId, MarketFeedEvent)> + Send + use<> {
+        let streams = self.get_all_parsed_channels().into_iter().map(|(id, rx)| {
+            futures_util::stream::unfold(rx, move |mut rx| async move {
+                loop {
+                    match rx.recv().await {
+                        Ok(ev) => return Some(((id, ev), rx)),
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            tracing::warn!(connection = %id, skipped = n, "Merged stream lagging behind feed");
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            })
+            .boxed()
+        });
+        futures_util::stream::select_all(streams)
+    }
+
+    /// The events of all connections conflated to at most
+    /// `updates_per_sec` per instrument and packet kind, keeping the latest
+    /// (see [`FeedStreamExt::conflate`]), for frontends that cannot render
+    /// every packet but must not fall behind either.
+    ///
+    /// ```no_run
+    /// use dhan_rs::ws::manager::DhanFeedManagerBuilder;
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn example() {
+    /// let manager = DhanFeedManagerBuilder::new("client_id", "access_token").build();
+    /// let mut updates = manager.ui_stream(10);
+    /// while let Some(event) = updates.next().await {
+    ///     println!("{event}");
+    /// }
+    /// # }
+    /// ```
+    pub fn ui_stream(
+        &self,
+        updates_per_sec: u32,
+    ) -> impl Stream<Item = MarketFeedEvent> + Send + use<> {
+        self.merged_stream()
+            .map(|(_, ev)| ev)
+            .conflate(updates_per_sec)
+    }
+
+    /// Like [`Self::get_parsed_channel`], but counting the events consumer
+    /// `name` loses by falling behind (see [`Self::consumer_stats`]).
+    pub fn get_metered_parsed_channel(
+        &self,
+        id: ConnectionId,
+        name: impl Into<String>,
+    ) -> Option<MeteredReceiver<MarketFeedEvent>> {
+        let rx = self.get_parsed_channel(id)?;
+        Some(self.lag.wrap(name, rx))
+    }
+
+    /// Like [`Self::get_raw_channel`], but counting the frames consumer
+    /// `name` loses by falling behind.
+    pub fn get_metered_raw_channel(
+        &self,
+        id: ConnectionId,
+        name: impl Into<String>,
+    ) -> Option<MeteredReceiver<Bytes>> {
+        let rx = self.get_raw_channel(id)?;
+        Some(self.lag.wrap(name, rx))
+    }
+
+    /// Delivered and lost counts of every metered consumer.
+    pub fn consumer_stats(&self) -> Vec<ConsumerStats> {
+        self.lag.stats()
+    }
+
+    /// Previous close of `instrument`, if its `PrevClose` packet has been
+    /// received on any connection.
+    pub fn prev_close(&self, instrument: &InstrumentId) -> Option<PrevClose> {
+        self.prev_closes.get(instrument)
+    }
+
+    /// The shared previous-close cache, e.g. for enriching events in a
+    /// consumer task.
+    pub fn prev_closes(&self) -> PrevCloseCache {
+        self.prev_closes.clone()
+    }
+
+    /// Wrap `event` with its change against the cached previous close.
+    pub fn enrich(&self, event: MarketFeedEvent) -> EnrichedEvent {
+        self.prev_closes.enrich(event)
+    }
+
     /// Get a broadcast receiver for raw binary frames from a specific
     /// connection.
     ///
@@ -611,6 +1489,7 @@ impl DhanFeedManager {
                 is_alive: c.task.as_ref().is_some_and(|t| !t.is_finished()),
                 instrument_count: c.instruments.len(),
                 reconnect_count: c.reconnect_count,
+                last_message_at: Some(c.last_message.load(Ordering::Relaxed)).filter(|ms| *ms > 0),
             })
             .collect();
 
@@ -621,7 +1500,54 @@ impl DhanFeedManager {
             connections,
             total_instruments,
             alive_connections,
+            consumers: self.lag.stats(),
+        }
+    }
+
+    /// Used and free instrument slots per connection and overall.
+    pub fn capacity(&self) -> FeedCapacity {
+        let loads: Vec<_> = self
+            .connections
+            .iter()
+            .map(|c| c.instruments.len())
+            .collect();
+        FeedCapacity::from_loads(
+            self.connections.iter().map(|c| c.id),
+            &loads,
+            self.config.max_instruments_per_connection,
+        )
+    }
+
+    /// The slots [`Self::capacity`] would report after subscribing
+    /// `instruments`, placed as [`Self::subscribe`] would place them.
+    /// Instruments already subscribed take no new slot; those that would
+    /// not fit are counted in [`FeedCapacity::unplaced`].
+    pub fn capacity_after(&self, instruments: &[Instrument]) -> FeedCapacity {
+        let max_per = self.config.max_instruments_per_connection;
+        let mut loads: Vec<_> = self
+            .connections
+            .iter()
+            .map(|c| c.instruments.len())
+            .collect();
+        let mut seen: HashSet<InstrumentKey> = self
+            .connections
+            .iter()
+            .flat_map(|c| c.instruments.keys().cloned())
+            .collect();
+        let mut unplaced = 0;
+        for inst in instruments {
+            if !seen.insert(InstrumentKey::from(inst)) {
+                continue;
+            }
+            match loads.iter_mut().min() {
+                Some(load) if *load < max_per => *load += 1,
+                _ => unplaced += 1,
+            }
         }
+        let mut capacity =
+            FeedCapacity::from_loads(self.connections.iter().map(|c| c.id), &loads, max_per);
+        capacity.unplaced = unplaced;
+        capacity
     }
 
     /// Shut down all managed connections gracefully.
@@ -640,6 +1566,7 @@ impl DhanFeedManager {
             }
             conn.instruments.clear();
         }
+        self.slot_guards.clear();
         self.started = false;
 
         tracing::info!("DhanFeedManager shut down");
@@ -661,6 +1588,31 @@ impl DhanFeedManager {
     // -----------------------------------------------------------------------
 
     /// Assign instruments to connections using round-robin load balancing.
+    /// Start a snapshot bootstrap for `assignments` if it is enabled and
+    /// `mode` is Quote or Full.
+    fn bootstrap_snapshots(&self, assignments: &[(usize, Vec<Instrument>)], mode: FeedRequestCode) {
+        let full = match mode {
+            FeedRequestCode::SubscribeQuote => false,
+            FeedRequestCode::SubscribeFull => true,
+            _ => return,
+        };
+        let Some(client) = &self.snapshot_client else {
+            return;
+        };
+        let targets: Vec<_> = assignments
+            .iter()
+            .flat_map(|(conn_idx, batch)| {
+                let tx = &self.connections[*conn_idx].parsed_tx;
+                batch
+                    .iter()
+                    .filter_map(|inst| Some((instrument_id(inst)?, tx.clone())))
+            })
+            .collect();
+        if !targets.is_empty() {
+            spawn_bootstrap(client.clone(), targets, full);
+        }
+    }
+
     fn assign_instruments(
         &self,
         instruments: &[Instrument],
@@ -718,19 +1670,25 @@ impl DhanFeedManager {
 
     /// Spawn (or re-spawn) a WebSocket connection task for the given
     /// connection slot.
+    #[allow(clippy::too_many_arguments)]
     async fn spawn_connection(
         client_id: &str,
-        access_token: &str,
+        access_token: &SharedToken,
         conn: &mut ManagedConnection,
         auto_reconnect: bool,
         reconnect_delay_ms: u64,
         enable_raw: bool,
+        idle_timeout: Option<Duration>,
+        prev_closes: PrevCloseCache,
+        events: Option<EventBus>,
+        auth_failure: watch::Sender<Option<FeedAuthFailure>>,
+        dns_pins: Option<DnsPins>,
     ) -> Result<()> {
-        let url = format!(
-            "{WS_MARKET_FEED_URL}?version=2&token={access_token}&clientId={client_id}&authType=2"
-        );
+        let url = feed_url(client_id, access_token);
 
-        let (ws, _resp) = connect_async(&url).await?;
+        let (ws, _resp) = connect_ws(&url, dns_pins.as_ref())
+            .await
+            .map_err(connect_error)?;
         let (write, read) = ws.split();
         *conn.writer.lock().await = Some(write);
 
@@ -738,13 +1696,21 @@ impl DhanFeedManager {
         let raw_tx = conn.raw_tx.clone();
         let conn_id = conn.id;
         let writer_arc = conn.writer.clone();
+        let last_message = conn.last_message.clone();
 
         // Collect instruments to re-subscribe on reconnect
         let existing_subs: Vec<(Instrument, FeedRequestCode)> =
             conn.instruments.values().cloned().collect();
 
         let client_id_owned = client_id.to_owned();
-        let access_token_owned = access_token.to_owned();
+        let access_token_owned = access_token.clone();
+
+        publish_lifecycle(
+            &events,
+            FeedLifecycle::Connected {
+                connection: conn_id,
+            },
+        );
 
         let task = tokio::spawn(async move {
             Self::connection_loop(
@@ -756,9 +1722,15 @@ impl DhanFeedManager {
                 auto_reconnect,
                 reconnect_delay_ms,
                 enable_raw,
+                idle_timeout,
+                last_message,
                 &client_id_owned,
                 &access_token_owned,
                 existing_subs,
+                prev_closes,
+                events,
+                auth_failure,
+                dns_pins,
             )
             .await;
         });
@@ -783,9 +1755,15 @@ impl DhanFeedManager {
         auto_reconnect: bool,
         reconnect_delay_ms: u64,
         enable_raw: bool,
+        idle_timeout: Option<Duration>,
+        last_message: Arc<AtomicI64>,
         client_id: &str,
-        access_token: &str,
+        access_token: &SharedToken,
         existing_subs: Vec<(Instrument, FeedRequestCode)>,
+        prev_closes: PrevCloseCache,
+        events: Option<EventBus>,
+        auth_failure: watch::Sender<Option<FeedAuthFailure>>,
+        dns_pins: Option<DnsPins>,
     ) {
         // Re-subscribe existing instruments after initial connect or reconnect
         if !existing_subs.is_empty() {
@@ -798,8 +1776,56 @@ impl DhanFeedManager {
             }
         }
 
-        loop {
-            match read.next().await {
+        let mut auth_code = None;
+        let mut over_limit = false;
+        let mut last_frame = tokio::time::Instant::now();
+        let mut pinged = false;
+        let reason = loop {
+            let next = match idle_timeout {
+                None => read.next().await,
+                Some(timeout) => {
+                    let window = if pinged { timeout } else { timeout / 2 };
+                    match tokio::time::timeout_at(last_frame + window, read.next()).await {
+                        Ok(next) => next,
+                        Err(_) if pinged => {
+                            let idle = last_frame.elapsed();
+                            tracing::warn!(
+                                connection = %conn_id,
+                                ?idle,
+                                "WebSocket is stale"
+                            );
+                            publish_lifecycle(
+                                &events,
+                                FeedLifecycle::Stale {
+                                    connection: conn_id,
+                                    idle,
+                                },
+                            );
+                            break DhanError::StaleConnection(idle).to_string();
+                        }
+                        Err(_) => {
+                            // Half the window elapsed in silence: ask for a pong.
+                            if let Some(w) = writer.lock().await.as_mut() {
+                                if let Err(e) = w.send(Message::Ping(Vec::new().into())).await {
+                                    tracing::warn!(
+                                        connection = %conn_id,
+                                        error = %e,
+                                        "Failed to send ping"
+                                    );
+                                }
+                            }
+                            pinged = true;
+                            continue;
+                        }
+                    }
+                }
+            };
+            if next.as_ref().is_some_and(|r| r.is_ok()) {
+                last_frame = tokio::time::Instant::now();
+                pinged = false;
+                last_message.store(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed);
+            }
+            match next {
                 Some(Ok(msg)) => match msg {
                     Message::Binary(data) => {
                         // Broadcast raw frame first (if enabled)
@@ -812,7 +1838,21 @@ impl DhanFeedManager {
                         // Parse and broadcast
                         match parse_packet(&data) {
                             Ok(event) => {
+                                if let MarketFeedEvent::Disconnect { reason_code, .. } = event {
+                                    if disconnect_auth_error(reason_code).is_some() {
+                                        auth_code = Some(reason_code);
+                                    }
+                                    over_limit =
+                                        reason_code == disconnect_code::TOO_MANY_CONNECTIONS;
+                                }
+                                prev_closes.observe(&event);
                                 let _ = parsed_tx.send(event);
+                                if let Some(code) = auth_code {
+                                    break format!("credentials rejected (reason {code})");
+                                }
+                                if over_limit {
+                                    break "too many connections (reason 805)".to_owned();
+                                }
                             }
                             Err(e) => {
                                 tracing::warn!(
@@ -829,7 +1869,7 @@ impl DhanFeedManager {
                             connection = %conn_id,
                             "WebSocket closed by server"
                         );
-                        break;
+                        break "closed by server".to_owned();
                     }
                     Message::Text(text) => {
                         tracing::debug!(
@@ -845,16 +1885,44 @@ impl DhanFeedManager {
                         error = %e,
                         "WebSocket error"
                     );
-                    break;
+                    break e.to_string();
                 }
                 None => {
                     tracing::info!(
                         connection = %conn_id,
                         "WebSocket stream ended"
                     );
-                    break;
+                    break "stream ended".to_owned();
                 }
             }
+        };
+        publish_lifecycle(
+            &events,
+            FeedLifecycle::Disconnected {
+                connection: conn_id,
+                reason,
+            },
+        );
+
+        if let Some(code) = auth_code {
+            Self::fail_auth(conn_id, Some(code), &writer, &events, &auth_failure).await;
+            return;
+        }
+        if over_limit {
+            // Reconnecting would only knock off another connection of the
+            // account, possibly one of another process.
+            tracing::error!(
+                connection = %conn_id,
+                "Account connection limit reached; not reconnecting"
+            );
+            *writer.lock().await = None;
+            publish_lifecycle(
+                &events,
+                FeedLifecycle::ConnectionLimit {
+                    connection: conn_id,
+                },
+            );
+            return;
         }
 
         // Reconnect if enabled
@@ -866,11 +1934,13 @@ impl DhanFeedManager {
             );
             tokio::time::sleep(Duration::from_millis(reconnect_delay_ms)).await;
 
-            let url = format!(
-                "{WS_MARKET_FEED_URL}?version=2&token={access_token}&clientId={client_id}&authType=2"
-            );
+            // Read the token again: it may have been renewed since.
+            let url = feed_url(client_id, access_token);
 
-            match connect_async(&url).await {
+            match connect_ws(&url, dns_pins.as_ref())
+                .await
+                .map_err(connect_error)
+            {
                 Ok((ws, _)) => {
                     let (write, new_read) = ws.split();
                     *writer.lock().await = Some(write);
@@ -879,6 +1949,12 @@ impl DhanFeedManager {
                         connection = %conn_id,
                         "Reconnected successfully"
                     );
+                    publish_lifecycle(
+                        &events,
+                        FeedLifecycle::Connected {
+                            connection: conn_id,
+                        },
+                    );
 
                     // Recurse into connection_loop for the new read half
                     Box::pin(Self::connection_loop(
@@ -890,9 +1966,15 @@ impl DhanFeedManager {
                         auto_reconnect,
                         reconnect_delay_ms,
                         enable_raw,
+                        idle_timeout,
+                        last_message,
                         client_id,
                         access_token,
                         existing_subs,
+                        prev_closes,
+                        events,
+                        auth_failure,
+                        dns_pins,
                     ))
                     .await;
                 }
@@ -902,11 +1984,44 @@ impl DhanFeedManager {
                         error = %e,
                         "Reconnection failed"
                     );
+                    if matches!(e, DhanError::TokenExpired(_)) {
+                        Self::fail_auth(conn_id, None, &writer, &events, &auth_failure).await;
+                        return;
+                    }
+                    publish_lifecycle(
+                        &events,
+                        FeedLifecycle::ReconnectFailed {
+                            connection: conn_id,
+                            error: e.to_string(),
+                        },
+                    );
                 }
             }
         }
     }
 
+    /// Stop a connection whose credentials were rejected and record why.
+    async fn fail_auth(
+        conn_id: ConnectionId,
+        reason_code: Option<i16>,
+        writer: &Arc<Mutex<Option<WriterHalf>>>,
+        events: &Option<EventBus>,
+        auth_failure: &watch::Sender<Option<FeedAuthFailure>>,
+    ) {
+        let failure = FeedAuthFailure {
+            connection: conn_id,
+            reason_code,
+        };
+        tracing::error!(
+            connection = %conn_id,
+            reason_code,
+            "Feed credentials rejected; not reconnecting"
+        );
+        *writer.lock().await = None;
+        auth_failure.send_replace(Some(failure));
+        publish_lifecycle(events, FeedLifecycle::AuthFailed(failure));
+    }
+
     /// Re-subscribe a set of instruments on a connection writer.
     async fn resubscribe(
         writer: &Arc<Mutex<Option<WriterHalf>>>,
@@ -939,6 +2054,27 @@ impl DhanFeedManager {
     }
 }
 
+/// The [`InstrumentId`] of a subscription entry, if its segment and
+/// security ID parse.
+fn instrument_id(inst: &Instrument) -> Option<InstrumentId> {
+    Some(InstrumentId::new(
+        inst.ExchangeSegment.parse().ok()?,
+        inst.SecurityId.trim().parse().ok()?,
+    ))
+}
+
+/// The feed URL with the token `token` holds now.
+fn feed_url(client_id: &str, token: &SharedToken) -> String {
+    let access_token = token.get();
+    format!("{WS_MARKET_FEED_URL}?version=2&token={access_token}&clientId={client_id}&authType=2")
+}
+
+fn publish_lifecycle(events: &Option<EventBus>, event: FeedLifecycle) {
+    if let Some(bus) = events {
+        bus.publish(SessionEvent::Feed(event));
+    }
+}
+
 impl Drop for DhanFeedManager {
     fn drop(&mut self) {
         for conn in &mut self.connections {
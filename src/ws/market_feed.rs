@@ -31,19 +31,26 @@
 //! # }
 //! ```
 
+use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, Stream, StreamExt};
 use serde::Serialize;
 use tokio::net::TcpStream;
+use tokio::time::{Instant, Sleep};
 use tokio_tungstenite::tungstenite::Message;
-use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 
 use crate::constants::WS_MARKET_FEED_URL;
+use crate::dns::{DnsPins, connect_ws};
 use crate::error::{DhanError, Result};
+use crate::types::builders::PlaceOrderRequestBuilder;
+use crate::types::depth::DepthBook;
 use crate::types::enums::{ExchangeSegment, FeedRequestCode, FeedResponseCode};
+use crate::types::instrument::InstrumentId;
 
 // ---------------------------------------------------------------------------
 // Subscribe / Unsubscribe request types
@@ -91,7 +98,7 @@ struct FeedDisconnectRequest {
 // ---------------------------------------------------------------------------
 
 /// Header parsed from the first 8 bytes of every binary market feed packet.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct PacketHeader {
     /// The response code identifying the packet type.
     pub response_code: FeedResponseCode,
@@ -110,7 +117,11 @@ pub struct PacketHeader {
 // ---------------------------------------------------------------------------
 
 /// A parsed market feed event.
-#[derive(Debug, Clone)]
+///
+/// Serializes as a JSON object tagged with `"type"` (the variant name), so
+/// events can be written to JSON lines or forwarded to other tools as-is.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
 pub enum MarketFeedEvent {
     /// Ticker data (LTP + LTT). Response code 2.
     Ticker {
@@ -218,13 +229,236 @@ pub enum MarketFeedEvent {
     /// Server-initiated disconnect. Response code 50.
     Disconnect {
         header: PacketHeader,
-        /// Disconnect reason code (e.g. 805 = too many connections).
+        /// Disconnect reason code (see [`disconnect_code`]).
         reason_code: i16,
     },
 }
 
+impl MarketFeedEvent {
+    /// The packet header common to every event.
+    pub fn header(&self) -> &PacketHeader {
+        match self {
+            MarketFeedEvent::Ticker { header, .. }
+            | MarketFeedEvent::PrevClose { header, .. }
+            | MarketFeedEvent::Quote { header, .. }
+            | MarketFeedEvent::OI { header, .. }
+            | MarketFeedEvent::Full { header, .. }
+            | MarketFeedEvent::MarketStatus { header, .. }
+            | MarketFeedEvent::Index { header, .. }
+            | MarketFeedEvent::Disconnect { header, .. } => header,
+        }
+    }
+
+    /// For a Disconnect packet whose reason reconnecting cannot fix, the
+    /// matching error (see [`disconnect_auth_error`]).
+    pub fn auth_error(&self) -> Option<DhanError> {
+        match *self {
+            MarketFeedEvent::Disconnect { reason_code, .. } => disconnect_auth_error(reason_code),
+            _ => None,
+        }
+    }
+
+    /// An order builder for the event's instrument (see
+    /// [`InstrumentId::order_seed`]).
+    ///
+    /// Returns `None` for market status and disconnect packets, which carry
+    /// no instrument, and for indices and unknown segments, which cannot be
+    /// traded.
+    pub fn to_order_seed(&self) -> Option<PlaceOrderRequestBuilder> {
+        if matches!(
+            self,
+            MarketFeedEvent::MarketStatus { .. } | MarketFeedEvent::Disconnect { .. }
+        ) {
+            return None;
+        }
+        InstrumentId::from_header(self.header())
+            .filter(|id| id.segment != ExchangeSegment::IDX_I)
+            .map(|id| id.order_seed())
+    }
+
+    /// Normalize a Ticker, Quote or Full packet into a [`Tick`].
+    ///
+    /// Returns `None` for other events and for packets with an unknown
+    /// exchange segment.
+    pub fn to_tick(&self) -> Option<Tick> {
+        let instrument = InstrumentId::from_header(self.header())?;
+        let tick = match *self {
+            MarketFeedEvent::Ticker { ltp, ltt, .. } => Tick {
+                instrument,
+                ltp: f64::from(ltp),
+                ltt: i64::from(ltt),
+                ..Tick::default_for(instrument)
+            },
+            MarketFeedEvent::Quote {
+                ltp,
+                last_qty,
+                ltt,
+                volume,
+                open,
+                high,
+                low,
+                close,
+                ..
+            } => Tick {
+                instrument,
+                ltp: f64::from(ltp),
+                ltt: i64::from(ltt),
+                last_qty: Some(i64::from(last_qty)),
+                volume: Some(i64::from(volume)),
+                open: Some(f64::from(open)),
+                high: Some(f64::from(high)),
+                low: Some(f64::from(low)),
+                close: Some(f64::from(close)),
+                oi: None,
+            },
+            MarketFeedEvent::Full {
+                ltp,
+                last_qty,
+                ltt,
+                volume,
+                oi,
+                open,
+                high,
+                low,
+                close,
+                ..
+            } => Tick {
+                instrument,
+                ltp: f64::from(ltp),
+                ltt: i64::from(ltt),
+                last_qty: Some(i64::from(last_qty)),
+                volume: Some(i64::from(volume)),
+                open: Some(f64::from(open)),
+                high: Some(f64::from(high)),
+                low: Some(f64::from(low)),
+                close: Some(f64::from(close)),
+                oi: Some(i64::from(oi)),
+            },
+            _ => return None,
+        };
+        Some(tick)
+    }
+
+    /// Market depth of a Full packet as a [`DepthBook`]; `None` for other events.
+    pub fn depth_book(&self) -> Option<DepthBook> {
+        match self {
+            MarketFeedEvent::Full { depth, .. } => Some(DepthBook::from_feed_depth(depth)),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PacketHeader {
+    /// `SEGMENT:SECURITY_ID`, with the raw segment byte if it is unknown.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.exchange_segment {
+            Some(segment) => write!(f, "{segment}:{}", self.security_id),
+            None => write!(f, "{}:{}", self.exchange_segment_raw, self.security_id),
+        }
+    }
+}
+
+impl std::fmt::Display for MarketFeedEvent {
+    /// A compact one-line summary, e.g. `Ticker NSE_EQ:1333 ltp=1500.5 ltt=1726041600`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarketFeedEvent::Ticker { header, ltp, ltt } => {
+                write!(f, "Ticker {header} ltp={ltp} ltt={ltt}")
+            }
+            MarketFeedEvent::PrevClose {
+                header,
+                prev_close,
+                prev_oi,
+            } => write!(f, "PrevClose {header} close={prev_close} oi={prev_oi}"),
+            MarketFeedEvent::Quote {
+                header,
+                ltp,
+                ltt,
+                volume,
+                ..
+            } => write!(f, "Quote {header} ltp={ltp} ltt={ltt} vol={volume}"),
+            MarketFeedEvent::OI { header, oi } => write!(f, "OI {header} oi={oi}"),
+            MarketFeedEvent::Full {
+                header,
+                ltp,
+                ltt,
+                volume,
+                oi,
+                depth,
+                ..
+            } => write!(
+                f,
+                "Full {header} ltp={ltp} ltt={ltt} vol={volume} oi={oi} bid={} ask={}",
+                depth[0].bid_price, depth[0].ask_price
+            ),
+            MarketFeedEvent::MarketStatus { header, raw } => {
+                write!(f, "MarketStatus {header} ({} bytes)", raw.len())
+            }
+            MarketFeedEvent::Index { header, raw } => {
+                write!(f, "Index {header} ({} bytes)", raw.len())
+            }
+            MarketFeedEvent::Disconnect {
+                header,
+                reason_code,
+            } => write!(f, "Disconnect {header} reason={reason_code}"),
+        }
+    }
+}
+
+/// A trade-price update normalized from Ticker, Quote or Full packets.
+///
+/// Fields only present in richer packets are `None` for Ticker packets.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Tick {
+    /// The instrument the tick belongs to.
+    pub instrument: InstrumentId,
+    /// Last traded price.
+    pub ltp: f64,
+    /// Last trade time (epoch seconds).
+    pub ltt: i64,
+    /// Last traded quantity.
+    pub last_qty: Option<i64>,
+    /// Cumulative traded volume for the day.
+    pub volume: Option<i64>,
+    /// Day open price.
+    pub open: Option<f64>,
+    /// Day high price.
+    pub high: Option<f64>,
+    /// Day low price.
+    pub low: Option<f64>,
+    /// Day close price.
+    pub close: Option<f64>,
+    /// Open interest.
+    pub oi: Option<i64>,
+}
+
+impl Tick {
+    /// Last trade time as UTC (see [`crate::time::from_feed_epoch`]).
+    pub fn trade_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        (self.ltt > 0)
+            .then(|| crate::time::from_feed_epoch(self.ltt))
+            .flatten()
+    }
+
+    /// A tick carrying only the instrument, with zero price and time.
+    pub(crate) fn default_for(instrument: InstrumentId) -> Self {
+        Self {
+            instrument,
+            ltp: 0.0,
+            ltt: 0,
+            last_qty: None,
+            volume: None,
+            open: None,
+            high: None,
+            low: None,
+            close: None,
+            oi: None,
+        }
+    }
+}
+
 /// A single level of market depth (bid or ask side) from a Full packet.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct DepthLevel {
     /// Bid (buy) quantity.
     pub bid_qty: i32,
@@ -505,6 +739,56 @@ pub fn parse_packet(data: &[u8]) -> Result<MarketFeedEvent> {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Disconnect reasons
+// ---------------------------------------------------------------------------
+
+/// Reason codes carried by [`MarketFeedEvent::Disconnect`].
+pub mod disconnect_code {
+    /// Too many requests or connections.
+    pub const TOO_MANY_CONNECTIONS: i16 = 805;
+    /// Data APIs are not subscribed on the account.
+    pub const DATA_NOT_SUBSCRIBED: i16 = 806;
+    /// The access token has expired.
+    pub const TOKEN_EXPIRED: i16 = 807;
+    /// Authentication failed: client ID or access token invalid.
+    pub const AUTHENTICATION_FAILED: i16 = 808;
+    /// The access token is invalid.
+    pub const TOKEN_INVALID: i16 = 809;
+    /// The client ID is invalid.
+    pub const CLIENT_ID_INVALID: i16 = 810;
+}
+
+/// The error for a Disconnect reason code that reconnecting cannot fix,
+/// or `None` for codes worth retrying.
+///
+/// Token problems map to [`DhanError::TokenExpired`]; an invalid client ID
+/// or missing data subscription to [`DhanError::WsAuthFailed`].
+pub fn disconnect_auth_error(reason_code: i16) -> Option<DhanError> {
+    use disconnect_code::*;
+    match reason_code {
+        TOKEN_EXPIRED | AUTHENTICATION_FAILED | TOKEN_INVALID => Some(DhanError::TokenExpired(
+            format!("market feed disconnected with reason {reason_code}"),
+        )),
+        DATA_NOT_SUBSCRIBED | CLIENT_ID_INVALID => Some(DhanError::WsAuthFailed(format!(
+            "market feed disconnected with reason {reason_code}"
+        ))),
+        _ => None,
+    }
+}
+
+/// Convert a feed connect error, turning a 401/403 handshake response into
+/// [`DhanError::TokenExpired`].
+pub(crate) fn connect_error(err: tokio_tungstenite::tungstenite::Error) -> DhanError {
+    use tokio_tungstenite::tungstenite::Error as WsError;
+    match &err {
+        WsError::Http(resp) if matches!(resp.status().as_u16(), 401 | 403) => {
+            DhanError::TokenExpired(format!("market feed handshake returned {}", resp.status()))
+        }
+        _ => err.into(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Stream wrapper
 // ---------------------------------------------------------------------------
@@ -518,26 +802,130 @@ type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 ///
 /// Subscribe to instruments using [`subscribe()`](Self::subscribe) with the
 /// desired [`FeedRequestCode`] mode after connecting.
+///
+/// If the server disconnects for an authentication reason (see
+/// [`disconnect_auth_error`]), the stream yields that error and ends.
+/// With [`with_idle_timeout()`](Self::with_idle_timeout), a silent
+/// connection ends the same way with [`DhanError::StaleConnection`].
 pub struct MarketFeedStream {
     read: SplitStream<WsStream>,
     write: SplitSink<WsStream, Message>,
+    finished: bool,
+    last_message_at: Instant,
+    watchdog: Option<Watchdog>,
+}
+
+/// Idle-detection state for a [`MarketFeedStream`].
+struct Watchdog {
+    /// Maximum silence before the connection is considered dead.
+    timeout: Duration,
+    /// Fires at the next check point (ping at half-window, stale at full).
+    sleep: Pin<Box<Sleep>>,
+    /// Whether a ping has been sent since the last received frame.
+    pinged: bool,
 }
 
 impl MarketFeedStream {
     /// Connect to the market feed WebSocket.
     ///
     /// Authentication is done via query parameters on the WebSocket URL.
+    /// A rejected token fails with [`DhanError::TokenExpired`].
     pub async fn connect(client_id: &str, access_token: &str) -> Result<Self> {
         let url = format!(
             "{WS_MARKET_FEED_URL}?version=2&token={access_token}&clientId={client_id}&authType=2"
         );
 
-        let (ws, _resp) = connect_async(&url).await?;
+        Self::connect_via(&url, None).await
+    }
+
+    /// Like [`Self::connect`], connecting to the feed host's addresses in
+    /// `pins` (see [`crate::dns`]).
+    pub async fn connect_pinned(
+        client_id: &str,
+        access_token: &str,
+        pins: &DnsPins,
+    ) -> Result<Self> {
+        let url = format!(
+            "{WS_MARKET_FEED_URL}?version=2&token={access_token}&clientId={client_id}&authType=2"
+        );
+        Self::connect_via(&url, Some(pins)).await
+    }
+
+    async fn connect_via(url: &str, pins: Option<&DnsPins>) -> Result<Self> {
+        let (ws, _resp) = connect_ws(url, pins).await.map_err(connect_error)?;
         let (write, read) = ws.split();
 
         tracing::info!("Connected to market-feed WebSocket");
 
-        Ok(Self { read, write })
+        Ok(Self {
+            read,
+            write,
+            finished: false,
+            last_message_at: Instant::now(),
+            watchdog: None,
+        })
+    }
+
+    /// Enable the idle watchdog.
+    ///
+    /// If no frame (packet, ping or pong) arrives for half of `timeout`, a
+    /// ping is sent to provoke a pong. If the socket stays silent for the
+    /// full `timeout`, the stream yields [`DhanError::StaleConnection`] and
+    /// then ends. The server drops connections that miss its pongs for
+    /// [`PONG_TIMEOUT_SECS`](crate::constants::rate_limits::websocket::PONG_TIMEOUT_SECS),
+    /// which is a sensible upper bound.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.watchdog = Some(Watchdog {
+            timeout,
+            sleep: Box::pin(tokio::time::sleep(timeout / 2)),
+            pinged: false,
+        });
+        self
+    }
+
+    /// When the last frame of any kind was received (or the stream was
+    /// connected, if nothing has arrived yet).
+    pub fn last_message_at(&self) -> Instant {
+        self.last_message_at
+    }
+
+    /// How long the connection has been silent.
+    pub fn idle_for(&self) -> Duration {
+        self.last_message_at.elapsed()
+    }
+
+    /// Record that a frame was just received and re-arm the watchdog.
+    fn touch(&mut self) {
+        self.last_message_at = Instant::now();
+        if let Some(w) = self.watchdog.as_mut() {
+            w.pinged = false;
+            w.sleep.as_mut().reset(self.last_message_at + w.timeout / 2);
+        }
+    }
+
+    /// Drive the watchdog. Returns `Some(idle)` once the connection is stale.
+    fn poll_watchdog(&mut self, cx: &mut Context<'_>) -> Option<Duration> {
+        let w = self.watchdog.as_mut()?;
+        loop {
+            if w.sleep.as_mut().poll(cx).is_pending() {
+                return None;
+            }
+            if w.pinged {
+                return Some(self.last_message_at.elapsed());
+            }
+            // Half the window elapsed in silence: ask the server for a pong.
+            if let Poll::Ready(Ok(())) = self.write.poll_ready_unpin(cx) {
+                if let Err(e) = self
+                    .write
+                    .start_send_unpin(Message::Ping(Vec::new().into()))
+                {
+                    tracing::warn!("Failed to send market-feed ping: {e}");
+                }
+                let _ = self.write.poll_flush_unpin(cx);
+            }
+            w.pinged = true;
+            w.sleep.as_mut().reset(self.last_message_at + w.timeout);
+        }
     }
 
     /// Subscribe to instruments in the given data mode.
@@ -609,12 +997,23 @@ impl Stream for MarketFeedStream {
     type Item = Result<MarketFeedEvent>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
         loop {
             match self.read.poll_next_unpin(cx) {
                 Poll::Ready(Some(Ok(msg))) => {
+                    self.touch();
                     match msg {
                         Message::Binary(data) => match parse_packet(&data) {
-                            Ok(event) => return Poll::Ready(Some(Ok(event))),
+                            Ok(event) => {
+                                if let Some(err) = event.auth_error() {
+                                    tracing::error!(error = %err, "Market feed rejected credentials");
+                                    self.finished = true;
+                                    return Poll::Ready(Some(Err(err)));
+                                }
+                                return Poll::Ready(Some(Ok(event)));
+                            }
                             Err(e) => {
                                 tracing::warn!("Failed to parse market feed packet: {e}");
                                 return Poll::Ready(Some(Err(e)));
@@ -639,7 +1038,14 @@ impl Stream for MarketFeedStream {
                     return Poll::Ready(Some(Err(DhanError::WebSocket(Box::new(e)))));
                 }
                 Poll::Ready(None) => return Poll::Ready(None),
-                Poll::Pending => return Poll::Pending,
+                Poll::Pending => {
+                    if let Some(idle) = self.poll_watchdog(cx) {
+                        tracing::warn!(?idle, "Market-feed WebSocket is stale");
+                        self.finished = true;
+                        return Poll::Ready(Some(Err(DhanError::StaleConnection(idle))));
+                    }
+                    return Poll::Pending;
+                }
             }
         }
     }
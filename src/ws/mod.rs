@@ -20,6 +20,56 @@
 //! status changes as **JSON messages**. Supports both individual and partner
 //! authentication modes.
 //!
+//! ## [`encode`] — Event Encoders
+//!
+//! Fixed-layout, JSON and bincode encodings of feed events for
+//! redistribution over shared memory or multicast.
+//!
+//! ## [`expiry`] — Expired Contract Roll
+//!
+//! Unsubscribes futures and options after their expiry day and optionally
+//! subscribes the next contract of the series.
+//!
+//! ## [`filter`] — Feed Stream Filters
+//!
+//! Combinators selecting instruments, segments and packet kinds from feed
+//! event streams, and throttling them per instrument.
+//!
+//! ## [`lag`] — Consumer Lag Telemetry
+//!
+//! Broadcast receivers that count the events a slow consumer loses.
+//!
+//! ## [`quality`] — Feed Quality
+//!
+//! Flags stale instruments, implausible price jumps and time/volume
+//! regressions in the market feed.
+//!
+//! ## [`reconnect`] — Reconnecting Market Feed
+//!
+//! A single market feed connection that reconnects with backoff,
+//! re-subscribes its instruments and reports each gap.
+//!
+//! ## `shm` — Shared-Memory Tick Ring
+//!
+//! Publishes normalized ticks into a memory-mapped ring buffer with a
+//! documented layout, for co-located readers in any language (feature
+//! `shm`).
+//!
+//! ## [`slots`] — Connection Slots
+//!
+//! Loopback-port or lock-file slots keeping processes that share an
+//! account within its five feed connections.
+//!
+//! ## [`snapshot`] — Snapshot Bootstrap
+//!
+//! Synthetic Quote/Full events built from REST quotes, emitted on new
+//! subscriptions so consumers have state before the first live packet.
+//!
+//! ## [`warmup`] — Paced Bulk Subscription
+//!
+//! Progress and completion of large subscriptions sent by
+//! [`manager::DhanFeedManager::warm_subscribe`].
+//!
 //! ## Usage
 //!
 //! Both streams implement [`futures_util::Stream`] so you can use them with
@@ -31,6 +81,17 @@
 //! - Up to 5,000 instruments per connection
 //! - Up to 100 instruments per subscribe/unsubscribe message
 
+pub mod encode;
+pub mod expiry;
+pub mod filter;
+pub mod lag;
 pub mod manager;
 pub mod market_feed;
 pub mod order_update;
+pub mod quality;
+pub mod reconnect;
+#[cfg(feature = "shm")]
+pub mod shm;
+pub mod slots;
+pub mod snapshot;
+pub mod warmup;
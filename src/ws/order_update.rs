@@ -24,18 +24,30 @@
 //! # }
 //! ```
 
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::{BufWriter, Write};
+use std::path::Path;
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
+use chrono::{NaiveDate, NaiveDateTime};
 use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::net::TcpStream;
+use tokio::time::{Instant, Sleep};
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
 
 use crate::constants::WS_ORDER_UPDATE_URL;
 use crate::error::{DhanError, Result};
+use crate::types::enums::{
+    ExchangeSegment, OrderStatus, OrderType, ProductType, TransactionType, Validity,
+};
+use crate::types::instrument::InstrumentId;
 
 // ---------------------------------------------------------------------------
 // Auth messages
@@ -92,11 +104,26 @@ pub struct OrderUpdateMessage {
     pub Data: OrderUpdateData,
 }
 
-/// Detailed order update data received via WebSocket.
+impl OrderUpdateMessage {
+    /// Convert the wire payload into a normalized [`OrderUpdate`].
+    pub fn into_update(self) -> OrderUpdate {
+        OrderUpdate::from(self.Data)
+    }
+}
+
+/// Detailed order update data received via WebSocket — the **wire-level**
+/// representation.
 ///
 /// Field names are PascalCase matching the wire format. Abbreviated product /
 /// transaction / order-type codes are used (e.g. `"C"` for CNC, `"B"` for Buy,
-/// `"LMT"` for Limit).
+/// `"LMT"` for Limit), and several fields arrive in both PascalCase and
+/// camelCase variants (`Series` / `series`, `RefLtp` / `refLtp`, …).
+///
+/// Application code should usually convert this into the normalized
+/// [`OrderUpdate`] (via [`Self::to_typed`], `From` or
+/// [`OrderUpdateMessage::into_update`]). The coded fields are also
+/// available one by one as [`WireCode`]s, which keep codes the enums do not
+/// know.
 #[derive(Debug, Clone, Deserialize)]
 #[allow(non_snake_case)]
 pub struct OrderUpdateData {
@@ -263,32 +290,497 @@ pub struct OrderUpdateData {
     pub multiplier: Option<serde_json::Value>,
 }
 
+/// A wire code mapped onto a shared enum, or kept verbatim if it is not
+/// recognized (e.g. a product type added by Dhan after this release).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WireCode<T> {
+    /// A recognized code.
+    Known(T),
+    /// An unrecognized code, as sent.
+    Unknown(String),
+}
+
+impl<T> WireCode<T> {
+    /// Map `raw` with `parse`; `None` if it is missing or blank.
+    fn parse(raw: Option<&str>, parse: impl FnOnce(&str) -> Option<T>) -> Option<Self> {
+        let raw = raw.map(str::trim).filter(|s| !s.is_empty())?;
+        Some(parse(raw).map_or_else(|| Self::Unknown(raw.to_owned()), Self::Known))
+    }
+
+    /// The enum value, if the code was recognized.
+    pub fn known(&self) -> Option<&T> {
+        match self {
+            Self::Known(value) => Some(value),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// The enum value, if the code was recognized.
+    pub fn into_known(self) -> Option<T> {
+        match self {
+            Self::Known(value) => Some(value),
+            Self::Unknown(_) => None,
+        }
+    }
+
+    /// The raw code, if it was not recognized.
+    pub fn unknown(&self) -> Option<&str> {
+        match self {
+            Self::Known(_) => None,
+            Self::Unknown(code) => Some(code),
+        }
+    }
+}
+
+impl OrderUpdateData {
+    /// The normalized [`OrderUpdate`].
+    pub fn to_typed(&self) -> OrderUpdate {
+        OrderUpdate::from(self.clone())
+    }
+
+    /// `Product` (`"C"`, `"I"`, `"M"`, `"F"`, `"V"`, `"B"`).
+    pub fn product_type(&self) -> Option<WireCode<ProductType>> {
+        WireCode::parse(self.Product.as_deref(), ProductType::from_order_update_code)
+    }
+
+    /// `TxnType` (`"B"`, `"S"`).
+    pub fn transaction_type(&self) -> Option<WireCode<TransactionType>> {
+        WireCode::parse(
+            self.TxnType.as_deref(),
+            TransactionType::from_order_update_code,
+        )
+    }
+
+    /// `OrderType` (`"LMT"`, `"MKT"`, `"SL"`, `"SLM"`).
+    pub fn order_type(&self) -> Option<WireCode<OrderType>> {
+        WireCode::parse(self.OrderType.as_deref(), OrderType::from_order_update_code)
+    }
+
+    /// `Validity` (`"DAY"`, `"IOC"`).
+    pub fn validity(&self) -> Option<WireCode<Validity>> {
+        WireCode::parse(self.Validity.as_deref(), Validity::from_order_update_code)
+    }
+
+    /// `Status` (e.g. `"TRADED"`, `"REJECTED"`).
+    pub fn status(&self) -> Option<WireCode<OrderStatus>> {
+        WireCode::parse(self.Status.as_deref(), |s| s.parse().ok())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Normalized order update
+// ---------------------------------------------------------------------------
+
+/// A normalized order update with single snake_case fields and parsed types.
+///
+/// Built from the wire-level [`OrderUpdateData`]: abbreviated codes are mapped
+/// to the shared enums, duplicated case-variant fields are merged (the
+/// PascalCase value wins when both are present), loosely typed JSON values
+/// are converted to numbers, and timestamps are parsed.
+///
+/// Timestamps are naive exchange-local (IST) date-times, as sent by Dhan.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderUpdate {
+    /// Exchange (e.g. `"NSE"`, `"BSE"`, `"MCX"`).
+    pub exchange: Option<String>,
+    /// Segment code (e.g. `"E"` for Equity, `"D"` for Derivatives).
+    pub segment: Option<String>,
+    /// Source platform (`"P"` for API orders).
+    pub source: Option<String>,
+    /// Exchange standard security ID.
+    pub security_id: Option<String>,
+    /// Dhan client ID.
+    pub client_id: Option<String>,
+    /// Exchange-generated order number.
+    pub exchange_order_id: Option<String>,
+    /// Dhan-generated order number.
+    pub order_id: Option<String>,
+    /// User/partner generated tracking ID.
+    pub correlation_id: Option<String>,
+    /// Product type.
+    pub product_type: Option<ProductType>,
+    /// Buy or sell.
+    pub transaction_type: Option<TransactionType>,
+    /// Order type.
+    pub order_type: Option<OrderType>,
+    /// Order validity.
+    pub validity: Option<Validity>,
+    /// Order status.
+    pub status: Option<OrderStatus>,
+    /// Total order quantity placed.
+    pub quantity: Option<i64>,
+    /// Quantity executed on the exchange.
+    pub traded_quantity: Option<i64>,
+    /// Quantity pending execution.
+    pub remaining_quantity: Option<i64>,
+    /// Disclosed quantity.
+    pub disclosed_quantity: Option<i64>,
+    /// Disclosed quantity remaining.
+    pub disclosed_quantity_remaining: Option<i64>,
+    /// Order price.
+    pub price: Option<f64>,
+    /// Trigger price for SL/SL-M/CO/BO.
+    pub trigger_price: Option<f64>,
+    /// Price of the last execution.
+    pub traded_price: Option<f64>,
+    /// Average traded price.
+    pub average_traded_price: Option<f64>,
+    /// Entry leg order number for BO/CO tracking.
+    pub algo_order_id: Option<String>,
+    /// `true` for after-market orders.
+    pub after_market_order: bool,
+    /// Time at which the order was received by Dhan (IST wall clock).
+    pub order_time: Option<NaiveDateTime>,
+    /// Time at which the order was placed on the exchange (IST wall clock).
+    pub exchange_order_time: Option<NaiveDateTime>,
+    /// Last update time of modification or trade (IST wall clock).
+    pub last_updated_time: Option<NaiveDateTime>,
+    /// Additional remarks (e.g. `"Super Order"`).
+    pub remarks: Option<String>,
+    /// Market type (`"NL"` = Normal, `"AU"` / `"A1"` / `"A2"` = Auction).
+    pub market_type: Option<String>,
+    /// Rejection/status reason description.
+    pub reason_description: Option<String>,
+    /// Leg number (1 = Entry, 2 = Stop Loss, 3 = Target).
+    pub leg_no: Option<i32>,
+    /// Instrument type (e.g. `"EQUITY"`, `"FUTIDX"`).
+    pub instrument_type: Option<String>,
+    /// Trading symbol.
+    pub symbol: Option<String>,
+    /// Display name of the instrument.
+    pub display_name: Option<String>,
+    /// Product type name (e.g. `"CNC"`, `"INTRADAY"`).
+    pub product_name: Option<String>,
+    /// Lot size for derivatives.
+    pub lot_size: Option<i64>,
+    /// Strike price for option contracts.
+    pub strike_price: Option<f64>,
+    /// Expiry date of the contract.
+    pub expiry_date: Option<NaiveDate>,
+    /// Option type (`"CE"` / `"PE"`; `None` for non-options).
+    pub option_type: Option<String>,
+    /// ISIN of the instrument.
+    pub isin: Option<String>,
+    /// Exchange series (e.g. `"EQ"`).
+    pub series: Option<String>,
+    /// Good-till date for forever orders.
+    pub good_till_date: Option<NaiveDate>,
+    /// LTP at time of order update.
+    pub ref_ltp: Option<f64>,
+    /// Tick size of the instrument.
+    pub tick_size: Option<f64>,
+    /// Exchange algo ID for special order types.
+    pub algo_id: Option<String>,
+    /// Multiplier for commodity/currency contracts.
+    pub multiplier: Option<i64>,
+}
+
+impl OrderUpdate {
+    /// [`order_time`](Self::order_time) as UTC.
+    pub fn order_time_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.order_time.map(crate::time::ist_to_utc)
+    }
+
+    /// [`exchange_order_time`](Self::exchange_order_time) as UTC.
+    pub fn exchange_order_time_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.exchange_order_time.map(crate::time::ist_to_utc)
+    }
+
+    /// [`last_updated_time`](Self::last_updated_time) as UTC.
+    pub fn last_updated_time_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last_updated_time.map(crate::time::ist_to_utc)
+    }
+
+    /// The exchange segment, from the `exchange` and `segment` codes
+    /// (`"NSE"` + `"D"` → `NSE_FNO`).
+    pub fn exchange_segment(&self) -> Option<ExchangeSegment> {
+        let exchange = self.exchange.as_deref()?.trim().to_ascii_uppercase();
+        let segment = self.segment.as_deref()?.trim().to_ascii_uppercase();
+        match (exchange.as_str(), segment.as_str()) {
+            (_, "I") => Some(ExchangeSegment::IDX_I),
+            ("NSE", "E") => Some(ExchangeSegment::NSE_EQ),
+            ("NSE", "D") => Some(ExchangeSegment::NSE_FNO),
+            ("NSE", "C") => Some(ExchangeSegment::NSE_CURRENCY),
+            ("BSE", "E") => Some(ExchangeSegment::BSE_EQ),
+            ("BSE", "D") => Some(ExchangeSegment::BSE_FNO),
+            ("BSE", "C") => Some(ExchangeSegment::BSE_CURRENCY),
+            ("MCX", _) => Some(ExchangeSegment::MCX_COMM),
+            _ => None,
+        }
+    }
+
+    /// The instrument this order is for.
+    pub fn instrument(&self) -> Option<InstrumentId> {
+        let security_id = self.security_id.as_deref()?.trim().parse().ok()?;
+        Some(InstrumentId::new(self.exchange_segment()?, security_id))
+    }
+}
+
+impl From<OrderUpdateData> for OrderUpdate {
+    fn from(d: OrderUpdateData) -> Self {
+        let product_type = d.product_type();
+        let transaction_type = d.transaction_type();
+        let order_type = d.order_type();
+        let validity = d.validity();
+        let status = d.status();
+        for (field, code) in [
+            ("Product", product_type.as_ref().and_then(WireCode::unknown)),
+            (
+                "TxnType",
+                transaction_type.as_ref().and_then(WireCode::unknown),
+            ),
+            ("OrderType", order_type.as_ref().and_then(WireCode::unknown)),
+            ("Validity", validity.as_ref().and_then(WireCode::unknown)),
+            ("Status", status.as_ref().and_then(WireCode::unknown)),
+        ] {
+            if let Some(code) = code {
+                tracing::debug!(field, code, "Unrecognized order update code");
+            }
+        }
+        Self {
+            exchange: non_empty(d.Exchange),
+            segment: non_empty(d.Segment),
+            source: non_empty(d.Source),
+            security_id: non_empty(d.SecurityId),
+            client_id: non_empty(d.ClientId),
+            exchange_order_id: non_empty(d.ExchOrderNo),
+            order_id: non_empty(d.OrderNo),
+            correlation_id: non_empty(d.CorrelationId),
+            product_type: product_type.and_then(WireCode::into_known),
+            transaction_type: transaction_type.and_then(WireCode::into_known),
+            order_type: order_type.and_then(WireCode::into_known),
+            validity: validity.and_then(WireCode::into_known),
+            status: status.and_then(WireCode::into_known),
+            quantity: d.Quantity,
+            traded_quantity: d.TradedQty,
+            remaining_quantity: d.RemainingQuantity,
+            disclosed_quantity: d.DiscQuantity,
+            disclosed_quantity_remaining: d.DiscQtyRem,
+            price: d.Price,
+            trigger_price: d.TriggerPrice,
+            traded_price: d.TradedPrice,
+            average_traded_price: d.AvgTradedPrice,
+            algo_order_id: d.AlgoOrdNo.as_ref().and_then(value_to_string),
+            after_market_order: d.OffMktFlag.as_deref() == Some("1"),
+            order_time: d
+                .OrderDateTime
+                .as_deref()
+                .and_then(crate::time::parse_naive),
+            exchange_order_time: d
+                .ExchOrderTime
+                .as_deref()
+                .and_then(crate::time::parse_naive),
+            last_updated_time: d
+                .LastUpdatedTime
+                .as_deref()
+                .and_then(crate::time::parse_naive),
+            remarks: non_empty(d.Remarks),
+            market_type: non_empty(d.MktType),
+            reason_description: non_empty(d.ReasonDescription),
+            leg_no: d.LegNo,
+            instrument_type: non_empty(d.Instrument).or_else(|| non_empty(d.instrument_type)),
+            symbol: non_empty(d.Symbol),
+            display_name: non_empty(d.DisplayName),
+            product_name: non_empty(d.ProductName),
+            lot_size: d.LotSize,
+            strike_price: d.StrikePrice.as_ref().and_then(value_to_f64),
+            expiry_date: d.ExpiryDate.as_deref().and_then(parse_wire_date),
+            option_type: non_empty(d.OptType).filter(|t| t != "XX"),
+            isin: non_empty(d.Isin),
+            series: non_empty(d.Series).or_else(|| non_empty(d.series)),
+            good_till_date: d
+                .GoodTillDaysDate
+                .or(d.good_till_days_date)
+                .as_deref()
+                .and_then(parse_wire_date),
+            ref_ltp: d.RefLtp.or(d.ref_ltp),
+            tick_size: d.TickSize.or(d.tick_size),
+            algo_id: non_empty(d.AlgoId).or_else(|| non_empty(d.algo_id)),
+            multiplier: d
+                .Multiplier
+                .or_else(|| d.multiplier.as_ref().and_then(value_to_i64)),
+        }
+    }
+}
+
+/// Treat empty strings as missing values.
+fn non_empty(v: Option<String>) -> Option<String> {
+    v.filter(|s| !s.trim().is_empty())
+}
+
+/// Render a loosely typed JSON value (string or number) as a string.
+fn value_to_string(v: &serde_json::Value) -> Option<String> {
+    match v {
+        serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Parse a loosely typed JSON value (string or number) as `f64`.
+fn value_to_f64(v: &serde_json::Value) -> Option<f64> {
+    match v {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Parse a loosely typed JSON value (string or number) as `i64`.
+fn value_to_i64(v: &serde_json::Value) -> Option<i64> {
+    match v {
+        serde_json::Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Parse a wire date, accepting either a bare date or a full timestamp.
+fn parse_wire_date(s: &str) -> Option<NaiveDate> {
+    let s = s.trim();
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .or_else(|| crate::time::parse_naive(s).map(|dt| dt.date()))
+}
+
 // ---------------------------------------------------------------------------
 // Stream wrapper
 // ---------------------------------------------------------------------------
 
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
+/// How long [`OrderUpdateStream::connect`] waits for a reply to the login
+/// message before assuming it was accepted.
+///
+/// Dhan usually stays silent after a successful login, so this is also how
+/// long a successful connect typically takes. Connect ahead of time rather
+/// than on a latency-sensitive path.
+pub const AUTH_ACK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Classification of the first message received after logging in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthReply {
+    /// A regular order alert — login succeeded.
+    Update,
+    /// Any other non-error message — treated as a login acknowledgement.
+    Ack,
+    /// The server reported an authentication failure.
+    Failed,
+}
+
+/// Decide whether the first server message after login accepts or rejects
+/// it.
+///
+/// A JSON object fails the login if it has an error field, or if its
+/// status or message field reads like a failure ("invalid token", "login
+/// failed", ...); other fields are not inspected, so an acknowledgement
+/// that merely mentions e.g. rejected orders is not mistaken for one.
+/// Anything else, e.g. plain text, is judged by the same wording.
+pub fn classify_auth_reply(text: &str) -> AuthReply {
+    const FAILURE_HINTS: [&str; 5] = ["fail", "invalid", "unauthori", "reject", "expired"];
+    const ERROR_FIELDS: [&str; 5] = ["errorCode", "errorMessage", "error", "ErrorCode", "Error"];
+    const STATUS_FIELDS: [&str; 4] = ["status", "Status", "message", "Message"];
+    let looks_failed = |s: &str| {
+        let s = s.to_ascii_lowercase();
+        FAILURE_HINTS.iter().any(|h| s.contains(h))
+    };
+
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(serde_json::Value::Object(map)) => {
+            if map.get("Type").and_then(|t| t.as_str()) == Some("order_alert") {
+                return AuthReply::Update;
+            }
+            let has_error_field = ERROR_FIELDS.iter().any(|k| map.contains_key(*k));
+            let status_failed = STATUS_FIELDS
+                .iter()
+                .filter_map(|k| map.get(*k)?.as_str())
+                .any(looks_failed);
+            if has_error_field || status_failed {
+                AuthReply::Failed
+            } else {
+                AuthReply::Ack
+            }
+        }
+        _ if looks_failed(text) => AuthReply::Failed,
+        _ => AuthReply::Ack,
+    }
+}
+
+/// Idle-detection state for a live [`OrderUpdateStream`].
+struct Watchdog {
+    /// Maximum silence before the connection is considered dead.
+    timeout: Duration,
+    /// Fires at the next check point (ping at half-window, stale at full).
+    sleep: Pin<Box<Sleep>>,
+    /// Whether a ping has been sent since the last received frame.
+    pinged: bool,
+}
+
+/// Where an [`OrderUpdateStream`] reads its JSON messages from.
+enum Source {
+    /// A live WebSocket connection.
+    Live {
+        read: SplitStream<WsStream>,
+        write: SplitSink<WsStream, Message>,
+    },
+    /// Messages replayed from a recording file, one JSON document per line.
+    Replay(std::vec::IntoIter<String>),
+}
+
 /// A streaming connection for receiving live order updates.
 ///
 /// Implements [`Stream<Item = Result<OrderUpdateMessage>>`] so you can use it
 /// with `StreamExt::next()` and other stream combinators.
+///
+/// # Recording and replay
+///
+/// Every raw JSON text message can be appended to a file with
+/// [`record_to()`](Self::record_to). The resulting file (one message per line)
+/// can later be fed back through [`from_recording()`](Self::from_recording),
+/// which yields exactly the same items — including deserialization errors —
+/// without a network connection.
+///
+/// ```no_run
+/// use dhan_rs::ws::order_update::OrderUpdateStream;
+/// use futures_util::StreamExt;
+///
+/// # #[tokio::main]
+/// # async fn main() -> dhan_rs::error::Result<()> {
+/// let mut replay = OrderUpdateStream::from_recording("order-updates.jsonl")?;
+/// while let Some(msg) = replay.next().await {
+///     println!("{msg:?}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
 pub struct OrderUpdateStream {
-    read: SplitStream<WsStream>,
-    _write: SplitSink<WsStream, Message>,
+    source: Source,
+    /// The read half of a socket replaced by
+    /// [`rotate_token()`](Self::rotate_token), read until it ends.
+    draining: Option<SplitStream<WsStream>>,
+    /// Messages received while waiting for a login reply, yielded first.
+    pending: VecDeque<String>,
+    /// When the last frame (of any kind) was received.
+    last_message_at: Instant,
+    /// Optional idle watchdog (see [`with_idle_timeout()`](Self::with_idle_timeout)).
+    watchdog: Option<Watchdog>,
+    /// Set once the watchdog has fired; the stream ends afterwards.
+    stale: bool,
+    /// Append-only sink for raw text messages (recording mode).
+    recorder: Option<BufWriter<File>>,
 }
 
 impl OrderUpdateStream {
     /// Connect to the order-update WebSocket as an individual user.
     ///
     /// Sends the authentication message immediately after connection is
-    /// established.
+    /// established and waits up to [`AUTH_ACK_TIMEOUT`] for the server's
+    /// reply. Returns [`DhanError::WsAuthFailed`] if the login is rejected.
+    ///
+    /// Dhan often does not acknowledge a successful login, in which case
+    /// this returns only once the full [`AUTH_ACK_TIMEOUT`] (3 s) has
+    /// passed. The same applies to [`rotate_token()`](Self::rotate_token).
     pub async fn connect(client_id: &str, access_token: &str) -> Result<Self> {
-        let (ws, _resp) = connect_async(WS_ORDER_UPDATE_URL).await?;
-
-        let (mut write, read) = ws.split();
-
-        // Send individual auth message
         let auth = IndividualAuthMessage {
             LoginReq: LoginRequest {
                 MsgCode: 42,
@@ -297,25 +789,17 @@ impl OrderUpdateStream {
             },
             UserType: "SELF".to_owned(),
         };
-        let auth_json = serde_json::to_string(&auth)?;
-        write.send(Message::Text(auth_json.into())).await?;
+        let stream = Self::connect_with(serde_json::to_string(&auth)?).await?;
 
         tracing::info!("Connected to order-update WebSocket");
-
-        Ok(Self {
-            read,
-            _write: write,
-        })
+        Ok(stream)
     }
 
     /// Connect to the order-update WebSocket as a partner.
     ///
     /// Partner platforms receive order updates for all connected users.
+    /// Authentication is verified as in [`connect()`](Self::connect).
     pub async fn connect_partner(partner_id: &str, partner_secret: &str) -> Result<Self> {
-        let (ws, _resp) = connect_async(WS_ORDER_UPDATE_URL).await?;
-
-        let (mut write, read) = ws.split();
-
         let auth = PartnerAuthMessage {
             LoginReq: PartnerLoginRequest {
                 MsgCode: 42,
@@ -324,42 +808,297 @@ impl OrderUpdateStream {
             UserType: "PARTNER".to_owned(),
             Secret: partner_secret.to_owned(),
         };
-        let auth_json = serde_json::to_string(&auth)?;
-        write.send(Message::Text(auth_json.into())).await?;
+        let stream = Self::connect_with(serde_json::to_string(&auth)?).await?;
 
         tracing::info!("Connected to order-update WebSocket (partner mode)");
+        Ok(stream)
+    }
+
+    /// Open the socket, send `auth_json` and wait for the login outcome.
+    ///
+    /// Dhan does not always acknowledge a successful login, so silence for the
+    /// whole [`AUTH_ACK_TIMEOUT`] window is treated as acceptance. A close
+    /// frame, end of stream or an explicit failure message is a rejection.
+    /// An order alert that arrives inside the window is kept and yielded
+    /// first by the stream.
+    async fn connect_with(auth_json: String) -> Result<Self> {
+        let (ws, _resp) = connect_async(WS_ORDER_UPDATE_URL).await?;
+        let (mut write, mut read) = ws.split();
+        write.send(Message::Text(auth_json.into())).await?;
+
+        let deadline = tokio::time::Instant::now() + AUTH_ACK_TIMEOUT;
+        let mut pending = None;
+        loop {
+            let msg = match tokio::time::timeout_at(deadline, read.next()).await {
+                // No reply within the window: assume the login was accepted.
+                Err(_) => break,
+                Ok(msg) => msg,
+            };
+            match msg {
+                Some(Ok(Message::Text(text))) => match classify_auth_reply(&text) {
+                    AuthReply::Update => {
+                        pending = Some(text.to_string());
+                        break;
+                    }
+                    AuthReply::Ack => {
+                        tracing::debug!("Order-update login acknowledged: {text}");
+                        break;
+                    }
+                    AuthReply::Failed => return Err(DhanError::WsAuthFailed(text.to_string())),
+                },
+                Some(Ok(Message::Close(frame))) => {
+                    let reason = frame
+                        .map(|f| format!("connection closed ({}): {}", f.code, f.reason))
+                        .unwrap_or_else(|| "connection closed by server".to_owned());
+                    return Err(DhanError::WsAuthFailed(reason));
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+                None => {
+                    return Err(DhanError::WsAuthFailed(
+                        "connection ended before login completed".to_owned(),
+                    ));
+                }
+            }
+        }
+
+        Ok(Self {
+            source: Source::Live { read, write },
+            draining: None,
+            pending: pending.into_iter().collect(),
+            last_message_at: Instant::now(),
+            watchdog: None,
+            stale: false,
+            recorder: None,
+        })
+    }
+
+    /// Log in again with a renewed `access_token` on a new socket and
+    /// switch to it without missing updates.
+    ///
+    /// The new connection is authenticated before the old one is closed;
+    /// updates already on their way over the old socket are still yielded
+    /// until it ends. An update sent on both sockets during the switch is
+    /// yielded twice. Fails for replayed streams.
+    pub async fn rotate_token(&mut self, client_id: &str, access_token: &str) -> Result<()> {
+        if self.is_replay() {
+            return Err(DhanError::InvalidArgument(
+                "cannot rotate the token of a replayed stream".into(),
+            ));
+        }
+        let fresh = Self::connect(client_id, access_token).await?;
+        let Source::Live { read, write } = fresh.source else {
+            unreachable!("connect() returns a live stream");
+        };
+        if let Source::Live {
+            read: old_read,
+            write: mut old_write,
+        } = std::mem::replace(&mut self.source, Source::Live { read, write })
+        {
+            if let Err(e) = old_write.send(Message::Close(None)).await {
+                tracing::debug!("Failed to close the replaced order-update socket: {e}");
+            }
+            self.draining = Some(old_read);
+        }
+        self.pending.extend(fresh.pending);
+        self.touch();
+        tracing::info!("Order-update WebSocket rotated to a new token");
+        Ok(())
+    }
+
+    /// Create a stream that replays messages previously captured with
+    /// [`record_to()`](Self::record_to).
+    ///
+    /// Each non-empty line of the file is treated as one JSON text message
+    /// and parsed exactly as a live message would be. The stream ends after
+    /// the last line.
+    pub fn from_recording(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = std::fs::read_to_string(path.as_ref())?;
+        let lines: Vec<String> = contents
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(str::to_owned)
+            .collect();
+
+        tracing::info!(
+            path = %path.as_ref().display(),
+            messages = lines.len(),
+            "Replaying recorded order updates"
+        );
 
         Ok(Self {
-            read,
-            _write: write,
+            source: Source::Replay(lines.into_iter()),
+            draining: None,
+            pending: VecDeque::new(),
+            last_message_at: Instant::now(),
+            watchdog: None,
+            stale: false,
+            recorder: None,
         })
     }
 
+    /// Enable recording mode: append every received JSON text message to the
+    /// file at `path` (created if missing), one message per line.
+    ///
+    /// Messages are written before they are parsed, so payloads that fail to
+    /// deserialize are captured too.
+    pub fn record_to(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())?;
+        self.recorder = Some(BufWriter::new(file));
+
+        tracing::info!(path = %path.as_ref().display(), "Recording order updates");
+        Ok(self)
+    }
+
+    /// Enable the idle watchdog.
+    ///
+    /// If no frame (order alert, ping or pong) arrives for half of `timeout`,
+    /// a ping is sent to provoke a pong. If the socket stays silent for the
+    /// full `timeout`, the stream yields [`DhanError::StaleConnection`] and
+    /// then ends, so a dead socket is noticed before fills are missed.
+    ///
+    /// Has no effect on replayed streams.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.watchdog = Some(Watchdog {
+            timeout,
+            sleep: Box::pin(tokio::time::sleep(timeout / 2)),
+            pinged: false,
+        });
+        self
+    }
+
+    /// When the last frame of any kind was received (or the stream was
+    /// created, if nothing has arrived yet).
+    pub fn last_message_at(&self) -> Instant {
+        self.last_message_at
+    }
+
+    /// How long the connection has been silent.
+    pub fn idle_for(&self) -> Duration {
+        self.last_message_at.elapsed()
+    }
+
+    /// Record that a frame was just received and re-arm the watchdog.
+    fn touch(&mut self) {
+        self.last_message_at = Instant::now();
+        if let Some(w) = self.watchdog.as_mut() {
+            w.pinged = false;
+            w.sleep.as_mut().reset(self.last_message_at + w.timeout / 2);
+        }
+    }
+
+    /// Drive the watchdog. Returns `Some(idle)` once the connection is stale.
+    fn poll_watchdog(&mut self, cx: &mut Context<'_>) -> Option<Duration> {
+        let Source::Live { write, .. } = &mut self.source else {
+            return None;
+        };
+        let w = self.watchdog.as_mut()?;
+        loop {
+            if w.sleep.as_mut().poll(cx).is_pending() {
+                return None;
+            }
+            if w.pinged {
+                return Some(self.last_message_at.elapsed());
+            }
+            // Half the window elapsed in silence: ask the server for a pong.
+            if let Poll::Ready(Ok(())) = write.poll_ready_unpin(cx) {
+                if let Err(e) = write.start_send_unpin(Message::Ping(Vec::new().into())) {
+                    tracing::warn!("Failed to send order-update ping: {e}");
+                }
+                let _ = write.poll_flush_unpin(cx);
+            }
+            w.pinged = true;
+            w.sleep.as_mut().reset(self.last_message_at + w.timeout);
+        }
+    }
+
+    /// Returns `true` if this stream is replaying a recording rather than
+    /// reading from a live connection.
+    pub fn is_replay(&self) -> bool {
+        matches!(self.source, Source::Replay(_))
+    }
+
     /// Close the WebSocket connection gracefully.
+    ///
+    /// For replayed streams this only flushes the recorder (if any).
     pub async fn close(mut self) -> Result<()> {
-        self._write.send(Message::Close(None)).await?;
+        if let Some(rec) = self.recorder.as_mut() {
+            rec.flush()?;
+        }
+        if let Source::Live { write, .. } = &mut self.source {
+            write.send(Message::Close(None)).await?;
+        }
         Ok(())
     }
+
+    /// Append a raw text message to the recording file, if recording is on.
+    ///
+    /// Newlines inside the payload are insignificant JSON whitespace, so they
+    /// are replaced with spaces to keep one message per line.
+    fn record(&mut self, text: &str) {
+        let Some(rec) = self.recorder.as_mut() else {
+            return;
+        };
+        let line = text.replace(['\n', '\r'], " ");
+        if let Err(e) = writeln!(rec, "{line}").and_then(|_| rec.flush()) {
+            tracing::warn!("Failed to record order update: {e}");
+        }
+    }
+
+    /// Parse a JSON text message into an [`OrderUpdateMessage`].
+    fn parse_text(text: &str) -> Result<OrderUpdateMessage> {
+        serde_json::from_str::<OrderUpdateMessage>(text).map_err(|e| {
+            tracing::warn!("Failed to parse order update: {e}, raw: {text}");
+            DhanError::Json(e)
+        })
+    }
 }
 
 impl Stream for OrderUpdateStream {
     type Item = Result<OrderUpdateMessage>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        if this.stale {
+            return Poll::Ready(None);
+        }
+        while let Some(read) = this.draining.as_mut() {
+            match read.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(Message::Text(text)))) => {
+                    this.record(&text);
+                    return Poll::Ready(Some(Self::parse_text(&text)));
+                }
+                Poll::Ready(Some(Ok(Message::Close(_)) | Err(_)) | None) => this.draining = None,
+                Poll::Ready(Some(Ok(_))) => continue,
+                Poll::Pending => break,
+            }
+        }
+        if let Some(text) = this.pending.pop_front() {
+            this.record(&text);
+            return Poll::Ready(Some(Self::parse_text(&text)));
+        }
         loop {
-            match self.read.poll_next_unpin(cx) {
+            let read = match &mut this.source {
+                Source::Live { read, .. } => read,
+                Source::Replay(lines) => {
+                    let Some(line) = lines.next() else {
+                        return Poll::Ready(None);
+                    };
+                    this.record(&line);
+                    return Poll::Ready(Some(Self::parse_text(&line)));
+                }
+            };
+
+            match read.poll_next_unpin(cx) {
                 Poll::Ready(Some(Ok(msg))) => {
+                    this.touch();
                     match msg {
                         Message::Text(text) => {
-                            match serde_json::from_str::<OrderUpdateMessage>(&text) {
-                                Ok(update) => return Poll::Ready(Some(Ok(update))),
-                                Err(e) => {
-                                    tracing::warn!(
-                                        "Failed to parse order update: {e}, raw: {text}"
-                                    );
-                                    return Poll::Ready(Some(Err(DhanError::Json(e))));
-                                }
-                            }
+                            this.record(&text);
+                            return Poll::Ready(Some(Self::parse_text(&text)));
                         }
                         Message::Ping(_) | Message::Pong(_) => {
                             // Ping/pong handled automatically by tungstenite
@@ -376,7 +1115,14 @@ impl Stream for OrderUpdateStream {
                     return Poll::Ready(Some(Err(DhanError::WebSocket(Box::new(e)))));
                 }
                 Poll::Ready(None) => return Poll::Ready(None),
-                Poll::Pending => return Poll::Pending,
+                Poll::Pending => {
+                    if let Some(idle) = this.poll_watchdog(cx) {
+                        tracing::warn!(?idle, "Order-update WebSocket is stale");
+                        this.stale = true;
+                        return Poll::Ready(Some(Err(DhanError::StaleConnection(idle))));
+                    }
+                    return Poll::Pending;
+                }
             }
         }
     }
@@ -0,0 +1,310 @@
+//! Feed-quality monitoring for market data.
+//!
+//! [`FeedQualityMonitor`] watches the tick stream and raises
+//! [`FeedQualityEvent`]s when data looks wrong:
+//!
+//! - **Stale** — a watched instrument has not updated for the configured
+//!   time while the market is open (and **Recovered** when it resumes);
+//! - **Price spike** — a price jumped further than a sanity bound, or
+//!   landed outside the instrument's circuit band when one is known;
+//! - **Regression** — last-trade time or cumulative volume went backwards.
+//!
+//! Strategies can treat these events as a signal to stop trading an
+//! instrument until data is healthy again.
+//!
+//! ```
+//! use dhan_rs::ws::quality::{FeedQualityConfig, FeedQualityMonitor};
+//!
+//! let mut monitor = FeedQualityMonitor::new(FeedQualityConfig::default());
+//! // In the feed loop:
+//! // for event in monitor.on_tick(&tick) { ... }
+//! // Periodically:
+//! // for event in monitor.check_stale() { ... }
+//! # let _ = &mut monitor;
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+
+use crate::scheduler::ist;
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::Tick;
+
+/// Thresholds for [`FeedQualityMonitor`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedQualityConfig {
+    /// Flag a watched instrument after this long without updates (default 30 s).
+    pub stale_after: Duration,
+    /// Largest plausible tick-to-tick move, in percent (default 20 %, the
+    /// widest exchange circuit band).
+    pub max_jump_pct: f64,
+    /// IST session start for staleness checks (default 09:15).
+    pub market_open: NaiveTime,
+    /// IST session end for staleness checks (default 15:30).
+    pub market_close: NaiveTime,
+}
+
+impl Default for FeedQualityConfig {
+    fn default() -> Self {
+        Self {
+            stale_after: Duration::from_secs(30),
+            max_jump_pct: 20.0,
+            market_open: NaiveTime::from_hms_opt(9, 15, 0).expect("valid time"),
+            market_close: NaiveTime::from_hms_opt(15, 30, 0).expect("valid time"),
+        }
+    }
+}
+
+/// A feed-quality problem (or its resolution).
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedQualityEvent {
+    /// No update for `idle` while the market is open.
+    Stale {
+        /// The silent instrument.
+        instrument: InstrumentId,
+        /// Time since its last update.
+        idle: Duration,
+    },
+    /// A previously stale instrument updated again.
+    Recovered {
+        /// The instrument.
+        instrument: InstrumentId,
+    },
+    /// The price moved further than [`FeedQualityConfig::max_jump_pct`].
+    PriceSpike {
+        /// The instrument.
+        instrument: InstrumentId,
+        /// Previous price.
+        previous: f64,
+        /// New price.
+        price: f64,
+        /// Move in percent.
+        change_pct: f64,
+    },
+    /// The price is outside the instrument's circuit band.
+    OutsideCircuit {
+        /// The instrument.
+        instrument: InstrumentId,
+        /// Offending price.
+        price: f64,
+        /// Lower circuit limit.
+        lower: f64,
+        /// Upper circuit limit.
+        upper: f64,
+    },
+    /// Last-trade time went backwards.
+    TimeRegression {
+        /// The instrument.
+        instrument: InstrumentId,
+        /// Previous last-trade time (epoch seconds).
+        previous: i64,
+        /// New last-trade time (epoch seconds).
+        ltt: i64,
+    },
+    /// Cumulative day volume went backwards.
+    VolumeRegression {
+        /// The instrument.
+        instrument: InstrumentId,
+        /// Previous cumulative volume.
+        previous: i64,
+        /// New cumulative volume.
+        volume: i64,
+    },
+}
+
+impl FeedQualityEvent {
+    /// The instrument the event concerns.
+    pub fn instrument(&self) -> InstrumentId {
+        match self {
+            Self::Stale { instrument, .. }
+            | Self::Recovered { instrument }
+            | Self::PriceSpike { instrument, .. }
+            | Self::OutsideCircuit { instrument, .. }
+            | Self::TimeRegression { instrument, .. }
+            | Self::VolumeRegression { instrument, .. } => *instrument,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct InstrumentState {
+    received_at: DateTime<Utc>,
+    ltp: Option<f64>,
+    ltt: Option<i64>,
+    volume: Option<i64>,
+    stale: bool,
+}
+
+/// Flags stale, spiking and regressing instruments in a tick stream.
+#[derive(Debug, Clone)]
+pub struct FeedQualityMonitor {
+    config: FeedQualityConfig,
+    instruments: HashMap<InstrumentId, InstrumentState>,
+    circuits: HashMap<InstrumentId, (f64, f64)>,
+}
+
+impl FeedQualityMonitor {
+    /// Create a monitor with `config`.
+    pub fn new(config: FeedQualityConfig) -> Self {
+        Self {
+            config,
+            instruments: HashMap::new(),
+            circuits: HashMap::new(),
+        }
+    }
+
+    /// The active configuration.
+    pub fn config(&self) -> &FeedQualityConfig {
+        &self.config
+    }
+
+    /// Expect updates for `instrument` from now on, so it is flagged stale
+    /// even if it never ticks.
+    pub fn watch(&mut self, instrument: InstrumentId) {
+        self.watch_at(instrument, Utc::now());
+    }
+
+    /// [`watch()`](Self::watch) with an explicit clock.
+    pub fn watch_at(&mut self, instrument: InstrumentId, now: DateTime<Utc>) {
+        self.instruments
+            .entry(instrument)
+            .or_insert(InstrumentState {
+                received_at: now,
+                ltp: None,
+                ltt: None,
+                volume: None,
+                stale: false,
+            });
+    }
+
+    /// Stop monitoring `instrument`.
+    pub fn unwatch(&mut self, instrument: &InstrumentId) {
+        self.instruments.remove(instrument);
+        self.circuits.remove(instrument);
+    }
+
+    /// Set the circuit band of `instrument` (e.g. from a REST quote).
+    pub fn set_circuit_band(&mut self, instrument: InstrumentId, lower: f64, upper: f64) {
+        self.circuits.insert(instrument, (lower, upper));
+    }
+
+    /// Check a tick received now.
+    pub fn on_tick(&mut self, tick: &Tick) -> Vec<FeedQualityEvent> {
+        self.on_tick_at(tick, Utc::now())
+    }
+
+    /// [`on_tick()`](Self::on_tick) with an explicit receive time.
+    pub fn on_tick_at(&mut self, tick: &Tick, now: DateTime<Utc>) -> Vec<FeedQualityEvent> {
+        let id = tick.instrument;
+        self.watch_at(id, now);
+        let circuit = self.circuits.get(&id).copied();
+        let max_jump_pct = self.config.max_jump_pct;
+        let state = self.instruments.get_mut(&id).expect("just watched");
+        let mut events = Vec::new();
+
+        state.received_at = now;
+        if std::mem::take(&mut state.stale) {
+            events.push(FeedQualityEvent::Recovered { instrument: id });
+        }
+
+        if tick.ltt > 0 {
+            if let Some(previous) = state.ltt.filter(|&p| tick.ltt < p) {
+                events.push(FeedQualityEvent::TimeRegression {
+                    instrument: id,
+                    previous,
+                    ltt: tick.ltt,
+                });
+            }
+            state.ltt = Some(state.ltt.map_or(tick.ltt, |p| p.max(tick.ltt)));
+        }
+
+        if let Some(volume) = tick.volume {
+            if let Some(previous) = state.volume.filter(|&p| volume < p) {
+                events.push(FeedQualityEvent::VolumeRegression {
+                    instrument: id,
+                    previous,
+                    volume,
+                });
+            } else {
+                state.volume = Some(volume);
+            }
+        }
+
+        if tick.ltp > 0.0 {
+            let price = tick.ltp;
+            if let Some(previous) = state.ltp {
+                let change_pct = (price - previous) / previous * 100.0;
+                if change_pct.abs() > max_jump_pct {
+                    events.push(FeedQualityEvent::PriceSpike {
+                        instrument: id,
+                        previous,
+                        price,
+                        change_pct,
+                    });
+                }
+            }
+            if let Some((lower, upper)) = circuit.filter(|&(lo, hi)| price < lo || price > hi) {
+                events.push(FeedQualityEvent::OutsideCircuit {
+                    instrument: id,
+                    price,
+                    lower,
+                    upper,
+                });
+            }
+            state.ltp = Some(price);
+        }
+
+        for event in &events {
+            tracing::warn!(?event, "Feed quality issue");
+        }
+        events
+    }
+
+    /// Flag watched instruments that have gone quiet.
+    pub fn check_stale(&mut self) -> Vec<FeedQualityEvent> {
+        self.check_stale_at(Utc::now())
+    }
+
+    /// [`check_stale()`](Self::check_stale) with an explicit clock.
+    ///
+    /// Outside market hours (or at weekends) nothing is flagged. Each stale
+    /// episode is reported once; a later tick reports it recovered.
+    pub fn check_stale_at(&mut self, now: DateTime<Utc>) -> Vec<FeedQualityEvent> {
+        if !self.market_open_at(now) {
+            return Vec::new();
+        }
+        let stale_after = self.config.stale_after;
+        let mut events = Vec::new();
+        for (id, state) in &mut self.instruments {
+            let idle = (now - state.received_at).to_std().unwrap_or_default();
+            if !state.stale && idle >= stale_after {
+                state.stale = true;
+                tracing::warn!(instrument = %id, ?idle, "Instrument feed is stale");
+                events.push(FeedQualityEvent::Stale {
+                    instrument: *id,
+                    idle,
+                });
+            }
+        }
+        events
+    }
+
+    /// Instruments currently flagged stale.
+    pub fn stale_instruments(&self) -> Vec<InstrumentId> {
+        self.instruments
+            .iter()
+            .filter(|(_, s)| s.stale)
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    fn market_open_at(&self, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&ist());
+        let time = local.time();
+        !matches!(local.weekday(), Weekday::Sat | Weekday::Sun)
+            && time >= self.config.market_open
+            && time < self.config.market_close
+    }
+}
@@ -0,0 +1,388 @@
+//! A market feed stream that survives disconnects.
+//!
+//! [`MarketFeedStream`] ends when its connection does; only the
+//! [`DhanFeedManager`] reconnects on its own. [`ReconnectingMarketFeedStream`]
+//! gives a single connection the same behaviour: when the socket closes,
+//! errors or goes silent past the idle timeout, it reconnects with
+//! exponential backoff, re-sends every subscription made through it, and
+//! yields [`FeedStreamEvent::Reconnected`] so consumers know packets may
+//! have been missed in between (e.g. to re-fetch snapshots).
+//!
+//! Rejected credentials end the stream with the error instead of retrying,
+//! as does running out of attempts under a [`ReconnectPolicy`] with
+//! `max_attempts`.
+//! Opened with [`ReconnectingMarketFeedStream::connect_shared`], each
+//! reconnect uses whatever token the [`SharedToken`] holds by then, so a
+//! renewal made through the client carries over.
+//!
+//! ```no_run
+//! use dhan_rs::types::enums::FeedRequestCode;
+//! use dhan_rs::ws::market_feed::Instrument;
+//! use dhan_rs::ws::reconnect::{FeedStreamEvent, ReconnectingMarketFeedStream};
+//! use futures_util::StreamExt;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! let mut stream = ReconnectingMarketFeedStream::connect("client-id", "token").await?;
+//! stream
+//!     .subscribe(FeedRequestCode::SubscribeQuote, &[Instrument::new("NSE_EQ", "1333")])
+//!     .await?;
+//!
+//! while let Some(event) = stream.next().await {
+//!     match event? {
+//!         FeedStreamEvent::Event(e) => println!("{e}"),
+//!         FeedStreamEvent::Reconnected { down_for, .. } => {
+//!             println!("feed was down for {down_for:?}; refreshing state");
+//!         }
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`DhanFeedManager`]: crate::ws::manager::DhanFeedManager
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+use tokio::time::Instant;
+
+use crate::dns::DnsPins;
+use crate::error::{DhanError, Result};
+use crate::token::SharedToken;
+use crate::types::enums::FeedRequestCode;
+use crate::ws::market_feed::{Instrument, MarketFeedEvent, MarketFeedStream};
+
+/// An item of a [`ReconnectingMarketFeedStream`].
+#[derive(Debug, Clone)]
+pub enum FeedStreamEvent {
+    /// A market feed packet.
+    Event(MarketFeedEvent),
+    /// The connection was re-established and subscriptions re-sent.
+    /// Packets between the disconnect and now were lost.
+    Reconnected {
+        /// Connection attempts it took.
+        attempts: u32,
+        /// Time from noticing the disconnect to being subscribed again.
+        down_for: Duration,
+    },
+}
+
+/// How a [`ReconnectingMarketFeedStream`] retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Wait before the first attempt; doubled after every failed one.
+    pub initial_delay: Duration,
+    /// Upper bound of the wait between attempts.
+    pub max_delay: Duration,
+    /// Give up after this many failed attempts (`None`: never).
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// The wait before attempt `attempt` (1-based).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+}
+
+/// Instruments subscribed through the stream, with their mode.
+type Subscriptions = Arc<Mutex<HashMap<(String, String), (Instrument, FeedRequestCode)>>>;
+
+type Reconnect = Pin<Box<dyn Future<Output = Result<(MarketFeedStream, u32)>> + Send>>;
+
+enum State {
+    Connected(MarketFeedStream),
+    Reconnecting { since: Instant, connect: Reconnect },
+    Done,
+}
+
+/// A [`MarketFeedStream`] that reconnects and re-subscribes on its own.
+pub struct ReconnectingMarketFeedStream {
+    client_id: String,
+    access_token: SharedToken,
+    pins: Option<DnsPins>,
+    policy: ReconnectPolicy,
+    idle_timeout: Option<Duration>,
+    subscriptions: Subscriptions,
+    state: State,
+}
+
+impl ReconnectingMarketFeedStream {
+    /// Connect to the market feed with the default [`ReconnectPolicy`].
+    ///
+    /// Only later disconnects are retried; a failing first connect is
+    /// returned as the error.
+    pub async fn connect(
+        client_id: impl Into<String>,
+        access_token: impl Into<String>,
+    ) -> Result<Self> {
+        Self::connect_inner(client_id.into(), SharedToken::new(access_token), None).await
+    }
+
+    /// Like [`Self::connect`], reading the access token from `token` on
+    /// every reconnect, e.g. [`DhanClient::shared_token`], so a renewal
+    /// reaches the next connection.
+    ///
+    /// [`DhanClient::shared_token`]: crate::client::DhanClient::shared_token
+    pub async fn connect_shared(client_id: impl Into<String>, token: SharedToken) -> Result<Self> {
+        Self::connect_inner(client_id.into(), token, None).await
+    }
+
+    /// Like [`Self::connect`], connecting (and reconnecting) to the feed
+    /// host's addresses in `pins` (see [`crate::dns`]).
+    pub async fn connect_pinned(
+        client_id: impl Into<String>,
+        access_token: impl Into<String>,
+        pins: DnsPins,
+    ) -> Result<Self> {
+        Self::connect_inner(client_id.into(), SharedToken::new(access_token), Some(pins)).await
+    }
+
+    async fn connect_inner(
+        client_id: String,
+        access_token: SharedToken,
+        pins: Option<DnsPins>,
+    ) -> Result<Self> {
+        let stream = open(&client_id, &access_token.get(), pins.as_ref(), None).await?;
+        Ok(Self {
+            client_id,
+            access_token,
+            pins,
+            policy: ReconnectPolicy::default(),
+            idle_timeout: None,
+            subscriptions: Arc::default(),
+            state: State::Connected(stream),
+        })
+    }
+
+    /// Retry according to `policy`.
+    pub fn with_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Treat `timeout` of silence as a disconnect (see
+    /// [`MarketFeedStream::with_idle_timeout`]), on this and every later
+    /// connection.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        if let State::Connected(stream) = std::mem::replace(&mut self.state, State::Done) {
+            self.state = State::Connected(stream.with_idle_timeout(timeout));
+        }
+        self
+    }
+
+    /// Subscribe to instruments in `mode` and remember them for
+    /// reconnects.
+    ///
+    /// While reconnecting, the instruments are only remembered and are
+    /// subscribed once the connection is back. If sending fails, they stay
+    /// remembered and the error is returned.
+    pub async fn subscribe(
+        &mut self,
+        mode: FeedRequestCode,
+        instruments: &[Instrument],
+    ) -> Result<()> {
+        {
+            let mut subs = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+            for inst in instruments {
+                subs.insert(key(inst), (inst.clone(), mode));
+            }
+        }
+        match &mut self.state {
+            State::Connected(stream) => stream.subscribe(mode, instruments).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Unsubscribe from instruments and forget them.
+    pub async fn unsubscribe(
+        &mut self,
+        mode: FeedRequestCode,
+        instruments: &[Instrument],
+    ) -> Result<()> {
+        {
+            let mut subs = self.subscriptions.lock().unwrap_or_else(|e| e.into_inner());
+            for inst in instruments {
+                subs.remove(&key(inst));
+            }
+        }
+        match &mut self.state {
+            State::Connected(stream) => stream.unsubscribe(mode, instruments).await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Number of instruments that would be re-subscribed on a reconnect.
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    /// Returns `true` while the stream is trying to reconnect.
+    pub fn is_reconnecting(&self) -> bool {
+        matches!(self.state, State::Reconnecting { .. })
+    }
+
+    /// Stop reconnecting and, if connected, disconnect.
+    pub async fn disconnect(self) -> Result<()> {
+        match self.state {
+            State::Connected(stream) => stream.disconnect().await,
+            _ => Ok(()),
+        }
+    }
+
+    /// Drop the current connection and start reconnecting.
+    fn start_reconnect(&mut self) {
+        let client_id = self.client_id.clone();
+        let access_token = self.access_token.clone();
+        let pins = self.pins.clone();
+        let policy = self.policy;
+        let idle_timeout = self.idle_timeout;
+        let subscriptions = self.subscriptions.clone();
+        let connect = async move {
+            let mut attempt = 0;
+            loop {
+                attempt += 1;
+                tokio::time::sleep(policy.delay(attempt)).await;
+                let result = async {
+                    let mut stream =
+                        open(&client_id, &access_token.get(), pins.as_ref(), idle_timeout).await?;
+                    resubscribe(&mut stream, &subscriptions).await?;
+                    Ok::<_, DhanError>(stream)
+                }
+                .await;
+                match result {
+                    Ok(stream) => return Ok((stream, attempt)),
+                    Err(e) if is_auth_error(&e) => return Err(e),
+                    Err(e) if policy.max_attempts.is_some_and(|max| attempt >= max) => {
+                        return Err(e);
+                    }
+                    Err(e) => {
+                        tracing::warn!(attempt, error = %e, "Market-feed reconnect failed");
+                    }
+                }
+            }
+        };
+        self.state = State::Reconnecting {
+            since: Instant::now(),
+            connect: Box::pin(connect),
+        };
+    }
+}
+
+impl Stream for ReconnectingMarketFeedStream {
+    type Item = Result<FeedStreamEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            match &mut this.state {
+                State::Connected(stream) => match stream.poll_next_unpin(cx) {
+                    Poll::Ready(Some(Ok(event))) => {
+                        return Poll::Ready(Some(Ok(FeedStreamEvent::Event(event))));
+                    }
+                    Poll::Ready(Some(Err(e))) if is_auth_error(&e) => {
+                        this.state = State::Done;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    Poll::Ready(Some(Err(
+                        e @ (DhanError::WebSocket(_) | DhanError::StaleConnection(_)),
+                    ))) => {
+                        tracing::warn!(error = %e, "Market feed lost; reconnecting");
+                        this.start_reconnect();
+                    }
+                    // A packet that failed to parse; the connection is fine.
+                    Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                    Poll::Ready(None) => {
+                        tracing::warn!("Market feed closed; reconnecting");
+                        this.start_reconnect();
+                    }
+                    Poll::Pending => return Poll::Pending,
+                },
+                State::Reconnecting { since, connect } => match connect.as_mut().poll(cx) {
+                    Poll::Ready(Ok((stream, attempts))) => {
+                        let down_for = since.elapsed();
+                        tracing::info!(attempts, ?down_for, "Market feed reconnected");
+                        this.state = State::Connected(stream);
+                        return Poll::Ready(Some(Ok(FeedStreamEvent::Reconnected {
+                            attempts,
+                            down_for,
+                        })));
+                    }
+                    Poll::Ready(Err(e)) => {
+                        tracing::error!(error = %e, "Giving up reconnecting the market feed");
+                        this.state = State::Done;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                    Poll::Pending => return Poll::Pending,
+                },
+                State::Done => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+fn key(inst: &Instrument) -> (String, String) {
+    (inst.ExchangeSegment.clone(), inst.SecurityId.clone())
+}
+
+/// Errors that retrying with the same credentials cannot fix.
+fn is_auth_error(e: &DhanError) -> bool {
+    matches!(e, DhanError::TokenExpired(_) | DhanError::WsAuthFailed(_))
+}
+
+async fn open(
+    client_id: &str,
+    access_token: &str,
+    pins: Option<&DnsPins>,
+    idle_timeout: Option<Duration>,
+) -> Result<MarketFeedStream> {
+    let stream = match pins {
+        Some(pins) => MarketFeedStream::connect_pinned(client_id, access_token, pins).await?,
+        None => MarketFeedStream::connect(client_id, access_token).await?,
+    };
+    Ok(match idle_timeout {
+        Some(timeout) => stream.with_idle_timeout(timeout),
+        None => stream,
+    })
+}
+
+/// Send the remembered subscriptions, grouped by mode, 100 per message.
+async fn resubscribe(stream: &mut MarketFeedStream, subscriptions: &Subscriptions) -> Result<()> {
+    let mut by_mode: HashMap<FeedRequestCode, Vec<Instrument>> = HashMap::new();
+    for (inst, mode) in subscriptions
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+    {
+        by_mode.entry(*mode).or_default().push(inst.clone());
+    }
+    for (mode, instruments) in by_mode {
+        for chunk in instruments.chunks(100) {
+            stream.subscribe(mode, chunk).await?;
+        }
+    }
+    Ok(())
+}
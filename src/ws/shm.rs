@@ -0,0 +1,377 @@
+//! Shared-memory tick ring for co-located readers (feature `shm`).
+//!
+//! [`ShmRingPublisher`] writes normalized [`Tick`]s into a memory-mapped
+//! file (put it on `/dev/shm` for a RAM-only ring), so other processes on
+//! the same host — in any language — can read the feed without sockets.
+//! Any number of publishers (see [`ShmRingPublisher::attach`]) and readers
+//! may share one ring. Writers never wait for readers: a reader that falls
+//! more than the capacity behind loses the oldest ticks and can tell how
+//! many ([`ShmRingReader::dropped`]).
+//!
+//! # Layout
+//!
+//! Every field is a little-endian 64-bit word, 8-byte aligned, and must be
+//! accessed atomically (plain aligned 64-bit loads and stores on x86-64
+//! and AArch64).
+//!
+//! The file starts with a 128-byte header:
+//!
+//! | Word | Byte | Field                                                    |
+//! |------|------|----------------------------------------------------------|
+//! | 0    | 0    | Magic, the ASCII bytes `DHANRNG1`                        |
+//! | 1    | 8    | Layout version ([`LAYOUT_VERSION`])                      |
+//! | 2    | 16   | Capacity in slots (a power of two)                       |
+//! | 3    | 24   | Slot size in bytes ([`SLOT_BYTES`])                      |
+//! | 8    | 64   | Write cursor: the next sequence number to be claimed     |
+//!
+//! followed by `capacity` slots of 128 bytes. Sequence number `n` lives in
+//! slot `n % capacity`:
+//!
+//! | Word | Byte | Field                                                                |
+//! |------|------|----------------------------------------------------------------------|
+//! | 0    | 0    | Stamp: `n + 1` once written, `u64::MAX` while being written, 0 never |
+//! | 1    | 8    | Bits 0–31 security ID, 32–39 segment code, 40–47 presence flags      |
+//! | 2    | 16   | LTP (`f64`)                                                          |
+//! | 3    | 24   | Last trade time (feed epoch seconds, `i64`)                          |
+//! | 4    | 32   | Last traded quantity (`i64`, flag bit 0)                             |
+//! | 5    | 40   | Day volume (`i64`, flag bit 1)                                       |
+//! | 6–9  | 48   | Open, high, low, close (`f64`, flag bits 2–5)                        |
+//! | 10   | 80   | Open interest (`i64`, flag bit 6)                                    |
+//!
+//! Segment codes are the feed's ([`ExchangeSegment::segment_code`]).
+//!
+//! A publisher claims `n` by incrementing the write cursor, stores
+//! `u64::MAX` in the stamp, writes the fields, then stores `n + 1` with
+//! release ordering. A reader expecting `n` loads the stamp (acquire), and
+//! if it is `n + 1` reads the fields and loads the stamp again: if it is
+//! unchanged the copy is consistent, otherwise the slot was overwritten.
+//!
+//! ```no_run
+//! use dhan_rs::ws::shm::{ShmRingPublisher, ShmRingReader};
+//!
+//! # fn main() -> dhan_rs::Result<()> {
+//! let publisher = ShmRingPublisher::create("/dev/shm/dhan-ticks", 1 << 16)?;
+//! // In a consumer task: publisher.publish(&tick);
+//!
+//! // In another process:
+//! let mut reader = ShmRingReader::open("/dev/shm/dhan-ticks")?;
+//! while let Some(tick) = reader.try_read() {
+//!     println!("{} {}", tick.instrument, tick.ltp);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering, fence};
+
+use memmap2::MmapMut;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+use crate::error::{DhanError, Result};
+use crate::types::enums::ExchangeSegment;
+use crate::types::instrument::InstrumentId;
+use crate::ws::market_feed::{MarketFeedEvent, Tick};
+
+/// `DHANRNG1` as a little-endian word.
+const MAGIC: u64 = u64::from_le_bytes(*b"DHANRNG1");
+
+/// Version of the layout documented above.
+pub const LAYOUT_VERSION: u64 = 1;
+
+/// Size of the header and of each slot, in bytes.
+pub const SLOT_BYTES: usize = 128;
+
+const WORDS_PER_SLOT: usize = SLOT_BYTES / 8;
+const CURSOR_WORD: usize = 8;
+const BUSY: u64 = u64::MAX;
+
+/// A mapped ring file, viewed as 64-bit atomic words.
+#[derive(Debug)]
+struct Ring {
+    /// Keeps the mapping alive; only accessed through `ptr`.
+    _map: MmapMut,
+    /// Start of the mapping, taken mutably once so the atomics derived
+    /// from it may be written through.
+    ptr: *mut u8,
+    /// Length of the mapping in bytes.
+    len: usize,
+    capacity: u64,
+}
+
+// SAFETY: `ptr` points into `_map`, which lives as long as the `Ring` and
+// does not move when the `Ring` does; every access through it is atomic.
+unsafe impl Send for Ring {}
+// SAFETY: as above; shared access only ever creates `&AtomicU64`.
+unsafe impl Sync for Ring {}
+
+impl Ring {
+    fn map(path: &Path, create_capacity: Option<usize>) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(create_capacity.is_some())
+            .truncate(create_capacity.is_some())
+            .open(path)?;
+        if let Some(capacity) = create_capacity {
+            file.set_len(((capacity + 1) * SLOT_BYTES) as u64)?;
+        }
+        // SAFETY: the ring is only accessed through aligned atomic words
+        // (see `word`), so concurrent writes by other processes cannot
+        // cause torn reads; a file truncated underneath the mapping is the
+        // caller's responsibility, as with any shared mapping.
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        if map.len() < SLOT_BYTES {
+            return Err(DhanError::InvalidArgument(format!(
+                "{} is too small for a tick ring",
+                path.display()
+            )));
+        }
+        let mut ring = Self {
+            ptr: map.as_mut_ptr(),
+            len: map.len(),
+            _map: map,
+            capacity: 0,
+        };
+
+        if let Some(capacity) = create_capacity {
+            ring.word(1).store(LAYOUT_VERSION, Ordering::Relaxed);
+            ring.word(2).store(capacity as u64, Ordering::Relaxed);
+            ring.word(3).store(SLOT_BYTES as u64, Ordering::Relaxed);
+            ring.word(0).store(MAGIC, Ordering::Release);
+        }
+        if ring.word(0).load(Ordering::Acquire) != MAGIC
+            || ring.word(1).load(Ordering::Relaxed) != LAYOUT_VERSION
+            || ring.word(3).load(Ordering::Relaxed) != SLOT_BYTES as u64
+        {
+            return Err(DhanError::InvalidArgument(format!(
+                "{} is not a version {LAYOUT_VERSION} tick ring",
+                path.display()
+            )));
+        }
+        let capacity = ring.word(2).load(Ordering::Relaxed);
+        let expected = (capacity as usize + 1) * SLOT_BYTES;
+        if !capacity.is_power_of_two() || ring.len < expected {
+            return Err(DhanError::InvalidArgument(format!(
+                "{} has an invalid capacity",
+                path.display()
+            )));
+        }
+        ring.capacity = capacity;
+        Ok(ring)
+    }
+
+    /// The `index`-th 64-bit word of the file.
+    fn word(&self, index: usize) -> &AtomicU64 {
+        assert!(
+            (index + 1) * 8 <= self.len,
+            "ring word {index} out of bounds"
+        );
+        // SAFETY: the word is in bounds (checked above) and 8-byte aligned
+        // (the mapping is page-aligned and `index * 8` is a multiple of 8);
+        // `ptr` came from `MmapMut::as_mut_ptr`, so writing through it is
+        // allowed, `AtomicU64` has the size and alignment of `u64` and
+        // every access to the ring goes through atomics.
+        unsafe { AtomicU64::from_ptr(self.ptr.add(index * 8).cast()) }
+    }
+
+    fn cursor(&self) -> &AtomicU64 {
+        self.word(CURSOR_WORD)
+    }
+
+    /// Word `field` of the slot holding sequence number `seq`.
+    fn slot(&self, seq: u64, field: usize) -> &AtomicU64 {
+        let slot = (seq & (self.capacity - 1)) as usize;
+        self.word((slot + 1) * WORDS_PER_SLOT + field)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Publisher
+// ---------------------------------------------------------------------------
+
+/// Writes ticks into a shared-memory ring.
+#[derive(Debug)]
+pub struct ShmRingPublisher {
+    ring: Ring,
+}
+
+impl ShmRingPublisher {
+    /// Create (or reset) the ring at `path` with room for `capacity` ticks,
+    /// rounded up to a power of two.
+    pub fn create(path: impl AsRef<Path>, capacity: usize) -> Result<Self> {
+        let capacity = capacity.max(2).next_power_of_two();
+        Ok(Self {
+            ring: Ring::map(path.as_ref(), Some(capacity))?,
+        })
+    }
+
+    /// Publish into an existing ring alongside its other publishers.
+    pub fn attach(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            ring: Ring::map(path.as_ref(), None)?,
+        })
+    }
+
+    /// Number of slots.
+    pub fn capacity(&self) -> u64 {
+        self.ring.capacity
+    }
+
+    /// Append `tick`, returning its sequence number.
+    pub fn publish(&self, tick: &Tick) -> u64 {
+        let ring = &self.ring;
+        let seq = ring.cursor().fetch_add(1, Ordering::AcqRel);
+        ring.slot(seq, 0).store(BUSY, Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        let mut flags = 0u64;
+        let mut put = |field: usize, bit: u32, value: Option<u64>| {
+            if let Some(v) = value {
+                flags |= 1 << bit;
+                ring.slot(seq, field).store(v, Ordering::Relaxed);
+            }
+        };
+        put(4, 0, tick.last_qty.map(|v| v as u64));
+        put(5, 1, tick.volume.map(|v| v as u64));
+        put(6, 2, tick.open.map(f64::to_bits));
+        put(7, 3, tick.high.map(f64::to_bits));
+        put(8, 4, tick.low.map(f64::to_bits));
+        put(9, 5, tick.close.map(f64::to_bits));
+        put(10, 6, tick.oi.map(|v| v as u64));
+        let meta = u64::from(tick.instrument.security_id)
+            | u64::from(tick.instrument.segment.segment_code()) << 32
+            | flags << 40;
+        ring.slot(seq, 1).store(meta, Ordering::Relaxed);
+        ring.slot(seq, 2)
+            .store(tick.ltp.to_bits(), Ordering::Relaxed);
+        ring.slot(seq, 3).store(tick.ltt as u64, Ordering::Relaxed);
+
+        ring.slot(seq, 0).store(seq + 1, Ordering::Release);
+        seq
+    }
+
+    /// Publish the ticks of `rx` (Ticker, Quote and Full events) in the
+    /// background until the channel closes.
+    pub fn spawn(self, mut rx: broadcast::Receiver<MarketFeedEvent>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => {
+                        if let Some(tick) = event.to_tick() {
+                            self.publish(&tick);
+                        }
+                    }
+                    Err(RecvError::Lagged(n)) => {
+                        tracing::warn!(dropped = n, "Shared-memory publisher fell behind");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reader
+// ---------------------------------------------------------------------------
+
+/// Reads ticks from a shared-memory ring, for Rust readers and as a
+/// reference implementation of the protocol.
+#[derive(Debug)]
+pub struct ShmRingReader {
+    ring: Ring,
+    next: u64,
+    dropped: u64,
+}
+
+impl ShmRingReader {
+    /// Open the ring at `path`, starting after the last published tick.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let ring = Ring::map(path.as_ref(), None)?;
+        let next = ring.cursor().load(Ordering::Acquire);
+        Ok(Self {
+            ring,
+            next,
+            dropped: 0,
+        })
+    }
+
+    /// Move back to the oldest tick still in the ring.
+    pub fn rewind(&mut self) {
+        let head = self.ring.cursor().load(Ordering::Acquire);
+        self.next = head.saturating_sub(self.ring.capacity);
+    }
+
+    /// The next tick, or `None` if none has been published yet.
+    ///
+    /// Ticks overwritten before they were read are skipped and counted in
+    /// [`Self::dropped`].
+    pub fn try_read(&mut self) -> Option<Tick> {
+        loop {
+            let head = self.ring.cursor().load(Ordering::Acquire);
+            if self.next >= head {
+                return None;
+            }
+            if head - self.next > self.ring.capacity {
+                let oldest = head - self.ring.capacity;
+                self.dropped += oldest - self.next;
+                self.next = oldest;
+            }
+
+            let seq = self.next;
+            let stamp = self.ring.slot(seq, 0).load(Ordering::Acquire);
+            if stamp == BUSY || stamp < seq + 1 {
+                // Claimed but not yet written.
+                return None;
+            }
+            if stamp > seq + 1 {
+                // Overwritten by a later lap; the head check skips ahead.
+                continue;
+            }
+            let words: [u64; 11] =
+                std::array::from_fn(|field| self.ring.slot(seq, field).load(Ordering::Relaxed));
+            fence(Ordering::Acquire);
+            if self.ring.slot(seq, 0).load(Ordering::Relaxed) != stamp {
+                continue;
+            }
+            self.next += 1;
+            match decode(&words) {
+                Some(tick) => return Some(tick),
+                None => self.dropped += 1,
+            }
+        }
+    }
+
+    /// Ticks lost because they were overwritten before being read.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Number of slots.
+    pub fn capacity(&self) -> u64 {
+        self.ring.capacity
+    }
+}
+
+/// The tick in a slot's words, or `None` if its segment is unknown.
+fn decode(words: &[u64; 11]) -> Option<Tick> {
+    let meta = words[1];
+    let segment = ExchangeSegment::from_segment_code((meta >> 32) as u8)?;
+    let flags = meta >> 40;
+    let get = |field: usize, bit: u32| (flags & (1 << bit) != 0).then_some(words[field]);
+    Some(Tick {
+        instrument: InstrumentId::new(segment, meta as u32),
+        ltp: f64::from_bits(words[2]),
+        ltt: words[3] as i64,
+        last_qty: get(4, 0).map(|v| v as i64),
+        volume: get(5, 1).map(|v| v as i64),
+        open: get(6, 2).map(f64::from_bits),
+        high: get(7, 3).map(f64::from_bits),
+        low: get(8, 4).map(f64::from_bits),
+        close: get(9, 5).map(f64::from_bits),
+        oi: get(10, 6).map(|v| v as i64),
+    })
+}
@@ -0,0 +1,193 @@
+//! Sharing the account's feed connection limit between processes.
+//!
+//! Dhan allows five market feed connections per account; a sixth is
+//! refused or knocks another off with Disconnect reason 805. Two daemons
+//! on one host using the same credentials cannot see each other's
+//! sockets, so [`ConnectionSlots`] coordinates them cooperatively: each
+//! connection first claims one of the account's slots, and holds it for as
+//! long as the returned [`SlotGuard`] lives. A slot is either
+//!
+//! - a TCP port on the loopback interface, bound while held — released by
+//!   the OS whenever the holder exits, however it exits — or
+//! - a lock file in a shared directory, advisory-locked (`flock` on Unix,
+//!   `LockFileEx` on Windows) while held — usable where ports are not, and
+//!   likewise released by the OS when the holder exits. The file records
+//!   the holder's process ID for diagnostics and is left in place.
+//!
+//! Give a [`DhanFeedManager`] the slots with
+//! [`DhanFeedManager::with_connection_slots`]; it then opens only as many
+//! connections as there are free slots.
+//!
+//! ```
+//! use dhan_rs::ws::slots::ConnectionSlots;
+//!
+//! let dir = std::env::temp_dir().join(format!("dhan-rs-slots-doc-{}", std::process::id()));
+//! let slots = ConnectionSlots::directory(&dir).with_limit(2);
+//! let first = slots.try_acquire()?.unwrap();
+//! let _second = slots.try_acquire()?.unwrap();
+//! assert!(slots.try_acquire()?.is_none());
+//!
+//! drop(first);
+//! assert_eq!(slots.in_use()?, 1);
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok::<(), dhan_rs::DhanError>(())
+//! ```
+//!
+//! [`DhanFeedManager`]: crate::ws::manager::DhanFeedManager
+//! [`DhanFeedManager::with_connection_slots`]: crate::ws::manager::DhanFeedManager::with_connection_slots
+
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+
+use fs4::{FileExt, TryLockError};
+
+use crate::constants::rate_limits::websocket::MAX_CONNECTIONS;
+use crate::error::Result;
+
+#[derive(Debug, Clone)]
+enum Coordination {
+    Ports(u16),
+    Directory(PathBuf),
+}
+
+/// The feed connection slots of one account, shared by every process on
+/// the host configured with the same ports or directory.
+#[derive(Debug, Clone)]
+pub struct ConnectionSlots {
+    coordination: Coordination,
+    limit: u8,
+}
+
+impl ConnectionSlots {
+    /// Slots as loopback TCP ports `base_port .. base_port + limit`.
+    pub fn ports(base_port: u16) -> Self {
+        Self {
+            coordination: Coordination::Ports(base_port),
+            limit: MAX_CONNECTIONS as u8,
+        }
+    }
+
+    /// Slots as lock files `slot-<n>.lock` in `dir` (created if missing).
+    pub fn directory(dir: impl AsRef<Path>) -> Self {
+        Self {
+            coordination: Coordination::Directory(dir.as_ref().to_path_buf()),
+            limit: MAX_CONNECTIONS as u8,
+        }
+    }
+
+    /// Share `limit` slots instead of the account's five, e.g. to keep
+    /// some for a process that does not coordinate.
+    pub fn with_limit(mut self, limit: u8) -> Self {
+        self.limit = limit.min(MAX_CONNECTIONS as u8);
+        self
+    }
+
+    /// Number of slots shared.
+    pub fn limit(&self) -> u8 {
+        self.limit
+    }
+
+    /// Claim the lowest free slot, or `None` if all are held.
+    pub fn try_acquire(&self) -> Result<Option<SlotGuard>> {
+        for slot in 0..self.limit {
+            if let Some(guard) = self.claim(slot)? {
+                return Ok(Some(guard));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Claim up to `n` free slots.
+    pub fn acquire_up_to(&self, n: usize) -> Result<Vec<SlotGuard>> {
+        let mut guards = Vec::new();
+        for slot in 0..self.limit {
+            if guards.len() == n {
+                break;
+            }
+            guards.extend(self.claim(slot)?);
+        }
+        Ok(guards)
+    }
+
+    /// Number of slots currently held, by any process.
+    pub fn in_use(&self) -> Result<usize> {
+        let mut held = 0;
+        for slot in 0..self.limit {
+            // Probing claims a free slot for a moment and releases it.
+            if self.claim(slot)?.is_none() {
+                held += 1;
+            }
+        }
+        Ok(held)
+    }
+
+    fn claim(&self, slot: u8) -> Result<Option<SlotGuard>> {
+        match &self.coordination {
+            Coordination::Ports(base) => {
+                let port = base.saturating_add(u16::from(slot));
+                match TcpListener::bind((Ipv4Addr::LOCALHOST, port)) {
+                    Ok(listener) => Ok(Some(SlotGuard {
+                        slot,
+                        _held: Held::Port {
+                            _listener: listener,
+                        },
+                    })),
+                    Err(e) if e.kind() == ErrorKind::AddrInUse => Ok(None),
+                    Err(e) => Err(e.into()),
+                }
+            }
+            Coordination::Directory(dir) => {
+                std::fs::create_dir_all(dir)?;
+                lock_file(slot, &dir.join(format!("slot-{slot}.lock")))
+            }
+        }
+    }
+}
+
+/// Take the advisory lock on the file at `path`, creating it if missing.
+///
+/// The file is never removed: unlinking it while another process has it
+/// open would let two holders lock different files for the same slot.
+fn lock_file(slot: u8, path: &Path) -> Result<Option<SlotGuard>> {
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    // Called through the trait: `File::try_lock` is inherent since Rust
+    // 1.89 and has a different error type.
+    match FileExt::try_lock(&file) {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => return Ok(None),
+        Err(TryLockError::Error(e)) => return Err(e.into()),
+    }
+    file.set_len(0)?;
+    write!(file, "{}", std::process::id())?;
+    Ok(Some(SlotGuard {
+        slot,
+        _held: Held::File { _file: file },
+    }))
+}
+
+#[derive(Debug)]
+enum Held {
+    Port { _listener: TcpListener },
+    File { _file: File },
+}
+
+/// A held connection slot, released on drop.
+#[derive(Debug)]
+pub struct SlotGuard {
+    slot: u8,
+    _held: Held,
+}
+
+impl SlotGuard {
+    /// The slot's index.
+    pub fn slot(&self) -> u8 {
+        self.slot
+    }
+}
@@ -0,0 +1,180 @@
+//! REST snapshot bootstrap for new Quote/Full subscriptions.
+//!
+//! A freshly subscribed instrument has no state until its first live packet,
+//! which for an illiquid contract can take minutes. With
+//! [`DhanFeedManager::with_snapshot_bootstrap`], every Quote or Full
+//! subscription also fetches a REST quote (`POST /v2/marketfeed/quote`) for
+//! its instruments and emits it on the connection's parsed channel as a
+//! synthetic [`MarketFeedEvent::Quote`] or [`MarketFeedEvent::Full`].
+//!
+//! Synthetic events have a `message_length` of `0`, which no real packet
+//! has; use [`is_snapshot`] to tell them apart. Requests carry up to
+//! [`MAX_INSTRUMENTS_PER_REQUEST`] instruments and are spaced
+//! [`REST_QUOTE_INTERVAL`] apart.
+//!
+//! [`DhanFeedManager::with_snapshot_bootstrap`]: super::manager::DhanFeedManager::with_snapshot_bootstrap
+//! [`MAX_INSTRUMENTS_PER_REQUEST`]: crate::constants::rate_limits::market_quote::MAX_INSTRUMENTS_PER_REQUEST
+
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+use crate::cache::REST_QUOTE_INTERVAL;
+use crate::client::DhanClient;
+use crate::constants::rate_limits::market_quote::MAX_INSTRUMENTS_PER_REQUEST;
+use crate::time::parse_naive;
+use crate::types::enums::FeedResponseCode;
+use crate::types::instrument::InstrumentId;
+use crate::types::market_quote::{MarketQuoteRequest, QuoteData};
+use crate::ws::market_feed::{DepthLevel, MarketFeedEvent, PacketHeader};
+
+/// Build the synthetic feed event for a REST quote: a
+/// [`MarketFeedEvent::Full`] if `full`, otherwise a
+/// [`MarketFeedEvent::Quote`].
+///
+/// Values that do not fit the packet's field widths are clamped; missing
+/// values are zero, as in a packet for an untraded instrument.
+pub fn snapshot_event(id: InstrumentId, quote: &QuoteData, full: bool) -> MarketFeedEvent {
+    let header = PacketHeader {
+        response_code: if full {
+            FeedResponseCode::Full
+        } else {
+            FeedResponseCode::Quote
+        },
+        message_length: 0,
+        exchange_segment: Some(id.segment),
+        exchange_segment_raw: id.segment.segment_code(),
+        security_id: id.security_id,
+    };
+    let ltp = quote.last_price as f32;
+    let last_qty = quote.last_quantity.unwrap_or(0).clamp(0, i16::MAX.into()) as i16;
+    // Feed times are IST wall-clock seconds, which is what the naive
+    // REST timestamp holds.
+    let ltt = quote
+        .last_trade_time
+        .as_deref()
+        .and_then(parse_naive)
+        .map_or(0, |t| clamp_i32(t.and_utc().timestamp()));
+    let atp = quote.average_price.unwrap_or(0.0) as f32;
+    let volume = clamp_i32(quote.volume.unwrap_or(0));
+    let total_sell_qty = clamp_i32(quote.sell_quantity.unwrap_or(0));
+    let total_buy_qty = clamp_i32(quote.buy_quantity.unwrap_or(0));
+    let (open, close, high, low) = quote.ohlc.as_ref().map_or((0.0, 0.0, 0.0, 0.0), |o| {
+        (o.open as f32, o.close as f32, o.high as f32, o.low as f32)
+    });
+
+    if !full {
+        return MarketFeedEvent::Quote {
+            header,
+            ltp,
+            last_qty,
+            ltt,
+            atp,
+            volume,
+            total_sell_qty,
+            total_buy_qty,
+            open,
+            close,
+            high,
+            low,
+        };
+    }
+
+    let mut depth = [DepthLevel {
+        bid_qty: 0,
+        ask_qty: 0,
+        bid_orders: 0,
+        ask_orders: 0,
+        bid_price: 0.0,
+        ask_price: 0.0,
+    }; 5];
+    if let Some(book) = &quote.depth {
+        for (level, bid) in depth.iter_mut().zip(&book.buy) {
+            level.bid_qty = clamp_i32(bid.quantity);
+            level.bid_orders = bid.orders.clamp(0, i16::MAX.into()) as i16;
+            level.bid_price = bid.price as f32;
+        }
+        for (level, ask) in depth.iter_mut().zip(&book.sell) {
+            level.ask_qty = clamp_i32(ask.quantity);
+            level.ask_orders = ask.orders.clamp(0, i16::MAX.into()) as i16;
+            level.ask_price = ask.price as f32;
+        }
+    }
+    MarketFeedEvent::Full {
+        header,
+        ltp,
+        last_qty,
+        ltt,
+        atp,
+        volume,
+        total_sell_qty,
+        total_buy_qty,
+        oi: clamp_i32(quote.oi.unwrap_or(0)),
+        oi_day_high: clamp_i32(quote.oi_day_high.unwrap_or(0)),
+        oi_day_low: clamp_i32(quote.oi_day_low.unwrap_or(0)),
+        open,
+        close,
+        high,
+        low,
+        depth,
+    }
+}
+
+/// Returns `true` for events built by [`snapshot_event`] rather than
+/// parsed from a packet.
+pub fn is_snapshot(event: &MarketFeedEvent) -> bool {
+    event.header().message_length == 0
+}
+
+fn clamp_i32(v: i64) -> i32 {
+    v.clamp(i32::MIN.into(), i32::MAX.into()) as i32
+}
+
+/// Fetch REST quotes for `targets` and send each snapshot on the channel of
+/// its connection, in the background.
+///
+/// Failed requests are logged and skipped: the live feed still delivers
+/// those instruments, only later.
+pub(crate) fn spawn_bootstrap(
+    client: DhanClient,
+    targets: Vec<(InstrumentId, broadcast::Sender<MarketFeedEvent>)>,
+    full: bool,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let batches: Vec<_> = targets
+            .chunks(MAX_INSTRUMENTS_PER_REQUEST as usize)
+            .map(<[_]>::to_vec)
+            .collect();
+        for (i, batch) in batches.into_iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(REST_QUOTE_INTERVAL).await;
+            }
+            let mut req = MarketQuoteRequest::new();
+            for (id, _) in &batch {
+                req.entry(id.segment.to_string())
+                    .or_default()
+                    .push(u64::from(id.security_id));
+            }
+            let resp = match client.get_quote(&req).await {
+                Ok(resp) => resp,
+                Err(e) => {
+                    tracing::warn!(
+                        count = batch.len(),
+                        error = %e,
+                        "Snapshot bootstrap request failed"
+                    );
+                    continue;
+                }
+            };
+            let mut sent = 0;
+            for (id, tx) in batch {
+                let Some(quote) = resp.get(&id) else {
+                    continue;
+                };
+                if tx.send(snapshot_event(id, quote, full)).is_ok() {
+                    sent += 1;
+                }
+            }
+            tracing::debug!(sent, "Snapshot bootstrap batch emitted");
+        }
+    })
+}
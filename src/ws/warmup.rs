@@ -0,0 +1,174 @@
+//! Paced bulk subscription ("warm-up") progress.
+//!
+//! Subscribing tens of thousands of instruments sends hundreds of subscribe
+//! messages; sending them back to back floods the sockets.
+//! [`DhanFeedManager::warm_subscribe`] sends them in the background at
+//! [`DhanFeedConfig::warmup_messages_per_sec`] and returns a
+//! [`WarmupHandle`] that reports progress and resolves once every
+//! instrument has sent its first packet, or the warm-up times out.
+//!
+//! Dhan does not acknowledge subscribe messages, so an instrument counts as
+//! acknowledged when its first packet arrives. Instruments that never trade
+//! (illiquid contracts in Ticker mode) may only be covered by the timeout.
+//!
+//! ```no_run
+//! use dhan_rs::types::enums::FeedRequestCode;
+//! use dhan_rs::ws::manager::DhanFeedManagerBuilder;
+//! use dhan_rs::ws::market_feed::Instrument;
+//!
+//! # #[tokio::main]
+//! # async fn main() -> dhan_rs::Result<()> {
+//! # let instruments: Vec<Instrument> = Vec::new();
+//! let mut manager = DhanFeedManagerBuilder::new("client-id", "token")
+//!     .warmup_messages_per_sec(5)
+//!     .build();
+//! manager.start().await?;
+//!
+//! let warmup = manager.warm_subscribe(&instruments, FeedRequestCode::SubscribeQuote)?;
+//! let mut progress = warmup.watch();
+//! tokio::spawn(async move {
+//!     while progress.changed().await.is_ok() {
+//!         let p = *progress.borrow();
+//!         println!("{}/{} batches, {:.0}% live", p.batches_sent, p.total_batches, p.fraction() * 100.0);
+//!     }
+//! });
+//! let done = warmup.await_warmup().await;
+//! println!("warm-up complete: {}", !done.timed_out);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`DhanFeedManager::warm_subscribe`]: super::manager::DhanFeedManager::warm_subscribe
+//! [`DhanFeedConfig::warmup_messages_per_sec`]: super::manager::DhanFeedConfig::warmup_messages_per_sec
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::types::instrument::InstrumentId;
+
+/// Progress of a warm-up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct WarmupProgress {
+    /// Subscribe messages to send.
+    pub total_batches: usize,
+    /// Subscribe messages sent.
+    pub batches_sent: usize,
+    /// Instruments being subscribed.
+    pub total_instruments: usize,
+    /// Instruments whose first packet has arrived.
+    pub acknowledged: usize,
+    /// Subscribe messages that failed to send.
+    pub failed_batches: usize,
+    /// Set when [`WarmupHandle::await_warmup`] gave up waiting (timeout or
+    /// all connections closed).
+    pub timed_out: bool,
+}
+
+impl WarmupProgress {
+    /// Returns `true` once every batch was sent and every instrument
+    /// acknowledged.
+    pub fn is_complete(&self) -> bool {
+        self.batches_sent + self.failed_batches >= self.total_batches
+            && self.acknowledged >= self.total_instruments
+    }
+
+    /// Fraction of instruments acknowledged (`1.0` when there are none).
+    pub fn fraction(&self) -> f64 {
+        if self.total_instruments == 0 {
+            1.0
+        } else {
+            self.acknowledged as f64 / self.total_instruments as f64
+        }
+    }
+}
+
+/// Instruments still waiting for their first packet.
+#[derive(Debug, Default)]
+pub(crate) struct AckTracker {
+    pending: HashSet<InstrumentId>,
+}
+
+impl AckTracker {
+    pub(crate) fn new(pending: HashSet<InstrumentId>) -> Self {
+        Self { pending }
+    }
+
+    /// Mark `id` acknowledged; returns `true` if it was pending.
+    pub(crate) fn ack(&mut self, id: &InstrumentId) -> bool {
+        self.pending.remove(id)
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Handle to a warm-up running in the background.
+///
+/// Dropping the handle stops acknowledgement tracking; the remaining
+/// subscribe messages are still sent.
+#[derive(Debug)]
+pub struct WarmupHandle {
+    progress: watch::Receiver<WarmupProgress>,
+    timeout: Duration,
+    ack_task: Option<JoinHandle<()>>,
+}
+
+impl WarmupHandle {
+    pub(crate) fn new(
+        progress: watch::Receiver<WarmupProgress>,
+        timeout: Duration,
+        ack_task: JoinHandle<()>,
+    ) -> Self {
+        Self {
+            progress,
+            timeout,
+            ack_task: Some(ack_task),
+        }
+    }
+
+    /// Current progress.
+    pub fn progress(&self) -> WarmupProgress {
+        *self.progress.borrow()
+    }
+
+    /// A receiver that changes on every progress update, for reporting.
+    pub fn watch(&self) -> watch::Receiver<WarmupProgress> {
+        self.progress.clone()
+    }
+
+    /// Wait until every batch is sent and every instrument acknowledged,
+    /// or the warm-up timeout passes. Returns the final progress.
+    pub async fn await_warmup(mut self) -> WarmupProgress {
+        let mut rx = self.progress.clone();
+        let done =
+            tokio::time::timeout(self.timeout, rx.wait_for(WarmupProgress::is_complete)).await;
+        let mut progress = *self.progress.borrow();
+        // The channel also closes if every connection went away mid-way.
+        if !matches!(done, Ok(Ok(_))) {
+            progress.timed_out = true;
+            tracing::warn!(
+                acknowledged = progress.acknowledged,
+                total = progress.total_instruments,
+                sent = progress.batches_sent,
+                "Feed warm-up timed out"
+            );
+        }
+        if let Some(task) = self.ack_task.take() {
+            task.abort();
+        }
+        progress
+    }
+}
+
+impl Drop for WarmupHandle {
+    fn drop(&mut self) {
+        if let Some(task) = self.ack_task.take() {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Tests for intraday analytics.
+
+use std::time::Duration;
+
+use dhan_rs::analytics::rolling::{RollingStats, Window};
+use dhan_rs::analytics::synthetic::{SyntheticFormula, SyntheticSeries};
+use dhan_rs::analytics::volume_profile::{MarketProfile, VolumeProfile};
+use dhan_rs::types::enums::ExchangeSegment;
+use dhan_rs::types::historical::Candle;
+use dhan_rs::types::instrument::InstrumentId;
+
+fn bar(timestamp: i64, low: f64, high: f64, volume: f64) -> Candle {
+    Candle {
+        timestamp,
+        open: low,
+        high,
+        low,
+        close: high,
+        volume,
+        open_interest: None,
+    }
+}
+
+#[test]
+fn test_volume_profile_spreads_candle_volume() {
+    let candles = [bar(0, 100.0, 101.0, 300.0), bar(60, 101.0, 101.0, 600.0)];
+    let profile = VolumeProfile::from_candles(0.5, &candles);
+
+    assert_eq!(
+        profile.levels(),
+        vec![(100.0, 100.0), (100.5, 100.0), (101.0, 700.0)]
+    );
+    assert_eq!(profile.poc(), Some(101.0));
+    let va = profile.value_area(0.7).unwrap();
+    assert_eq!((va.low, va.high, va.volume), (101.0, 101.0, 700.0));
+}
+
+#[test]
+fn test_market_profile_counts_tpos_per_period() {
+    let mut profile = MarketProfile::new(1.0, 1800);
+    profile.add_candle(&bar(0, 100.0, 102.0, 0.0));
+    profile.add_candle(&bar(60, 101.0, 103.0, 0.0));
+    profile.add_candle(&bar(1800, 101.0, 102.0, 0.0));
+    profile.add_candle(&bar(3600, 95.0, 96.0, 0.0));
+
+    assert_eq!(profile.period_count(), 3);
+    assert_eq!(profile.poc(), Some(101.0));
+    assert_eq!(profile.initial_balance(2), Some((100.0, 103.0)));
+}
+
+#[test]
+fn test_rolling_span_window_evicts_old_samples() {
+    let mut stats = RollingStats::new(Window::Span(Duration::from_secs(10)));
+    stats.push(0, 20.0, 100.0);
+    stats.push(5, 10.0, 100.0);
+    stats.push(9, 12.0, 300.0);
+    assert_eq!(stats.max(), Some(20.0));
+
+    // At t=12 the t=0 sample is more than 10 s old.
+    stats.push(12, 14.0, 100.0);
+    assert_eq!(stats.len(), 3);
+    assert_eq!(stats.max(), Some(14.0));
+    assert_eq!(stats.min(), Some(10.0));
+    assert_eq!(stats.vwap(), Some((1000.0 + 3600.0 + 1400.0) / 500.0));
+}
+
+#[test]
+fn test_synthetic_spread_withholds_stale_legs() {
+    let a = InstrumentId::new(ExchangeSegment::NSE_FNO, 1);
+    let b = InstrumentId::new(ExchangeSegment::NSE_FNO, 2);
+    let mut spread =
+        SyntheticSeries::new(SyntheticFormula::spread(a, b)).with_max_skew(Duration::from_secs(2));
+
+    assert!(spread.update(a, 100, 250.0).is_none());
+    assert_eq!(spread.update(b, 101, 240.0).unwrap().value, 10.0);
+    // B's price is now 4 s older than A's.
+    assert!(spread.update(a, 105, 252.0).is_none());
+    let point = spread.update(b, 105, 241.0).unwrap();
+    assert_eq!((point.time, point.value), (105, 11.0));
+}
+
+#[test]
+fn test_synthetic_index_builds_candles() {
+    use dhan_rs::analytics::synthetic::{BasketConstituent, BasketIndexConfig, SyntheticIndex};
+
+    let a = InstrumentId::new(ExchangeSegment::NSE_EQ, 1);
+    let b = InstrumentId::new(ExchangeSegment::NSE_EQ, 2);
+    let mut index = SyntheticIndex::new(BasketIndexConfig {
+        id: InstrumentId::new(ExchangeSegment::IDX_I, 900_001),
+        constituents: vec![
+            BasketConstituent {
+                instrument: a,
+                weight: 1.0,
+            },
+            BasketConstituent {
+                instrument: b,
+                weight: 1.0,
+            },
+        ],
+        divisor: 2.0,
+        base_value: None,
+        candle_secs: 60,
+    });
+
+    // Minute 0: values 100 then 110; minute 1 closes it.
+    let t0 = 1_100_000_040;
+    assert!(index.update(a, t0, 100.0).is_none());
+    assert_eq!(index.update(b, t0, 100.0).unwrap().tick.ltp, 100.0);
+    assert!(index.update(a, t0 + 2, 120.0).unwrap().closed.is_none());
+    // Leg A is now 56 s stale: withheld until it trades again.
+    assert!(index.update(b, t0 + 58, 100.0).is_none());
+    let update = index.update(a, t0 + 60, 80.0).unwrap();
+    assert_eq!(update.tick.instrument, index.id());
+    let candle = update.closed.unwrap();
+    assert_eq!(
+        (candle.open, candle.high, candle.close),
+        (100.0, 110.0, 110.0)
+    );
+    assert_eq!(index.flush().unwrap().open, 90.0);
+}
@@ -0,0 +1,56 @@
+//! Offline tests for the multicast bridge, over loopback unicast.
+
+use dhan_rs::bridge::multicast::{DATAGRAM_LEN, MulticastPublisher, MulticastSubscriber};
+use dhan_rs::types::enums::ExchangeSegment;
+use dhan_rs::types::instrument::InstrumentId;
+use dhan_rs::ws::market_feed::Tick;
+use tokio::net::UdpSocket;
+
+fn tick(security_id: u32, ltp: f64) -> Tick {
+    Tick {
+        instrument: InstrumentId::new(ExchangeSegment::NSE_EQ, security_id),
+        ltp,
+        ltt: 1_726_048_740,
+        last_qty: Some(10),
+        volume: Some(5_000),
+        open: None,
+        high: None,
+        low: None,
+        close: None,
+        oi: None,
+    }
+}
+
+#[tokio::test]
+async fn test_multicast_sequence_gap_detection() {
+    // Publisher -> relay (drops the second datagram) -> subscriber.
+    let relay = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let sub_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let sub_addr = sub_socket.local_addr().unwrap();
+    let mut subscriber = MulticastSubscriber::from_socket(sub_socket);
+
+    let pub_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let mut publisher = MulticastPublisher::from_socket(pub_socket, relay.local_addr().unwrap());
+    for id in 1..=3 {
+        publisher.send(&tick(id, id as f64 * 100.0)).await.unwrap();
+    }
+
+    let mut buf = [0u8; 256];
+    for i in 0..3 {
+        let (len, _) = relay.recv_from(&mut buf).await.unwrap();
+        assert_eq!(len, DATAGRAM_LEN);
+        if i != 1 {
+            relay.send_to(&buf[..len], sub_addr).await.unwrap();
+        }
+    }
+
+    let first = subscriber.recv().await.unwrap();
+    assert_eq!((first.seq, first.missed), (0, 0));
+    assert_eq!(first.session, publisher.session());
+    assert_eq!(first.tick, tick(1, 100.0));
+
+    let third = subscriber.recv().await.unwrap();
+    assert_eq!((third.seq, third.missed), (2, 1));
+    assert_eq!(third.tick.instrument.security_id, 3);
+    assert_eq!(subscriber.missed(), 1);
+}
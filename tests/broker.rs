@@ -0,0 +1,131 @@
+//! Offline tests for the paper broker.
+
+use dhan_rs::DhanClient;
+use dhan_rs::broker::Broker;
+use dhan_rs::broker::paper::PaperBroker;
+use dhan_rs::runtime::{RuntimeEvent, Strategy, StrategyContext, StrategyRunner};
+use dhan_rs::types::enums::*;
+use dhan_rs::types::historical::Candle;
+use dhan_rs::types::instrument::InstrumentId;
+use dhan_rs::types::orders::PlaceOrderRequest;
+use dhan_rs::ws::market_feed::{Tick, parse_packet};
+
+const RELIANCE: InstrumentId = InstrumentId {
+    segment: ExchangeSegment::NSE_EQ,
+    security_id: 1333,
+};
+
+fn order(side: TransactionType, order_type: OrderType) -> PlaceOrderRequest {
+    PlaceOrderRequest {
+        dhan_client_id: "1000000001".into(),
+        correlation_id: None,
+        transaction_type: side,
+        exchange_segment: ExchangeSegment::NSE_EQ,
+        product_type: ProductType::INTRADAY,
+        order_type,
+        validity: Validity::DAY,
+        security_id: "1333".into(),
+        quantity: 10,
+        disclosed_quantity: None,
+        price: None,
+        trigger_price: None,
+        after_market_order: None,
+        amo_time: None,
+        bo_profit_value: None,
+        bo_stop_loss_value: None,
+    }
+}
+
+fn candle(timestamp: i64, open: f64, high: f64, low: f64, close: f64) -> Candle {
+    Candle {
+        timestamp,
+        open,
+        high,
+        low,
+        close,
+        volume: 1000.0,
+        open_interest: None,
+    }
+}
+
+#[tokio::test]
+async fn test_paper_broker_fills_limit_and_stop_orders_on_candles() {
+    let paper = PaperBroker::new();
+    let mut updates = paper.subscribe_updates();
+    paper.on_candle(RELIANCE, &candle(1_726_041_600, 100.0, 100.5, 99.5, 100.0));
+
+    let mut buy = order(TransactionType::BUY, OrderType::LIMIT);
+    buy.price = Some(99.0);
+    let placed = paper.place_order(&buy).await.unwrap();
+    assert_eq!(placed.status(), Some(OrderStatus::PENDING));
+
+    // The bar trades through the limit: filled at the limit, not the low.
+    paper.on_candle(RELIANCE, &candle(1_726_041_660, 100.5, 101.0, 98.5, 99.5));
+    let mut stop = order(TransactionType::SELL, OrderType::STOP_LOSS_MARKET);
+    stop.trigger_price = Some(97.0);
+    paper.place_order(&stop).await.unwrap();
+    // A gap below the trigger fills at the open.
+    paper.on_candle(RELIANCE, &candle(1_726_041_720, 96.0, 96.5, 95.0, 95.5));
+
+    let orders = paper.get_orders().await.unwrap();
+    let fills: Vec<_> = orders.iter().map(|o| o.average_traded_price).collect();
+    assert_eq!(fills, [Some(99.0), Some(96.0)]);
+    assert_eq!(
+        orders[1].create_time.as_deref(),
+        Some("2024-09-11 13:31:00")
+    );
+
+    let position = &paper.get_positions().await.unwrap()[0];
+    assert_eq!(position.net_qty, Some(0));
+    assert_eq!(position.realized_profit, Some(-30.0));
+
+    let statuses: Vec<_> = std::iter::from_fn(|| updates.try_recv().ok())
+        .map(|u| (u.order_id.unwrap(), u.status.unwrap()))
+        .collect();
+    assert_eq!(
+        statuses,
+        [
+            ("PAPER-1".into(), OrderStatus::PENDING),
+            ("PAPER-1".into(), OrderStatus::TRADED),
+            ("PAPER-2".into(), OrderStatus::PENDING),
+            ("PAPER-2".into(), OrderStatus::TRADED),
+        ]
+    );
+}
+
+/// Buys whenever the price is below 101.
+struct BuyOnce;
+
+impl Strategy for BuyOnce {
+    fn name(&self) -> &str {
+        "buy-once"
+    }
+
+    fn on_tick(&mut self, ctx: &mut StrategyContext, tick: &Tick) {
+        if tick.ltp < 101.0 {
+            ctx.place_order(order(TransactionType::BUY, OrderType::MARKET));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_runner_places_orders_with_paper_broker() {
+    let paper = PaperBroker::new().with_slippage_bps(10.0);
+    let mut runner =
+        StrategyRunner::new(BuyOnce, DhanClient::new("1000000001", "t")).with_broker(paper.clone());
+
+    let mut buf = vec![2u8];
+    buf.extend_from_slice(&16u16.to_le_bytes());
+    buf.push(1);
+    buf.extend_from_slice(&1333u32.to_le_bytes());
+    buf.extend_from_slice(&100.0f32.to_le_bytes());
+    buf.extend_from_slice(&1_726_041_600i32.to_le_bytes());
+    let event = parse_packet(&buf).unwrap();
+
+    paper.on_event(&event);
+    runner.handle_event(RuntimeEvent::Market(event)).await;
+
+    let position = &paper.get_positions().await.unwrap()[0];
+    assert_eq!(position.net_qty, Some(10));
+    assert!((position.buy_avg.unwrap() - 100.1).abs() < 1e-9);
+}
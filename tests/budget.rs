@@ -0,0 +1,62 @@
+//! Tests for the daily/hourly order budget.
+
+use chrono::{Duration, TimeZone, Utc};
+use dhan_rs::budget::{BudgetWindow, OrderBudget};
+use dhan_rs::events::{EventBus, EventFilter, EventKind, RiskEvent, SessionEvent};
+use dhan_rs::{DhanClient, DhanError};
+
+#[tokio::test]
+async fn test_order_budget_warns_and_rolls_the_hour() {
+    let bus = EventBus::new();
+    let mut risk = bus.subscribe(EventFilter::kinds([EventKind::Risk]));
+    let budget = OrderBudget::new()
+        .with_limits(100, 4)
+        .with_warnings([0.5])
+        .with_hard_stop()
+        .with_event_bus(bus);
+
+    let t0 = Utc.with_ymd_and_hms(2024, 9, 2, 4, 0, 0).unwrap();
+    for i in 0..4 {
+        budget.record_at(t0 + Duration::minutes(i)).unwrap();
+    }
+    let remaining = budget.remaining_at(t0 + Duration::minutes(5));
+    assert_eq!(
+        (remaining.daily, remaining.hourly, remaining.orders()),
+        (96, 0, 0)
+    );
+    assert!(matches!(
+        budget.record_at(t0 + Duration::minutes(5)),
+        Err(DhanError::RiskRejected(_))
+    ));
+
+    // The first order leaves the rolling hour.
+    budget.record_at(t0 + Duration::minutes(60)).unwrap();
+    assert_eq!(budget.remaining_at(t0 + Duration::minutes(60)).daily, 95);
+
+    // Daily usage never reached half; hourly did, then was exhausted.
+    for used in [2, 4] {
+        let Some(SessionEvent::Risk(RiskEvent::OrderBudget(w))) = risk.recv().await else {
+            panic!("expected a budget warning");
+        };
+        assert_eq!((w.window, w.used, w.limit), (BudgetWindow::Hourly, used, 4));
+    }
+}
+
+#[tokio::test]
+async fn test_order_budget_blocks_client_requests() {
+    let client = DhanClient::with_base_url("client-id", "token", "http://127.0.0.1:1")
+        .with_order_budget(OrderBudget::new().with_limits(1, 1).with_hard_stop());
+
+    // Counted, then fails to connect.
+    assert!(matches!(
+        client.cancel_order("O1").await,
+        Err(DhanError::Http(_))
+    ));
+    assert!(matches!(
+        client.cancel_order("O2").await,
+        Err(DhanError::RiskRejected(_))
+    ));
+    // Reads are not counted.
+    assert!(client.get_orders().await.is_err());
+    assert_eq!(client.remaining_order_budget().unwrap().daily, 0);
+}
@@ -0,0 +1,223 @@
+//! Tests for persisted candle history.
+
+use chrono::NaiveDate;
+use dhan_rs::candles::history::CandleHistory;
+use dhan_rs::types::enums::ExchangeSegment;
+use dhan_rs::types::historical::Candle;
+use dhan_rs::types::instrument::InstrumentId;
+
+fn bar(timestamp: i64, close: f64) -> Candle {
+    Candle {
+        timestamp,
+        open: close,
+        high: close,
+        low: close,
+        close,
+        volume: 10.0,
+        open_interest: None,
+    }
+}
+
+#[test]
+fn test_history_persists_and_skips_stale_candles() {
+    let dir = std::env::temp_dir().join(format!("dhan-rs-candles-{}", std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    let id = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+    // 2024-09-11 09:20 and 09:21 IST.
+    let (t0, t1) = (1_726_026_600, 1_726_026_660);
+
+    let mut history = CandleHistory::open(&dir).unwrap();
+    assert!(history.insert(id, bar(t0, 100.0)).unwrap());
+    assert!(history.insert(id, bar(t1, 101.0)).unwrap());
+    assert!(!history.insert(id, bar(t0, 99.0)).unwrap());
+    drop(history);
+
+    let mut reopened = CandleHistory::open(&dir).unwrap();
+    let day = NaiveDate::from_ymd_opt(2024, 9, 11).unwrap();
+    let loaded = reopened.load(id, day).unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded[1].close, 101.0);
+    assert!(!reopened.insert(id, bar(t1, 102.0)).unwrap());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_candle_quality_report() {
+    use dhan_rs::candles::quality::{CandleIssue, TradingCalendar};
+    use dhan_rs::types::historical::CandleData;
+
+    let data = |timestamp: Vec<f64>| CandleData {
+        open: vec![100.0; timestamp.len()],
+        high: vec![101.0; timestamp.len()],
+        low: vec![99.0; timestamp.len()],
+        close: vec![100.5; timestamp.len()],
+        volume: vec![10.0; timestamp.len()],
+        timestamp,
+        open_interest: vec![],
+    };
+
+    // Daily candles at IST midnight: Fri 2024-09-06, Mon 09-09, Thu 09-12.
+    // The weekend is not a gap; Tue 09-10 is a holiday, Wed 09-11 is missing.
+    let day = |d: u32| {
+        let midnight = NaiveDate::from_ymd_opt(2024, 9, d)
+            .unwrap()
+            .and_hms_opt(0, 0, 0);
+        dhan_rs::time::ist_to_utc(midnight.unwrap()).timestamp() as f64
+    };
+    let calendar =
+        TradingCalendar::nse().with_holidays([NaiveDate::from_ymd_opt(2024, 9, 10).unwrap()]);
+    let report = data(vec![day(6), day(9), day(12)]).validate_daily(&calendar);
+    assert_eq!(report.missing_bars(), 1);
+
+    // 1-minute bars: 15:29 on 09-11, then 09:15 and 09:15 again on 09-12.
+    let close = 1_726_048_740.0;
+    let open = close + 63_960.0;
+    let mut bad = data(vec![close, open, open]);
+    bad.volume[1] = -5.0;
+    bad.low.pop();
+    let report = bad.validate_intraday(60, &TradingCalendar::nse());
+    assert_eq!(report.candles, 2);
+    assert_eq!(
+        report.issues,
+        vec![
+            CandleIssue::LengthMismatch {
+                field: "low",
+                len: 2,
+                expected: 3
+            },
+            CandleIssue::NegativeValue {
+                index: 1,
+                field: "volume",
+                value: -5.0
+            },
+        ]
+    );
+    bad.low.push(99.0);
+    assert!(matches!(
+        bad.validate_intraday(60, &TradingCalendar::nse()).issues[..],
+        [
+            CandleIssue::NegativeValue { .. },
+            CandleIssue::NonMonotonic { index: 2, .. }
+        ]
+    ));
+}
+
+#[test]
+fn test_trade_tape_infers_sizes_and_persists() {
+    use dhan_rs::candles::tape::{TapeBuilder, TapeStore};
+    use dhan_rs::ws::market_feed::Tick;
+
+    let id = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+    let tick = |ltp: f64, ltt: i64, volume: Option<i64>| Tick {
+        instrument: id,
+        ltp,
+        ltt,
+        last_qty: None,
+        volume,
+        open: None,
+        high: None,
+        low: None,
+        close: None,
+        oi: None,
+    };
+    let ltt = 1_100_000_000;
+
+    let mut tape = TapeBuilder::new();
+    assert_eq!(tape.on_tick(&tick(100.0, ltt, Some(1_000))), None);
+    let trade = tape.on_tick(&tick(100.5, ltt + 1, Some(1_250))).unwrap();
+    assert_eq!((trade.price, trade.size), (100.5, Some(250)));
+    // Repeated volume and Ticker packets add nothing.
+    assert_eq!(tape.on_tick(&tick(100.5, ltt + 1, Some(1_250))), None);
+    assert_eq!(tape.on_tick(&tick(100.6, ltt + 2, None)), None);
+
+    // A Ticker-only instrument prints on every change, without a size.
+    let other = Tick {
+        instrument: InstrumentId::new(ExchangeSegment::NSE_EQ, 11536),
+        ..tick(50.0, ltt, None)
+    };
+    assert_eq!(tape.on_tick(&other).unwrap().size, None);
+    assert_eq!(tape.on_tick(&other), None);
+
+    let dir = std::env::temp_dir().join(format!("dhan-rs-tape-{}", std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    let mut store = TapeStore::open(&dir).unwrap();
+    store.append(&trade).unwrap();
+    let day = dhan_rs::time::to_ist(chrono::DateTime::from_timestamp(trade.timestamp, 0).unwrap())
+        .date_naive();
+    assert_eq!(store.load(&id, day).unwrap(), vec![trade]);
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_multi_interval_candles_share_ticks() {
+    use dhan_rs::candles::MultiCandleAggregator;
+    use dhan_rs::types::historical::CandleData;
+    use dhan_rs::ws::market_feed::Tick;
+
+    let id = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+    // 2024-09-11 09:15:00 IST as a feed epoch.
+    let open = dhan_rs::time::unix_to_feed_epoch(1_726_026_300);
+    let tick = |ltp: f64, offset: i64| Tick {
+        instrument: id,
+        ltp,
+        ltt: open + offset,
+        last_qty: None,
+        volume: None,
+        open: None,
+        high: None,
+        low: None,
+        close: None,
+        oi: None,
+    };
+
+    let mut bars = MultiCandleAggregator::standard();
+    assert!(bars.on_tick(&tick(100.0, 0)).is_empty());
+    assert!(bars.on_tick(&tick(102.0, 30)).is_empty());
+    let closed = bars.on_tick(&tick(101.0, 60));
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].interval_secs, 60);
+    assert_eq!(closed[0].candle.timestamp, 1_726_026_300);
+
+    // 09:20 closes both the 1- and the 5-minute bar.
+    let closed = bars.on_tick(&tick(99.0, 300));
+    assert_eq!(
+        closed.iter().map(|c| c.interval_secs).collect::<Vec<_>>(),
+        vec![60, 300]
+    );
+    let five = closed[1].candle;
+    assert_eq!(
+        (five.open, five.high, five.low, five.close),
+        (100.0, 102.0, 100.0, 101.0)
+    );
+    assert_eq!(bars.current(900, &id).unwrap().low, 99.0);
+
+    let data = CandleData::from_candles(&[closed[0].candle, five]);
+    assert_eq!(data.len(), 2);
+    assert!(data.open_interest.is_empty());
+    assert_eq!(data.candles()[1], five);
+}
+
+#[test]
+fn test_candle_data_iterates_complete_candles() {
+    use dhan_rs::types::historical::CandleData;
+
+    // A truncated volume array: the last index is not a complete candle.
+    let data = CandleData {
+        open: vec![100.0, 101.0, 102.0],
+        high: vec![101.0, 102.0, 103.0],
+        low: vec![99.0, 100.0, 101.0],
+        close: vec![100.5, 101.5, 102.5],
+        volume: vec![10.0, 20.0],
+        timestamp: vec![60.0, 120.0, 180.0],
+        open_interest: vec![5.0, 6.0, 7.0],
+    };
+    assert_eq!(data.iter().len(), 2);
+    let last = data.iter().next_back().unwrap();
+    assert_eq!((last.timestamp, last.open_interest), (120, Some(6.0)));
+    assert!(data.candle(2).is_none());
+
+    let volume: f64 = (&data).into_iter().map(|c| c.volume).sum();
+    assert_eq!(volume, 30.0);
+    assert_eq!(data.candles(), data.clone().into_candles());
+}
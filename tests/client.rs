@@ -0,0 +1,483 @@
+//! Offline tests for client construction, against a local HTTP listener.
+
+use std::time::{Duration, Instant};
+
+use dhan_rs::{DhanClientBuilder, DhanError};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn test_builder_timeout_and_user_agent() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 4096];
+        let n = socket.read(&mut buf).await.unwrap();
+        let head = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+        // Answer too late.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        socket.write_all(b"HTTP/1.1 200 OK\r\n\r\n").await.ok();
+        head
+    });
+
+    let client = DhanClientBuilder::new("1000000001", "token")
+        .base_url(format!("http://127.0.0.1:{port}"))
+        .timeout(Duration::from_millis(200))
+        .user_agent("dhan-rs-test/1.0")
+        .build()
+        .unwrap();
+    let started = Instant::now();
+    let err = client.get_orders().await.unwrap_err();
+    assert!(
+        matches!(err, DhanError::Http(ref e) if e.is_timeout()),
+        "{err:?}"
+    );
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    let head = server.await.unwrap();
+    assert!(head.contains("user-agent: dhan-rs-test/1.0"));
+    assert!(head.contains("access-token: token"));
+}
+
+#[tokio::test]
+async fn test_clock_skew_from_date_header() {
+    use dhan_rs::DhanClient;
+    use dhan_rs::clock::{FeedClock, clock_skew};
+    use dhan_rs::types::enums::ExchangeSegment;
+    use dhan_rs::types::instrument::InstrumentId;
+    use dhan_rs::ws::market_feed::Tick;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            // A server clock 10 s ahead of ours.
+            let date = (chrono::Utc::now() + chrono::TimeDelta::seconds(10))
+                .format("%a, %d %b %Y %H:%M:%S GMT");
+            let response = format!(
+                "HTTP/1.1 401 Unauthorized\r\ndate: {date}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+            );
+            socket.write_all(response.as_bytes()).await.ok();
+        }
+    });
+
+    let client = DhanClient::with_base_url("1000000001", "t", format!("http://127.0.0.1:{port}"));
+    let skew = clock_skew(&client).await.unwrap();
+    assert_eq!(skew.samples, 3);
+    assert!(
+        (skew.offset.num_milliseconds() - 10_000).abs() <= 1_000,
+        "{skew:?}"
+    );
+    assert!(skew.exceeds(Duration::from_secs(5)));
+
+    // A trade stamped 3 s in the future (feed epochs are IST wall clock).
+    let now = chrono::Utc::now();
+    let tick = Tick {
+        instrument: InstrumentId::new(ExchangeSegment::NSE_EQ, 1333),
+        ltp: 1500.0,
+        ltt: dhan_rs::time::unix_to_feed_epoch(now.timestamp() + 3),
+        last_qty: None,
+        volume: None,
+        open: None,
+        high: None,
+        low: None,
+        close: None,
+        oi: None,
+    };
+    let mut feed = FeedClock::new();
+    feed.observe_at(&tick, now);
+    assert!(feed.skew().unwrap().exceeds(Duration::from_secs(2)));
+}
+
+#[tokio::test]
+async fn test_token_manager_renews_shared_token() {
+    use dhan_rs::DhanClient;
+    use dhan_rs::token::{Renewal, TokenManager, TotpLogin};
+    use dhan_rs::ws::manager::DhanFeedManagerBuilder;
+
+    // RFC 6238 test key "12345678901234567890".
+    let totp = TotpLogin::new("000000", "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ").unwrap();
+    let at = chrono::DateTime::from_timestamp(59, 0).unwrap();
+    assert_eq!(totp.code_at(at), "287082");
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 4096];
+        let n = socket.read(&mut buf).await.unwrap();
+        let head = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+        let body = r#"{"dhanClientId":"1000000001","accessToken":"fresh","expiryTime":"2030-01-01 09:00:00"}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        socket.write_all(response.as_bytes()).await.ok();
+        head
+    });
+
+    let client =
+        DhanClient::with_base_url("1000000001", "stale", format!("http://127.0.0.1:{port}"));
+    let clone = client.clone();
+    let other = DhanClient::new("1000000001", "unused").with_shared_token(client.shared_token());
+    let feed = DhanFeedManagerBuilder::new("1000000001", "unused")
+        .shared_token(client.shared_token())
+        .build();
+    let manager = TokenManager::new(client, Renewal);
+    let mut tokens = manager.subscribe();
+    manager.refresh_now().await.unwrap();
+
+    assert!(server.await.unwrap().contains("access-token: stale"));
+    assert_eq!(clone.access_token(), "fresh");
+    assert_eq!(other.access_token(), "fresh");
+    assert_eq!(feed.shared_token().get(), "fresh");
+    assert_eq!(*tokens.borrow_and_update(), "fresh");
+    assert_eq!(
+        manager.expires_at().unwrap().to_rfc3339(),
+        "2030-01-01T03:30:00+00:00"
+    );
+}
+
+#[tokio::test]
+async fn test_mock_transport_serves_canned_responses_in_order() {
+    use dhan_rs::DhanClient;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use dhan_rs::types::enums::*;
+    use dhan_rs::types::orders::PlaceOrderRequest;
+    use reqwest::Method;
+
+    let mock = MockTransport::new()
+        .on(
+            Method::POST,
+            "/v2/orders",
+            MockResponse::api_error(400, "DH-906", "Order error"),
+        )
+        .on(
+            Method::POST,
+            "/v2/orders",
+            MockResponse::json(
+                200,
+                serde_json::json!({"orderId": "112111182198", "orderStatus": "PENDING"}),
+            ),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+    let req = PlaceOrderRequest::builder()
+        .transaction_type(TransactionType::BUY)
+        .exchange_segment(ExchangeSegment::NSE_EQ)
+        .product_type(ProductType::INTRADAY)
+        .order_type(OrderType::MARKET)
+        .validity(Validity::DAY)
+        .security_id("1333")
+        .quantity(5);
+
+    let err = client.place_order_for_self(req.clone()).await.unwrap_err();
+    assert!(
+        matches!(err, DhanError::Api(ref body) if body.error_code.as_deref() == Some("DH-906"))
+    );
+    let placed = client.place_order_for_self(req).await.unwrap();
+    assert_eq!(placed.order_id, "112111182198");
+    // Unrouted requests get a 404 rather than reaching the network.
+    assert!(matches!(
+        client.get_positions().await,
+        Err(DhanError::HttpStatus { status, .. }) if status == 404
+    ));
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0].header("access-token"), Some("token"));
+    assert_eq!(requests[1].json().unwrap()["quantity"], 5);
+}
+
+#[tokio::test]
+async fn test_modify_order_price_keeps_current_fields() {
+    use dhan_rs::DhanClient;
+    use dhan_rs::error::DhanError;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use reqwest::Method;
+
+    let order = |status: &str| {
+        MockResponse::json(
+            200,
+            serde_json::json!({
+                "orderId": "112111182198",
+                "orderStatus": status,
+                "orderType": "STOP_LOSS",
+                "validity": "DAY",
+                "quantity": 10,
+                "disclosedQuantity": 0,
+                "price": 1500.0,
+                "triggerPrice": 1498.0
+            }),
+        )
+    };
+    let mock = MockTransport::new()
+        .on(Method::GET, "/v2/orders/112111182198", order("PENDING"))
+        .on(Method::GET, "/v2/orders/112111182198", order("TRADED"))
+        .on(
+            Method::PUT,
+            "/v2/orders/112111182198",
+            MockResponse::json(
+                200,
+                serde_json::json!({"orderId": "112111182198", "orderStatus": "PENDING"}),
+            ),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+
+    client
+        .modify_order_price("112111182198", 1502.5)
+        .await
+        .unwrap();
+    let err = client
+        .modify_order_quantity("112111182198", 20)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DhanError::InvalidArgument(_)));
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(
+        requests[1].json().unwrap(),
+        serde_json::json!({
+            "dhanClientId": "1000000001",
+            "orderId": "112111182198",
+            "orderType": "STOP_LOSS",
+            "quantity": 10,
+            "price": 1502.5,
+            "triggerPrice": 1498.0,
+            "validity": "DAY"
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_all_orders_reports_each_order() {
+    use dhan_rs::DhanClient;
+    use dhan_rs::oms::bulk::OrderFilter;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use dhan_rs::types::enums::ProductType;
+    use reqwest::Method;
+
+    let order = |id: &str, status: &str, product: &str| serde_json::json!({"orderId": id, "orderStatus": status, "productType": product});
+    let cancelled = |id: &str| {
+        MockResponse::json(
+            200,
+            serde_json::json!({"orderId": id, "orderStatus": "CANCELLED"}),
+        )
+    };
+    let mock = MockTransport::new()
+        .on(
+            Method::GET,
+            "/v2/orders",
+            MockResponse::json(
+                200,
+                serde_json::json!([
+                    order("1", "PENDING", "INTRADAY"),
+                    order("2", "TRADED", "INTRADAY"),
+                    order("3", "PART_TRADED", "INTRADAY"),
+                    order("4", "PENDING", "CNC"),
+                ]),
+            ),
+        )
+        .on(Method::DELETE, "/v2/orders/1", cancelled("1"));
+    let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+
+    let filter = OrderFilter::new().with_product_type(ProductType::INTRADAY);
+    let report = client.cancel_all_orders(&filter).await.unwrap();
+
+    assert_eq!(report.len(), 2);
+    assert_eq!(report.succeeded[0].order_id, "1");
+    let failed: Vec<_> = report.failed_inputs().map(|o| o.order_id.clone()).collect();
+    assert_eq!(failed, [Some("3".to_owned())]);
+    let deletes = mock
+        .requests()
+        .iter()
+        .filter(|r| r.method == Method::DELETE)
+        .count();
+    assert_eq!(deletes, 2);
+}
+
+#[tokio::test]
+async fn test_square_off_position_buys_back_short_at_ltp() {
+    use dhan_rs::DhanClient;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use dhan_rs::types::enums::OrderType;
+    use dhan_rs::types::portfolio::Position;
+    use reqwest::Method;
+
+    let position: Position = serde_json::from_value(serde_json::json!({
+        "securityId": "52175",
+        "positionType": "SHORT",
+        "exchangeSegment": "NSE_FNO",
+        "productType": "INTRADAY",
+        "netQty": -75
+    }))
+    .unwrap();
+    let mock = MockTransport::new()
+        .on(
+            Method::POST,
+            "/v2/marketfeed/ltp",
+            MockResponse::json(
+                200,
+                serde_json::json!({
+                    "status": "success",
+                    "data": {"NSE_FNO": {"52175": {"last_price": 112.35}}}
+                }),
+            ),
+        )
+        .on(
+            Method::POST,
+            "/v2/orders",
+            MockResponse::json(
+                200,
+                serde_json::json!({"orderId": "112111182200", "orderStatus": "PENDING"}),
+            ),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+
+    client
+        .square_off_position(&position, OrderType::LIMIT)
+        .await
+        .unwrap();
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    let body = requests[1].json().unwrap();
+    assert_eq!(body["transactionType"], "BUY");
+    assert_eq!(body["quantity"], 75);
+    assert_eq!(body["productType"], "INTRADAY");
+    assert_eq!(body["orderType"], "LIMIT");
+    assert_eq!(body["price"], 112.35);
+
+    let flat = Position {
+        net_qty: Some(0),
+        ..position
+    };
+    assert!(
+        client
+            .square_off_position(&flat, OrderType::MARKET)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_extra_headers_layer_under_auth() {
+    use dhan_rs::DhanClient;
+    use dhan_rs::client::RequestOptions;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use reqwest::Method;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    let mock = MockTransport::new().on(
+        Method::GET,
+        "/v2/orders",
+        MockResponse::json(200, serde_json::json!([])),
+    );
+    let mut partner = HeaderMap::new();
+    partner.insert("x-partner-id", HeaderValue::from_static("acme"));
+    partner.insert("access-token", HeaderValue::from_static("spoofed"));
+    let client = DhanClient::new("1000000001", "token")
+        .with_transport(mock.clone())
+        .with_headers(partner)
+        .with_api_version("2.1");
+
+    let options = RequestOptions::new()
+        .header("x-request-id", "abc-1")
+        .unwrap()
+        .header("x-partner-id", "acme-eu")
+        .unwrap();
+    client.with_options(&options).get_orders().await.unwrap();
+    client.get_orders().await.unwrap();
+    assert!(RequestOptions::new().header("bad header", "x").is_err());
+
+    let requests = mock.requests();
+    let scoped = &requests[0];
+    assert_eq!(scoped.header("access-token"), Some("token"));
+    assert_eq!(scoped.header("x-api-version"), Some("2.1"));
+    assert_eq!(scoped.header("x-partner-id"), Some("acme-eu"));
+    assert_eq!(scoped.header("x-request-id"), Some("abc-1"));
+    let plain = &requests[1];
+    assert_eq!(plain.header("x-partner-id"), Some("acme"));
+    assert_eq!(plain.header("x-request-id"), None);
+}
+
+#[tokio::test]
+async fn test_forecast_funds_prices_market_orders_at_ltp() {
+    use dhan_rs::DhanClient;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use dhan_rs::types::enums::*;
+    use dhan_rs::types::orders::PlaceOrderRequest;
+    use reqwest::Method;
+
+    let order = |security_id: &str, order_type, price: Option<f64>| {
+        let mut builder = PlaceOrderRequest::builder()
+            .dhan_client_id("1000000001")
+            .transaction_type(TransactionType::BUY)
+            .exchange_segment(ExchangeSegment::NSE_EQ)
+            .product_type(ProductType::INTRADAY)
+            .order_type(order_type)
+            .validity(Validity::DAY)
+            .security_id(security_id)
+            .quantity(10);
+        if let Some(price) = price {
+            builder = builder.price(price);
+        }
+        builder.build().unwrap()
+    };
+    let basket = [
+        order("1333", OrderType::MARKET, None),
+        order("11536", OrderType::LIMIT, Some(4100.0)),
+    ];
+    let mock = MockTransport::new()
+        .on(
+            Method::POST,
+            "/v2/marketfeed/ltp",
+            MockResponse::json(
+                200,
+                serde_json::json!({
+                    "status": "success",
+                    "data": {"NSE_EQ": {"1333": {"last_price": 1650.0}}}
+                }),
+            ),
+        )
+        .on(
+            Method::POST,
+            "/v2/margincalculator/multi",
+            MockResponse::json(
+                200,
+                serde_json::json!({"total_margin": "11500.00", "hedge_benefit": "0.00"}),
+            ),
+        )
+        .on(
+            Method::GET,
+            "/v2/fundlimit",
+            MockResponse::json(
+                200,
+                serde_json::json!({"availabelBalance": 40000.0, "utilizedAmount": 10000.0}),
+            ),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+
+    let forecast = client.forecast_funds(&basket).await.unwrap();
+    assert!(forecast.is_affordable());
+    assert_eq!(forecast.available_after, 28500.0);
+    assert_eq!(forecast.utilization, 0.2);
+    assert_eq!(forecast.utilization_after, 0.43);
+
+    let margin_req = mock
+        .requests()
+        .into_iter()
+        .find(|r| r.path == "/v2/margincalculator/multi")
+        .unwrap()
+        .json()
+        .unwrap();
+    let prices: Vec<_> = margin_req["scripts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["price"].as_f64().unwrap())
+        .collect();
+    assert_eq!(prices, [1650.0, 4100.0]);
+}
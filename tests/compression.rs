@@ -0,0 +1,89 @@
+//! Offline tests for compressed REST traffic, against a local HTTP listener.
+
+use std::io::{Read, Write};
+
+use dhan_rs::DhanClient;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Accept one request and return its head and body, answering with a
+/// gzipped copy of the (decoded) request body.
+async fn serve_echo_gzip() -> (u16, tokio::task::JoinHandle<(String, Vec<u8>)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut raw = Vec::new();
+        let mut buf = [0u8; 4096];
+        let (head, body) = loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            raw.extend_from_slice(&buf[..n]);
+            let Some(end) = raw.windows(4).position(|w| w == b"\r\n\r\n") else {
+                continue;
+            };
+            let head = String::from_utf8_lossy(&raw[..end]).to_lowercase();
+            let len: usize = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length: "))
+                .map_or(0, |v| v.trim().parse().unwrap());
+            if raw.len() >= end + 4 + len {
+                break (head, raw[end + 4..end + 4 + len].to_vec());
+            }
+        };
+
+        let json = if head.contains("content-encoding: gzip") {
+            let mut json = Vec::new();
+            GzDecoder::new(&body[..]).read_to_end(&mut json).unwrap();
+            json
+        } else {
+            body.clone()
+        };
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&json).unwrap();
+        let gz = encoder.finish().unwrap();
+        let resp_head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            gz.len()
+        );
+        socket.write_all(resp_head.as_bytes()).await.unwrap();
+        socket.write_all(&gz).await.unwrap();
+        (head, body)
+    });
+    (port, server)
+}
+
+#[tokio::test]
+async fn test_gzip_requests_and_responses() {
+    let payload = serde_json::json!({ "NSE_EQ": (0..1000).collect::<Vec<u32>>() });
+
+    let (port, server) = serve_echo_gzip().await;
+    let client = DhanClient::with_base_url("1000000001", "t", format!("http://127.0.0.1:{port}"))
+        .with_request_compression(1024);
+    let echoed: serde_json::Value = client.post("/v2/marketfeed/ltp", &payload).await.unwrap();
+    assert_eq!(echoed, payload);
+    let (head, body) = server.await.unwrap();
+    assert!(head.contains("accept-encoding: gzip"), "{head}");
+    assert!(head.contains("content-encoding: gzip"), "{head}");
+    assert!(body.len() < serde_json::to_vec(&payload).unwrap().len() / 2);
+
+    // Small bodies and disabled response compression go out plain.
+    let (port, server) = serve_echo_gzip().await;
+    let client = DhanClient::with_base_url("1000000001", "t", format!("http://127.0.0.1:{port}"))
+        .with_request_compression(1024)
+        .with_response_compression(false);
+    assert!(!client.response_compression());
+    let small = serde_json::json!({ "NSE_EQ": [1333] });
+    // The server answers gzip regardless, which is now not decoded.
+    assert!(
+        client
+            .post::<_, serde_json::Value>("/v2/marketfeed/ltp", &small)
+            .await
+            .is_err()
+    );
+    let (head, _) = server.await.unwrap();
+    assert!(!head.contains("accept-encoding"), "{head}");
+    assert!(!head.contains("content-encoding"), "{head}");
+}
@@ -0,0 +1,158 @@
+//! Tests for the session control server over a loopback socket.
+#![cfg(feature = "control")]
+
+use dhan_rs::DhanClient;
+use dhan_rs::risk::RiskEngine;
+use dhan_rs::runtime::Strategy;
+use dhan_rs::runtime::control::ControlServer;
+use dhan_rs::runtime::session::{DhanSession, StrategyState};
+use serde_json::{Value, json};
+
+struct Idle;
+
+impl Strategy for Idle {
+    fn name(&self) -> &str {
+        "idle"
+    }
+}
+
+#[tokio::test]
+async fn test_control_server_authenticates_and_steers_strategies() {
+    let mut session = DhanSession::new(DhanClient::new("1000000001", "t"));
+    session.add_strategy(Idle, RiskEngine::new()).unwrap();
+    let (addr, _server) = ControlServer::new(session.handle(), "s3cret")
+        .spawn("127.0.0.1:0")
+        .await
+        .unwrap();
+    let base = format!("http://{addr}");
+    let http = reqwest::Client::new();
+
+    let denied = http.get(format!("{base}/health")).send().await.unwrap();
+    assert_eq!(denied.status(), 401);
+
+    let health: Value = http
+        .get(format!("{base}/health"))
+        .bearer_auth("s3cret")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(health["status"], "ok");
+    assert_eq!(health["strategies"][0]["name"], "idle");
+
+    let paused = http
+        .post(format!("{base}/strategies/idle/pause"))
+        .bearer_auth("s3cret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(paused.status(), 200);
+    assert_eq!(session.strategies()[0].state, StrategyState::Paused);
+
+    let limits = http
+        .put(format!("{base}/strategies/idle/limits"))
+        .bearer_auth("s3cret")
+        .json(&json!({ "max_order_quantity": 25 }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(limits.status(), 200);
+    assert_eq!(
+        session.risk("idle").unwrap().limits().max_order_quantity,
+        Some(25)
+    );
+
+    let missing = http
+        .post(format!("{base}/strategies/nope/halt"))
+        .bearer_auth("s3cret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), 400);
+
+    session.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_flatten_cancels_working_orders_and_closes_every_product() {
+    use std::time::Duration;
+
+    use dhan_rs::scheduler::square_off::SquareOffConfig;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use reqwest::Method;
+
+    let position = |net_qty: i64| {
+        MockResponse::json(
+            200,
+            json!([{"securityId": "52175", "exchangeSegment": "NSE_FNO", "productType": "MARGIN", "netQty": net_qty}]),
+        )
+    };
+    let mock = MockTransport::new()
+        .on(
+            Method::GET,
+            "/v2/orders",
+            MockResponse::json(
+                200,
+                json!([
+                    {"orderId": "E1", "orderStatus": "PENDING"},
+                    {"orderId": "E0", "orderStatus": "TRADED"}
+                ]),
+            ),
+        )
+        .on(
+            Method::DELETE,
+            "/v2/orders/E1",
+            MockResponse::json(200, json!({"orderId": "E1", "orderStatus": "CANCELLED"})),
+        )
+        .on(Method::GET, "/v2/positions", position(50))
+        .on(Method::GET, "/v2/positions", position(0))
+        .on(
+            Method::POST,
+            "/v2/orders",
+            MockResponse::json(200, json!({"orderId": "X1", "orderStatus": "TRANSIT"})),
+        )
+        .on(
+            Method::GET,
+            "/v2/orders/X1",
+            MockResponse::json(200, json!({"orderId": "X1", "orderStatus": "TRADED"})),
+        );
+    let client = DhanClient::new("1000000001", "t").with_transport(mock.clone());
+    let session = DhanSession::new(client);
+    let config = SquareOffConfig {
+        verify_delay: Duration::ZERO,
+        ..SquareOffConfig::default()
+    };
+    let (addr, _server) = ControlServer::new(session.handle(), "s3cret")
+        .with_square_off(config)
+        .spawn("127.0.0.1:0")
+        .await
+        .unwrap();
+
+    let report: Value = reqwest::Client::new()
+        .post(format!("http://{addr}/flatten"))
+        .bearer_auth("s3cret")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(report["cancelled"], json!(["E1"]));
+    assert_eq!(report["cancel_failed"], json!([]));
+    assert_eq!(report["placed"], json!(["X1"]));
+    assert_eq!(report["flat"], true);
+
+    // Working orders are cancelled before the exits are placed.
+    let requests = mock.requests();
+    let cancels: Vec<_> = requests
+        .iter()
+        .filter(|r| r.method == Method::DELETE)
+        .map(|r| r.path.as_str())
+        .collect();
+    assert_eq!(cancels, ["/v2/orders/E1"]);
+    let cancel = requests.iter().position(|r| r.method == Method::DELETE);
+    let exit = requests.iter().position(|r| r.method == Method::POST);
+    assert!(cancel.unwrap() < exit.unwrap());
+}
@@ -0,0 +1,59 @@
+//! Tests for the `decimal` feature.
+#![cfg(feature = "decimal")]
+
+use dhan_rs::decimal::{Decimal, ToDecimal, round_to_tick};
+use dhan_rs::types::enums::*;
+use dhan_rs::types::funds::FundLimit;
+use dhan_rs::types::orders::PlaceOrderRequest;
+use serde::{Deserialize, Serialize};
+
+#[test]
+fn test_decimal_round_trips_api_numbers() {
+    let funds: FundLimit =
+        serde_json::from_str(r#"{"availabelBalance": 10000.1, "utilizedAmount": 0.2}"#).unwrap();
+    let total =
+        funds.available_balance.to_decimal().unwrap() + funds.utilized_amount.to_decimal().unwrap();
+    assert_eq!(total.to_string(), "10000.3");
+    assert_eq!(f64::NAN.to_decimal(), None);
+
+    let price = round_to_tick(Decimal::new(245_063, 2), Decimal::new(5, 2));
+    let req = PlaceOrderRequest::builder()
+        .dhan_client_id("1000000001")
+        .transaction_type(TransactionType::BUY)
+        .exchange_segment(ExchangeSegment::NSE_EQ)
+        .product_type(ProductType::CNC)
+        .order_type(OrderType::LIMIT)
+        .validity(Validity::DAY)
+        .security_id("1333")
+        .quantity(1)
+        .price_decimal(price)
+        .build()
+        .unwrap();
+    assert!(
+        serde_json::to_string(&req)
+            .unwrap()
+            .contains(r#""price":2450.65"#)
+    );
+}
+
+#[test]
+fn test_decimal_serde_helpers_accept_numbers_and_strings() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Fill {
+        #[serde(with = "dhan_rs::decimal::float")]
+        price: Decimal,
+        #[serde(with = "dhan_rs::decimal::float_option", default)]
+        charges: Option<Decimal>,
+    }
+
+    let fill: Fill = serde_json::from_str(r#"{"price": 101.35, "charges": "20.06"}"#).unwrap();
+    assert_eq!(fill.price, Decimal::new(10_135, 2));
+    assert_eq!(fill.charges, Some(Decimal::new(2006, 2)));
+    assert_eq!(
+        serde_json::to_string(&fill).unwrap(),
+        r#"{"price":101.35,"charges":20.06}"#
+    );
+
+    let bare: Fill = serde_json::from_str(r#"{"price": 7, "charges": null}"#).unwrap();
+    assert_eq!(bare.charges, None);
+}
@@ -0,0 +1,37 @@
+//! Offline tests for the shared market depth book.
+
+use dhan_rs::types::depth::{BookLevel, DepthBook, MAX_DEPTH_LEVELS};
+use dhan_rs::types::market_quote::DepthData;
+
+#[test]
+fn test_book_from_rest_depth_sorts_and_drops_padding() {
+    let depth: DepthData = serde_json::from_str(
+        r#"{
+            "buy":  [{"quantity":50,"orders":2,"price":99.5},{"quantity":100,"orders":3,"price":100.0},{"quantity":0,"orders":0,"price":0.0}],
+            "sell": [{"quantity":150,"orders":4,"price":100.5},{"quantity":80,"orders":1,"price":101.0}]
+        }"#,
+    )
+    .unwrap();
+    let book = DepthBook::from(&depth);
+
+    assert_eq!(book.bids.len(), 2);
+    assert_eq!(book.best_bid().unwrap().price, 100.0);
+    assert_eq!(book.best_ask().unwrap().price, 100.5);
+    assert_eq!(book.spread(), Some(0.5));
+    assert_eq!(book.cumulative_bid_quantity_at(99.5), 150);
+    assert_eq!(book.cumulative_ask_quantity_at(100.75), 150);
+
+    // (100.0 × 150 + 100.5 × 100) / 250
+    let micro = book.microprice().unwrap();
+    assert!((micro - 100.2).abs() < 1e-9);
+}
+
+#[test]
+fn test_book_caps_levels_per_side() {
+    let mut book = DepthBook::default();
+    book.set_asks((1..=30).map(|i| BookLevel::new(100.0 + i as f64, 10, 1)));
+
+    assert_eq!(book.asks.len(), MAX_DEPTH_LEVELS);
+    assert_eq!(book.best_ask().unwrap().price, 101.0);
+    assert!(book.microprice().is_none());
+}
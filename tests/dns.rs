@@ -0,0 +1,44 @@
+//! Offline tests for DNS pinning, against a local HTTP listener.
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::Duration;
+
+use dhan_rs::DhanClient;
+use dhan_rs::dns::{DEFAULT_PIN_CONNECT_TIMEOUT, DnsPins};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn test_pinned_host_skips_dns() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 4096];
+        let _ = socket.read(&mut buf).await.unwrap();
+        let body = r#"{"dhanClientId":"1000000001"}"#;
+        let resp = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        socket.write_all(resp.as_bytes()).await.unwrap();
+    });
+
+    // An unreachable address first: the connection fails over to the next.
+    let pins = DnsPins::new().with_fallback("broker.invalid", [IpAddr::V4(Ipv4Addr::LOCALHOST)]);
+    pins.pin("broker.invalid", [IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2))]);
+    assert_eq!(pins.addrs("broker.invalid").len(), 2);
+    assert!(pins.addrs("api.dhan.co").is_empty());
+    assert_eq!(pins.connect_timeout(), DEFAULT_PIN_CONNECT_TIMEOUT);
+    let quick = pins
+        .clone()
+        .with_connect_timeout(Duration::from_millis(500));
+    assert_eq!(quick.connect_timeout(), Duration::from_millis(500));
+    assert_eq!(quick.addrs("broker.invalid"), pins.addrs("broker.invalid"));
+
+    let client =
+        DhanClient::with_base_url("1000000001", "t", format!("http://broker.invalid:{port}"))
+            .with_dns_pins(&pins);
+    let profile: serde_json::Value = client.get("/v2/profile").await.unwrap();
+    assert_eq!(profile["dhanClientId"], "1000000001");
+}
@@ -0,0 +1,53 @@
+//! Event bus tests.
+
+use dhan_rs::events::{EventBus, EventFilter, EventKind, RiskEvent, SessionEvent};
+use dhan_rs::journal::Journal;
+use dhan_rs::risk::{MaxOrderQuantity, RiskEngine};
+use dhan_rs::types::enums::{ExchangeSegment, OrderType, ProductType, TransactionType, Validity};
+use dhan_rs::types::orders::PlaceOrderRequest;
+
+#[tokio::test]
+async fn test_bus_carries_risk_and_journal_events() {
+    let bus = EventBus::new();
+    let mut all = bus.subscribe(EventFilter::all());
+    let mut journal_only = bus.subscribe(EventFilter::kinds([EventKind::Journal]));
+
+    let risk = RiskEngine::new()
+        .with_check(MaxOrderQuantity(10))
+        .with_event_bus(bus.clone());
+    let req = PlaceOrderRequest::builder()
+        .dhan_client_id("1000000001")
+        .transaction_type(TransactionType::BUY)
+        .exchange_segment(ExchangeSegment::NSE_EQ)
+        .product_type(ProductType::INTRADAY)
+        .order_type(OrderType::MARKET)
+        .validity(Validity::DAY)
+        .security_id("1333")
+        .quantity(50)
+        .build()
+        .unwrap();
+    assert!(risk.check(&req).is_err());
+
+    let path = std::env::temp_dir().join(format!("dhan-rs-events-{}.jsonl", std::process::id()));
+    let mut journal = Journal::open(&path).unwrap().with_event_bus(bus.clone());
+    journal
+        .append(&serde_json::json!({ "event": "started" }))
+        .unwrap();
+    std::fs::remove_file(&path).ok();
+
+    match all.recv().await {
+        Some(SessionEvent::Risk(RiskEvent::Rejected {
+            security_id,
+            reason,
+        })) => {
+            assert_eq!(security_id, "1333");
+            assert!(reason.starts_with("max_order_quantity"));
+        }
+        other => panic!("unexpected {other:?}"),
+    }
+    assert_eq!(all.recv().await.unwrap().kind(), EventKind::Journal);
+    match journal_only.recv().await {
+        Some(SessionEvent::Journal(ev)) => assert_eq!(ev.record["event"], "started"),
+        other => panic!("unexpected {other:?}"),
+    }
+}
@@ -0,0 +1,389 @@
+//! Offline tests for feed-manager helpers, driven by hand-built packets.
+
+use std::time::Duration;
+
+use dhan_rs::DhanClient;
+use dhan_rs::analytics::breadth::BreadthAggregator;
+use dhan_rs::analytics::oi::{OiQuadrant, OiTracker};
+use dhan_rs::cache::{HybridQuotes, QuoteCache};
+use dhan_rs::types::enums::ExchangeSegment;
+use dhan_rs::types::instrument::InstrumentId;
+use dhan_rs::ws::manager::PrevCloseCache;
+use dhan_rs::ws::market_feed::parse_packet;
+
+/// Build a packet for NSE_EQ:1333 with response `code` and `payload`.
+fn packet(code: u8, payload: &[u8]) -> Vec<u8> {
+    packet_for(1333, code, payload)
+}
+
+/// Build an NSE_EQ packet for `security_id`.
+fn packet_for(security_id: u32, code: u8, payload: &[u8]) -> Vec<u8> {
+    let mut buf = vec![code];
+    buf.extend_from_slice(&((8 + payload.len()) as u16).to_le_bytes());
+    buf.push(1);
+    buf.extend_from_slice(&security_id.to_le_bytes());
+    buf.extend_from_slice(payload);
+    buf
+}
+
+#[test]
+fn test_prev_close_cache_enriches_later_ticks() {
+    let cache = PrevCloseCache::default();
+    let mut prev = 1500f32.to_le_bytes().to_vec();
+    prev.extend_from_slice(&0i32.to_le_bytes());
+    cache.observe(&parse_packet(&packet(6, &prev)).unwrap());
+
+    let id = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+    assert_eq!(cache.get(&id).unwrap().close, 1500.0);
+
+    let mut ticker = 1530f32.to_le_bytes().to_vec();
+    ticker.extend_from_slice(&1_726_041_600i32.to_le_bytes());
+    let enriched = cache.enrich(parse_packet(&packet(2, &ticker)).unwrap());
+    assert_eq!(enriched.prev_close, Some(1500.0));
+    assert_eq!(enriched.change, Some(30.0));
+    assert_eq!(enriched.change_pct, Some(2.0));
+}
+
+#[test]
+fn test_oi_tracker_classifies_quadrants() {
+    let mut tracker = OiTracker::new();
+    let mut prev = 100f32.to_le_bytes().to_vec();
+    prev.extend_from_slice(&1000i32.to_le_bytes());
+    assert!(
+        tracker
+            .on_event(&parse_packet(&packet(6, &prev)).unwrap())
+            .is_none()
+    );
+
+    let mut ticker = 105f32.to_le_bytes().to_vec();
+    ticker.extend_from_slice(&1_726_041_600i32.to_le_bytes());
+    assert!(
+        tracker
+            .on_event(&parse_packet(&packet(2, &ticker)).unwrap())
+            .is_none()
+    );
+
+    let update = tracker
+        .on_event(&parse_packet(&packet(5, &1200i32.to_le_bytes())).unwrap())
+        .unwrap();
+    assert_eq!(update.oi_change, 200);
+    assert_eq!(update.oi_change_pct, Some(20.0));
+    assert_eq!(update.quadrant, Some(OiQuadrant::LongBuildup));
+}
+
+#[test]
+fn test_breadth_counts_advances_and_declines() {
+    let mut breadth = BreadthAggregator::new();
+    for (security_id, prev_close, ltp) in [(1u32, 100f32, 101f32), (2, 50.0, 49.0), (3, 10.0, 12.0)]
+    {
+        let mut prev = prev_close.to_le_bytes().to_vec();
+        prev.extend_from_slice(&0i32.to_le_bytes());
+        breadth.on_event(&parse_packet(&packet_for(security_id, 6, &prev)).unwrap());
+        let mut ticker = ltp.to_le_bytes().to_vec();
+        ticker.extend_from_slice(&1_726_041_600i32.to_le_bytes());
+        breadth.on_event(&parse_packet(&packet_for(security_id, 2, &ticker)).unwrap());
+    }
+
+    let snap = breadth.snapshot();
+    assert_eq!((snap.advances, snap.declines, snap.total), (2, 1, 3));
+    assert_eq!(snap.advance_decline_ratio(), Some(2.0));
+    assert_eq!(snap.new_highs, 3);
+}
+
+#[tokio::test]
+async fn test_hybrid_quotes_serve_fresh_prices_from_cache() {
+    let cache = QuoteCache::new();
+    let mut ticker = 1530f32.to_le_bytes().to_vec();
+    ticker.extend_from_slice(&1_726_041_600i32.to_le_bytes());
+    let tick = parse_packet(&packet(2, &ticker))
+        .unwrap()
+        .to_tick()
+        .unwrap();
+    cache.update(&tick);
+
+    let quotes = HybridQuotes::new(DhanClient::new("1000000001", "t"), cache)
+        .with_max_age(Duration::from_secs(60));
+    let id = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+    assert_eq!(quotes.ltp(id).await.unwrap(), 1530.0);
+    assert!(quotes.cache().age(&id).unwrap() < Duration::from_secs(60));
+}
+
+#[test]
+fn test_feed_events_serialize_and_display() {
+    let mut ticker = 1530.5f32.to_le_bytes().to_vec();
+    ticker.extend_from_slice(&1_726_041_600i32.to_le_bytes());
+    let event = parse_packet(&packet(2, &ticker)).unwrap();
+
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["type"], "Ticker");
+    assert_eq!(json["header"]["exchange_segment"], "NSE_EQ");
+    assert_eq!(json["ltp"], 1530.5);
+    assert_eq!(
+        event.to_string(),
+        "Ticker NSE_EQ:1333 ltp=1530.5 ltt=1726041600"
+    );
+}
+
+#[test]
+fn test_snapshot_event_from_rest_quote() {
+    use dhan_rs::types::market_quote::QuoteData;
+    use dhan_rs::ws::snapshot::{is_snapshot, snapshot_event};
+
+    let quote: QuoteData = serde_json::from_value(serde_json::json!({
+        "last_price": 1530.5,
+        "last_quantity": 10,
+        "last_trade_time": "2024-09-11 09:58:03",
+        "volume": 125000,
+        "ohlc": { "open": 1520.0, "close": 1510.0, "high": 1535.0, "low": 1515.0 },
+        "depth": {
+            "buy": [{ "quantity": 50, "orders": 2, "price": 1530.0 }],
+            "sell": [{ "quantity": 75, "orders": 3, "price": 1531.0 }]
+        }
+    }))
+    .unwrap();
+    let id = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+
+    let event = snapshot_event(id, &quote, true);
+    assert!(is_snapshot(&event));
+    let tick = event.to_tick().unwrap();
+    assert_eq!(tick.instrument, id);
+    assert_eq!(tick.ltp, 1530.5);
+    assert_eq!(tick.ltt, 1_726_048_683);
+    assert_eq!(tick.high, Some(1535.0));
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["type"], "Full");
+    assert_eq!(json["depth"][0]["bid_qty"], 50);
+    assert_eq!(json["depth"][0]["ask_price"], 1531.0);
+
+    let quote_event = snapshot_event(id, &quote, false);
+    assert_eq!(serde_json::to_value(&quote_event).unwrap()["type"], "Quote");
+    assert!(!is_snapshot(&parse_packet(&packet(6, &[0; 8])).unwrap()));
+}
+
+#[test]
+fn test_auth_disconnects_map_to_token_expired() {
+    use dhan_rs::DhanError;
+    use dhan_rs::ws::manager::{ConnectionId, FeedAuthFailure};
+    use dhan_rs::ws::market_feed::disconnect_code;
+
+    let expired = parse_packet(&packet(50, &disconnect_code::TOKEN_EXPIRED.to_le_bytes())).unwrap();
+    let err = expired.auth_error().unwrap();
+    assert!(matches!(err, DhanError::TokenExpired(_)));
+    assert!(err.is_token_expired());
+
+    let busy = parse_packet(&packet(
+        50,
+        &disconnect_code::TOO_MANY_CONNECTIONS.to_le_bytes(),
+    ))
+    .unwrap();
+    assert!(busy.auth_error().is_none());
+
+    let failure = FeedAuthFailure {
+        connection: ConnectionId(0),
+        reason_code: Some(disconnect_code::CLIENT_ID_INVALID),
+    };
+    assert!(matches!(failure.to_error(), DhanError::WsAuthFailed(_)));
+    let handshake = FeedAuthFailure {
+        reason_code: None,
+        ..failure
+    };
+    assert!(handshake.to_error().is_token_expired());
+}
+
+#[test]
+fn test_fixed_encoder_reproduces_feed_packets() {
+    use dhan_rs::ws::encode::{EventEncoder, FixedEncoder, JsonEncoder};
+
+    let full_payload: Vec<u8> = (0..154u8).collect();
+    let packets = [
+        packet(2, &[0, 0, 200, 66, 10, 0, 0, 0]),
+        packet(5, &7i32.to_le_bytes()),
+        packet(8, &full_payload),
+        packet(50, &807i16.to_le_bytes()),
+    ];
+    let mut stream = Vec::new();
+    for raw in &packets {
+        let event = parse_packet(raw).unwrap();
+        let encoded = FixedEncoder.to_vec(&event).unwrap();
+        assert_eq!(&encoded, raw);
+        FixedEncoder.encode(&event, &mut stream).unwrap();
+        assert!(!JsonEncoder.to_vec(&event).unwrap().is_empty());
+    }
+    assert_eq!(stream, packets.concat());
+}
+
+#[test]
+fn test_reconnect_policy_backs_off_exponentially() {
+    use dhan_rs::ws::reconnect::ReconnectPolicy;
+
+    let policy = ReconnectPolicy {
+        initial_delay: Duration::from_millis(500),
+        max_delay: Duration::from_secs(5),
+        max_attempts: Some(10),
+    };
+    let delays: Vec<u64> = (1..=6)
+        .map(|n| policy.delay(n).as_millis() as u64)
+        .collect();
+    assert_eq!(delays, vec![500, 1000, 2000, 4000, 5000, 5000]);
+    assert_eq!(policy.delay(u32::MAX), Duration::from_secs(5));
+}
+
+#[test]
+fn test_capacity_projection() {
+    use dhan_rs::ws::manager::DhanFeedManagerBuilder;
+    use dhan_rs::ws::market_feed::Instrument;
+
+    let manager = DhanFeedManagerBuilder::new("client-id", "token")
+        .max_connections(2)
+        .max_instruments_per_connection(2)
+        .build();
+    let now = manager.capacity();
+    assert_eq!((now.used, now.free, now.limit), (0, 4, 4));
+
+    let batch: Vec<_> = (1..=5)
+        .chain([1])
+        .map(|id| Instrument::new("NSE_EQ", id.to_string()))
+        .collect();
+    let after = manager.capacity_after(&batch);
+    assert_eq!((after.used, after.free, after.unplaced), (4, 0, 1));
+    assert!(after.connections.iter().all(|c| c.used == 2));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_feed_stream_filters_and_throttle() {
+    use dhan_rs::types::enums::FeedResponseCode;
+    use dhan_rs::ws::filter::{FeedStreamExt, receiver_stream};
+    use futures_util::StreamExt;
+
+    let mut ticker = 1530f32.to_le_bytes().to_vec();
+    ticker.extend_from_slice(&1_726_041_600i32.to_le_bytes());
+    let oi = 7i32.to_le_bytes();
+    let events = [
+        packet_for(1, 2, &ticker),
+        packet_for(2, 2, &ticker),
+        packet_for(1, 5, &oi),
+    ]
+    .map(|p| parse_packet(&p).unwrap());
+
+    let ids = |evs: Vec<dhan_rs::ws::market_feed::MarketFeedEvent>| {
+        evs.iter()
+            .map(|e| e.header().security_id)
+            .collect::<Vec<_>>()
+    };
+    let only = futures_util::stream::iter(events.clone())
+        .only([InstrumentId::new(ExchangeSegment::NSE_EQ, 1)])
+        .event_kinds(&[FeedResponseCode::Ticker])
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(ids(only), [1]);
+    let none = futures_util::stream::iter(events.clone())
+        .segments(&[ExchangeSegment::NSE_FNO])
+        .collect::<Vec<_>>()
+        .await;
+    assert!(none.is_empty());
+
+    let (tx, rx) = tokio::sync::broadcast::channel(16);
+    let mut throttled = receiver_stream(rx).throttle_per_instrument(Duration::from_secs(1));
+    for event in events.iter().chain(&events) {
+        tx.send(event.clone()).unwrap();
+    }
+    // The repeats within the interval are dropped; the OI packet of
+    // instrument 1 is not throttled by its ticker.
+    let mut passed = Vec::new();
+    for _ in 0..3 {
+        passed.push(throttled.next().await.unwrap());
+    }
+    assert_eq!(ids(passed), [1, 2, 1]);
+    tokio::time::advance(Duration::from_secs(1)).await;
+    tx.send(events[0].clone()).unwrap();
+    assert_eq!(throttled.next().await.unwrap().header().security_id, 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_conflate_keeps_latest_per_instrument() {
+    use dhan_rs::ws::filter::{FeedStreamExt, receiver_stream};
+    use futures_util::StreamExt;
+
+    let ticker = |id: u32, ltp: f32| {
+        let mut body = ltp.to_le_bytes().to_vec();
+        body.extend_from_slice(&1_726_041_600i32.to_le_bytes());
+        parse_packet(&packet_for(id, 2, &body)).unwrap()
+    };
+    let (tx, rx) = tokio::sync::broadcast::channel(16);
+    let mut ui = receiver_stream(rx).conflate(2);
+    for (id, ltp) in [(1, 1530.0), (1, 1531.0), (2, 99.0), (1, 1532.0)] {
+        tx.send(ticker(id, ltp)).unwrap();
+    }
+    drop(tx);
+
+    let start = tokio::time::Instant::now();
+    let mut seen = Vec::new();
+    while let Some(event) = ui.next().await {
+        let tick = event.to_tick().unwrap();
+        seen.push((tick.instrument.security_id, tick.ltp, start.elapsed()));
+    }
+    // 1531 is superseded while held; 1532 waits out the 500 ms interval.
+    assert_eq!(
+        seen,
+        [
+            (1, 1530.0, Duration::ZERO),
+            (2, 99.0, Duration::ZERO),
+            (1, 1532.0, Duration::from_millis(500)),
+        ]
+    );
+}
+
+#[test]
+fn test_connection_slots_share_limit() {
+    use dhan_rs::ws::slots::ConnectionSlots;
+
+    let dir = std::env::temp_dir().join(format!("dhan-rs-slots-{}", std::process::id()));
+    let base = 40_000 + (std::process::id() % 20_000) as u16;
+    for slots in [
+        ConnectionSlots::directory(&dir).with_limit(2),
+        ConnectionSlots::ports(base).with_limit(2),
+    ] {
+        // A second process sees the same slots.
+        let other = slots.clone();
+        let held = slots.acquire_up_to(5).unwrap();
+        assert_eq!(held.iter().map(|g| g.slot()).collect::<Vec<_>>(), [0, 1]);
+        assert!(other.try_acquire().unwrap().is_none());
+        assert_eq!(other.in_use().unwrap(), 2);
+
+        drop(held);
+        assert_eq!(other.in_use().unwrap(), 0);
+        assert_eq!(other.try_acquire().unwrap().unwrap().slot(), 0);
+    }
+    // A lock file left behind by an exited holder is free once unlocked,
+    // whatever process ID it records.
+    std::fs::write(dir.join("slot-0.lock"), "4194305").unwrap();
+    let slots = ConnectionSlots::directory(&dir).with_limit(2);
+    assert_eq!(slots.try_acquire().unwrap().unwrap().slot(), 0);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_feed_event_seeds_order() {
+    use dhan_rs::types::enums::{OrderType, ProductType, TransactionType, Validity};
+
+    let mut payload = 1530.5f32.to_le_bytes().to_vec();
+    payload.extend_from_slice(&1_700_000_000i32.to_le_bytes());
+    let event = parse_packet(&packet(2, &payload)).unwrap();
+    let req = event
+        .to_order_seed()
+        .unwrap()
+        .dhan_client_id("1000000001")
+        .transaction_type(TransactionType::SELL)
+        .product_type(ProductType::INTRADAY)
+        .order_type(OrderType::MARKET)
+        .validity(Validity::DAY)
+        .quantity(10)
+        .build()
+        .unwrap();
+    assert_eq!(req.exchange_segment, ExchangeSegment::NSE_EQ);
+    assert_eq!(req.security_id, "1333");
+
+    // Disconnect packets carry no instrument.
+    let disconnect = parse_packet(&packet(50, &805i16.to_le_bytes())).unwrap();
+    assert!(disconnect.to_order_seed().is_none());
+}
@@ -0,0 +1,93 @@
+//! Offline tests for streamed historical responses, against a local HTTP
+//! listener.
+
+use dhan_rs::DhanClient;
+use dhan_rs::types::enums::{ExchangeSegment, Instrument};
+use dhan_rs::types::historical::HistoricalDataRequest;
+use futures_util::StreamExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Serve `body` once, in chunks of `chunk` bytes.
+async fn serve_chunked(body: String, chunk: usize) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 4096];
+        let _ = socket.read(&mut buf).await.unwrap();
+        let head = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n";
+        socket.write_all(head.as_bytes()).await.unwrap();
+        for part in body.as_bytes().chunks(chunk) {
+            socket
+                .write_all(format!("{:x}\r\n", part.len()).as_bytes())
+                .await
+                .unwrap();
+            socket.write_all(part).await.unwrap();
+            socket.write_all(b"\r\n").await.unwrap();
+        }
+        socket.write_all(b"0\r\n\r\n").await.unwrap();
+    });
+    port
+}
+
+fn request() -> HistoricalDataRequest {
+    HistoricalDataRequest {
+        security_id: "1333".into(),
+        exchange_segment: ExchangeSegment::NSE_EQ,
+        instrument: Instrument::EQUITY,
+        expiry_code: None,
+        oi: None,
+        from_date: "2020-01-01".into(),
+        to_date: "2024-01-01".into(),
+    }
+}
+
+#[tokio::test]
+async fn test_daily_historical_stream_yields_every_candle() {
+    let n = 1000;
+    let column = |base: f64| {
+        (0..n)
+            .map(|i| (base + f64::from(i)).to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let body = format!(
+        r#"{{"open":[{}],"high":[{}],"low":[{}],"close":[{}],"volume":[{}],"timestamp":[{}]}}"#,
+        column(100.0),
+        column(101.0),
+        column(99.0),
+        column(100.5),
+        column(1000.0),
+        column(1_600_000_000.0),
+    );
+    let port = serve_chunked(body, 97).await;
+
+    let client = DhanClient::with_base_url("1000000001", "t", format!("http://127.0.0.1:{port}"));
+    let candles: Vec<_> = client
+        .get_daily_historical_stream(&request())
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect()
+        .await;
+    assert_eq!(candles.len(), n as usize);
+    assert_eq!(candles[0].timestamp, 1_600_000_000);
+    assert_eq!(candles[999].close, 1099.5);
+    assert_eq!(candles[999].open_interest, None);
+}
+
+#[tokio::test]
+async fn test_daily_historical_stream_reports_malformed_body() {
+    let port = serve_chunked(r#"{"open":[1,2],"high":[oops"#.into(), 5).await;
+    let client = DhanClient::with_base_url("1000000001", "t", format!("http://127.0.0.1:{port}"));
+    let mut stream = client
+        .get_daily_historical_stream(&request())
+        .await
+        .unwrap();
+    assert!(matches!(
+        stream.next().await,
+        Some(Err(dhan_rs::DhanError::Json(_)))
+    ));
+    assert!(stream.next().await.is_none());
+}
@@ -0,0 +1,137 @@
+//! Tests for scrip master parsing and lookups.
+
+use dhan_rs::instruments::InstrumentMaster;
+use dhan_rs::types::enums::{ExchangeSegment, Instrument};
+use dhan_rs::types::instrument::InstrumentId;
+
+const DETAILED: &str = "\
+EXCH_ID,SEGMENT,SECURITY_ID,ISIN,INSTRUMENT,UNDERLYING_SECURITY_ID,UNDERLYING_SYMBOL,SYMBOL_NAME,DISPLAY_NAME,INSTRUMENT_TYPE,SERIES,LOT_SIZE,SM_EXPIRY_DATE,STRIKE_PRICE,OPTION_TYPE,TICK_SIZE
+NSE,E,2885,INE002A01018,EQUITY,,,RELIANCE,Reliance Industries,ES,EQ,1.0,,-0.01000,XX,10.0000
+BSE,E,500325,INE002A01018,EQUITY,,,RELIANCE,Reliance Industries,ES,A,1.0,,-0.01000,XX,5.0000
+NSE,D,35001,NA,OPTIDX,13,NIFTY,NIFTY-Jun2025-24000-CE,NIFTY 26 JUN 24000 CALL,OP,NA,75.0,2025-06-26 14:30:00,24000.00000,CE,5.0000
+NSE,X,1,NA,EQUITY,,,UNKNOWN,,,,1.0,,,,
+";
+
+const COMPACT: &str = "\
+SEM_EXM_EXCH_ID,SEM_SEGMENT,SEM_SMST_SECURITY_ID,SEM_INSTRUMENT_NAME,SEM_EXPIRY_CODE,SEM_TRADING_SYMBOL,SEM_LOT_UNITS,SEM_CUSTOM_SYMBOL,SEM_EXPIRY_DATE,SEM_STRIKE_PRICE,SEM_OPTION_TYPE,SEM_TICK_SIZE,SEM_EXPIRY_FLAG,SEM_EXCH_INSTRUMENT_TYPE,SEM_SERIES,SM_SYMBOL_NAME
+NSE,I,13,INDEX,0,NIFTY,1.0,Nifty 50,,0.0,XX,0.0,NA,INDEX,NA,NIFTY
+";
+
+#[test]
+fn test_detailed_master_lookups() {
+    let master = InstrumentMaster::from_reader(DETAILED.as_bytes()).unwrap();
+    assert_eq!((master.len(), master.skipped()), (3, 1));
+
+    let reliance = master.find(ExchangeSegment::NSE_EQ, "reliance").unwrap();
+    assert_eq!(
+        reliance.id,
+        InstrumentId::new(ExchangeSegment::NSE_EQ, 2885)
+    );
+    assert_eq!(reliance.instrument, Some(Instrument::EQUITY));
+    assert_eq!(
+        (reliance.strike, reliance.option_type.as_deref()),
+        (None, None)
+    );
+    assert_eq!(master.by_isin("INE002A01018").len(), 2);
+    assert_eq!(
+        master.in_segment(ExchangeSegment::BSE_EQ)[0].id.security_id,
+        500325
+    );
+
+    let option = &master.by_security_id(35001)[0];
+    assert_eq!(option.lot_size, 75.0);
+    assert_eq!(option.strike, Some(24_000.0));
+    assert_eq!(option.expiry.unwrap().to_string(), "2025-06-26");
+    assert_eq!(option.underlying_security_id, Some(13));
+    assert_eq!(option.isin, None);
+
+    let compact = InstrumentMaster::from_reader(COMPACT.as_bytes()).unwrap();
+    let nifty = compact
+        .get(&InstrumentId::new(ExchangeSegment::IDX_I, 13))
+        .unwrap();
+    assert_eq!(nifty.display_name.as_deref(), Some("Nifty 50"));
+    assert_eq!(nifty.instrument, Some(Instrument::INDEX));
+}
+
+#[test]
+fn test_expiry_roll_plan() {
+    use std::sync::Arc;
+
+    use chrono::NaiveDate;
+    use dhan_rs::types::enums::FeedRequestCode;
+    use dhan_rs::ws::expiry::ExpiryRoll;
+
+    const OPTIONS: &str = "\
+EXCH_ID,SEGMENT,SECURITY_ID,ISIN,INSTRUMENT,UNDERLYING_SECURITY_ID,UNDERLYING_SYMBOL,SYMBOL_NAME,DISPLAY_NAME,INSTRUMENT_TYPE,SERIES,LOT_SIZE,SM_EXPIRY_DATE,STRIKE_PRICE,OPTION_TYPE,TICK_SIZE
+NSE,D,35001,NA,OPTIDX,13,NIFTY,NIFTY-Jun2025-24000-CE,,OP,NA,75.0,2025-06-26 14:30:00,24000.00000,CE,5.0000
+NSE,D,35002,NA,OPTIDX,13,NIFTY,NIFTY-Jul2025-24000-CE,,OP,NA,75.0,2025-07-31 14:30:00,24000.00000,CE,5.0000
+NSE,D,35003,NA,OPTIDX,13,NIFTY,NIFTY-Jul2025-24000-PE,,OP,NA,75.0,2025-07-31 14:30:00,24000.00000,PE,5.0000
+NSE,D,35004,NA,OPTIDX,13,NIFTY,NIFTY-Aug2025-24000-CE,,OP,NA,75.0,2025-08-28 14:30:00,24000.00000,CE,5.0000
+";
+    let master = Arc::new(InstrumentMaster::from_reader(OPTIONS.as_bytes()).unwrap());
+    let id = |sid| InstrumentId::new(ExchangeSegment::NSE_FNO, sid);
+    let june = master.get(&id(35001)).unwrap();
+    assert_eq!(master.next_in_series(june).unwrap().id, id(35002));
+
+    let subscribed = [(id(35001), FeedRequestCode::SubscribeFull)];
+    let on = |d| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+    // Still live on expiry day.
+    assert!(
+        ExpiryRoll::new(master.clone())
+            .plan(subscribed, on(26))
+            .is_empty()
+    );
+
+    let rolls = ExpiryRoll::new(master.clone()).plan(subscribed, on(27));
+    assert_eq!((rolls[0].expired, rolls[0].next), (id(35001), None));
+
+    let rolls = ExpiryRoll::new(master.clone())
+        .with_next_contract()
+        .plan(subscribed, on(27));
+    assert_eq!(rolls[0].next, Some(id(35002)));
+    assert_eq!(rolls[0].mode, FeedRequestCode::SubscribeFull);
+
+    // A successor that is already subscribed is not subscribed again.
+    let both = [subscribed[0], (id(35002), FeedRequestCode::SubscribeTicker)];
+    let rolls = ExpiryRoll::new(master)
+        .with_next_contract()
+        .plan(both, on(27));
+    assert_eq!(rolls.len(), 1);
+    assert_eq!(rolls[0].next, None);
+}
+
+#[test]
+fn test_filter_by_underlying_and_expiry() {
+    use chrono::NaiveDate;
+    use dhan_rs::instruments::{ExpirySelector, next_monthly, next_weekly};
+
+    const CHAIN: &str = "\
+EXCH_ID,SEGMENT,SECURITY_ID,ISIN,INSTRUMENT,UNDERLYING_SECURITY_ID,UNDERLYING_SYMBOL,SYMBOL_NAME,DISPLAY_NAME,INSTRUMENT_TYPE,SERIES,LOT_SIZE,SM_EXPIRY_DATE,STRIKE_PRICE,OPTION_TYPE,TICK_SIZE
+NSE,D,1,NA,OPTIDX,25,BANKNIFTY,BANKNIFTY-Jun2025-52000-CE,,OP,NA,30.0,2025-06-05 14:30:00,52000.0,CE,5.0
+NSE,D,2,NA,OPTIDX,25,BANKNIFTY,BANKNIFTY-Jun2025-52000-PE,,OP,NA,30.0,2025-06-05 14:30:00,52000.0,PE,5.0
+NSE,D,3,NA,OPTIDX,25,BANKNIFTY,BANKNIFTY-Jun2025-52000-CE,,OP,NA,30.0,2025-06-26 14:30:00,52000.0,CE,5.0
+NSE,D,4,NA,OPTIDX,13,NIFTY,NIFTY-Jun2025-24000-CE,,OP,NA,75.0,2025-06-05 14:30:00,24000.0,CE,5.0
+NSE,D,5,NA,OPTIDX,25,BANKNIFTY,BANKNIFTY-Jul2025-52000-CE,,OP,NA,30.0,2025-07-31 14:30:00,52000.0,CE,5.0
+";
+    let master = InstrumentMaster::from_reader(CHAIN.as_bytes()).unwrap();
+    let day = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let sids = |ids: Vec<InstrumentId>| ids.iter().map(|i| i.security_id).collect::<Vec<_>>();
+    let bank = master
+        .filter()
+        .segment(ExchangeSegment::NSE_FNO)
+        .underlying("banknifty")
+        .as_of(day(6, 2));
+
+    assert_eq!(sids(bank.clone().expiry(next_weekly()).ids()), [1, 2]);
+    assert_eq!(sids(bank.clone().expiry(next_monthly()).ids()), [3]);
+    assert_eq!(
+        sids(bank.clone().expiry(next_weekly()).option_type("pe").ids()),
+        [2]
+    );
+    assert_eq!(sids(bank.clone().expiry(day(7, 31)).ids()), [5]);
+    // After the June weekly, the nearest expiry is the June monthly.
+    assert_eq!(
+        sids(bank.as_of(day(6, 6)).expiry(ExpirySelector::Next).ids()),
+        [3]
+    );
+}
@@ -0,0 +1,59 @@
+//! Offline tests for alert fan-out (requires the `notify` feature).
+#![cfg(feature = "notify")]
+
+use std::sync::{Arc, Mutex};
+
+use dhan_rs::Result;
+use dhan_rs::notify::{Alert, AlertKind, AlertSink, Notifier, NotifyConfig, Severity};
+use futures_util::future::BoxFuture;
+
+/// Collects delivered alert messages in memory.
+#[derive(Clone, Default)]
+struct MemorySink(Arc<Mutex<Vec<String>>>);
+
+impl AlertSink for MemorySink {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    fn send<'a>(&'a self, alert: &'a Alert) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.0.lock().unwrap().push(alert.message.clone());
+            Ok(())
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_notifier_filters_by_severity() {
+    let sink = MemorySink::default();
+    let notifier = Notifier::new()
+        .with_sink(sink.clone())
+        .with_min_severity(Severity::Warning);
+
+    notifier
+        .notify(&Alert::new(Severity::Info, AlertKind::Risk, "ignored"))
+        .await;
+    let report = notifier
+        .notify(&Alert::new(
+            Severity::Critical,
+            AlertKind::KillSwitch,
+            "kill",
+        ))
+        .await;
+
+    assert!(report.all_ok());
+    assert_eq!(report.succeeded, ["memory".to_owned()]);
+    assert_eq!(*sink.0.lock().unwrap(), vec!["kill".to_owned()]);
+}
+
+#[test]
+fn test_config_builds_declared_sinks() {
+    let config: NotifyConfig = serde_json::from_str(
+        r#"{"sinks":[{"type":"slack","webhook_url":"https://example.invalid/hook"},
+                     {"type":"webhook","url":"https://example.invalid/alerts"}]}"#,
+    )
+    .unwrap();
+    assert_eq!(config.min_severity, Severity::Info);
+    assert_eq!(Notifier::from_config(&config).len(), 2);
+}
@@ -0,0 +1,792 @@
+//! Offline tests for the client-side order management state machines.
+
+use dhan_rs::DhanClient;
+use dhan_rs::oms::chain::OrderChains;
+use dhan_rs::oms::oco::{OcoBook, OcoEvent, OcoLeg};
+use dhan_rs::oms::tracker::{OrderTracker, TrackerEvent};
+use dhan_rs::types::enums::OrderStatus;
+use dhan_rs::types::orders::PlaceOrderRequest;
+use dhan_rs::ws::order_update::OrderUpdate;
+
+fn update(order_id: &str, status: OrderStatus) -> OrderUpdate {
+    OrderUpdate {
+        order_id: Some(order_id.to_owned()),
+        status: Some(status),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_oco_fill_then_sibling_cancel_completes() {
+    let mut book = OcoBook::new();
+    let id = book.watch("T1", "SL1");
+
+    let events = book.on_update(&update("SL1", OrderStatus::TRADED));
+    assert_eq!(
+        events,
+        vec![OcoEvent::Filled {
+            id,
+            leg: OcoLeg::StopLoss,
+            order_id: "SL1".into(),
+            sibling_order_id: "T1".into(),
+        }]
+    );
+
+    let events = book.on_update(&update("T1", OrderStatus::CANCELLED));
+    assert_eq!(
+        events,
+        vec![OcoEvent::Completed {
+            id,
+            winner: OcoLeg::StopLoss
+        }]
+    );
+    assert!(book.is_empty());
+}
+
+#[test]
+fn test_oco_sibling_partial_fill_while_cancelling_is_reported() {
+    let mut book = OcoBook::new();
+    let id = book.watch("T1", "SL1");
+
+    book.on_update(&update("T1", OrderStatus::TRADED));
+    let mut partial = update("SL1", OrderStatus::PART_TRADED);
+    partial.traded_quantity = Some(20);
+    assert!(book.on_update(&partial).is_empty());
+    // The cancellation lands on the rest of the stop-loss.
+    let events = book.on_update(&update("SL1", OrderStatus::CANCELLED));
+    assert_eq!(
+        events,
+        vec![OcoEvent::BothFilled {
+            id,
+            sibling_traded_quantity: Some(20)
+        }]
+    );
+    assert!(book.is_empty());
+}
+
+#[tokio::test]
+async fn test_oco_partial_fill_resizes_sibling() {
+    use dhan_rs::oms::oco::OcoCoordinator;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use reqwest::Method;
+
+    let mock = MockTransport::new()
+        .on(
+            Method::GET,
+            "/v2/orders/SL1",
+            MockResponse::json(
+                200,
+                serde_json::json!({"orderId": "SL1", "orderStatus": "PENDING", "orderType": "STOP_LOSS_MARKET", "quantity": 50, "triggerPrice": 1480.0, "validity": "DAY"}),
+            ),
+        )
+        .on(
+            Method::PUT,
+            "/v2/orders/SL1",
+            MockResponse::json(200, serde_json::json!({"orderId": "SL1", "orderStatus": "PENDING"})),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+    let mut oco = OcoCoordinator::new(client);
+    let id = oco.watch("T1", "SL1");
+
+    let mut partial = update("T1", OrderStatus::PART_TRADED);
+    partial.quantity = Some(50);
+    partial.traded_quantity = Some(30);
+    let events = oco.handle(&partial).await;
+    assert_eq!(
+        events,
+        vec![OcoEvent::PartiallyFilled {
+            id,
+            leg: OcoLeg::Target,
+            order_id: "T1".into(),
+            sibling_order_id: "SL1".into(),
+            traded_quantity: 30,
+            remaining_quantity: Some(20),
+        }]
+    );
+    let modify = mock
+        .requests()
+        .into_iter()
+        .find(|r| r.method == Method::PUT)
+        .unwrap()
+        .json()
+        .unwrap();
+    assert_eq!(modify["quantity"], 20);
+    assert_eq!(modify["triggerPrice"], 1480.0);
+}
+
+#[test]
+fn test_oco_simultaneous_fills_reported_once() {
+    let mut book = OcoBook::new();
+    let id = book.watch("T1", "SL1");
+
+    book.on_update(&update("T1", OrderStatus::TRADED));
+    let events = book.on_update(&update("SL1", OrderStatus::TRADED));
+    assert_eq!(
+        events,
+        vec![OcoEvent::BothFilled {
+            id,
+            sibling_traded_quantity: None
+        }]
+    );
+
+    // Late duplicates for a resolved pair are ignored.
+    assert!(
+        book.on_update(&update("SL1", OrderStatus::TRADED))
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_tracker_emits_fill_once() {
+    let mut tracker = OrderTracker::new();
+    tracker.apply(&update("E1", OrderStatus::PENDING));
+
+    let events = tracker.apply(&OrderUpdate {
+        traded_quantity: Some(10),
+        ..update("E1", OrderStatus::TRADED)
+    });
+    assert!(events.contains(&TrackerEvent::Filled {
+        order_id: "E1".into(),
+        quantity: 10,
+        average_price: None,
+    }));
+    assert!(tracker.apply(&update("E1", OrderStatus::TRADED)).is_empty());
+    assert_eq!(tracker.working().count(), 0);
+}
+
+#[test]
+fn test_armed_chains_survive_reopen() {
+    let path = std::env::temp_dir().join(format!("dhan-rs-chains-{}.jsonl", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let client = DhanClient::new("1000000001", "token");
+    let stop_loss: PlaceOrderRequest = serde_json::from_value(serde_json::json!({
+        "dhanClientId": "1000000001",
+        "transactionType": "SELL",
+        "exchangeSegment": "NSE_EQ",
+        "productType": "INTRADAY",
+        "orderType": "STOP_LOSS_MARKET",
+        "validity": "DAY",
+        "securityId": "1333",
+        "quantity": 10,
+        "triggerPrice": 1480.0
+    }))
+    .unwrap();
+
+    let mut chains = OrderChains::open(client.clone(), &path).unwrap();
+    let first = chains.on_fill("E1", stop_loss.clone()).unwrap();
+    drop(chains);
+
+    let mut reopened = OrderChains::open(client, &path).unwrap();
+    let pending: Vec<_> = reopened.pending().collect();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].parent_order_id, "E1");
+    assert!(reopened.on_fill("E2", stop_loss).unwrap() > first);
+
+    std::fs::remove_file(path).ok();
+}
+
+#[tokio::test]
+async fn test_chain_interrupted_mid_placement_is_not_placed_twice() {
+    use dhan_rs::oms::chain::ChainEvent;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use reqwest::Method;
+
+    let path = std::env::temp_dir().join(format!("dhan-rs-firing-{}.jsonl", std::process::id()));
+    let request = serde_json::json!({
+        "dhanClientId": "1000000001",
+        "transactionType": "SELL",
+        "exchangeSegment": "NSE_EQ",
+        "productType": "INTRADAY",
+        "orderType": "STOP_LOSS_MARKET",
+        "validity": "DAY",
+        "securityId": "1333",
+        "quantity": 10,
+        "triggerPrice": 1480.0
+    });
+    // The process died after journaling the intent, before recording the result.
+    let lines = [
+        serde_json::json!({"event": "armed", "chain_id": 1, "parent_order_id": "E1", "request": request}),
+        serde_json::json!({"event": "firing", "chain_id": 1, "correlation_id": "ch1-E1"}),
+    ];
+    std::fs::write(
+        &path,
+        lines.iter().map(|l| format!("{l}\n")).collect::<String>(),
+    )
+    .unwrap();
+
+    let mock = MockTransport::new()
+        .on(
+            Method::GET,
+            "/v2/orders/E1",
+            MockResponse::json(200, serde_json::json!({"orderId": "E1", "orderStatus": "TRADED"})),
+        )
+        .on(
+            Method::GET,
+            "/v2/orders/external/ch1-E1",
+            MockResponse::json(
+                200,
+                serde_json::json!({"orderId": "SL1", "orderStatus": "PENDING", "correlationId": "ch1-E1"}),
+            ),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+    let mut chains = OrderChains::open(client.clone(), &path).unwrap();
+    let events = chains.reconcile().await;
+
+    assert!(
+        matches!(&events[..], [ChainEvent::Fired { response, .. }] if response.order_id == "SL1")
+    );
+    assert!(mock.requests().iter().all(|r| r.method != Method::POST));
+    assert_eq!(
+        OrderChains::open(client, &path).unwrap().pending().count(),
+        0
+    );
+    std::fs::remove_file(path).ok();
+}
+
+#[tokio::test]
+async fn test_expiry_cancels_triggered_order() {
+    use dhan_rs::oms::expiry::ExpiryOutcome;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use reqwest::Method;
+
+    let mock = MockTransport::new()
+        .on(
+            Method::POST,
+            "/v2/orders",
+            MockResponse::json(
+                200,
+                serde_json::json!({"orderId": "S1", "orderStatus": "PENDING"}),
+            ),
+        )
+        .on(
+            Method::GET,
+            "/v2/orders/S1",
+            MockResponse::json(
+                200,
+                serde_json::json!({"orderId": "S1", "orderStatus": "TRIGGERED"}),
+            ),
+        )
+        .on(
+            Method::DELETE,
+            "/v2/orders/S1",
+            MockResponse::json(
+                200,
+                serde_json::json!({"orderId": "S1", "orderStatus": "CANCELLED"}),
+            ),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+    let req: PlaceOrderRequest = serde_json::from_value(serde_json::json!({
+        "dhanClientId": "1000000001",
+        "transactionType": "SELL",
+        "exchangeSegment": "NSE_FNO",
+        "productType": "INTRADAY",
+        "orderType": "STOP_LOSS",
+        "validity": "DAY",
+        "securityId": "35001",
+        "quantity": 50,
+        "price": 99.0,
+        "triggerPrice": 100.0
+    }))
+    .unwrap();
+
+    let order = client
+        .place_order_with_expiry(&req, chrono::Utc::now())
+        .await
+        .unwrap();
+    assert_eq!(order.outcome().await.unwrap(), ExpiryOutcome::Cancelled);
+    assert!(
+        mock.requests()
+            .iter()
+            .any(|r| r.method == Method::DELETE && r.path == "/v2/orders/S1")
+    );
+}
+
+#[test]
+fn test_journal_reopen_cuts_torn_tail() {
+    use dhan_rs::journal::Journal;
+    use std::io::Write;
+
+    let path = std::env::temp_dir().join(format!("dhan-rs-torn-{}.jsonl", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let mut journal = Journal::open(&path).unwrap();
+    journal.append(&serde_json::json!({ "n": 1 })).unwrap();
+    drop(journal);
+    // A crash mid-write leaves a partial line without a newline.
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    file.write_all(br#"{"n": 2, "pa"#).unwrap();
+    drop(file);
+
+    let mut journal = Journal::open(&path).unwrap();
+    journal.append(&serde_json::json!({ "n": 3 })).unwrap();
+    journal.append(&serde_json::json!({ "n": 4 })).unwrap();
+    drop(journal);
+
+    let records: Vec<serde_json::Value> = Journal::read(&path).unwrap();
+    let numbers: Vec<_> = records.iter().map(|r| r["n"].as_i64().unwrap()).collect();
+    assert_eq!(numbers, [1, 3, 4]);
+    std::fs::remove_file(path).ok();
+}
+
+#[test]
+fn test_request_builder_reports_missing_fields() {
+    use dhan_rs::types::enums::{ExchangeSegment, LegName};
+    use dhan_rs::types::super_order::ModifySuperOrderRequest;
+
+    let err = PlaceOrderRequest::builder()
+        .dhan_client_id("1000000001")
+        .exchange_segment(ExchangeSegment::NSE_EQ)
+        .build()
+        .unwrap_err();
+    let msg = err.to_string();
+    assert!(msg.contains("transaction_type"), "{msg}");
+    assert!(msg.contains("quantity"), "{msg}");
+    assert!(!msg.contains("dhan_client_id"), "{msg}");
+
+    let req = ModifySuperOrderRequest::builder()
+        .dhan_client_id("1000000001")
+        .order_id("SO1")
+        .leg_name(LegName::TARGET_LEG)
+        .target_price(1600.0)
+        .build()
+        .unwrap();
+    let json = serde_json::to_value(&req).unwrap();
+    assert_eq!(json["targetPrice"], 1600.0);
+    assert!(json.get("price").is_none());
+}
+
+#[test]
+fn test_build_request_fills_client_id() {
+    use dhan_rs::types::enums::*;
+    use dhan_rs::types::funds::MultiMarginRequest;
+
+    let client = DhanClient::new("1000000001", "token");
+    let req = client
+        .build_request(
+            PlaceOrderRequest::builder()
+                .transaction_type(TransactionType::BUY)
+                .exchange_segment(ExchangeSegment::NSE_EQ)
+                .product_type(ProductType::INTRADAY)
+                .order_type(OrderType::MARKET)
+                .validity(Validity::DAY)
+                .security_id("1333")
+                .quantity(1),
+        )
+        .unwrap();
+    assert_eq!(req.dhan_client_id, "1000000001");
+
+    let explicit = client
+        .build_request(
+            MultiMarginRequest::builder()
+                .dhan_client_id("2000000002")
+                .scripts(Vec::new()),
+        )
+        .unwrap();
+    assert_eq!(explicit.dhan_client_id.as_deref(), Some("2000000002"));
+}
+
+#[test]
+fn test_rejected_order_response_surfaces_reason() {
+    use dhan_rs::error::DhanError;
+    use dhan_rs::types::orders::OrderResponse;
+
+    let resp: OrderResponse = serde_json::from_value(serde_json::json!({
+        "orderId": "112111182198",
+        "orderStatus": "REJECTED",
+        "omsErrorCode": "16387",
+        "omsErrorDescription": "Insufficient margin"
+    }))
+    .unwrap();
+    assert_eq!(resp.status(), Some(OrderStatus::REJECTED));
+    match resp.into_result() {
+        Err(DhanError::OrderRejected(r)) => {
+            assert_eq!(r.description.as_deref(), Some("Insufficient margin"));
+            assert_eq!(
+                r.to_string(),
+                "order 112111182198: [16387] Insufficient margin"
+            );
+        }
+        other => panic!("expected rejection, got {other:?}"),
+    }
+
+    let ok: OrderResponse = serde_json::from_value(serde_json::json!({
+        "orderId": "1",
+        "orderStatus": "PENDING"
+    }))
+    .unwrap();
+    assert!(ok.into_result().is_ok());
+}
+
+#[test]
+fn test_order_filter_and_price_adjustment() {
+    use dhan_rs::oms::bulk::{OrderFilter, PriceAdjustment};
+    use dhan_rs::types::enums::{OrderType, TransactionType};
+    use dhan_rs::types::orders::OrderDetail;
+
+    let order: OrderDetail = serde_json::from_value(serde_json::json!({
+        "orderId": "1",
+        "orderStatus": "PENDING",
+        "transactionType": "SELL",
+        "orderType": "STOP_LOSS",
+        "securityId": "1333",
+        "correlationId": "MOMO-42",
+        "price": 1490.0,
+        "triggerPrice": 1495.0
+    }))
+    .unwrap();
+    let sl_sells = OrderFilter::new()
+        .with_transaction_type(TransactionType::SELL)
+        .with_order_type(OrderType::STOP_LOSS)
+        .with_correlation_prefix("MOMO-");
+    assert!(sl_sells.matches(&order));
+    assert!(!OrderFilter::new().with_security_id("11536").matches(&order));
+
+    assert_eq!(PriceAdjustment::Offset(0.05).apply(1495.0, 0.05), 1495.05);
+    assert_eq!(PriceAdjustment::Percent(-1.0).apply(1495.0, 0.05), 1480.05);
+    assert_eq!(PriceAdjustment::To(1500.02).apply(1495.0, 0.05), 1500.0);
+    // Currency derivatives tick in fractions of a paisa.
+    assert_eq!(PriceAdjustment::To(83.12374).apply(83.0, 0.0025), 83.1225);
+    assert_eq!(
+        PriceAdjustment::Offset(0.0025).apply(83.1225, 0.0025),
+        83.125
+    );
+}
+
+#[tokio::test]
+async fn test_bulk_modify_reports_failures_by_order() {
+    use std::time::Duration;
+
+    use dhan_rs::oms::bulk::{BulkModifier, ModifyOutcome, OrderFilter, PriceAdjustment};
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use reqwest::Method;
+
+    let mock = MockTransport::new()
+        .on(
+            Method::GET,
+            "/v2/orders",
+            MockResponse::json(
+                200,
+                serde_json::json!([
+                    {"orderId": "1", "orderStatus": "PENDING", "orderType": "LIMIT", "quantity": 10, "price": 100.0, "validity": "DAY"},
+                    {"orderId": "2", "orderStatus": "PENDING", "orderType": "LIMIT", "quantity": 10, "price": 200.0, "validity": "DAY"},
+                    {"orderId": "3", "orderStatus": "PENDING", "orderType": "MARKET", "quantity": 10, "validity": "DAY"}
+                ]),
+            ),
+        )
+        .on(
+            Method::PUT,
+            "/v2/orders/1",
+            MockResponse::json(200, serde_json::json!({"orderId": "1", "orderStatus": "PENDING"})),
+        )
+        .on(
+            Method::PUT,
+            "/v2/orders/2",
+            MockResponse::json(
+                400,
+                serde_json::json!({"errorType": "Order_Error", "errorCode": "DH-906", "errorMessage": "Order not found"}),
+            ),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock);
+    let mut modifier = BulkModifier::new(client).with_pacing(Duration::ZERO);
+
+    let report = modifier
+        .modify_orders_where(&OrderFilter::new(), PriceAdjustment::Offset(0.05))
+        .await
+        .unwrap();
+    assert_eq!(report.len(), 3);
+    assert_eq!(report.failed.len(), 1);
+    assert_eq!(report.failed[0].0.order_id.as_deref(), Some("2"));
+    let modified = &report.succeeded[0];
+    assert_eq!(modified.order_id, "1");
+    assert_eq!(modified.price, Some(100.05));
+    assert!(matches!(modified.outcome, ModifyOutcome::Modified(_)));
+    assert!(matches!(
+        report.succeeded[1].outcome,
+        ModifyOutcome::Unchanged
+    ));
+    assert_eq!(modifier.modifications("1"), 1);
+    assert_eq!(modifier.modifications("2"), 0);
+}
+
+#[test]
+fn test_order_tags_round_trip() {
+    use dhan_rs::oms::tags::{encode_tags, has_tag, tags_of};
+
+    let id = encode_tags(&["MOMO", "LEG1", "B7"], "42").unwrap();
+    assert_eq!(id, "MOMO-LEG1-B7-42");
+    assert_eq!(tags_of(&id), vec!["MOMO", "LEG1", "B7"]);
+    assert!(has_tag(&id, "LEG1"));
+    assert!(!has_tag(&id, "42"));
+
+    assert!(encode_tags(&["bad tag"], "1").is_err());
+    assert!(encode_tags(&["A".repeat(31).as_str()], "").is_err());
+}
+
+#[test]
+fn test_sell_authorization_blocks_uncovered_cnc_sells() {
+    use dhan_rs::risk::{RiskEngine, SellAuthorization, SellAuthorizationCheck};
+    use dhan_rs::types::enums::{
+        ExchangeSegment, OrderType, ProductType, TransactionType, Validity,
+    };
+    use dhan_rs::types::orders::PlaceOrderRequest;
+
+    let sell = |quantity: u64, product_type: ProductType| {
+        PlaceOrderRequest::builder()
+            .dhan_client_id("1000000001")
+            .transaction_type(TransactionType::SELL)
+            .exchange_segment(ExchangeSegment::NSE_EQ)
+            .product_type(product_type)
+            .order_type(OrderType::MARKET)
+            .validity(Validity::DAY)
+            .security_id("2885")
+            .quantity(quantity)
+            .build()
+            .unwrap()
+    };
+    let check = SellAuthorizationCheck::new(SellAuthorization::new().with_approval("2885", 10));
+    let risk = RiskEngine::new().with_check(check.clone());
+
+    assert!(risk.check(&sell(10, ProductType::CNC)).is_ok());
+    assert!(risk.check(&sell(11, ProductType::CNC)).is_err());
+    assert!(risk.check(&sell(50, ProductType::INTRADAY)).is_ok());
+
+    check.set(SellAuthorization::new().with_ddpi(true));
+    assert!(risk.check(&sell(50, ProductType::CNC)).is_ok());
+}
+
+#[tokio::test]
+async fn test_read_only_client_refuses_mutations() {
+    use dhan_rs::DhanError;
+    use dhan_rs::scope::{Scope, required_scope};
+
+    let client = DhanClient::read_only("1000000001", "t");
+    assert!(client.is_read_only());
+    assert!(client.clone().is_read_only());
+    let data_only = client.scoped(dhan_rs::scope::Scopes::only(Scope::MarketData));
+    assert!(matches!(
+        data_only.get_fund_limit().await.unwrap_err(),
+        DhanError::ScopeDenied {
+            scope: Scope::Funds,
+            ..
+        }
+    ));
+    // The leading slash does not change the scope a path needs.
+    assert_eq!(
+        required_scope(&reqwest::Method::GET, "v2/fundlimit"),
+        Scope::Funds
+    );
+    assert_eq!(
+        required_scope(&reqwest::Method::POST, "v2/marketfeed/ltp"),
+        Scope::MarketData
+    );
+    assert!(matches!(
+        data_only
+            .get::<serde_json::Value>("v2/fundlimit")
+            .await
+            .unwrap_err(),
+        DhanError::ScopeDenied {
+            scope: Scope::Funds,
+            ..
+        }
+    ));
+
+    let req: PlaceOrderRequest = serde_json::from_value(serde_json::json!({
+        "dhanClientId": "1000000001",
+        "transactionType": "BUY",
+        "exchangeSegment": "NSE_EQ",
+        "productType": "INTRADAY",
+        "orderType": "MARKET",
+        "validity": "DAY",
+        "securityId": "1333",
+        "quantity": 1
+    }))
+    .unwrap();
+    let err = client.place_order(&req).await.unwrap_err();
+    assert!(
+        matches!(err, DhanError::ScopeDenied { scope: Scope::OrderWrite, ref endpoint } if endpoint == "POST /v2/orders"),
+        "{err}"
+    );
+    assert!(matches!(
+        client.cancel_order("O1").await.unwrap_err(),
+        DhanError::ScopeDenied { .. }
+    ));
+    assert!(matches!(
+        client.manage_kill_switch("ACTIVATE").await.unwrap_err(),
+        DhanError::ScopeDenied {
+            scope: Scope::Admin,
+            ..
+        }
+    ));
+    assert!(matches!(
+        client.generate_tpin().await.unwrap_err(),
+        DhanError::ScopeDenied { .. }
+    ));
+}
+
+#[tokio::test]
+async fn test_audit_log_records_and_verifies_sent_orders() {
+    use dhan_rs::audit::AuditLog;
+
+    let path = std::env::temp_dir().join(format!("dhan-rs-audit-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    // Nothing listens on the discard port; the audit entry is written
+    // before the request fails to connect.
+    let client = DhanClient::with_base_url("1000000001", "t", "http://127.0.0.1:9")
+        .with_audit_log(AuditLog::open(&path).unwrap());
+    let req: PlaceOrderRequest = serde_json::from_value(serde_json::json!({
+        "dhanClientId": "1000000001",
+        "transactionType": "BUY",
+        "exchangeSegment": "NSE_EQ",
+        "productType": "INTRADAY",
+        "orderType": "MARKET",
+        "validity": "DAY",
+        "securityId": "1333",
+        "quantity": 1
+    }))
+    .unwrap();
+    assert!(client.place_order(&req).await.is_err());
+    assert!(client.cancel_order("O1").await.is_err());
+    // Reads are not audited.
+    assert!(client.get_orders().await.is_err());
+
+    // Reopening continues the chain.
+    let reopened = client
+        .clone()
+        .with_audit_log(AuditLog::open(&path).unwrap());
+    assert!(reopened.cancel_order("O2").await.is_err());
+
+    let report = AuditLog::verify(&path).unwrap();
+    assert_eq!(report.records, 3);
+    assert!(report.is_intact());
+    let found = AuditLog::find(&path, &serde_json::to_vec(&req).unwrap()).unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(
+        (found[0].method.as_str(), found[0].path.as_str()),
+        ("POST", "/v2/orders")
+    );
+
+    let tampered = std::fs::read_to_string(&path)
+        .unwrap()
+        .replace("/v2/orders/O1", "/v2/orders/O9");
+    std::fs::write(&path, &tampered).unwrap();
+    assert_eq!(AuditLog::verify(&path).unwrap().first_invalid, Some(1));
+
+    // A record cut short by a crash is reported, not silently skipped.
+    let intact = tampered.replace("/v2/orders/O9", "/v2/orders/O1");
+    std::fs::write(&path, format!("{intact}{{\"seq\":3,")).unwrap();
+    let report = AuditLog::verify(&path).unwrap();
+    assert_eq!((report.records, report.first_invalid), (3, None));
+    assert!(report.torn_tail);
+    assert!(!report.is_intact());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_client_fills_missing_correlation_id() {
+    use dhan_rs::oms::correlation::{CorrelationIdGenerator, SequenceGenerator, UuidGenerator};
+    use dhan_rs::types::enums::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    let id = UuidGenerator.next_id();
+    assert_eq!(id.len(), 25);
+    assert_ne!(id, UuidGenerator.next_id());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let server = tokio::spawn(async move {
+        let mut bodies = Vec::new();
+        for _ in 0..2 {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 8192];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).into_owned();
+            let body = request.split("\r\n\r\n").nth(1).unwrap_or_default();
+            bodies.push(serde_json::from_str::<serde_json::Value>(body).unwrap());
+            let reply = r#"{"orderId":"1","orderStatus":"TRANSIT"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{reply}",
+                reply.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+        bodies
+    });
+
+    let client =
+        DhanClient::with_base_url("1000000001", "token", format!("http://127.0.0.1:{port}"))
+            .with_correlation_ids(SequenceGenerator::starting_at("bot-", 7));
+    let req = PlaceOrderRequest::builder()
+        .dhan_client_id("1000000001")
+        .transaction_type(TransactionType::BUY)
+        .exchange_segment(ExchangeSegment::NSE_EQ)
+        .product_type(ProductType::INTRADAY)
+        .order_type(OrderType::MARKET)
+        .validity(Validity::DAY)
+        .security_id("1333")
+        .quantity(1)
+        .build()
+        .unwrap();
+    client.place_order(&req).await.unwrap();
+    let mut tagged = req.clone();
+    tagged.correlation_id = Some("mine".into());
+    client.place_order(&tagged).await.unwrap();
+
+    let bodies = server.await.unwrap();
+    assert_eq!(bodies[0]["correlationId"], "bot-7");
+    assert_eq!(bodies[1]["correlationId"], "mine");
+    assert!(req.correlation_id.is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_duplicate_guard_rejects_repeats_within_window() {
+    use std::time::Duration;
+
+    use dhan_rs::oms::tracker::OrderTracker;
+    use dhan_rs::risk::{DuplicateOrderGuard, RiskEngine};
+    use dhan_rs::types::enums::*;
+    use dhan_rs::ws::order_update::OrderUpdate;
+
+    let guard = DuplicateOrderGuard::new(Duration::from_secs(5));
+    let risk = RiskEngine::new().with_check(guard.clone());
+    let req = PlaceOrderRequest::builder()
+        .dhan_client_id("1000000001")
+        .transaction_type(TransactionType::BUY)
+        .exchange_segment(ExchangeSegment::NSE_EQ)
+        .product_type(ProductType::INTRADAY)
+        .order_type(OrderType::LIMIT)
+        .validity(Validity::DAY)
+        .security_id("1333")
+        .quantity(10)
+        .price(1500.0)
+        .build()
+        .unwrap();
+    let mut other = req.clone();
+    other.quantity = 11;
+
+    assert!(risk.check(&req).is_ok());
+    assert!(risk.check(&req).is_err());
+    assert!(risk.check(&other).is_ok());
+
+    guard.allow_next(&req);
+    assert!(risk.check(&req).is_ok());
+    assert!(risk.check(&req).is_err());
+
+    tokio::time::advance(Duration::from_secs(5)).await;
+    assert!(risk.check(&req).is_ok());
+
+    let mut tracker = OrderTracker::new().with_duplicate_guard(guard.clone());
+    tracker.apply(&OrderUpdate {
+        order_id: Some("1".into()),
+        security_id: Some("1333".into()),
+        transaction_type: Some(TransactionType::BUY),
+        status: Some(OrderStatus::REJECTED),
+        quantity: Some(10),
+        price: Some(1500.0),
+        ..OrderUpdate::default()
+    });
+    assert!(risk.check(&req).is_ok());
+}
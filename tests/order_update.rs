@@ -0,0 +1,144 @@
+//! Offline tests for the order-update stream, driven by recorded sessions.
+
+use std::io::Write;
+
+use dhan_rs::error::DhanError;
+use dhan_rs::types::enums::{OrderStatus, ProductType, TransactionType};
+use dhan_rs::ws::order_update::{AuthReply, OrderUpdate, OrderUpdateStream, classify_auth_reply};
+use futures_util::StreamExt;
+
+const TRADED_ALERT: &str = r#"{"Type":"order_alert","Data":{"Exchange":"NSE","Segment":"E","SecurityId":"1333","OrderNo":"1124091136546","Product":"I","TxnType":"B","OrderType":"LMT","Validity":"DAY","Quantity":1,"TradedQty":1,"Price":1500.0,"AvgTradedPrice":1499.5,"Status":"Traded","OrderDateTime":"2024-09-11 09:58:03","series":"EQ","refLtp":1499.9}}"#;
+
+/// Write `lines` to a fresh temp file and return its path.
+fn recording(name: &str, lines: &[&str]) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("dhan-rs-{name}-{}.jsonl", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    for line in lines {
+        writeln!(file, "{line}").unwrap();
+    }
+    path
+}
+
+#[tokio::test]
+async fn test_replay_yields_recorded_messages() {
+    let path = recording("replay", &[TRADED_ALERT, "", "{not json"]);
+    let mut stream = OrderUpdateStream::from_recording(&path).unwrap();
+    assert!(stream.is_replay());
+
+    let first = stream.next().await.unwrap().unwrap();
+    assert_eq!(first.Type, "order_alert");
+    assert_eq!(first.Data.OrderNo.as_deref(), Some("1124091136546"));
+
+    // Blank lines are skipped; malformed payloads surface as JSON errors.
+    let second = stream.next().await.unwrap();
+    assert!(matches!(second, Err(DhanError::Json(_))));
+    assert!(stream.next().await.is_none());
+
+    std::fs::remove_file(path).ok();
+}
+
+#[tokio::test]
+async fn test_replay_can_be_re_recorded() {
+    let src = recording("src", &[TRADED_ALERT]);
+    let dst = std::env::temp_dir().join(format!("dhan-rs-dst-{}.jsonl", std::process::id()));
+    std::fs::remove_file(&dst).ok();
+
+    let mut stream = OrderUpdateStream::from_recording(&src)
+        .unwrap()
+        .record_to(&dst)
+        .unwrap();
+    while stream.next().await.is_some() {}
+
+    let copied = std::fs::read_to_string(&dst).unwrap();
+    assert_eq!(copied.trim_end(), TRADED_ALERT);
+
+    std::fs::remove_file(src).ok();
+    std::fs::remove_file(dst).ok();
+}
+
+#[test]
+fn test_normalized_update_merges_and_parses_fields() {
+    let msg: dhan_rs::ws::order_update::OrderUpdateMessage =
+        serde_json::from_str(TRADED_ALERT).unwrap();
+    let update: OrderUpdate = msg.into_update();
+
+    assert_eq!(update.product_type, Some(ProductType::INTRADAY));
+    assert_eq!(update.transaction_type, Some(TransactionType::BUY));
+    assert_eq!(update.status, Some(OrderStatus::TRADED));
+    assert_eq!(update.series.as_deref(), Some("EQ"));
+    assert_eq!(update.ref_ltp, Some(1499.9));
+    assert_eq!(
+        update.order_time.unwrap().to_string(),
+        "2024-09-11 09:58:03"
+    );
+    assert_eq!(
+        update.order_time_utc().unwrap().to_rfc3339(),
+        "2024-09-11T04:28:03+00:00"
+    );
+}
+
+#[test]
+fn test_wire_codes_keep_unrecognized_values() {
+    use dhan_rs::types::enums::OrderType;
+    use dhan_rs::ws::order_update::{OrderUpdateMessage, WireCode};
+
+    let alert = TRADED_ALERT.replace(r#""Product":"I""#, r#""Product":"Z""#);
+    let data = serde_json::from_str::<OrderUpdateMessage>(&alert)
+        .unwrap()
+        .Data;
+
+    assert_eq!(data.order_type(), Some(WireCode::Known(OrderType::LIMIT)));
+    assert_eq!(
+        data.status().and_then(WireCode::into_known),
+        Some(OrderStatus::TRADED)
+    );
+    assert_eq!(data.product_type(), Some(WireCode::Unknown("Z".into())));
+    assert_eq!(data.to_typed().product_type, None);
+}
+
+#[test]
+fn test_postback_converts_to_normalized_update() {
+    use dhan_rs::types::enums::ExchangeSegment;
+    use dhan_rs::types::postback::PostbackPayload;
+
+    let payload: PostbackPayload = serde_json::from_str(
+        r#"{"orderId":"1124091136546","orderStatus":"TRADED","transactionType":"BUY",
+            "exchangeSegment":"NSE_FNO","productType":"INTRADAY","securityId":"52175",
+            "quantity":50,"filled_qty":25,"updateTime":"2024-09-11 09:58:03"}"#,
+    )
+    .unwrap();
+    let update = payload.into_update();
+    assert_eq!(update.exchange_segment(), Some(ExchangeSegment::NSE_FNO));
+    assert_eq!(update.status, Some(OrderStatus::TRADED));
+    assert_eq!(update.transaction_type, Some(TransactionType::BUY));
+    assert_eq!(update.product_type, Some(ProductType::INTRADAY));
+    assert_eq!(update.remaining_quantity, Some(25));
+    assert!(update.last_updated_time.is_some());
+}
+
+#[test]
+fn test_classify_auth_reply() {
+    assert_eq!(classify_auth_reply(TRADED_ALERT), AuthReply::Update);
+    assert_eq!(
+        classify_auth_reply(
+            r#"{"errorCode":"DH-901","errorMessage":"Client ID or token is invalid"}"#
+        ),
+        AuthReply::Failed
+    );
+    assert_eq!(
+        classify_auth_reply(r#"{"status":"failed","message":"Token expired"}"#),
+        AuthReply::Failed
+    );
+    assert_eq!(classify_auth_reply("Unauthorized"), AuthReply::Failed);
+
+    assert_eq!(
+        classify_auth_reply(r#"{"status":"success","message":"Login successful"}"#),
+        AuthReply::Ack
+    );
+    // Failure words outside the status fields do not fail the login.
+    assert_eq!(
+        classify_auth_reply(r#"{"Type":"login","Data":{"Remarks":"0 orders rejected today"}}"#),
+        AuthReply::Ack
+    );
+    assert_eq!(classify_auth_reply("connected"), AuthReply::Ack);
+}
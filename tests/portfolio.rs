@@ -0,0 +1,386 @@
+//! Offline tests for book-level position views.
+
+use std::collections::HashMap;
+
+use dhan_rs::portfolio::net_by_underlying;
+use dhan_rs::portfolio::netting::InstrumentInfo;
+use dhan_rs::types::portfolio::Position;
+
+fn position(value: serde_json::Value) -> Position {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn test_net_by_underlying_folds_futures_and_options() {
+    let positions = vec![
+        position(serde_json::json!({
+            "tradingSymbol": "NIFTY-Dec2024-FUT",
+            "securityId": "35001",
+            "netQty": 75,
+            "drvExpiryDate": "2024-12-26",
+            "drvOptionType": "XX",
+            "unrealizedProfit": 1200.0
+        })),
+        position(serde_json::json!({
+            "tradingSymbol": "NIFTY-Dec2024-24000-PE",
+            "securityId": "35002",
+            "netQty": 150,
+            "buyQty": 150,
+            "buyAvg": 80.0,
+            "drvOptionType": "PUT"
+        })),
+        position(serde_json::json!({
+            "tradingSymbol": "NIFTY-Dec2024-25000-CE",
+            "securityId": "35003",
+            "netQty": -75,
+            "sellQty": 75,
+            "sellAvg": 40.0,
+            "drvOptionType": "CALL"
+        })),
+        position(serde_json::json!({
+            "tradingSymbol": "RELIANCE",
+            "securityId": "2885",
+            "netQty": 10
+        })),
+    ];
+    let master: HashMap<String, InstrumentInfo> = HashMap::from([(
+        "35002".to_owned(),
+        InstrumentInfo {
+            delta: Some(-0.4),
+            margin_per_unit: Some(10.0),
+            ..Default::default()
+        },
+    )]);
+
+    let book = net_by_underlying(&positions, &master);
+    assert_eq!(book.len(), 2);
+    let nifty = &book[0];
+    assert_eq!(nifty.underlying, "NIFTY");
+    assert_eq!(nifty.positions, 3);
+    assert_eq!(nifty.net_quantity, 75);
+    assert!((nifty.delta_quantity - (75.0 - 60.0)).abs() < 1e-9);
+    assert_eq!(nifty.missing_delta, 1);
+    assert!((nifty.net_premium - (12_000.0 - 3_000.0)).abs() < 1e-9);
+    assert_eq!(nifty.margin, Some(1500.0));
+    assert_eq!(book[1].underlying, "RELIANCE");
+    assert_eq!(book[1].delta_quantity, 10.0);
+}
+
+#[tokio::test]
+async fn test_pnl_stream_applies_fills_and_marks() {
+    use dhan_rs::portfolio::pnl::{PnlBook, PnlStream};
+    use dhan_rs::types::enums::{ExchangeSegment, FeedResponseCode, TransactionType};
+    use dhan_rs::types::instrument::InstrumentId;
+    use dhan_rs::ws::market_feed::{MarketFeedEvent, PacketHeader};
+    use dhan_rs::ws::order_update::OrderUpdate;
+    use futures_util::StreamExt;
+
+    let fill = |order_id: &str, side, traded: i64, avg: f64| OrderUpdate {
+        exchange: Some("NSE".into()),
+        segment: Some("E".into()),
+        security_id: Some("1333".into()),
+        order_id: Some(order_id.into()),
+        transaction_type: Some(side),
+        traded_quantity: Some(traded),
+        average_traded_price: Some(avg),
+        ..Default::default()
+    };
+    let ticker = |ltp: f32| MarketFeedEvent::Ticker {
+        header: PacketHeader {
+            response_code: FeedResponseCode::Ticker,
+            message_length: 16,
+            exchange_segment: Some(ExchangeSegment::NSE_EQ),
+            exchange_segment_raw: 1,
+            security_id: 1333,
+        },
+        ltp,
+        ltt: 1_726_041_600,
+    };
+    let id = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+
+    let mut book = PnlBook::new();
+    assert!(book.on_order_update(&fill("B1", TransactionType::BUY, 10, 100.0)));
+    // Second partial fill of 10 more at 110 (average 105).
+    assert!(book.on_order_update(&fill("B1", TransactionType::BUY, 20, 105.0)));
+    assert!(!book.on_order_update(&fill("B1", TransactionType::BUY, 20, 105.0)));
+    assert_eq!(book.get(&id).unwrap().average_price, 105.0);
+
+    let orders = futures_util::stream::iter(vec![fill("S1", TransactionType::SELL, 5, 115.0)]);
+    // Keep the feed open briefly so the order stream is drained too.
+    let events = futures_util::stream::iter(vec![ticker(110.0)]).chain(
+        futures_util::stream::pending::<MarketFeedEvent>()
+            .take_until(tokio::time::sleep(std::time::Duration::from_millis(50))),
+    );
+    let updates: Vec<_> = PnlStream::new(book)
+        .with_order_updates(orders)
+        .stream(events)
+        .collect()
+        .await;
+    let last = updates.last().unwrap();
+    assert_eq!(last.realized, 50.0);
+    assert_eq!(last.unrealized, 75.0);
+    assert_eq!(last.per_instrument[&id].net_quantity, 15);
+}
+
+#[test]
+fn test_diff_holdings_flags_unexplained_changes() {
+    use dhan_rs::portfolio::holdings::diff_holdings;
+    use dhan_rs::types::portfolio::Holding;
+    use dhan_rs::types::statements::TradeHistoryEntry;
+
+    let holding = |isin: &str, qty: i64| -> Holding {
+        serde_json::from_value(serde_json::json!({
+            "isin": isin, "tradingSymbol": isin, "totalQty": qty
+        }))
+        .unwrap()
+    };
+    let trade: TradeHistoryEntry = serde_json::from_value(serde_json::json!({
+        "isin": "INE002A01018",
+        "transactionType": "BUY",
+        "productType": "CNC",
+        "tradedQuantity": 5
+    }))
+    .unwrap();
+
+    let previous = [holding("INE002A01018", 10), holding("INE040A01034", 20)];
+    // Bought 5 RELIANCE; HDFCBANK doubled with no trade (bonus).
+    let current = [holding("INE002A01018", 15), holding("INE040A01034", 40)];
+    let deltas = diff_holdings(&previous, &current, &[trade]);
+    assert_eq!(deltas.len(), 1);
+    assert_eq!(deltas[0].key, "INE040A01034");
+    assert_eq!(deltas[0].unexplained_qty, 20);
+}
+
+#[test]
+fn test_sellable_quantities_skips_empty_and_pledged() {
+    use dhan_rs::portfolio::edis::sellable_quantities;
+    use dhan_rs::types::portfolio::Holding;
+
+    let holdings: Vec<Holding> = serde_json::from_value(serde_json::json!([
+        { "isin": "INE002A01018", "totalQty": 10, "availableQty": 8 },
+        { "isin": "INE040A01034", "totalQty": 5, "availableQty": 0 },
+        { "securityId": "1333", "totalQty": 3 }
+    ]))
+    .unwrap();
+    let qty = sellable_quantities(&holdings);
+    assert_eq!(qty.len(), 1);
+    assert_eq!(qty["INE002A01018"], 8);
+}
+
+#[test]
+fn test_diff_positions_reports_added_removed_and_changed() {
+    use dhan_rs::diff::diff_positions;
+
+    let reliance = |qty: i64| {
+        position(serde_json::json!({
+            "securityId": "2885",
+            "exchangeSegment": "NSE_EQ",
+            "productType": "INTRADAY",
+            "netQty": qty
+        }))
+    };
+    let infy = position(serde_json::json!({
+        "securityId": "1594",
+        "exchangeSegment": "NSE_EQ",
+        "productType": "CNC",
+        "netQty": 5
+    }));
+    let tcs = position(serde_json::json!({
+        "securityId": "11536",
+        "exchangeSegment": "NSE_EQ",
+        "productType": "INTRADAY",
+        "netQty": -3
+    }));
+
+    let changes = diff_positions(&[reliance(10), infy.clone()], &[reliance(0), tcs, infy]);
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes.added[0].security_id.as_deref(), Some("11536"));
+    assert!(changes.removed.is_empty());
+    let modified = &changes.changed[0];
+    assert_eq!(modified.key, "NSE_EQ:2885:INTRADAY");
+    let qty = modified.field("netQty").unwrap();
+    assert_eq!((qty.old.clone(), qty.new.clone()), (10.into(), 0.into()));
+
+    let changes = diff_positions(&[reliance(0)], &[]);
+    assert_eq!(changes.removed.len(), 1);
+    assert!(diff_positions(&[reliance(1)], &[reliance(1)]).is_empty());
+}
+
+#[test]
+fn test_attribution_splits_gap_from_session() {
+    use dhan_rs::portfolio::attribution::PnlAttribution;
+    use dhan_rs::types::enums::{ExchangeSegment, FeedResponseCode};
+    use dhan_rs::types::instrument::InstrumentId;
+    use dhan_rs::ws::market_feed::{MarketFeedEvent, PacketHeader};
+
+    let header = |code| PacketHeader {
+        response_code: code,
+        message_length: 16,
+        exchange_segment: Some(ExchangeSegment::NSE_FNO),
+        exchange_segment_raw: 2,
+        security_id: 35001,
+    };
+    let id = InstrumentId::new(ExchangeSegment::NSE_FNO, 35001);
+
+    // Short 75 carried overnight; no fills today.
+    let mut attribution = PnlAttribution::from_positions(&[position(serde_json::json!({
+        "exchangeSegment": "NSE_FNO",
+        "securityId": "35001",
+        "carryForwardSellQty": 75,
+        "multiplier": 1
+    }))]);
+    attribution.on_event(&MarketFeedEvent::PrevClose {
+        header: header(FeedResponseCode::PrevClose),
+        prev_close: 100.0,
+        prev_oi: 0,
+    });
+    let row = &attribution.report()[0];
+    assert_eq!((row.overnight_qty, row.gap), (-75, None));
+
+    attribution.set_prices(id, 110.0, 104.0);
+    let row = &attribution.report()[0];
+    assert_eq!(row.gap, Some(-750.0));
+    assert_eq!(row.intraday(), Some(450.0));
+    assert_eq!(row.total(), Some(-300.0));
+}
+
+#[test]
+fn test_equity_recorder_drawdown_and_turnover() {
+    use dhan_rs::portfolio::equity::EquityRecorder;
+
+    let mut recorder = EquityRecorder::new(std::time::Duration::from_secs(60));
+    for (i, equity) in [100.0, 120.0, 90.0, 110.0, 80.0, 130.0]
+        .into_iter()
+        .enumerate()
+    {
+        assert!(recorder.record(i as i64 * 60, equity, equity * 2.0));
+    }
+    // Inside the cadence of the last sample.
+    assert!(!recorder.record(5 * 60 + 30, 0.0, 0.0));
+
+    let dd = recorder.max_drawdown().unwrap();
+    assert_eq!((dd.peak_at, dd.trough_at, dd.amount), (60, 240, 40.0));
+    assert_eq!(dd.fraction, 40.0 / 120.0);
+    assert_eq!(recorder.current_drawdown(), 0.0);
+    assert_eq!(recorder.max_exposure(), 260.0);
+
+    recorder.add_turnover(-210.0);
+    assert_eq!(recorder.turnover_ratio(), 2.0);
+}
+
+#[test]
+fn test_strategy_report_compares_round_trips() {
+    use dhan_rs::portfolio::performance::StrategyReport;
+    use dhan_rs::types::statements::TradeHistoryEntry;
+
+    let trade = |order: &str, side: &str, qty: i64, price: f64, time: &str| -> TradeHistoryEntry {
+        serde_json::from_value(serde_json::json!({
+            "orderId": order,
+            "transactionType": side,
+            "exchangeSegment": "NSE_EQ",
+            "securityId": "1333",
+            "tradedQuantity": qty,
+            "tradedPrice": price,
+            "brokerageCharges": 10.0,
+            "stt": 2.5,
+            "exchangeTime": time,
+        }))
+        .unwrap()
+    };
+    let mut report = StrategyReport::new();
+    report.tag_order("1", "mr");
+    report.tag_order("2", "mr");
+    report.tag_order("3", "mr");
+    report.tag_order("4", "mr");
+    report.tag_order("5", "breakout");
+    let used = report.add_trades(&[
+        trade("2", "SELL", 10, 110.0, "2024-09-02 10:00:00"),
+        trade("1", "BUY", 10, 100.0, "2024-09-02 09:30:00"),
+        trade("3", "SELL", 5, 100.0, "2024-09-03 09:30:00"),
+        trade("4", "BUY", 5, 104.0, "2024-09-03 10:00:00"),
+        trade("5", "BUY", 1, 500.0, "2024-09-03 10:00:00"),
+        trade("9", "BUY", 1, 500.0, "2024-09-03 10:00:00"),
+    ]);
+    assert_eq!(used, 5);
+
+    let rows = report.rows();
+    assert_eq!(rows[0].strategy, "breakout");
+    assert_eq!((rows[0].round_trips, rows[0].win_rate), (0, None));
+    let mr = &rows[1];
+    assert_eq!((mr.fills, mr.round_trips), (4, 2));
+    assert_eq!((mr.gross_pnl, mr.costs, mr.net_pnl), (80.0, 50.0, 30.0));
+    assert_eq!(mr.win_rate, Some(0.5));
+    assert_eq!(
+        (mr.average_win, mr.average_loss),
+        (Some(100.0), Some(-20.0))
+    );
+
+    let csv = report.to_csv().unwrap();
+    assert!(csv.starts_with("strategy,fills,round_trips,gross_pnl"));
+    assert_eq!(csv.lines().count(), 3);
+}
+
+#[test]
+fn test_reconcile_flags_overcharged_settlement_day() {
+    use dhan_rs::portfolio::reconcile::{Reconciler, SettlementStatus};
+    use dhan_rs::types::statements::{LedgerEntry, TradeHistoryEntry};
+
+    let trade = |side: &str, time: &str, qty: i64, price: f64, brokerage: f64| {
+        serde_json::from_value::<TradeHistoryEntry>(serde_json::json!({
+            "transactionType": side,
+            "tradedQuantity": qty,
+            "tradedPrice": price,
+            "brokerageCharges": brokerage,
+            "stt": 2.5,
+            "exchangeTime": time,
+        }))
+        .unwrap()
+    };
+    let bill = |date: &str, debit: &str, credit: &str| {
+        serde_json::from_value::<LedgerEntry>(serde_json::json!({
+            "narration": "Trade Bill NSE-CAPITAL",
+            "voucherdate": date,
+            "debit": debit,
+            "credit": credit,
+        }))
+        .unwrap()
+    };
+    let trades = [
+        trade("BUY", "2024-09-12 09:30:00", 10, 100.0, 20.0),
+        trade("SELL", "2024-09-12 14:00:00", 10, 110.0, 20.0),
+        trade("BUY", "2024-09-13 10:00:00", 5, 200.0, 20.0),
+    ];
+    let ledger = [
+        // 100 traded − 45 charges.
+        bill("Sep 12, 2024", "0.00", "55.00"),
+        // 1000 bought + 22.5 charges expected; 40 of brokerage extra.
+        bill("Sep 13, 2024", "1,062.50", "0.00"),
+        LedgerEntry {
+            narration: Some("Funds added via UPI".into()),
+            ..bill("Sep 13, 2024", "0.00", "5000.00")
+        },
+        bill("Sep 16, 2024", "15.00", "0.00"),
+    ];
+
+    let report = Reconciler::new().reconcile(&ledger, &trades);
+    let statuses: Vec<_> = report.days.iter().map(|d| d.status).collect();
+    assert_eq!(
+        statuses,
+        [
+            SettlementStatus::Matched,
+            SettlementStatus::Mismatch,
+            SettlementStatus::MissingTrades,
+        ]
+    );
+    let day = &report.days[1];
+    assert_eq!(day.expected, -1022.5);
+    assert_eq!(day.difference, -40.0);
+    assert_eq!(day.ledger_charges, 62.5);
+    assert_eq!(report.discrepancies().count(), 2);
+
+    // Posted a trading day later, the 13 Sep trade settles on Monday.
+    let report = Reconciler::new()
+        .with_settlement_lag(1)
+        .reconcile(&ledger, &trades);
+    assert_eq!(report.days.last().unwrap().trades, 1);
+}
@@ -0,0 +1,56 @@
+//! Tests for the feed-quality monitor.
+
+use chrono::{TimeZone, Utc};
+use dhan_rs::types::enums::ExchangeSegment;
+use dhan_rs::types::instrument::InstrumentId;
+use dhan_rs::ws::market_feed::Tick;
+use dhan_rs::ws::quality::{FeedQualityConfig, FeedQualityEvent, FeedQualityMonitor};
+
+fn tick(ltp: f64, ltt: i64, volume: i64) -> Tick {
+    Tick {
+        instrument: InstrumentId::new(ExchangeSegment::NSE_EQ, 1333),
+        ltp,
+        ltt,
+        last_qty: None,
+        volume: Some(volume),
+        open: None,
+        high: None,
+        low: None,
+        close: None,
+        oi: None,
+    }
+}
+
+#[test]
+fn test_monitor_flags_spikes_regressions_and_staleness() {
+    let mut monitor = FeedQualityMonitor::new(FeedQualityConfig::default());
+    // Wednesday 2024-09-11, 10:00 IST.
+    let t0 = Utc.with_ymd_and_hms(2024, 9, 11, 4, 30, 0).unwrap();
+
+    assert!(monitor.on_tick_at(&tick(100.0, 1_000, 500), t0).is_empty());
+    let events = monitor.on_tick_at(&tick(150.0, 990, 400), t0);
+    assert!(matches!(
+        events[0],
+        FeedQualityEvent::TimeRegression {
+            previous: 1_000,
+            ..
+        }
+    ));
+    assert!(matches!(
+        events[1],
+        FeedQualityEvent::VolumeRegression { previous: 500, .. }
+    ));
+    assert!(matches!(events[2], FeedQualityEvent::PriceSpike { .. }));
+
+    let later = t0 + chrono::Duration::seconds(31);
+    let stale = monitor.check_stale_at(later);
+    assert!(matches!(stale[..], [FeedQualityEvent::Stale { .. }]));
+    assert!(monitor.check_stale_at(later).is_empty());
+
+    let events = monitor.on_tick_at(&tick(151.0, 1_040, 600), later);
+    assert!(matches!(events[..], [FeedQualityEvent::Recovered { .. }]));
+
+    // Saturday: never stale.
+    let saturday = Utc.with_ymd_and_hms(2024, 9, 14, 6, 0, 0).unwrap();
+    assert!(monitor.check_stale_at(saturday).is_empty());
+}
@@ -0,0 +1,90 @@
+//! Offline tests for multi-request quote fetching, against a local HTTP
+//! listener.
+
+use std::time::Duration;
+
+use dhan_rs::DhanClient;
+use dhan_rs::types::enums::ExchangeSegment;
+use dhan_rs::types::instrument::InstrumentId;
+use dhan_rs::types::market_quote::MarketQuoteRequest;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn test_quotes_multi_merges_and_reports_failed_chunks() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let replies = [
+            (
+                "200 OK",
+                r#"{"status":"success","data":{"NSE_EQ":{"1333":{"last_price":1650.5,"depth":null,"last_trade_time":null,"ohlc":null}}}}"#,
+            ),
+            (
+                "429 Too Many Requests",
+                r#"{"errorType":"Rate_Limit","errorCode":"DH-904","errorMessage":"Too many requests"}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"status":"success","data":{"NSE_EQ":{"11536":{"last_price":3900.0,"depth":null,"last_trade_time":null,"ohlc":null}}}}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"status":"success","data":{"NSE_EQ":{"2885":{"last_price":2950.0,"depth":null,"last_trade_time":null,"ohlc":null}}}}"#,
+            ),
+        ];
+        for (status, body) in replies {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await.unwrap();
+            let resp = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(resp.as_bytes()).await.unwrap();
+        }
+    });
+
+    let request = |id: u64| MarketQuoteRequest::from([("NSE_EQ".to_owned(), vec![id])]);
+    let client = DhanClient::with_base_url("1000000001", "t", format!("http://127.0.0.1:{port}"));
+    let started = tokio::time::Instant::now();
+    let mut quotes = client
+        .get_quotes_multi(vec![request(1333), request(2885), request(11536)])
+        .await;
+
+    // Three requests, paced one second apart.
+    assert!(started.elapsed() >= Duration::from_secs(2));
+    assert_eq!(quotes.entry_count(), 2);
+    assert_eq!(
+        quotes
+            .get(&InstrumentId::new(ExchangeSegment::NSE_EQ, 1333))
+            .unwrap()
+            .last_price,
+        1650.5
+    );
+    assert_eq!(
+        quotes
+            .get(&InstrumentId::new(ExchangeSegment::NSE_EQ, 11536))
+            .unwrap()
+            .last_price,
+        3900.0
+    );
+    assert!(!quotes.all_ok());
+    assert_eq!(quotes.failed.len(), 1);
+    assert_eq!(quotes.failed[0].0, request(2885));
+
+    assert_eq!(
+        quotes
+            .retry_failed(async |req| client.get_quote(req).await)
+            .await,
+        1
+    );
+    assert!(quotes.all_ok());
+    assert_eq!(
+        quotes
+            .get(&InstrumentId::new(ExchangeSegment::NSE_EQ, 2885))
+            .unwrap()
+            .last_price,
+        2950.0
+    );
+}
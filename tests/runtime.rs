@@ -0,0 +1,394 @@
+//! Offline tests for the strategy runtime, driven by synthetic events.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use dhan_rs::DhanClient;
+use dhan_rs::cache::QuoteCache;
+use dhan_rs::error::DhanError;
+use dhan_rs::risk::{CircuitLimitCheck, MaxOrderQuantity, OrderCountBudget, RiskEngine};
+use dhan_rs::runtime::session::{DhanSession, StrategyState};
+use dhan_rs::runtime::{RuntimeEvent, Strategy, StrategyContext, StrategyRunner};
+use dhan_rs::types::enums::*;
+use dhan_rs::types::historical::Candle;
+use dhan_rs::types::instrument::InstrumentId;
+use dhan_rs::types::market_quote::{MarketQuoteResponse, QuoteData};
+use dhan_rs::types::orders::PlaceOrderRequest;
+use dhan_rs::ws::market_feed::{Tick, parse_packet};
+use dhan_rs::ws::order_update::OrderUpdate;
+
+/// Build a Ticker packet (response code 2) for NSE_EQ:1333.
+fn ticker(ltp: f32, ltt: i32) -> RuntimeEvent {
+    let mut buf = vec![2u8];
+    buf.extend_from_slice(&16u16.to_le_bytes());
+    buf.push(1);
+    buf.extend_from_slice(&1333u32.to_le_bytes());
+    buf.extend_from_slice(&ltp.to_le_bytes());
+    buf.extend_from_slice(&ltt.to_le_bytes());
+    RuntimeEvent::Market(parse_packet(&buf).unwrap())
+}
+
+fn big_order() -> PlaceOrderRequest {
+    PlaceOrderRequest {
+        dhan_client_id: "1000000001".into(),
+        correlation_id: None,
+        transaction_type: TransactionType::BUY,
+        exchange_segment: ExchangeSegment::NSE_EQ,
+        product_type: ProductType::INTRADAY,
+        order_type: OrderType::MARKET,
+        validity: Validity::DAY,
+        security_id: "1333".into(),
+        quantity: 10_000,
+        disclosed_quantity: None,
+        price: None,
+        trigger_price: None,
+        after_market_order: None,
+        amo_time: None,
+        bo_profit_value: None,
+        bo_stop_loss_value: None,
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    ticks: usize,
+    candles: Vec<Candle>,
+    errors: Vec<String>,
+}
+
+impl Strategy for Recorder {
+    fn name(&self) -> &str {
+        "recorder"
+    }
+
+    fn on_tick(&mut self, ctx: &mut StrategyContext, tick: &Tick) {
+        self.ticks += 1;
+        assert_eq!(
+            tick.instrument,
+            InstrumentId::new(ExchangeSegment::NSE_EQ, 1333)
+        );
+        if self.ticks == 1 {
+            ctx.place_order(big_order());
+        }
+    }
+
+    fn on_candle(&mut self, _ctx: &mut StrategyContext, _id: InstrumentId, candle: &Candle) {
+        self.candles.push(*candle);
+    }
+
+    fn on_error(&mut self, _ctx: &mut StrategyContext, error: &DhanError) {
+        self.errors.push(error.to_string());
+    }
+}
+
+#[tokio::test]
+async fn test_runner_dispatches_ticks_candles_and_risk_rejections() {
+    let runner = StrategyRunner::new(Recorder::default(), DhanClient::new("1000000001", "t"))
+        .with_risk(RiskEngine::new().with_check(MaxOrderQuantity(100)));
+
+    let events = futures_util::stream::iter([
+        ticker(100.0, 1_726_041_600),
+        ticker(101.5, 1_726_041_630),
+        ticker(99.0, 1_726_041_645),
+        ticker(100.5, 1_726_041_661),
+    ]);
+    let strategy = runner.run(events).await.unwrap();
+
+    assert_eq!(strategy.ticks, 4);
+    assert_eq!(strategy.candles.len(), 1);
+    let bar = strategy.candles[0];
+    // Feed times are IST wall-clock epochs; candles carry Unix time.
+    assert_eq!(bar.timestamp, 1_726_041_600 - 19_800);
+    assert_eq!(
+        (bar.open, bar.high, bar.low, bar.close),
+        (100.0, 101.5, 99.0, 99.0)
+    );
+
+    // The oversized order never reached the network.
+    assert_eq!(strategy.errors.len(), 1);
+    assert!(strategy.errors[0].contains("max_order_quantity"));
+}
+
+struct Counter {
+    name: &'static str,
+    ticks: Arc<AtomicUsize>,
+    orders: Arc<AtomicUsize>,
+}
+
+impl Counter {
+    fn new(name: &'static str) -> (Self, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let orders = Arc::new(AtomicUsize::new(0));
+        let counter = Self {
+            name,
+            ticks: ticks.clone(),
+            orders: orders.clone(),
+        };
+        (counter, ticks, orders)
+    }
+}
+
+impl Strategy for Counter {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn on_tick(&mut self, _ctx: &mut StrategyContext, _tick: &Tick) {
+        self.ticks.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn on_order_update(&mut self, _ctx: &mut StrategyContext, _update: &OrderUpdate) {
+        self.orders.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn test_session_shares_quotes_and_isolates_strategies() {
+    let mut session = DhanSession::new(DhanClient::new("1000000001", "t"));
+    let (alpha, alpha_ticks, alpha_orders) = Counter::new("alpha");
+    let (beta, beta_ticks, beta_orders) = Counter::new("beta");
+    session.add_strategy(alpha, RiskEngine::new()).unwrap();
+    session
+        .add_strategy(beta, RiskEngine::new().with_check(OrderCountBudget::new(5)))
+        .unwrap();
+    let (dup, _, _) = Counter::new("alpha");
+    assert!(session.add_strategy(dup, RiskEngine::new()).is_err());
+
+    session.dispatch(ticker(100.0, 1_726_041_600)).await;
+    session.pause("beta").unwrap();
+    session.dispatch(ticker(101.0, 1_726_041_601)).await;
+
+    let update = OrderUpdate {
+        correlation_id: Some("BETA-1".into()),
+        ..Default::default()
+    };
+    session
+        .dispatch(RuntimeEvent::Order(Box::new(update)))
+        .await;
+    session.shutdown().await;
+
+    let id = InstrumentId::new(ExchangeSegment::NSE_EQ, 1333);
+    assert_eq!(session.quotes().ltp(&id), Some(101.0));
+    assert_eq!(alpha_ticks.load(Ordering::SeqCst), 2);
+    assert_eq!(beta_ticks.load(Ordering::SeqCst), 1);
+    assert_eq!(alpha_orders.load(Ordering::SeqCst), 0);
+    assert_eq!(beta_orders.load(Ordering::SeqCst), 1);
+    assert!(
+        session
+            .strategies()
+            .iter()
+            .all(|s| s.state == StrategyState::Stopped)
+    );
+}
+
+#[test]
+fn test_circuit_limit_check_uses_bands_from_rest_quotes() {
+    let quotes = QuoteCache::new();
+    let response: MarketQuoteResponse<QuoteData> = serde_json::from_str(
+        r#"{"status":"success","data":{"NSE_EQ":{"1333":{
+            "last_price":1500.0,"depth":null,"last_trade_time":null,"ohlc":null,
+            "lower_circuit_limit":1350.0,"upper_circuit_limit":1650.0}}}}"#,
+    )
+    .unwrap();
+    assert_eq!(quotes.update_from_quotes(&response), 1);
+
+    let risk = RiskEngine::new().with_check(CircuitLimitCheck::new(quotes));
+    let mut req = big_order();
+    req.order_type = OrderType::LIMIT;
+    req.price = Some(1600.0);
+    assert!(risk.check(&req).is_ok());
+    req.price = Some(1700.0);
+    assert!(matches!(risk.check(&req), Err(DhanError::RiskRejected(_))));
+}
+
+#[tokio::test]
+async fn test_open_circuit_puts_session_in_safe_mode() {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use dhan_rs::circuit::{CircuitBreaker, CircuitState};
+    use dhan_rs::events::{EventBus, EventFilter, RiskEvent, SessionEvent};
+    use dhan_rs::runtime::degrade::DegradationPolicy;
+
+    // Nothing listens on port 1, so every call fails at the transport.
+    let breaker = CircuitBreaker::new()
+        .with_failure_threshold(1)
+        .with_cooldown(Duration::from_secs(60));
+    let client = DhanClient::with_base_url("1000000001", "t", "http://127.0.0.1:1")
+        .with_circuit_breaker(breaker.clone());
+    let bus = EventBus::new();
+    let mut events = bus.subscribe(EventFilter::all());
+    let mut session = DhanSession::new(client.clone())
+        .with_degradation(DegradationPolicy::new(breaker.clone()).with_event_bus(bus));
+    let (recorder, _, _) = Counter::new("recorder");
+    session.add_strategy(recorder, RiskEngine::new()).unwrap();
+
+    let request = HashMap::from([("NSE_EQ".to_string(), vec![1333])]);
+    assert!(matches!(
+        client.get_ltp(&request).await.unwrap_err(),
+        DhanError::Http(_)
+    ));
+    assert_eq!(breaker.state(), CircuitState::Open);
+    assert!(matches!(
+        client.get_ltp(&request).await.unwrap_err(),
+        DhanError::CircuitOpen(_)
+    ));
+
+    assert!(matches!(
+        events.recv().await,
+        Some(SessionEvent::Risk(RiskEvent::SafeModeEntered { .. }))
+    ));
+    assert!(session.handle().safe_mode().is_active());
+
+    breaker.reset();
+    assert!(matches!(
+        events.recv().await,
+        Some(SessionEvent::Risk(RiskEvent::SafeModeExited))
+    ));
+    assert!(!session.handle().safe_mode().is_active());
+    session.shutdown().await;
+}
+
+/// Places `order` on every tick.
+struct Placer {
+    order: PlaceOrderRequest,
+    errors: Vec<String>,
+}
+
+impl Strategy for Placer {
+    fn name(&self) -> &str {
+        "placer"
+    }
+
+    fn on_tick(&mut self, ctx: &mut StrategyContext, _tick: &Tick) {
+        ctx.place_order(self.order.clone());
+    }
+
+    fn on_error(&mut self, _ctx: &mut StrategyContext, error: &DhanError) {
+        self.errors.push(error.to_string());
+    }
+}
+
+#[tokio::test]
+async fn test_safe_mode_blocks_entries_but_not_exits() {
+    use dhan_rs::runtime::degrade::SafeMode;
+
+    let safe_mode = SafeMode::new();
+    safe_mode.enter();
+    let mut exit = big_order();
+    exit.transaction_type = TransactionType::SELL;
+    let placer = Placer {
+        order: exit,
+        errors: Vec::new(),
+    };
+    let mut runner = StrategyRunner::new(placer, DhanClient::new("1000000001", "t"))
+        .with_risk(RiskEngine::new().with_check(MaxOrderQuantity(100)))
+        .with_safe_mode(safe_mode);
+
+    // No position yet: the sell would open a short.
+    runner.handle_event(ticker(100.0, 1_726_041_600)).await;
+    runner
+        .handle_event(RuntimeEvent::Order(Box::new(OrderUpdate {
+            order_id: Some("1".into()),
+            security_id: Some("1333".into()),
+            transaction_type: Some(TransactionType::BUY),
+            status: Some(OrderStatus::TRADED),
+            traded_quantity: Some(10_000),
+            ..OrderUpdate::default()
+        })))
+        .await;
+    // Now it closes the long, so it passes safe mode and reaches the
+    // quantity check.
+    runner.handle_event(ticker(100.5, 1_726_041_601)).await;
+
+    let errors = &runner.strategy().errors;
+    assert!(errors[0].contains("safe mode"));
+    assert!(errors[1].contains("max_order_quantity"));
+}
+
+/// Logs a signal, places an entry plus an oversized add-on for it and
+/// stops.
+struct Signaller;
+
+impl Strategy for Signaller {
+    fn name(&self) -> &str {
+        "signaller"
+    }
+
+    fn on_tick(&mut self, ctx: &mut StrategyContext, tick: &Tick) {
+        use dhan_rs::runtime::signals::Signal;
+
+        ctx.log_signal(
+            Signal::new("breakout")
+                .instrument(tick.instrument)
+                .indicator("sma_20", 99.5)
+                .position(tick.instrument, 0),
+        );
+        let mut entry = big_order();
+        entry.quantity = 10;
+        ctx.place_order(entry);
+        ctx.place_order(big_order());
+        ctx.stop();
+    }
+}
+
+#[tokio::test]
+async fn test_signal_log_links_orders_to_signal() {
+    use dhan_rs::broker::paper::PaperBroker;
+    use dhan_rs::runtime::signals::SignalLog;
+
+    let path = std::env::temp_dir().join(format!("dhan-rs-signals-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let runner = StrategyRunner::new(Signaller, DhanClient::new("1000000001", "t"))
+        .with_broker(PaperBroker::new())
+        .with_correlation_prefix("SIG-")
+        .with_risk(RiskEngine::new().with_check(MaxOrderQuantity(100)))
+        .with_signal_log(SignalLog::open(&path).unwrap());
+    runner
+        .run(futures_util::stream::iter([ticker(100.0, 1_726_041_600)]))
+        .await
+        .unwrap();
+
+    let records = SignalLog::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(records.len(), 1);
+    let record = &records[0];
+    assert_eq!(
+        (record.strategy.as_str(), record.name.as_str()),
+        ("signaller", "breakout")
+    );
+    assert_eq!(record.quotes[0].ltp, 100.0);
+    assert_eq!(record.indicators["sma_20"], 99.5);
+    assert!(record.has_correlation_id("SIG-1"));
+    assert_eq!(record.orders[0].order_id.as_deref(), Some("PAPER-1"));
+    assert_eq!(record.orders[1].correlation_id.as_deref(), Some("SIG-2"));
+    assert!(
+        record.orders[1]
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("max_order_quantity")
+    );
+}
+
+#[tokio::test]
+async fn test_correlation_prefix_refuses_ids_over_the_limit() {
+    use dhan_rs::broker::paper::PaperBroker;
+
+    let mut order = big_order();
+    order.quantity = 10;
+    order.correlation_id = Some("A".repeat(25));
+    let placer = Placer {
+        order,
+        errors: Vec::new(),
+    };
+    let strategy = StrategyRunner::new(placer, DhanClient::new("1000000001", "t"))
+        .with_broker(PaperBroker::new())
+        .with_correlation_prefix("PLACER-")
+        .run(futures_util::stream::iter([ticker(100.0, 1_726_041_600)]))
+        .await
+        .unwrap();
+
+    assert_eq!(strategy.errors.len(), 1);
+    assert!(strategy.errors[0].contains("exceeds 30 characters"));
+}
@@ -534,7 +534,7 @@ async fn test_with_base_url_trailing_slash() {
 
 #[tokio::test]
 async fn test_set_access_token() {
-    let mut client = DhanClient::with_base_url("test", "old-token", SANDBOX_BASE_URL);
+    let client = DhanClient::with_base_url("test", "old-token", SANDBOX_BASE_URL);
     assert_eq!(client.access_token(), "old-token");
     client.set_access_token("new-token");
     assert_eq!(client.access_token(), "new-token");
@@ -0,0 +1,161 @@
+//! Offline tests for IST time-of-day scheduling.
+
+use chrono::{TimeZone, Utc};
+use dhan_rs::scheduler::DailySchedule;
+use dhan_rs::scheduler::square_off::SquareOffScheduler;
+use dhan_rs::types::enums::{OrderType, ProductType, TransactionType};
+use dhan_rs::types::portfolio::Position;
+
+#[test]
+fn test_next_after_uses_ist_and_rolls_over() {
+    let schedule = DailySchedule::parse(&["15:25", "09:20"]).unwrap();
+
+    // 2024-09-11 04:00 UTC = 09:30 IST → next is 15:25 IST the same day.
+    let now = Utc.with_ymd_and_hms(2024, 9, 11, 4, 0, 0).unwrap();
+    let next = schedule.next_after(now).unwrap();
+    assert_eq!(next, Utc.with_ymd_and_hms(2024, 9, 11, 9, 55, 0).unwrap());
+
+    // After the last slot the next run is 09:20 IST tomorrow.
+    let next = schedule.next_after(next).unwrap();
+    assert_eq!(next, Utc.with_ymd_and_hms(2024, 9, 12, 3, 50, 0).unwrap());
+}
+
+#[test]
+fn test_weekdays_only_skips_weekend() {
+    let schedule = DailySchedule::parse(&["09:20"]).unwrap().weekdays_only();
+
+    // Friday 2024-09-13 12:00 IST → Monday 2024-09-16 09:20 IST.
+    let now = Utc.with_ymd_and_hms(2024, 9, 13, 6, 30, 0).unwrap();
+    let next = schedule.next_after(now).unwrap();
+    assert_eq!(next, Utc.with_ymd_and_hms(2024, 9, 16, 3, 50, 0).unwrap());
+
+    assert!(DailySchedule::parse(&["25:00"]).is_err());
+}
+
+#[test]
+fn test_square_off_orders_flatten_intraday_only() {
+    let positions: Vec<Position> = serde_json::from_value(serde_json::json!([
+        {"securityId": "1333", "exchangeSegment": "NSE_EQ", "productType": "INTRADAY", "netQty": 10},
+        {"securityId": "11536", "exchangeSegment": "NSE_EQ", "productType": "INTRADAY", "netQty": -5},
+        {"securityId": "2885", "exchangeSegment": "NSE_EQ", "productType": "CNC", "netQty": 3},
+        {"securityId": "3045", "exchangeSegment": "NSE_EQ", "productType": "INTRADAY", "netQty": 0}
+    ]))
+    .unwrap();
+
+    let orders =
+        SquareOffScheduler::build_orders("1000000001", &positions, &[ProductType::INTRADAY]);
+    assert_eq!(orders.len(), 2);
+    assert_eq!(orders[0].transaction_type, TransactionType::SELL);
+    assert_eq!(orders[0].quantity, 10);
+    assert_eq!(orders[1].transaction_type, TransactionType::BUY);
+    assert_eq!(orders[1].quantity, 5);
+    assert!(orders.iter().all(|o| o.order_type == OrderType::MARKET));
+}
+
+#[tokio::test]
+async fn test_square_off_remaining_matches_segment_and_product() {
+    use std::time::Duration;
+
+    use dhan_rs::DhanClient;
+    use dhan_rs::scheduler::square_off::SquareOffConfig;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use reqwest::Method;
+
+    let positions = |intraday: i64| {
+        MockResponse::json(
+            200,
+            serde_json::json!([
+                {"securityId": "1333", "exchangeSegment": "NSE_EQ", "productType": "INTRADAY", "netQty": intraday},
+                {"securityId": "1333", "exchangeSegment": "NSE_EQ", "productType": "CNC", "netQty": 5}
+            ]),
+        )
+    };
+    let mock = MockTransport::new()
+        .on(Method::GET, "/v2/positions", positions(10))
+        .on(Method::GET, "/v2/positions", positions(4))
+        .on(
+            Method::POST,
+            "/v2/orders",
+            MockResponse::json(
+                200,
+                serde_json::json!({"orderId": "X1", "orderStatus": "TRANSIT"}),
+            ),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock);
+    let config = SquareOffConfig {
+        max_attempts: 1,
+        verify_delay: Duration::ZERO,
+        ..SquareOffConfig::default()
+    };
+
+    let report = SquareOffScheduler::new(client, config)
+        .square_off_now()
+        .await
+        .unwrap();
+    // The CNC holding of the same security is not part of the square-off.
+    assert_eq!(report.remaining.len(), 1);
+    assert_eq!(
+        report.remaining[0].product_type.as_deref(),
+        Some("INTRADAY")
+    );
+}
+
+#[tokio::test]
+async fn test_square_off_does_not_double_a_pending_exit() {
+    use std::time::Duration;
+
+    use dhan_rs::DhanClient;
+    use dhan_rs::scheduler::square_off::SquareOffConfig;
+    use dhan_rs::transport::{MockResponse, MockTransport};
+    use reqwest::Method;
+
+    // The exit is slow to fill and cannot be cancelled: positions stay open.
+    let mock = MockTransport::new()
+        .on(
+            Method::GET,
+            "/v2/positions",
+            MockResponse::json(
+                200,
+                serde_json::json!([
+                    {"securityId": "1333", "exchangeSegment": "NSE_EQ", "productType": "INTRADAY", "netQty": 10}
+                ]),
+            ),
+        )
+        .on(
+            Method::POST,
+            "/v2/orders",
+            MockResponse::json(200, serde_json::json!({"orderId": "X1", "orderStatus": "TRANSIT"})),
+        )
+        .on(
+            Method::GET,
+            "/v2/orders/X1",
+            MockResponse::json(200, serde_json::json!({"orderId": "X1", "orderStatus": "PENDING"})),
+        )
+        .on(
+            Method::DELETE,
+            "/v2/orders/X1",
+            MockResponse::json(
+                400,
+                serde_json::json!({"errorType": "Order_Error", "errorCode": "DH-906", "errorMessage": "Order is being processed"}),
+            ),
+        );
+    let client = DhanClient::new("1000000001", "token").with_transport(mock.clone());
+    let config = SquareOffConfig {
+        max_attempts: 3,
+        verify_delay: Duration::ZERO,
+        ..SquareOffConfig::default()
+    };
+
+    let report = SquareOffScheduler::new(client, config)
+        .square_off_now()
+        .await
+        .unwrap();
+    let requests = mock.requests();
+    assert_eq!(
+        requests.iter().filter(|r| r.method == Method::POST).count(),
+        1
+    );
+    assert!(requests.iter().any(|r| r.method == Method::DELETE));
+    assert_eq!(report.orders.succeeded.len(), 1);
+    assert!(!report.is_flat());
+}
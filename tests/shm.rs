@@ -0,0 +1,52 @@
+//! Tests for the shared-memory tick ring.
+#![cfg(feature = "shm")]
+
+use dhan_rs::types::enums::ExchangeSegment;
+use dhan_rs::types::instrument::InstrumentId;
+use dhan_rs::ws::market_feed::Tick;
+use dhan_rs::ws::shm::{ShmRingPublisher, ShmRingReader};
+
+fn tick(security_id: u32, ltp: f64) -> Tick {
+    Tick {
+        instrument: InstrumentId::new(ExchangeSegment::NSE_FNO, security_id),
+        ltp,
+        ltt: 1_726_048_740,
+        last_qty: Some(25),
+        volume: None,
+        open: Some(100.0),
+        high: None,
+        low: None,
+        close: None,
+        oi: Some(1_000),
+    }
+}
+
+#[test]
+fn test_ring_round_trip_and_overrun() {
+    let path = std::env::temp_dir().join(format!("dhan-rs-ring-{}", std::process::id()));
+    let publisher = ShmRingPublisher::create(&path, 3).unwrap();
+    assert_eq!(publisher.capacity(), 4);
+
+    let mut reader = ShmRingReader::open(&path).unwrap();
+    assert!(reader.try_read().is_none());
+    publisher.publish(&tick(1, 10.5));
+    let read = reader.try_read().unwrap();
+    assert_eq!(read.instrument, tick(1, 10.5).instrument);
+    assert_eq!(read.ltp, 10.5);
+    assert_eq!(read.last_qty, Some(25));
+    assert_eq!(read.volume, None);
+    assert_eq!(read.oi, Some(1_000));
+
+    // A second publisher shares the cursor; six ticks overrun four slots.
+    let other = ShmRingPublisher::attach(&path).unwrap();
+    for id in 2..8 {
+        other.publish(&tick(id, id as f64));
+    }
+    let ids: Vec<u32> = std::iter::from_fn(|| reader.try_read())
+        .map(|t| t.instrument.security_id)
+        .collect();
+    assert_eq!(ids, vec![4, 5, 6, 7]);
+    assert_eq!(reader.dropped(), 2);
+
+    std::fs::remove_file(path).ok();
+}
@@ -0,0 +1,43 @@
+//! Partner token vault tests.
+
+use chrono::{Duration, TimeZone, Utc};
+use dhan_rs::types::auth::TokenResponse;
+use dhan_rs::vault::{TokenVault, VaultEntry};
+
+#[test]
+fn test_vault_hands_out_clients_and_lists_expiring_tokens() {
+    let token: TokenResponse = serde_json::from_value(serde_json::json!({
+        "dhanClientId": "1000000001",
+        "dhanClientName": "A User",
+        "accessToken": "token-a",
+        "expiryTime": "2099-01-01T09:00:00"
+    }))
+    .unwrap();
+    let vault = TokenVault::new();
+    assert_eq!(vault.insert_token(&token), "1000000001");
+    vault.insert(VaultEntry {
+        client_id: "1000000002".into(),
+        client_name: None,
+        access_token: "token-b".into(),
+        expires_at: Utc::now() - Duration::minutes(5),
+    });
+
+    let client = vault.client("1000000001").unwrap();
+    assert_eq!(client.access_token(), "token-a");
+    // Expired and unknown users get no client.
+    assert!(vault.client("1000000002").is_none());
+    assert!(vault.client("1000000003").is_none());
+
+    let expiry = Utc.with_ymd_and_hms(2099, 1, 1, 3, 30, 0).unwrap();
+    assert_eq!(vault.entries().len(), 2);
+    assert_eq!(
+        vault.get("1000000001").unwrap().expires_at,
+        expiry,
+        "expiry is IST"
+    );
+    assert_eq!(
+        vault.expiring(Utc::now(), Duration::hours(1)),
+        ["1000000002"]
+    );
+    assert_eq!(vault.expiring(expiry, Duration::zero()).len(), 2);
+}